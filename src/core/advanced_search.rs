@@ -0,0 +1,178 @@
+//! # Advanced search
+//!
+//! Narrows down series by premiere year, genre, status, rating and runtime, answering
+//! queries like "running science fiction series from 2020 onwards rated above 8".
+//!
+//! The filter is applied over the TVmaze search results of the search term combined with
+//! the series of the full schedule, so that a filter alone without a term still finds the
+//! series currently airing.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::api::tv_maze::series_searching;
+use super::caching::tv_schedule::full_schedule::FullSchedule;
+use super::content_filter;
+
+/// The genres used by TVmaze
+pub const GENRES: [&str; 26] = [
+    "Action",
+    "Adventure",
+    "Anime",
+    "Children",
+    "Comedy",
+    "Crime",
+    "DIY",
+    "Drama",
+    "Espionage",
+    "Family",
+    "Fantasy",
+    "Food",
+    "History",
+    "Horror",
+    "Legal",
+    "Medical",
+    "Music",
+    "Mystery",
+    "Nature",
+    "Romance",
+    "Science-Fiction",
+    "Sports",
+    "Supernatural",
+    "Thriller",
+    "Travel",
+    "Western",
+];
+
+/// The show statuses used by TVmaze
+pub const STATUSES: [&str; 4] = ["Running", "Ended", "To Be Determined", "In Development"];
+
+/// The minimum ratings offered for filtering
+pub const MIN_RATINGS: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+/// The maximum average runtimes in minutes offered for filtering
+pub const MAX_RUNTIMES: [u32; 5] = [15, 30, 45, 60, 90];
+
+/// What the series have to match to be in the results, unset fields match everything
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchFilter {
+    /// The earliest premiere year
+    pub min_year: Option<i32>,
+    /// The latest premiere year
+    pub max_year: Option<i32>,
+    /// A genre as named by TVmaze, like `Science-Fiction`
+    pub genre: Option<String>,
+    /// A status as named by TVmaze, like `Running`
+    pub status: Option<String>,
+    /// The lowest TVmaze average rating
+    pub min_rating: Option<u8>,
+    /// The longest average episode runtime in minutes
+    pub max_runtime: Option<u32>,
+}
+
+impl SearchFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn matches(&self, series_info: &SeriesMainInformation) -> bool {
+        let premiere_year = series_info
+            .premiered
+            .as_ref()
+            .and_then(|premiered| premiered.get(..4))
+            .and_then(|year| year.parse::<i32>().ok());
+
+        let matches_min_year = self
+            .min_year
+            .is_none_or(|min_year| premiere_year.is_some_and(|year| year >= min_year));
+
+        let matches_max_year = self
+            .max_year
+            .is_none_or(|max_year| premiere_year.is_some_and(|year| year <= max_year));
+
+        let matches_genre = self
+            .genre
+            .as_ref()
+            .is_none_or(|genre| series_info.genres.contains(genre));
+
+        let matches_status = self
+            .status
+            .as_ref()
+            .is_none_or(|status| series_info.status == *status);
+
+        let matches_rating = self.min_rating.is_none_or(|min_rating| {
+            series_info
+                .rating
+                .average
+                .is_some_and(|rating| rating >= min_rating as f32)
+        });
+
+        let matches_runtime = self.max_runtime.is_none_or(|max_runtime| {
+            series_info
+                .average_runtime
+                .is_some_and(|runtime| runtime <= max_runtime)
+        });
+
+        matches_min_year
+            && matches_max_year
+            && matches_genre
+            && matches_status
+            && matches_rating
+            && matches_runtime
+    }
+}
+
+/// Searches the series matching the term and the filter, the best rated first
+///
+/// An empty term searches the series of the full schedule only.
+pub async fn search(
+    term: String,
+    filter: SearchFilter,
+) -> anyhow::Result<Vec<SeriesMainInformation>> {
+    let term = term.trim().to_lowercase();
+
+    let mut series_infos: Vec<SeriesMainInformation> = if term.is_empty() {
+        vec![]
+    } else {
+        series_searching::search_series(term.clone())
+            .await?
+            .into_iter()
+            .map(|search_result| search_result.show)
+            .collect()
+    };
+
+    let full_schedule: &'static FullSchedule = FullSchedule::new().await?;
+
+    // Getting the series of the full schedule reads the hidden series with a blocking lock
+    let schedule_series = tokio::task::spawn_blocking(move || {
+        full_schedule
+            .get_series()
+            .into_iter()
+            .filter(|series_info| {
+                term.is_empty() || series_info.name.to_lowercase().contains(&term)
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    })
+    .await?;
+
+    series_infos.extend(schedule_series);
+
+    let mut seen_series_ids = HashSet::new();
+    series_infos.retain(|series_info| {
+        seen_series_ids.insert(series_info.id) && filter.matches(series_info)
+    });
+
+    let mut series_infos = content_filter::filter_series(series_infos, |series_info| series_info);
+    series_infos.sort_by(|a, b| {
+        b.rating
+            .average
+            .unwrap_or_default()
+            .total_cmp(&a.rating.average.unwrap_or_default())
+    });
+
+    Ok(series_infos)
+}
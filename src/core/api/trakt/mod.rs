@@ -112,9 +112,7 @@ mod convert {
         let tvmaze_series_id = tvmaze_series_info.id;
 
         // Caching the series information
-        let series_info_str = serde_json::to_string_pretty(&tvmaze_series_info)
-            .expect("SeriesMainInformation should be seriealizable");
-        cache_series_information(tvmaze_series_id, &series_info_str).await;
+        cache_series_information(&tvmaze_series_info).await;
 
         let mut troxide_db_series =
             ManuallyDrop::new(Series::new(tvmaze_series_info.name, tvmaze_series_id));
@@ -167,7 +165,7 @@ mod convert {
             })
             .collect();
 
-        for (show, handle) in trakt_shows.into_iter().zip(handles.into_iter()) {
+        for (show, handle) in trakt_shows.into_iter().zip(handles) {
             if let Some(id_and_series) = handle.await.expect("failed to join all the handles")? {
                 ids_and_series.push(id_and_series);
             } else {
@@ -0,0 +1,83 @@
+//! Alternate episode orders of a series such as the DVD releases
+
+use serde::Deserialize;
+
+use super::{deserialize_json_from_url, get_pretty_json_from_url, ApiError};
+
+// replace SERIES-ID with the actual series id
+const ALTERNATE_LISTS_ADDRESS: &str = "https://api.tvmaze.com/shows/SERIES-ID/alternatelists";
+
+// replace LIST-ID with the actual alternate list id
+const ALTERNATE_EPISODES_ADDRESS: &str =
+    "https://api.tvmaze.com/alternatelists/LIST-ID/alternateepisodes?embed=episodes";
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AlternateList {
+    pub id: u32,
+    #[serde(default)]
+    pub dvd_release: bool,
+}
+
+/// An episode placed in an alternate list
+#[derive(Debug, Deserialize, Clone)]
+pub struct AlternateEpisode {
+    /// The season in the alternate list
+    pub season: u32,
+    /// The number in the season of the alternate list
+    pub number: u32,
+    #[serde(rename = "_embedded")]
+    embedded: AlternateEpisodeEmbedded,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+struct AlternateEpisodeEmbedded {
+    episodes: Vec<OriginalEpisode>,
+}
+
+/// The episode as numbered by TVmaze, only its season is of interest
+#[derive(Debug, Deserialize, Clone)]
+struct OriginalEpisode {
+    season: u32,
+}
+
+impl AlternateEpisode {
+    /// The TVmaze season numbers of the episodes placed here
+    pub fn original_seasons(&self) -> impl Iterator<Item = u32> + '_ {
+        self.embedded.episodes.iter().map(|episode| episode.season)
+    }
+}
+
+pub async fn get_alternate_lists(series_id: u32) -> Result<Vec<AlternateList>, ApiError> {
+    let url = ALTERNATE_LISTS_ADDRESS.replace("SERIES-ID", &series_id.to_string());
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &pretty_json)
+}
+
+pub async fn get_alternate_episodes(list_id: u32) -> Result<Vec<AlternateEpisode>, ApiError> {
+    let url = ALTERNATE_EPISODES_ADDRESS.replace("LIST-ID", &list_id.to_string());
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &pretty_json)
+}
+
+/// The TVmaze season numbers in the order their episodes first appear on DVD
+///
+/// `None` when TVmaze knows of no DVD release of the series.
+pub async fn get_dvd_season_order(series_id: u32) -> Result<Option<Vec<u32>>, ApiError> {
+    let lists = get_alternate_lists(series_id).await?;
+    let Some(dvd_list) = lists.into_iter().find(|list| list.dvd_release) else {
+        return Ok(None);
+    };
+
+    let mut episodes = get_alternate_episodes(dvd_list.id).await?;
+    episodes.sort_by_key(|episode| (episode.season, episode.number));
+
+    let mut season_order = vec![];
+    for season in episodes.iter().flat_map(AlternateEpisode::original_seasons) {
+        if !season_order.contains(&season) {
+            season_order.push(season);
+        }
+    }
+    Ok(Some(season_order))
+}
@@ -0,0 +1,31 @@
+//! # Connectivity
+//!
+//! Checks whether the TVmaze api can be reached, for telling the user once that cached data
+//! is being shown rather than having every section report its own failed requests.
+
+use std::time::Duration;
+
+/// A small resource of the api to request
+const PING_URL: &str = "https://api.tvmaze.com/shows/1";
+
+/// How long the api has to answer before it's considered unreachable
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether the api answers at all, even error responses mean that it's reachable
+pub async fn is_api_reachable() -> bool {
+    let client = match reqwest::Client::builder().timeout(PING_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("failed to build connectivity client: {}", err);
+            return true;
+        }
+    };
+
+    match client.head(PING_URL).send().await {
+        Ok(_) => true,
+        Err(err) => {
+            tracing::warn!("tvmaze api is unreachable: {}", err);
+            false
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! # Data usage
+//!
+//! Counts the bytes downloaded from TVmaze, both the api responses and the images, for the
+//! current session and the current month so that users on metered connections can see what
+//! the bandwidth saver options save them.
+//!
+//! The monthly count is kept in the data directory and saved when the app is closed.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use chrono::Local;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::core::paths;
+
+/// Name of the file in the data directory keeping the monthly data usage
+const DATA_USAGE_FILENAME: &str = "data-usage.json";
+
+static SESSION_BYTES: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    static ref MONTHLY_USAGE: Mutex<MonthlyUsage> = Mutex::new(MonthlyUsage::load());
+}
+
+/// The bytes downloaded in a month
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct MonthlyUsage {
+    /// Formatted like `2024-01`
+    month: String,
+    bytes: u64,
+}
+
+impl MonthlyUsage {
+    fn load() -> Self {
+        std::fs::read(get_data_usage_filepath())
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Starts counting from zero when the month has changed since the last download
+    fn roll_over(&mut self) {
+        let current_month = current_month();
+        if self.month != current_month {
+            self.month = current_month;
+            self.bytes = 0;
+        }
+    }
+}
+
+fn current_month() -> String {
+    Local::now().format("%Y-%m").to_string()
+}
+
+fn get_data_usage_filepath() -> PathBuf {
+    let mut data_usage_filepath = paths::PATHS
+        .read()
+        .expect("failed to read paths")
+        .get_data_dir_path()
+        .into_owned();
+    data_usage_filepath.push(DATA_USAGE_FILENAME);
+    data_usage_filepath
+}
+
+/// Adds the given amount of downloaded bytes to the session and monthly counts
+pub fn record_download(bytes: usize) {
+    SESSION_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+
+    let mut monthly_usage = MONTHLY_USAGE.lock().unwrap();
+    monthly_usage.roll_over();
+    monthly_usage.bytes += bytes as u64;
+}
+
+/// The bytes downloaded since the app was started
+pub fn get_session_bytes() -> u64 {
+    SESSION_BYTES.load(Ordering::Relaxed)
+}
+
+/// The bytes downloaded this month, sessions before this one included
+pub fn get_month_bytes() -> u64 {
+    let mut monthly_usage = MONTHLY_USAGE.lock().unwrap();
+    monthly_usage.roll_over();
+    monthly_usage.bytes
+}
+
+/// Keeps the monthly count for the next sessions
+pub fn save() {
+    let monthly_usage = MONTHLY_USAGE.lock().unwrap().clone();
+
+    if let Err(err) = std::fs::write(
+        get_data_usage_filepath(),
+        serde_json::to_vec(&monthly_usage).unwrap(),
+    ) {
+        tracing::error!("failed to save the data usage: {}", err);
+    }
+}
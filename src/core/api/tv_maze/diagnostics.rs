@@ -0,0 +1,196 @@
+//! # Diagnostics of TVmaze responses that could not be decoded
+//!
+//! When TVmaze changes the shape of its responses, decoding them fails. The offending json
+//! is kept in the diagnostics folder of the data directory and the failure is broadcast so
+//! that the gui can tell the user and offer reporting it with the details filled in.
+//!
+//! Only the latest json of every endpoint is kept and every endpoint is only reported once per
+//! run, so that a changed endpoint queried for every tracked series does not flood the folder
+//! nor the user. The folder is kept under [`MAX_DIAGNOSTICS_SIZE`] by removing the oldest files.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use tokio::sync::broadcast;
+
+use crate::core::paths;
+
+/// Name of the folder in the data directory keeping the json that failed to decode
+const DIAGNOSTICS_DIR_NAME: &str = "diagnostics";
+
+/// How many schema errors are kept for slow receivers before the oldest are dropped
+const SCHEMA_ERRORS_CAPACITY: usize = 16;
+
+/// The most bytes of json kept in the diagnostics folder
+const MAX_DIAGNOSTICS_SIZE: u64 = 5 * 1024 * 1024;
+
+lazy_static! {
+    static ref SCHEMA_ERRORS: broadcast::Sender<SchemaError> =
+        broadcast::channel(SCHEMA_ERRORS_CAPACITY).0;
+    /// The endpoints whose schema errors were already recorded during this run
+    static ref RECORDED_ENDPOINTS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// A TVmaze response that could not be decoded
+#[derive(Debug, Clone)]
+pub struct SchemaError {
+    /// The url the json came from, `None` when it came from the cache
+    pub url: Option<String>,
+    pub reason: String,
+    /// Where the offending json was saved relative to the data directory, `None` when saving it
+    /// failed
+    pub json_path: Option<PathBuf>,
+}
+
+impl SchemaError {
+    /// A link to open an issue on the repository with the details of the error filled in
+    pub fn report_url(&self) -> String {
+        let issues_url = format!("{}/issues/new", env!("CARGO_PKG_REPOSITORY"));
+        let title = format!("Failed to decode TVmaze response from {}", self.endpoint());
+        let body = format!(
+            "**Endpoint:** {}\n**Error:** {}\n**Version:** {}\n\nThe offending json was saved to `{}` in the data folder, please attach it.",
+            self.url.as_deref().unwrap_or("cache"),
+            self.reason,
+            env!("CARGO_PKG_VERSION"),
+            self.json_path
+                .as_ref()
+                .map(|json_path| json_path.display().to_string())
+                .unwrap_or_default(),
+        );
+
+        reqwest::Url::parse_with_params(&issues_url, &[("title", title), ("body", body)])
+            .map(|url| url.to_string())
+            .unwrap_or(issues_url)
+    }
+
+    /// The path of the url without ids i.e. `/shows/ID/episodes`
+    pub fn endpoint(&self) -> String {
+        get_endpoint(self.url.as_deref())
+    }
+}
+
+fn get_endpoint(url: Option<&str>) -> String {
+    let Some(url) = url else {
+        return "cache".to_owned();
+    };
+
+    reqwest::Url::parse(url)
+        .map(|url| {
+            url.path()
+                .split('/')
+                .map(|segment| {
+                    if !segment.is_empty() && segment.chars().all(|char| char.is_ascii_digit()) {
+                        "ID"
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_else(|_| url.to_owned())
+}
+
+/// Keeps the json that failed to decode and broadcasts the error, once per endpoint
+pub fn record_schema_error(url: Option<&str>, json: &str, reason: &str) {
+    let endpoint = get_endpoint(url);
+    if !RECORDED_ENDPOINTS.lock().unwrap().insert(endpoint.clone()) {
+        return;
+    }
+
+    let url = url.map(ToOwned::to_owned);
+    let json = json.to_owned();
+    let reason = reason.to_owned();
+
+    let record = move || {
+        let json_path = save_json(&endpoint, &json)
+            .map_err(|err| {
+                tracing::error!("failed to save the json that failed to decode: {}", err)
+            })
+            .ok();
+
+        let schema_error = SchemaError {
+            url,
+            reason,
+            json_path,
+        };
+
+        // Nothing is listening when running from the command line
+        let _ = SCHEMA_ERRORS.send(schema_error);
+    };
+
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => {
+            runtime.spawn_blocking(record);
+        }
+        Err(_) => record(),
+    }
+}
+
+/// Receives the schema errors recorded from now on
+pub fn subscribe_schema_errors() -> broadcast::Receiver<SchemaError> {
+    SCHEMA_ERRORS.subscribe()
+}
+
+/// Saves the json in place of the last one of the endpoint, returning where relative to the
+/// data directory
+fn save_json(endpoint: &str, json: &str) -> std::io::Result<PathBuf> {
+    let data_dir = paths::PATHS
+        .read()
+        .expect("failed to read paths")
+        .get_data_dir_path()
+        .into_owned();
+    let diagnostics_dir = data_dir.join(DIAGNOSTICS_DIR_NAME);
+    std::fs::create_dir_all(&diagnostics_dir)?;
+
+    let file_name: String = endpoint
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let relative_json_path = PathBuf::from(DIAGNOSTICS_DIR_NAME)
+        .join(format!("schema-error-{}.json", file_name.trim_matches('-')));
+
+    let json_path = data_dir.join(&relative_json_path);
+    std::fs::write(&json_path, json)?;
+
+    if let Err(err) = limit_dir_size(&diagnostics_dir, &json_path) {
+        tracing::error!("failed to clean up the diagnostics folder: {}", err);
+    }
+
+    Ok(relative_json_path)
+}
+
+/// Removes the oldest files of the directory until it is under [`MAX_DIAGNOSTICS_SIZE`], never
+/// the file to keep
+fn limit_dir_size(dir: &Path, file_to_keep: &Path) -> std::io::Result<()> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            files.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+    }
+
+    let mut total_size: u64 = files.iter().map(|(_, size, _)| size).sum();
+    files.sort_unstable();
+
+    for (_, size, path) in files {
+        if total_size <= MAX_DIAGNOSTICS_SIZE {
+            break;
+        }
+        if path != file_to_keep {
+            std::fs::remove_file(path)?;
+            total_size -= size;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Guest cast and guest crew of episodes and the guest appearances of people
+
+use serde::Deserialize;
+
+use super::episodes_information::Episode;
+use super::show_cast::{Cast, Character, Person};
+use super::{deserialize_json_from_url, get_pretty_json_from_url, ApiError};
+
+// replace ID with the actual episode id
+const GUEST_CAST_ADDRESS: &str = "https://api.tvmaze.com/episodes/ID/guestcast";
+const GUEST_CREW_ADDRESS: &str = "https://api.tvmaze.com/episodes/ID/guestcrew";
+
+// replace ID with the actual person id
+const GUEST_CAST_CREDITS_ADDRESS: &str =
+    "https://api.tvmaze.com/people/ID/guestcastcredits?embed[]=episode&embed[]=character";
+
+/// A guest cast member is described just like a member of the main cast
+pub type GuestCast = Cast;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GuestCrew {
+    /// What the person did in the episode i.e. `Director` or `Writer`
+    #[serde(rename = "guestCrewType")]
+    pub kind: String,
+    pub person: Person,
+}
+
+/// An episode a person appeared in as a guest
+#[derive(Deserialize, Debug, Clone)]
+pub struct GuestCastCredit {
+    #[serde(rename = "_embedded")]
+    embedded: GuestCastCreditEmbedded,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct GuestCastCreditEmbedded {
+    episode: Episode,
+    character: Character,
+}
+
+impl GuestCastCredit {
+    pub fn episode(&self) -> &Episode {
+        &self.embedded.episode
+    }
+
+    pub fn character(&self) -> &Character {
+        &self.embedded.character
+    }
+}
+
+pub async fn get_guest_cast(episode_id: u32) -> Result<Vec<GuestCast>, ApiError> {
+    let url = GUEST_CAST_ADDRESS.replace("ID", &episode_id.to_string());
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &pretty_json)
+}
+
+pub async fn get_guest_crew(episode_id: u32) -> Result<Vec<GuestCrew>, ApiError> {
+    let url = GUEST_CREW_ADDRESS.replace("ID", &episode_id.to_string());
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &pretty_json)
+}
+
+pub async fn get_guest_cast_credits(person_id: u32) -> Result<Vec<GuestCastCredit>, ApiError> {
+    let url = GUEST_CAST_CREDITS_ADDRESS.replace("ID", &person_id.to_string());
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &pretty_json)
+}
+
+/// Retrieves both the guest cast and the guest crew of an episode
+pub async fn get_episode_guests(
+    episode_id: u32,
+) -> Result<(Vec<GuestCast>, Vec<GuestCrew>), ApiError> {
+    tokio::try_join!(get_guest_cast(episode_id), get_guest_crew(episode_id))
+}
@@ -1,6 +1,7 @@
-use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc};
+use chrono::{DateTime, Datelike, Duration, Local, Utc};
 
 use super::{series_information::SeriesMainInformation, *};
+use crate::core::date_formatting;
 
 const EPISODE_INFORMATION_ADDRESS: &str =
     "https://api.tvmaze.com/shows/SERIES-ID/episodebynumber?season=SEASON&number=EPISODE";
@@ -12,7 +13,7 @@ const EPISODE_LIST_ADDRESS: &str = "https://api.tvmaze.com/shows/SERIES-ID/episo
 /// This data describes an episode found in a season of a particular series
 ///
 /// ## Note
-
+///
 /// There are two important fields to pay attention to
 ///
 /// ### show
@@ -28,6 +29,7 @@ const EPISODE_LIST_ADDRESS: &str = "https://api.tvmaze.com/shows/SERIES-ID/episo
 /// [link](https://www.tvmaze.com/api#web-schedule)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Episode {
+    pub id: u32,
     pub name: String,
     pub season: u32,
     pub number: Option<u32>,
@@ -38,6 +40,9 @@ pub struct Episode {
     pub rating: Rating,
     pub image: Option<Image>,
     pub summary: Option<String>,
+    /// Either `regular`, `significant_special` or `insignificant_special`
+    #[serde(rename = "type", default)]
+    pub episode_type: Option<String>,
     /// Local aired episodes normally have this field as `Some`
     pub show: Option<SeriesMainInformation>,
     #[serde(rename = "_links")]
@@ -72,6 +77,16 @@ pub enum EpisodeDateError {
 }
 
 impl Episode {
+    /// Whether the episode is a special rather than a regular episode of the season
+    pub fn is_special(&self) -> bool {
+        self.number.is_none()
+            || self
+                .episode_type
+                .as_deref()
+                .map(|episode_type| episode_type != "regular")
+                .unwrap_or(false)
+    }
+
     pub fn date_naive(&self) -> Result<chrono::NaiveDate, EpisodeDateError> {
         Ok(self.local_date_time()?.date_naive())
     }
@@ -117,27 +132,11 @@ impl EpisodeReleaseTime {
 
 impl std::fmt::Display for EpisodeReleaseTime {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        /// appends zero the minute digit if it's below 10 for better display
-        fn append_zero(num: u32) -> String {
-            if num < 10 {
-                format!("0{num}")
-            } else {
-                format!("{num}")
-            }
-        }
-
-        let (is_pm, hour) = self.release_time.hour12();
-        let pm_am = if is_pm { "p.m." } else { "a.m." };
-
-        let minute = append_zero(self.release_time.minute());
-
         let str = format!(
-            "{} {} {}:{} {}",
-            self.release_time.date_naive(),
+            "{} {} {}",
+            date_formatting::date_str(self.release_time.date_naive()),
             self.release_time.weekday(),
-            hour,
-            minute,
-            pm_am
+            date_formatting::time_str(&self.release_time)
         );
 
         write!(f, "{}", str)
@@ -153,18 +152,21 @@ pub async fn get_episode_information(
     let url = url.replace("SEASON", &season.to_string());
     let url = url.replace("EPISODE", &episode.to_string());
 
-    let prettified_json = get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)?;
+    let prettified_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &prettified_json)
+}
 
-    deserialize_json(&prettified_json)
+pub fn get_episode_list_url(series_id: u32) -> String {
+    EPISODE_LIST_ADDRESS.replace("SERIES-ID", &series_id.to_string())
 }
 
 pub async fn get_episode_list(series_id: u32) -> Result<(Vec<Episode>, String), ApiError> {
-    let url = EPISODE_LIST_ADDRESS.replace("SERIES-ID", &series_id.to_string());
-    let prettified_json = get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)?;
+    let url = get_episode_list_url(series_id);
+    let prettified_json = get_pretty_json_from_url(url.clone()).await?;
 
-    Ok((deserialize_json(&prettified_json)?, prettified_json))
+    Ok((
+        deserialize_json_from_url(&url, &prettified_json)?,
+        prettified_json,
+    ))
 }
@@ -0,0 +1,119 @@
+//! # Errors of the TVmaze api
+//!
+//! Every error knows how it should be retried and how it is explained to the user, so
+//! that callers don't have to inspect the underlying network or decoding errors.
+
+use std::time::Duration;
+
+use reqwest::header::{self, HeaderMap};
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// How long to wait before retrying when TVmaze does not say how long to wait
+///
+/// TVmaze allows at least 20 calls every 10 seconds per ip address.
+const DEFAULT_RATE_LIMIT_DELAY: Duration = Duration::from_secs(10);
+
+/// How long to wait before retrying when TVmaze has trouble serving requests
+const SERVER_ERROR_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("no internet connection: {0}")]
+    Offline(reqwest::Error),
+    #[error("network error during request: {0}")]
+    Network(reqwest::Error),
+    #[error("tvmaze rate limit reached for '{url}'")]
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("'{0}' was not found on tvmaze")]
+    NotFound(String),
+    #[error("invalid status code '{1}' from '{0}'")]
+    InvalidStatusCode(String, StatusCode),
+    #[error("failed to decode json from '{}': unexpected '{line}': {reason}", .url.as_deref().unwrap_or("cache"))]
+    Decode {
+        /// `None` when the json came from the cache
+        url: Option<String>,
+        line: String,
+        reason: String,
+    },
+    #[error("errored json from tvmaze: name: '{0}', message: '{1}'")]
+    BadJson(String, String),
+}
+
+/// How a failed request should be retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryStrategy {
+    /// Retrying will fail the same way
+    Never,
+    /// Retrying can succeed after the given delay
+    After(Duration),
+    /// Retrying can only succeed once the internet connection is back
+    WhenOnline,
+}
+
+impl ApiError {
+    /// Classifies a failed request
+    pub fn from_request_error(err: reqwest::Error) -> Self {
+        if err.is_connect() {
+            Self::Offline(err)
+        } else {
+            Self::Network(err)
+        }
+    }
+
+    /// Classifies an error status code of the response from the given url
+    pub fn from_status(url: &str, status: StatusCode, headers: &HeaderMap) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => Self::NotFound(url.to_owned()),
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited {
+                url: url.to_owned(),
+                retry_after: headers
+                    .get(header::RETRY_AFTER)
+                    .and_then(|retry_after| retry_after.to_str().ok())
+                    .and_then(|retry_after| retry_after.parse().ok())
+                    .map(Duration::from_secs),
+            },
+            status => Self::InvalidStatusCode(url.to_owned(), status),
+        }
+    }
+
+    pub fn retry_strategy(&self) -> RetryStrategy {
+        match self {
+            Self::Offline(_) => RetryStrategy::WhenOnline,
+            Self::Network(err) if err.is_timeout() || err.is_request() => {
+                RetryStrategy::After(super::random_delay())
+            }
+            Self::Network(_) => RetryStrategy::Never,
+            Self::RateLimited { retry_after, .. } => {
+                RetryStrategy::After(retry_after.unwrap_or(DEFAULT_RATE_LIMIT_DELAY))
+            }
+            Self::InvalidStatusCode(_, status) if status.is_server_error() => {
+                RetryStrategy::After(SERVER_ERROR_DELAY)
+            }
+            Self::InvalidStatusCode(..) | Self::NotFound(_) => RetryStrategy::Never,
+            Self::Decode { .. } | Self::BadJson(..) => RetryStrategy::Never,
+        }
+    }
+
+    /// Explains the error to the user
+    pub fn user_message(&self) -> &'static str {
+        match self {
+            Self::Offline(_) => "You appear to be offline, check your internet connection",
+            Self::Network(_) => "Could not reach TVmaze, please try again",
+            Self::RateLimited { .. } => {
+                "TVmaze is receiving too many requests, please try again in a moment"
+            }
+            Self::NotFound(_) => "This could not be found on TVmaze, it may have been removed",
+            Self::InvalidStatusCode(_, status) if status.is_server_error() => {
+                "TVmaze is having trouble right now, please try again later"
+            }
+            Self::InvalidStatusCode(..) => "TVmaze refused the request",
+            Self::Decode { .. } | Self::BadJson(..) => {
+                "TVmaze sent data that could not be understood, updating may fix this"
+            }
+        }
+    }
+}
@@ -3,6 +3,9 @@ use std::io::Write;
 use bytes::Bytes;
 use tracing::error;
 
+use super::{data_usage, ApiError, RetryStrategy};
+use crate::core::metrics;
+
 const POSTER_WIDTH: u32 = 480;
 const POSTER_HEIGHT: u32 = 853;
 const BACKGROUND_WIDTH: u32 = 1280;
@@ -24,10 +27,13 @@ pub enum ImageKind {
 /// Since Original images from TvMaze may have extremely high resolution up to 4k which can cause `wgpu` to crash,
 /// this function will thumbnail the original image to the size that is good enough to be displayed in the GUI.
 pub async fn load_image(image_url: String, image_resolution: ImageResolution) -> Option<Bytes> {
+    let _request_guard = metrics::track_request(&image_url);
     loop {
         match reqwest::get(&image_url).await {
             Ok(response) => {
                 if let Ok(bytes) = response.bytes().await {
+                    data_usage::record_download(bytes.len());
+
                     let image = image::load_from_memory(&bytes)
                         .map_err(|err| error!("failed to load image from the api: {}", err))
                         .ok()?;
@@ -44,11 +50,15 @@ pub async fn load_image(image_url: String, image_resolution: ImageResolution) ->
                     };
                 }
             }
-            Err(ref err) => {
-                if err.is_request() {
-                    super::random_async_sleep().await;
-                } else {
-                    break None;
+            Err(err) => {
+                let err = ApiError::from_request_error(err);
+                match err.retry_strategy() {
+                    RetryStrategy::After(delay) => tokio::time::sleep(delay).await,
+                    _ => {
+                        error!("failed to load image '{}': {}", image_url, err);
+                        metrics::record_error(format!("{}: {}", image_url, err));
+                        break None;
+                    }
                 }
             }
         }
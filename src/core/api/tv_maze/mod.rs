@@ -1,8 +1,22 @@
+use std::time::Duration;
+
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
 
+use crate::core::metrics;
+
+pub use error::{ApiError, RetryStrategy};
+
+pub mod alternate_lists;
+pub mod connectivity;
+pub mod data_usage;
+pub mod diagnostics;
+pub mod episode_guests;
 pub mod episodes_information;
+mod error;
 pub mod image;
+pub mod people;
 pub mod seasons_list;
 pub mod series_information;
 pub mod series_searching;
@@ -12,15 +26,8 @@ pub mod show_lookup;
 pub mod tv_schedule;
 pub mod updates;
 
-#[derive(Debug, Error)]
-pub enum ApiError {
-    #[error("network error during request")]
-    Network(reqwest::Error),
-    #[error("tvmaze api error when deserializing json: unexpected '{0}'")]
-    Deserialization(String, serde_json::Error),
-    #[error("errored json from tvmaze: name: '{0}', message: '{1}'")]
-    BadJson(String, String),
-}
+/// How many times a request is attempted before giving up on retryable errors
+const MAX_REQUEST_ATTEMPTS: u32 = 4;
 
 #[derive(Debug, Deserialize, Clone)]
 struct BadResponse {
@@ -58,6 +65,22 @@ fn try_bad_json(json_string: &str) -> Option<(String, String)> {
 
 pub fn deserialize_json<'a, T: serde::Deserialize<'a>>(
     prettified_json: &'a str,
+) -> Result<T, ApiError> {
+    decode_json(None, prettified_json)
+}
+
+/// Deserializes the json requested from the given url
+pub fn deserialize_json_from_url<'a, T: serde::Deserialize<'a>>(
+    url: &str,
+    prettified_json: &'a str,
+) -> Result<T, ApiError> {
+    decode_json(Some(url), prettified_json)
+}
+
+/// Deserializes the json, recording it for diagnostics when it does not have the expected shape
+fn decode_json<'a, T: serde::Deserialize<'a>>(
+    url: Option<&str>,
+    prettified_json: &'a str,
 ) -> Result<T, ApiError> {
     serde_json::from_str::<T>(prettified_json).map_err(|err| {
         if let Some(data) = try_bad_json(prettified_json) {
@@ -72,33 +95,155 @@ pub fn deserialize_json<'a, T: serde::Deserialize<'a>>(
             .skip(line_number)
             .take(1)
             .for_each(|line| errored_line = line.to_owned());
-        ApiError::Deserialization(errored_line, err)
+        let reason = err.to_string();
+        diagnostics::record_schema_error(url, prettified_json, &reason);
+
+        ApiError::Decode {
+            url: url.map(ToOwned::to_owned),
+            line: errored_line,
+            reason,
+        }
     })
 }
 
+fn prettify_json(url: &str, text: &str) -> Result<String, ApiError> {
+    json::parse(text)
+        .map(|json| json::stringify_pretty(json, 1))
+        .map_err(|err| ApiError::Decode {
+            url: Some(url.to_owned()),
+            line: text.lines().next().unwrap_or_default().to_owned(),
+            reason: err.to_string(),
+        })
+}
+
+/// Validators of a cached response, sent along when refreshing the cache so that the
+/// response is only sent again when it has changed
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header_value = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(|value| value.to_owned())
+        };
+
+        Self {
+            etag: header_value(header::ETAG),
+            last_modified: header_value(header::LAST_MODIFIED),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// The outcome of a conditional request
+#[derive(Debug)]
+pub enum Revalidation {
+    /// The response the validators belong to is still up to date
+    NotModified,
+    /// The response changed, carrying the new json and it's validators
+    Modified(String, CacheValidators),
+}
+
 /// Requests text response from the provided url
-async fn get_pretty_json_from_url(url: String) -> Result<String, reqwest::Error> {
-    let response = loop {
-        match reqwest::get(&url).await {
-            Ok(response) => break response,
-            Err(err) => {
-                if err.is_request() {
-                    random_async_sleep().await;
+async fn get_pretty_json_from_url(url: String) -> Result<String, ApiError> {
+    let response = send_request(&url, &CacheValidators::default()).await?;
+
+    let text = response
+        .text()
+        .await
+        .map_err(ApiError::from_request_error)?;
+    data_usage::record_download(text.len());
+
+    prettify_json(&url, &text)
+}
+
+/// Requests text response from the provided url only when it has changed since the
+/// response the validators belong to
+pub async fn revalidate_pretty_json_from_url(
+    url: String,
+    validators: &CacheValidators,
+) -> Result<Revalidation, ApiError> {
+    let response = send_request(&url, validators).await?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Revalidation::NotModified);
+    }
+
+    let validators = CacheValidators::from_headers(response.headers());
+    let text = response
+        .text()
+        .await
+        .map_err(ApiError::from_request_error)?;
+    data_usage::record_download(text.len());
+
+    Ok(Revalidation::Modified(
+        prettify_json(&url, &text)?,
+        validators,
+    ))
+}
+
+/// Sends a get request with the revalidation headers of the given validators
+///
+/// Failed requests are retried according to the retry strategy of their error.
+async fn send_request(
+    url: &str,
+    validators: &CacheValidators,
+) -> Result<reqwest::Response, ApiError> {
+    let client = reqwest::Client::new();
+    let _request_guard = metrics::track_request(url);
+
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+
+        let mut request = client.get(url);
+        if let Some(etag) = &validators.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let result = request
+            .send()
+            .await
+            .map_err(ApiError::from_request_error)
+            .and_then(|response| {
+                let status = response.status();
+                if status.is_client_error() || status.is_server_error() {
+                    Err(ApiError::from_status(url, status, response.headers()))
                 } else {
+                    Ok(response)
+                }
+            });
+
+        match result {
+            Ok(response) => break Ok(response),
+            Err(err) => match err.retry_strategy() {
+                RetryStrategy::After(delay) if attempts < MAX_REQUEST_ATTEMPTS => {
+                    tracing::warn!("retrying request in {:?}: {}", delay, err);
+                    tokio::time::sleep(delay).await;
+                }
+                _ => {
+                    metrics::record_error(format!("{}: {}", url, err));
                     return Err(err);
                 }
-            }
+            },
         }
-    };
-
-    let text = response.text().await?;
-
-    Ok(json::stringify_pretty(json::parse(&text).unwrap(), 1))
+    }
 }
 
-/// Sleeps the current thread asynchronously between 0-0.2 seconds choosing a random
-/// value in between.
-async fn random_async_sleep() {
+/// A random delay between 0-0.2 seconds
+fn random_delay() -> Duration {
     let random_val = rand::random::<u64>() / 100_000_000_000_000_000;
-    tokio::time::sleep(std::time::Duration::from_millis(random_val)).await;
+    Duration::from_millis(random_val)
 }
@@ -0,0 +1,32 @@
+use serde::Deserialize;
+
+use super::series_information::SeriesMainInformation;
+use super::{deserialize_json_from_url, get_pretty_json_from_url, ApiError};
+
+// replace ID with the actual person id
+const CAST_CREDITS_ADDRESS: &str = "https://api.tvmaze.com/people/ID/castcredits?embed=show";
+
+/// A series a person is in the main cast of
+#[derive(Deserialize, Debug, Clone)]
+pub struct CastCredit {
+    #[serde(rename = "_embedded")]
+    embedded: CastCreditEmbedded,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct CastCreditEmbedded {
+    show: SeriesMainInformation,
+}
+
+impl CastCredit {
+    pub fn series_info(&self) -> &SeriesMainInformation {
+        &self.embedded.show
+    }
+}
+
+pub async fn get_cast_credits(person_id: u32) -> Result<Vec<CastCredit>, ApiError> {
+    let url = CAST_CREDITS_ADDRESS.replace("ID", &person_id.to_string());
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &pretty_json)
+}
@@ -16,9 +16,7 @@ pub struct Season {
 
 pub async fn get_seasons_list(series_id: u32) -> Result<Vec<Season>, ApiError> {
     let url = SEASONS_LIST_ADDRESS.replace("SERIES-ID", &series_id.to_string());
-    let prettified_json = get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)?;
+    let prettified_json = get_pretty_json_from_url(url.clone()).await?;
 
-    deserialize_json(&prettified_json)
+    deserialize_json_from_url(&url, &prettified_json)
 }
@@ -235,7 +235,8 @@ pub struct SeriesMainInformation {
     /// with an embedded list of series' episodes.
     #[serde(rename = "_embedded")]
     pub embedded_episode_list: Option<EmbeddedEpisodeList>,
-    // pub externals: ExternalIds,
+    #[serde(default)]
+    pub externals: Option<ExternalIds>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -343,23 +344,27 @@ pub struct ExternalIds {
     pub thetvdb: Option<u32>,
 }
 
-pub async fn get_series_main_info_with_url(url: String) -> Result<String, ApiError> {
-    get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)
+pub async fn get_series_main_info_with_url(url: String) -> Result<SeriesMainInformation, ApiError> {
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &pretty_json)
+}
+
+pub fn get_series_main_info_url(series_id: u32) -> String {
+    format!("{}{}", SERIES_INFORMATION_ADDRESS, series_id)
 }
 
-pub async fn get_series_main_info_with_id(series_id: u32) -> Result<String, ApiError> {
-    get_series_main_info_with_url(format!("{}{}", SERIES_INFORMATION_ADDRESS, series_id)).await
+pub async fn get_series_main_info_with_id(
+    series_id: u32,
+) -> Result<SeriesMainInformation, ApiError> {
+    get_series_main_info_with_url(get_series_main_info_url(series_id)).await
 }
 
 pub async fn get_series_info_and_episode_list(
     series_id: u32,
 ) -> Result<SeriesMainInformation, ApiError> {
     let url = SERIES_INFO_AND_EPISODE_LIST.replace("ID", &series_id.to_string());
-    let pretty_json = get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)?;
+    let pretty_json = get_pretty_json_from_url(url.clone()).await?;
 
-    deserialize_json(&pretty_json)
+    deserialize_json_from_url(&url, &pretty_json)
 }
@@ -6,6 +6,9 @@ use super::*;
 // The series name goes after the equals sign
 const SERIES_SEARCH_ADDRESS: &str = "https://api.tvmaze.com/search/shows?q=";
 
+// The series name goes after the equals sign, only the best match is returned
+const SERIES_SINGLE_SEARCH_ADDRESS: &str = "https://api.tvmaze.com/singlesearch/shows?q=";
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct SeriesSearchResult {
     pub show: series_information::SeriesMainInformation,
@@ -14,9 +17,23 @@ pub struct SeriesSearchResult {
 pub async fn search_series(series_name: String) -> Result<Vec<SeriesSearchResult>, ApiError> {
     let url = format!("{}{}", SERIES_SEARCH_ADDRESS, series_name);
 
-    let prettified_json = get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)?;
+    let prettified_json = get_pretty_json_from_url(url.clone()).await?;
+
+    deserialize_json_from_url(&url, &prettified_json)
+}
+
+/// Searches the single series best matching the name, `None` when nothing matches
+pub async fn single_search_series(
+    series_name: String,
+) -> Result<Option<series_information::SeriesMainInformation>, ApiError> {
+    let url = format!("{}{}", SERIES_SINGLE_SEARCH_ADDRESS, series_name);
+
+    let prettified_json = match get_pretty_json_from_url(url.clone()).await {
+        Ok(prettified_json) => prettified_json,
+        // tvmaze responds with 404 when no series matches
+        Err(ApiError::NotFound(_)) => return Ok(None),
+        Err(err) => return Err(err),
+    };
 
-    deserialize_json(&prettified_json)
+    Ok(Some(deserialize_json_from_url(&url, &prettified_json)?))
 }
@@ -6,10 +6,17 @@ use super::{get_pretty_json_from_url, ApiError, Image};
 pub struct Cast {
     pub person: Person,
     pub character: Character,
+    /// Whether the person plays themself
+    #[serde(rename = "self", default)]
+    pub is_self: bool,
+    /// Whether the person only voices the character
+    #[serde(default)]
+    pub voice: bool,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Person {
+    pub id: u32,
     pub name: String,
     pub gender: Option<String>,
     pub birthday: Option<String>,
@@ -28,6 +35,21 @@ pub enum AgeError {
 }
 
 impl Cast {
+    /// Describes the role of the person i.e. `as Jane Doe (voice)`
+    pub fn role(&self) -> String {
+        let character = if self.is_self {
+            "Themself"
+        } else {
+            &self.character.name
+        };
+
+        if self.voice {
+            format!("as {} (voice)", character)
+        } else {
+            format!("as {}", character)
+        }
+    }
+
     pub fn duration_since_birth(&self) -> Result<chrono::Duration, AgeError> {
         let date = self.person.birthday.as_ref().ok_or(AgeError::NotFound)?;
         let birthdate =
@@ -53,10 +75,10 @@ pub struct Character {
 // replace ID with the actual show id
 const SHOW_CAST_ADDRESS: &str = "https://api.tvmaze.com/shows/ID/cast";
 
-pub async fn get_show_cast(series_id: u32) -> Result<String, ApiError> {
-    let url = SHOW_CAST_ADDRESS.replace("ID", &series_id.to_string());
+pub fn get_show_cast_url(series_id: u32) -> String {
+    SHOW_CAST_ADDRESS.replace("ID", &series_id.to_string())
+}
 
-    get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)
+pub async fn get_show_cast(series_id: u32) -> Result<String, ApiError> {
+    get_pretty_json_from_url(get_show_cast_url(series_id)).await
 }
@@ -58,13 +58,13 @@ pub struct MediumResolution {
 // Relplace ID with the actual series id
 const IMAGES_ADDRESS: &str = "https://api.tvmaze.com/shows/ID/images";
 
+pub fn get_show_images_url(series_id: u32) -> String {
+    IMAGES_ADDRESS.replace("ID", &series_id.to_string())
+}
+
 /// Retrieves all the images available for the given series id
 pub async fn get_show_images(series_id: u32) -> Result<String, ApiError> {
-    let url = IMAGES_ADDRESS.replace("ID", &series_id.to_string());
-
-    get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)
+    get_pretty_json_from_url(get_show_images_url(series_id)).await
 }
 
 // /// Loads the most recent image banner from the provided series id
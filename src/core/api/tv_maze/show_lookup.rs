@@ -1,5 +1,5 @@
 use super::series_information::SeriesMainInformation;
-use super::{deserialize_json, ApiError};
+use super::{deserialize_json_from_url, ApiError};
 
 // For tvdb, the link should look like https://api.tvmaze.com/lookup/shows?thetvdb=81189
 // For imdb, the link should look like https://api.tvmaze.com/lookup/shows?imdb=tt0944947
@@ -18,14 +18,17 @@ pub async fn show_lookup(show_id: Id) -> Result<Option<SeriesMainInformation>, A
         Id::Tvdb(tvdb_id) => format!("{}{}{}", SHOW_LOOKUP_ADDRESS, "thetvdb=", tvdb_id),
     };
 
-    let pretty_json_str = super::get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)?;
+    let pretty_json_str = match super::get_pretty_json_from_url(url.clone()).await {
+        Ok(pretty_json_str) => pretty_json_str,
+        // tvmaze responds with 404 when there is no show with the given id
+        Err(ApiError::NotFound(_)) => return Ok(None),
+        Err(err) => return Err(err),
+    };
 
     // handling the case when the show is not found
     if serde_json::from_str::<Option<()>>(&pretty_json_str).is_ok() {
         return Ok(None);
     }
 
-    Ok(Some(deserialize_json(&pretty_json_str)?))
+    Ok(Some(deserialize_json_from_url(&url, &pretty_json_str)?))
 }
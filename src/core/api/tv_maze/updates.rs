@@ -1,4 +1,4 @@
-use super::deserialize_json;
+use super::deserialize_json_from_url;
 use super::get_pretty_json_from_url;
 use super::ApiError;
 
@@ -25,11 +25,9 @@ pub async fn get_shows_updates_index(
 
     tracing::info!("fetching shows updates");
 
-    let prettified_json = get_pretty_json_from_url(url)
-        .await
-        .map_err(ApiError::Network)?;
+    let prettified_json = get_pretty_json_from_url(url.clone()).await?;
 
-    deserialize_json(&prettified_json)
+    deserialize_json_from_url(&url, &prettified_json)
 }
 
 pub enum LastUpdated {
@@ -0,0 +1,66 @@
+//! # App lock
+//!
+//! An optional PIN or password asked when the app starts and after being idle for a while,
+//! keeping the notes and watch history away from others on shared computers.
+//! It only locks the graphical interface, the data on disk is left as is. The `history`
+//! command line and the exported notes are not protected by it.
+
+use super::passwords;
+use super::settings_config::{AppLockSettings, SETTINGS};
+
+impl AppLockSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.password_hash.is_some()
+    }
+
+    /// Whether the given PIN or password unlocks the app, always true when the lock is disabled
+    pub fn verify(&self, password: &str) -> bool {
+        self.password_hash
+            .as_ref()
+            .map(|password_hash| passwords::verify(password, password_hash))
+            .unwrap_or(true)
+    }
+
+    pub fn set_password(&mut self, password: &str) {
+        self.password_hash = Some(passwords::hash(password));
+    }
+
+    /// Whether the app should be locked after the given time without any input
+    pub fn is_idle_timeout(&self, idle_time: std::time::Duration) -> bool {
+        self.is_enabled()
+            && self.idle_minutes > 0
+            && idle_time.as_secs() >= self.idle_minutes as u64 * 60
+    }
+}
+
+/// Whether the given PIN or password unlocks the app, always true when the lock is disabled
+///
+/// A password hashed by an older version is hashed again once it is entered.
+pub fn unlock(password: &str) -> bool {
+    let app_lock = get_app_lock_settings();
+
+    if !app_lock.verify(password) {
+        return false;
+    }
+
+    if app_lock
+        .password_hash
+        .as_deref()
+        .is_some_and(passwords::needs_rehash)
+    {
+        SETTINGS
+            .write()
+            .unwrap()
+            .save_app_lock_password_hash(passwords::hash(password));
+    }
+    true
+}
+
+pub fn get_app_lock_settings() -> AppLockSettings {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .app_lock
+        .clone()
+}
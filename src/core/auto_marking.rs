@@ -0,0 +1,105 @@
+//! # Auto marking aired episodes
+//!
+//! Some series, like reality or news shows, are always watched live. Series with auto marking
+//! turned on have their episodes marked watched once they air, checked every hour in the
+//! background.
+//!
+//! Every episode is only marked once, right after it aired, so unmarking it or skipping it
+//! afterwards is kept. The marked episodes are labeled as such, so that the statistics can
+//! tell them apart from the episodes marked by hand.
+
+use std::time;
+
+use anyhow::Context;
+use chrono::Utc;
+use tracing::{error, info};
+
+use super::caching::episode_list::EpisodeList;
+use super::database::DB;
+
+/// Marks the newly aired episodes of the series using auto marking, forever
+pub async fn run_auto_marking() {
+    loop {
+        mark_aired_episodes().await;
+        tokio::time::sleep(time::Duration::from_secs(60 * 60)).await;
+    }
+}
+
+/// Marks the episodes aired since the last time for every series using auto marking,
+/// archived series aside
+async fn mark_aired_episodes() {
+    let archived_series_ids = DB.get_archived_series_ids();
+
+    for (series_id, since) in DB
+        .get_auto_mark_aired_series()
+        .into_iter()
+        .filter(|(series_id, _)| !archived_series_ids.contains(series_id))
+    {
+        let until = Utc::now().timestamp();
+
+        match mark_series_aired_episodes(series_id, since, until).await {
+            Ok(marked_episodes) => {
+                if marked_episodes != 0 {
+                    info!(
+                        "automatically marked {} aired episodes of series {}",
+                        marked_episodes, series_id
+                    );
+                }
+                DB.advance_auto_mark_aired(series_id, until);
+            }
+            Err(err) => error!(
+                "failed to mark the aired episodes of series {}: {}",
+                series_id, err
+            ),
+        }
+    }
+}
+
+/// Marks the episodes of the series aired between the given unix timestamps, returning how
+/// many were newly marked
+///
+/// Skipped episodes are left unwatched.
+async fn mark_series_aired_episodes(
+    series_id: u32,
+    since: i64,
+    until: i64,
+) -> anyhow::Result<usize> {
+    let episode_list = EpisodeList::new(series_id)
+        .await
+        .with_context(|| format!("failed to get episode list of series {}", series_id))?;
+
+    let Some(mut series) = DB.get_series(series_id) else {
+        return Ok(0);
+    };
+    if !series.is_tracked() {
+        return Ok(0);
+    }
+
+    let skipped_episodes = DB.get_skipped_episodes(series_id);
+    let mut marked_episodes = 0;
+
+    episode_list
+        .get_all_episodes()
+        .iter()
+        .filter(|episode| {
+            episode
+                .local_date_time()
+                .is_ok_and(|aired_at| (since..until).contains(&aired_at.timestamp()))
+        })
+        .filter_map(|episode| episode.number.map(|number| (episode.season, number)))
+        .filter(|episode| !skipped_episodes.contains(episode))
+        .for_each(|(season_number, episode_number)| {
+            let is_watched = series
+                .get_season(season_number)
+                .map(|season| season.is_episode_watched(episode_number))
+                .unwrap_or(false);
+
+            if !is_watched {
+                series.add_episode_unchecked(season_number, episode_number);
+                DB.set_episode_auto_marked(series_id, season_number, episode_number, true);
+                marked_episodes += 1;
+            }
+        });
+
+    Ok(marked_episodes)
+}
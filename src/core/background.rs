@@ -0,0 +1,171 @@
+//! # Background tasks
+//!
+//! Work the user is not waiting on, like refreshing the cache, prefetching images and
+//! syncing, runs on its own small runtime so that it never slows down what the user asked
+//! to load. Only a few of these tasks run at once and only so many wait for their turn,
+//! the rest are turned away to be picked up by their next schedule.
+//!
+//! Long lived services, like the schedules of the syncs and backups, run on the same runtime.
+//! They spend their time waiting so they do not take a slot, the work they schedule does.
+//!
+//! How the tasks of each kind fared is kept for the background tasks panel in the settings.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use thiserror::Error;
+use tokio::runtime::Runtime;
+use tokio::sync::Semaphore;
+
+const WORKER_THREADS: usize = 2;
+
+/// How many background tasks run at the same time
+const MAX_RUNNING_TASKS: usize = 2;
+
+/// How many background tasks can wait for a free slot before new ones are turned away
+const MAX_QUEUED_TASKS: usize = 16;
+
+lazy_static! {
+    static ref RUNTIME: Runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(WORKER_THREADS)
+        .thread_name("troxide-background")
+        .enable_all()
+        .build()
+        .expect("failed to create the background runtime");
+    static ref RUNNING_SLOTS: Semaphore = Semaphore::new(MAX_RUNNING_TASKS);
+    static ref STATS: Mutex<HashMap<TaskKind, TaskStats>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskKind {
+    Refresh,
+    Prefetch,
+    Sync,
+    Backup,
+    LanSync,
+}
+
+pub const ALL_TASK_KINDS: [TaskKind; 5] = [
+    TaskKind::Refresh,
+    TaskKind::Prefetch,
+    TaskKind::Sync,
+    TaskKind::Backup,
+    TaskKind::LanSync,
+];
+
+impl std::fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            TaskKind::Refresh => "Cache refresh",
+            TaskKind::Prefetch => "Image prefetch",
+            TaskKind::Sync => "Media server sync",
+            TaskKind::Backup => "Remote backup",
+            TaskKind::LanSync => "Local network sync",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskStats {
+    pub queued: usize,
+    pub running: usize,
+    pub completed: usize,
+    /// Tasks that panicked
+    pub failed: usize,
+    /// Tasks turned away as too many were waiting
+    pub rejected: usize,
+    total_run_time: Duration,
+}
+
+impl TaskStats {
+    /// How long the completed tasks took on average
+    pub fn average_run_time(&self) -> Option<Duration> {
+        (self.completed > 0).then(|| self.total_run_time / self.completed as u32)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BackgroundError {
+    #[error("too many background tasks are waiting")]
+    QueueFull,
+    #[error("background task failed: {0}")]
+    Failed(#[from] tokio::task::JoinError),
+}
+
+fn update_stats(kind: TaskKind, update: impl FnOnce(&mut TaskStats)) {
+    update(STATS.lock().unwrap().entry(kind).or_default())
+}
+
+/// Runs the task on the background runtime once there is a free slot, returning its output
+pub async fn run<F>(kind: TaskKind, task: F) -> Result<F::Output, BackgroundError>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    {
+        let mut stats = STATS.lock().unwrap();
+        let total_queued: usize = stats.values().map(|stats| stats.queued).sum();
+        let stats = stats.entry(kind).or_default();
+        if total_queued >= MAX_QUEUED_TASKS {
+            stats.rejected += 1;
+            return Err(BackgroundError::QueueFull);
+        }
+        stats.queued += 1;
+    }
+
+    let handle = RUNTIME.spawn(async move {
+        let _slot = RUNNING_SLOTS
+            .acquire()
+            .await
+            .expect("running slots are never closed");
+        update_stats(kind, |stats| {
+            stats.queued -= 1;
+            stats.running += 1;
+        });
+
+        let started = Instant::now();
+        let output = task.await;
+
+        update_stats(kind, |stats| {
+            stats.running -= 1;
+            stats.completed += 1;
+            stats.total_run_time += started.elapsed();
+        });
+        output
+    });
+
+    handle.await.map_err(|err| {
+        update_stats(kind, |stats| {
+            stats.running = stats.running.saturating_sub(1);
+            stats.failed += 1;
+        });
+        err.into()
+    })
+}
+
+/// Like [`run`] for tasks nobody waits on
+pub fn spawn(kind: TaskKind, task: impl Future<Output = ()> + Send + 'static) {
+    RUNTIME.spawn(async move {
+        if let Err(err) = run(kind, task).await {
+            tracing::warn!("{} task did not run: {}", kind, err);
+        }
+    });
+}
+
+/// Runs a service that lives as long as the program on the background runtime
+pub fn spawn_service(service: impl Future<Output = ()> + Send + 'static) {
+    RUNTIME.spawn(service);
+}
+
+/// How the tasks of every kind fared so far
+pub fn get_stats() -> Vec<(TaskKind, TaskStats)> {
+    let stats = STATS.lock().unwrap();
+    ALL_TASK_KINDS
+        .into_iter()
+        .map(|kind| (kind, stats.get(&kind).cloned().unwrap_or_default()))
+        .collect()
+}
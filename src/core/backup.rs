@@ -0,0 +1,4 @@
+//! Backing up of series tracking data away from the device
+
+pub mod encryption;
+pub mod remote;
@@ -0,0 +1,167 @@
+//! # Passphrase based encryption of backups
+//!
+//! Backups are encrypted with AES-256-GCM before leaving the device. The key is derived
+//! from the passphrase with Argon2id and a random salt, so a wrong passphrase or a
+//! tampered backup fails authentication before anything is imported.
+//!
+//! The encrypted layout is `MAGIC | salt | nonce | ciphertext`, the header being
+//! authenticated along with the ciphertext.
+//!
+//! Deriving the key is deliberately slow, these functions should be run with
+//! `tokio::task::spawn_blocking` from async code.
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::Argon2;
+use rand::RngCore;
+use thiserror::Error;
+
+const MAGIC: &[u8] = b"TRXBAK2";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum DecryptionError {
+    #[error("the data is not an encrypted series troxide backup")]
+    NotABackup,
+    #[error("wrong passphrase or corrupted backup")]
+    Authentication,
+}
+
+/// Encrypts the data with the given passphrase
+pub fn encrypt(passphrase: &str, data: &[u8]) -> Vec<u8> {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut encrypted = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN);
+    encrypted.extend_from_slice(MAGIC);
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&nonce);
+
+    let ciphertext = cipher(passphrase, &salt)
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: data,
+                aad: &encrypted,
+            },
+        )
+        .expect("backup encryption");
+    encrypted.extend_from_slice(&ciphertext);
+
+    encrypted
+}
+
+/// Decrypts data produced by [`encrypt`] with the same passphrase
+pub fn decrypt(passphrase: &str, encrypted: &[u8]) -> Result<Vec<u8>, DecryptionError> {
+    let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if encrypted.len() < header_len || !encrypted.starts_with(MAGIC) {
+        return Err(DecryptionError::NotABackup);
+    }
+
+    let (header, ciphertext) = encrypted.split_at(header_len);
+    let salt = &header[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = &header[MAGIC.len() + SALT_LEN..];
+
+    cipher(passphrase, salt)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| DecryptionError::Authentication)
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Aes256Gcm {
+    let mut key = [0; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .expect("valid key derivation parameters");
+    Aes256Gcm::new(&key.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+    const DATA: &[u8] = b"(series: [], episode_changes: [])";
+
+    #[test]
+    fn encrypted_data_round_trips() {
+        let encrypted = encrypt(PASSPHRASE, DATA);
+
+        assert!(!encrypted.windows(DATA.len()).any(|window| window == DATA));
+        assert_eq!(decrypt(PASSPHRASE, &encrypted).unwrap(), DATA);
+    }
+
+    #[test]
+    fn empty_data_round_trips() {
+        let encrypted = encrypt(PASSPHRASE, &[]);
+        assert!(decrypt(PASSPHRASE, &encrypted).unwrap().is_empty());
+    }
+
+    #[test]
+    fn same_data_encrypts_differently() {
+        assert_ne!(encrypt(PASSPHRASE, DATA), encrypt(PASSPHRASE, DATA));
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let encrypted = encrypt(PASSPHRASE, DATA);
+
+        assert!(matches!(
+            decrypt("wrong passphrase", &encrypted),
+            Err(DecryptionError::Authentication)
+        ));
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let mut encrypted = encrypt(PASSPHRASE, DATA);
+        *encrypted.last_mut().unwrap() ^= 1;
+
+        assert!(matches!(
+            decrypt(PASSPHRASE, &encrypted),
+            Err(DecryptionError::Authentication)
+        ));
+    }
+
+    #[test]
+    fn tampered_header_is_rejected() {
+        let mut encrypted = encrypt(PASSPHRASE, DATA);
+        encrypted[MAGIC.len()] ^= 1;
+
+        assert!(matches!(
+            decrypt(PASSPHRASE, &encrypted),
+            Err(DecryptionError::Authentication)
+        ));
+    }
+
+    #[test]
+    fn truncated_backup_is_rejected() {
+        let encrypted = encrypt(PASSPHRASE, DATA);
+
+        assert!(matches!(
+            decrypt(PASSPHRASE, &encrypted[..encrypted.len() - 1]),
+            Err(DecryptionError::Authentication)
+        ));
+        assert!(matches!(
+            decrypt(PASSPHRASE, &encrypted[..MAGIC.len() + SALT_LEN]),
+            Err(DecryptionError::NotABackup)
+        ));
+    }
+
+    #[test]
+    fn other_data_is_not_a_backup() {
+        assert!(matches!(
+            decrypt(PASSPHRASE, b"(series: [], episode_changes: [])"),
+            Err(DecryptionError::NotABackup)
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+//! # Remote backups
+//!
+//! Uploads an encrypted copy of the database to a user supplied WebDAV folder or
+//! S3 compatible bucket and restores from it. Backups are made manually from the
+//! settings or automatically at the interval set in the settings.
+//!
+//! Only a single backup is kept on the remote, every new backup overwrites the previous one.
+//! The credentials and the passphrase are kept in the OS keyring, see `core::secrets`.
+
+use std::time;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tracing::{error, info};
+
+use super::encryption::{self, DecryptionError};
+use crate::core::background::{self, TaskKind};
+use crate::core::database::database_transfer::{ImportError, TransferData};
+use crate::core::database::DB;
+use crate::core::paths;
+use crate::core::settings_config::{RemoteBackupKind, RemoteBackupSettings, SETTINGS};
+
+/// The name of the backup file on the remote
+const BACKUP_FILE_NAME: &str = "series-troxide-backup.bin";
+
+/// File in the data directory remembering when the last backup was made
+const LAST_BACKUP_FILE_NAME: &str = "last-remote-backup";
+
+#[derive(Debug, Error)]
+pub enum RemoteBackupError {
+    #[error("network error during request: {0}")]
+    Network(reqwest::Error),
+    #[error("invalid status code from the backup endpoint: '{0}'")]
+    InvalidStatusCode(reqwest::StatusCode),
+    #[error("invalid backup endpoint '{0}'")]
+    InvalidEndpoint(String),
+    #[error("a passphrase is required to encrypt backups")]
+    MissingPassphrase,
+    #[error("{0}")]
+    Decryption(DecryptionError),
+    #[error("backup encryption failed: {0}")]
+    Encryption(tokio::task::JoinError),
+    #[error("invalid backup data: {0}")]
+    Import(ImportError),
+}
+
+/// Encrypts the whole database and uploads it to the remote
+pub async fn backup(settings: &RemoteBackupSettings) -> Result<(), RemoteBackupError> {
+    if settings.passphrase.is_empty() {
+        return Err(RemoteBackupError::MissingPassphrase);
+    }
+
+    let backup_data = DB.export().ron_str();
+    let passphrase = settings.passphrase.clone();
+    let encrypted = tokio::task::spawn_blocking(move || {
+        encryption::encrypt(&passphrase, backup_data.as_bytes())
+    })
+    .await
+    .map_err(RemoteBackupError::Encryption)?;
+
+    send(settings, reqwest::Method::PUT, encrypted).await?;
+    save_last_backup_time(Utc::now()).await;
+
+    Ok(())
+}
+
+/// Downloads the backup from the remote and imports it into the database
+///
+/// Returns the amount of series in the restored backup.
+pub async fn restore(settings: &RemoteBackupSettings) -> Result<usize, RemoteBackupError> {
+    let encrypted = send(settings, reqwest::Method::GET, vec![]).await?;
+
+    let passphrase = settings.passphrase.clone();
+    let backup_data =
+        tokio::task::spawn_blocking(move || encryption::decrypt(&passphrase, &encrypted))
+            .await
+            .map_err(RemoteBackupError::Encryption)?
+            .map_err(RemoteBackupError::Decryption)?;
+    let transfer_data = TransferData::from_ron_str(&String::from_utf8_lossy(&backup_data))
+        .map_err(RemoteBackupError::Import)?;
+
+    let total_series = transfer_data.get_series().len();
+    DB.import(transfer_data);
+
+    Ok(total_series)
+}
+
+/// Runs the scheduled backups forever at the interval set in the settings
+///
+/// Backups are skipped when remote backups are not configured or are manual only.
+pub async fn run_scheduled_backup() {
+    loop {
+        let remote_backup_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .remote_backup
+            .clone();
+
+        if let Some(settings) = remote_backup_settings {
+            if let Some(schedule_interval) = settings.schedule_interval {
+                let is_due = get_last_backup_time()
+                    .await
+                    .map(|last_backup_time| {
+                        Utc::now() - last_backup_time
+                            >= chrono::Duration::hours(schedule_interval.max(1) as i64)
+                    })
+                    .unwrap_or(true);
+
+                if is_due {
+                    info!("making a scheduled remote backup");
+                    let backup_result = background::run(TaskKind::Backup, {
+                        let settings = settings.clone();
+                        async move { backup(&settings).await }
+                    })
+                    .await;
+
+                    match backup_result {
+                        Ok(Ok(_)) => info!("scheduled remote backup complete"),
+                        Ok(Err(err)) => {
+                            error!("failed to make a scheduled remote backup: {}", err)
+                        }
+                        Err(err) => error!("failed to make a scheduled remote backup: {}", err),
+                    }
+                }
+            }
+        }
+
+        // Rechecking every hour so that settings changes are picked up
+        tokio::time::sleep(time::Duration::from_secs(60 * 60)).await;
+    }
+}
+
+/// When the last backup was successfully made from this device
+pub async fn get_last_backup_time() -> Option<DateTime<Utc>> {
+    let last_backup = tokio::fs::read_to_string(get_last_backup_file_path())
+        .await
+        .ok()?;
+
+    DateTime::parse_from_rfc3339(last_backup.trim())
+        .ok()
+        .map(|last_backup_time| last_backup_time.with_timezone(&Utc))
+}
+
+async fn save_last_backup_time(backup_time: DateTime<Utc>) {
+    if let Err(err) = tokio::fs::write(get_last_backup_file_path(), backup_time.to_rfc3339()).await
+    {
+        error!("failed to save the last remote backup time: {}", err);
+    }
+}
+
+fn get_last_backup_file_path() -> std::path::PathBuf {
+    let mut last_backup_file = paths::PATHS
+        .read()
+        .expect("failed to read paths")
+        .get_data_dir_path()
+        .to_path_buf();
+    last_backup_file.push(LAST_BACKUP_FILE_NAME);
+    last_backup_file
+}
+
+async fn send(
+    settings: &RemoteBackupSettings,
+    method: reqwest::Method,
+    body: Vec<u8>,
+) -> Result<Vec<u8>, RemoteBackupError> {
+    let request = match settings.kind {
+        RemoteBackupKind::WebDav => webdav_request(settings, method, body)?,
+        RemoteBackupKind::S3 => s3_request(settings, method, body)?,
+    };
+
+    let response = request.send().await.map_err(RemoteBackupError::Network)?;
+
+    if !response.status().is_success() {
+        return Err(RemoteBackupError::InvalidStatusCode(response.status()));
+    }
+
+    response
+        .bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(RemoteBackupError::Network)
+}
+
+fn webdav_request(
+    settings: &RemoteBackupSettings,
+    method: reqwest::Method,
+    body: Vec<u8>,
+) -> Result<reqwest::RequestBuilder, RemoteBackupError> {
+    let url = format!(
+        "{}/{}",
+        settings.endpoint.trim_end_matches('/'),
+        BACKUP_FILE_NAME
+    );
+    let url = reqwest::Url::parse(&url)
+        .map_err(|_| RemoteBackupError::InvalidEndpoint(settings.endpoint.clone()))?;
+
+    Ok(reqwest::Client::new()
+        .request(method, url)
+        .basic_auth(&settings.username, Some(&settings.password))
+        .body(body))
+}
+
+/// Builds a path style S3 request signed with AWS signature version 4
+fn s3_request(
+    settings: &RemoteBackupSettings,
+    method: reqwest::Method,
+    body: Vec<u8>,
+) -> Result<reqwest::RequestBuilder, RemoteBackupError> {
+    let invalid_endpoint = || RemoteBackupError::InvalidEndpoint(settings.endpoint.clone());
+
+    let url = format!(
+        "{}/{}/{}",
+        settings.endpoint.trim_end_matches('/'),
+        settings.bucket,
+        BACKUP_FILE_NAME
+    );
+    let url = reqwest::Url::parse(&url).map_err(|_| invalid_endpoint())?;
+
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().ok_or_else(invalid_endpoint)?, port),
+        None => url.host_str().ok_or_else(invalid_endpoint)?.to_owned(),
+    };
+
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = format!("{:x}", Sha256::digest(&body));
+
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method,
+        url.path(),
+        host,
+        payload_hash,
+        amz_date,
+        signed_headers,
+        payload_hash
+    );
+
+    let scope = format!("{}/{}/s3/aws4_request", date, settings.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+        amz_date,
+        scope,
+        Sha256::digest(canonical_request.as_bytes())
+    );
+
+    let signing_key = [settings.region.as_str(), "s3", "aws4_request"]
+        .iter()
+        .fold(
+            hmac_sha256(
+                format!("AWS4{}", settings.password).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+    let signature: String = hmac_sha256(&signing_key, string_to_sign.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        settings.username, scope, signed_headers, signature
+    );
+
+    Ok(reqwest::Client::new()
+        .request(method, url)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("x-amz-date", amz_date)
+        .header("authorization", authorization)
+        .body(body))
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().into()
+}
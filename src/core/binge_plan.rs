@@ -0,0 +1,87 @@
+//! # Binge plans
+//!
+//! Spreads the remaining unwatched episodes of a series evenly over a date range chosen by
+//! the user. The plan is kept in the database so that the suggested viewing days can be
+//! shown in the schedule of those days.
+
+use anyhow::bail;
+use chrono::NaiveDate;
+
+use super::caching::episode_list::EpisodeList;
+use super::database::{BingePlan, BingePlanDay, DB};
+use crate::gui::helpers::season_episode_str_gen;
+
+/// Generates the binge plan of the series from the first to the last day, both included
+///
+/// # Note
+/// The plan is not saved into the database.
+pub async fn generate(
+    series_id: u32,
+    series_name: String,
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+) -> anyhow::Result<BingePlan> {
+    if last_day < first_day {
+        bail!("the plan can not end before it starts");
+    }
+    if DB.get_series(series_id).is_none() {
+        bail!("the series has to be tracked first");
+    }
+
+    let episode_list = EpisodeList::new(series_id).await?;
+    let episodes: Vec<(u32, u32)> = episode_list
+        .get_unwatched_episodes()
+        .into_iter()
+        .filter_map(|episode| Some((episode.season, episode.number?)))
+        .collect();
+
+    if episodes.is_empty() {
+        bail!("there are no unwatched episodes to plan");
+    }
+
+    let total_days = (last_day - first_day).num_days() as usize + 1;
+    let mut days: Vec<BingePlanDay> = vec![];
+
+    for (index, episode) in episodes.iter().enumerate() {
+        // Spreading the episodes evenly, with the extra episodes going to the first days
+        let date = first_day + chrono::Duration::days((index * total_days / episodes.len()) as i64);
+        match days.last_mut() {
+            Some(day) if day.date == date => day.episodes.push(*episode),
+            _ => days.push(BingePlanDay {
+                date,
+                episodes: vec![*episode],
+            }),
+        }
+    }
+
+    Ok(BingePlan {
+        series_id,
+        series_name,
+        days,
+    })
+}
+
+/// The binge plan days of all the series suggested for the given date
+pub fn get_plans_of_day(date: NaiveDate) -> Vec<(BingePlan, BingePlanDay)> {
+    DB.get_binge_plans()
+        .into_iter()
+        .filter_map(|binge_plan| {
+            let day = binge_plan.days.iter().find(|day| day.date == date)?.clone();
+            Some((binge_plan, day))
+        })
+        .collect()
+}
+
+impl BingePlanDay {
+    /// The episodes of the day like `S01E01 - S01E03`
+    pub fn episodes_range(&self) -> String {
+        let to_str = |(season, episode): &(u32, u32)| season_episode_str_gen(*season, *episode);
+        match (self.episodes.first(), self.episodes.last()) {
+            (Some(first), Some(last)) if first != last => {
+                format!("{} - {}", to_str(first), to_str(last))
+            }
+            (Some(first), _) => to_str(first),
+            _ => String::new(),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! # Actions applied to many series at once
+//!
+//! Used by the multi-select mode of the My Shows tab.
+
+use super::caching::episode_list::EpisodeList;
+use super::catch_up;
+use super::database;
+
+/// Untracks all the given series, keeping their watched episodes
+pub fn untrack_series(series_ids: &[u32]) {
+    for series_id in series_ids {
+        if let Some(mut series) = database::DB.get_series(*series_id) {
+            series.mark_untracked();
+        }
+    }
+}
+
+/// Stops following all the given series, untracking and archiving them
+///
+/// Unlike only untracking them, they are no longer listed in My Shows while their watched
+/// episodes keep counting in the statistics.
+pub fn stop_following_series(series_ids: &[u32]) {
+    untrack_series(series_ids);
+    for series_id in series_ids {
+        database::DB.set_archived(*series_id, true);
+    }
+}
+
+/// Gives all the given series the provided tag
+pub fn tag_series(series_ids: &[u32], tag: &str) {
+    for series_id in series_ids {
+        database::DB.add_series_tag(*series_id, tag);
+    }
+}
+
+/// The outcome of marking the aired episodes of many series at once
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MarkedEpisodes {
+    /// The total amount of episodes that were newly marked
+    pub marked_episodes: usize,
+    /// The amount of series whose aired episodes were marked
+    pub marked_series: usize,
+    /// The series whose episode list could not be loaded, left untouched
+    pub failed_series: usize,
+}
+
+/// Marks every already aired episode of the given series as watched
+///
+/// A series whose episode list can't be loaded is skipped and counted as failed, so that it
+/// does not prevent the others from being marked.
+pub async fn mark_aired_episodes_watched(series_ids: Vec<u32>) -> MarkedEpisodes {
+    let mut outcome = MarkedEpisodes::default();
+
+    for series_id in series_ids {
+        let episode_list = match EpisodeList::new(series_id).await {
+            Ok(episode_list) => episode_list,
+            Err(err) => {
+                tracing::error!(
+                    "failed to get episode list of series {}: {}",
+                    series_id,
+                    err
+                );
+                outcome.failed_series += 1;
+                continue;
+            }
+        };
+
+        let Some(mut series) = database::DB.get_series(series_id) else {
+            continue;
+        };
+
+        outcome.marked_episodes += catch_up::mark_aired_episodes(&mut series, &episode_list, None);
+        outcome.marked_series += 1;
+    }
+
+    outcome
+}
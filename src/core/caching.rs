@@ -17,6 +17,13 @@
 //! - `show-cast`. The list of top cast of the series.
 //! - `image-list`. The list of all images of the series i.e posters, banners, backgrounds etc.
 //!
+//! These files are zstd compressed on disk. Files written uncompressed or gzip compressed by
+//! older versions are still read and get compressed with zstd the first time they are read.
+//!
+//! Each cache file may have a `.validators` file next to it holding the `ETag` and
+//! `Last-Modified` headers of the response it was made from. These are sent along when
+//! refreshing the cache so that unchanged responses are not downloaded again.
+//!
 //! ## Images cache directory
 //! This contains all the images ever loaded by series troxide. Having all images ever loaded by
 //! the program in the same directory makes it more efficient by reusing the same images that might
@@ -24,6 +31,16 @@
 //! in different shows and tvmaze reuses the same image for that actor. Another example is when
 //! searching for series, results may include a series that is already tracked and whose image
 //! is already cached.
+//!
+//! When saving bandwidth, images missing from the cache are only downloaded when the user asks
+//! for them.
+//!
+//! Concurrent loads of the same cache file are coalesced into a single request, see
+//! [`in_flight`], and loads of many series at once are queued with bounded concurrency, see
+//! [`refresh_queue`].
+//!
+//! The root of the cache also keeps a small time series of the series popular in Discover,
+//! see [`popularity_trends`].
 
 use bytes::Bytes;
 use std::io::{self, ErrorKind};
@@ -31,14 +48,25 @@ use std::path;
 
 pub use super::api::tv_maze::image::{ImageKind, ImageResolution};
 use super::api::tv_maze::{series_information::SeriesMainInformation, ApiError};
+use super::metrics::{self, CacheKind};
 use super::paths;
-use crate::core::api::tv_maze::{self, deserialize_json};
+use super::settings_config::{ImageQuality, SETTINGS};
+use crate::core::api::tv_maze::{
+    self, deserialize_json, episodes_information, series_information as api_series_information,
+    show_cast as api_show_cast, show_images as api_show_images, CacheValidators, Image,
+    Revalidation,
+};
 use lazy_static::lazy_static;
 use tokio::fs;
 use tracing::{error, info};
 
 pub mod cache_updating;
+pub mod episode_guests;
 pub mod episode_list;
+pub mod image_prefetch;
+pub mod in_flight;
+pub mod popularity_trends;
+pub mod refresh_queue;
 pub mod series_info_and_episode_list;
 pub mod series_information;
 pub mod series_list;
@@ -52,6 +80,7 @@ const EPISODE_LIST_FILENAME: &str = "episode-list";
 const SERIES_MAIN_INFORMATION_FILENAME: &str = "main-info";
 const SERIES_CAST_FILENAME: &str = "show-cast";
 const SERIES_IMAGE_LIST_FILENAME: &str = "image-list";
+const CACHE_VALIDATORS_EXTENSION: &str = "validators";
 
 lazy_static! {
     pub static ref CACHER: Cacher = Cacher::init();
@@ -62,6 +91,7 @@ pub enum CacheFolderType {
     Images,
 }
 
+#[derive(Clone, Copy)]
 pub enum CacheFilePath {
     SeriesMainInformation(u32),
     SeriesEpisodeList(u32),
@@ -69,6 +99,24 @@ pub enum CacheFilePath {
     SeriesImageList(u32),
 }
 
+impl CacheFilePath {
+    /// The api url the cache file is downloaded from
+    pub fn source_url(&self) -> String {
+        match *self {
+            CacheFilePath::SeriesMainInformation(series_id) => {
+                api_series_information::get_series_main_info_url(series_id)
+            }
+            CacheFilePath::SeriesEpisodeList(series_id) => {
+                episodes_information::get_episode_list_url(series_id)
+            }
+            CacheFilePath::SeriesShowCast(series_id) => api_show_cast::get_show_cast_url(series_id),
+            CacheFilePath::SeriesImageList(series_id) => {
+                api_show_images::get_show_images_url(series_id)
+            }
+        }
+    }
+}
+
 pub struct Cacher {
     cache_path: path::PathBuf,
 }
@@ -135,8 +183,66 @@ impl Cacher {
     }
 }
 
+/// Whether the bandwidth saver of the network settings is on
+pub fn is_saving_bandwidth() -> bool {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .network
+        .bandwidth_saver
+}
+
+/// The resolution to load an image in following the image quality setting
+///
+/// With the auto quality, `large` images like the series page poster are loaded in their
+/// original resolution and the rest in medium. All images are medium when saving bandwidth.
+pub fn preferred_resolution(image_kind: ImageKind, large: bool) -> ImageResolution {
+    let network_settings = SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .network
+        .clone();
+
+    if network_settings.bandwidth_saver {
+        return ImageResolution::Medium;
+    }
+
+    match network_settings.image_quality {
+        ImageQuality::Original => ImageResolution::Original(image_kind),
+        ImageQuality::Auto if large => ImageResolution::Original(image_kind),
+        ImageQuality::Auto | ImageQuality::Medium => ImageResolution::Medium,
+    }
+}
+
+/// The url and resolution to load the image in, see [`preferred_resolution`]
+pub fn image_source(image: Image, image_kind: ImageKind, large: bool) -> (String, ImageResolution) {
+    match preferred_resolution(image_kind, large) {
+        ImageResolution::Medium => (image.medium_image_url, ImageResolution::Medium),
+        original => (image.original_image_url, original),
+    }
+}
+
 /// Loads the image from the provided url
+///
+/// Images are cached by their url, so each resolution of an image has its own cache file.
+/// Only cached images are loaded when saving bandwidth, see [`load_image_on_demand`].
 pub async fn load_image(image_url: String, image_type: ImageResolution) -> Option<Bytes> {
+    load_image_from(image_url, image_type, !is_saving_bandwidth()).await
+}
+
+/// Loads the image from the provided url even when saving bandwidth, for the images the
+/// user asked for
+pub async fn load_image_on_demand(image_url: String, image_type: ImageResolution) -> Option<Bytes> {
+    load_image_from(image_url, image_type, true).await
+}
+
+async fn load_image_from(
+    image_url: String,
+    image_type: ImageResolution,
+    fall_back_online: bool,
+) -> Option<Bytes> {
     // Hashing the image url as a file name as the forward slashes in web urls
     // mimic paths
     use sha2::{Digest, Sha256};
@@ -148,13 +254,19 @@ pub async fn load_image(image_url: String, image_type: ImageResolution) -> Optio
     let mut image_path = CACHER.get_cache_folder_path(CacheFolderType::Images);
     image_path.push(&image_hash);
 
-    match fs::read(&image_path).await {
+    let _in_flight = in_flight::join(&image_path).await;
+
+    let cached_image = fs::read(&image_path).await;
+    metrics::record_cache_lookup(CacheKind::Images, cached_image.is_ok());
+
+    match cached_image {
         Ok(image_bytes) => Some(Bytes::from(image_bytes)),
         Err(err) => {
-            if err.kind() == ErrorKind::NotFound {
+            if err.kind() == ErrorKind::NotFound && fall_back_online {
                 info!("falling back online for image with link {}", image_url);
                 if let Some(image_bytes) = tv_maze::image::load_image(image_url, image_type).await {
-                    write_cache(&image_bytes, &image_path).await;
+                    // Images are already compressed
+                    write_file(&image_bytes, &image_path).await;
                     Some(image_bytes)
                 } else {
                     None
@@ -166,11 +278,82 @@ pub async fn load_image(image_url: String, image_type: ImageResolution) -> Optio
     }
 }
 
+/// The first bytes of every zstd frame
+const ZSTD_MAGIC_BYTES: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The first bytes of every gzip stream, used by older versions
+const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
 pub async fn read_cache(cache_filepath: impl AsRef<path::Path>) -> io::Result<String> {
-    fs::read_to_string(cache_filepath).await
+    let cache_bytes = read_cache_bytes(cache_filepath).await?;
+    String::from_utf8(cache_bytes).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Reads the cache file decompressing it, cache files in an older format are compressed again
+/// along the way
+pub async fn read_cache_bytes(cache_filepath: impl AsRef<path::Path>) -> io::Result<Vec<u8>> {
+    let cache_filepath = cache_filepath.as_ref();
+    let cache_bytes = fs::read(cache_filepath).await?;
+
+    if cache_bytes.starts_with(&ZSTD_MAGIC_BYTES) {
+        return tokio::task::spawn_blocking(move || decompress(&cache_bytes))
+            .await
+            .map_err(io::Error::other)?;
+    }
+
+    let cache_bytes = if cache_bytes.starts_with(&GZIP_MAGIC_BYTES) {
+        tokio::task::spawn_blocking(move || decompress_gzip(&cache_bytes))
+            .await
+            .map_err(io::Error::other)??
+    } else {
+        cache_bytes
+    };
+
+    info!("compressing cache '{}'", cache_filepath.display());
+    write_cache(&cache_bytes, cache_filepath).await;
+    Ok(cache_bytes)
 }
 
+/// Compresses the data and writes it to the cache file
 pub async fn write_cache(cache_data: impl AsRef<[u8]>, cache_filepath: &path::Path) {
+    let cache_data = cache_data.as_ref().to_vec();
+    match tokio::task::spawn_blocking(move || compress(&cache_data)).await {
+        Ok(compressed_data) => write_file(compressed_data, cache_filepath).await,
+        Err(err) => error!(
+            "failed to compress cache '{}': {}",
+            cache_filepath.display(),
+            err
+        ),
+    }
+}
+
+fn compress(data: &[u8]) -> Vec<u8> {
+    use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+    compress_to_vec(data, CompressionLevel::Fastest)
+}
+
+fn decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    use ruzstd::decoding::StreamingDecoder;
+    use std::io::Read;
+
+    let mut decoder = StreamingDecoder::new(data).map_err(io::Error::other)?;
+    let mut decompressed_data = Vec::new();
+    decoder.read_to_end(&mut decompressed_data)?;
+    Ok(decompressed_data)
+}
+
+fn decompress_gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let mut decompressed_data = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decompressed_data)?;
+    Ok(decompressed_data)
+}
+
+/// Writes the data as is, creating the parent directories when missing
+async fn write_file(cache_data: impl AsRef<[u8]>, cache_filepath: &path::Path) {
     loop {
         if let Err(err) = fs::write(cache_filepath, &cache_data).await {
             if err.kind() == ErrorKind::NotFound {
@@ -199,3 +382,77 @@ pub async fn write_cache(cache_data: impl AsRef<[u8]>, cache_filepath: &path::Pa
         break;
     }
 }
+
+/// Refreshes the cache file from the url it was downloaded from returning it's up to date contents
+///
+/// The validators of the cached response are sent along so that the response is only
+/// downloaded again when it has changed, otherwise the cache is rewritten as is to mark
+/// it as fresh.
+pub async fn refresh_cache(url: String, cache_filepath: &path::Path) -> Result<String, ApiError> {
+    let validators_filepath = cache_filepath.with_extension(CACHE_VALIDATORS_EXTENSION);
+
+    let cached_json = read_cache(cache_filepath).await.ok();
+
+    // Validators are only of use along the response they belong to
+    let validators = match cached_json {
+        Some(_) => read_cache(&validators_filepath)
+            .await
+            .ok()
+            .and_then(|validators| serde_json::from_str::<CacheValidators>(&validators).ok())
+            .unwrap_or_default(),
+        None => CacheValidators::default(),
+    };
+
+    match tv_maze::revalidate_pretty_json_from_url(url, &validators).await? {
+        Revalidation::NotModified => {
+            info!("cache '{}' is still up to date", cache_filepath.display());
+            let cached_json = cached_json.unwrap_or_default();
+            write_cache(&cached_json, cache_filepath).await;
+            Ok(cached_json)
+        }
+        Revalidation::Modified(json, validators) => {
+            write_cache(&json, cache_filepath).await;
+            if validators.is_empty() {
+                let _ = fs::remove_file(&validators_filepath).await;
+            } else {
+                write_cache(
+                    serde_json::to_string(&validators).expect("validators should be serializable"),
+                    &validators_filepath,
+                )
+                .await;
+            }
+            Ok(json)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn older_cache_files_are_compressed_with_zstd() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let data = br#"{"id":82,"name":"Game of Thrones"}"#;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        let gzip_data = encoder.finish().unwrap();
+
+        let dir = std::env::temp_dir().join(format!("series-troxide-cache-{}", std::process::id()));
+        for (file_name, file_data) in [("uncompressed", data.to_vec()), ("gzip", gzip_data)] {
+            let cache_filepath = dir.join(file_name);
+            write_file(file_data, &cache_filepath).await;
+
+            assert_eq!(read_cache_bytes(&cache_filepath).await.unwrap(), data);
+
+            let migrated_data = fs::read(&cache_filepath).await.unwrap();
+            assert!(migrated_data.starts_with(&ZSTD_MAGIC_BYTES));
+            assert_eq!(decompress(&migrated_data).unwrap(), data);
+        }
+
+        fs::remove_dir_all(dir).await.unwrap();
+    }
+}
@@ -2,6 +2,15 @@
 //!
 //! Since the program keeps cache to avoid performing too many requests,
 //! we need some form of cache updating so that we stay up to date.
+//!
+//! Cache of series in the database is revalidated with the api rather than removed, so
+//! only the responses that actually changed are downloaded again. What changed in them is
+//! recorded, see [`series_updates`](crate::core::series_updates).
+//!
+//! Episode lists of running series whose cached episodes have all aired are revalidated
+//! regardless of the updates index, so newly announced episodes show up promptly.
+//! Series left without cache are looked up again to notice the ones TVmaze no longer
+//! knows, see [`relinking`](crate::core::relinking).
 
 use std::path;
 use std::time;
@@ -10,10 +19,14 @@ use anyhow::Context;
 use tokio::fs;
 use tracing::{error, info, warn};
 
+use super::episode_list::EpisodeList;
+use super::refresh_queue::{self, RefreshPriority};
 use super::series_info_and_episode_list::SeriesInfoAndEpisodeList;
-use super::{CacheFolderType, CACHER};
+use super::{is_saving_bandwidth, refresh_cache, CacheFilePath, CacheFolderType, CACHER};
 use crate::core::api::tv_maze::updates::get_shows_updates_index;
 use crate::core::database::DB;
+use crate::core::relinking;
+use crate::core::series_updates::{self, SeriesSnapshot};
 
 async fn get_all_series_cache_directories(
 ) -> anyhow::Result<Vec<(String, path::PathBuf, time::Duration)>> {
@@ -30,13 +43,7 @@ async fn get_all_series_cache_directories(
         .context("failed to read a series directory entry")?
     {
         let dir_path = dir_entry.path();
-        let duration = dir_path
-            .metadata()
-            .context("failed to get series cache directory metadata")?
-            .created()
-            .context("failed to get creating time of a series cache directory")?
-            .duration_since(time::SystemTime::UNIX_EPOCH)
-            .context("system clock failure when determining series cache folder creation")?;
+        let duration = get_cache_directory_timestamp(&dir_path).await?;
 
         let series_id = dir_path
             .file_name()
@@ -48,36 +55,109 @@ async fn get_all_series_cache_directories(
     Ok(series_dirs)
 }
 
+/// When the series cache directory was last refreshed
+///
+/// This is when it's oldest file was written as the cache is only as fresh as it's oldest
+/// file, or when the directory was created if it is empty.
+async fn get_cache_directory_timestamp(dir_path: &path::Path) -> anyhow::Result<time::Duration> {
+    let mut read_dir = fs::read_dir(dir_path)
+        .await
+        .context("failed to read a series cache directory")?;
+
+    let mut oldest_write_time = None;
+    while let Some(file_entry) = read_dir
+        .next_entry()
+        .await
+        .context("failed to read a series cache file entry")?
+    {
+        let write_time = file_entry
+            .metadata()
+            .await
+            .context("failed to get series cache file metadata")?
+            .modified()
+            .context("failed to get modification time of a series cache file")?;
+
+        oldest_write_time = Some(
+            oldest_write_time
+                .map(|oldest_write_time: time::SystemTime| oldest_write_time.min(write_time))
+                .unwrap_or(write_time),
+        );
+    }
+
+    let timestamp = match oldest_write_time {
+        Some(oldest_write_time) => oldest_write_time,
+        None => dir_path
+            .metadata()
+            .context("failed to get series cache directory metadata")?
+            .created()
+            .context("failed to get creating time of a series cache directory")?,
+    };
+
+    timestamp
+        .duration_since(time::SystemTime::UNIX_EPOCH)
+        .context("system clock failure when determining series cache folder creation")
+}
+
+/// Updates the series cache when it is due
 pub async fn update_cache() -> anyhow::Result<()> {
-    if !should_update().await? {
-        return Ok(());
+    if should_update().await? {
+        update_series_cache().await?;
+        revalidate_aired_out_episode_lists().await;
+        relinking::check_uncached_series().await;
     }
 
+    Ok(())
+}
+
+async fn update_series_cache() -> anyhow::Result<()> {
     info!("updating series cache...");
 
     let updates_index = get_shows_updates_index(None).await?;
 
     let series_cache_directories = get_all_series_cache_directories().await?;
+    let archived_series_ids = DB.get_archived_series_ids();
 
     let mut handles = Vec::with_capacity(series_cache_directories.len());
     for (series_id, path, cache_timestamp) in series_cache_directories {
+        // Archived series keep their cache as it is, saving their requests
+        if series_id
+            .parse()
+            .is_ok_and(|series_id| archived_series_ids.contains(&series_id))
+        {
+            continue;
+        }
+
         let time_stamp = updates_index.get(&series_id).copied();
 
-        let handle = tokio::spawn(async move {
+        let handle = refresh_queue::spawn(RefreshPriority::Background, async move {
             if let Some(time_stamp) = time_stamp {
                 let update_timestamp = time::Duration::from_secs(time_stamp as u64);
 
                 if update_timestamp > cache_timestamp {
-                    clean_cache_directory(&path).await;
-
-                    // Caching the series if it's in the database
+                    // Refreshing the series if it's in the database
                     let series_id: u32 = series_id.parse().expect("series id should be parsable");
                     if DB.get_series(series_id).is_some() {
+                        let previous_snapshot = SeriesSnapshot::from_cache(series_id).await;
+                        refresh_series_cache(series_id).await;
+
+                        if let (Some(previous_snapshot), Some(snapshot)) = (
+                            previous_snapshot,
+                            SeriesSnapshot::from_cache(series_id).await,
+                        ) {
+                            series_updates::record_update(
+                                series_id,
+                                time_stamp,
+                                previous_snapshot.changes_to(&snapshot),
+                            );
+                        }
+
                         SeriesInfoAndEpisodeList::cache_series(series_id)
                             .await
                             .unwrap_or_else(|err| {
                                 error!("failed to cache series with id '{}': {}", series_id, err)
                             });
+                    } else {
+                        clean_cache_directory(&path).await;
                     }
                 }
             } else {
@@ -103,8 +183,57 @@ pub async fn update_cache() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Revalidates the cached episode lists of the tracked running series that ran out of upcoming
+/// episodes, as the updates index may not have caught up with the episodes announced since
+async fn revalidate_aired_out_episode_lists() {
+    let archived_series_ids = DB.get_archived_series_ids();
+
+    let series_ids: Vec<u32> = DB
+        .get_series_id_collection()
+        .into_iter()
+        .filter_map(|series_id| series_id.parse().ok())
+        .filter(|series_id| !archived_series_ids.contains(series_id))
+        .collect();
+
+    let mut handles = Vec::with_capacity(series_ids.len());
+    for series_id in series_ids {
+        let episode_list_path =
+            CACHER.get_cache_file_path(CacheFilePath::SeriesEpisodeList(series_id));
+
+        // Episode lists never cached are downloaded fresh when needed
+        if !fs::try_exists(&episode_list_path).await.unwrap_or(false) {
+            continue;
+        }
+
+        // Loading the episode list revalidates it when it has aired out
+        handles.push(refresh_queue::spawn(
+            RefreshPriority::Background,
+            async move {
+                if let Err(err) = EpisodeList::new(series_id).await {
+                    error!(
+                        "failed to load episode list of series with id '{}': {}",
+                        series_id, err
+                    );
+                }
+            },
+        ));
+    }
+
+    for handle in handles {
+        handle
+            .await
+            .expect("failed to join episode list revalidation handles");
+    }
+}
+
 const LAST_UPDATE_FILENAME: &str = "last-cache-update";
 
+/// How long the cache is used before being updated
+const UPDATE_INTERVAL: time::Duration = time::Duration::from_secs(60 * 60 * 24);
+
+/// How long the cache is used before being updated when saving bandwidth
+const BANDWIDTH_SAVER_UPDATE_INTERVAL: time::Duration = time::Duration::from_secs(60 * 60 * 24 * 7);
+
 fn get_last_update_filepath() -> path::PathBuf {
     let mut last_update_file = CACHER.get_root_cache_path().to_owned();
     last_update_file.push(LAST_UPDATE_FILENAME);
@@ -119,8 +248,8 @@ fn duration_since_epoch() -> anyhow::Result<time::Duration> {
 
 /// Whether cache should be updated or not
 ///
-/// Checks if a day has passed since the last cache update and returns `true`,
-/// Otherwise the opposite
+/// Checks if a day, or a week when saving bandwidth, has passed since the last cache
+/// update and returns `true`, Otherwise the opposite
 async fn should_update() -> anyhow::Result<bool> {
     let last_update_file = get_last_update_filepath();
 
@@ -144,7 +273,13 @@ async fn should_update() -> anyhow::Result<bool> {
 
     let last_update_timestamp = time::Duration::from_secs(last_update_timestamp);
 
-    Ok((current_timestamp - last_update_timestamp) > time::Duration::from_secs(60 * 60 * 24))
+    let update_interval = if is_saving_bandwidth() {
+        BANDWIDTH_SAVER_UPDATE_INTERVAL
+    } else {
+        UPDATE_INTERVAL
+    };
+
+    Ok((current_timestamp - last_update_timestamp) > update_interval)
 }
 
 async fn record_last_update() -> anyhow::Result<()> {
@@ -157,6 +292,33 @@ async fn record_last_update() -> anyhow::Result<()> {
         .context("failed to write 'last-cache-update' file")
 }
 
+/// Revalidates the existing cache files of the series with the api
+async fn refresh_series_cache(series_id: u32) {
+    let cache_files = [
+        CacheFilePath::SeriesMainInformation(series_id),
+        CacheFilePath::SeriesEpisodeList(series_id),
+        CacheFilePath::SeriesShowCast(series_id),
+        CacheFilePath::SeriesImageList(series_id),
+    ];
+
+    for cache_file in cache_files {
+        let cache_filepath = CACHER.get_cache_file_path(cache_file);
+        if !fs::try_exists(&cache_filepath).await.unwrap_or(false) {
+            continue;
+        }
+
+        if let Err(err) = refresh_cache(cache_file.source_url(), &cache_filepath).await {
+            error!(
+                "failed to refresh cache '{}': {}",
+                cache_filepath.display(),
+                err
+            );
+            // Falling back to downloading it again when needed
+            let _ = fs::remove_file(&cache_filepath).await;
+        }
+    }
+}
+
 /// Removes the directory and it's contents at the given path
 async fn clean_cache_directory(path: &path::Path) {
     info!("cleaning cache: {}", path.display());
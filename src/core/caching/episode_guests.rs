@@ -0,0 +1,38 @@
+use super::series_information::get_series_main_info_with_url;
+use crate::core::api::tv_maze::{episode_guests, ApiError};
+
+/// An episode of a series a person appeared in as a guest
+#[derive(Debug, Clone)]
+pub struct GuestAppearance {
+    pub series_id: u32,
+    pub series_name: String,
+    pub season: u32,
+    pub number: Option<u32>,
+    pub episode_name: String,
+    pub character_name: String,
+}
+
+/// Retrieves the guest appearances of a person, newest first
+///
+/// The names of the series are resolved through the cached series information.
+pub async fn get_guest_appearances(person_id: u32) -> Result<Vec<GuestAppearance>, ApiError> {
+    let mut credits = episode_guests::get_guest_cast_credits(person_id).await?;
+    credits.sort_by(|a, b| b.episode().airdate.cmp(&a.episode().airdate));
+
+    let mut guest_appearances = Vec::with_capacity(credits.len());
+    for credit in credits {
+        let episode = credit.episode();
+        let series_info = get_series_main_info_with_url(episode.links.show.href.clone()).await?;
+
+        guest_appearances.push(GuestAppearance {
+            series_id: series_info.id,
+            series_name: series_info.name,
+            season: episode.season,
+            number: episode.number,
+            episode_name: episode.name.clone(),
+            character_name: credit.character().name.clone(),
+        });
+    }
+
+    Ok(guest_appearances)
+}
@@ -1,14 +1,24 @@
 use std::io::ErrorKind;
+use std::path;
+use std::time;
 
-use tracing::info;
+use chrono::Local;
+use tokio::fs;
+use tracing::{error, info};
 
-use super::{read_cache, write_cache, CacheFilePath};
+use super::{in_flight, read_cache, refresh_cache, write_cache, CacheFilePath};
 use crate::core::api::tv_maze::deserialize_json;
 pub use crate::core::api::tv_maze::episodes_information::EpisodeReleaseTime;
 use crate::core::api::tv_maze::episodes_information::{get_episode_list, Episode};
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 use crate::core::api::tv_maze::ApiError;
+use crate::core::metrics::{self, CacheKind};
 use crate::core::{caching::CACHER, database};
 
+/// How long the episode list of a running series, with all it's cached episodes aired, is
+/// used before being revalidated for the episodes announced since
+const AIRED_OUT_REVALIDATION_INTERVAL: time::Duration = time::Duration::from_secs(60 * 60 * 12);
+
 #[derive(Clone, Debug)]
 pub struct EpisodeList {
     series_id: u32,
@@ -20,7 +30,12 @@ impl EpisodeList {
         let episodes_list_path =
             CACHER.get_cache_file_path(CacheFilePath::SeriesEpisodeList(series_id));
 
-        let json_string = match read_cache(&episodes_list_path).await {
+        let _in_flight = in_flight::join(&episodes_list_path).await;
+
+        let cached_json = read_cache(&episodes_list_path).await;
+        metrics::record_cache_lookup(CacheKind::EpisodeList, cached_json.is_ok());
+
+        let json_string = match cached_json {
             Ok(json_string) => json_string,
             Err(err) => {
                 info!("falling back online for 'episode list' for series id: {series_id}");
@@ -36,7 +51,21 @@ impl EpisodeList {
             }
         };
 
-        let episodes = deserialize_json::<Vec<Episode>>(&json_string)?;
+        let mut episodes = deserialize_json::<Vec<Episode>>(&json_string)?;
+
+        if is_aired_out(series_id, &episodes, &episodes_list_path).await {
+            info!("revalidating the aired out 'episode list' for series id: {series_id}");
+            let source_url = CacheFilePath::SeriesEpisodeList(series_id).source_url();
+            match refresh_cache(source_url, &episodes_list_path).await {
+                Ok(json_string) => episodes = deserialize_json::<Vec<Episode>>(&json_string)?,
+                // The cached episodes are still of use when offline
+                Err(err) => error!(
+                    "failed to revalidate 'episode list' for series id {}: {}",
+                    series_id, err
+                ),
+            }
+        }
+
         Ok(Self {
             series_id,
             episodes,
@@ -58,6 +87,37 @@ impl EpisodeList {
         })
     }
 
+    /// Returns the position of the episode counting from the first episode of the series
+    ///
+    /// Specials, having no episode number, are not counted.
+    pub fn get_absolute_number(&self, season_number: u32, episode_number: u32) -> Option<u32> {
+        self.episodes
+            .iter()
+            .filter(|episode| episode.number.is_some())
+            .position(|episode| {
+                episode.season == season_number && episode.number == Some(episode_number)
+            })
+            .map(|position| position as u32 + 1)
+    }
+
+    pub fn get_episode_by_absolute_number(&self, absolute_number: u32) -> Option<&Episode> {
+        let position = absolute_number.checked_sub(1)? as usize;
+        self.episodes
+            .iter()
+            .filter(|episode| episode.number.is_some())
+            .nth(position)
+    }
+
+    /// Finds the episode referred to by either it's seasonal or absolute number
+    pub fn find_episode(&self, episode_number: &EpisodeNumber) -> Option<&Episode> {
+        match episode_number {
+            EpisodeNumber::Seasonal { season, episode } => self.get_episode(*season, *episode),
+            EpisodeNumber::Absolute(absolute_number) => {
+                self.get_episode_by_absolute_number(*absolute_number)
+            }
+        }
+    }
+
     pub fn get_episodes(&self, season: u32) -> Vec<&Episode> {
         self.episodes
             .iter()
@@ -93,6 +153,41 @@ impl EpisodeList {
         TotalEpisodes::new(total_episodes, total_watchable_episodes)
     }
 
+    /// Returns whether the episode opens or closes it's season or the series
+    ///
+    /// # Note
+    /// The last episode announced for a season is taken as it's finale, which may not hold
+    /// while the rest of the season is yet to be announced.
+    pub fn get_episode_badge(&self, episode: &Episode) -> Option<EpisodeBadge> {
+        if episode.is_special() {
+            return None;
+        }
+
+        let season_episode_numbers: Vec<u32> = self
+            .get_episodes(episode.season)
+            .into_iter()
+            .filter(|episode| !episode.is_special())
+            .filter_map(|episode| episode.number)
+            .collect();
+
+        let episode_number = episode.number?;
+        let first_episode_number = season_episode_numbers.iter().min().copied()?;
+        let last_episode_number = season_episode_numbers.iter().max().copied()?;
+
+        if episode_number == first_episode_number {
+            let first_season = self.episodes.iter().map(|episode| episode.season).min();
+            if first_season == Some(episode.season) {
+                Some(EpisodeBadge::SeriesPremiere)
+            } else {
+                Some(EpisodeBadge::SeasonPremiere)
+            }
+        } else if episode_number == last_episode_number {
+            Some(EpisodeBadge::SeasonFinale)
+        } else {
+            None
+        }
+    }
+
     /// Returns the next episode to air from the current time
     pub fn get_next_episode_to_air(&self) -> Option<&Episode> {
         self.episodes
@@ -100,15 +195,30 @@ impl EpisodeList {
             .find(|episode| episode.is_future_release() == Ok(true))
     }
 
+    /// Returns the first aired episode that is neither watched nor skipped
     pub fn get_next_episode_to_watch(&self) -> Option<&Episode> {
+        self.get_unwatched_episodes().into_iter().next()
+    }
+
+    /// Returns the aired episodes that are neither watched nor skipped, in airing order
+    pub fn get_unwatched_episodes(&self) -> Vec<&Episode> {
         let series = database::DB
             .get_series(self.series_id)
             .expect("series not in the database");
+        let skipped_episodes = database::DB.get_skipped_episodes(self.series_id);
 
         self.get_all_episodes()
             .iter()
             .filter(|episode| episode.is_future_release() == Ok(false))
-            .find(|episode| {
+            .filter(|episode| {
+                episode
+                    .number
+                    .map(|episode_number| {
+                        !skipped_episodes.contains(&(episode.season, episode_number))
+                    })
+                    .unwrap_or(true)
+            })
+            .filter(|episode| {
                 series
                     .get_season(episode.season)
                     .map(|season| {
@@ -119,6 +229,72 @@ impl EpisodeList {
                     })
                     .unwrap_or(true) // if season isn't watched, let's get it's first episode
             })
+            .collect()
+    }
+}
+
+/// Marks an episode with a special place in the series
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpisodeBadge {
+    SeriesPremiere,
+    SeasonPremiere,
+    SeasonFinale,
+}
+
+impl std::fmt::Display for EpisodeBadge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            EpisodeBadge::SeriesPremiere => "Series Premiere",
+            EpisodeBadge::SeasonPremiere => "Season Premiere",
+            EpisodeBadge::SeasonFinale => "Season Finale",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// A reference to an episode as typed by the user
+///
+/// Accepts seasonal numbers like `S07E05` and absolute numbers like `E137` or just `137`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EpisodeNumber {
+    Seasonal { season: u32, episode: u32 },
+    Absolute(u32),
+}
+
+impl std::str::FromStr for EpisodeNumber {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase();
+        let invalid = || format!("'{}' is not a valid episode number", s.trim());
+
+        if let Some(season_and_episode) = normalized.strip_prefix('s') {
+            let (season, episode) = season_and_episode.split_once('e').ok_or_else(invalid)?;
+            return Ok(Self::Seasonal {
+                season: season.parse().map_err(|_| invalid())?,
+                episode: episode.parse().map_err(|_| invalid())?,
+            });
+        }
+
+        normalized
+            .strip_prefix('e')
+            .unwrap_or(&normalized)
+            .parse()
+            .map(Self::Absolute)
+            .map_err(|_| invalid())
+    }
+}
+
+impl EpisodeNumber {
+    /// Splits a search term ending with an episode number, like `Show 145` or `Show S07E05`,
+    /// into the series name and the episode number
+    pub fn split_search_term(term: &str) -> Option<(&str, Self)> {
+        let (series_name, episode_number) = term.trim().rsplit_once(char::is_whitespace)?;
+        let series_name = series_name.trim_end();
+        if series_name.is_empty() {
+            return None;
+        }
+        Some((series_name, episode_number.parse().ok()?))
     }
 }
 
@@ -146,3 +322,78 @@ impl TotalEpisodes {
         self.all_watchable_episodes
     }
 }
+
+/// Whether the cached episode list of a running series has run out of upcoming episodes
+///
+/// The list is taken as aired out when it's latest episode has aired after the list was
+/// cached, or when it aired before and the cache is older than
+/// `AIRED_OUT_REVALIDATION_INTERVAL`, as the next episodes are likely announced by then.
+/// Only the cached series information is consulted for the status, costing no request.
+async fn is_aired_out(series_id: u32, episodes: &[Episode], cache_filepath: &path::Path) -> bool {
+    let Some(latest_airstamp) = episodes
+        .iter()
+        .filter_map(|episode| episode.local_date_time().ok())
+        .max()
+    else {
+        return false;
+    };
+
+    if latest_airstamp > Local::now() {
+        return false;
+    }
+
+    let series_information_path =
+        CACHER.get_cache_file_path(CacheFilePath::SeriesMainInformation(series_id));
+    let is_running = read_cache(&series_information_path)
+        .await
+        .ok()
+        .and_then(|json_string| deserialize_json::<SeriesMainInformation>(&json_string).ok())
+        .is_some_and(|series_info| series_info.status == "Running");
+
+    if !is_running {
+        return false;
+    }
+
+    let Ok(cached_at) = fs::metadata(cache_filepath)
+        .await
+        .and_then(|metadata| metadata.modified())
+    else {
+        return false;
+    };
+
+    let is_cached_before_airing = chrono::DateTime::<Local>::from(cached_at) < latest_airstamp;
+    let is_cache_expired = cached_at
+        .elapsed()
+        .is_ok_and(|cache_age| cache_age > AIRED_OUT_REVALIDATION_INTERVAL);
+
+    is_cached_before_airing || is_cache_expired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn episode_numbers_are_split_from_search_terms() {
+        assert_eq!(
+            EpisodeNumber::split_search_term("One Piece 145"),
+            Some(("One Piece", EpisodeNumber::Absolute(145)))
+        );
+        assert_eq!(
+            EpisodeNumber::split_search_term(" Show  s07e05 "),
+            Some((
+                "Show",
+                EpisodeNumber::Seasonal {
+                    season: 7,
+                    episode: 5
+                }
+            ))
+        );
+        assert_eq!(
+            EpisodeNumber::split_search_term("Show E137"),
+            Some(("Show", EpisodeNumber::Absolute(137)))
+        );
+        assert_eq!(EpisodeNumber::split_search_term("145"), None);
+        assert_eq!(EpisodeNumber::split_search_term("The Office"), None);
+    }
+}
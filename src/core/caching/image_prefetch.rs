@@ -0,0 +1,56 @@
+//! # Image prefetching
+//!
+//! Loads the images of the next episode to watch of every tracked series into the cache
+//! ahead of time, so that the watchlist shows them right away. The loading is queued with
+//! the lowest priority of the refresh queue, only running when nothing else is waiting.
+//!
+//! Prefetching can be turned off in the network settings for metered connections, and
+//! never happens when saving bandwidth.
+
+use super::episode_list::EpisodeList;
+use super::refresh_queue::{self, RefreshPriority};
+use super::{image_source, load_image, ImageKind};
+use crate::core::database::DB;
+use crate::core::settings_config::SETTINGS;
+
+/// Prefetches the images of the next episodes to watch, returning how many are now cached
+pub async fn prefetch_next_episode_images() -> usize {
+    let network_settings = SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .network
+        .clone();
+    if !network_settings.prefetch_images || network_settings.bandwidth_saver {
+        return 0;
+    }
+
+    let tracked_series_ids: Vec<u32> = DB
+        .get_series_collection()
+        .iter()
+        .filter(|series| series.is_tracked() && !DB.is_archived(series.id()))
+        .map(|series| series.id())
+        .collect();
+
+    let handles: Vec<_> = tracked_series_ids
+        .into_iter()
+        .map(|series_id| {
+            refresh_queue::spawn(RefreshPriority::Prefetch, async move {
+                let episode_list = EpisodeList::new(series_id).await.ok()?;
+                let image = episode_list.get_next_episode_to_watch()?.image.clone()?;
+                // The same resolution the episode posters load
+                let (image_url, image_resolution) =
+                    image_source(image, ImageKind::Background, false);
+                load_image(image_url, image_resolution).await
+            })
+        })
+        .collect();
+
+    let mut prefetched = 0;
+    for handle in handles {
+        if let Ok(Some(_)) = handle.await {
+            prefetched += 1;
+        }
+    }
+    prefetched
+}
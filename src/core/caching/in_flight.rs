@@ -0,0 +1,54 @@
+//! # Coalescing of identical cache misses
+//!
+//! Different parts of the program often ask for the same data at the same time, i.e. my
+//! shows, discover and the series page all loading the information of the same series.
+//! A caller holds an [`InFlightGuard`] for the cache file it is reading while it falls
+//! back online so that the other callers wait for that single fetch and then find the
+//! data in the cache instead of firing their own requests.
+//!
+//! When the fetch fails nothing gets cached, the next waiting caller then tries on it's own.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+lazy_static! {
+    static ref IN_FLIGHT_REQUESTS: Mutex<HashMap<PathBuf, Arc<AsyncMutex<()>>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Marks the cache file as being loaded until dropped
+pub struct InFlightGuard {
+    cache_filepath: PathBuf,
+    lock: Arc<AsyncMutex<()>>,
+    _guard: OwnedMutexGuard<()>,
+}
+
+/// Waits for any load of the cache file already in flight then marks this one as in flight
+pub async fn join(cache_filepath: &Path) -> InFlightGuard {
+    let lock = IN_FLIGHT_REQUESTS
+        .lock()
+        .unwrap()
+        .entry(cache_filepath.to_path_buf())
+        .or_default()
+        .clone();
+
+    InFlightGuard {
+        cache_filepath: cache_filepath.to_path_buf(),
+        _guard: lock.clone().lock_owned().await,
+        lock,
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight_requests = IN_FLIGHT_REQUESTS.lock().unwrap();
+        // Only the map and this guard refer to the lock when no one else is waiting
+        if Arc::strong_count(&self.lock) == 2 {
+            in_flight_requests.remove(&self.cache_filepath);
+        }
+    }
+}
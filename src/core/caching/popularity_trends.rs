@@ -0,0 +1,121 @@
+//! # Popularity trends
+//!
+//! Daily snapshots of the rank and rating of the series in the popular section of Discover,
+//! kept in the cache root for `SNAPSHOTS_RETENTION_DAYS` days. Comparing today's snapshot
+//! with the one of a week ago shows which series are climbing or falling.
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use super::{read_cache, write_cache, CACHER};
+
+const SNAPSHOTS_FILENAME: &str = "popularity-snapshots";
+
+/// How long the snapshots are kept, longer than a week so that a few days without opening
+/// Discover still leave a snapshot to compare with
+const SNAPSHOTS_RETENTION_DAYS: i64 = 21;
+
+/// How old the snapshot compared with has to be at least
+const TREND_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct SeriesSnapshot {
+    /// The position in the popular section, starting from 1
+    rank: u32,
+    rating: Option<f32>,
+}
+
+/// The snapshots of the popular series by the day they were taken, keyed by series id
+type Snapshots = BTreeMap<NaiveDate, HashMap<u32, SeriesSnapshot>>;
+
+/// How a popular series moved since a week ago
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trend {
+    /// The series was not popular a week ago
+    New,
+    Moved {
+        /// How many places the series climbed, negative when it fell
+        rank_change: i32,
+        rating_change: Option<f32>,
+    },
+}
+
+/// Records today's snapshot of the popular series when not taken yet and gets their trends
+///
+/// The series are given by their id and rating, the most popular first. No trends are
+/// given until a snapshot at least `TREND_DAYS` days old exists.
+pub async fn record_and_get_trends(popular_series: Vec<(u32, Option<f32>)>) -> HashMap<u32, Trend> {
+    let mut snapshots = load_snapshots().await;
+    let today = Local::now().date_naive();
+
+    if let Entry::Vacant(entry) = snapshots.entry(today) {
+        let snapshot = popular_series
+            .iter()
+            .enumerate()
+            .map(|(index, (series_id, rating))| {
+                (
+                    *series_id,
+                    SeriesSnapshot {
+                        rank: index as u32 + 1,
+                        rating: *rating,
+                    },
+                )
+            })
+            .collect();
+        entry.insert(snapshot);
+        snapshots.retain(|date, _| *date > today - Duration::days(SNAPSHOTS_RETENTION_DAYS));
+        save_snapshots(&snapshots).await;
+    }
+
+    let Some((_, previous_snapshot)) = snapshots
+        .range(..=today - Duration::days(TREND_DAYS))
+        .next_back()
+    else {
+        return HashMap::new();
+    };
+
+    popular_series
+        .into_iter()
+        .enumerate()
+        .map(|(index, (series_id, rating))| {
+            let trend = match previous_snapshot.get(&series_id) {
+                Some(previous) => Trend::Moved {
+                    rank_change: previous.rank as i32 - (index as i32 + 1),
+                    rating_change: rating
+                        .zip(previous.rating)
+                        .map(|(rating, previous_rating)| rating - previous_rating),
+                },
+                None => Trend::New,
+            };
+            (series_id, trend)
+        })
+        .collect()
+}
+
+fn get_snapshots_path() -> std::path::PathBuf {
+    let mut snapshots_path = CACHER.get_root_cache_path().to_owned();
+    snapshots_path.push(SNAPSHOTS_FILENAME);
+    snapshots_path
+}
+
+async fn load_snapshots() -> Snapshots {
+    let Ok(json_string) = read_cache(get_snapshots_path()).await else {
+        return Snapshots::new();
+    };
+
+    serde_json::from_str(&json_string).unwrap_or_else(|err| {
+        error!("failed to parse the popularity snapshots: {}", err);
+        Snapshots::new()
+    })
+}
+
+async fn save_snapshots(snapshots: &Snapshots) {
+    match serde_json::to_string(snapshots) {
+        Ok(json_string) => write_cache(json_string, &get_snapshots_path()).await,
+        Err(err) => error!("failed to serialize the popularity snapshots: {}", err),
+    }
+}
@@ -0,0 +1,145 @@
+//! # Cache refresh queue
+//!
+//! Loading many series at once used to spawn a task per series all hitting the api at
+//! the same time, which easily runs into the rate limit of TVmaze. Work that may end up
+//! requesting the api is instead queued here and only [`MAX_CONCURRENT_REFRESHES`] of it
+//! runs at a time. Queued work is started by priority, so what is on screen is loaded
+//! before background updating and prefetching.
+//!
+//! # Note
+//! Queued work must not queue more work and wait for it, as that can wait forever once all
+//! the slots are taken by such work.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// How many queued loads can run at the same time
+pub const MAX_CONCURRENT_REFRESHES: usize = 4;
+
+lazy_static! {
+    static ref REFRESH_QUEUE: Mutex<QueueState> = Mutex::new(QueueState::default());
+}
+
+/// The order queued work is started in, higher priorities first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RefreshPriority {
+    /// Loading what may be needed later
+    Prefetch,
+    /// Keeping the cache up to date
+    Background,
+    /// Loading what is currently shown
+    Visible,
+}
+
+/// Queued work waiting for a free slot
+struct Waiter {
+    priority: RefreshPriority,
+    /// Keeps work of the same priority in the order it was queued
+    sequence: Reverse<u64>,
+    slot_sender: oneshot::Sender<RefreshSlot>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        (self.priority, self.sequence) == (other.priority, other.sequence)
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.priority, self.sequence).cmp(&(other.priority, other.sequence))
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    running: usize,
+    next_sequence: u64,
+    waiters: BinaryHeap<Waiter>,
+}
+
+/// A taken slot of the queue, handed to the next waiter when dropped
+struct RefreshSlot;
+
+impl Drop for RefreshSlot {
+    fn drop(&mut self) {
+        let mut queue = REFRESH_QUEUE
+            .lock()
+            .expect("failed to lock the refresh queue");
+
+        while let Some(waiter) = queue.waiters.pop() {
+            match waiter.slot_sender.send(RefreshSlot) {
+                Ok(()) => return,
+                // The waiter is gone, forgetting the slot instead of handing it again
+                // while the queue is locked
+                Err(slot) => std::mem::forget(slot),
+            }
+        }
+
+        queue.running -= 1;
+    }
+}
+
+async fn take_slot(priority: RefreshPriority) -> RefreshSlot {
+    let slot_receiver = {
+        let mut queue = REFRESH_QUEUE
+            .lock()
+            .expect("failed to lock the refresh queue");
+
+        if queue.running < MAX_CONCURRENT_REFRESHES {
+            queue.running += 1;
+            return RefreshSlot;
+        }
+
+        let (slot_sender, slot_receiver) = oneshot::channel();
+        let sequence = Reverse(queue.next_sequence);
+        queue.next_sequence += 1;
+        queue.waiters.push(Waiter {
+            priority,
+            sequence,
+            slot_sender,
+        });
+        slot_receiver
+    };
+
+    slot_receiver
+        .await
+        .expect("the refresh queue should hand over a slot")
+}
+
+/// Runs the future once a slot of the queue is free for the given priority
+pub async fn run<F: Future>(priority: RefreshPriority, future: F) -> F::Output {
+    let _slot = take_slot(priority).await;
+    future.await
+}
+
+/// Queues the future like [`run`] in a task of it's own
+pub fn spawn<F>(priority: RefreshPriority, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(run(priority, future))
+}
+
+/// How much queued work is running and how much is waiting for a slot
+pub fn get_queue_depth() -> (usize, usize) {
+    let queue = REFRESH_QUEUE
+        .lock()
+        .expect("failed to lock the refresh queue");
+    (queue.running, queue.waiters.len())
+}
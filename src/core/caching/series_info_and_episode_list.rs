@@ -3,8 +3,9 @@ use tokio::sync::mpsc;
 use tracing::info;
 
 use super::episode_list::EpisodeList;
+use super::refresh_queue::{self, RefreshPriority};
 use super::series_information::get_series_main_info_with_id;
-use super::CACHER;
+use super::{write_cache, CACHER};
 use crate::core::api::tv_maze::series_information::get_series_info_and_episode_list;
 
 #[derive(Copy, Clone)]
@@ -44,7 +45,7 @@ impl SeriesInfoAndEpisodeList {
             .map(|series_id| {
                 let series_id = *series_id;
                 let sender = self.completion_signal_sender.clone();
-                tokio::spawn(async move {
+                refresh_queue::spawn(RefreshPriority::Visible, async move {
                     let res = Self::cache_series(series_id).await;
                     if report_progress {
                         sender
@@ -73,49 +74,28 @@ impl SeriesInfoAndEpisodeList {
                     .get_episode_list()
                     .expect("series info should have embedded episode list");
 
-                let handle_1 = tokio::spawn({
-                    let series_cache_path = CACHER.get_cache_file_path(
-                        super::CacheFilePath::SeriesMainInformation(series_id),
-                    );
-
-                    let mut series_cache_folder = series_cache_path.clone();
-                    series_cache_folder.pop();
-
-                    fs::create_dir_all(&series_cache_folder)
-                        .await
-                        .expect("failed to create series cache directory");
+                let series_cache_path = CACHER
+                    .get_cache_file_path(super::CacheFilePath::SeriesMainInformation(series_id));
+                let series_info_json = serde_json::to_string_pretty(&series_info)
+                    .expect("series information should be serializable");
 
+                let handle_1 = tokio::spawn(async move {
                     info!("caching 'series information' for series id {}", series_id);
-
-                    fs::write(
-                        series_cache_path,
-                        serde_json::to_string_pretty(&series_info)
-                            .expect("series information should be serializable"),
-                    )
+                    write_cache(series_info_json, &series_cache_path).await
                 });
 
-                let handle_2 = tokio::spawn({
-                    let episode_cache_path = CACHER
-                        .get_cache_file_path(super::CacheFilePath::SeriesEpisodeList(series_id));
-
-                    let mut episodes_cache_folder = episode_cache_path.clone();
-                    episodes_cache_folder.pop();
-
-                    fs::create_dir_all(&episodes_cache_folder)
-                        .await
-                        .expect("failed to create series cache directory");
+                let episode_cache_path =
+                    CACHER.get_cache_file_path(super::CacheFilePath::SeriesEpisodeList(series_id));
+                let episode_list_json = serde_json::to_string_pretty(&episode_list)
+                    .expect("series information should be serializable");
 
+                let handle_2 = tokio::spawn(async move {
                     info!("caching 'episode list' for series id {}", series_id);
-
-                    fs::write(
-                        episode_cache_path,
-                        serde_json::to_string_pretty(&episode_list)
-                            .expect("series information should be serializable"),
-                    )
+                    write_cache(episode_list_json, &episode_cache_path).await
                 });
 
-                handle_1.await??;
-                handle_2.await??;
+                handle_1.await?;
+                handle_2.await?;
             }
         }
         Ok(())
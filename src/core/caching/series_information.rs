@@ -1,12 +1,14 @@
+use super::refresh_queue::{self, RefreshPriority};
 use super::tv_maze::series_information;
 use super::*;
+use crate::core::metrics::{self, CacheKind};
 
 use std::io::ErrorKind;
 
 pub async fn get_series_main_info_with_url(url: String) -> Result<SeriesMainInformation, ApiError> {
     let id = url
         .split('/')
-        .last()
+        .next_back()
         .expect("invalid url, no series id at the end of url")
         .parse::<u32>()
         .expect("could not parse series id from url");
@@ -20,34 +22,45 @@ pub async fn get_series_main_info_with_id(
     let series_information_path =
         CACHER.get_cache_file_path(CacheFilePath::SeriesMainInformation(series_id));
 
-    let series_information_json = match read_cache(&series_information_path).await {
-        Ok(json_string) => json_string,
+    let _in_flight = in_flight::join(&series_information_path).await;
+
+    let cached_json = read_cache(&series_information_path).await;
+    metrics::record_cache_lookup(CacheKind::SeriesInformation, cached_json.is_ok());
+
+    match cached_json {
+        Ok(json_string) => deserialize_json(&json_string),
         Err(err) => {
             info!("falling back online for 'series information' for series id: {series_id}");
-            let json_string = series_information::get_series_main_info_with_id(series_id).await?;
+            let series_info = series_information::get_series_main_info_with_id(series_id).await?;
 
             if err.kind() == ErrorKind::NotFound {
-                write_cache(&json_string, &series_information_path).await;
+                cache_series_information(&series_info).await;
             }
-            json_string
+            Ok(series_info)
         }
-    };
-    deserialize_json(&series_information_json)
+    }
 }
 
-/// Caches the given `SeriesMainInformation`'s `&str` if not cached already
-pub async fn cache_series_information(series_id: u32, series_info_str: &str) {
+/// Caches the given `SeriesMainInformation` if not cached already
+pub async fn cache_series_information(series_info: &SeriesMainInformation) {
     let series_information_path =
-        CACHER.get_cache_file_path(CacheFilePath::SeriesMainInformation(series_id));
+        CACHER.get_cache_file_path(CacheFilePath::SeriesMainInformation(series_info.id));
     if !series_information_path.exists() {
-        write_cache(series_info_str, &series_information_path).await;
+        let series_info_str = serde_json::to_string_pretty(series_info)
+            .expect("SeriesMainInformation should be serializable");
+        write_cache(&series_info_str, &series_information_path).await;
     }
 }
 
 pub async fn get_series_main_info_with_ids(series_ids: Vec<String>) -> Vec<SeriesMainInformation> {
     let handles: Vec<_> = series_ids
         .iter()
-        .map(|id| tokio::spawn(get_series_main_info_with_id(id.parse().unwrap())))
+        .map(|id| {
+            refresh_queue::spawn(
+                RefreshPriority::Visible,
+                get_series_main_info_with_id(id.parse().unwrap()),
+            )
+        })
         .collect();
 
     let mut series_infos = Vec::with_capacity(handles.len());
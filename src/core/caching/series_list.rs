@@ -1,12 +1,24 @@
 //! Perform different operations on the database series
 
-use super::{episode_list::EpisodeReleaseTime, series_information};
+use super::{
+    episode_list::{EpisodeBadge, EpisodeReleaseTime},
+    refresh_queue::{self, RefreshPriority},
+    series_information,
+};
 use crate::core::{
     api::tv_maze::{episodes_information::Episode, series_information::SeriesMainInformation},
-    database::{self, Series},
+    database::{self, Series, SeriesStatus},
 };
 use lazy_static::lazy_static;
 
+/// A series with it's next episode to air, when it airs and whether it is a premiere or finale
+pub type UpcomingRelease = (
+    SeriesMainInformation,
+    Episode,
+    EpisodeReleaseTime,
+    Option<EpisodeBadge>,
+);
+
 lazy_static! {
     static ref TRACKED_SERIES_INFORMATION_REQUEST_LOCK: tokio::sync::Mutex<()> =
         tokio::sync::Mutex::new(());
@@ -17,12 +29,79 @@ pub struct SeriesList {
 }
 
 impl SeriesList {
+    /// Lists the series in the database that are not archived
     pub fn new() -> Self {
+        let archived_series_ids = database::DB.get_archived_series_ids();
+        Self {
+            series_list: database::DB
+                .get_ids_and_series()
+                .into_iter()
+                .filter(|(_, series)| !archived_series_ids.contains(&series.id()))
+                .collect(),
+        }
+    }
+
+    /// Lists all the series in the database, the archived ones included
+    pub fn all() -> Self {
         Self {
             series_list: database::DB.get_ids_and_series(),
         }
     }
 
+    /// Only lists the series in the database that have the given status and are not archived
+    pub fn with_status(status: SeriesStatus) -> Self {
+        let archived_series_ids = database::DB.get_archived_series_ids();
+        Self {
+            series_list: database::DB
+                .get_ids_and_series()
+                .into_iter()
+                .filter(|(_, series)| !archived_series_ids.contains(&series.id()))
+                .filter(|(_, series)| database::DB.get_series_status(series.id()) == status)
+                .collect(),
+        }
+    }
+
+    /// Only lists the archived series in the database
+    pub fn archived() -> Self {
+        let archived_series_ids = database::DB.get_archived_series_ids();
+        Self {
+            series_list: database::DB
+                .get_ids_and_series()
+                .into_iter()
+                .filter(|(_, series)| archived_series_ids.contains(&series.id()))
+                .collect(),
+        }
+    }
+
+    /// Gets the series information of all the series given the provided status, whether
+    /// they are in the database or not, leaving out the archived ones
+    ///
+    /// # Note
+    /// Does not work for the default status, use `with_status` for it instead.
+    pub async fn get_series_information_with_status(
+        status: SeriesStatus,
+    ) -> anyhow::Result<Vec<SeriesMainInformation>> {
+        let archived_series_ids = database::DB.get_archived_series_ids();
+        let handles: Vec<_> = database::DB
+            .get_series_ids_with_status(status)
+            .into_iter()
+            .filter(|id| !archived_series_ids.contains(id))
+            .map(|id| {
+                refresh_queue::spawn(
+                    RefreshPriority::Visible,
+                    series_information::get_series_main_info_with_id(id),
+                )
+            })
+            .collect();
+
+        let mut series_information = Vec::with_capacity(handles.len());
+        for handle in handles {
+            series_information.push(handle.await??)
+        }
+
+        Ok(series_information)
+    }
+
     pub fn get_tracked_series_ids(&self) -> Vec<&str> {
         self.series_list
             .iter()
@@ -58,7 +137,12 @@ impl SeriesList {
 
         let handles: Vec<_> = untracked_ids
             .iter()
-            .map(|id| tokio::spawn(series_information::get_series_main_info_with_id(*id)))
+            .map(|id| {
+                refresh_queue::spawn(
+                    RefreshPriority::Visible,
+                    series_information::get_series_main_info_with_id(*id),
+                )
+            })
             .collect();
 
         let mut series_information = Vec::with_capacity(handles.len());
@@ -92,7 +176,12 @@ impl SeriesList {
 
         let handles: Vec<_> = tracked_ids
             .iter()
-            .map(|id| tokio::spawn(series_information::get_series_main_info_with_id(*id)))
+            .map(|id| {
+                refresh_queue::spawn(
+                    RefreshPriority::Visible,
+                    series_information::get_series_main_info_with_id(*id),
+                )
+            })
             .collect();
 
         let mut series_information = Vec::with_capacity(handles.len());
@@ -112,7 +201,10 @@ impl SeriesList {
             .iter()
             .map(|(id, _)| {
                 let id = id.parse().expect("could not parse series id");
-                tokio::spawn(series_information::get_series_main_info_with_id(id))
+                refresh_queue::spawn(
+                    RefreshPriority::Visible,
+                    series_information::get_series_main_info_with_id(id),
+                )
             })
             .collect();
 
@@ -153,16 +245,14 @@ impl SeriesList {
 
         let mut episode_list_handles = Vec::with_capacity(series_infos.len());
         for series_info in series_infos.iter() {
-            episode_list_handles.push(tokio::spawn(super::episode_list::EpisodeList::new(
-                series_info.id,
-            )))
+            episode_list_handles.push(refresh_queue::spawn(
+                RefreshPriority::Visible,
+                super::episode_list::EpisodeList::new(series_info.id),
+            ))
         }
 
         let mut waiting_releases_series_infos = Vec::with_capacity(series_infos.len());
-        for (handle, series_info) in episode_list_handles
-            .into_iter()
-            .zip(series_infos.into_iter())
-        {
+        for (handle, series_info) in episode_list_handles.into_iter().zip(series_infos) {
             let episode_list = handle.await??;
             if episode_list.get_next_episode_to_air().is_none() {
                 waiting_releases_series_infos.push(series_info)
@@ -173,16 +263,21 @@ impl SeriesList {
 
     pub async fn get_upcoming_release_series_information_and_episodes(
         &self,
-    ) -> anyhow::Result<Vec<(SeriesMainInformation, Episode, EpisodeReleaseTime)>> {
+    ) -> anyhow::Result<Vec<UpcomingRelease>> {
         let series_infos = self.get_running_tracked_series_information().await?;
         let mut waiting_releases_series_infos = Vec::with_capacity(series_infos.len());
 
         let handles: Vec<_> = series_infos
             .iter()
-            .map(|series_info| tokio::spawn(super::episode_list::EpisodeList::new(series_info.id)))
+            .map(|series_info| {
+                refresh_queue::spawn(
+                    RefreshPriority::Visible,
+                    super::episode_list::EpisodeList::new(series_info.id),
+                )
+            })
             .collect();
 
-        for (handle, series_info) in handles.into_iter().zip(series_infos.into_iter()) {
+        for (handle, series_info) in handles.into_iter().zip(series_infos) {
             let episode_list = handle.await??;
             if let Some((next_episode, Some(release_time))) = episode_list
                 .get_next_episode_to_air()
@@ -192,6 +287,7 @@ impl SeriesList {
                     series_info,
                     next_episode.to_owned(),
                     release_time,
+                    episode_list.get_episode_badge(next_episode),
                 ))
             }
         }
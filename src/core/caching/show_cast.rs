@@ -2,7 +2,7 @@ use std::io::ErrorKind;
 
 use tracing::info;
 
-use super::{CacheFilePath, CACHER};
+use super::{in_flight, CacheFilePath, CACHER};
 use crate::core::{
     api::tv_maze::{
         deserialize_json,
@@ -10,12 +10,18 @@ use crate::core::{
         ApiError,
     },
     caching::{read_cache, write_cache},
+    metrics::{self, CacheKind},
 };
 
 pub async fn get_show_cast(series_id: u32) -> Result<Vec<Cast>, ApiError> {
     let series_cast_filepath = CACHER.get_cache_file_path(CacheFilePath::SeriesShowCast(series_id));
 
-    let json_string = match read_cache(&series_cast_filepath).await {
+    let _in_flight = in_flight::join(&series_cast_filepath).await;
+
+    let cached_json = read_cache(&series_cast_filepath).await;
+    metrics::record_cache_lookup(CacheKind::Cast, cached_json.is_ok());
+
+    let json_string = match cached_json {
         Ok(json_string) => json_string,
         Err(err) => {
             info!("falling back online for 'show cast' for series id: {series_id}");
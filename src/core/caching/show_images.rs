@@ -1,20 +1,27 @@
 use std::io::ErrorKind;
 
 use super::{
-    load_image, read_cache, write_cache, CacheFilePath, ImageKind, ImageResolution, CACHER,
+    in_flight, load_image, load_image_on_demand, preferred_resolution, read_cache, write_cache,
+    CacheFilePath, ImageKind, ImageResolution, CACHER,
 };
 use crate::core::api::tv_maze::{
     deserialize_json,
     show_images::{get_show_images as get_show_images_api, Image, ImageType},
     ApiError,
 };
+use crate::core::metrics::{self, CacheKind};
 use tracing::info;
 
 pub async fn get_show_images(series_id: u32) -> Result<Vec<Image>, ApiError> {
     let series_image_list_path =
         CACHER.get_cache_file_path(CacheFilePath::SeriesImageList(series_id));
 
-    let image_list_json = match read_cache(&series_image_list_path).await {
+    let _in_flight = in_flight::join(&series_image_list_path).await;
+
+    let cached_json = read_cache(&series_image_list_path).await;
+    metrics::record_cache_lookup(CacheKind::ImageList, cached_json.is_ok());
+
+    let image_list_json = match cached_json {
         Ok(info) => info,
         Err(err) => {
             info!(
@@ -33,31 +40,38 @@ pub async fn get_show_images(series_id: u32) -> Result<Vec<Image>, ApiError> {
 }
 
 /// Loads the most recent image banner from the provided series id
-pub async fn get_recent_banner(series_id: u32) -> Option<bytes::Bytes> {
+///
+/// The resolution follows the image quality setting, `on_demand` loads the banner
+/// even then.
+pub async fn get_recent_banner(series_id: u32, on_demand: bool) -> Option<bytes::Bytes> {
     let images = get_show_images(series_id).await.ok()?;
 
-    // Trying to take a background first if any
-    if let Some(recent_background) = images
+    // Trying to take a background first if any, falling to anything that is not a poster
+    // as poster dimensions don't look great as a background
+    let recent_banner = match images
         .iter()
-        .filter(|image| image.get_image_type() == Some(ImageType::Background))
-        .last()
+        .rfind(|image| image.get_image_type() == Some(ImageType::Background))
     {
-        return load_image(
-            recent_background.resolutions.original.url.clone(),
+        Some(recent_background) => recent_background.clone(),
+        None => images
+            .into_iter()
+            .rfind(|image| image.get_image_type() != Some(ImageType::Poster))?,
+    };
+
+    let (image_url, image_resolution) = match (
+        preferred_resolution(ImageKind::Background, true),
+        recent_banner.resolutions.medium,
+    ) {
+        (ImageResolution::Medium, Some(medium)) => (medium.url, ImageResolution::Medium),
+        _ => (
+            recent_banner.resolutions.original.url,
             ImageResolution::Original(ImageKind::Background),
-        )
-        .await;
+        ),
     };
 
-    // Falling to anything that is not a poster as poster dimensions don't look great as a background
-    let recent_banner = images
-        .into_iter()
-        .filter(|image| image.get_image_type() != Some(ImageType::Poster))
-        .last()?;
-
-    load_image(
-        recent_banner.resolutions.original.url,
-        ImageResolution::Original(ImageKind::Background),
-    )
-    .await
+    if on_demand {
+        load_image_on_demand(image_url, image_resolution).await
+    } else {
+        load_image(image_url, image_resolution).await
+    }
 }
@@ -1,12 +1,15 @@
 use std::collections::HashSet;
 
+use super::refresh_queue::{self, RefreshPriority};
 use crate::core::api::tv_maze::episodes_information::Episode;
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 use crate::core::api::tv_maze::tv_schedule::{get_episodes_with_country, get_episodes_with_date};
 use crate::core::api::tv_maze::Rated;
 use crate::core::posters_hiding::HIDDEN_SERIES;
+use crate::core::{content_filter, discover_filter};
 
 pub mod full_schedule;
+pub mod prime_time_grid;
 
 /// Retrieves series aired on a specific date through the provided optional &str
 /// If None is supplied, it will default the the current day
@@ -26,14 +29,19 @@ pub async fn get_series_with_date(
 
     let mut series_infos = deduplicate_items(series_infos)
         .into_iter()
-        .filter(|series| hidden_series_ids.get(&series.id).is_none())
+        .filter(|series| !hidden_series_ids.contains(&series.id))
+        .filter(|series| !content_filter::is_filtered(series))
+        .filter(|series| !discover_filter::is_filtered(series))
         .collect::<Vec<SeriesMainInformation>>();
 
     sort_by_rating(&mut series_infos);
     Ok(series_infos)
 }
 
-/// # Retrieves series aired on the current day at a particular country provided in ISO 3166-1
+/// # Retrieves series aired at a particular country provided in ISO 3166-1 on a specific date
+///
+/// The date is provided through the optional &str, if None is supplied, it will default the
+/// the current day.
 ///
 /// ## Note
 /// Expect slightly different results for the when calling multiple times with very small time gap,
@@ -44,8 +52,9 @@ pub async fn get_series_with_date(
 /// Excludes hidden series
 pub async fn get_series_with_country(
     country_iso: &str,
+    date: Option<&str>,
 ) -> anyhow::Result<Vec<SeriesMainInformation>> {
-    let episodes = get_episodes_with_country(country_iso).await?;
+    let episodes = get_episodes_with_country(country_iso, date).await?;
 
     let series_infos = get_series_infos_from_episodes(episodes).await?;
 
@@ -53,7 +62,9 @@ pub async fn get_series_with_country(
 
     let mut series_infos = deduplicate_items(series_infos)
         .into_iter()
-        .filter(|series| hidden_series_ids.get(&series.id).is_none())
+        .filter(|series| !hidden_series_ids.contains(&series.id))
+        .filter(|series| !content_filter::is_filtered(series))
+        .filter(|series| !discover_filter::is_filtered(series))
         .collect::<Vec<SeriesMainInformation>>();
 
     sort_by_rating(&mut series_infos);
@@ -105,9 +116,10 @@ async fn get_series_infos_from_episodes(
         .into_iter()
         .filter_map(|mut episode| episode.take())
         .map(|episode| {
-            tokio::spawn(super::series_information::get_series_main_info_with_url(
-                episode.links.show.href,
-            ))
+            refresh_queue::spawn(
+                RefreshPriority::Visible,
+                super::series_information::get_series_main_info_with_url(episode.links.show.href),
+            )
         })
         .collect();
 
@@ -2,7 +2,6 @@ use std::collections::HashSet;
 
 use anyhow::{bail, Context};
 use chrono::{Datelike, Local, NaiveDate};
-use tokio::fs;
 use tokio::sync::{OnceCell, RwLock};
 use tracing::{error, info};
 
@@ -10,21 +9,58 @@ use crate::core::api::tv_maze::episodes_information::Episode;
 use crate::core::api::tv_maze::series_information::{
     Genre, SeriesMainInformation, ShowNetwork, ShowWebChannel,
 };
-use crate::core::api::tv_maze::tv_schedule::get_full_schedule;
+use crate::core::api::tv_maze::tv_schedule::get_full_schedule_url;
 use crate::core::api::tv_maze::{deserialize_json, Rated};
-use crate::core::caching::CACHER;
+use crate::core::caching::{read_cache, read_cache_bytes, refresh_cache, write_cache, CACHER};
+use crate::core::{content_filter, discover_filter};
 
 const FULL_SCHEDULE_CACHE_FILENAME: &str = "full-schedule";
+/// The episodes of the full schedule already parsed, stored as bincode
+///
+/// Parsing the multi-MB json of the full schedule takes a while, so it's parsed result
+/// is kept for as long as the json itself is not outdated.
+const PARSED_FULL_SCHEDULE_CACHE_FILENAME: &str = "full-schedule-parsed";
+/// How long the full schedule is used before being refreshed
+const FULL_SCHEDULE_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
 
 static FULL_SCHEDULE: OnceCell<FullSchedule> = OnceCell::const_new();
 static HIDDEN_SERIES_IDS: RwLock<Option<HashSet<u32>>> = RwLock::const_new(None);
 
-fn is_hidden(id: u32) -> bool {
-    HIDDEN_SERIES_IDS
-        .blocking_read()
-        .as_ref()
-        .map(|hidden_series_id| hidden_series_id.get(&id).is_some())
-        .unwrap_or_default()
+/// Whether the series was hidden by the user, the content filter or the discover filter
+fn is_hidden(series_info: &SeriesMainInformation) -> bool {
+    content_filter::is_filtered(series_info)
+        || discover_filter::is_filtered(series_info)
+        || HIDDEN_SERIES_IDS
+            .blocking_read()
+            .as_ref()
+            .map(|hidden_series_id| hidden_series_id.get(&series_info.id).is_some())
+            .unwrap_or_default()
+}
+
+/// How long ago the cache file was last written
+fn get_cache_age(cache_path: &std::path::Path) -> Option<std::time::Duration> {
+    let modified = cache_path
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .map_err(|err| {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                error!(
+                    "failed to get the time of modification of '{}': {}",
+                    cache_path.display(),
+                    err
+                )
+            }
+        })
+        .ok()?;
+
+    Some(modified.elapsed().unwrap_or_else(|err| {
+        error!(
+            "failed to get the age of '{}': {}",
+            cache_path.display(),
+            err
+        );
+        std::time::Duration::default()
+    }))
 }
 
 fn sort_by_rating<T>(series_infos: &mut [&T])
@@ -34,10 +70,55 @@ where
     series_infos.sort_unstable_by(|a, b| b.rating().total_cmp(&a.rating()));
 }
 
+/// A series of the full schedule with it's searchable fields lowercased
+#[derive(Clone, Debug)]
+struct IndexedSeries {
+    /// The index of an episode of the series in the schedule, the series being embedded in it
+    episode_index: usize,
+    name: String,
+    genres: Vec<String>,
+    /// The name of the network or of the webchannel
+    network: Option<String>,
+    premiere_year: Option<i32>,
+}
+
+impl IndexedSeries {
+    fn matches_word(&self, query_word: &str) -> bool {
+        if let Ok(year) = query_word.parse::<i32>() {
+            return self.premiere_year == Some(year) || self.name.contains(query_word);
+        }
+
+        self.name.contains(query_word)
+            || self
+                .genres
+                .iter()
+                .any(|genre| genre.starts_with(query_word))
+            || self
+                .network
+                .as_ref()
+                .is_some_and(|network| network.contains(query_word))
+    }
+
+    /// How well the name matches the whole query, higher is better
+    fn name_score(&self, query: &str) -> u8 {
+        if self.name == query {
+            3
+        } else if self.name.starts_with(query) {
+            2
+        } else if self.name.contains(query) {
+            1
+        } else {
+            0
+        }
+    }
+}
+
 /// `FullSchedule` is a list of all future episodes known to TVmaze, regardless of their country.
 #[derive(Clone, Debug)]
 pub struct FullSchedule {
     episodes: Vec<Episode>,
+    /// The series of the episodes, one entry per series
+    search_index: Vec<IndexedSeries>,
 }
 
 impl FullSchedule {
@@ -61,49 +142,49 @@ impl FullSchedule {
         let mut cache_path = CACHER.get_root_cache_path().to_owned();
         cache_path.push(FULL_SCHEDULE_CACHE_FILENAME);
 
-        match cache_path.metadata() {
-            Ok(metadata) => match metadata.created() {
-                Ok(sys_time) => {
-                    let daily_schedule_age = sys_time.elapsed().unwrap_or_else(|err| {
-                        error!("failed to get daily episode schedule age: {}", err);
-                        std::time::Duration::default()
-                    });
-                    if daily_schedule_age > std::time::Duration::from_secs(24 * 60 * 60) {
-                        info!("cleaning outdated daily episode schedule");
-                        fs::remove_file(&cache_path).await.unwrap_or_else(|err| {
-                            error!("failed to clean outdated daily episode schedule: {}", err)
-                        });
-                    }
-                }
-                Err(err) => error!(
-                    "failed to get daily episode schedule time of creating: {}",
+        let mut parsed_cache_path = CACHER.get_root_cache_path().to_owned();
+        parsed_cache_path.push(PARSED_FULL_SCHEDULE_CACHE_FILENAME);
+
+        let is_outdated = get_cache_age(&cache_path)
+            .map(|age| age > FULL_SCHEDULE_TTL)
+            .unwrap_or_default();
+
+        // The parsed schedule is always written after the json, being older means it's stale
+        let is_parsed_fresh = match (
+            get_cache_age(&parsed_cache_path),
+            get_cache_age(&cache_path),
+        ) {
+            (Some(parsed_age), Some(age)) => parsed_age <= age,
+            _ => false,
+        };
+
+        if !is_outdated && is_parsed_fresh {
+            match read_cache_bytes(&parsed_cache_path).await.map(|parsed| {
+                bincode::deserialize::<Vec<Episode>>(&parsed).map_err(|err| err.to_string())
+            }) {
+                Ok(Ok(episodes)) => return Ok(Self::with_episodes(episodes)),
+                Ok(Err(err)) => error!(
+                    "failed to decode the parsed daily episode schedule: {}",
                     err
                 ),
-            },
-            Err(err) => error!("failed to get daily episode schedule metadata: {}", err),
+                Err(err) => error!("failed to read the parsed daily episode schedule: {}", err),
+            }
         }
 
-        let json_string = match fs::read_to_string(&cache_path).await {
+        let json_string = match read_cache(&cache_path).await {
+            Ok(_) if is_outdated => {
+                info!("refreshing outdated daily episode schedule");
+                refresh_cache(get_full_schedule_url(), &cache_path)
+                    .await
+                    .context("failed to refresh daily episode schedule")?
+            }
             Ok(json_string) => json_string,
             Err(err) => {
                 if let std::io::ErrorKind::NotFound = err.kind() {
                     info!("downloading daily episode schedule");
-                    let cache_str = get_full_schedule()
-                        .await
-                        .context("failed to download daily episode schedule")?;
-                    {
-                        // Creating cache directory if it does not exist
-                        let mut cache_dir = cache_path.clone();
-                        cache_dir.pop();
-                        fs::create_dir_all(cache_dir)
-                            .await
-                            .context("failed to create cache dir")?;
-                    }
-
-                    fs::write(cache_path, &cache_str)
+                    refresh_cache(get_full_schedule_url(), &cache_path)
                         .await
-                        .context("failed to save daily episode schedule")?;
-                    cache_str
+                        .context("failed to download daily episode schedule")?
                 } else {
                     bail!(
                         "critical error when reading daily episode schedule: {}",
@@ -114,7 +195,106 @@ impl FullSchedule {
         };
 
         let episodes = deserialize_json::<Vec<Episode>>(&json_string)?;
-        Ok(Self { episodes })
+
+        match bincode::serialize(&episodes) {
+            Ok(parsed) => write_cache(parsed, &parsed_cache_path).await,
+            Err(err) => error!(
+                "failed to encode the parsed daily episode schedule: {}",
+                err
+            ),
+        }
+
+        Ok(Self::with_episodes(episodes))
+    }
+
+    /// Builds the search index of the series of the episodes along the episodes
+    fn with_episodes(episodes: Vec<Episode>) -> Self {
+        let mut seen_series_ids = HashSet::new();
+
+        let search_index = episodes
+            .iter()
+            .enumerate()
+            .filter_map(|(episode_index, episode)| {
+                Some((episode_index, &episode.embedded.as_ref()?.show))
+            })
+            .filter(|(_, series_info)| seen_series_ids.insert(series_info.id))
+            .map(|(episode_index, series_info)| IndexedSeries {
+                episode_index,
+                name: series_info.name.to_lowercase(),
+                genres: series_info
+                    .genres
+                    .iter()
+                    .map(|genre| genre.to_lowercase())
+                    .collect(),
+                network: series_info
+                    .network
+                    .as_ref()
+                    .map(|network| network.name.to_lowercase())
+                    .or_else(|| {
+                        series_info
+                            .web_channel
+                            .as_ref()
+                            .map(|webchannel| webchannel.name.to_lowercase())
+                    }),
+                premiere_year: series_info
+                    .premiered
+                    .as_ref()
+                    .and_then(|premiered| premiered.get(..4))
+                    .and_then(|year| year.parse().ok()),
+            })
+            .collect();
+
+        Self {
+            episodes,
+            search_index,
+        }
+    }
+
+    /// Searches the series by name, genres, network and premiere year, the best matches first
+    ///
+    /// Every word of the query has to match one of the fields, numbers only matching the
+    /// premiere year. Series whose name matches the whole query come first, then the best
+    /// rated ones. Nothing is requested, making it instant.
+    ///
+    /// # Note
+    /// Reads the hidden series with a blocking lock, so it must not be called from async code.
+    pub fn search(&self, query: &str, amount: usize) -> Vec<&SeriesMainInformation> {
+        let query = query.trim().to_lowercase();
+        let query_words: Vec<&str> = query.split_whitespace().collect();
+
+        if query_words.is_empty() {
+            return vec![];
+        }
+
+        let mut matches: Vec<(u8, &SeriesMainInformation)> = self
+            .search_index
+            .iter()
+            .filter(|indexed_series| {
+                query_words
+                    .iter()
+                    .all(|query_word| indexed_series.matches_word(query_word))
+            })
+            .filter_map(|indexed_series| {
+                let series_info = &self.episodes[indexed_series.episode_index]
+                    .embedded
+                    .as_ref()?
+                    .show;
+                Some((indexed_series.name_score(&query), series_info))
+            })
+            .filter(|(_, series_info)| !is_hidden(series_info))
+            .collect();
+
+        matches.sort_unstable_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| b.rating().total_cmp(&a.rating()))
+        });
+
+        matches
+            .into_iter()
+            .take(amount)
+            .map(|(_, series_info)| series_info)
+            .collect()
     }
 
     /// # Returns new series aired in the given month
@@ -251,7 +431,7 @@ impl FullSchedule {
             series_info
                 .get_genres()
                 .into_iter()
-                .any(|series_genre| genres.iter().any(|genre| *genre == series_genre))
+                .any(|series_genre| genres.contains(&series_genre))
         })
     }
 
@@ -353,7 +533,7 @@ impl FullSchedule {
             .filter_map(|episode| episode.embedded.as_ref())
             .map(|embedded| &embedded.show)
             .filter(|series_info| condition(series_info))
-            .filter(|series| !is_hidden(series.id))
+            .filter(|series| !is_hidden(series))
             .collect::<HashSet<&SeriesMainInformation>>()
             .into_iter()
             .collect()
@@ -370,7 +550,7 @@ impl FullSchedule {
             .iter()
             .filter_map(|episode| episode.embedded.as_ref())
             .map(|embedded| &embedded.show)
-            .filter(|series| !is_hidden(series.id))
+            .filter(|series| !is_hidden(series))
             .collect::<HashSet<&SeriesMainInformation>>()
             .into_iter()
             .collect()
@@ -412,7 +592,7 @@ impl FullSchedule {
             .filter(|episode| {
                 episode
                     .date_naive()
-                    .map(|naive_date| all_dates_of_month.iter().any(|date| *date == naive_date))
+                    .map(|naive_date| all_dates_of_month.contains(&naive_date))
                     .unwrap_or(false)
             })
             .collect();
@@ -422,7 +602,7 @@ impl FullSchedule {
                 .into_iter()
                 .filter_map(|episode| episode.embedded.as_ref())
                 .map(|embedded| &embedded.show)
-                .filter(|series| !is_hidden(series.id))
+                .filter(|series| !is_hidden(series))
                 .collect(),
         );
 
@@ -471,7 +651,7 @@ impl FullSchedule {
                 .filter_map(|episode| episode.embedded.as_ref())
                 .map(|embedded| &embedded.show)
                 .filter(|series_info| condition(series_info))
-                .filter(|series| !is_hidden(series.id))
+                .filter(|series| !is_hidden(series))
                 .collect(),
         );
 
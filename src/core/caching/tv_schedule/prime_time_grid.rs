@@ -0,0 +1,142 @@
+//! # Prime time grid
+//!
+//! A TV guide of the prime time of a day in a country, with the channels as rows and
+//! the time slots as columns. Air times are the ones of the country, not the local ones.
+
+use chrono::{NaiveTime, Timelike};
+
+use crate::core::api::tv_maze::episodes_information::Episode;
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::api::tv_maze::tv_schedule::get_episodes_with_country;
+use crate::core::{content_filter, discover_filter};
+
+/// The hour the prime time grid starts at
+pub const PRIME_TIME_START_HOUR: u32 = 18;
+/// How many time slots the grid has, reaching midnight
+pub const SLOTS_AMOUNT: usize = 12;
+/// The length of a time slot
+pub const SLOT_MINUTES: u32 = 30;
+
+/// Episodes with no runtime are assumed to fill a single time slot
+const DEFAULT_RUNTIME: u32 = SLOT_MINUTES;
+
+#[derive(Debug, Clone)]
+pub struct GridEntry {
+    pub series_info: SeriesMainInformation,
+    pub episode_name: String,
+    pub season: u32,
+    pub number: Option<u32>,
+    /// The index of the first time slot covered by the entry
+    pub start_slot: usize,
+    /// How many time slots are covered by the entry, at least one
+    pub slots: usize,
+}
+
+/// A row of the grid, entries are sorted by their start slot and never overlap
+#[derive(Debug, Clone)]
+pub struct GridChannel {
+    pub name: String,
+    pub entries: Vec<GridEntry>,
+}
+
+/// The start time of the time slot at the given index
+pub fn slot_start_time(slot: usize) -> NaiveTime {
+    let minutes = PRIME_TIME_START_HOUR * 60 + slot as u32 * SLOT_MINUTES;
+    NaiveTime::from_hms_opt(minutes / 60, minutes % 60, 0).expect("slot should be within a day")
+}
+
+/// Retrieves the prime time grid of a particular country provided in ISO 3166-1 on the
+/// given date in the format 2020-05-29
+pub async fn get_prime_time_grid(
+    country_iso: &str,
+    date: &str,
+) -> anyhow::Result<Vec<GridChannel>> {
+    let episodes = get_episodes_with_country(country_iso, Some(date)).await?;
+    Ok(build_grid(episodes))
+}
+
+fn build_grid(episodes: Vec<Episode>) -> Vec<GridChannel> {
+    let mut channels: Vec<GridChannel> = vec![];
+
+    for episode in episodes {
+        let Some(series_info) = episode.show.clone() else {
+            continue;
+        };
+
+        if content_filter::is_filtered(&series_info) || discover_filter::is_filtered(&series_info) {
+            continue;
+        }
+
+        let Some((start_slot, slots)) = get_slots(&episode) else {
+            continue;
+        };
+
+        let channel_name = series_info
+            .network
+            .as_ref()
+            .map(|network| network.name.clone())
+            .or_else(|| {
+                series_info
+                    .web_channel
+                    .as_ref()
+                    .map(|web_channel| web_channel.name.clone())
+            })
+            .unwrap_or_else(|| "Other".to_owned());
+
+        let entry = GridEntry {
+            series_info,
+            episode_name: episode.name,
+            season: episode.season,
+            number: episode.number,
+            start_slot,
+            slots,
+        };
+
+        match channels
+            .iter_mut()
+            .find(|channel| channel.name == channel_name)
+        {
+            Some(channel) => channel.entries.push(entry),
+            None => channels.push(GridChannel {
+                name: channel_name,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    for channel in channels.iter_mut() {
+        channel.entries.sort_by_key(|entry| entry.start_slot);
+
+        // Keeping the first of overlapping entries as a cell can only show one
+        let mut next_free_slot = 0;
+        channel.entries.retain(|entry| {
+            let is_free = entry.start_slot >= next_free_slot;
+            if is_free {
+                next_free_slot = entry.start_slot + entry.slots;
+            }
+            is_free
+        });
+    }
+
+    channels.sort_by(|a, b| a.name.cmp(&b.name));
+    channels
+}
+
+/// The start slot and the amount of slots of an episode airing within the prime time
+fn get_slots(episode: &Episode) -> Option<(usize, usize)> {
+    let airtime = NaiveTime::parse_from_str(&episode.airtime, "%H:%M").ok()?;
+    let start_minutes =
+        (airtime.hour() * 60 + airtime.minute()).checked_sub(PRIME_TIME_START_HOUR * 60)?;
+
+    let start_slot = (start_minutes / SLOT_MINUTES) as usize;
+    if start_slot >= SLOTS_AMOUNT {
+        return None;
+    }
+
+    let runtime = episode.runtime.unwrap_or(DEFAULT_RUNTIME);
+    let slots = (runtime.div_ceil(SLOT_MINUTES) as usize)
+        .max(1)
+        .min(SLOTS_AMOUNT - start_slot);
+
+    Some((start_slot, slots))
+}
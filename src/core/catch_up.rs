@@ -0,0 +1,72 @@
+//! # Catching up
+//!
+//! Marks the part of a series already seen before tracking it as watched in one go, offered
+//! when a series is tracked for the first time so that seasons don't have to be ticked one
+//! by one.
+//!
+//! Episodes marked here were watched in the past, so no hooks are triggered for them.
+
+use anyhow::Context;
+
+use super::caching::episode_list::EpisodeList;
+use super::database::{self, Series};
+
+/// How much of the series was watched before it was tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlreadyWatched {
+    /// Every aired episode
+    CaughtUp,
+    /// The aired episodes of the given season and the ones before it
+    UpToSeason(u32),
+}
+
+/// Marks the already watched episodes of the series, returning how many were newly marked
+pub async fn mark_already_watched(
+    series_id: u32,
+    already_watched: AlreadyWatched,
+) -> anyhow::Result<usize> {
+    let episode_list = EpisodeList::new(series_id)
+        .await
+        .with_context(|| format!("failed to get episode list of series {}", series_id))?;
+
+    let Some(mut series) = database::DB.get_series(series_id) else {
+        return Ok(0);
+    };
+
+    let last_season = match already_watched {
+        AlreadyWatched::CaughtUp => None,
+        AlreadyWatched::UpToSeason(season_number) => Some(season_number),
+    };
+
+    Ok(mark_aired_episodes(&mut series, &episode_list, last_season))
+}
+
+/// Marks the aired episodes of the series up to the given season or all of them when there is
+/// none, returning how many were newly marked
+pub fn mark_aired_episodes(
+    series: &mut Series,
+    episode_list: &EpisodeList,
+    last_season: Option<u32>,
+) -> usize {
+    let mut marked_episodes = 0;
+
+    episode_list
+        .get_all_episodes()
+        .iter()
+        .filter(|episode| last_season.is_none_or(|last_season| episode.season <= last_season))
+        .filter(|episode| episode.is_future_release() == Ok(false))
+        .filter_map(|episode| episode.number.map(|number| (episode.season, number)))
+        .for_each(|(season_number, episode_number)| {
+            let is_watched = series
+                .get_season(season_number)
+                .map(|season| season.is_episode_watched(episode_number))
+                .unwrap_or(false);
+
+            if !is_watched {
+                series.add_episode_unchecked(season_number, episode_number);
+                marked_episodes += 1;
+            }
+        });
+
+    marked_episodes
+}
@@ -4,15 +4,28 @@ pub mod cli_handler {
     //! Handlers for command-line argument parsing
 
     use clap::Parser;
+    use colored::Colorize;
     use std::process::exit;
 
     use super::cli_data::*;
+    use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+    use crate::core::caching::episode_list::{EpisodeList, EpisodeNumber};
+    use crate::core::caching::series_information::get_series_main_info_with_id;
+    use crate::core::data_location::{self, DataLocation};
     use crate::core::database;
+    use crate::core::date_formatting;
+    use crate::core::deep_link::{self, DeepLink};
+    use crate::core::export::{csv, rss};
     use crate::core::paths;
     use crate::core::settings_config;
+    use crate::core::statistics::Statistics;
+    use crate::core::watch_history::{self, WatchEntry};
+    use crate::gui::helpers::{self, season_episode_str_gen};
 
     /// Handles all the logic for the command line arguments
-    pub fn handle_cli() -> anyhow::Result<()> {
+    ///
+    /// Returns the link the app was opened with when it should be opened in this instance.
+    pub fn handle_cli() -> anyhow::Result<Option<DeepLink>> {
         let mut cli = Cli::parse();
 
         let command = cli.command.take();
@@ -20,6 +33,11 @@ pub mod cli_handler {
         setup_custom_paths(cli);
 
         if let Some(command) = command {
+            // Links are forwarded to the running instance, which holds the database
+            if !matches!(command, Command::Open { .. }) {
+                database::open()?;
+            }
+
             match command {
                 Command::ImportData { file_path } => {
                     database::database_transfer::TransferData::blocking_import_to_db(file_path)?;
@@ -35,20 +53,359 @@ pub mod cli_handler {
                     println!("data exported successfully!");
                     exit(0);
                 }
+                Command::ExportRss { file_path } => {
+                    tokio::runtime::Runtime::new()?.block_on(rss::export_feed(file_path))?;
+                    println!("rss feed exported successfully!");
+                    exit(0);
+                }
+                Command::ServeRss { port } => {
+                    println!("serving rss feed at http://127.0.0.1:{}", port);
+                    tokio::runtime::Runtime::new()?.block_on(rss::serve_feed(port))?;
+                    exit(0);
+                }
+                Command::MarkWatched { series, episode } => {
+                    let series_id = resolve_series_id(series)?;
+                    let episode_number = episode
+                        .parse::<EpisodeNumber>()
+                        .map_err(|err| anyhow::anyhow!(err))?;
+                    tokio::runtime::Runtime::new()?
+                        .block_on(mark_episode_watched(series_id, episode_number))?;
+                    exit(0);
+                }
+                Command::SeriesSummary { series } => {
+                    let series_id = resolve_series_id(series)?;
+                    let series_info = tokio::runtime::Runtime::new()?
+                        .block_on(get_series_main_info_with_id(series_id))?;
+                    print_series_summary(&series_info);
+                    exit(0);
+                }
+                Command::History { since, show, csv } => {
+                    let series_id = show.as_deref().map(resolve_series_name).transpose()?;
+                    let since = since
+                        .as_deref()
+                        .map(|since| chrono::NaiveDate::parse_from_str(since, "%Y-%m-%d"))
+                        .transpose()
+                        .map_err(|_| anyhow::anyhow!("dates are written like 2023-12-31"))?;
+
+                    let history = tokio::runtime::Runtime::new()?
+                        .block_on(watch_history::get_watch_history(since, series_id))?;
+                    if csv {
+                        print!("{}", csv::watch_history_to_csv(&history));
+                    } else {
+                        print_watch_history(&history);
+                    }
+                    exit(0);
+                }
+                Command::Stats { json } => {
+                    let statistics = tokio::runtime::Runtime::new()?.block_on(Statistics::load());
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&statistics)?);
+                    } else {
+                        print_statistics(&statistics);
+                    }
+                    exit(0);
+                }
+                Command::Open { uri } => {
+                    let deep_link = uri.parse::<DeepLink>()?;
+                    if deep_link::forward_to_running_instance(deep_link)? {
+                        exit(0);
+                    }
+                    return Ok(Some(deep_link));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// How similar the name of a tracked series has to be to the given one to be suggested
+    const SUGGESTION_SIMILARITY: f64 = 0.8;
+
+    /// The most suggestions given for a mistyped series name
+    const MAX_SUGGESTIONS: usize = 3;
+
+    /// Finds the id of the tracked series the user meant
+    ///
+    /// Names are matched ignoring case and surrounding whitespace, and an unknown name gets
+    /// the closest tracked series names suggested. A name made of only digits that matches
+    /// no series is taken as an id, so that scripts passing the id keep working.
+    fn resolve_series_id(series_selector: SeriesSelector) -> anyhow::Result<u32> {
+        let name = series_selector.series;
+        if series_selector.id {
+            return name
+                .trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("\"{}\" is not a valid series id", name));
+        }
+
+        resolve_series_name(&name)
+    }
+
+    /// Finds the id of the tracked series with the given name, see `resolve_series_id`
+    fn resolve_series_name(name: &str) -> anyhow::Result<u32> {
+        let normalized_name = name.trim().to_lowercase();
+        let tracked_series: Vec<(u32, String)> = database::DB
+            .get_series_collection()
+            .iter()
+            .filter(|series| series.is_tracked())
+            .map(|series| (series.id(), series.get_name().to_owned()))
+            .collect();
+
+        let matches: Vec<&(u32, String)> = tracked_series
+            .iter()
+            .filter(|(_, series_name)| series_name.to_lowercase() == normalized_name)
+            .collect();
+
+        match matches.as_slice() {
+            [(series_id, _)] => return Ok(*series_id),
+            [] => {}
+            _ => {
+                let ids: Vec<String> = matches
+                    .iter()
+                    .map(|(series_id, _)| format!("--id {}", series_id))
+                    .collect();
+                anyhow::bail!(
+                    "more than one tracked series is named \"{}\", use one of {}",
+                    name,
+                    ids.join(", ")
+                );
             }
         }
+
+        if let Ok(series_id) = normalized_name.parse::<u32>() {
+            return Ok(series_id);
+        }
+
+        let mut suggestions: Vec<(f64, &(u32, String))> = tracked_series
+            .iter()
+            .map(|series| {
+                let similarity = strsim::jaro_winkler(&normalized_name, &series.1.to_lowercase());
+                (similarity, series)
+            })
+            .filter(|(similarity, _)| *similarity >= SUGGESTION_SIMILARITY)
+            .collect();
+        suggestions.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        if suggestions.is_empty() {
+            anyhow::bail!("no tracked series is named \"{}\"", name);
+        }
+
+        let suggestions: Vec<String> = suggestions
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, (series_id, series_name))| {
+                format!("\"{}\" (--id {})", series_name, series_id)
+            })
+            .collect();
+
+        anyhow::bail!(
+            "no tracked series is named \"{}\", did you mean {}?",
+            name,
+            suggestions.join(" or ")
+        )
+    }
+
+    async fn mark_episode_watched(
+        series_id: u32,
+        episode_number: EpisodeNumber,
+    ) -> anyhow::Result<()> {
+        let episode_list = EpisodeList::new(series_id).await?;
+        let episode = episode_list
+            .find_episode(&episode_number)
+            .ok_or_else(|| anyhow::anyhow!("episode not found"))?;
+        let (season_number, number) = (
+            episode.season,
+            episode
+                .number
+                .ok_or_else(|| anyhow::anyhow!("specials can not be marked"))?,
+        );
+
+        let mut series = match database::DB.get_series(series_id) {
+            Some(series) => series,
+            None => {
+                let series_info = get_series_main_info_with_id(series_id).await?;
+                database::Series::new(series_info.name, series_id)
+            }
+        };
+
+        if series.add_episode(season_number, number).await {
+            println!(
+                "marked {} {} '{}' as watched",
+                series.get_name(),
+                season_episode_str_gen(season_number, number),
+                episode.name
+            );
+        } else {
+            println!("episode is already watched or has not aired yet");
+        }
         Ok(())
     }
 
+    fn print_series_summary(series_info: &SeriesMainInformation) {
+        // TVmaze dates are in the format 2020-05-29
+        let year = |date: &Option<String>| {
+            date.as_deref()
+                .and_then(|date| date.split('-').next())
+                .unwrap_or_default()
+                .to_owned()
+        };
+        let years = if series_info.premiered.is_some() {
+            format!(
+                " ({} - {})",
+                year(&series_info.premiered),
+                year(&series_info.ended)
+            )
+        } else {
+            String::new()
+        };
+        println!("{}{}", series_info.name.bold().cyan(), years.dimmed());
+
+        let field = |name: &str, value: String| println!("{:>10}  {}", name.bold(), value);
+
+        field("Status", series_info.get_status().to_string());
+        if !series_info.genres.is_empty() {
+            field("Genres", series_info.genres.join(", "));
+        }
+        if let Some(network) = &series_info.network {
+            field("Network", network.name.clone());
+        } else if let Some(web_channel) = &series_info.web_channel {
+            field("Streaming", web_channel.name.clone());
+        }
+        if let Some(language) = &series_info.language {
+            field("Language", language.clone());
+        }
+        if let Some(average_runtime) = series_info.average_runtime {
+            field("Runtime", format!("{} mins", average_runtime));
+        }
+        if let Some(average_rating) = series_info.rating.average {
+            field("Rating", format!("{} {}", "★".yellow(), average_rating));
+        }
+
+        if let Some(series) = database::DB.get_series(series_info.id) {
+            let watched = format!("{} episodes watched", series.get_total_episodes());
+            field(
+                "Tracking",
+                if series.is_tracked() {
+                    watched.green().to_string()
+                } else {
+                    watched
+                },
+            );
+        }
+
+        if let Some(summary) = &series_info.summary {
+            println!("\n{}", "Summary".bold().underline());
+            print!("{}", html2text::from_read(summary.as_bytes(), 80));
+        }
+    }
+
+    fn print_watch_history(history: &[WatchEntry]) {
+        if history.is_empty() {
+            println!("no watched episodes found");
+            return;
+        }
+
+        let mut current_day = None;
+        for entry in history {
+            let day = entry.watched_at.date_naive();
+            if current_day != Some(day) {
+                println!("{}", date_formatting::long_date_str(day).bold().underline());
+                current_day = Some(day);
+            }
+
+            println!(
+                "{}  {} {} {}",
+                date_formatting::time_str(&entry.watched_at).dimmed(),
+                entry.series_name.cyan(),
+                season_episode_str_gen(entry.season, entry.number),
+                entry.episode_name.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    fn print_statistics(statistics: &Statistics) {
+        let totals = &statistics.totals;
+        let field = |name: &str, value: String| println!("{:>10}  {}", name.bold(), value);
+
+        field("Series", totals.series.to_string());
+        field("Seasons", totals.seasons.to_string());
+        field("Episodes", totals.episodes.to_string());
+        if totals.skipped_episodes != 0 {
+            field("Skipped", totals.skipped_episodes.to_string());
+        }
+        if totals.auto_marked_episodes != 0 {
+            field("Automatic", totals.auto_marked_episodes.to_string());
+        }
+        field(
+            "Watched",
+            format!(
+                "{} minutes ({})",
+                totals.watch_minutes,
+                helpers::time::SaneTime::new(totals.watch_minutes)
+                    .to_string()
+                    .trim_end()
+            ),
+        );
+        field(
+            "Streak",
+            format!(
+                "{} days, longest {} days",
+                statistics.streaks.current_days, statistics.streaks.longest_days
+            ),
+        );
+
+        if !statistics.series.is_empty() {
+            println!("\n{}", "Series".bold().underline());
+            for series in statistics.series.iter() {
+                let watch_time = series
+                    .watch_minutes
+                    .map(|minutes| format!("{} mins", minutes))
+                    .unwrap_or_else(|| "unknown".to_owned());
+                println!("{:>10}  {}", watch_time.cyan(), series.name);
+            }
+        }
+
+        if !statistics.genres.is_empty() {
+            println!("\n{}", "Genres".bold().underline());
+            for (genre, count) in statistics.genres.iter() {
+                println!("{:>10}  {} series", genre.cyan(), count);
+            }
+        }
+    }
+
     fn setup_custom_paths(cli: Cli) {
         // Setting the config file path first before we read other custom paths from the settings
-        if let Some(config_dir_path) = cli.config_dir {
+        if let Some(config_dir_path) = cli.config_dir.clone() {
             paths::PATHS
                 .write()
                 .expect("failed to write to paths")
                 .set_config_dir_path(config_dir_path);
         }
 
+        // Locations given from the cli are used as they are, their pending moves wait for a
+        // start without them
+        let mut pending_moves = vec![];
+        if cli.cache_dir.is_none() {
+            pending_moves.push(DataLocation::Cache);
+        }
+        if cli.data_dir.is_none() {
+            pending_moves.push(DataLocation::Database);
+        }
+
+        set_paths(cli);
+
+        // Moving before anything opens the database or the cache
+        for location in pending_moves {
+            if let Err(err) = data_location::apply_pending_move(location) {
+                tracing::error!(
+                    "failed to move the {}: {}",
+                    location.to_string().to_lowercase(),
+                    err
+                );
+            }
+        }
+    }
+
+    fn set_paths(cli: Cli) {
         let settings = settings_config::SETTINGS
             .read()
             .expect("failed to read settings");
@@ -79,7 +436,7 @@ pub mod cli_handler {
 pub mod cli_data {
     //! Data structures for command-line argument parsing
 
-    use clap::{Parser, Subcommand};
+    use clap::{Args, Parser, Subcommand};
     use std::path::PathBuf;
 
     #[derive(Parser)]
@@ -114,5 +471,69 @@ pub mod cli_data {
             /// Export filepath
             file_path: PathBuf,
         },
+
+        /// Export an rss feed of recently aired and upcoming tracked episodes
+        ExportRss {
+            /// Export filepath
+            file_path: PathBuf,
+        },
+
+        /// Serve an rss feed of recently aired and upcoming tracked episodes locally
+        ServeRss {
+            /// Local port to serve the feed on
+            #[clap(short, long, default_value_t = 8080)]
+            port: u16,
+        },
+
+        /// Mark an episode of a series as watched
+        MarkWatched {
+            #[clap(flatten)]
+            series: SeriesSelector,
+            /// Seasonal (S07E05) or absolute (E137 or 137) episode number
+            episode: String,
+        },
+
+        /// Print a summary of a series
+        SeriesSummary {
+            #[clap(flatten)]
+            series: SeriesSelector,
+        },
+
+        /// Print the log of watched episodes
+        History {
+            /// Only episodes watched on or after the date, like 2023-12-31
+            #[clap(short, long)]
+            since: Option<String>,
+            /// Only episodes of the tracked series with the name
+            #[clap(long)]
+            show: Option<String>,
+            /// Print the log as csv for spreadsheets
+            #[clap(long)]
+            csv: bool,
+        },
+
+        /// Print the watch statistics shown in the Statistics tab
+        Stats {
+            /// Print the statistics as json
+            #[clap(long)]
+            json: bool,
+        },
+
+        /// Open a troxide:// link, in the already running app if there is one
+        Open {
+            /// The link to open, like troxide://series/82
+            uri: String,
+        },
+    }
+
+    /// A series given either by its name or by its id
+    #[derive(Args)]
+    pub struct SeriesSelector {
+        /// Name of a tracked series, or its TVmaze id with --id
+        pub series: String,
+
+        /// Take the series as a TVmaze id, for unambiguous scripting
+        #[clap(long)]
+        pub id: bool,
     }
 }
@@ -0,0 +1,96 @@
+//! # Content filter
+//!
+//! Parental controls hiding series with unwanted content from Discover, search and
+//! recommendations. It is applied as a filter over the series information coming from
+//! the api, series already in the database are never hidden.
+//!
+//! TVmaze does not provide age ratings, so series are filtered by their genres.
+//! Changing the filter can be protected by a PIN.
+
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::passwords;
+use super::settings_config::{ContentFilterSettings, SETTINGS};
+
+/// The TVmaze genre of series with adult content
+pub const ADULT_GENRE: &str = "Adult";
+
+/// The TVmaze genres that can be hidden besides adult content
+pub const FILTERABLE_GENRES: [&str; 9] = [
+    "Horror",
+    "Thriller",
+    "Crime",
+    "War",
+    "Supernatural",
+    "Mystery",
+    "Espionage",
+    "Medical",
+    "Romance",
+];
+
+/// Whether the series should be hidden according to the content filter settings
+pub fn is_filtered(series_info: &SeriesMainInformation) -> bool {
+    let settings = SETTINGS.read().unwrap();
+    let content_filter = &settings.get_current_settings().content_filter;
+
+    series_info.genres.iter().any(|genre| {
+        (content_filter.hide_adult && genre == ADULT_GENRE)
+            || content_filter.hidden_genres.contains(genre)
+    })
+}
+
+/// Removes the series hidden by the content filter from the collection
+pub fn filter_series<T>(
+    series: impl IntoIterator<Item = T>,
+    series_info: impl Fn(&T) -> &SeriesMainInformation,
+) -> Vec<T> {
+    series
+        .into_iter()
+        .filter(|item| !is_filtered(series_info(item)))
+        .collect()
+}
+
+/// Whether the given PIN unlocks the content filter, always true when no PIN is set
+///
+/// A PIN hashed by an older version is hashed again once it is entered.
+pub fn unlock(pin: &str) -> bool {
+    let content_filter = SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .content_filter
+        .clone();
+
+    if !content_filter.verify_pin(pin) {
+        return false;
+    }
+
+    if content_filter
+        .pin_hash
+        .as_deref()
+        .is_some_and(passwords::needs_rehash)
+    {
+        SETTINGS
+            .write()
+            .unwrap()
+            .save_content_filter_pin_hash(passwords::hash(pin));
+    }
+    true
+}
+
+impl ContentFilterSettings {
+    pub fn is_pin_protected(&self) -> bool {
+        self.pin_hash.is_some()
+    }
+
+    /// Whether the given PIN unlocks the content filter, always true when no PIN is set
+    pub fn verify_pin(&self, pin: &str) -> bool {
+        self.pin_hash
+            .as_ref()
+            .map(|pin_hash| passwords::verify(pin, pin_hash))
+            .unwrap_or(true)
+    }
+
+    pub fn set_pin(&mut self, pin: &str) {
+        self.pin_hash = Some(passwords::hash(pin));
+    }
+}
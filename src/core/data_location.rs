@@ -0,0 +1,174 @@
+//! # Data location
+//!
+//! The database and the cache can be relocated through the settings. Moving them while the
+//! program runs would copy a database that is still being written to, so the new location is
+//! only recorded as a pending move. The files are copied on the next start, before the
+//! database and the cache are opened.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+use super::paths;
+use super::settings_config::SETTINGS;
+
+/// The relocatable directories of the program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataLocation {
+    /// The data directory holding the database
+    Database,
+    Cache,
+}
+
+impl DataLocation {
+    /// The directory currently in use
+    pub fn current_dir(&self) -> PathBuf {
+        let paths = paths::PATHS.read().expect("failed to read paths");
+        match self {
+            DataLocation::Database => paths.get_data_dir_path().to_path_buf(),
+            DataLocation::Cache => paths.get_cache_dir_path().to_path_buf(),
+        }
+    }
+
+    /// The directory the location moves to on the next start
+    pub fn pending_dir(&self) -> Option<PathBuf> {
+        let settings = SETTINGS.read().expect("failed to read settings");
+        let custom_paths = settings.get_current_settings().custom_paths.as_ref()?;
+        match self {
+            DataLocation::Database => custom_paths.pending_data_dir.clone(),
+            DataLocation::Cache => custom_paths.pending_cache_dir.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for DataLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataLocation::Database => write!(f, "Database"),
+            DataLocation::Cache => write!(f, "Cache"),
+        }
+    }
+}
+
+/// Records the destination the location is moved to on the next start
+pub async fn schedule_move(location: DataLocation, destination: PathBuf) -> anyhow::Result<()> {
+    let source = location.current_dir();
+
+    let destination = tokio::task::spawn_blocking(move || {
+        check_destination(&source, &destination).map(|_| destination)
+    })
+    .await
+    .context("failed to check the new location")??;
+
+    SETTINGS
+        .write()
+        .unwrap()
+        .save_pending_path(location, Some(destination));
+
+    Ok(())
+}
+
+/// Forgets about the pending move of the location
+pub fn cancel_move(location: DataLocation) {
+    SETTINGS.write().unwrap().save_pending_path(location, None);
+}
+
+/// Copies the files of the location to its pending destination and saves it as the new location
+///
+/// # Note
+/// This has to run before the database or the cache is opened. The old cache is removed once
+/// copied, the old database is kept in place in case anything went wrong.
+pub fn apply_pending_move(location: DataLocation) -> anyhow::Result<()> {
+    let Some(destination) = location.pending_dir() else {
+        return Ok(());
+    };
+
+    // The move is only tried once, a failed copy leaves the destination non empty anyway
+    SETTINGS.write().unwrap().save_pending_path(location, None);
+
+    let source = location.current_dir();
+    check_destination(&source, &destination)?;
+
+    tracing::info!(
+        "moving the {} from '{}' to '{}'",
+        location.to_string().to_lowercase(),
+        source.display(),
+        destination.display()
+    );
+
+    let files = list_files(&source).context("failed to list the files")?;
+
+    for (index, file) in files.iter().enumerate() {
+        let relative_path = file.strip_prefix(&source)?;
+        let new_file = destination.join(relative_path);
+
+        if let Some(parent) = new_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(file, &new_file)
+            .with_context(|| format!("failed to copy '{}'", file.display()))?;
+
+        tracing::debug!("copied {} / {} files", index + 1, files.len());
+    }
+
+    SETTINGS
+        .write()
+        .unwrap()
+        .save_custom_path(location, destination.clone());
+
+    let mut paths = paths::PATHS.write().expect("failed to write to paths");
+    match location {
+        DataLocation::Database => paths.set_data_dir_path(destination),
+        DataLocation::Cache => {
+            paths.set_cache_dir_path(destination);
+            if let Err(err) = std::fs::remove_dir_all(&source) {
+                tracing::error!("failed to remove the old cache: {}", err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_destination(source: &Path, destination: &Path) -> anyhow::Result<()> {
+    if destination.starts_with(source) {
+        bail!("the new location can not be inside the current one");
+    }
+    if is_non_empty_dir(destination)? {
+        bail!("the new location must be an empty folder");
+    }
+    Ok(())
+}
+
+fn is_non_empty_dir(path: &Path) -> anyhow::Result<bool> {
+    match std::fs::read_dir(path) {
+        Ok(mut entries) => Ok(entries.next().transpose()?.is_some()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Lists all the files under the directory recursively
+fn list_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    Ok(files)
+}
@@ -2,27 +2,159 @@ use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use sled::Db;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    mem::ManuallyDrop,
     ops::RangeInclusive,
+    sync::OnceLock,
 };
 use tracing::info;
 
+use super::advanced_search::SearchFilter;
+use super::hooks::{self, HookEvent};
 use super::{api::tv_maze::series_information::SeriesMainInformation, caching};
 use crate::core::paths;
+use crate::core::settings_config::SETTINGS;
+
+mod encryption;
+pub mod merge;
 
 // The last digit represents the version of the database.
 const DATABASE_FOLDER_NAME: &str = "series-troxide-db-1";
 
+/// Tree holding the user defined tags of the series, kept apart from the series themselves
+const SERIES_TAGS_TREE: &str = "series-tags";
+
+/// Tree holding the seasons left expanded in the series page of each series
+const EXPANDED_SEASONS_TREE: &str = "expanded-seasons";
+
+/// Tree holding how the seasons of each series are displayed
+///
+/// Stored as json rather than bincode so that new preferences can be added
+/// without invalidating the existing ones.
+const SEASON_PREFERENCES_TREE: &str = "season-preferences";
+
+/// Tree holding when each episode was last marked as watched or unwatched
+///
+/// Keyed by `series_id:season_number:episode_number`, used to resolve conflicts
+/// when merging watch data from other devices.
+const EPISODE_CHANGES_TREE: &str = "episode-changes";
+
+/// Tree holding the episodes of each series the user chose to skip
+///
+/// Skipped episodes count towards the progress of a series without being watched.
+const SKIPPED_EPISODES_TREE: &str = "skipped-episodes";
+
+/// Tree holding the watching status the user gave each series
+///
+/// Stored as json for the same reason as the season preferences, series without
+/// an entry are being watched.
+const SERIES_STATUS_TREE: &str = "series-status";
+
+/// Tree holding the series planned to be watched, ordered by priority
+///
+/// Series in it always have the plan to watch status.
+const PLAN_TO_WATCH_TREE: &str = "plan-to-watch";
+const PLAN_TO_WATCH_QUEUE_KEY: &str = "queue";
+
+/// Tree holding the people (actors, creators etc) followed by the user, keyed by their TVmaze id
+///
+/// Stored as json for the same reason as the season preferences.
+const FOLLOWED_PEOPLE_TREE: &str = "followed-people";
+
+/// Tree holding the notes the user wrote on episodes
+///
+/// Keyed the same way as the episode changes, the notes are stored as plain utf-8.
+const EPISODE_NOTES_TREE: &str = "episode-notes";
+
+/// Tree holding the ratings from 1 to 10 the user gave to episodes
+///
+/// Keyed the same way as the episode changes, the ratings are stored as a single byte.
+const EPISODE_RATINGS_TREE: &str = "episode-ratings";
+
+/// Tree holding what changed in the latest TVmaze update of each series, keyed by series id
+///
+/// Stored as json for the same reason as the season preferences.
+const SERIES_UPDATES_TREE: &str = "series-updates";
+
+/// Tree holding the series only notified about once their season finale airs, keyed by series id
+///
+/// The values are empty, being in the tree is what matters.
+const BINGE_MODE_TREE: &str = "binge-mode";
+
+/// Tree holding the binge plan of each series, keyed by series id
+///
+/// Stored as json for the same reason as the season preferences.
+const BINGE_PLANS_TREE: &str = "binge-plans";
+
+/// Tree holding the series whose episodes are marked watched as they air, keyed by series id
+///
+/// The values are the unix timestamps the option was turned on at, episodes aired before
+/// are left alone.
+const AUTO_MARK_AIRED_TREE: &str = "auto-mark-aired";
+
+/// Tree holding the episodes of each series that were marked watched automatically as they aired
+///
+/// Stored like the skipped episodes, an episode leaves it once it is marked by hand.
+const AUTO_MARKED_EPISODES_TREE: &str = "auto-marked-episodes";
+
+/// Tree holding the archived series, keyed by series id
+///
+/// Archived series keep counting in the statistics but are neither refreshed nor listed with
+/// the series being followed. The values are empty, being in the tree is what matters.
+const ARCHIVED_SERIES_TREE: &str = "archived-series";
+
+/// Tree holding the removed series that had watched episodes, keyed by series id
+///
+/// Stored as json for the same reason as the season preferences, kept for
+/// `REMOVED_SERIES_RETENTION_DAYS` so that they can be recovered.
+const RECENTLY_REMOVED_TREE: &str = "recently-removed";
+
+/// Tree holding the saved advanced searches, keyed by their name
+///
+/// Stored as json for the same reason as the season preferences.
+const SMART_LISTS_TREE: &str = "smart-lists";
+
+/// Tree holding the series TVmaze answered with 404 for, keyed by series id
+///
+/// Stored as json for the same reason as the season preferences, an entry is removed as
+/// soon as TVmaze knows the series again.
+const MISSING_SERIES_TREE: &str = "missing-series";
+
+/// How many daily checks in a row a series has to be missing on TVmaze to be offered
+/// for relinking, a single 404 can be a hiccup
+pub const MISSES_BEFORE_MISSING: u32 = 3;
+
+/// How long removed series can be recovered
+pub const REMOVED_SERIES_RETENTION_DAYS: i64 = 30;
+
+pub use encryption::OpenError;
+
+static DATABASE: OnceLock<Database> = OnceLock::new();
+
 lazy_static! {
-    pub static ref DB: Database = Database::init();
+    pub static ref DB: &'static Database =
+        open().unwrap_or_else(|err| panic!("failed to open the database: {}", err));
+}
+
+/// Opens the database, to be called before it is first used so that a database that can
+/// not be read is reported instead of panicking
+pub fn open() -> Result<&'static Database, OpenError> {
+    if let Some(database) = DATABASE.get() {
+        return Ok(database);
+    }
+    let database = Database::init()?;
+    Ok(DATABASE.get_or_init(|| database))
 }
 
 pub struct Database {
     db: Db,
+    /// The default tree of the database, holding the series
+    series: encryption::Tree,
+    cipher: Option<encryption::Cipher>,
 }
 
 impl Database {
-    fn init() -> Self {
+    fn init() -> Result<Self, OpenError> {
         let mut database_path = paths::PATHS
             .read()
             .expect("failed to read paths")
@@ -36,34 +168,156 @@ impl Database {
         if !db.was_recovered() {
             info!("created a fresh database as none was found");
         }
-        Self { db }
+
+        let encrypt = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .database
+            .encryption;
+        let cipher = encryption::open(&db, encrypt)?;
+        let series = encryption::Tree::new((*db).clone(), cipher.clone());
+
+        let database = Self { db, series, cipher };
+        database.purge_removed_series();
+        Ok(database)
     }
 
     /// Adds the given series to the database.
     ///
     /// # Note
-    /// This will overwrite any previous series with the same id.
+    /// This will overwrite any previous series with the same id. Nothing is written when
+    /// the series is unchanged, as series are written back every time they are dropped and
+    /// every write is seen by the watchers of the series changes.
     pub fn add_series(&self, series_id: u32, series: &Series) {
-        self.db
-            .insert(series_id.to_string(), bincode::serialize(series).unwrap())
+        let series_bytes = bincode::serialize(series).unwrap();
+
+        let is_unchanged = self
+            .series
+            .get(series_id.to_string())
+            .unwrap()
+            .is_some_and(|stored_series| stored_series == series_bytes);
+
+        if is_unchanged {
+            return;
+        }
+
+        self.series
+            .insert(series_id.to_string(), series_bytes)
             .unwrap();
     }
 
+    fn open_tree<V: AsRef<[u8]>>(&self, name: V) -> sled::Result<encryption::Tree> {
+        let tree = self.db.open_tree(name)?;
+        Ok(encryption::Tree::new(tree, self.cipher.clone()))
+    }
+
+    /// Whether the values of the database are encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Writes all the pending changes of the database to disk
+    pub fn flush(&self) {
+        self.db.flush().expect("flushing database");
+    }
+
+    /// Subscribes to the changes of the series, including their watched episodes
+    pub fn watch_series_changes(&self) -> sled::Subscriber {
+        self.db.watch_prefix([])
+    }
+
     /// Removes a series in the database.
     ///
     /// # Note
-    /// Does nothing when the series does not exist
+    /// Does nothing when the series does not exist. Series with watched episodes are kept
+    /// in the recently removed series so that they can be restored.
     pub fn remove_series(&self, series_id: u32) {
-        self.db.remove(series_id.to_string()).unwrap();
+        let Some(series_bytes) = self.series.remove(series_id.to_string()).unwrap() else {
+            return;
+        };
+
+        let Ok(series) = bincode::deserialize::<Series>(&series_bytes) else {
+            return;
+        };
+
+        let watched_episodes = series.get_total_episodes();
+        let series_name = series.get_name().to_owned();
+        // The removed series must not be written back
+        series.discard();
+
+        if watched_episodes == 0 {
+            return;
+        }
+
+        let removed_series = RemovedSeries {
+            series_id,
+            series_name,
+            watched_episodes,
+            removed_at: chrono::Utc::now().timestamp(),
+            series_bytes: series_bytes.to_vec(),
+        };
+
+        let removed_tree = self.open_tree(RECENTLY_REMOVED_TREE).unwrap();
+        removed_tree
+            .insert(
+                series_id.to_string(),
+                serde_json::to_vec(&removed_series).unwrap(),
+            )
+            .unwrap();
+    }
+
+    /// The recently removed series, the most recently removed first
+    pub fn get_removed_series(&self) -> Vec<RemovedSeries> {
+        let removed_tree = self.open_tree(RECENTLY_REMOVED_TREE).unwrap();
+        let mut removed_series: Vec<RemovedSeries> = removed_tree
+            .iter()
+            .values()
+            .filter_map(|removed_series| serde_json::from_slice(&removed_series.ok()?).ok())
+            .collect();
+        removed_series.sort_by_key(|removed_series| std::cmp::Reverse(removed_series.removed_at));
+        removed_series
+    }
+
+    /// Brings the removed series back, merging it when the series has been added again since
+    ///
+    /// Returns the amount of episodes that were newly watched by restoring.
+    pub fn restore_removed_series(&self, series_id: u32) -> Option<usize> {
+        let removed_tree = self.open_tree(RECENTLY_REMOVED_TREE).unwrap();
+        let removed_series: RemovedSeries =
+            serde_json::from_slice(&removed_tree.remove(series_id.to_string()).unwrap()?).ok()?;
+        let series: Series = bincode::deserialize(&removed_series.series_bytes).ok()?;
+
+        Some(merge::merge_series_into_db(series).newly_watched)
+    }
+
+    /// Forgets the removed series kept for longer than the retention time
+    fn purge_removed_series(&self) {
+        let oldest_kept =
+            chrono::Utc::now().timestamp() - REMOVED_SERIES_RETENTION_DAYS * 24 * 60 * 60;
+
+        let removed_tree = self.open_tree(RECENTLY_REMOVED_TREE).unwrap();
+        self.get_removed_series()
+            .into_iter()
+            .filter(|removed_series| removed_series.removed_at < oldest_kept)
+            .for_each(|removed_series| {
+                info!(
+                    "forgetting \"{}\" removed more than {} days ago",
+                    removed_series.series_name, REMOVED_SERIES_RETENTION_DAYS
+                );
+                removed_tree
+                    .remove(removed_series.series_id.to_string())
+                    .unwrap();
+            });
     }
 
     pub fn get_series(&self, series_id: u32) -> Option<Series> {
-        let series_bytes = self.db.get(series_id.to_string()).unwrap()?;
+        let series_bytes = self.series.get(series_id.to_string()).unwrap()?;
         Some(bincode::deserialize(&series_bytes).unwrap())
     }
 
     pub fn get_series_collection(&self) -> Vec<Series> {
-        self.db
+        self.series
             .iter()
             .values()
             .map(|series| {
@@ -74,7 +328,7 @@ impl Database {
     }
 
     pub fn get_series_id_collection(&self) -> Vec<String> {
-        self.db
+        self.series
             .iter()
             .keys()
             .map(|series| {
@@ -87,7 +341,7 @@ impl Database {
 
     /// get series ids and their corresponding series structures
     pub fn get_ids_and_series(&self) -> Vec<(String, Series)> {
-        self.db
+        self.series
             .iter()
             .map(|tup| {
                 let (series_id, series) = tup.unwrap();
@@ -100,7 +354,7 @@ impl Database {
 
     /// Returns the total number of series being tracked
     pub fn get_total_series(&self) -> usize {
-        self.db.len()
+        self.series.len()
     }
 
     /// Get the total amount of seasons watched across all
@@ -121,23 +375,1164 @@ impl Database {
             .sum()
     }
 
+    /// Returns the tags given to the series, sorted alphabetically
+    pub fn get_series_tags(&self, series_id: u32) -> Vec<String> {
+        let tags_tree = self.open_tree(SERIES_TAGS_TREE).unwrap();
+        tags_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .map(|tags| bincode::deserialize(&tags).unwrap())
+            .unwrap_or_default()
+    }
+
+    /// Gives the series the provided tag
+    ///
+    /// # Note
+    /// Does nothing when the series already has the tag
+    pub fn add_series_tag(&self, series_id: u32, tag: &str) {
+        let mut tags = self.get_series_tags(series_id);
+        if tags.iter().any(|existing_tag| existing_tag == tag) {
+            return;
+        }
+        tags.push(tag.to_owned());
+        tags.sort_unstable();
+
+        let tags_tree = self.open_tree(SERIES_TAGS_TREE).unwrap();
+        tags_tree
+            .insert(series_id.to_string(), bincode::serialize(&tags).unwrap())
+            .unwrap();
+    }
+
+    /// Returns the seasons that were left expanded in the series page
+    pub fn get_expanded_seasons(&self, series_id: u32) -> HashSet<u32> {
+        let expanded_seasons_tree = self.open_tree(EXPANDED_SEASONS_TREE).unwrap();
+        expanded_seasons_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .map(|seasons| bincode::deserialize(&seasons).unwrap())
+            .unwrap_or_default()
+    }
+
+    /// Records the seasons that are expanded in the series page
+    pub fn set_expanded_seasons(&self, series_id: u32, expanded_seasons: &HashSet<u32>) {
+        let expanded_seasons_tree = self.open_tree(EXPANDED_SEASONS_TREE).unwrap();
+        if expanded_seasons.is_empty() {
+            expanded_seasons_tree.remove(series_id.to_string()).unwrap();
+        } else {
+            expanded_seasons_tree
+                .insert(
+                    series_id.to_string(),
+                    bincode::serialize(expanded_seasons).unwrap(),
+                )
+                .unwrap();
+        }
+    }
+
+    pub fn get_season_preferences(&self, series_id: u32) -> SeasonPreferences {
+        let preferences_tree = self.open_tree(SEASON_PREFERENCES_TREE).unwrap();
+        preferences_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .and_then(|preferences| serde_json::from_slice(&preferences).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_season_preferences(&self, series_id: u32, preferences: &SeasonPreferences) {
+        let preferences_tree = self.open_tree(SEASON_PREFERENCES_TREE).unwrap();
+        preferences_tree
+            .insert(
+                series_id.to_string(),
+                serde_json::to_vec(preferences).unwrap(),
+            )
+            .unwrap();
+    }
+
+    /// Returns the skipped episodes of the series as season and episode numbers
+    pub fn get_skipped_episodes(&self, series_id: u32) -> HashSet<(u32, Episode)> {
+        let skipped_episodes_tree = self.open_tree(SKIPPED_EPISODES_TREE).unwrap();
+        skipped_episodes_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .map(|episodes| bincode::deserialize(&episodes).unwrap())
+            .unwrap_or_default()
+    }
+
+    pub fn is_episode_skipped(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+    ) -> bool {
+        self.get_skipped_episodes(series_id)
+            .contains(&(season_number, episode_number))
+    }
+
+    /// Returns the total amount of skipped episodes in the given season of the series
+    /// or in the whole series when no season is given
+    pub fn get_total_skipped_episodes(&self, series_id: u32, season_number: Option<u32>) -> usize {
+        self.get_skipped_episodes(series_id)
+            .iter()
+            .filter(|(season, _)| season_number.is_none_or(|number| number == *season))
+            .count()
+    }
+
+    /// Marks the given episode as skipped or not skipped
+    ///
+    /// # Note
+    /// Skipping does not unwatch the episode, that is left to the caller.
+    pub fn set_episode_skipped(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+        skipped: bool,
+    ) {
+        let mut skipped_episodes = self.get_skipped_episodes(series_id);
+        let changed = if skipped {
+            skipped_episodes.insert((season_number, episode_number))
+        } else {
+            skipped_episodes.remove(&(season_number, episode_number))
+        };
+
+        if !changed {
+            return;
+        }
+
+        let skipped_episodes_tree = self.open_tree(SKIPPED_EPISODES_TREE).unwrap();
+        if skipped_episodes.is_empty() {
+            skipped_episodes_tree.remove(series_id.to_string()).unwrap();
+        } else {
+            skipped_episodes_tree
+                .insert(
+                    series_id.to_string(),
+                    bincode::serialize(&skipped_episodes).unwrap(),
+                )
+                .unwrap();
+        }
+    }
+
+    /// Returns the total amount of skipped episodes across all the series
+    pub fn get_all_skipped_episodes_count(&self) -> usize {
+        let skipped_episodes_tree = self.open_tree(SKIPPED_EPISODES_TREE).unwrap();
+        skipped_episodes_tree
+            .iter()
+            .values()
+            .filter_map(|episodes| {
+                bincode::deserialize::<HashSet<(u32, Episode)>>(&episodes.ok()?).ok()
+            })
+            .map(|episodes| episodes.len())
+            .sum()
+    }
+
+    /// Unskips all the skipped episodes in the given season of the series
+    pub fn unskip_season(&self, series_id: u32, season_number: u32) {
+        self.get_skipped_episodes(series_id)
+            .into_iter()
+            .filter(|(season, _)| *season == season_number)
+            .for_each(|(season, episode)| {
+                self.set_episode_skipped(series_id, season, episode, false)
+            });
+    }
+
+    /// Unwatches and unskips all the episodes of the season, returning what they were so that
+    /// it can be restored
+    pub fn clear_season(&self, series_id: u32, season_number: u32) -> Option<ClearedSeason> {
+        let mut series = self.get_series(series_id)?;
+
+        let episode_changes: HashMap<Episode, EpisodeChange> = self
+            .get_episode_changes(series_id)
+            .into_iter()
+            .filter(|(season, _, _)| *season == season_number)
+            .map(|(_, episode, change)| (episode, change))
+            .collect();
+
+        let watched_episodes = series
+            .get_season(season_number)
+            .map(|season| {
+                season
+                    .get_episodes()
+                    .map(|episode| (episode, episode_changes.get(&episode).copied()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let skipped_episodes = self
+            .get_skipped_episodes(series_id)
+            .into_iter()
+            .filter(|(season, _)| *season == season_number)
+            .map(|(_, episode)| episode)
+            .collect();
+
+        series.remove_season(season_number);
+        self.unskip_season(series_id, season_number);
+
+        Some(ClearedSeason {
+            series_id,
+            season_number,
+            watched_episodes,
+            skipped_episodes,
+        })
+    }
+
+    /// Puts back the episodes of a cleared season as they were, watch times included
+    pub fn restore_cleared_season(&self, cleared_season: &ClearedSeason) {
+        let Some(mut series) = self.get_series(cleared_season.series_id) else {
+            return;
+        };
+
+        for (episode, episode_change) in cleared_season.watched_episodes.iter() {
+            series.add_episode_unchecked(cleared_season.season_number, *episode);
+            if let Some(episode_change) = episode_change {
+                self.set_episode_change(
+                    cleared_season.series_id,
+                    cleared_season.season_number,
+                    *episode,
+                    *episode_change,
+                );
+            }
+        }
+
+        for episode in cleared_season.skipped_episodes.iter() {
+            self.set_episode_skipped(
+                cleared_season.series_id,
+                cleared_season.season_number,
+                *episode,
+                true,
+            );
+        }
+    }
+
+    /// Records that the given episode has just been marked as watched or unwatched
+    ///
+    /// An episode marked as watched is no longer skipped, and a changed episode is no longer
+    /// taken as automatically marked.
+    pub fn record_episode_change(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+        watched: bool,
+    ) {
+        if watched {
+            self.set_episode_skipped(series_id, season_number, episode_number, false);
+        }
+        self.set_episode_auto_marked(series_id, season_number, episode_number, false);
+
+        let episode_change = EpisodeChange {
+            changed_at: chrono::Utc::now().timestamp_millis(),
+            watched,
+        };
+        self.set_episode_change(series_id, season_number, episode_number, episode_change);
+    }
+
+    pub fn set_episode_change(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+        episode_change: EpisodeChange,
+    ) {
+        let episode_changes_tree = self.open_tree(EPISODE_CHANGES_TREE).unwrap();
+        episode_changes_tree
+            .insert(
+                format!("{}:{}:{}", series_id, season_number, episode_number),
+                bincode::serialize(&episode_change).unwrap(),
+            )
+            .unwrap();
+    }
+
+    /// Returns the recorded changes of all the episodes of the given series
+    /// together with their season and episode numbers
+    pub fn get_episode_changes(&self, series_id: u32) -> Vec<(u32, Episode, EpisodeChange)> {
+        let episode_changes_tree = self.open_tree(EPISODE_CHANGES_TREE).unwrap();
+        episode_changes_tree
+            .scan_prefix(format!("{}:", series_id))
+            .filter_map(|entry| {
+                let (key, value) = entry.ok()?;
+                let key = String::from_utf8(key.to_vec()).ok()?;
+                let mut key_parts = key.split(':').skip(1);
+                let season_number = key_parts.next()?.parse().ok()?;
+                let episode_number = key_parts.next()?.parse().ok()?;
+                Some((
+                    season_number,
+                    episode_number,
+                    bincode::deserialize(&value).ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn get_series_status(&self, series_id: u32) -> SeriesStatus {
+        let status_tree = self.open_tree(SERIES_STATUS_TREE).unwrap();
+        status_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .and_then(|status| serde_json::from_slice(&status).ok())
+            .unwrap_or_default()
+    }
+
+    /// Sets the status of the series, adding it to the end of the plan to watch queue
+    /// when planned to be watched and removing it from the queue otherwise
+    pub fn set_series_status(&self, series_id: u32, status: SeriesStatus) {
+        let mut queue = self.get_plan_to_watch_queue();
+        let is_queued = queue.contains(&series_id);
+        if status == SeriesStatus::PlanToWatch && !is_queued {
+            queue.push(series_id);
+            self.set_plan_to_watch_queue(&queue);
+        } else if status != SeriesStatus::PlanToWatch && is_queued {
+            queue.retain(|queued_id| *queued_id != series_id);
+            self.set_plan_to_watch_queue(&queue);
+        }
+
+        let status_tree = self.open_tree(SERIES_STATUS_TREE).unwrap();
+        if status == SeriesStatus::default() {
+            status_tree.remove(series_id.to_string()).unwrap();
+        } else {
+            status_tree
+                .insert(series_id.to_string(), serde_json::to_vec(&status).unwrap())
+                .unwrap();
+        }
+    }
+
+    /// Returns the ids of the series given the provided status
+    ///
+    /// # Note
+    /// Does not work for the default status as series being watched have no entry.
+    pub fn get_series_ids_with_status(&self, status: SeriesStatus) -> Vec<u32> {
+        let status_tree = self.open_tree(SERIES_STATUS_TREE).unwrap();
+        status_tree
+            .iter()
+            .filter_map(|entry| {
+                let (key, value) = entry.ok()?;
+                let series_status: SeriesStatus = serde_json::from_slice(&value).ok()?;
+                (series_status == status)
+                    .then(|| String::from_utf8(key.to_vec()).ok()?.parse().ok())
+                    .flatten()
+            })
+            .collect()
+    }
+
+    /// Returns the ids of the series planned to be watched, from the highest priority
+    pub fn get_plan_to_watch_queue(&self) -> Vec<u32> {
+        let queue_tree = self.open_tree(PLAN_TO_WATCH_TREE).unwrap();
+        queue_tree
+            .get(PLAN_TO_WATCH_QUEUE_KEY)
+            .unwrap()
+            .map(|queue| bincode::deserialize(&queue).unwrap())
+            .unwrap_or_default()
+    }
+
+    fn set_plan_to_watch_queue(&self, queue: &[u32]) {
+        let queue_tree = self.open_tree(PLAN_TO_WATCH_TREE).unwrap();
+        queue_tree
+            .insert(PLAN_TO_WATCH_QUEUE_KEY, bincode::serialize(queue).unwrap())
+            .unwrap();
+    }
+
+    /// Moves the series one place up or down the plan to watch queue
+    ///
+    /// # Note
+    /// Does nothing when the series is not in the queue or can not move further.
+    pub fn move_in_plan_to_watch_queue(&self, series_id: u32, up: bool) {
+        let mut queue = self.get_plan_to_watch_queue();
+        let Some(position) = queue.iter().position(|queued_id| *queued_id == series_id) else {
+            return;
+        };
+
+        let new_position = if up {
+            position.checked_sub(1)
+        } else {
+            Some(position + 1).filter(|new_position| *new_position < queue.len())
+        };
+
+        if let Some(new_position) = new_position {
+            queue.swap(position, new_position);
+            self.set_plan_to_watch_queue(&queue);
+        }
+    }
+
+    pub fn get_followed_person(&self, person_id: u32) -> Option<FollowedPerson> {
+        let followed_people_tree = self.open_tree(FOLLOWED_PEOPLE_TREE).unwrap();
+        followed_people_tree
+            .get(person_id.to_string())
+            .unwrap()
+            .and_then(|person| serde_json::from_slice(&person).ok())
+    }
+
+    pub fn is_following_person(&self, person_id: u32) -> bool {
+        let followed_people_tree = self.open_tree(FOLLOWED_PEOPLE_TREE).unwrap();
+        followed_people_tree
+            .contains_key(person_id.to_string())
+            .unwrap()
+    }
+
+    pub fn get_followed_people(&self) -> Vec<FollowedPerson> {
+        let followed_people_tree = self.open_tree(FOLLOWED_PEOPLE_TREE).unwrap();
+        followed_people_tree
+            .iter()
+            .values()
+            .filter_map(|person| serde_json::from_slice(&person.ok()?).ok())
+            .collect()
+    }
+
+    /// Adds or updates the followed person
+    pub fn set_followed_person(&self, person: &FollowedPerson) {
+        let followed_people_tree = self.open_tree(FOLLOWED_PEOPLE_TREE).unwrap();
+        followed_people_tree
+            .insert(person.id.to_string(), serde_json::to_vec(person).unwrap())
+            .unwrap();
+    }
+
+    pub fn unfollow_person(&self, person_id: u32) {
+        let followed_people_tree = self.open_tree(FOLLOWED_PEOPLE_TREE).unwrap();
+        followed_people_tree.remove(person_id.to_string()).unwrap();
+    }
+
+    /// The latest updates of the series, the most recent first
+    pub fn get_series_updates(&self) -> Vec<SeriesUpdate> {
+        let series_updates_tree = self.open_tree(SERIES_UPDATES_TREE).unwrap();
+        let mut series_updates: Vec<SeriesUpdate> = series_updates_tree
+            .iter()
+            .values()
+            .filter_map(|series_update| serde_json::from_slice(&series_update.ok()?).ok())
+            .collect();
+        series_updates.sort_by_key(|series_update| std::cmp::Reverse(series_update.updated_at));
+        series_updates
+    }
+
+    /// Replaces the previous update of the series
+    pub fn set_series_update(&self, series_update: &SeriesUpdate) {
+        let series_updates_tree = self.open_tree(SERIES_UPDATES_TREE).unwrap();
+        series_updates_tree
+            .insert(
+                series_update.series_id.to_string(),
+                serde_json::to_vec(series_update).unwrap(),
+            )
+            .unwrap();
+    }
+
+    /// Whether the series is only notified about once a whole season is out
+    pub fn is_binge_mode(&self, series_id: u32) -> bool {
+        let binge_mode_tree = self.open_tree(BINGE_MODE_TREE).unwrap();
+        binge_mode_tree.contains_key(series_id.to_string()).unwrap()
+    }
+
+    pub fn set_binge_mode(&self, series_id: u32, binge_mode: bool) {
+        let binge_mode_tree = self.open_tree(BINGE_MODE_TREE).unwrap();
+        if binge_mode {
+            binge_mode_tree.insert(series_id.to_string(), &[]).unwrap();
+        } else {
+            binge_mode_tree.remove(series_id.to_string()).unwrap();
+        }
+    }
+
+    pub fn get_binge_plan(&self, series_id: u32) -> Option<BingePlan> {
+        let binge_plans_tree = self.open_tree(BINGE_PLANS_TREE).unwrap();
+        binge_plans_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .and_then(|binge_plan| serde_json::from_slice(&binge_plan).ok())
+    }
+
+    pub fn get_binge_plans(&self) -> Vec<BingePlan> {
+        let binge_plans_tree = self.open_tree(BINGE_PLANS_TREE).unwrap();
+        binge_plans_tree
+            .iter()
+            .values()
+            .filter_map(|binge_plan| serde_json::from_slice(&binge_plan.ok()?).ok())
+            .collect()
+    }
+
+    /// Replaces the previous binge plan of the series
+    pub fn set_binge_plan(&self, binge_plan: &BingePlan) {
+        let binge_plans_tree = self.open_tree(BINGE_PLANS_TREE).unwrap();
+        binge_plans_tree
+            .insert(
+                binge_plan.series_id.to_string(),
+                serde_json::to_vec(binge_plan).unwrap(),
+            )
+            .unwrap();
+    }
+
+    pub fn remove_binge_plan(&self, series_id: u32) {
+        let binge_plans_tree = self.open_tree(BINGE_PLANS_TREE).unwrap();
+        binge_plans_tree.remove(series_id.to_string()).unwrap();
+    }
+
+    /// Returns the smart lists ordered by name
+    pub fn get_smart_lists(&self) -> Vec<SmartList> {
+        let smart_lists_tree = self.open_tree(SMART_LISTS_TREE).unwrap();
+        smart_lists_tree
+            .iter()
+            .values()
+            .filter_map(|smart_list| serde_json::from_slice(&smart_list.ok()?).ok())
+            .collect()
+    }
+
+    pub fn get_smart_list(&self, name: &str) -> Option<SmartList> {
+        let smart_lists_tree = self.open_tree(SMART_LISTS_TREE).unwrap();
+        smart_lists_tree
+            .get(name)
+            .unwrap()
+            .and_then(|smart_list| serde_json::from_slice(&smart_list).ok())
+    }
+
+    /// Replaces the previous smart list with the same name
+    pub fn set_smart_list(&self, smart_list: &SmartList) {
+        let smart_lists_tree = self.open_tree(SMART_LISTS_TREE).unwrap();
+        smart_lists_tree
+            .insert(
+                smart_list.name.as_str(),
+                serde_json::to_vec(smart_list).unwrap(),
+            )
+            .unwrap();
+    }
+
+    pub fn remove_smart_list(&self, name: &str) {
+        let smart_lists_tree = self.open_tree(SMART_LISTS_TREE).unwrap();
+        smart_lists_tree.remove(name).unwrap();
+    }
+
+    /// Records that TVmaze answered with 404 for the series
+    pub fn record_series_miss(&self, series_id: u32, series_name: &str) {
+        let missing_series_tree = self.open_tree(MISSING_SERIES_TREE).unwrap();
+        let missing_series = missing_series_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .and_then(|missing_series| {
+                serde_json::from_slice::<MissingSeries>(&missing_series).ok()
+            })
+            .map(|missing_series| MissingSeries {
+                misses: missing_series.misses + 1,
+                ..missing_series
+            })
+            .unwrap_or_else(|| MissingSeries {
+                series_id,
+                series_name: series_name.to_owned(),
+                first_missed_at: chrono::Utc::now().timestamp(),
+                misses: 1,
+            });
+
+        missing_series_tree
+            .insert(
+                series_id.to_string(),
+                serde_json::to_vec(&missing_series).unwrap(),
+            )
+            .unwrap();
+    }
+
+    /// Forgets the recorded misses of the series, for when TVmaze knows it again
+    pub fn clear_series_misses(&self, series_id: u32) {
+        let missing_series_tree = self.open_tree(MISSING_SERIES_TREE).unwrap();
+        missing_series_tree.remove(series_id.to_string()).unwrap();
+    }
+
+    /// The series missing on TVmaze for at least `MISSES_BEFORE_MISSING` checks in a row
+    pub fn get_missing_series(&self) -> Vec<MissingSeries> {
+        let missing_series_tree = self.open_tree(MISSING_SERIES_TREE).unwrap();
+        missing_series_tree
+            .iter()
+            .values()
+            .filter_map(|missing_series| serde_json::from_slice(&missing_series.ok()?).ok())
+            .filter(|missing_series: &MissingSeries| missing_series.misses >= MISSES_BEFORE_MISSING)
+            .collect()
+    }
+
+    /// Moves what is recorded about the series besides it's watched episodes to another id
+    ///
+    /// What the other id already has recorded is kept. Binge plans and updates describe the
+    /// show of the old id and are dropped instead. The watched episodes are moved by merging,
+    /// see [`merge::merge_into_db`].
+    pub fn move_series_records(&self, old_id: u32, new_id: u32) {
+        let series_trees = [
+            SERIES_TAGS_TREE,
+            EXPANDED_SEASONS_TREE,
+            SEASON_PREFERENCES_TREE,
+            SKIPPED_EPISODES_TREE,
+            SERIES_STATUS_TREE,
+            BINGE_MODE_TREE,
+            AUTO_MARK_AIRED_TREE,
+            AUTO_MARKED_EPISODES_TREE,
+            ARCHIVED_SERIES_TREE,
+        ];
+
+        for tree_name in series_trees {
+            let tree = self.open_tree(tree_name).unwrap();
+            if let Some(value) = tree.remove(old_id.to_string()).unwrap() {
+                if !tree.contains_key(new_id.to_string()).unwrap() {
+                    tree.insert(new_id.to_string(), value).unwrap();
+                }
+            }
+        }
+
+        let old_prefix = format!("{}:", old_id);
+        let new_prefix = format!("{}:", new_id);
+        for tree_name in [EPISODE_NOTES_TREE, EPISODE_RATINGS_TREE] {
+            let tree = self.open_tree(tree_name).unwrap();
+            let entries: Vec<_> = tree
+                .scan_prefix(&old_prefix)
+                .filter_map(Result::ok)
+                .collect();
+            for (key, value) in entries {
+                tree.remove(&key).unwrap();
+                let new_key = [new_prefix.as_bytes(), &key[old_prefix.len()..]].concat();
+                if !tree.contains_key(&new_key).unwrap() {
+                    tree.insert(new_key, value).unwrap();
+                }
+            }
+        }
+
+        for tree_name in [BINGE_PLANS_TREE, SERIES_UPDATES_TREE] {
+            let tree = self.open_tree(tree_name).unwrap();
+            tree.remove(old_id.to_string()).unwrap();
+        }
+
+        let mut queue = self.get_plan_to_watch_queue();
+        if let Some(position) = queue.iter().position(|queued_id| *queued_id == old_id) {
+            if queue.contains(&new_id) {
+                queue.remove(position);
+            } else {
+                queue[position] = new_id;
+            }
+            self.set_plan_to_watch_queue(&queue);
+        }
+    }
+
+    pub fn is_archived(&self, series_id: u32) -> bool {
+        let archived_series_tree = self.open_tree(ARCHIVED_SERIES_TREE).unwrap();
+        archived_series_tree
+            .contains_key(series_id.to_string())
+            .unwrap()
+    }
+
+    pub fn set_archived(&self, series_id: u32, archived: bool) {
+        let archived_series_tree = self.open_tree(ARCHIVED_SERIES_TREE).unwrap();
+        if archived {
+            archived_series_tree
+                .insert(series_id.to_string(), &[])
+                .unwrap();
+        } else {
+            archived_series_tree.remove(series_id.to_string()).unwrap();
+        }
+    }
+
+    pub fn get_archived_series_ids(&self) -> HashSet<u32> {
+        let archived_series_tree = self.open_tree(ARCHIVED_SERIES_TREE).unwrap();
+        archived_series_tree
+            .iter()
+            .keys()
+            .filter_map(|series_id| String::from_utf8(series_id.ok()?.to_vec()).ok())
+            .filter_map(|series_id| series_id.parse().ok())
+            .collect()
+    }
+
+    /// Since when the aired episodes of the series are still to be marked watched, as a unix
+    /// timestamp
+    ///
+    /// Starts when marking them was turned on and moves forward every time they are marked.
+    pub fn get_auto_mark_aired_since(&self, series_id: u32) -> Option<i64> {
+        let auto_mark_aired_tree = self.open_tree(AUTO_MARK_AIRED_TREE).unwrap();
+        auto_mark_aired_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .and_then(|since| bincode::deserialize(&since).ok())
+    }
+
+    pub fn is_auto_mark_aired(&self, series_id: u32) -> bool {
+        self.get_auto_mark_aired_since(series_id).is_some()
+    }
+
+    /// The series marking their aired episodes watched and since when episodes are still to be
+    /// marked
+    pub fn get_auto_mark_aired_series(&self) -> Vec<(u32, i64)> {
+        let auto_mark_aired_tree = self.open_tree(AUTO_MARK_AIRED_TREE).unwrap();
+        auto_mark_aired_tree
+            .iter()
+            .filter_map(|entry| {
+                let (series_id, since) = entry.ok()?;
+                Some((
+                    String::from_utf8(series_id.to_vec()).ok()?.parse().ok()?,
+                    bincode::deserialize(&since).ok()?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Turns marking the episodes of the series watched as they air on or off
+    ///
+    /// Turning it on again keeps the original timestamp.
+    pub fn set_auto_mark_aired(&self, series_id: u32, auto_mark_aired: bool) {
+        let auto_mark_aired_tree = self.open_tree(AUTO_MARK_AIRED_TREE).unwrap();
+        if !auto_mark_aired {
+            auto_mark_aired_tree.remove(series_id.to_string()).unwrap();
+        } else if !self.is_auto_mark_aired(series_id) {
+            auto_mark_aired_tree
+                .insert(
+                    series_id.to_string(),
+                    bincode::serialize(&chrono::Utc::now().timestamp()).unwrap(),
+                )
+                .unwrap();
+        }
+    }
+
+    /// Records that the episodes of the series aired until the given unix timestamp were marked
+    ///
+    /// Nothing is recorded when marking them was turned off in the meantime.
+    pub fn advance_auto_mark_aired(&self, series_id: u32, until: i64) {
+        let auto_mark_aired_tree = self.open_tree(AUTO_MARK_AIRED_TREE).unwrap();
+        if self.is_auto_mark_aired(series_id) {
+            auto_mark_aired_tree
+                .insert(series_id.to_string(), bincode::serialize(&until).unwrap())
+                .unwrap();
+        }
+    }
+
+    /// Returns the automatically marked episodes of the series as season and episode numbers
+    pub fn get_auto_marked_episodes(&self, series_id: u32) -> HashSet<(u32, Episode)> {
+        let auto_marked_episodes_tree = self.open_tree(AUTO_MARKED_EPISODES_TREE).unwrap();
+        auto_marked_episodes_tree
+            .get(series_id.to_string())
+            .unwrap()
+            .map(|episodes| bincode::deserialize(&episodes).unwrap())
+            .unwrap_or_default()
+    }
+
+    /// Marks the given episode as watched automatically or not
+    ///
+    /// # Note
+    /// This only labels the episode, watching it is left to the caller.
+    pub fn set_episode_auto_marked(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+        auto_marked: bool,
+    ) {
+        let mut auto_marked_episodes = self.get_auto_marked_episodes(series_id);
+        let changed = if auto_marked {
+            auto_marked_episodes.insert((season_number, episode_number))
+        } else {
+            auto_marked_episodes.remove(&(season_number, episode_number))
+        };
+
+        if !changed {
+            return;
+        }
+
+        let auto_marked_episodes_tree = self.open_tree(AUTO_MARKED_EPISODES_TREE).unwrap();
+        if auto_marked_episodes.is_empty() {
+            auto_marked_episodes_tree
+                .remove(series_id.to_string())
+                .unwrap();
+        } else {
+            auto_marked_episodes_tree
+                .insert(
+                    series_id.to_string(),
+                    bincode::serialize(&auto_marked_episodes).unwrap(),
+                )
+                .unwrap();
+        }
+    }
+
+    /// Returns the total amount of automatically marked episodes across all the series
+    pub fn get_all_auto_marked_episodes_count(&self) -> usize {
+        let auto_marked_episodes_tree = self.open_tree(AUTO_MARKED_EPISODES_TREE).unwrap();
+        auto_marked_episodes_tree
+            .iter()
+            .values()
+            .filter_map(|episodes| {
+                bincode::deserialize::<HashSet<(u32, Episode)>>(&episodes.ok()?).ok()
+            })
+            .map(|episodes| episodes.len())
+            .sum()
+    }
+
+    pub fn get_episode_note(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+    ) -> Option<String> {
+        let episode_notes_tree = self.open_tree(EPISODE_NOTES_TREE).unwrap();
+        episode_notes_tree
+            .get(format!(
+                "{}:{}:{}",
+                series_id, season_number, episode_number
+            ))
+            .unwrap()
+            .and_then(|note| String::from_utf8(note.to_vec()).ok())
+    }
+
+    /// Saves the note of the given episode, a blank note removes it
+    pub fn set_episode_note(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+        note: &str,
+    ) {
+        let episode_notes_tree = self.open_tree(EPISODE_NOTES_TREE).unwrap();
+        let key = format!("{}:{}:{}", series_id, season_number, episode_number);
+        if note.trim().is_empty() {
+            episode_notes_tree.remove(key).unwrap();
+        } else {
+            episode_notes_tree.insert(key, note.as_bytes()).unwrap();
+        }
+    }
+
+    /// Returns the notes of all the episodes across all the series
+    pub fn get_all_episode_notes(&self) -> Vec<EpisodeNote> {
+        let episode_notes_tree = self.open_tree(EPISODE_NOTES_TREE).unwrap();
+        episode_notes_tree
+            .iter()
+            .filter_map(|entry| {
+                let (key, value) = entry.ok()?;
+                let key = String::from_utf8(key.to_vec()).ok()?;
+                let mut key_parts = key.split(':');
+                Some(EpisodeNote {
+                    series_id: key_parts.next()?.parse().ok()?,
+                    season_number: key_parts.next()?.parse().ok()?,
+                    episode_number: key_parts.next()?.parse().ok()?,
+                    note: String::from_utf8(value.to_vec()).ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the episode notes containing the given term, ignoring case
+    pub fn search_episode_notes(&self, term: &str) -> Vec<EpisodeNote> {
+        let term = term.to_lowercase();
+        self.get_all_episode_notes()
+            .into_iter()
+            .filter(|episode_note| episode_note.note.to_lowercase().contains(&term))
+            .collect()
+    }
+
+    pub fn get_episode_rating(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+    ) -> Option<u8> {
+        let episode_ratings_tree = self.open_tree(EPISODE_RATINGS_TREE).unwrap();
+        episode_ratings_tree
+            .get(format!(
+                "{}:{}:{}",
+                series_id, season_number, episode_number
+            ))
+            .unwrap()
+            .and_then(|rating| rating.first().copied())
+    }
+
+    /// Saves the rating of the given episode, `None` removes it
+    pub fn set_episode_rating(
+        &self,
+        series_id: u32,
+        season_number: u32,
+        episode_number: Episode,
+        rating: Option<u8>,
+    ) {
+        let episode_ratings_tree = self.open_tree(EPISODE_RATINGS_TREE).unwrap();
+        let key = format!("{}:{}:{}", series_id, season_number, episode_number);
+        if let Some(rating) = rating {
+            episode_ratings_tree.insert(key, &[rating]).unwrap();
+        } else {
+            episode_ratings_tree.remove(key).unwrap();
+        }
+    }
+
+    /// Returns the ratings of all the rated episodes of the given series
+    pub fn get_episode_ratings(&self, series_id: u32) -> Vec<EpisodeRating> {
+        let episode_ratings_tree = self.open_tree(EPISODE_RATINGS_TREE).unwrap();
+        episode_ratings_tree
+            .scan_prefix(format!("{}:", series_id))
+            .filter_map(|entry| parse_episode_rating(entry.ok()?))
+            .collect()
+    }
+
+    /// Returns the ratings of all the rated episodes across all the series
+    pub fn get_all_episode_ratings(&self) -> Vec<EpisodeRating> {
+        let episode_ratings_tree = self.open_tree(EPISODE_RATINGS_TREE).unwrap();
+        episode_ratings_tree
+            .iter()
+            .filter_map(|entry| parse_episode_rating(entry.ok()?))
+            .collect()
+    }
+
+    /// The average rating of the rated episodes of the series, of a single season when given
+    pub fn get_average_episode_rating(
+        &self,
+        series_id: u32,
+        season_number: Option<u32>,
+    ) -> Option<f32> {
+        let ratings: Vec<u8> = self
+            .get_episode_ratings(series_id)
+            .into_iter()
+            .filter(|episode_rating| {
+                season_number
+                    .is_none_or(|season_number| episode_rating.season_number == season_number)
+            })
+            .map(|episode_rating| episode_rating.rating)
+            .collect();
+
+        if ratings.is_empty() {
+            return None;
+        }
+
+        Some(ratings.iter().map(|rating| *rating as f32).sum::<f32>() / ratings.len() as f32)
+    }
+
     pub fn export(&self) -> database_transfer::TransferData {
         database_transfer::TransferData::new(self.get_series_collection())
+            .with_episode_notes(self.get_all_episode_notes())
+            .with_episode_ratings(self.get_all_episode_ratings())
     }
 
-    pub fn import(&self, transfer_data: &database_transfer::TransferData) {
-        for series in transfer_data.get_series() {
-            self.add_series(series.id, series);
+    /// Merges the series in the transfer data into the database
+    ///
+    /// Episode notes and ratings are only imported for episodes without one, see
+    /// [`merge::resolve_note`].
+    /// Returns the total amount of episodes whose watched state changed.
+    pub fn import(&self, mut transfer_data: database_transfer::TransferData) -> usize {
+        merge::merge_notes_into_db(transfer_data.take_episode_notes());
+
+        for episode_rating in transfer_data.take_episode_ratings() {
+            if self
+                .get_episode_rating(
+                    episode_rating.series_id,
+                    episode_rating.season_number,
+                    episode_rating.episode_number,
+                )
+                .is_none()
+            {
+                self.set_episode_rating(
+                    episode_rating.series_id,
+                    episode_rating.season_number,
+                    episode_rating.episode_number,
+                    Some(episode_rating.rating),
+                );
+            }
         }
+
+        let changed_episodes = transfer_data
+            .into_series()
+            .into_iter()
+            .map(|series| merge::merge_series_into_db(series).changed_episodes())
+            .sum();
         self.db.flush().expect("flushing database");
+        changed_episodes
+    }
+}
+
+/// How the seasons of a series are ordered in the series page
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SeasonOrdering {
+    /// The season numbers as provided by TVmaze
+    #[default]
+    SeasonNumber,
+    /// The airdate of the first episode of each season
+    Airdate,
+    /// Where the episodes of each season first appear in the DVD release known to TVmaze
+    Dvd,
+}
+
+impl SeasonOrdering {
+    pub const ALL: [Self; 3] = [Self::SeasonNumber, Self::Airdate, Self::Dvd];
+}
+
+impl std::fmt::Display for SeasonOrdering {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            SeasonOrdering::SeasonNumber => "Season number",
+            SeasonOrdering::Airdate => "Airdate",
+            SeasonOrdering::Dvd => "DVD",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// Where the user is at with a series, independently of it being tracked
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SeriesStatus {
+    #[default]
+    Watching,
+    OnHold,
+    Dropped,
+    PlanToWatch,
+    Completed,
+}
+
+impl SeriesStatus {
+    pub const ALL: [Self; 5] = [
+        Self::Watching,
+        Self::OnHold,
+        Self::Dropped,
+        Self::PlanToWatch,
+        Self::Completed,
+    ];
+}
+
+impl std::fmt::Display for SeriesStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            SeriesStatus::Watching => "Watching",
+            SeriesStatus::OnHold => "On hold",
+            SeriesStatus::Dropped => "Dropped",
+            SeriesStatus::PlanToWatch => "Plan to watch",
+            SeriesStatus::Completed => "Completed",
+        };
+        write!(f, "{}", str)
     }
 }
 
+/// A person followed by the user along with what is known about their credits
 #[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FollowedPerson {
+    pub id: u32,
+    pub name: String,
+    /// Ids of the series the person was known to be in the cast of when last checked
+    #[serde(default)]
+    pub known_series: HashSet<u32>,
+    /// Ids of the episodes the person was known to guest in when last checked
+    #[serde(default)]
+    pub known_episodes: HashSet<u32>,
+    /// Ids of the series the person joined since being followed, newest first
+    #[serde(default)]
+    pub new_series: Vec<u32>,
+}
+
+/// What changed in a series when TVmaze last updated it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeriesUpdate {
+    pub series_id: u32,
+    /// The TVmaze update timestamp in seconds
+    pub updated_at: i64,
+    /// Empty when nothing shown by series troxide changed
+    pub changes: Vec<SeriesChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum SeriesChange {
+    /// The amount of episodes added
+    NewEpisodes(usize),
+    ArtworkChanged,
+    StatusChanged {
+        from: String,
+        to: String,
+    },
+}
+
+/// A removed series that can still be restored
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemovedSeries {
+    pub series_id: u32,
+    pub series_name: String,
+    pub watched_episodes: usize,
+    /// The unix timestamp in seconds of the removal
+    pub removed_at: i64,
+    /// The series as it was stored, kept serialized as series are written back when dropped
+    series_bytes: Vec<u8>,
+}
+
+/// A series TVmaze answered with 404 for, like after the show was deleted or merged
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MissingSeries {
+    pub series_id: u32,
+    pub series_name: String,
+    /// The unix timestamp in seconds of the first miss
+    pub first_missed_at: i64,
+    /// How many checks in a row the series was missing for
+    pub misses: u32,
+}
+
+/// An advanced search saved under a name, evaluated again every time it's shown
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SmartList {
+    pub name: String,
+    /// Only the series whose name contains the term match, all of them when empty
+    pub term: String,
+    pub filter: SearchFilter,
+    pub source: SmartListSource,
+}
+
+/// Where the series of a smart list come from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmartListSource {
+    /// The TVmaze search results of the term together with the series currently airing
+    #[default]
+    Discover,
+    /// The series in My Shows
+    MyShows,
+    /// The series in My Shows with aired episodes left to watch
+    UnfinishedShows,
+}
+
+impl SmartListSource {
+    pub const ALL: [Self; 3] = [Self::Discover, Self::MyShows, Self::UnfinishedShows];
+}
+
+impl std::fmt::Display for SmartListSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            Self::Discover => "Search results",
+            Self::MyShows => "My shows",
+            Self::UnfinishedShows => "Unfinished shows",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
+/// The unwatched episodes of a series spread over suggested viewing days
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BingePlan {
+    pub series_id: u32,
+    pub series_name: String,
+    /// Only the days with episodes, in order
+    pub days: Vec<BingePlanDay>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BingePlanDay {
+    pub date: chrono::NaiveDate,
+    /// The season and episode numbers of the episodes to watch on the day
+    pub episodes: Vec<(u32, u32)>,
+}
+
+/// User preferences on how the seasons of a series are displayed
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SeasonPreferences {
+    #[serde(default)]
+    pub ordering: SeasonOrdering,
+    /// Custom labels of the seasons by their season numbers
+    #[serde(default)]
+    labels: HashMap<u32, String>,
+    /// Whether episodes are numbered from the start of the series instead of their season
+    #[serde(default)]
+    pub absolute_numbering: bool,
+}
+
+impl SeasonPreferences {
+    pub fn get_label(&self, season_number: u32) -> Option<&str> {
+        self.labels.get(&season_number).map(|label| label.as_str())
+    }
+
+    /// Sets the custom label of a season, an empty label restores the default one
+    pub fn set_label(&mut self, season_number: u32, label: String) {
+        if label.trim().is_empty() {
+            self.labels.remove(&season_number);
+        } else {
+            self.labels.insert(season_number, label.trim().to_owned());
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Series {
     id: u32,
     name: String,
     is_tracked: bool,
+    #[serde(serialize_with = "serialize_sorted_map")]
     seasons: HashMap<u32, Season>,
 }
 
@@ -201,7 +1596,11 @@ impl Series {
     }
 
     pub fn remove_season(&mut self, season_number: u32) {
-        self.seasons.remove(&season_number);
+        if let Some(season) = self.seasons.remove(&season_number) {
+            season.get_episodes().for_each(|episode| {
+                DB.record_episode_change(self.id, season_number, episode, false)
+            });
+        }
     }
 
     /// adds an episode into the series
@@ -212,13 +1611,18 @@ impl Series {
     /// tracks only when the supplied episode is watchable preventing allowing watched episodes that
     /// are released into the future.
     pub async fn add_episode(&mut self, season_number: u32, episode: Episode) -> bool {
-        loop {
+        let is_added = loop {
             if let Some(season) = self.seasons.get_mut(&season_number) {
                 break season.track_episode(self.id, season_number, episode).await;
             } else {
                 self.add_season(season_number);
             }
+        };
+
+        if is_added {
+            self.record_watched_episode(season_number, episode);
         }
+        is_added
     }
 
     /// adds an episode into the series
@@ -230,7 +1634,10 @@ impl Series {
     pub fn add_episode_unchecked(&mut self, season_number: u32, episode: Episode) {
         loop {
             if let Some(season) = self.seasons.get_mut(&season_number) {
-                season.track_episode_unchecked(episode);
+                if !season.is_episode_watched(episode) {
+                    season.track_episode_unchecked(episode);
+                    self.record_watched_episode(season_number, episode);
+                }
                 break;
             } else {
                 self.add_season(season_number);
@@ -243,21 +1650,94 @@ impl Series {
         season_number: u32,
         episodes_range: RangeInclusive<u32>,
     ) -> AddResult {
-        loop {
+        let previously_watched = self
+            .seasons
+            .get(&season_number)
+            .map(|season| season.episodes.clone())
+            .unwrap_or_default();
+
+        // Leaving skipped episodes skipped rather than watching them
+        let skipped_episodes = DB.get_skipped_episodes(self.id);
+        let episodes: Vec<Episode> = episodes_range
+            .filter(|episode| !skipped_episodes.contains(&(season_number, *episode)))
+            .collect();
+
+        let add_result = loop {
             if let Some(season) = self.seasons.get_mut(&season_number) {
                 break season
-                    .track_episodes(self.id, season_number, episodes_range)
+                    .track_episodes(self.id, season_number, &episodes)
                     .await;
             } else {
                 self.add_season(season_number);
             }
+        };
+
+        if let Some(season) = self.seasons.get(&season_number) {
+            season
+                .episodes
+                .difference(&previously_watched)
+                .for_each(|episode| self.record_watched_episode(season_number, *episode));
+        }
+        add_result
+    }
+
+    /// Watches the episodes of the season up to the given one, returning the newly watched ones
+    ///
+    /// Like [`Series::add_episodes`], skipped and unaired episodes are left as they are.
+    pub async fn add_episodes_up_to(
+        &mut self,
+        season_number: u32,
+        episode: Episode,
+    ) -> Vec<Episode> {
+        let previously_watched = self
+            .seasons
+            .get(&season_number)
+            .map(|season| season.episodes.clone())
+            .unwrap_or_default();
+
+        self.add_episodes(season_number, 1..=episode).await;
+
+        let mut newly_watched: Vec<Episode> = self
+            .seasons
+            .get(&season_number)
+            .map(|season| {
+                season
+                    .episodes
+                    .difference(&previously_watched)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+        newly_watched.sort_unstable();
+        newly_watched
+    }
+
+    /// Records the newly watched episode and runs the hooks for it, so that every way of
+    /// watching an episode is seen by them
+    fn record_watched_episode(&self, season_number: u32, episode: Episode) {
+        DB.record_episode_change(self.id, season_number, episode, true);
+        hooks::trigger(HookEvent::episode_watched(
+            self.id,
+            self.name.clone(),
+            season_number,
+            episode,
+        ));
+    }
+
+    /// removes the given episodes of the season from the series
+    pub fn remove_episodes(&mut self, season_number: u32, episodes: &[Episode]) {
+        for episode in episodes {
+            self.remove_episode(season_number, *episode);
         }
     }
 
     /// removes an episode from the series
     pub fn remove_episode(&mut self, season_number: u32, episode_number: Episode) {
         if let Some(season) = self.seasons.get_mut(&season_number) {
-            season.untrack_episode(episode_number)
+            if season.is_episode_watched(episode_number) {
+                season.untrack_episode(episode_number);
+                DB.record_episode_change(self.id, season_number, episode_number, false);
+            }
         }
     }
 
@@ -269,6 +1749,13 @@ impl Series {
         self.seasons.get_mut(&season_number)
     }
 
+    /// Returns all the seasons of the series together with their numbers
+    pub fn get_seasons(&self) -> impl Iterator<Item = (u32, &Season)> {
+        self.seasons
+            .iter()
+            .map(|(season_number, season)| (*season_number, season))
+    }
+
     /// Get the total amount of seasons tracked
     pub fn get_total_seasons(&self) -> usize {
         self.seasons.len()
@@ -310,6 +1797,17 @@ impl Series {
     }
 }
 
+impl Series {
+    /// Drops the series without writing it to the database, for series that are only
+    /// read from somewhere else
+    pub fn discard(self) {
+        let mut series = ManuallyDrop::new(self);
+        // Freeing what the series owns as its drop never runs
+        drop(std::mem::take(&mut series.name));
+        drop(std::mem::take(&mut series.seasons));
+    }
+}
+
 impl Drop for Series {
     fn drop(&mut self) {
         // Making sure database series is updated
@@ -324,11 +1822,32 @@ impl Drop for Series {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Season {
+    #[serde(serialize_with = "serialize_sorted_set")]
     episodes: HashSet<Episode>,
 }
 
+// Hash maps and sets are serialized in order so that an unchanged series always gives the
+// same bytes, which is how unchanged series are told apart when written to the database
+
+fn serialize_sorted_map<S, K, V>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
+
+fn serialize_sorted_set<S, T>(set: &HashSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: Serialize + Ord,
+{
+    serializer.collect_seq(set.iter().collect::<BTreeSet<_>>())
+}
+
 impl Season {
     pub fn new() -> Self {
         Self {
@@ -367,30 +1886,30 @@ impl Season {
         self.episodes.insert(episode_number);
     }
 
-    /// adds a range of episode to be tracked
+    /// adds the given episodes to be tracked
     ///
-    /// if all episodes in the range were newly added, true is returned. if atleast one episode was not newly
+    /// if all the episodes were newly added, true is returned. if atleast one episode was not newly
     /// added i.e. it existed already before adding, false is returned.
     pub async fn track_episodes(
         &mut self,
         series_id: u32,
         season_number: u32,
-        episodes_range: RangeInclusive<u32>,
+        episodes: &[Episode],
     ) -> AddResult {
         let mut already_added_items = 0;
-        for episode_number in episodes_range.clone() {
+        for episode_number in episodes {
             if !self
-                .track_episode(series_id, season_number, episode_number)
+                .track_episode(series_id, season_number, *episode_number)
                 .await
             {
                 already_added_items += 1;
             };
         }
 
-        if already_added_items == 0 {
-            AddResult::Full
-        } else if already_added_items == episodes_range.count() {
+        if already_added_items == episodes.len() {
             AddResult::None
+        } else if already_added_items == 0 {
+            AddResult::Full
         } else {
             AddResult::Partial
         }
@@ -415,6 +1934,11 @@ impl Season {
     pub fn get_total_episodes(&self) -> usize {
         self.episodes.len()
     }
+
+    /// Returns the watched episodes of the season in no particular order
+    pub fn get_episodes(&self) -> impl Iterator<Item = Episode> + '_ {
+        self.episodes.iter().copied()
+    }
 }
 
 impl Default for Season {
@@ -425,6 +1949,56 @@ impl Default for Season {
 
 pub type Episode = u32;
 
+/// The last time an episode was marked as watched or unwatched
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeChange {
+    /// Unix timestamp in milliseconds
+    pub changed_at: i64,
+    pub watched: bool,
+}
+
+/// The watch data a season had before it was cleared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClearedSeason {
+    pub series_id: u32,
+    pub season_number: u32,
+    /// The watched episodes with their last recorded change, when there is one
+    pub watched_episodes: Vec<(Episode, Option<EpisodeChange>)>,
+    pub skipped_episodes: Vec<Episode>,
+}
+
+/// A note the user wrote on an episode
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct EpisodeNote {
+    pub series_id: u32,
+    pub season_number: u32,
+    pub episode_number: Episode,
+    pub note: String,
+}
+
+/// A rating from 1 to 10 the user gave to an episode
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeRating {
+    pub series_id: u32,
+    pub season_number: u32,
+    pub episode_number: Episode,
+    pub rating: u8,
+}
+
+/// The ratings an episode can be given
+pub const EPISODE_RATINGS: [u8; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+fn parse_episode_rating((key, value): (sled::IVec, sled::IVec)) -> Option<EpisodeRating> {
+    let key = String::from_utf8(key.to_vec()).ok()?;
+    let mut key_parts = key.split(':');
+    Some(EpisodeRating {
+        series_id: key_parts.next()?.parse().ok()?,
+        season_number: key_parts.next()?.parse().ok()?,
+        episode_number: key_parts.next()?.parse().ok()?,
+        rating: *value.first()?,
+    })
+}
+
 /// Indicates if adding episodes has been fully added(when none of the episodes were present before adding) or
 /// partial(when some were already present) and none when all the added apisode where already present
 #[derive(Debug, Clone)]
@@ -442,8 +2016,8 @@ pub mod database_transfer {
 
     use std::{io, path};
 
-    use super::Series;
     use super::DB;
+    use super::{EpisodeNote, EpisodeRating, Series};
 
     use ron::ser;
     use serde::{Deserialize, Serialize};
@@ -465,6 +2039,12 @@ pub mod database_transfer {
     pub struct TransferData {
         version: u16,
         series: Vec<Series>,
+        /// Absent in data exported before episode notes existed
+        #[serde(default)]
+        episode_notes: Vec<EpisodeNote>,
+        /// Absent in data exported before episode ratings existed
+        #[serde(default)]
+        episode_ratings: Vec<EpisodeRating>,
     }
 
     impl TransferData {
@@ -472,9 +2052,29 @@ pub mod database_transfer {
             Self {
                 version: CURRENT_DATA_VERSION,
                 series,
+                episode_notes: vec![],
+                episode_ratings: vec![],
             }
         }
 
+        pub fn with_episode_notes(mut self, episode_notes: Vec<EpisodeNote>) -> Self {
+            self.episode_notes = episode_notes;
+            self
+        }
+
+        pub fn take_episode_notes(&mut self) -> Vec<EpisodeNote> {
+            std::mem::take(&mut self.episode_notes)
+        }
+
+        pub fn with_episode_ratings(mut self, episode_ratings: Vec<EpisodeRating>) -> Self {
+            self.episode_ratings = episode_ratings;
+            self
+        }
+
+        pub fn take_episode_ratings(&mut self) -> Vec<EpisodeRating> {
+            std::mem::take(&mut self.episode_ratings)
+        }
+
         fn error_when_incompatible(import_data_version: u16) -> Result<(), ImportError> {
             if import_data_version == CURRENT_DATA_VERSION {
                 Ok(())
@@ -486,16 +2086,21 @@ pub mod database_transfer {
             }
         }
 
-        pub fn blocking_import(path: impl AsRef<path::Path>) -> Result<Self, ImportError> {
-            let import = std::fs::read_to_string(path).map_err(ImportError::Io)?;
+        /// Parses transfer data from it's ron representation
+        pub fn from_ron_str(ron_str: &str) -> Result<Self, ImportError> {
             let imported_data =
-                ron::from_str::<Self>(&import).map_err(ImportError::Deserialization)?;
+                ron::from_str::<Self>(ron_str).map_err(ImportError::Deserialization)?;
 
             Self::error_when_incompatible(imported_data.version).map(|_| imported_data)
         }
 
+        pub fn blocking_import(path: impl AsRef<path::Path>) -> Result<Self, ImportError> {
+            let import = std::fs::read_to_string(path).map_err(ImportError::Io)?;
+            Self::from_ron_str(&import)
+        }
+
         pub fn blocking_import_to_db(path: impl AsRef<path::Path>) -> Result<(), ImportError> {
-            DB.import(&Self::blocking_import(path)?);
+            DB.import(Self::blocking_import(path)?);
             Ok(())
         }
 
@@ -503,14 +2108,11 @@ pub mod database_transfer {
             let import = tokio::fs::read_to_string(path)
                 .await
                 .map_err(ImportError::Io)?;
-            let imported_data =
-                ron::from_str::<Self>(&import).map_err(ImportError::Deserialization)?;
-
-            Self::error_when_incompatible(imported_data.version).map(|_| imported_data)
+            Self::from_ron_str(&import)
         }
 
         pub async fn async_import_to_db(path: impl AsRef<path::Path>) -> Result<(), ImportError> {
-            DB.import(&Self::async_import(path).await?);
+            DB.import(Self::async_import(path).await?);
             Ok(())
         }
 
@@ -518,7 +2120,11 @@ pub mod database_transfer {
             &self.series
         }
 
-        fn ron_str(&self) -> String {
+        pub fn into_series(self) -> Vec<Series> {
+            self.series
+        }
+
+        pub fn ron_str(&self) -> String {
             let pretty_config = ser::PrettyConfig::new().depth_limit(4);
             ser::to_string_pretty(self, pretty_config).expect("transfer data serialization")
         }
@@ -542,3 +2148,34 @@ pub mod database_transfer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_encrypted_database() -> Database {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let cipher = Some(encryption::Cipher::new("passphrase", &[0; 16]));
+        let series = encryption::Tree::new((*db).clone(), cipher.clone());
+        Database { db, series, cipher }
+    }
+
+    #[test]
+    fn unchanged_series_is_not_rewritten_when_encrypted() {
+        let database = temporary_encrypted_database();
+        let series = Series::new("Series".to_owned(), 1);
+
+        // What dropping the series does, against this database instead of the global one
+        database.add_series(series.id, &series);
+        let stored_series = database.db.get("1").unwrap().unwrap();
+        let mut subscriber = database.db.watch_prefix("1");
+
+        database.add_series(series.id, &series);
+
+        assert_eq!(database.db.get("1").unwrap().unwrap(), stored_series);
+        assert!(subscriber
+            .next_timeout(std::time::Duration::from_millis(50))
+            .is_err());
+        series.discard();
+    }
+}
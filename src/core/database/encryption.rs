@@ -0,0 +1,495 @@
+//! # Database encryption
+//!
+//! When turned on in the settings every value of the database is encrypted on its own with
+//! AES-256-GCM. The keys stay in plain text so that they keep their order and can still be
+//! scanned by prefix, they are only series ids and episode numbers. The encryption key is
+//! derived with Argon2 from a random passphrase kept in the keyring of the OS.
+//!
+//! Turning the encryption on or off rewrites the values of every tree on the next start. Each
+//! tree is rewritten in a single transaction along with a marker of its new state, so that an
+//! interrupted rewrite picks up where it stopped.
+
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use rand::RngCore;
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+use sled::{Db, IVec, Transactional};
+use thiserror::Error;
+use tracing::{error, info};
+
+use crate::core::secrets::{self, Secret};
+
+/// Tree holding the state of the encryption, its values are never encrypted
+const META_TREE: &str = "encryption";
+
+/// Salt of the key derivation, generated once per database
+const SALT_KEY: &str = "salt";
+
+/// Known value encrypted with the key of the database, only present when it is encrypted
+///
+/// Used to tell a wrong passphrase apart from corrupted values.
+const CHECK_KEY: &str = "check";
+const CHECK_VALUE: &[u8] = b"series-troxide";
+
+/// Prefix of the markers of the trees already rewritten by an unfinished migration
+const MIGRATED_PREFIX: &str = "migrated:";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// The key the values of the database are encrypted with
+#[derive(Clone)]
+pub struct Cipher(Arc<Aes256Gcm>);
+
+impl Cipher {
+    pub(super) fn new(passphrase: &str, salt: &[u8]) -> Self {
+        let mut key = [0; KEY_LEN];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .expect("the salt and key lengths are valid");
+
+        Self(Arc::new(
+            Aes256Gcm::new_from_slice(&key).expect("the key length is valid"),
+        ))
+    }
+
+    /// Encrypts the value, binding it to its tree and key so that it can not be moved around
+    fn seal(&self, tree_name: &[u8], key: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut nonce = [0; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let aad = associated_data(tree_name, key);
+        let ciphertext = self
+            .0
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: value,
+                    aad: &aad,
+                },
+            )
+            .expect("encrypting in memory does not fail");
+
+        [&nonce[..], &ciphertext].concat()
+    }
+
+    fn open(&self, tree_name: &[u8], key: &[u8], sealed_value: &[u8]) -> Option<Vec<u8>> {
+        if sealed_value.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = sealed_value.split_at(NONCE_LEN);
+
+        let aad = associated_data(tree_name, key);
+        self.0
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .ok()
+    }
+}
+
+fn associated_data(tree_name: &[u8], key: &[u8]) -> Vec<u8> {
+    [tree_name, &[0], key].concat()
+}
+
+/// A sled tree encrypting its values when the database is encrypted
+///
+/// Mirrors the parts of the sled api used by the database.
+#[derive(Clone)]
+pub struct Tree {
+    tree: sled::Tree,
+    cipher: Option<Cipher>,
+}
+
+impl Tree {
+    pub fn new(tree: sled::Tree, cipher: Option<Cipher>) -> Self {
+        Self { tree, cipher }
+    }
+
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> sled::Result<Option<IVec>> {
+        self.tree
+            .get(&key)?
+            .map(|value| self.decrypt(key.as_ref(), value))
+            .transpose()
+    }
+
+    pub fn insert<K: AsRef<[u8]>, V: Into<IVec>>(
+        &self,
+        key: K,
+        value: V,
+    ) -> sled::Result<Option<IVec>> {
+        let value = self.encrypt(key.as_ref(), value.into());
+        self.tree
+            .insert(&key, value)?
+            .map(|old_value| self.decrypt(key.as_ref(), old_value))
+            .transpose()
+    }
+
+    pub fn remove<K: AsRef<[u8]>>(&self, key: K) -> sled::Result<Option<IVec>> {
+        self.tree
+            .remove(&key)?
+            .map(|old_value| self.decrypt(key.as_ref(), old_value))
+            .transpose()
+    }
+
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> sled::Result<bool> {
+        self.tree.contains_key(key)
+    }
+
+    pub fn iter(&self) -> Iter {
+        Iter {
+            iter: self.tree.iter(),
+            tree: self.clone(),
+        }
+    }
+
+    pub fn scan_prefix<P: AsRef<[u8]>>(&self, prefix: P) -> Iter {
+        Iter {
+            iter: self.tree.scan_prefix(prefix),
+            tree: self.clone(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    fn encrypt(&self, key: &[u8], value: IVec) -> IVec {
+        match &self.cipher {
+            Some(cipher) => cipher.seal(&self.tree.name(), key, &value).into(),
+            None => value,
+        }
+    }
+
+    fn decrypt(&self, key: &[u8], value: IVec) -> sled::Result<IVec> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(value);
+        };
+
+        match cipher.open(&self.tree.name(), key, &value) {
+            Some(value) => Ok(value.into()),
+            None => {
+                error!(
+                    "failed to decrypt '{}' in the '{}' tree",
+                    String::from_utf8_lossy(key),
+                    String::from_utf8_lossy(&self.tree.name())
+                );
+                Err(sled::Error::Corruption { at: None, bt: () })
+            }
+        }
+    }
+}
+
+/// Iterator over the decrypted entries of a `Tree`
+pub struct Iter {
+    iter: sled::Iter,
+    tree: Tree,
+}
+
+impl Iter {
+    pub fn keys(self) -> impl DoubleEndedIterator<Item = sled::Result<IVec>> {
+        // The keys are not encrypted, no need to decrypt the values
+        self.iter.keys()
+    }
+
+    pub fn values(self) -> impl DoubleEndedIterator<Item = sled::Result<IVec>> {
+        self.map(|entry| entry.map(|(_, value)| value))
+    }
+
+    fn decrypt_entry(&self, entry: sled::Result<(IVec, IVec)>) -> sled::Result<(IVec, IVec)> {
+        let (key, value) = entry?;
+        let value = self.tree.decrypt(&key, value)?;
+        Ok((key, value))
+    }
+}
+
+impl Iterator for Iter {
+    type Item = sled::Result<(IVec, IVec)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.iter.next()?;
+        Some(self.decrypt_entry(entry))
+    }
+}
+
+impl DoubleEndedIterator for Iter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let entry = self.iter.next_back()?;
+        Some(self.decrypt_entry(entry))
+    }
+}
+
+/// Why the database could not be opened
+#[derive(Debug, Error)]
+pub enum OpenError {
+    #[error("the database is encrypted but its passphrase is missing from the keyring, the keyring may be locked")]
+    MissingPassphrase,
+    #[error("the database passphrase in the keyring does not match the database")]
+    WrongPassphrase,
+    #[error("failed to bring the database to its encryption state: {0}")]
+    Storage(#[from] sled::Error),
+}
+
+/// Brings the database to the wanted encryption state, returning the cipher its values are
+/// encrypted with
+///
+/// Fails when the database is encrypted and the passphrase in the keyring is missing or
+/// wrong, as nothing can be read from it then.
+pub fn open(db: &Db, encrypt: bool) -> Result<Option<Cipher>, OpenError> {
+    let meta = db.open_tree(META_TREE)?;
+
+    let is_encrypted = meta.contains_key(CHECK_KEY)?;
+    let is_migrating = meta.scan_prefix(MIGRATED_PREFIX).next().is_some();
+
+    if !encrypt && !is_encrypted && !is_migrating {
+        return Ok(None);
+    }
+
+    let passphrase = match secrets::get(Secret::DatabasePassphrase) {
+        Some(passphrase) => passphrase,
+        None if is_encrypted || is_migrating => return Err(OpenError::MissingPassphrase),
+        None => match generate_passphrase() {
+            Some(passphrase) => passphrase,
+            None => {
+                error!("no keyring to keep the database passphrase in, leaving it unencrypted");
+                return Ok(None);
+            }
+        },
+    };
+
+    let salt = match meta.get(SALT_KEY)? {
+        Some(salt) => salt,
+        None => {
+            let mut salt = [0; SALT_LEN];
+            rand::thread_rng().fill_bytes(&mut salt);
+            meta.insert(SALT_KEY, &salt[..])?;
+            IVec::from(&salt)
+        }
+    };
+
+    let cipher = Cipher::new(&passphrase, &salt);
+
+    if let Some(check) = meta.get(CHECK_KEY)? {
+        if cipher.open(META_TREE.as_bytes(), CHECK_KEY.as_bytes(), &check)
+            != Some(CHECK_VALUE.to_vec())
+        {
+            return Err(OpenError::WrongPassphrase);
+        }
+    }
+
+    migrate(db, &cipher, encrypt)?;
+
+    if encrypt {
+        Ok(Some(cipher))
+    } else {
+        secrets::set(Secret::DatabasePassphrase, "");
+        Ok(None)
+    }
+}
+
+/// Generates and stores a new passphrase, `None` when it could not be stored
+fn generate_passphrase() -> Option<String> {
+    let mut passphrase = [0; KEY_LEN];
+    rand::thread_rng().fill_bytes(&mut passphrase);
+    let passphrase: String = passphrase
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    secrets::set(Secret::DatabasePassphrase, &passphrase);
+
+    // Reading it back, a passphrase that is not kept would lock the database for good
+    secrets::get(Secret::DatabasePassphrase).filter(|stored| *stored == passphrase)
+}
+
+/// Encrypts or decrypts the values of every tree not in the wanted state yet
+fn migrate(db: &Db, cipher: &Cipher, encrypt: bool) -> sled::Result<()> {
+    let meta = db.open_tree(META_TREE)?;
+    let is_encrypted = meta.contains_key(CHECK_KEY)?;
+
+    for tree_name in db.tree_names() {
+        if tree_name == META_TREE.as_bytes() {
+            continue;
+        }
+
+        let marker = [MIGRATED_PREFIX.as_bytes(), &tree_name].concat();
+        let is_migrated = meta.contains_key(&marker)?;
+        // Migrated trees are in the opposite state of the database until the migration ends
+        let is_tree_encrypted = is_encrypted != is_migrated;
+        if is_tree_encrypted == encrypt {
+            continue;
+        }
+
+        info!(
+            "{} the '{}' tree of the database",
+            if encrypt { "encrypting" } else { "decrypting" },
+            String::from_utf8_lossy(&tree_name)
+        );
+
+        let raw_tree = db.open_tree(&tree_name)?;
+        let from = Tree::new(raw_tree.clone(), (!encrypt).then(|| cipher.clone()));
+        let to = Tree::new(raw_tree.clone(), encrypt.then(|| cipher.clone()));
+
+        let values = from
+            .iter()
+            .map(|entry| {
+                let (key, value) = entry?;
+                let value = to.encrypt(&key, value);
+                Ok((key, value))
+            })
+            .collect::<sled::Result<Vec<_>>>()?;
+
+        (&raw_tree, &meta)
+            .transaction(|(tree, meta)| {
+                for (key, value) in &values {
+                    tree.insert(key, value)?;
+                }
+                if is_migrated {
+                    meta.remove(marker.as_slice())?;
+                } else {
+                    meta.insert(marker.as_slice(), IVec::default())?;
+                }
+                Ok::<_, ConflictableTransactionError<()>>(())
+            })
+            .map_err(unwrap_transaction_error)?;
+    }
+
+    if is_encrypted == encrypt {
+        return Ok(());
+    }
+
+    // Every tree is in the wanted state, the database as a whole can switch to it
+    let markers = meta
+        .scan_prefix(MIGRATED_PREFIX)
+        .keys()
+        .collect::<sled::Result<Vec<_>>>()?;
+    let check = cipher.seal(META_TREE.as_bytes(), CHECK_KEY.as_bytes(), CHECK_VALUE);
+
+    meta.transaction(|meta| {
+        if encrypt {
+            meta.insert(CHECK_KEY, check.as_slice())?;
+        } else {
+            meta.remove(CHECK_KEY)?;
+        }
+        for marker in &markers {
+            meta.remove(marker)?;
+        }
+        Ok::<_, ConflictableTransactionError<()>>(())
+    })
+    .map_err(unwrap_transaction_error)?;
+
+    meta.flush()?;
+    Ok(())
+}
+
+fn unwrap_transaction_error(err: TransactionError<()>) -> sled::Error {
+    match err {
+        TransactionError::Storage(err) => err,
+        TransactionError::Abort(()) => unreachable!("the migration never aborts"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_db() -> Db {
+        sled::Config::new().temporary(true).open().unwrap()
+    }
+
+    fn fill(db: &Db) {
+        db.insert("1", "series").unwrap();
+        let tree = db.open_tree("episode-notes").unwrap();
+        tree.insert("1:1:1", "a note").unwrap();
+        tree.insert("1:1:2", "").unwrap();
+    }
+
+    #[test]
+    fn tree_round_trip() {
+        let db = temporary_db();
+        let cipher = Cipher::new("passphrase", &[0; SALT_LEN]);
+        let tree = Tree::new(db.open_tree("tree").unwrap(), Some(cipher));
+
+        tree.insert("key", "value").unwrap();
+
+        assert_eq!(tree.get("key").unwrap().unwrap(), "value");
+        assert_ne!(
+            db.open_tree("tree").unwrap().get("key").unwrap().unwrap(),
+            "value"
+        );
+        assert_eq!(
+            tree.scan_prefix("k").values().next().unwrap().unwrap(),
+            "value"
+        );
+        assert_eq!(tree.remove("key").unwrap().unwrap(), "value");
+    }
+
+    #[test]
+    fn values_can_not_be_moved_between_keys() {
+        let db = temporary_db();
+        let cipher = Cipher::new("passphrase", &[0; SALT_LEN]);
+        let raw_tree = db.open_tree("tree").unwrap();
+        let tree = Tree::new(raw_tree.clone(), Some(cipher));
+
+        tree.insert("key", "value").unwrap();
+        let sealed_value = raw_tree.get("key").unwrap().unwrap();
+        raw_tree.insert("other-key", sealed_value).unwrap();
+
+        assert!(tree.get("other-key").is_err());
+    }
+
+    #[test]
+    fn migration_encrypts_and_decrypts_every_tree() {
+        let db = temporary_db();
+        fill(&db);
+        let cipher = Cipher::new("passphrase", &[0; SALT_LEN]);
+
+        migrate(&db, &cipher, true).unwrap();
+
+        let meta = db.open_tree(META_TREE).unwrap();
+        assert!(meta.contains_key(CHECK_KEY).unwrap());
+        assert!(meta.scan_prefix(MIGRATED_PREFIX).next().is_none());
+        assert_ne!(db.get("1").unwrap().unwrap(), "series");
+        let notes = Tree::new(db.open_tree("episode-notes").unwrap(), Some(cipher.clone()));
+        assert_eq!(notes.get("1:1:1").unwrap().unwrap(), "a note");
+        assert_eq!(notes.get("1:1:2").unwrap().unwrap(), "");
+
+        migrate(&db, &cipher, false).unwrap();
+
+        assert!(!meta.contains_key(CHECK_KEY).unwrap());
+        assert_eq!(db.get("1").unwrap().unwrap(), "series");
+        let notes = db.open_tree("episode-notes").unwrap();
+        assert_eq!(notes.get("1:1:1").unwrap().unwrap(), "a note");
+    }
+
+    #[test]
+    fn interrupted_migration_is_resumed() {
+        let db = temporary_db();
+        fill(&db);
+        let cipher = Cipher::new("passphrase", &[0; SALT_LEN]);
+
+        // The notes were encrypted before the migration got interrupted
+        let meta = db.open_tree(META_TREE).unwrap();
+        let notes = db.open_tree("episode-notes").unwrap();
+        for (key, value) in notes.iter().map(Result::unwrap).collect::<Vec<_>>() {
+            let value = cipher.seal(b"episode-notes", &key, &value);
+            notes.insert(key, value).unwrap();
+        }
+        meta.insert(format!("{}episode-notes", MIGRATED_PREFIX), IVec::default())
+            .unwrap();
+
+        migrate(&db, &cipher, true).unwrap();
+
+        let notes = Tree::new(notes, Some(cipher.clone()));
+        assert_eq!(notes.get("1:1:1").unwrap().unwrap(), "a note");
+        let series = Tree::new(db.open_tree(db.name()).unwrap(), Some(cipher));
+        assert_eq!(series.get("1").unwrap().unwrap(), "series");
+    }
+}
@@ -0,0 +1,348 @@
+//! # Merging of series records
+//!
+//! Deterministic merging of series records coming from elsewhere (Trakt, another device,
+//! an import file) into the database, used instead of overwriting so that no progress
+//! made on either side is lost.
+//!
+//! - The series is tracked when either side tracks it.
+//! - Watched episodes are the union of both sides, unless one side has a later recorded
+//!   change marking the episode as unwatched.
+//! - When both sides changed an episode, the change with the latest timestamp wins and
+//!   watched wins when both happened at the same time.
+//! - Episode notes are kept from the side that has a non-empty one, the local note wins
+//!   when both sides wrote one.
+
+use std::collections::HashMap;
+
+use super::{Episode, EpisodeChange, EpisodeNote, Series, DB};
+
+/// Episodes watched before changes were recorded are treated as watched at the
+/// beginning of time so that any recorded change wins over them.
+const WATCHED_BEFORE_RECORDING: EpisodeChange = EpisodeChange {
+    changed_at: 0,
+    watched: true,
+};
+
+/// The watch state of episodes keyed by their season and episode numbers
+pub type EpisodeStates = HashMap<(u32, Episode), EpisodeChange>;
+
+/// What merging changed in the database
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MergeOutcome {
+    pub newly_watched: usize,
+    pub newly_unwatched: usize,
+}
+
+impl MergeOutcome {
+    pub fn changed_episodes(&self) -> usize {
+        self.newly_watched + self.newly_unwatched
+    }
+}
+
+/// Combines the watched episodes of the series with their recorded changes
+///
+/// Recorded changes that disagree with the watched episodes are ignored, the watched
+/// episodes being the source of truth.
+pub fn episode_states(
+    series: &Series,
+    changes: impl IntoIterator<Item = (u32, Episode, EpisodeChange)>,
+) -> EpisodeStates {
+    let mut states: EpisodeStates = changes
+        .into_iter()
+        .filter(|(_, _, change)| !change.watched)
+        .map(|(season, episode, change)| ((season, episode), change))
+        .collect();
+
+    for (season_number, season) in series.seasons.iter() {
+        for episode in season.episodes.iter() {
+            states.insert((*season_number, *episode), WATCHED_BEFORE_RECORDING);
+        }
+    }
+
+    states
+}
+
+/// The watch state of the given series in the database
+pub fn local_episode_states(series: &Series) -> EpisodeStates {
+    let changes = DB.get_episode_changes(series.id);
+    let watched_changes: HashMap<_, _> = changes
+        .iter()
+        .filter(|(_, _, change)| change.watched)
+        .map(|(season, episode, change)| ((*season, *episode), *change))
+        .collect();
+
+    let mut states = episode_states(series, changes);
+
+    // Restoring the time watched episodes were recorded at
+    for (key, state) in states.iter_mut() {
+        if let Some(watched_change) = watched_changes.get(key) {
+            if state.watched {
+                *state = *watched_change;
+            }
+        }
+    }
+
+    states
+}
+
+/// Picks the winning change between two changes of the same episode
+pub fn resolve(local: EpisodeChange, incoming: EpisodeChange) -> EpisodeChange {
+    let incoming_is_newer = incoming.changed_at > local.changed_at;
+    let incoming_wins_tie =
+        incoming.changed_at == local.changed_at && incoming.watched && !local.watched;
+
+    if incoming_is_newer || incoming_wins_tie {
+        incoming
+    } else {
+        local
+    }
+}
+
+/// Picks the note kept for an episode, `None` when the local note stays as it is
+pub fn resolve_note<'a>(local: Option<&str>, incoming: &'a str) -> Option<&'a str> {
+    let local_is_empty = local.is_none_or(|local| local.trim().is_empty());
+    (local_is_empty && !incoming.trim().is_empty()).then_some(incoming)
+}
+
+/// Merges episode notes from elsewhere into the database
+///
+/// Returns the amount of notes written.
+pub fn merge_notes_into_db(incoming_notes: impl IntoIterator<Item = EpisodeNote>) -> usize {
+    incoming_notes
+        .into_iter()
+        .filter(|incoming_note| {
+            let local_note = DB.get_episode_note(
+                incoming_note.series_id,
+                incoming_note.season_number,
+                incoming_note.episode_number,
+            );
+
+            let Some(note) = resolve_note(local_note.as_deref(), &incoming_note.note) else {
+                return false;
+            };
+
+            DB.set_episode_note(
+                incoming_note.series_id,
+                incoming_note.season_number,
+                incoming_note.episode_number,
+                note,
+            );
+            true
+        })
+        .count()
+}
+
+/// Merges the episode states of a series from elsewhere into the database
+///
+/// The series is added to the database when it is not already there.
+pub fn merge_into_db(
+    series_id: u32,
+    series_name: &str,
+    is_tracked: bool,
+    incoming_states: &EpisodeStates,
+) -> MergeOutcome {
+    let mut series = DB
+        .get_series(series_id)
+        .unwrap_or_else(|| Series::new(series_name.to_owned(), series_id));
+
+    if is_tracked {
+        series.mark_tracked();
+    }
+
+    let local_states = local_episode_states(&series);
+    let mut outcome = MergeOutcome::default();
+
+    for (&(season_number, episode_number), incoming_change) in incoming_states {
+        let local_change = local_states.get(&(season_number, episode_number));
+
+        let resolved = match local_change {
+            Some(local_change) => resolve(*local_change, *incoming_change),
+            None => *incoming_change,
+        };
+
+        if Some(&resolved) == local_change {
+            continue;
+        }
+
+        let is_watched = local_change
+            .map(|local_change| local_change.watched)
+            .unwrap_or(false);
+
+        if resolved.watched && !is_watched {
+            series.add_episode_unchecked(season_number, episode_number);
+            outcome.newly_watched += 1;
+        } else if !resolved.watched && is_watched {
+            series.remove_episode(season_number, episode_number);
+            outcome.newly_unwatched += 1;
+        }
+
+        // Keeping the time of the winning change rather than the time it was merged
+        DB.set_episode_change(series_id, season_number, episode_number, resolved);
+    }
+
+    outcome
+}
+
+/// Merges a series that has no recorded changes, like the ones from import files
+/// or Trakt, into the database
+///
+/// # Note
+/// The incoming series itself is never written to the database.
+pub fn merge_series_into_db(incoming: Series) -> MergeOutcome {
+    let outcome = merge_into_db(
+        incoming.id,
+        &incoming.name,
+        incoming.is_tracked,
+        &episode_states(&incoming, []),
+    );
+    incoming.discard();
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Once;
+
+    use super::*;
+    use crate::core::paths;
+
+    fn change(changed_at: i64, watched: bool) -> EpisodeChange {
+        EpisodeChange {
+            changed_at,
+            watched,
+        }
+    }
+
+    /// Points the database to a folder of its own before it is first opened
+    fn use_temporary_database() {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            let dir = std::env::temp_dir().join(format!(
+                "{}-merge-tests-{}",
+                env!("CARGO_PKG_NAME"),
+                std::process::id()
+            ));
+            let mut paths = paths::PATHS.write().unwrap();
+            paths.set_config_dir_path(dir.join("config"));
+            paths.set_data_dir_path(dir.join("data"));
+            paths.set_cache_dir_path(dir.join("cache"));
+        });
+    }
+
+    #[test]
+    fn latest_change_wins() {
+        assert_eq!(resolve(change(1, true), change(2, false)), change(2, false));
+        assert_eq!(resolve(change(2, false), change(1, true)), change(2, false));
+        assert_eq!(resolve(change(1, false), change(2, true)), change(2, true));
+    }
+
+    #[test]
+    fn watched_wins_a_tie() {
+        assert_eq!(resolve(change(1, false), change(1, true)), change(1, true));
+        assert_eq!(resolve(change(1, true), change(1, false)), change(1, true));
+        assert_eq!(
+            resolve(change(1, false), change(1, false)),
+            change(1, false)
+        );
+    }
+
+    #[test]
+    fn non_empty_note_is_preferred() {
+        assert_eq!(resolve_note(None, "incoming"), Some("incoming"));
+        assert_eq!(resolve_note(Some(" "), "incoming"), Some("incoming"));
+        assert_eq!(resolve_note(Some("local"), "incoming"), None);
+        assert_eq!(resolve_note(None, " "), None);
+    }
+
+    #[test]
+    fn recorded_changes_do_not_override_watched_episodes() {
+        let mut series = Series::new("Series".to_owned(), 1);
+        series.add_episode_unchecked(1, 1);
+
+        let states = episode_states(
+            &series,
+            [(1, 1, change(5, false)), (1, 2, change(5, false))],
+        );
+        series.discard();
+
+        assert_eq!(states[&(1, 1)], WATCHED_BEFORE_RECORDING);
+        assert_eq!(states[&(1, 2)], change(5, false));
+    }
+
+    #[test]
+    fn merging_keeps_episodes_watched_on_either_side() {
+        use_temporary_database();
+
+        let mut local = Series::new("Local And Incoming".to_owned(), 101);
+        local.add_episode_unchecked(1, 1);
+        local.add_episode_unchecked(1, 2);
+        drop(local);
+
+        let mut incoming = Series::new("Local And Incoming".to_owned(), 101);
+        incoming.add_episode_unchecked(1, 2);
+        incoming.add_episode_unchecked(2, 1);
+
+        let outcome = merge_series_into_db(incoming);
+
+        assert_eq!(outcome.newly_watched, 1);
+        assert_eq!(outcome.newly_unwatched, 0);
+        let merged = DB.get_series(101).unwrap();
+        assert_eq!(merged.get_total_episodes(), 3);
+        merged.discard();
+    }
+
+    #[test]
+    fn merging_adds_missing_series() {
+        use_temporary_database();
+
+        let mut incoming = Series::new("Incoming Only".to_owned(), 102);
+        incoming.add_episode_unchecked(1, 1);
+
+        let outcome = merge_series_into_db(incoming);
+
+        assert_eq!(outcome.newly_watched, 1);
+        let merged = DB.get_series(102).unwrap();
+        assert_eq!(merged.get_total_episodes(), 1);
+        merged.discard();
+    }
+
+    #[test]
+    fn later_unwatch_wins_over_local_watch() {
+        use_temporary_database();
+
+        let mut local = Series::new("Unwatched Elsewhere".to_owned(), 103);
+        local.add_episode_unchecked(1, 1);
+        local.add_episode_unchecked(1, 2);
+        drop(local);
+        DB.set_episode_change(103, 1, 1, change(10, true));
+
+        let incoming_states =
+            EpisodeStates::from([((1, 1), change(20, false)), ((1, 2), change(-1, false))]);
+        let outcome = merge_into_db(103, "Unwatched Elsewhere", true, &incoming_states);
+
+        assert_eq!(outcome.newly_unwatched, 1);
+        let merged = DB.get_series(103).unwrap();
+        assert_eq!(merged.get_total_episodes(), 1);
+        merged.discard();
+    }
+
+    #[test]
+    fn notes_are_only_merged_into_episodes_without_one() {
+        use_temporary_database();
+
+        DB.set_episode_note(104, 1, 1, "local");
+        let note = |episode_number, note: &str| EpisodeNote {
+            series_id: 104,
+            season_number: 1,
+            episode_number,
+            note: note.to_owned(),
+        };
+
+        let written = merge_notes_into_db([note(1, "incoming"), note(2, "incoming"), note(3, "")]);
+
+        assert_eq!(written, 1);
+        assert_eq!(DB.get_episode_note(104, 1, 1).as_deref(), Some("local"));
+        assert_eq!(DB.get_episode_note(104, 1, 2).as_deref(), Some("incoming"));
+        assert_eq!(DB.get_episode_note(104, 1, 3), None);
+    }
+}
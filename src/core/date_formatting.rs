@@ -0,0 +1,98 @@
+//! # Date formatting
+//!
+//! Writes dates the way set in the locale settings, either ISO, US or EU, and times in
+//! the 12-hour or 24-hour format set there, and works out where weeks start from the first
+//! day of the week set there too. Everything
+//! showing a date to the user goes through here so that the app stays consistent.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone};
+
+use super::settings_config::{DateFormat, TimeFormat, SETTINGS};
+
+fn get_date_format() -> DateFormat {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .locale
+        .date_format
+}
+
+/// Formats the date like `2024-03-31`, `03/31/2024` or `31/03/2024`
+pub fn date_str(date: NaiveDate) -> String {
+    let pattern = match get_date_format() {
+        DateFormat::Iso => "%Y-%m-%d",
+        DateFormat::Us => "%m/%d/%Y",
+        DateFormat::Eu => "%d/%m/%Y",
+    };
+    date.format(pattern).to_string()
+}
+
+/// Formats the date with its weekday like `Sunday, 2024-03-31`
+pub fn long_date_str(date: NaiveDate) -> String {
+    format!("{}, {}", date.format("%A"), date_str(date))
+}
+
+/// Formats the date without the year like `Sun, 03-31`, `Sun, 03/31` or `Sun, 31/03`
+pub fn short_date_str(date: NaiveDate) -> String {
+    let pattern = match get_date_format() {
+        DateFormat::Iso => "%a, %m-%d",
+        DateFormat::Us => "%a, %m/%d",
+        DateFormat::Eu => "%a, %d/%m",
+    };
+    date.format(pattern).to_string()
+}
+
+/// Whether times are written like `8:30 PM` rather than `20:30`
+fn is_twelve_hour() -> bool {
+    let locale = SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .locale
+        .clone();
+
+    match locale.time_format {
+        TimeFormat::Auto => locale.date_format == DateFormat::Us,
+        TimeFormat::TwelveHour => true,
+        TimeFormat::TwentyFourHour => false,
+    }
+}
+
+/// Formats the time like `20:30` or `8:30 PM`
+pub fn naive_time_str(time: NaiveTime) -> String {
+    let pattern = if is_twelve_hour() {
+        "%-I:%M %p"
+    } else {
+        "%H:%M"
+    };
+    time.format(pattern).to_string()
+}
+
+/// Formats the time of the date time like `20:30` or `8:30 PM`
+pub fn time_str<Tz: TimeZone>(date_time: &DateTime<Tz>) -> String {
+    naive_time_str(date_time.time())
+}
+
+/// Formats a date as given by TVmaze like `2024-03-31`, keeping it as is when it can't be read
+pub fn api_date_str(api_date: &str) -> String {
+    NaiveDate::parse_from_str(api_date, "%Y-%m-%d")
+        .map(date_str)
+        .unwrap_or_else(|_| api_date.to_owned())
+}
+
+/// The first day of the week the date is in
+pub fn get_week_start(date: NaiveDate) -> NaiveDate {
+    let first_weekday = SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .locale
+        .week_start
+        .weekday();
+
+    let days_since_week_start =
+        (date.weekday().num_days_from_monday() + 7 - first_weekday.num_days_from_monday()) % 7;
+
+    date - Duration::days(days_since_week_start as i64)
+}
@@ -0,0 +1,336 @@
+//! # Deep links
+//!
+//! Links like `troxide://series/82` open a page of the app from outside of it, like from
+//! exported rss feeds and calendars. The operating system launches the app with the link,
+//! and when the app is already running the new process hands the link over to it through a
+//! local socket and exits, so that opening a link never opens a second window. Launching the
+//! app without a link is not affected, it always opens a new window.
+//!
+//! The app is only registered as the handler of the links when asked to from the settings,
+//! so that trying out another copy of the app does not take them over.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::str::FromStr;
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// The scheme of the links handled by the app
+pub const SCHEME: &str = "troxide";
+
+/// The local port the running instance receives links on
+const IPC_PORT: u16 = 47_821;
+
+/// How long a running instance has to accept a forwarded link
+const IPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Error)]
+pub enum DeepLinkError {
+    #[error("\"{0}\" is not a {SCHEME}:// link")]
+    InvalidScheme(String),
+    #[error("\"{0}\" does not point to anything in the app")]
+    UnknownTarget(String),
+    #[error("ipc error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A page of the app a link points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeepLink {
+    Series(u32),
+}
+
+impl FromStr for DeepLink {
+    type Err = DeepLinkError;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let path = uri
+            .trim()
+            .strip_prefix(SCHEME)
+            .and_then(|rest| rest.strip_prefix("://"))
+            .ok_or_else(|| DeepLinkError::InvalidScheme(uri.to_owned()))?;
+
+        let segments: Vec<&str> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        match segments.as_slice() {
+            ["series", series_id] => series_id
+                .parse()
+                .map(Self::Series)
+                .map_err(|_| DeepLinkError::UnknownTarget(uri.to_owned())),
+            _ => Err(DeepLinkError::UnknownTarget(uri.to_owned())),
+        }
+    }
+}
+
+impl std::fmt::Display for DeepLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeepLink::Series(series_id) => write!(f, "{}://series/{}", SCHEME, series_id),
+        }
+    }
+}
+
+fn ipc_address() -> SocketAddr {
+    SocketAddr::from((Ipv4Addr::LOCALHOST, IPC_PORT))
+}
+
+/// Hands the link over to an already running instance of the app
+///
+/// Returns false when no instance is running, meaning this one should open the link itself.
+pub fn forward_to_running_instance(deep_link: DeepLink) -> Result<bool, DeepLinkError> {
+    let mut stream = match TcpStream::connect_timeout(&ipc_address(), IPC_TIMEOUT) {
+        Ok(stream) => stream,
+        Err(err) if err.kind() == std::io::ErrorKind::ConnectionRefused => return Ok(false),
+        Err(err) => return Err(err.into()),
+    };
+    stream.set_write_timeout(Some(IPC_TIMEOUT))?;
+    writeln!(stream, "{}", deep_link)?;
+    Ok(true)
+}
+
+/// Receives the links forwarded by other instances of the app, calling `on_link` for each
+///
+/// Blocks for as long as the app runs, so it's meant to be run on its own thread.
+pub fn receive_forwarded_links(on_link: impl Fn(DeepLink)) -> Result<(), DeepLinkError> {
+    let listener = TcpListener::bind(ipc_address())?;
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                tracing::warn!("failed to accept forwarded link: {}", err);
+                continue;
+            }
+        };
+        stream.set_read_timeout(Some(IPC_TIMEOUT))?;
+
+        let mut uri = String::new();
+        if let Err(err) = BufReader::new(stream).read_line(&mut uri) {
+            tracing::warn!("failed to read forwarded link: {}", err);
+            continue;
+        }
+
+        match uri.parse() {
+            Ok(deep_link) => on_link(deep_link),
+            Err(err) => tracing::warn!("ignoring forwarded link: {}", err),
+        }
+    }
+    Ok(())
+}
+
+/// Whether links can be registered on this operating system
+///
+/// Supported on Linux desktops following the freedesktop specifications and on Windows.
+/// On macOS the scheme can only be declared by the app bundle.
+pub const fn is_uri_scheme_supported() -> bool {
+    platform::SUPPORTED
+}
+
+/// Whether the operating system opens `troxide://` links with this copy of the app
+pub fn is_uri_scheme_registered() -> bool {
+    std::env::current_exe().is_ok_and(|executable| platform::is_registered(&executable))
+}
+
+/// Registers this copy of the app as the handler of `troxide://` links with the operating
+/// system, doing nothing when it already is
+pub fn register_uri_scheme() -> anyhow::Result<()> {
+    let executable = std::env::current_exe()?;
+    if platform::is_registered(&executable) {
+        return Ok(());
+    }
+    platform::register(&executable)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    use directories::BaseDirs;
+
+    const DESKTOP_FILE_NAME: &str = "series-troxide-url-handler.desktop";
+
+    pub const SUPPORTED: bool = true;
+
+    fn applications_dir() -> anyhow::Result<PathBuf> {
+        let base_dirs = BaseDirs::new().ok_or(anyhow::anyhow!("could not get user directories"))?;
+        Ok(base_dirs.data_dir().join("applications"))
+    }
+
+    fn desktop_file(executable: &Path) -> String {
+        format!(
+            "[Desktop Entry]\nType=Application\nName=Series Troxide\nExec=\"{}\" open %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+            executable.display(),
+            super::SCHEME
+        )
+    }
+
+    fn mime_type() -> String {
+        format!("x-scheme-handler/{}", super::SCHEME)
+    }
+
+    pub fn is_registered(executable: &Path) -> bool {
+        let Ok(applications_dir) = applications_dir() else {
+            return false;
+        };
+
+        let is_current_desktop_file =
+            std::fs::read_to_string(applications_dir.join(DESKTOP_FILE_NAME))
+                .is_ok_and(|current| current == desktop_file(executable));
+
+        is_current_desktop_file
+            && Command::new("xdg-mime")
+                .args(["query", "default", &mime_type()])
+                .output()
+                .is_ok_and(|output| {
+                    String::from_utf8_lossy(&output.stdout).trim() == DESKTOP_FILE_NAME
+                })
+    }
+
+    pub fn register(executable: &Path) -> anyhow::Result<()> {
+        let applications_dir = applications_dir()?;
+
+        std::fs::create_dir_all(&applications_dir)?;
+        std::fs::write(
+            applications_dir.join(DESKTOP_FILE_NAME),
+            desktop_file(executable),
+        )?;
+
+        let status = Command::new("xdg-mime")
+            .args(["default", DESKTOP_FILE_NAME, &mime_type()])
+            .status()?;
+        if !status.success() {
+            anyhow::bail!("xdg-mime exited with {}", status);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::path::Path;
+    use std::process::Command;
+
+    pub const SUPPORTED: bool = true;
+
+    fn key() -> String {
+        format!("HKCU\\Software\\Classes\\{}", super::SCHEME)
+    }
+
+    fn command_key() -> String {
+        format!("{}\\shell\\open\\command", key())
+    }
+
+    fn command(executable: &Path) -> String {
+        format!("\"{}\" open \"%1\"", executable.display())
+    }
+
+    pub fn is_registered(executable: &Path) -> bool {
+        Command::new("reg")
+            .args(["query", &command_key(), "/ve"])
+            .output()
+            .is_ok_and(|output| {
+                output.status.success()
+                    && String::from_utf8_lossy(&output.stdout).contains(&command(executable))
+            })
+    }
+
+    pub fn register(executable: &Path) -> anyhow::Result<()> {
+        let key = key();
+        let command_key = command_key();
+        let command = command(executable);
+
+        let registry_entries: [&[&str]; 3] = [
+            &[&key, "/ve", "/d", "URL:Series Troxide"],
+            &[&key, "/v", "URL Protocol", "/d", ""],
+            &[&command_key, "/ve", "/d", &command],
+        ];
+
+        for entry in registry_entries {
+            let status = Command::new("reg")
+                .arg("add")
+                .args(entry)
+                .arg("/f")
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("reg exited with {}", status);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(all(unix, not(target_os = "macos")), windows)))]
+mod platform {
+    use std::path::Path;
+
+    pub const SUPPORTED: bool = false;
+
+    pub fn is_registered(_executable: &Path) -> bool {
+        false
+    }
+
+    pub fn register(_executable: &Path) -> anyhow::Result<()> {
+        anyhow::bail!("links can not be registered on this operating system")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_links_are_parsed() {
+        assert_eq!(
+            "troxide://series/82".parse::<DeepLink>().unwrap(),
+            DeepLink::Series(82)
+        );
+        assert_eq!(
+            " troxide://series/82/ \n".parse::<DeepLink>().unwrap(),
+            DeepLink::Series(82)
+        );
+    }
+
+    #[test]
+    fn links_are_parsed_back_from_their_display() {
+        let deep_link = DeepLink::Series(1371);
+        assert_eq!(
+            deep_link.to_string().parse::<DeepLink>().unwrap(),
+            deep_link
+        );
+    }
+
+    #[test]
+    fn invalid_links_are_rejected() {
+        assert!(matches!(
+            "https://www.tvmaze.com/shows/82".parse::<DeepLink>(),
+            Err(DeepLinkError::InvalidScheme(_))
+        ));
+        assert!(matches!(
+            "troxide:series/82".parse::<DeepLink>(),
+            Err(DeepLinkError::InvalidScheme(_))
+        ));
+
+        for uri in [
+            "troxide://",
+            "troxide://series",
+            "troxide://series/abc",
+            "troxide://series/-1",
+            "troxide://series/82/episodes",
+            "troxide://people/82",
+        ] {
+            assert!(
+                matches!(
+                    uri.parse::<DeepLink>(),
+                    Err(DeepLinkError::UnknownTarget(_))
+                ),
+                "{} should not point to anything",
+                uri
+            );
+        }
+    }
+}
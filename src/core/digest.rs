@@ -0,0 +1,219 @@
+//! # Weekly digest
+//!
+//! A summary of the week around now for the series in the database: the episodes of the
+//! tracked series that aired last week, the episodes watched last week and the episodes
+//! of the tracked series airing next week.
+//!
+//! The digest is shown in the statistics tab and can also be sent as a desktop
+//! notification once a week when enabled in the settings.
+
+use std::time;
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use tracing::{error, info};
+
+use super::caching::episode_list::EpisodeList;
+use super::database::{SeriesStatus, DB};
+use super::paths;
+use super::settings_config::SETTINGS;
+
+/// File in the data directory remembering when the last digest notification was sent
+const LAST_DIGEST_FILE_NAME: &str = "last-weekly-digest";
+
+/// How many days the digest looks back and ahead
+const DIGEST_DAYS: i64 = 7;
+
+/// An episode mentioned in the digest
+#[derive(Debug, Clone)]
+pub struct DigestEpisode {
+    pub series_id: u32,
+    pub series_name: String,
+    pub season: u32,
+    pub number: u32,
+    /// `None` when the episode is not in the series' episode list
+    pub name: Option<String>,
+    /// When the episode aired or was watched depending on the part of the digest
+    pub date: DateTime<Local>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WeeklyDigest {
+    pub aired_last_week: Vec<DigestEpisode>,
+    pub watched_last_week: Vec<DigestEpisode>,
+    pub airing_next_week: Vec<DigestEpisode>,
+}
+
+impl WeeklyDigest {
+    pub fn is_empty(&self) -> bool {
+        self.aired_last_week.is_empty()
+            && self.watched_last_week.is_empty()
+            && self.airing_next_week.is_empty()
+    }
+}
+
+/// Builds the digest of the past and the coming week
+pub async fn get_weekly_digest() -> anyhow::Result<WeeklyDigest> {
+    let now = Local::now();
+    let week = Duration::days(DIGEST_DAYS);
+    let week_ago_millis = (now - week).timestamp_millis();
+
+    // Only the basics are kept as series write themselves back to the database when dropped
+    let series_collection: Vec<_> = DB
+        .get_series_collection()
+        .iter()
+        .map(|series| {
+            (
+                series.id(),
+                series.get_name().to_owned(),
+                series.is_tracked()
+                    && DB.get_series_status(series.id()) != SeriesStatus::Dropped
+                    && !DB.is_archived(series.id()),
+            )
+        })
+        .collect();
+
+    let mut digest = WeeklyDigest::default();
+
+    for (series_id, series_name, is_followed) in series_collection {
+        let recent_watches: Vec<_> = DB
+            .get_episode_changes(series_id)
+            .into_iter()
+            .filter(|(_, _, change)| change.watched && change.changed_at >= week_ago_millis)
+            .collect();
+
+        if !is_followed && recent_watches.is_empty() {
+            continue;
+        }
+
+        let episode_list = EpisodeList::new(series_id).await?;
+
+        let digest_episode = |season: u32, number: u32, date: DateTime<Local>| DigestEpisode {
+            series_id,
+            series_name: series_name.clone(),
+            season,
+            number,
+            name: episode_list
+                .get_episode(season, number)
+                .map(|episode| episode.name.clone()),
+            date,
+        };
+
+        for (season, number, change) in recent_watches {
+            if let Some(watched_at) = Local.timestamp_millis_opt(change.changed_at).single() {
+                digest
+                    .watched_last_week
+                    .push(digest_episode(season, number, watched_at));
+            }
+        }
+
+        if !is_followed {
+            continue;
+        }
+
+        for episode in episode_list.get_all_episodes() {
+            let (Some(number), Ok(airdate)) = (episode.number, episode.local_date_time()) else {
+                continue;
+            };
+
+            if airdate <= now && airdate > now - week {
+                digest
+                    .aired_last_week
+                    .push(digest_episode(episode.season, number, airdate));
+            } else if airdate > now && airdate <= now + week {
+                digest
+                    .airing_next_week
+                    .push(digest_episode(episode.season, number, airdate));
+            }
+        }
+    }
+
+    digest.aired_last_week.sort_by_key(|episode| episode.date);
+    digest.watched_last_week.sort_by_key(|episode| episode.date);
+    digest.airing_next_week.sort_by_key(|episode| episode.date);
+
+    Ok(digest)
+}
+
+/// Sends the digest notification once a week forever while enabled in the settings
+pub async fn run_weekly_digest() {
+    loop {
+        let weekly_digest_enabled = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .notifications
+            .weekly_digest;
+
+        if weekly_digest_enabled {
+            let is_due = get_last_digest_time()
+                .await
+                .map(|last_digest_time| {
+                    Utc::now() - last_digest_time >= Duration::days(DIGEST_DAYS)
+                })
+                .unwrap_or(true);
+
+            if is_due {
+                info!("sending the weekly digest");
+                match get_weekly_digest().await {
+                    Ok(digest) => {
+                        if !digest.is_empty() {
+                            notify_weekly_digest(&digest);
+                        }
+                        save_last_digest_time(Utc::now()).await;
+                    }
+                    Err(err) => error!("failed to build the weekly digest: {}", err),
+                }
+            }
+        }
+
+        // Rechecking every hour so that settings changes are picked up
+        tokio::time::sleep(time::Duration::from_secs(60 * 60)).await;
+    }
+}
+
+fn notify_weekly_digest(digest: &WeeklyDigest) {
+    let notification_body = format!(
+        "{} episodes aired last week and you watched {}, {} episodes are airing next week.\nSee the statistics tab for the full digest.",
+        digest.aired_last_week.len(),
+        digest.watched_last_week.len(),
+        digest.airing_next_week.len(),
+    );
+
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("Series Troxide")
+        .summary("Your weekly digest")
+        .body(&notification_body)
+        .timeout(0)
+        .auto_icon()
+        .show()
+    {
+        error!("failed to show the weekly digest notification: {}", err);
+    }
+}
+
+async fn get_last_digest_time() -> Option<DateTime<Utc>> {
+    let last_digest = tokio::fs::read_to_string(get_last_digest_file_path())
+        .await
+        .ok()?;
+
+    DateTime::parse_from_rfc3339(last_digest.trim())
+        .ok()
+        .map(|last_digest_time| last_digest_time.with_timezone(&Utc))
+}
+
+async fn save_last_digest_time(digest_time: DateTime<Utc>) {
+    if let Err(err) = tokio::fs::write(get_last_digest_file_path(), digest_time.to_rfc3339()).await
+    {
+        error!("failed to save the last weekly digest time: {}", err);
+    }
+}
+
+fn get_last_digest_file_path() -> std::path::PathBuf {
+    let mut last_digest_file = paths::PATHS
+        .read()
+        .expect("failed to read paths")
+        .get_data_dir_path()
+        .to_path_buf();
+    last_digest_file.push(LAST_DIGEST_FILE_NAME);
+    last_digest_file
+}
@@ -0,0 +1,68 @@
+//! # Discover filter
+//!
+//! Narrows down the series shown in the Discover sections by their show type and
+//! language as provided by TVmaze, i.e. hiding talk shows or only showing English and
+//! Japanese series. Unlike the content filter, searching is not affected.
+
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::settings_config::{DiscoverFilterSettings, SETTINGS};
+
+/// The show types used by TVmaze
+pub const SHOW_TYPES: [&str; 11] = [
+    "Scripted",
+    "Animation",
+    "Reality",
+    "Talk Show",
+    "Documentary",
+    "Game Show",
+    "News",
+    "Sports",
+    "Variety",
+    "Award Show",
+    "Panel Show",
+];
+
+/// The languages offered for limiting Discover, the most common ones on TVmaze
+pub const LANGUAGES: [&str; 14] = [
+    "English",
+    "Japanese",
+    "Korean",
+    "Chinese",
+    "Spanish",
+    "Portuguese",
+    "French",
+    "German",
+    "Italian",
+    "Dutch",
+    "Swedish",
+    "Russian",
+    "Turkish",
+    "Hindi",
+];
+
+/// Whether the series should be left out of Discover according to the discover filter settings
+pub fn is_filtered(series_info: &SeriesMainInformation) -> bool {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .discover_filter
+        .filters(series_info)
+}
+
+impl DiscoverFilterSettings {
+    pub fn filters(&self, series_info: &SeriesMainInformation) -> bool {
+        let is_hidden_type = series_info
+            .kind
+            .as_ref()
+            .is_some_and(|kind| self.hidden_types.contains(kind));
+
+        let is_other_language = !self.languages.is_empty()
+            && series_info
+                .language
+                .as_ref()
+                .is_none_or(|language| !self.languages.contains(language));
+
+        is_hidden_type || is_other_language
+    }
+}
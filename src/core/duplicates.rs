@@ -0,0 +1,110 @@
+//! # Duplicate series
+//!
+//! The same show can end up tracked under two TVmaze ids, like after a rebrand. Such
+//! duplicates are detected by their IMDb id or their name, and can be merged into one
+//! series combining the watch records of both.
+
+use std::collections::HashMap;
+
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::caching::series_list::SeriesList;
+use super::database::merge::{self, MergeOutcome};
+use super::database::DB;
+
+/// Why two series are considered the same show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateReason {
+    SameImdbId,
+    SameName,
+}
+
+impl std::fmt::Display for DuplicateReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DuplicateReason::SameImdbId => write!(f, "same IMDb id"),
+            DuplicateReason::SameName => write!(f, "same name"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    pub first: SeriesMainInformation,
+    pub second: SeriesMainInformation,
+    pub reason: DuplicateReason,
+}
+
+/// Finds the tracked series that look like the same show
+pub async fn find_duplicates() -> anyhow::Result<Vec<Duplicate>> {
+    let tracked_series_information = SeriesList::new().get_tracked_series_information().await?;
+
+    let mut duplicates = vec![];
+    let mut seen_imdb_ids: HashMap<String, &SeriesMainInformation> = HashMap::new();
+    let mut seen_names: HashMap<String, &SeriesMainInformation> = HashMap::new();
+
+    for series_info in tracked_series_information.iter() {
+        let imdb_id = series_info
+            .externals
+            .as_ref()
+            .and_then(|externals| externals.imdb.clone());
+
+        let duplicate = if let Some(first) = imdb_id
+            .as_ref()
+            .and_then(|imdb_id| seen_imdb_ids.get(imdb_id))
+        {
+            Some((*first, DuplicateReason::SameImdbId))
+        } else {
+            seen_names
+                .get(&normalize_name(&series_info.name))
+                .map(|first| (*first, DuplicateReason::SameName))
+        };
+
+        if let Some((first, reason)) = duplicate {
+            duplicates.push(Duplicate {
+                first: first.clone(),
+                second: series_info.clone(),
+                reason,
+            });
+            continue;
+        }
+
+        if let Some(imdb_id) = imdb_id {
+            seen_imdb_ids.insert(imdb_id, series_info);
+        }
+        seen_names.insert(normalize_name(&series_info.name), series_info);
+    }
+
+    Ok(duplicates)
+}
+
+/// Merges the watch records of the duplicate into the kept series and removes the duplicate
+///
+/// # Note
+/// Episodes are matched by their season and episode numbers.
+pub fn merge(kept_series: &SeriesMainInformation, duplicate_id: u32) -> MergeOutcome {
+    let Some(duplicate) = DB.get_series(duplicate_id) else {
+        return MergeOutcome::default();
+    };
+
+    let duplicate_states = merge::local_episode_states(&duplicate);
+    let outcome = merge::merge_into_db(
+        kept_series.id,
+        &kept_series.name,
+        duplicate.is_tracked(),
+        &duplicate_states,
+    );
+
+    // The duplicate is written back when dropped, so it is removed only afterwards
+    drop(duplicate);
+    DB.remove_series(duplicate_id);
+
+    outcome
+}
+
+/// The name with only its lowercase letters and digits, ignoring punctuation and spacing
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|char| char.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,112 @@
+//! # Episode matching
+//!
+//! Finds the tracked series episode a title refers to, like the name of a video file
+//! `Show.Name.S02E05.1080p` or what a media player is playing `Show – S01E02 – Pilot`.
+
+use crate::core::database::DB;
+
+/// The series name and episode read from a title
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedTitle {
+    /// The words before the episode code, lowercase
+    pub series_words: Vec<String>,
+    pub season: u32,
+    pub episode: u32,
+}
+
+/// An unwatched episode of a tracked series
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedEpisode {
+    pub series_id: u32,
+    pub series_name: String,
+    pub season: u32,
+    pub episode: u32,
+}
+
+/// Reads the series name and episode from a title
+///
+/// The episode is given either like `S02E05` or like `2x05`, everything before it being
+/// the series name.
+pub fn parse_title(title: &str) -> Option<ParsedTitle> {
+    let title = title.to_lowercase();
+    let words: Vec<&str> = title
+        .split(['.', '_', '-', '–', '—', '[', ']', '(', ')', ' '])
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    words.iter().enumerate().find_map(|(index, word)| {
+        let (season, episode) = parse_episode_code(word)?;
+        if index == 0 {
+            return None;
+        }
+
+        Some(ParsedTitle {
+            series_words: words[..index].iter().map(|word| word.to_string()).collect(),
+            season,
+            episode,
+        })
+    })
+}
+
+/// Reads `s02e05` and `2x05`, the first episode being taken for multi episode titles
+fn parse_episode_code(word: &str) -> Option<(u32, u32)> {
+    if let Some(code) = word.strip_prefix('s') {
+        let (season, episode) = code.split_once('e')?;
+        let episode: String = episode.chars().take_while(char::is_ascii_digit).collect();
+        return Some((season.parse().ok()?, episode.parse().ok()?));
+    }
+
+    // Limiting the season digits to not take resolutions like 1920x1080 for episodes
+    let (season, episode) = word.split_once('x')?;
+    if season.len() > 2 || !episode.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((season.parse().ok()?, episode.parse().ok()?))
+}
+
+/// Lowercase letters and digits of the name, for comparing names written differently
+fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Matches the title to an unwatched episode of a tracked series
+pub fn match_unwatched_episode(title: &str) -> Option<MatchedEpisode> {
+    let parsed = parse_title(title)?;
+
+    let full_name_key = name_key(&parsed.series_words.concat());
+    // Releases often add the premiere year after the name
+    let name_key_without_year = match parsed.series_words.split_last() {
+        Some((last_word, rest))
+            if !rest.is_empty() && last_word.len() == 4 && last_word.parse::<u32>().is_ok() =>
+        {
+            Some(name_key(&rest.concat()))
+        }
+        _ => None,
+    };
+
+    let series = DB
+        .get_series_collection()
+        .into_iter()
+        .filter(|series| series.is_tracked())
+        .find(|series| {
+            let series_key = name_key(series.get_name());
+            series_key == full_name_key || name_key_without_year.as_ref() == Some(&series_key)
+        })?;
+
+    let is_watched = series
+        .get_season(parsed.season)
+        .is_some_and(|season| season.is_episode_watched(parsed.episode));
+    if is_watched {
+        return None;
+    }
+
+    Some(MatchedEpisode {
+        series_id: series.id(),
+        series_name: series.get_name().to_owned(),
+        season: parsed.season,
+        episode: parsed.episode,
+    })
+}
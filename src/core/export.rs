@@ -0,0 +1,7 @@
+//! Exporting of series tracking data to other formats
+
+pub mod checklist;
+pub mod csv;
+pub mod ical;
+pub mod rss;
+pub mod snapshot;
@@ -0,0 +1,627 @@
+//! # Episode checklist export and import
+//!
+//! Every episode of a series with whether it was watched or skipped and when, for people
+//! keeping track of their series in spreadsheets or notes, as CSV or as a markdown task
+//! list grouped by season.
+//!
+//! A CSV checklist of a single series can be imported back, the rows being checked against
+//! the episodes of the series first so that the changes can be previewed before applying
+//! them. Applying them marks the episodes like marking them by hand does, and can be undone.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path;
+
+use anyhow::Context;
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+
+use super::csv::{parse_csv, to_csv_line};
+use crate::core::caching::episode_list::{EpisodeList, EpisodeNumber};
+use crate::core::database::{self, Episode, EpisodeChange, Series, DB};
+use crate::gui::helpers::season_episode_str_gen;
+
+const CSV_HEADER: [&str; 6] = [
+    "season",
+    "episode",
+    "name",
+    "airdate",
+    "status",
+    "watched_at",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecklistFormat {
+    Csv,
+    Markdown,
+}
+
+impl ChecklistFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ChecklistFormat::Csv => "csv",
+            ChecklistFormat::Markdown => "md",
+        }
+    }
+}
+
+impl std::fmt::Display for ChecklistFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            ChecklistFormat::Csv => "CSV",
+            ChecklistFormat::Markdown => "Markdown",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpisodeStatus {
+    Watched,
+    Skipped,
+    Unwatched,
+    Unaired,
+}
+
+impl std::fmt::Display for EpisodeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            EpisodeStatus::Watched => "watched",
+            EpisodeStatus::Skipped => "skipped",
+            EpisodeStatus::Unwatched => "unwatched",
+            EpisodeStatus::Unaired => "unaired",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChecklistEntry {
+    pub season: u32,
+    pub number: u32,
+    pub name: String,
+    pub airdate: Option<NaiveDate>,
+    pub status: EpisodeStatus,
+    /// `None` when the episode was marked before changes were recorded
+    pub watched_at: Option<DateTime<Local>>,
+}
+
+/// The numbered episodes of the series in airing order
+pub async fn get_checklist(series_id: u32) -> anyhow::Result<Vec<ChecklistEntry>> {
+    let episode_list = EpisodeList::new(series_id).await?;
+
+    let series = DB.get_series(series_id);
+    let skipped_episodes = DB.get_skipped_episodes(series_id);
+    let episode_changes = DB.get_episode_changes(series_id);
+
+    let checklist = episode_list
+        .get_all_episodes()
+        .iter()
+        .filter_map(|episode| {
+            let number = episode.number?;
+
+            let is_watched = series
+                .as_ref()
+                .and_then(|series| series.get_season(episode.season))
+                .is_some_and(|season| season.is_episode_watched(number));
+
+            let status = if is_watched {
+                EpisodeStatus::Watched
+            } else if skipped_episodes.contains(&(episode.season, number)) {
+                EpisodeStatus::Skipped
+            } else if episode.is_future_release().unwrap_or(true) {
+                EpisodeStatus::Unaired
+            } else {
+                EpisodeStatus::Unwatched
+            };
+
+            let watched_at = is_watched
+                .then(|| {
+                    episode_changes
+                        .iter()
+                        .find(|(season, changed_number, change)| {
+                            *season == episode.season && *changed_number == number && change.watched
+                        })
+                        .and_then(|(_, _, change)| {
+                            Local.timestamp_millis_opt(change.changed_at).single()
+                        })
+                })
+                .flatten();
+
+            Some(ChecklistEntry {
+                season: episode.season,
+                number,
+                name: episode.name.clone(),
+                airdate: episode.date_naive().ok(),
+                status,
+                watched_at,
+            })
+        })
+        .collect();
+
+    Ok(checklist)
+}
+
+pub fn checklist_to_csv(checklist: &[ChecklistEntry]) -> String {
+    let mut csv = to_csv_line(CSV_HEADER);
+
+    for entry in checklist {
+        csv.push_str(&to_csv_line([
+            entry.season.to_string().as_str(),
+            entry.number.to_string().as_str(),
+            &entry.name,
+            entry
+                .airdate
+                .map(|airdate| airdate.format("%Y-%m-%d").to_string())
+                .unwrap_or_default()
+                .as_str(),
+            entry.status.to_string().as_str(),
+            entry
+                .watched_at
+                .map(|watched_at| watched_at.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default()
+                .as_str(),
+        ]));
+    }
+
+    csv
+}
+
+/// A task list per season, watched and skipped episodes being checked
+pub fn checklist_to_markdown(series_name: &str, checklist: &[ChecklistEntry]) -> String {
+    let mut markdown = format!("# {}\n", series_name);
+    let mut current_season = None;
+
+    for entry in checklist {
+        if current_season != Some(entry.season) {
+            current_season = Some(entry.season);
+            markdown.push_str(&format!("\n## Season {}\n\n", entry.season));
+        }
+
+        let check = match entry.status {
+            EpisodeStatus::Watched | EpisodeStatus::Skipped => 'x',
+            EpisodeStatus::Unwatched | EpisodeStatus::Unaired => ' ',
+        };
+
+        let mut details = vec![];
+        if let Some(airdate) = entry.airdate {
+            details.push(format!("aired {}", airdate.format("%Y-%m-%d")));
+        }
+        match entry.status {
+            EpisodeStatus::Watched => details.push(match entry.watched_at {
+                Some(watched_at) => format!("watched {}", watched_at.format("%Y-%m-%d")),
+                None => "watched".to_owned(),
+            }),
+            EpisodeStatus::Skipped => details.push("skipped".to_owned()),
+            EpisodeStatus::Unaired => details.push("unaired".to_owned()),
+            EpisodeStatus::Unwatched => {}
+        }
+
+        markdown.push_str(&format!(
+            "- [{}] {} {}",
+            check,
+            season_episode_str_gen(entry.season, entry.number),
+            entry.name
+        ));
+        if !details.is_empty() {
+            markdown.push_str(&format!(" ({})", details.join(", ")));
+        }
+        markdown.push('\n');
+    }
+
+    markdown
+}
+
+pub async fn write_checklist(
+    series_id: u32,
+    series_name: &str,
+    format: ChecklistFormat,
+    file_path: impl AsRef<path::Path>,
+) -> anyhow::Result<()> {
+    let checklist = get_checklist(series_id).await?;
+
+    let content = match format {
+        ChecklistFormat::Csv => checklist_to_csv(&checklist),
+        ChecklistFormat::Markdown => checklist_to_markdown(series_name, &checklist),
+    };
+
+    tokio::fs::write(file_path, content)
+        .await
+        .context("failed to write the checklist file")
+}
+
+/// A row of an imported checklist that is left out, with why
+#[derive(Debug, Clone)]
+pub struct InvalidRow {
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Where the columns of an imported checklist are
+struct ChecklistColumns {
+    episode: usize,
+    status: usize,
+    season: Option<usize>,
+}
+
+impl ChecklistColumns {
+    fn new(header: &[String]) -> anyhow::Result<Self> {
+        let header: Vec<String> = header
+            .iter()
+            .map(|column| column.trim().to_lowercase())
+            .collect();
+        let find_column = |names: &[&str]| {
+            header
+                .iter()
+                .position(|column| names.contains(&column.as_str()))
+        };
+
+        Ok(Self {
+            episode: find_column(&["episode"])
+                .ok_or(anyhow::anyhow!("there is no 'episode' column"))?,
+            status: find_column(&["watched", "status"])
+                .ok_or(anyhow::anyhow!("there is no 'watched' or 'status' column"))?,
+            season: find_column(&["season"]),
+        })
+    }
+}
+
+/// A CSV checklist read and checked against the episodes of the series, waiting to be applied
+#[derive(Debug, Clone)]
+pub struct ChecklistImport {
+    series_id: u32,
+    series_name: String,
+    /// The episodes whose status is different from the current one
+    changes: Vec<(u32, Episode, EpisodeStatus)>,
+    /// The amount of episodes already in the imported status
+    unchanged: usize,
+    invalid_rows: Vec<InvalidRow>,
+}
+
+impl ChecklistImport {
+    /// Reads the CSV which needs an `episode` column, holding either `S01E02` codes or
+    /// episode numbers next to a `season` column, and a `watched` or `status` column
+    pub async fn new(series_id: u32, series_name: String, csv: &str) -> anyhow::Result<Self> {
+        let mut records = parse_csv(csv).into_iter();
+
+        let header = records
+            .next()
+            .map(|(_, header)| header)
+            .ok_or(anyhow::anyhow!("the file is empty"))?;
+        let columns = ChecklistColumns::new(&header)?;
+
+        let episode_list = EpisodeList::new(series_id).await?;
+
+        let (imported_statuses, invalid_rows) =
+            read_rows(records, &columns, &episode_list, &series_name);
+
+        let series = DB.get_series(series_id);
+        let skipped_episodes = DB.get_skipped_episodes(series_id);
+
+        let mut changes = vec![];
+        let mut unchanged = 0;
+
+        for ((season, number), status) in imported_statuses {
+            let current_status =
+                get_episode_status(series.as_ref(), &skipped_episodes, season, number);
+
+            if current_status == status {
+                unchanged += 1;
+            } else {
+                changes.push((season, number, status));
+            }
+        }
+
+        Ok(Self {
+            series_id,
+            series_name,
+            changes,
+            unchanged,
+            invalid_rows,
+        })
+    }
+
+    /// The amount of episodes that will end up in the given status
+    pub fn get_total_changes(&self, status: EpisodeStatus) -> usize {
+        self.changes
+            .iter()
+            .filter(|(_, _, change)| *change == status)
+            .count()
+    }
+
+    pub fn get_total_unchanged(&self) -> usize {
+        self.unchanged
+    }
+
+    pub fn get_invalid_rows(&self) -> &[InvalidRow] {
+        &self.invalid_rows
+    }
+
+    /// Changes the episodes to their imported status, returning what is needed to undo it
+    pub fn apply(&self) -> AppliedChecklist {
+        let mut series = DB.get_series(self.series_id).unwrap_or_else(|| {
+            let mut series = database::Series::new(self.series_name.clone(), self.series_id);
+            series.mark_tracked();
+            series
+        });
+        let skipped_episodes = DB.get_skipped_episodes(self.series_id);
+        let episode_changes = DB.get_episode_changes(self.series_id);
+
+        let previous_statuses = self
+            .changes
+            .iter()
+            .copied()
+            .map(|(season, number, status)| {
+                let previous_status =
+                    get_episode_status(Some(&series), &skipped_episodes, season, number);
+                let previous_change = episode_changes
+                    .iter()
+                    .find(|(changed_season, changed_number, _)| {
+                        *changed_season == season && *changed_number == number
+                    })
+                    .map(|(_, _, change)| *change);
+
+                set_episode_status(&mut series, season, number, status);
+                (season, number, previous_status, previous_change)
+            })
+            .collect();
+
+        AppliedChecklist {
+            series_id: self.series_id,
+            previous_statuses,
+        }
+    }
+}
+
+/// Checks the rows of a checklist against the episodes of the series, returning the imported
+/// status of each episode along with the rows left out
+fn read_rows(
+    records: impl IntoIterator<Item = (usize, Vec<String>)>,
+    columns: &ChecklistColumns,
+    episode_list: &EpisodeList,
+    series_name: &str,
+) -> (BTreeMap<(u32, Episode), EpisodeStatus>, Vec<InvalidRow>) {
+    let mut imported_statuses = BTreeMap::new();
+    let mut invalid_rows = vec![];
+
+    for (line, fields) in records {
+        let field = |column: usize| fields.get(column).map(|field| field.trim()).unwrap_or("");
+
+        let status = parse_status(field(columns.status)).and_then(|status| {
+            let episode = parse_episode(field(columns.episode), columns.season.map(field))?;
+            let episode = episode_list.find_episode(&episode).ok_or(format!(
+                "'{}' is not an episode of {}",
+                field(columns.episode),
+                series_name
+            ))?;
+            let number = episode
+                .number
+                .ok_or("specials can't be imported".to_owned())?;
+
+            if status != EpisodeStatus::Unwatched && episode.is_future_release().unwrap_or(true) {
+                return Err(format!(
+                    "{} has not aired yet",
+                    season_episode_str_gen(episode.season, number)
+                ));
+            }
+            Ok((episode.season, number, status))
+        });
+
+        match status {
+            // Later rows of the same episode take over the earlier ones
+            Ok((season, number, status)) => {
+                imported_statuses.insert((season, number), status);
+            }
+            Err(reason) => invalid_rows.push(InvalidRow { line, reason }),
+        }
+    }
+
+    (imported_statuses, invalid_rows)
+}
+
+/// A checklist import that was applied, with the episodes it changed as they were before
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedChecklist {
+    series_id: u32,
+    previous_statuses: Vec<(u32, Episode, EpisodeStatus, Option<EpisodeChange>)>,
+}
+
+impl AppliedChecklist {
+    /// The amount of episodes whose status was changed
+    pub fn get_total_changes(&self) -> usize {
+        self.previous_statuses.len()
+    }
+
+    /// Puts the changed episodes back in their previous status, watch times included
+    pub fn undo(&self) {
+        let Some(mut series) = DB.get_series(self.series_id) else {
+            return;
+        };
+
+        for (season, number, status, episode_change) in self.previous_statuses.iter().copied() {
+            set_episode_status(&mut series, season, number, status);
+            if let Some(episode_change) = episode_change {
+                DB.set_episode_change(self.series_id, season, number, episode_change);
+            }
+        }
+    }
+}
+
+fn get_episode_status(
+    series: Option<&Series>,
+    skipped_episodes: &HashSet<(u32, Episode)>,
+    season: u32,
+    number: Episode,
+) -> EpisodeStatus {
+    let is_watched = series
+        .and_then(|series| series.get_season(season))
+        .is_some_and(|season| season.is_episode_watched(number));
+
+    if is_watched {
+        EpisodeStatus::Watched
+    } else if skipped_episodes.contains(&(season, number)) {
+        EpisodeStatus::Skipped
+    } else {
+        EpisodeStatus::Unwatched
+    }
+}
+
+/// Marks the episode like marking it by hand does, recording the change and running the hooks
+fn set_episode_status(series: &mut Series, season: u32, number: Episode, status: EpisodeStatus) {
+    match status {
+        EpisodeStatus::Watched => series.add_episode_unchecked(season, number),
+        EpisodeStatus::Skipped | EpisodeStatus::Unwatched | EpisodeStatus::Unaired => {
+            series.remove_episode(season, number)
+        }
+    }
+    DB.set_episode_skipped(
+        series.id(),
+        season,
+        number,
+        status == EpisodeStatus::Skipped,
+    );
+}
+
+/// Reads the flags and statuses spreadsheets are likely to have like `yes`, `x` or `watched`
+fn parse_status(status: &str) -> Result<EpisodeStatus, String> {
+    match status.to_lowercase().as_str() {
+        "watched" | "true" | "yes" | "y" | "1" | "x" => Ok(EpisodeStatus::Watched),
+        "skipped" | "skip" => Ok(EpisodeStatus::Skipped),
+        "unwatched" | "unaired" | "false" | "no" | "n" | "0" | "" => Ok(EpisodeStatus::Unwatched),
+        _ => Err(format!("'{}' is not a watched status", status)),
+    }
+}
+
+/// Reads an episode code, or an episode number of the season when a season is given
+fn parse_episode(episode: &str, season: Option<&str>) -> Result<EpisodeNumber, String> {
+    match (season.filter(|season| !season.is_empty()), episode.parse()) {
+        (Some(season), Ok(episode)) => Ok(EpisodeNumber::Seasonal {
+            season: season
+                .parse()
+                .map_err(|_| format!("'{}' is not a valid season number", season))?,
+            episode,
+        }),
+        _ => episode.parse(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AIRED: &str = "2020-01-01T20:00:00+00:00";
+    const UNAIRED: &str = "2999-01-01T20:00:00+00:00";
+
+    /// Two aired episodes in season 1, an aired special and an unaired episode in season 2
+    fn episode_list() -> EpisodeList {
+        let episodes = [
+            (1, 1, Some(1), AIRED),
+            (2, 1, Some(2), AIRED),
+            (3, 2, None, AIRED),
+            (4, 2, Some(1), UNAIRED),
+        ];
+        let episodes: Vec<String> = episodes
+            .into_iter()
+            .map(|(id, season, number, airstamp)| {
+                format!(
+                    r#"{{"id":{},"name":"","season":{},"number":{},"runtime":null,"airdate":null,"airtime":"","airstamp":"{}","rating":{{"average":null}},"image":null,"summary":null,"show":null,"_links":{{"show":{{"href":""}}}},"_embedded":null}}"#,
+                    id,
+                    season,
+                    number.map_or("null".to_owned(), |number: u32| number.to_string()),
+                    airstamp
+                )
+            })
+            .collect();
+        EpisodeList::with_cache(1, &format!("[{}]", episodes.join(","))).unwrap()
+    }
+
+    fn read(csv: &str) -> (BTreeMap<(u32, Episode), EpisodeStatus>, Vec<InvalidRow>) {
+        let mut records = parse_csv(csv).into_iter();
+        let columns = ChecklistColumns::new(&records.next().unwrap().1).unwrap();
+        read_rows(records, &columns, &episode_list(), "Series")
+    }
+
+    fn invalid_lines(invalid_rows: &[InvalidRow]) -> Vec<usize> {
+        invalid_rows.iter().map(|row| row.line).collect()
+    }
+
+    #[test]
+    fn episode_codes_and_season_columns_are_read_alike() {
+        let expected = BTreeMap::from([
+            ((1, 1), EpisodeStatus::Watched),
+            ((1, 2), EpisodeStatus::Skipped),
+        ]);
+
+        let (statuses, invalid_rows) = read("episode,status\nS01E01,watched\n2,skipped\n");
+        assert_eq!(statuses, expected);
+        assert!(invalid_rows.is_empty());
+
+        let (statuses, invalid_rows) = read("season,episode,status\n1,1,watched\n1,2,skipped\n");
+        assert_eq!(statuses, expected);
+        assert!(invalid_rows.is_empty());
+
+        // A code still works next to an empty season
+        let (statuses, _) = read("season,episode,status\n,S01E02,skipped\n");
+        assert_eq!(statuses, BTreeMap::from([((1, 2), EpisodeStatus::Skipped)]));
+    }
+
+    #[test]
+    fn status_aliases_are_read() {
+        for watched in ["watched", "TRUE", "yes", "y", "1", "x"] {
+            assert_eq!(parse_status(watched), Ok(EpisodeStatus::Watched));
+        }
+        for skipped in ["skipped", "Skip"] {
+            assert_eq!(parse_status(skipped), Ok(EpisodeStatus::Skipped));
+        }
+        for unwatched in ["unwatched", "unaired", "false", "No", "n", "0", ""] {
+            assert_eq!(parse_status(unwatched), Ok(EpisodeStatus::Unwatched));
+        }
+        assert!(parse_status("maybe").is_err());
+    }
+
+    #[test]
+    fn unaired_episodes_can_only_be_unwatched() {
+        let (statuses, invalid_rows) = read("episode,watched\nS02E01,yes\n");
+        assert!(statuses.is_empty());
+        assert_eq!(invalid_lines(&invalid_rows), [2]);
+
+        let (statuses, invalid_rows) = read("episode,watched\nS02E01,no\n");
+        assert_eq!(
+            statuses,
+            BTreeMap::from([((2, 1), EpisodeStatus::Unwatched)])
+        );
+        assert!(invalid_rows.is_empty());
+    }
+
+    #[test]
+    fn later_duplicate_rows_take_over() {
+        let (statuses, invalid_rows) =
+            read("episode,status\nS01E01,watched\nE1,skipped\nS01E01,unwatched\n");
+        assert_eq!(
+            statuses,
+            BTreeMap::from([((1, 1), EpisodeStatus::Unwatched)])
+        );
+        assert!(invalid_rows.is_empty());
+    }
+
+    #[test]
+    fn malformed_rows_are_left_out_with_their_line() {
+        let csv = "\
+season,episode,status
+1,1,watched
+one,2,watched
+1,two,watched
+1,1,maybe
+3,1,watched
+2,,watched
+1
+";
+        let (statuses, invalid_rows) = read(csv);
+        assert_eq!(statuses, BTreeMap::from([((1, 1), EpisodeStatus::Watched)]));
+        assert_eq!(invalid_lines(&invalid_rows), [3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn missing_columns_are_reported() {
+        assert!(ChecklistColumns::new(&["season".to_owned(), "status".to_owned()]).is_err());
+        assert!(ChecklistColumns::new(&["episode".to_owned()]).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! # CSV export of the watch history
+//!
+//! Comma separated values with a header row, quoted where needed so that spreadsheet
+//! applications read series and episode names containing commas correctly. Files written
+//! by spreadsheet applications are read back the same way when importing, including quoted
+//! fields spanning several lines.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::core::watch_history::WatchEntry;
+use crate::gui::helpers::season_episode_str_gen;
+
+const HEADER: [&str; 5] = [
+    "watched_at",
+    "series_id",
+    "series",
+    "episode",
+    "episode_name",
+];
+
+pub fn watch_history_to_csv(history: &[WatchEntry]) -> String {
+    let mut csv = to_csv_line(HEADER);
+
+    for entry in history {
+        csv.push_str(&to_csv_line([
+            entry
+                .watched_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .as_str(),
+            entry.series_id.to_string().as_str(),
+            &entry.series_name,
+            &season_episode_str_gen(entry.season, entry.number),
+            entry.episode_name.as_deref().unwrap_or_default(),
+        ]));
+    }
+
+    csv
+}
+
+pub fn to_csv_line<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    let fields: Vec<String> = fields.into_iter().map(escape_field).collect();
+    let mut line = fields.join(",");
+    line.push('\n');
+    line
+}
+
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Splits the content into records of fields, undoing the quoting done when writing them
+///
+/// Quoted fields can span several lines, so every record comes with the line it starts on,
+/// counting from 1. Blank lines are left out.
+pub fn parse_csv(csv: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = vec![];
+    let mut chars = csv.chars().peekable();
+    let mut line = 1;
+
+    while chars.peek().is_some() {
+        let (fields, read_lines) = parse_record(&mut chars);
+
+        let is_blank = fields.len() == 1 && fields[0].trim().is_empty();
+        if !is_blank {
+            records.push((line, fields));
+        }
+        line += read_lines;
+    }
+
+    records
+}
+
+/// Splits a line into its fields, undoing the quoting done when writing it
+pub fn parse_csv_line(line: &str) -> Vec<String> {
+    parse_record(&mut line.chars().peekable()).0
+}
+
+/// Reads the fields of the next record, returning them with the amount of lines read
+fn parse_record(chars: &mut Peekable<Chars>) -> (Vec<String>, usize) {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut read_lines = 0;
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if is_quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => is_quoted = !is_quoted,
+            ',' if !is_quoted => fields.push(std::mem::take(&mut field)),
+            '\n' if !is_quoted => {
+                read_lines += 1;
+                break;
+            }
+            '\r' if !is_quoted && matches!(chars.peek(), Some('\n') | None) => {}
+            char => {
+                if char == '\n' {
+                    read_lines += 1;
+                }
+                field.push(char)
+            }
+        }
+    }
+    fields.push(field);
+
+    (fields, read_lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_fields_are_split() {
+        assert_eq!(parse_csv_line("1,2,Pilot"), vec!["1", "2", "Pilot"]);
+        assert_eq!(parse_csv_line("1,,\r\n"), vec!["1", "", ""]);
+        assert_eq!(parse_csv_line(""), vec![""]);
+    }
+
+    #[test]
+    fn quoting_is_undone() {
+        assert_eq!(
+            parse_csv_line(r#"1,"Hello, Goodbye","The ""Best"" One""#),
+            vec!["1", "Hello, Goodbye", r#"The "Best" One"#]
+        );
+    }
+
+    #[test]
+    fn written_lines_are_read_back() {
+        let fields = ["2024-01-01 20:00", "Tom, Jerry", "\"quoted\"", ""];
+        assert_eq!(parse_csv_line(&to_csv_line(fields)), fields);
+    }
+
+    #[test]
+    fn quoted_fields_can_span_lines() {
+        let csv = "season,episode,name\r\n1,1,\"Pilot,\nPart 1\"\r\n\r\n1,2,Second\n";
+
+        assert_eq!(
+            parse_csv(csv),
+            vec![
+                (1, vec!["season".into(), "episode".into(), "name".into()]),
+                (2, vec!["1".into(), "1".into(), "Pilot,\nPart 1".into()]),
+                (5, vec!["1".into(), "2".into(), "Second".into()]),
+            ]
+        );
+    }
+}
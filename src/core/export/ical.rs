@@ -0,0 +1,76 @@
+//! # iCalendar export of binge plans
+//!
+//! Writes the suggested viewing days of a binge plan as all day events so that they can be
+//! imported into any calendar application.
+
+use std::path;
+
+use anyhow::Context;
+use chrono::{Duration, Utc};
+
+use crate::core::database::BingePlan;
+use crate::core::deep_link::DeepLink;
+
+/// Lines of an iCalendar file have to end with CRLF
+const LINE_ENDING: &str = "\r\n";
+
+pub fn binge_plan_to_ical(binge_plan: &BingePlan) -> String {
+    let timestamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!("PRODID:-//{}//Binge Plan//EN", env!("CARGO_PKG_NAME")),
+    ];
+
+    for day in binge_plan.days.iter() {
+        lines.extend([
+            "BEGIN:VEVENT".to_owned(),
+            format!(
+                "UID:{}-{}-{}",
+                env!("CARGO_PKG_NAME"),
+                binge_plan.series_id,
+                day.date.format("%Y%m%d")
+            ),
+            format!("DTSTAMP:{}", timestamp),
+            format!("DTSTART;VALUE=DATE:{}", day.date.format("%Y%m%d")),
+            format!(
+                "DTEND;VALUE=DATE:{}",
+                (day.date + Duration::days(1)).format("%Y%m%d")
+            ),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!(
+                    "Watch {} {}",
+                    binge_plan.series_name,
+                    day.episodes_range()
+                ))
+            ),
+            format!("URL:{}", DeepLink::Series(binge_plan.series_id)),
+            "TRANSP:TRANSPARENT".to_owned(),
+            "END:VEVENT".to_owned(),
+        ]);
+    }
+
+    lines.push("END:VCALENDAR".to_owned());
+
+    let mut ical = lines.join(LINE_ENDING);
+    ical.push_str(LINE_ENDING);
+    ical
+}
+
+pub async fn write_binge_plan(
+    binge_plan: &BingePlan,
+    file_path: impl AsRef<path::Path>,
+) -> anyhow::Result<()> {
+    tokio::fs::write(file_path, binge_plan_to_ical(binge_plan))
+        .await
+        .context("failed to write the iCalendar file")
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,205 @@
+//! # RSS feed of tracked series episodes
+//!
+//! Generates an RSS 2.0 feed containing the recently aired and upcoming episodes
+//! of all the tracked series. The feed can either be written to a file or served
+//! over http on a local port so that it can be consumed by feed readers.
+
+use std::path;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Local};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{error, info};
+
+use crate::core::api::tv_maze::episodes_information::Episode;
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::caching::episode_list::EpisodeList;
+use crate::core::caching::refresh_queue::{self, RefreshPriority};
+use crate::core::caching::series_list::SeriesList;
+use crate::core::deep_link::DeepLink;
+use crate::gui::helpers::season_episode_str_gen;
+
+/// How far into the past and future episodes are included in the feed
+const FEED_WINDOW_DAYS: i64 = 7;
+
+struct FeedItem {
+    series_info: SeriesMainInformation,
+    episode: Episode,
+    release_time: DateTime<Local>,
+}
+
+impl FeedItem {
+    fn to_xml(&self) -> String {
+        let episode_code = self
+            .episode
+            .number
+            .map(|number| season_episode_str_gen(self.episode.season, number))
+            .unwrap_or_else(|| format!("Season {} Special", self.episode.season));
+
+        let state = if self.release_time > Local::now() {
+            "Airs"
+        } else {
+            "Aired"
+        };
+
+        let title = format!(
+            "{} {}: {}",
+            self.series_info.name, episode_code, self.episode.name
+        );
+        let description = format!(
+            "{} on {}",
+            state,
+            self.release_time.format("%A, %B %e %Y at %H:%M")
+        );
+        let link = DeepLink::Series(self.series_info.id).to_string();
+        let guid = format!("series-troxide-{}-{}", self.series_info.id, episode_code);
+
+        format!(
+            "    <item>\n      <title>{}</title>\n      <link>{}</link>\n      <description>{}</description>\n      <pubDate>{}</pubDate>\n      <guid isPermaLink=\"false\">{}</guid>\n    </item>\n",
+            escape_xml(&title),
+            escape_xml(&link),
+            escape_xml(&description),
+            self.release_time.to_rfc2822(),
+            escape_xml(&guid),
+        )
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+async fn get_feed_items() -> anyhow::Result<Vec<FeedItem>> {
+    let series_infos = SeriesList::new().get_tracked_series_information().await?;
+
+    let now = Local::now();
+    let window = Duration::days(FEED_WINDOW_DAYS);
+
+    let handles: Vec<_> = series_infos
+        .iter()
+        .map(|series_info| {
+            refresh_queue::spawn(
+                RefreshPriority::Background,
+                EpisodeList::new(series_info.id),
+            )
+        })
+        .collect();
+
+    let mut feed_items = vec![];
+    for (handle, series_info) in handles.into_iter().zip(series_infos) {
+        // A series whose episodes can't be loaded is left out instead of failing the whole feed
+        let episode_list = match handle.await {
+            Ok(Ok(episode_list)) => episode_list,
+            Ok(Err(err)) => {
+                error!(
+                    "failed to get the episode list of '{}' for the feed: {}",
+                    series_info.name, err
+                );
+                continue;
+            }
+            Err(err) => {
+                error!(
+                    "failed to get the episode list of '{}' for the feed: {}",
+                    series_info.name, err
+                );
+                continue;
+            }
+        };
+
+        episode_list
+            .get_all_episodes()
+            .iter()
+            .filter_map(|episode| {
+                episode
+                    .local_date_time()
+                    .ok()
+                    .map(|release_time| (episode, release_time))
+            })
+            .filter(|(_, release_time)| {
+                (*release_time - now).num_seconds().abs() <= window.num_seconds()
+            })
+            .for_each(|(episode, release_time)| {
+                feed_items.push(FeedItem {
+                    series_info: series_info.clone(),
+                    episode: episode.clone(),
+                    release_time,
+                })
+            });
+    }
+
+    // Latest releases first as expected by most feed readers
+    feed_items.sort_unstable_by_key(|feed_item| std::cmp::Reverse(feed_item.release_time));
+
+    Ok(feed_items)
+}
+
+/// Generates the RSS feed of the recently aired and upcoming tracked episodes
+pub async fn generate_feed() -> anyhow::Result<String> {
+    let items: String = get_feed_items()
+        .await?
+        .iter()
+        .map(FeedItem::to_xml)
+        .collect();
+
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n  <channel>\n    <title>Series Troxide</title>\n    <link>https://github.com/MaarifaMaarifa/series-troxide</link>\n    <description>Recently aired and upcoming episodes of your tracked series</description>\n    <lastBuildDate>{}</lastBuildDate>\n{}  </channel>\n</rss>\n",
+        Local::now().to_rfc2822(),
+        items
+    ))
+}
+
+/// Writes the RSS feed to the given path
+pub async fn export_feed(path: impl AsRef<path::Path>) -> anyhow::Result<()> {
+    let feed = generate_feed().await?;
+    tokio::fs::write(path, feed)
+        .await
+        .context("failed to write rss feed")
+}
+
+/// Serves the RSS feed over http on the given local port
+///
+/// The feed is regenerated on every request so that it's always up to date.
+pub async fn serve_feed(port: u16) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .context("failed to bind rss feed port")?;
+
+    info!("serving rss feed at http://127.0.0.1:{}", port);
+
+    loop {
+        let (mut stream, address) = listener
+            .accept()
+            .await
+            .context("failed to accept rss feed connection")?;
+
+        tokio::spawn(async move {
+            // The request itself doesn't matter, we always respond with the feed
+            let mut request_buffer = [0; 1024];
+            if let Err(err) = stream.read(&mut request_buffer).await {
+                error!("failed to read request from {}: {}", address, err);
+                return;
+            }
+
+            let response = match generate_feed().await {
+                Ok(feed) => format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/rss+xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    feed.len(),
+                    feed
+                ),
+                Err(err) => {
+                    error!("failed to generate rss feed: {}", err);
+                    "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned()
+                }
+            };
+
+            if let Err(err) = stream.write_all(response.as_bytes()).await {
+                error!("failed to send rss feed to {}: {}", address, err);
+            }
+        });
+    }
+}
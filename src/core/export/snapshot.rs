@@ -0,0 +1,209 @@
+//! # Watch party snapshots
+//!
+//! A snapshot is a compact description of the watch state of a single series that can
+//! be shared with another Series Troxide user so that both can align their progress
+//! before watching together. Snapshots are shared either as a file or as a single line
+//! string that can be pasted through the clipboard.
+//!
+//! Importing a snapshot only ever adds watched episodes, episodes already watched
+//! locally are left untouched.
+
+use std::collections::BTreeMap;
+use std::path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::core::database;
+
+const CURRENT_SNAPSHOT_VERSION: u16 = 1;
+
+/// Prefix identifying a snapshot string among anything else that can end up in the clipboard
+const SNAPSHOT_PREFIX: &str = "troxide-snapshot:";
+
+/// The file extension used when saving snapshots to files
+pub const SNAPSHOT_FILE_EXTENSION: &str = "troxide";
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("IO error: {0}")]
+    Io(std::io::Error),
+    #[error("the provided text is not a series troxide snapshot")]
+    NotASnapshot,
+    #[error("incompatible version. Expected version {0}, found {1}")]
+    Version(u16, u16),
+    #[error("deserialization error: {0}")]
+    Deserialization(serde_json::Error),
+    #[error("invalid episode range '{0}'")]
+    InvalidEpisodeRange(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(rename = "v")]
+    version: u16,
+    #[serde(rename = "id")]
+    series_id: u32,
+    #[serde(rename = "n")]
+    series_name: String,
+    /// Watched episodes of each season written as ranges i.e. `1-10,12`
+    #[serde(rename = "s")]
+    seasons: BTreeMap<u32, String>,
+}
+
+impl Snapshot {
+    /// Creates a snapshot of the current watch state of the given series
+    ///
+    /// Returns `None` when the series is not in the database.
+    pub fn from_db(series_id: u32) -> Option<Self> {
+        let series = database::DB.get_series(series_id)?;
+
+        let seasons = series
+            .get_seasons()
+            .filter(|(_, season)| season.get_total_episodes() != 0)
+            .map(|(season_number, season)| {
+                let mut episodes: Vec<_> = season.get_episodes().collect();
+                episodes.sort_unstable();
+                (season_number, episodes_to_ranges(&episodes))
+            })
+            .collect();
+
+        Some(Self {
+            version: CURRENT_SNAPSHOT_VERSION,
+            series_id,
+            series_name: series.get_name().to_owned(),
+            seasons,
+        })
+    }
+
+    pub fn get_series_id(&self) -> u32 {
+        self.series_id
+    }
+
+    pub fn get_series_name(&self) -> &str {
+        &self.series_name
+    }
+
+    /// Parses a snapshot from it's shareable string
+    pub fn from_share_string(share_string: &str) -> Result<Self, SnapshotError> {
+        let json = share_string
+            .trim()
+            .strip_prefix(SNAPSHOT_PREFIX)
+            .ok_or(SnapshotError::NotASnapshot)?;
+
+        let snapshot =
+            serde_json::from_str::<Self>(json).map_err(SnapshotError::Deserialization)?;
+
+        if snapshot.version != CURRENT_SNAPSHOT_VERSION {
+            return Err(SnapshotError::Version(
+                CURRENT_SNAPSHOT_VERSION,
+                snapshot.version,
+            ));
+        }
+
+        Ok(snapshot)
+    }
+
+    /// A single line string of the snapshot suitable for the clipboard
+    pub fn to_share_string(&self) -> String {
+        format!(
+            "{}{}",
+            SNAPSHOT_PREFIX,
+            serde_json::to_string(self).expect("snapshot serialization")
+        )
+    }
+
+    pub async fn read_from_file(path: impl AsRef<path::Path>) -> Result<Self, SnapshotError> {
+        let share_string = tokio::fs::read_to_string(path)
+            .await
+            .map_err(SnapshotError::Io)?;
+        Self::from_share_string(&share_string)
+    }
+
+    pub async fn write_to_file(&self, path: impl AsRef<path::Path>) -> Result<(), SnapshotError> {
+        tokio::fs::write(path, self.to_share_string())
+            .await
+            .map_err(SnapshotError::Io)
+    }
+
+    /// Marks all the episodes in the snapshot as watched in the database
+    ///
+    /// The series is added to the database as tracked if it is not already there.
+    /// Returns the amount of episodes that were newly marked as watched.
+    pub fn apply(&self) -> Result<usize, SnapshotError> {
+        let mut seasons = Vec::with_capacity(self.seasons.len());
+        for (season_number, ranges) in self.seasons.iter() {
+            seasons.push((*season_number, ranges_to_episodes(ranges)?));
+        }
+
+        let mut series = database::DB.get_series(self.series_id).unwrap_or_else(|| {
+            let mut series = database::Series::new(self.series_name.clone(), self.series_id);
+            series.mark_tracked();
+            series
+        });
+
+        let mut marked_episodes = 0;
+        for (season_number, episodes) in seasons {
+            for episode in episodes {
+                let is_watched = series
+                    .get_season(season_number)
+                    .map(|season| season.is_episode_watched(episode))
+                    .unwrap_or(false);
+
+                if !is_watched {
+                    series.add_episode_unchecked(season_number, episode);
+                    marked_episodes += 1;
+                }
+            }
+        }
+
+        Ok(marked_episodes)
+    }
+}
+
+/// Compacts sorted episode numbers into ranges i.e. `[1, 2, 3, 5]` becomes `1-3,5`
+fn episodes_to_ranges(episodes: &[database::Episode]) -> String {
+    let mut ranges: Vec<(database::Episode, database::Episode)> = vec![];
+
+    for episode in episodes {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == *episode => *end = *episode,
+            _ => ranges.push((*episode, *episode)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn ranges_to_episodes(ranges: &str) -> Result<Vec<database::Episode>, SnapshotError> {
+    let mut episodes = vec![];
+
+    for range in ranges.split(',').filter(|range| !range.is_empty()) {
+        let invalid_range = || SnapshotError::InvalidEpisodeRange(range.to_owned());
+
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (start, end),
+            None => (range, range),
+        };
+        let start: database::Episode = start.trim().parse().map_err(|_| invalid_range())?;
+        let end: database::Episode = end.trim().parse().map_err(|_| invalid_range())?;
+
+        if start > end {
+            return Err(invalid_range());
+        }
+
+        episodes.extend(start..=end);
+    }
+
+    Ok(episodes)
+}
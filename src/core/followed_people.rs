@@ -0,0 +1,182 @@
+//! # Followed people
+//!
+//! People (actors, creators etc) from the cast of series can be followed. Their credits
+//! are checked whenever the schedule is refreshed, new series and guest appearances
+//! found since following them are shown in the discover page and optionally notified.
+
+use std::collections::HashSet;
+
+use tracing::{error, info};
+
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::api::tv_maze::{episode_guests, people, ApiError};
+use super::caching::series_information::get_series_main_info_with_id;
+use super::database::{FollowedPerson, DB};
+use super::settings_config::SETTINGS;
+
+/// How many of the newest series of a person are remembered
+const NEW_SERIES_LIMIT: usize = 20;
+
+/// Series and guest episodes of a person
+struct Credits {
+    series: HashSet<u32>,
+    /// Episode ids and the ids of their series
+    episodes: Vec<(u32, u32)>,
+}
+
+/// Starts following the person, only credits coming after this are considered new
+pub async fn follow(person_id: u32, name: String) -> Result<(), ApiError> {
+    let credits = get_credits(person_id).await?;
+
+    DB.set_followed_person(&FollowedPerson {
+        id: person_id,
+        name,
+        known_series: credits.series,
+        known_episodes: credits
+            .episodes
+            .into_iter()
+            .map(|(episode_id, _)| episode_id)
+            .collect(),
+        new_series: vec![],
+    });
+    Ok(())
+}
+
+/// Looks for new series and guest appearances of the followed people
+pub async fn refresh_followed_people() {
+    for mut person in DB.get_followed_people() {
+        let credits = match get_credits(person.id).await {
+            Ok(credits) => credits,
+            Err(err) => {
+                error!("failed to check the credits of '{}': {}", person.name, err);
+                continue;
+            }
+        };
+
+        let mut new_series: Vec<u32> = credits
+            .series
+            .iter()
+            .filter(|series_id| !person.known_series.contains(series_id))
+            .copied()
+            .collect();
+
+        let new_episodes: Vec<_> = credits
+            .episodes
+            .iter()
+            .filter(|(episode_id, _)| !person.known_episodes.contains(episode_id))
+            .collect();
+
+        for (_, series_id) in new_episodes.iter() {
+            if !new_series.contains(series_id) {
+                new_series.push(*series_id);
+            }
+        }
+
+        if new_series.is_empty() {
+            continue;
+        }
+
+        info!(
+            "found {} new series for followed person '{}'",
+            new_series.len(),
+            person.name
+        );
+
+        notify_new_series(&person.name, &new_series).await;
+
+        person.known_series = credits.series;
+        person
+            .known_episodes
+            .extend(credits.episodes.iter().map(|(episode_id, _)| *episode_id));
+
+        person
+            .new_series
+            .retain(|series_id| !new_series.contains(series_id));
+        new_series.append(&mut person.new_series);
+        new_series.truncate(NEW_SERIES_LIMIT);
+        person.new_series = new_series;
+
+        DB.set_followed_person(&person);
+    }
+}
+
+/// Ids of the new series of all the followed people, newest first per person
+pub fn get_new_series_ids() -> Vec<u32> {
+    let mut series_ids = vec![];
+    for series_id in DB
+        .get_followed_people()
+        .into_iter()
+        .flat_map(|person| person.new_series)
+    {
+        if !series_ids.contains(&series_id) {
+            series_ids.push(series_id);
+        }
+    }
+    series_ids
+}
+
+/// Information of the newest series of the followed people
+pub async fn get_followed_people_series(amount: usize) -> Vec<SeriesMainInformation> {
+    let mut series_infos = Vec::with_capacity(amount);
+    for series_id in get_new_series_ids().into_iter().take(amount) {
+        match get_series_main_info_with_id(series_id).await {
+            Ok(series_info) => series_infos.push(series_info),
+            Err(err) => error!("failed to load series {}: {}", series_id, err),
+        }
+    }
+    series_infos
+}
+
+async fn get_credits(person_id: u32) -> Result<Credits, ApiError> {
+    let (cast_credits, guest_cast_credits) = tokio::try_join!(
+        people::get_cast_credits(person_id),
+        episode_guests::get_guest_cast_credits(person_id)
+    )?;
+
+    Ok(Credits {
+        series: cast_credits
+            .iter()
+            .map(|credit| credit.series_info().id)
+            .collect(),
+        episodes: guest_cast_credits
+            .iter()
+            .filter_map(|credit| {
+                let episode = credit.episode();
+                let series_id = episode.links.show.href.rsplit('/').next()?.parse().ok()?;
+                Some((episode.id, series_id))
+            })
+            .collect(),
+    })
+}
+
+async fn notify_new_series(person_name: &str, series_ids: &[u32]) {
+    let notify = SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .notifications
+        .followed_people;
+
+    if !notify {
+        return;
+    }
+
+    let mut series_names = Vec::with_capacity(series_ids.len());
+    for series_id in series_ids {
+        match get_series_main_info_with_id(*series_id).await {
+            Ok(series_info) => series_names.push(series_info.name),
+            Err(err) => error!("failed to get the name of series {}: {}", series_id, err),
+        }
+    }
+
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("Series Troxide")
+        .summary(&format!("New from {}", person_name))
+        .body(&series_names.join(", "))
+        .timeout(0)
+        .auto_icon()
+        .show()
+    {
+        error!("failed to show the followed person notification: {}", err);
+    }
+}
@@ -0,0 +1,176 @@
+//! # Watch event hooks
+//!
+//! Notifies user configured endpoints about watch events. Every event is POSTed as
+//! json to the configured webhook url and/or passed to the configured command through
+//! the `TROXIDE_EVENT` and `TROXIDE_PAYLOAD` environment variables.
+//!
+//! Hooks are run on their own thread so that triggering them never blocks the caller. They
+//! are triggered by the database as episodes get watched, however they were watched.
+//!
+//! Events are handled one after the other, every webhook request and command is given
+//! `HOOK_TIMEOUT` so that a hanging endpoint does not hold up the events behind it.
+
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use tracing::{error, info};
+
+use super::settings_config::{HookSettings, SETTINGS};
+
+/// How long a webhook request or a hook command can take before it is given up on
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    static ref HOOKS_SENDER: Mutex<mpsc::Sender<HookEvent>> = Mutex::new(spawn_hooks_runner());
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    EpisodeWatched {
+        series_id: u32,
+        series_name: String,
+        season: u32,
+        episode: u32,
+        timestamp: DateTime<Local>,
+    },
+    SeriesTracked {
+        series_id: u32,
+        series_name: String,
+        timestamp: DateTime<Local>,
+    },
+}
+
+impl HookEvent {
+    pub fn episode_watched(series_id: u32, series_name: String, season: u32, episode: u32) -> Self {
+        Self::EpisodeWatched {
+            series_id,
+            series_name,
+            season,
+            episode,
+            timestamp: Local::now(),
+        }
+    }
+
+    pub fn series_tracked(series_id: u32, series_name: String) -> Self {
+        Self::SeriesTracked {
+            series_id,
+            series_name,
+            timestamp: Local::now(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            HookEvent::EpisodeWatched { .. } => "episode_watched",
+            HookEvent::SeriesTracked { .. } => "series_tracked",
+        }
+    }
+}
+
+/// Runs the configured hooks for the given event
+///
+/// Does nothing when no hook is configured.
+pub fn trigger(event: HookEvent) {
+    let hook_settings = SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .hooks
+        .clone();
+    if !hook_settings.is_configured() {
+        return;
+    }
+
+    if let Err(err) = HOOKS_SENDER.lock().unwrap().send(event) {
+        error!("failed to send hook event: {}", err);
+    }
+}
+
+fn spawn_hooks_runner() -> mpsc::Sender<HookEvent> {
+    let (sender, receiver) = mpsc::channel::<HookEvent>();
+
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create tokio runtime");
+        let client = reqwest::Client::builder()
+            .timeout(HOOK_TIMEOUT)
+            .build()
+            .expect("failed to build the hooks client");
+
+        while let Ok(event) = receiver.recv() {
+            let hook_settings = SETTINGS
+                .read()
+                .unwrap()
+                .get_current_settings()
+                .hooks
+                .clone();
+            runtime.block_on(run_hooks(&client, &hook_settings, &event));
+        }
+    });
+
+    sender
+}
+
+async fn run_hooks(client: &reqwest::Client, hook_settings: &HookSettings, event: &HookEvent) {
+    let payload = serde_json::to_string(event).expect("hook event should be serializable");
+
+    if let Some(webhook_url) = &hook_settings.webhook_url {
+        info!("posting '{}' event to webhook", event.name());
+        let response = client
+            .post(webhook_url)
+            .header("Content-Type", "application/json")
+            .body(payload.clone())
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if !response.status().is_success() => {
+                error!("webhook responded with status code {}", response.status())
+            }
+            Err(err) => error!("failed to post to webhook: {}", err),
+            _ => {}
+        }
+    }
+
+    if let Some(command) = &hook_settings.command {
+        info!("running hook command for '{}' event", event.name());
+        let status = shell_command(command)
+            .env("TROXIDE_EVENT", event.name())
+            .env("TROXIDE_PAYLOAD", &payload)
+            .kill_on_drop(true)
+            .status();
+
+        match tokio::time::timeout(HOOK_TIMEOUT, status).await {
+            Ok(Ok(status)) if !status.success() => error!("hook command exited with {}", status),
+            Ok(Err(err)) => error!("failed to run hook command: {}", err),
+            Err(_) => error!("hook command took too long and was stopped"),
+            _ => {}
+        }
+    }
+}
+
+fn shell_command(command: &str) -> tokio::process::Command {
+    #[cfg(target_os = "windows")]
+    let mut shell = {
+        let mut shell = tokio::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let mut shell = {
+        let mut shell = tokio::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    shell.arg(command);
+    shell
+}
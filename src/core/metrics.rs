@@ -0,0 +1,131 @@
+//! # Metrics
+//!
+//! What the app is busy with, for the hidden debug panel: the api requests currently in
+//! flight, how often the cache had what was asked for and the last errors of the requests.
+//! Everything is kept in memory for the current session only.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+
+/// How many of the last errors are kept
+const MAX_RECENT_ERRORS: usize = 20;
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    static ref IN_FLIGHT_REQUESTS: Mutex<HashMap<u64, (String, Instant)>> =
+        Mutex::new(HashMap::new());
+    static ref CACHE_STATS: Mutex<HashMap<CacheKind, CacheStats>> = Mutex::new(HashMap::new());
+    static ref RECENT_ERRORS: Mutex<VecDeque<(DateTime<Local>, String)>> =
+        Mutex::new(VecDeque::with_capacity(MAX_RECENT_ERRORS));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheKind {
+    SeriesInformation,
+    EpisodeList,
+    Cast,
+    ImageList,
+    Images,
+}
+
+pub const ALL_CACHE_KINDS: [CacheKind; 5] = [
+    CacheKind::SeriesInformation,
+    CacheKind::EpisodeList,
+    CacheKind::Cast,
+    CacheKind::ImageList,
+    CacheKind::Images,
+];
+
+impl std::fmt::Display for CacheKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            CacheKind::SeriesInformation => "Series information",
+            CacheKind::EpisodeList => "Episode lists",
+            CacheKind::Cast => "Cast",
+            CacheKind::ImageList => "Image lists",
+            CacheKind::Images => "Images",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// The fraction of the lookups found in the cache, `None` before any lookup
+    pub fn hit_rate(&self) -> Option<f32> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f32 / lookups as f32)
+    }
+}
+
+/// Marks an api request as in flight until dropped
+pub struct RequestGuard(u64);
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        IN_FLIGHT_REQUESTS.lock().unwrap().remove(&self.0);
+    }
+}
+
+pub fn track_request(url: &str) -> RequestGuard {
+    let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    IN_FLIGHT_REQUESTS
+        .lock()
+        .unwrap()
+        .insert(id, (url.to_owned(), Instant::now()));
+    RequestGuard(id)
+}
+
+pub fn record_cache_lookup(kind: CacheKind, hit: bool) {
+    let mut cache_stats = CACHE_STATS.lock().unwrap();
+    let stats = cache_stats.entry(kind).or_default();
+    if hit {
+        stats.hits += 1;
+    } else {
+        stats.misses += 1;
+    }
+}
+
+pub fn record_error(error: impl std::fmt::Display) {
+    let mut recent_errors = RECENT_ERRORS.lock().unwrap();
+    if recent_errors.len() == MAX_RECENT_ERRORS {
+        recent_errors.pop_back();
+    }
+    recent_errors.push_front((Local::now(), error.to_string()));
+}
+
+/// The urls of the requests in flight with how long they have been, the longest first
+pub fn get_in_flight_requests() -> Vec<(String, Duration)> {
+    let mut requests: Vec<_> = IN_FLIGHT_REQUESTS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|(url, started)| (url.clone(), started.elapsed()))
+        .collect();
+    requests.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+    requests
+}
+
+pub fn get_cache_stats() -> Vec<(CacheKind, CacheStats)> {
+    let cache_stats = CACHE_STATS.lock().unwrap();
+    ALL_CACHE_KINDS
+        .into_iter()
+        .map(|kind| (kind, cache_stats.get(&kind).copied().unwrap_or_default()))
+        .collect()
+}
+
+/// The last errors, the latest first
+pub fn get_recent_errors() -> Vec<(DateTime<Local>, String)> {
+    RECENT_ERRORS.lock().unwrap().iter().cloned().collect()
+}
@@ -1,8 +1,42 @@
+pub mod advanced_search;
 pub mod api;
+pub mod app_lock;
+pub mod auto_marking;
+pub mod background;
+pub mod backup;
+pub mod binge_plan;
+pub mod bulk_actions;
 pub mod caching;
+pub mod catch_up;
 pub mod cli;
+pub mod content_filter;
+pub mod data_location;
 pub mod database;
+pub mod date_formatting;
+pub mod deep_link;
+pub mod digest;
+pub mod discover_filter;
+pub mod duplicates;
+pub mod episode_matching;
+pub mod export;
+pub mod followed_people;
+pub mod hooks;
+pub mod metrics;
 pub mod notifications;
+pub mod passwords;
 pub mod paths;
 pub mod posters_hiding;
+pub mod quick_stats;
+pub mod random_picker;
+pub mod recommendations;
+pub mod reconciliation;
+pub mod relinking;
+pub mod season_stats;
+pub mod secrets;
+pub mod series_updates;
 pub mod settings_config;
+pub mod smart_lists;
+pub mod statistics;
+pub mod sync;
+pub mod watch_goal;
+pub mod watch_history;
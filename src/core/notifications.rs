@@ -1,27 +1,52 @@
 use super::{
     api::tv_maze::{episodes_information::Episode, series_information::SeriesMainInformation},
-    caching::series_list,
+    caching::{episode_list::EpisodeBadge, series_list},
+    database::{self, SeriesStatus},
+    date_formatting,
+    deep_link::{self, DeepLink},
     paths, settings_config,
 };
 use anyhow::Context;
 use chrono::Duration;
 use notify::{recommended_watcher, EventHandler, Watcher};
-use std::sync::mpsc;
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
+/// The action identifiers of the release notification buttons
+#[cfg(all(unix, not(target_os = "macos")))]
+const MARK_WATCHED_ACTION: &str = "mark-watched";
+#[cfg(all(unix, not(target_os = "macos")))]
+const SNOOZE_ACTION: &str = "snooze";
+#[cfg(all(unix, not(target_os = "macos")))]
+const REMIND_TOMORROW_ACTION: &str = "remind-tomorrow";
+#[cfg(all(unix, not(target_os = "macos")))]
+const OPEN_ACTION: &str = "open";
+
+/// What the user did with a release notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// Only freedesktop notifications have buttons to act on them
+#[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+enum ReleaseAction {
+    Closed,
+    MarkedWatched,
+    Snoozed,
+    RemindedTomorrow,
+    Opened,
+}
+
 enum Signal {
     SettingsFileChanged,
     NotificationSent,
 }
 
 pub struct TroxideNotify {
-    signal_receiver: mpsc::Receiver<Signal>,
-    signal_sender: mpsc::Sender<Signal>,
+    signal_receiver: mpsc::UnboundedReceiver<Signal>,
+    signal_sender: mpsc::UnboundedSender<Signal>,
 }
 
 impl TroxideNotify {
     pub fn new() -> anyhow::Result<Self> {
-        let (signal_sender, signal_receiver) = mpsc::channel();
+        let (signal_sender, signal_receiver) = mpsc::unbounded_channel();
 
         let file_change_signal_sender = signal_sender.clone();
         std::thread::spawn(move || Self::file_change_watcher(file_change_signal_sender));
@@ -32,74 +57,77 @@ impl TroxideNotify {
         })
     }
 
-    pub fn run(&self) -> anyhow::Result<()> {
-        tokio::runtime::Runtime::new()?.block_on(async {
-            let mut current_notification_time_setting = get_current_notification_time_setting();
-
-            loop {
-                // This is the time before the actual release of an episode that should be used by the notification
-                // to send notifications before the actual release of an episode.
-                let duration_before_release =
-                    Duration::minutes(current_notification_time_setting as i64);
-
-                // Creating a handle for each episode release notification so that we can be able to abort them at anytime
-                // we want.
-                let notification_handles: Vec<_> = get_releases_with_duration_to_release()
-                    .await
-                    .into_iter()
-                    .map(|(series_info, episode, duration)| {
-                        (series_info, episode, duration - duration_before_release)
-                    })
-                    .filter(|(_, _, duration)| duration.to_std().is_ok())
-                    .map(|(series_info, episode, duration)| {
-                        let signal_sender = self.signal_sender.clone();
-                        tokio::spawn(async move {
-                            tracing::info!(
-                                "waiting {} minutes for \"{}'s\" notification",
-                                duration.num_minutes(),
-                                series_info.name,
-                            );
-                            tokio::time::sleep(duration.to_std().unwrap()).await;
-                            notify_episode_release(
-                                &series_info,
-                                &episode,
-                                current_notification_time_setting,
-                            );
-                            signal_sender.send(Signal::NotificationSent).unwrap();
-                        })
-                    })
-                    .collect();
-
-                match &self.signal_receiver.recv().unwrap() {
-                    Signal::SettingsFileChanged => {
-                        /*
-                        Since the settings file can change the time to notify before the actual release, our notifications will
-                        be waiting to notify with a delay that is no longer correct, so be obtain the current settings from the
-                        settings file and abort all the upcoming notifications and reobtain all of them in the next loop iteration
-                        TODO: Make it detect only when the nofification settings changed
-                        */
-                        tracing::info!("config file change detected, refreshing notifications");
-                        current_notification_time_setting = get_current_notification_time_setting();
-
-                        Self::abort_notifications(notification_handles);
-                    }
-                    Signal::NotificationSent => {
-                        /*
-                        When a new episode has been notified, when can't keep on using the same obtained episode releases as it might
-                        turn out that that series is being released regularly(weekly) and thus the currently obtained releases won't
-                        have that information. So we just abort all the handles to reobtain all the releases information in the next
-                        iteration of the loop.
-                        */
+    /// Notifies about the upcoming releases for as long as the program runs
+    pub async fn run(mut self) {
+        let mut current_notification_time_setting = get_current_notification_time_setting();
+
+        loop {
+            // This is the time before the actual release of an episode that should be used by the notification
+            // to send notifications before the actual release of an episode.
+            let duration_before_release =
+                Duration::minutes(current_notification_time_setting as i64);
+
+            // Creating a handle for each episode release notification so that we can be able to abort them at anytime
+            // we want.
+            let notification_handles: Vec<_> = get_releases_with_duration_to_release()
+                .await
+                .into_iter()
+                .map(|(series_info, episode, badge, duration)| {
+                    // Binge mode series are notified once the season is actually out
+                    let duration = if database::DB.is_binge_mode(series_info.id) {
+                        duration
+                    } else {
+                        duration - duration_before_release
+                    };
+                    (series_info, episode, badge, duration)
+                })
+                .filter(|(_, _, _, duration)| duration.to_std().is_ok())
+                .map(|(series_info, episode, badge, duration)| {
+                    let signal_sender = self.signal_sender.clone();
+                    tokio::spawn(async move {
                         tracing::info!(
-                            "episode release notification sent, refreshing notifications"
+                            "waiting {} minutes for \"{}'s\" notification",
+                            duration.num_minutes(),
+                            series_info.name,
+                        );
+                        tokio::time::sleep(duration.to_std().unwrap()).await;
+                        notify_episode_release(
+                            &series_info,
+                            &episode,
+                            badge,
+                            current_notification_time_setting,
                         );
+                        signal_sender.send(Signal::NotificationSent).unwrap();
+                    })
+                })
+                .collect();
 
-                        Self::abort_notifications(notification_handles);
-                    }
+            match &self.signal_receiver.recv().await.unwrap() {
+                Signal::SettingsFileChanged => {
+                    /*
+                    Since the settings file can change the time to notify before the actual release, our notifications will
+                    be waiting to notify with a delay that is no longer correct, so be obtain the current settings from the
+                    settings file and abort all the upcoming notifications and reobtain all of them in the next loop iteration
+                    TODO: Make it detect only when the nofification settings changed
+                    */
+                    tracing::info!("config file change detected, refreshing notifications");
+                    current_notification_time_setting = get_current_notification_time_setting();
+
+                    Self::abort_notifications(notification_handles);
+                }
+                Signal::NotificationSent => {
+                    /*
+                    When a new episode has been notified, when can't keep on using the same obtained episode releases as it might
+                    turn out that that series is being released regularly(weekly) and thus the currently obtained releases won't
+                    have that information. So we just abort all the handles to reobtain all the releases information in the next
+                    iteration of the loop.
+                    */
+                    tracing::info!("episode release notification sent, refreshing notifications");
+
+                    Self::abort_notifications(notification_handles);
                 }
             }
-        });
-        Ok(())
+        }
     }
 
     fn abort_notifications(notification_handles: Vec<JoinHandle<()>>) {
@@ -108,7 +136,7 @@ impl TroxideNotify {
             .for_each(|handle| handle.abort())
     }
 
-    fn file_change_watcher(signal_sender: mpsc::Sender<Signal>) {
+    fn file_change_watcher(signal_sender: mpsc::UnboundedSender<Signal>) {
         let file_watcher_event_handler = FileWatcherEventHandler::new(signal_sender);
         let mut settings_file_watcher = recommended_watcher(file_watcher_event_handler)
             .context("failed to create settings file watcher")
@@ -131,18 +159,32 @@ impl TroxideNotify {
     }
 }
 
-async fn get_releases_with_duration_to_release() -> Vec<(SeriesMainInformation, Episode, Duration)>
-{
+async fn get_releases_with_duration_to_release() -> Vec<(
+    SeriesMainInformation,
+    Episode,
+    Option<EpisodeBadge>,
+    Duration,
+)> {
     series_list::SeriesList::new()
         .get_upcoming_release_series_information_and_episodes()
         .await
         .context("failed to get upcoming series releases")
         .unwrap()
         .into_iter()
-        .map(|(series_info, next_episode, release_time)| {
+        // Dropped series are not worth being notified about
+        .filter(|(series_info, _, _, _)| {
+            database::DB.get_series_status(series_info.id) != SeriesStatus::Dropped
+        })
+        // Binge mode series are only notified about when their season finale airs
+        .filter(|(series_info, _, _, badge)| {
+            !database::DB.is_binge_mode(series_info.id)
+                || *badge == Some(EpisodeBadge::SeasonFinale)
+        })
+        .map(|(series_info, next_episode, release_time, badge)| {
             (
                 series_info,
                 next_episode,
+                badge,
                 release_time.get_remaining_release_duration(),
             )
         })
@@ -152,47 +194,200 @@ async fn get_releases_with_duration_to_release() -> Vec<(SeriesMainInformation,
 fn notify_episode_release(
     series_info: &SeriesMainInformation,
     episode: &Episode,
+    badge: Option<EpisodeBadge>,
     release_time_in_minute: u32,
 ) {
     let series_name = series_info.name.as_str();
     let episode_name = episode.name.as_str();
-    let episode_order = crate::gui::helpers::season_episode_str_gen(
-        episode.season,
-        episode
-            .number
-            .expect("an episode should have a valid number"),
-    );
+    let season_number = episode.season;
+    let episode_number = episode
+        .number
+        .expect("an episode should have a valid number");
+    let episode_order = crate::gui::helpers::season_episode_str_gen(season_number, episode_number);
+
+    let (notification_summary, notification_body) = if database::DB.is_binge_mode(series_info.id) {
+        (
+            format!("\"{}\" season {} is out", series_name, episode.season),
+            format!(
+                "{}: {} has aired, the whole season is ready to binge",
+                episode_order, episode_name
+            ),
+        )
+    } else {
+        let notification_summary = match badge {
+            Some(badge) => format!("\"{}\" {}", series_name, badge),
+            None => format!("\"{}\" episode release", series_name),
+        };
+        let notification_body = match episode.local_date_time() {
+            Ok(release_time) => format!(
+                "{}: {}, will be released in {} minutes at {}",
+                episode_order,
+                episode_name,
+                release_time_in_minute,
+                date_formatting::time_str(&release_time)
+            ),
+            Err(_) => format!(
+                "{}: {}, will be released in {} minutes",
+                episode_order, episode_name, release_time_in_minute
+            ),
+        };
+        (notification_summary, notification_body)
+    };
+
+    let snoozed_body = format!("{}: {}", episode_order, episode_name);
+    let series_id = series_info.id;
+    let release_time = episode.local_date_time().ok();
 
-    let notification_summary = format!("\"{}\" episode release", series_name);
+    // Not waiting as the notification can be snoozed for long
+    tokio::spawn(async move {
+        let mut body = notification_body;
+        loop {
+            let notification = {
+                let (summary, body) = (notification_summary.clone(), body.clone());
+                tokio::task::spawn_blocking(move || show_release_notification(&summary, &body))
+            };
 
-    let notification_body = format!(
-        "{}: {}, will be released in {} minutes",
-        episode_order, episode_name, release_time_in_minute
+            match notification.await.unwrap_or(ReleaseAction::Closed) {
+                ReleaseAction::Closed => break,
+                ReleaseAction::MarkedWatched => {
+                    // Episodes notified about before their release are marked once they are out
+                    if let Some(remaining) = release_time.and_then(|release_time| {
+                        (release_time - chrono::Local::now()).to_std().ok()
+                    }) {
+                        tokio::time::sleep(remaining).await;
+                    }
+                    mark_episode_watched(series_id, season_number, episode_number).await;
+                    break;
+                }
+                ReleaseAction::Snoozed => {
+                    let snooze_time = get_current_notification_settings().snooze_time;
+                    tracing::info!(
+                        "\"{}\" notification snoozed for {} minutes",
+                        notification_summary,
+                        snooze_time
+                    );
+                    tokio::time::sleep(std::time::Duration::from_secs(snooze_time as u64 * 60))
+                        .await;
+                    body = snoozed_body.clone();
+                }
+                ReleaseAction::RemindedTomorrow => {
+                    tracing::info!(
+                        "\"{}\" notification snoozed until tomorrow",
+                        notification_summary
+                    );
+                    // The reminder only shows when the app is still running by then
+                    tokio::time::sleep(std::time::Duration::from_secs(24 * 60 * 60)).await;
+                    body = snoozed_body.clone();
+                }
+                ReleaseAction::Opened => {
+                    open_series_page(series_id);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+async fn mark_episode_watched(series_id: u32, season_number: u32, episode_number: u32) {
+    let Some(mut series) = database::DB.get_series(series_id) else {
+        return;
+    };
+
+    if series.add_episode(season_number, episode_number).await {
+        tracing::info!(
+            "marked {} of \"{}\" as watched from the notification",
+            crate::gui::helpers::season_episode_str_gen(season_number, episode_number),
+            series.get_name()
+        );
+    } else {
+        tracing::warn!("the notified episode could not be marked as watched");
+    }
+}
+
+/// Opens the series page in the app through the same socket links are forwarded on
+fn open_series_page(series_id: u32) {
+    match deep_link::forward_to_running_instance(DeepLink::Series(series_id)) {
+        Ok(true) => {}
+        Ok(false) => tracing::warn!("the app is not open to show the notified series"),
+        Err(err) => tracing::error!("failed to open the notified series: {}", err),
+    }
+}
+
+/// Shows the release notification with its action buttons, returns what the user did with it
+///
+/// Blocks until the notification is acted on or closed.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn show_release_notification(summary: &str, body: &str) -> ReleaseAction {
+    let snooze_label = format!(
+        "Snooze {} minutes",
+        get_current_notification_settings().snooze_time
     );
 
-    notify_rust::Notification::new()
+    let notification = notify_rust::Notification::new()
         .appname("Series Troxide")
-        .summary(&notification_summary)
-        .body(&notification_body)
+        .summary(summary)
+        .body(body)
+        .action(MARK_WATCHED_ACTION, "Mark watched")
+        .action(SNOOZE_ACTION, &snooze_label)
+        .action(REMIND_TOMORROW_ACTION, "Remind me tomorrow")
+        .action(OPEN_ACTION, "Open")
+        .timeout(0)
+        .auto_icon()
+        .show();
+
+    let mut release_action = ReleaseAction::Closed;
+    match notification {
+        Ok(handle) => handle.wait_for_action(|action| {
+            release_action = match action {
+                MARK_WATCHED_ACTION => ReleaseAction::MarkedWatched,
+                SNOOZE_ACTION => ReleaseAction::Snoozed,
+                REMIND_TOMORROW_ACTION => ReleaseAction::RemindedTomorrow,
+                // Clicking the notification itself opens the series too
+                OPEN_ACTION | "default" => ReleaseAction::Opened,
+                _ => ReleaseAction::Closed,
+            }
+        }),
+        Err(err) => tracing::error!("failed to show notification: {}", err),
+    }
+    release_action
+}
+
+/// Shows the release notification, returns what the user did with it
+///
+/// Notification actions are only supported on freedesktop systems, elsewhere the notification
+/// has no buttons and can only be closed.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn show_release_notification(summary: &str, body: &str) -> ReleaseAction {
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("Series Troxide")
+        .summary(summary)
+        .body(body)
         .timeout(0)
         .auto_icon()
         .show()
-        .expect("failed to show notification");
+    {
+        tracing::error!("failed to show notification: {}", err)
+    }
+    ReleaseAction::Closed
 }
 
 struct FileWatcherEventHandler {
-    sender: mpsc::Sender<Signal>,
+    sender: mpsc::UnboundedSender<Signal>,
 }
 
 fn get_current_notification_time_setting() -> u32 {
+    get_current_notification_settings().time_to_notify
+}
+
+fn get_current_notification_settings() -> settings_config::NotificationSettings {
     settings_config::Settings::new()
         .get_current_settings()
         .notifications
-        .time_to_notify
+        .clone()
 }
 
 impl FileWatcherEventHandler {
-    fn new(sender: mpsc::Sender<Signal>) -> Self {
+    fn new(sender: mpsc::UnboundedSender<Signal>) -> Self {
         Self { sender }
     }
 }
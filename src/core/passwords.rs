@@ -0,0 +1,96 @@
+//! # Passwords
+//!
+//! Hashing of the PINs and passwords protecting parts of the app, like the content filter
+//! and the app lock. They are hashed with Argon2id and a random salt, and stored as PHC
+//! strings in the config file.
+//!
+//! Hashes from older versions, unsalted SHA-256 in hex, are still accepted so that nobody is
+//! locked out. They should be replaced once verified, see [`needs_rehash`].
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use hmac::digest::CtOutput;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+const SALT_LEN: usize = 16;
+
+/// Hashes the password with a new random salt
+pub fn hash(password: &str) -> String {
+    let mut salt = [0; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt).expect("the salt length is valid");
+
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("hashing with the default parameters does not fail")
+        .to_string()
+}
+
+/// Whether the password matches the hash
+pub fn verify(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+        Err(_) => verify_legacy(password, hash),
+    }
+}
+
+/// Whether the hash comes from an older version and should be replaced
+pub fn needs_rehash(hash: &str) -> bool {
+    PasswordHash::new(hash).is_err()
+}
+
+/// Verifies an unsalted SHA-256 hash in hex
+fn verify_legacy(password: &str, hash: &str) -> bool {
+    let Some(hash) = from_hex(hash) else {
+        return false;
+    };
+    if hash.len() != Sha256::output_size() {
+        return false;
+    }
+
+    let expected = CtOutput::<Sha256>::new(Sha256::digest(password.as_bytes()));
+    expected == CtOutput::new(*sha2::digest::Output::<Sha256>::from_slice(&hash))
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_are_salted() {
+        let first_hash = hash("1234");
+        let second_hash = hash("1234");
+
+        assert_ne!(first_hash, second_hash);
+        assert!(verify("1234", &first_hash));
+        assert!(verify("1234", &second_hash));
+        assert!(!verify("4321", &first_hash));
+        assert!(!needs_rehash(&first_hash));
+    }
+
+    #[test]
+    fn legacy_hashes_are_accepted() {
+        let legacy_hash = format!("{:x}", Sha256::digest(b"1234"));
+
+        assert!(verify("1234", &legacy_hash));
+        assert!(!verify("4321", &legacy_hash));
+        assert!(needs_rehash(&legacy_hash));
+    }
+
+    #[test]
+    fn invalid_hashes_never_verify() {
+        assert!(!verify("", ""));
+        assert!(!verify("1234", "not a hash"));
+        assert!(!verify("1234", "abcd"));
+    }
+}
@@ -25,7 +25,7 @@ impl Paths {
         ProjectDirs::from("", "", env!("CARGO_PKG_NAME")).expect("could not get the program paths")
     }
 
-    pub fn get_data_dir_path(&self) -> Cow<PathBuf> {
+    pub fn get_data_dir_path(&self) -> Cow<'_, PathBuf> {
         if let Some(data_path) = &self.custom_data_dir_path {
             Cow::Borrowed(data_path)
         } else {
@@ -33,7 +33,7 @@ impl Paths {
         }
     }
 
-    pub fn get_config_dir_path(&self) -> Cow<PathBuf> {
+    pub fn get_config_dir_path(&self) -> Cow<'_, PathBuf> {
         if let Some(config_path) = &self.custom_config_dir_path {
             Cow::Borrowed(config_path)
         } else {
@@ -41,7 +41,7 @@ impl Paths {
         }
     }
 
-    pub fn get_cache_dir_path(&self) -> Cow<PathBuf> {
+    pub fn get_cache_dir_path(&self) -> Cow<'_, PathBuf> {
         if let Some(cache_path) = &self.custom_cache_dir_path {
             Cow::Borrowed(cache_path)
         } else {
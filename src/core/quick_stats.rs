@@ -0,0 +1,70 @@
+//! # Quick stats
+//!
+//! Totals of the tracked series shown at a glance, like in the tooltip of the My Shows tab.
+//! They are meant to be kept in memory and only loaded again when the database changes.
+
+use super::caching::episode_list::EpisodeList;
+use super::caching::refresh_queue::{self, RefreshPriority};
+use super::caching::series_list::SeriesList;
+use super::database::DB;
+
+#[derive(Debug, Clone, Default)]
+pub struct QuickStats {
+    pub tracked_series: usize,
+    pub unwatched_episodes: usize,
+    /// Based on the average runtime of each series
+    pub remaining_minutes: u32,
+}
+
+impl QuickStats {
+    pub async fn load() -> anyhow::Result<Self> {
+        let tracked_series_information = SeriesList::new().get_tracked_series_information().await?;
+
+        let handles: Vec<_> = tracked_series_information
+            .into_iter()
+            .map(|series_info| {
+                refresh_queue::spawn(RefreshPriority::Background, async move {
+                    let episode_list = EpisodeList::new(series_info.id).await?;
+                    let watched_episodes = DB
+                        .get_series(series_info.id)
+                        .map(|series| series.get_total_episodes())
+                        .unwrap_or_default()
+                        + DB.get_total_skipped_episodes(series_info.id, None);
+                    let unwatched_episodes = episode_list
+                        .get_total_watchable_episodes()
+                        .saturating_sub(watched_episodes);
+
+                    anyhow::Ok((
+                        unwatched_episodes,
+                        series_info.average_runtime.unwrap_or_default(),
+                    ))
+                })
+            })
+            .collect();
+
+        let mut quick_stats = Self {
+            tracked_series: handles.len(),
+            ..Self::default()
+        };
+
+        for handle in handles {
+            let (unwatched_episodes, average_runtime) = handle.await??;
+            quick_stats.unwatched_episodes += unwatched_episodes;
+            quick_stats.remaining_minutes += unwatched_episodes as u32 * average_runtime;
+        }
+
+        Ok(quick_stats)
+    }
+}
+
+impl std::fmt::Display for QuickStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} shows tracked\n{} episodes unwatched\n{} hours remaining",
+            self.tracked_series,
+            self.unwatched_episodes,
+            self.remaining_minutes / 60
+        )
+    }
+}
@@ -0,0 +1,75 @@
+//! # Random picks for when you can't decide what to watch
+//!
+//! Picks either a random unwatched episode from the tracked series or a random
+//! popular series that is not already in the database.
+
+use anyhow::Context;
+use rand::seq::SliceRandom;
+
+use super::api::tv_maze::episodes_information::Episode;
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::caching::episode_list::EpisodeList;
+use super::caching::refresh_queue::{self, RefreshPriority};
+use super::caching::series_list::SeriesList;
+use super::caching::tv_schedule::full_schedule::FullSchedule;
+use super::database;
+
+/// How many of the most popular series are considered when picking a random series
+const POPULAR_SERIES_POOL: usize = 100;
+
+/// Picks a random aired episode that has not been watched from all the tracked series
+///
+/// Returns `None` when every aired episode of the tracked series has been watched.
+pub async fn pick_random_unwatched_episode(
+) -> anyhow::Result<Option<(SeriesMainInformation, Episode)>> {
+    let series_infos = SeriesList::new()
+        .get_tracked_series_information()
+        .await
+        .context("failed to get tracked series information")?;
+
+    let handles: Vec<_> = series_infos
+        .iter()
+        .map(|series_info| {
+            refresh_queue::spawn(RefreshPriority::Visible, EpisodeList::new(series_info.id))
+        })
+        .collect();
+
+    let mut candidates = Vec::new();
+    for (series_info, handle) in series_infos.iter().zip(handles) {
+        let episode_list = handle.await??;
+        let database_series = database::DB.get_series(series_info.id);
+
+        episode_list
+            .get_all_episodes()
+            .iter()
+            .filter(|episode| episode.is_future_release() == Ok(false))
+            .filter(|episode| {
+                let Some(episode_number) = episode.number else {
+                    return false;
+                };
+                !database_series
+                    .as_ref()
+                    .and_then(|series| series.get_season(episode.season))
+                    .map(|season| season.is_episode_watched(episode_number))
+                    .unwrap_or(false)
+            })
+            .for_each(|episode| candidates.push((series_info, episode.clone())));
+    }
+
+    Ok(candidates
+        .choose(&mut rand::thread_rng())
+        .map(|(series_info, episode)| ((*series_info).clone(), episode.clone())))
+}
+
+/// Picks a random popular series that is not already in the database
+pub fn pick_random_popular_series(full_schedule: &FullSchedule) -> Option<SeriesMainInformation> {
+    let candidates: Vec<_> = full_schedule
+        .get_popular_series(Some(POPULAR_SERIES_POOL))
+        .into_iter()
+        .filter(|series_info| database::DB.get_series(series_info.id).is_none())
+        .collect();
+
+    candidates
+        .choose(&mut rand::thread_rng())
+        .map(|series_info| (*series_info).clone())
+}
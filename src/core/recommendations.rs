@@ -0,0 +1,318 @@
+//! # Personalized series recommendations
+//!
+//! Scores series from the `FullSchedule` against what the user has watched so far.
+//! Every series in the database contributes it's genres and network/webchannel weighted
+//! by the amount of episodes watched, so that series watched more influence recommendations
+//! more. Series already in the database are never recommended.
+//!
+//! Apart from the general recommendations, "Because you watched" rows are generated
+//! for a few of the most watched tracked series, rotating daily among them.
+
+use std::collections::{HashMap, HashSet};
+use std::path;
+
+use chrono::{Datelike, Local};
+use tokio::fs;
+use tracing::warn;
+
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::api::tv_maze::Rated;
+use super::caching::series_information::get_series_main_info_with_id;
+use super::caching::series_list::SeriesList;
+use super::caching::tv_schedule::full_schedule::FullSchedule;
+use super::{database, paths};
+
+const DISMISSED_ROWS_FILENAME: &str = "dismissed-recommendation-rows";
+
+/// The amount of most watched series from which the daily anchors are picked
+const ANCHOR_CANDIDATES_AMOUNT: usize = 6;
+
+/// How much each aspect of a series contributes to it's final score
+const GENRE_WEIGHT: f32 = 3.0;
+const NETWORK_WEIGHT: f32 = 1.0;
+const RATING_WEIGHT: f32 = 1.5;
+
+/// What the user likes based on their watch history
+#[derive(Debug, Clone, Default)]
+pub struct UserProfile {
+    /// Genres and the fraction of watched episodes belonging to them
+    genres: HashMap<String, f32>,
+    /// Networks and webchannels and the fraction of watched episodes belonging to them
+    networks: HashMap<String, f32>,
+    /// The average rating of the watched series
+    average_rating: f32,
+    /// Series already in the database
+    known_series_ids: HashSet<u32>,
+}
+
+impl UserProfile {
+    /// Builds the user's profile from the series in the database
+    pub async fn new() -> anyhow::Result<Self> {
+        // Archived series are still part of the user's taste
+        let series_list = SeriesList::all();
+        let series_infos = series_list.get_all_series_information().await?;
+
+        let watched_episodes: HashMap<u32, usize> = database::DB
+            .get_series_collection()
+            .iter()
+            .map(|series| (series.id(), series.get_total_episodes()))
+            .collect();
+
+        Ok(Self::with_series_infos(&series_infos, &watched_episodes))
+    }
+
+    fn with_series_infos(
+        series_infos: &[SeriesMainInformation],
+        watched_episodes: &HashMap<u32, usize>,
+    ) -> Self {
+        let mut genres: HashMap<String, f32> = HashMap::new();
+        let mut networks: HashMap<String, f32> = HashMap::new();
+        let mut total_weight = 0.0;
+        let mut rating_sum = 0.0;
+        let mut rated_series = 0;
+
+        for series_info in series_infos {
+            // Tracked series without any watched episode still tell something about the user
+            let weight = watched_episodes
+                .get(&series_info.id)
+                .copied()
+                .unwrap_or_default()
+                .max(1) as f32;
+
+            total_weight += weight;
+
+            series_info.genres.iter().for_each(|genre| {
+                *genres.entry(genre.clone()).or_default() += weight;
+            });
+
+            if let Some(network_name) = network_name(series_info) {
+                *networks.entry(network_name.to_owned()).or_default() += weight;
+            }
+
+            if series_info.rating.average.is_some() {
+                rating_sum += series_info.rating();
+                rated_series += 1;
+            }
+        }
+
+        if total_weight > 0.0 {
+            genres.values_mut().for_each(|value| *value /= total_weight);
+            networks
+                .values_mut()
+                .for_each(|value| *value /= total_weight);
+        }
+
+        Self {
+            genres,
+            networks,
+            average_rating: if rated_series > 0 {
+                rating_sum / rated_series as f32
+            } else {
+                0.0
+            },
+            known_series_ids: series_infos
+                .iter()
+                .map(|series_info| series_info.id)
+                .collect(),
+        }
+    }
+
+    /// Whether the profile has enough data for recommendations
+    pub fn is_empty(&self) -> bool {
+        self.known_series_ids.is_empty()
+    }
+
+    /// Whether the given series is already in the database
+    pub fn is_known(&self, series_id: u32) -> bool {
+        self.known_series_ids.contains(&series_id)
+    }
+
+    /// Scores how likely the user is to enjoy the given series
+    pub fn score(&self, series_info: &SeriesMainInformation) -> f32 {
+        let genre_score: f32 = series_info
+            .genres
+            .iter()
+            .filter_map(|genre| self.genres.get(genre))
+            .sum();
+
+        let network_score = network_name(series_info)
+            .and_then(|network_name| self.networks.get(network_name))
+            .copied()
+            .unwrap_or_default();
+
+        // Series rated around or above what the user usually watches are preferred
+        let rating_score = if series_info.rating.average.is_some() {
+            let rating = series_info.rating();
+            (rating / 10.0) - ((self.average_rating - rating).max(0.0) / 10.0)
+        } else {
+            0.0
+        };
+
+        (genre_score * GENRE_WEIGHT)
+            + (network_score * NETWORK_WEIGHT)
+            + (rating_score * RATING_WEIGHT)
+    }
+}
+
+fn network_name(series_info: &SeriesMainInformation) -> Option<&str> {
+    series_info
+        .network
+        .as_ref()
+        .map(|network| network.name.as_str())
+        .or(series_info
+            .web_channel
+            .as_ref()
+            .map(|webchannel| webchannel.name.as_str()))
+}
+
+/// Returns series from the `FullSchedule` the user has not seen yet, best matches first
+pub fn get_recommended_series<'a>(
+    full_schedule: &'a FullSchedule,
+    user_profile: &UserProfile,
+    amount: usize,
+) -> Vec<&'a SeriesMainInformation> {
+    if user_profile.is_empty() {
+        return vec![];
+    }
+
+    let mut scored_series: Vec<(f32, &SeriesMainInformation)> = full_schedule
+        .get_series()
+        .into_iter()
+        .filter(|series_info| !user_profile.is_known(series_info.id))
+        .map(|series_info| (user_profile.score(series_info), series_info))
+        .filter(|(score, _)| *score > 0.0)
+        .collect();
+
+    scored_series.sort_unstable_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    scored_series
+        .into_iter()
+        .take(amount)
+        .map(|(_, series_info)| series_info)
+        .collect()
+}
+
+/// Returns series from the `FullSchedule` similar to the given anchor series, best matches first
+///
+/// Series the user has already seen are excluded.
+pub fn get_similar_series<'a>(
+    full_schedule: &'a FullSchedule,
+    anchor_series_info: &SeriesMainInformation,
+    user_profile: &UserProfile,
+    amount: usize,
+) -> Vec<&'a SeriesMainInformation> {
+    let anchor_genres: HashSet<&String> = anchor_series_info.genres.iter().collect();
+    let anchor_network = network_name(anchor_series_info);
+
+    let mut scored_series: Vec<(f32, &SeriesMainInformation)> = full_schedule
+        .get_series()
+        .into_iter()
+        .filter(|series_info| {
+            series_info.id != anchor_series_info.id && !user_profile.is_known(series_info.id)
+        })
+        .filter_map(|series_info| {
+            let genres: HashSet<&String> = series_info.genres.iter().collect();
+            let shared_genres = anchor_genres.intersection(&genres).count();
+
+            if shared_genres == 0 {
+                return None;
+            }
+
+            let genre_similarity =
+                shared_genres as f32 / anchor_genres.union(&genres).count() as f32;
+            let network_similarity =
+                if anchor_network.is_some() && anchor_network == network_name(series_info) {
+                    1.0
+                } else {
+                    0.0
+                };
+
+            let score = (genre_similarity * GENRE_WEIGHT)
+                + (network_similarity * NETWORK_WEIGHT)
+                + ((series_info.rating() / 10.0) * RATING_WEIGHT);
+
+            Some((score, series_info))
+        })
+        .collect();
+
+    scored_series.sort_unstable_by(|(a, _), (b, _)| b.total_cmp(a));
+
+    scored_series
+        .into_iter()
+        .take(amount)
+        .map(|(_, series_info)| series_info)
+        .collect()
+}
+
+/// Returns the series to be used for the "Because you watched" rows
+///
+/// The anchors are picked from the most watched tracked series, rotating every day so
+/// that the rows stay fresh. Series whose rows were dismissed are never picked.
+pub async fn get_anchor_series(amount: usize) -> anyhow::Result<Vec<SeriesMainInformation>> {
+    let dismissed_anchors = load_dismissed_anchors().await;
+
+    let mut candidates: Vec<(usize, u32)> = database::DB
+        .get_series_collection()
+        .iter()
+        .filter(|series| series.is_tracked() && !dismissed_anchors.contains(&series.id()))
+        .map(|series| (series.get_total_episodes(), series.id()))
+        .filter(|(watched_episodes, _)| *watched_episodes > 0)
+        .collect();
+
+    candidates.sort_unstable_by(|a, b| b.cmp(a));
+    candidates.truncate(ANCHOR_CANDIDATES_AMOUNT);
+
+    if candidates.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let rotation = Local::now().ordinal() as usize % candidates.len();
+    candidates.rotate_left(rotation);
+
+    let mut anchors = Vec::with_capacity(amount);
+    for (_, series_id) in candidates.into_iter().take(amount) {
+        anchors.push(get_series_main_info_with_id(series_id).await?);
+    }
+
+    Ok(anchors)
+}
+
+fn get_dismissed_rows_filepath() -> path::PathBuf {
+    let mut dismissed_rows_filepath = paths::PATHS
+        .read()
+        .expect("failed to read paths")
+        .get_config_dir_path()
+        .to_path_buf();
+
+    dismissed_rows_filepath.push(DISMISSED_ROWS_FILENAME);
+    dismissed_rows_filepath
+}
+
+async fn load_dismissed_anchors() -> HashSet<u32> {
+    match fs::read_to_string(get_dismissed_rows_filepath()).await {
+        Ok(file_content) => serde_json::from_str(&file_content).unwrap_or_else(|err| {
+            warn!("could not parse dismissed recommendation rows: {}", err);
+            HashSet::new()
+        }),
+        Err(err) => {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("could not load dismissed recommendation rows: {}", err);
+            }
+            HashSet::new()
+        }
+    }
+}
+
+/// Prevents the "Because you watched" row of the given series from appearing again
+pub async fn dismiss_anchor(series_id: u32) -> anyhow::Result<()> {
+    let mut dismissed_anchors = load_dismissed_anchors().await;
+    dismissed_anchors.insert(series_id);
+
+    fs::write(
+        get_dismissed_rows_filepath(),
+        serde_json::to_string_pretty(&dismissed_anchors)?,
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+//! # Episode reconciliation
+//!
+//! Episodes get removed or renumbered on TVmaze now and then, leaving watched or skipped
+//! episodes in the database that no longer exist in the episode list of their series.
+//! Such orphaned episodes inflate the progress of their series and are reported here so
+//! that they can be cleared.
+
+use std::fmt;
+
+use tracing::error;
+
+use super::caching::episode_list::EpisodeList;
+use super::database::{Episode, DB};
+
+/// How the orphaned episode is recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanKind {
+    Watched,
+    Skipped,
+}
+
+impl fmt::Display for OrphanKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrphanKind::Watched => write!(f, "watched"),
+            OrphanKind::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// An episode recorded in the database that is not in the episode list of it's series
+#[derive(Debug, Clone)]
+pub struct OrphanedEpisode {
+    pub season_number: u32,
+    pub episode_number: Episode,
+    pub kind: OrphanKind,
+}
+
+impl fmt::Display for OrphanedEpisode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "S{:02}E{:02} ({})",
+            self.season_number, self.episode_number, self.kind
+        )
+    }
+}
+
+/// A series with orphaned episodes
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub series_id: u32,
+    pub series_name: String,
+    pub orphaned_episodes: Vec<OrphanedEpisode>,
+}
+
+/// Compares the episodes recorded for every series in the database with their episode list
+///
+/// Series whose episode list can not be loaded are left out of the report.
+pub async fn find_mismatches() -> Vec<Mismatch> {
+    // Only the ids and names are kept as series write themselves back to the database when dropped
+    let series: Vec<(u32, String)> = DB
+        .get_series_collection()
+        .iter()
+        .map(|series| (series.id(), series.get_name().to_owned()))
+        .collect();
+
+    let mut mismatches = vec![];
+
+    for (series_id, series_name) in series {
+        let episode_list = match EpisodeList::new(series_id).await {
+            Ok(episode_list) => episode_list,
+            Err(err) => {
+                error!(
+                    "failed to get episode list of series \"{}\" for reconciliation: {}",
+                    series_name, err
+                );
+                continue;
+            }
+        };
+
+        let orphaned_episodes = find_orphaned_episodes(series_id, &episode_list);
+        if !orphaned_episodes.is_empty() {
+            mismatches.push(Mismatch {
+                series_id,
+                series_name,
+                orphaned_episodes,
+            });
+        }
+    }
+
+    mismatches
+}
+
+fn find_orphaned_episodes(series_id: u32, episode_list: &EpisodeList) -> Vec<OrphanedEpisode> {
+    let watched_episodes: Vec<(u32, Episode)> = DB
+        .get_series(series_id)
+        .map(|series| {
+            series
+                .get_seasons()
+                .flat_map(|(season_number, season)| {
+                    season
+                        .get_episodes()
+                        .map(move |episode_number| (season_number, episode_number))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let watched_episodes = watched_episodes
+        .into_iter()
+        .map(|episode| (episode, OrphanKind::Watched));
+
+    let skipped_episodes = DB
+        .get_skipped_episodes(series_id)
+        .into_iter()
+        .map(|episode| (episode, OrphanKind::Skipped));
+
+    let mut orphaned_episodes: Vec<OrphanedEpisode> = watched_episodes
+        .chain(skipped_episodes)
+        .filter(|((season_number, episode_number), _)| {
+            episode_list
+                .get_episode(*season_number, *episode_number)
+                .is_none()
+        })
+        .map(|((season_number, episode_number), kind)| OrphanedEpisode {
+            season_number,
+            episode_number,
+            kind,
+        })
+        .collect();
+
+    orphaned_episodes.sort_by_key(|orphaned_episode| {
+        (
+            orphaned_episode.season_number,
+            orphaned_episode.episode_number,
+        )
+    });
+    orphaned_episodes
+}
+
+/// Unwatches and unskips the orphaned episodes of the mismatch
+///
+/// Returns the amount of episodes cleared.
+pub fn fix(mismatch: &Mismatch) -> usize {
+    let Some(mut series) = DB.get_series(mismatch.series_id) else {
+        return 0;
+    };
+
+    for orphaned_episode in mismatch.orphaned_episodes.iter() {
+        match orphaned_episode.kind {
+            OrphanKind::Watched => series.remove_episode(
+                orphaned_episode.season_number,
+                orphaned_episode.episode_number,
+            ),
+            OrphanKind::Skipped => DB.set_episode_skipped(
+                mismatch.series_id,
+                orphaned_episode.season_number,
+                orphaned_episode.episode_number,
+                false,
+            ),
+        }
+    }
+
+    mismatch.orphaned_episodes.len()
+}
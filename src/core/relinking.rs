@@ -0,0 +1,101 @@
+//! # Relinking series
+//!
+//! Shows are now and then deleted on TVmaze or merged into another show, leaving their
+//! tracked id answering with 404. Such series are noticed by the daily cache update and,
+//! once missing for `MISSES_BEFORE_MISSING` checks in a row, can be relinked to the show
+//! they live on as, moving their watch history over to the new id.
+
+use std::collections::HashSet;
+
+use tokio::fs;
+use tracing::error;
+
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::api::tv_maze::{series_searching, ApiError};
+use super::caching::{series_information, CacheFilePath, CACHER};
+use super::database::merge::{self, MergeOutcome};
+use super::database::{MissingSeries, DB};
+
+/// Looks up the series without cached information again, recording the ones TVmaze no
+/// longer knows
+///
+/// Revalidating the cache of a series that answers with 404 removes it, so such series
+/// are always among the uncached ones after a cache update.
+pub async fn check_uncached_series() {
+    let archived_series_ids = DB.get_archived_series_ids();
+
+    // Only the ids and names are kept as series write themselves back to the database when dropped
+    let series: Vec<(u32, String)> = DB
+        .get_series_collection()
+        .iter()
+        .filter(|series| !archived_series_ids.contains(&series.id()))
+        .map(|series| (series.id(), series.get_name().to_owned()))
+        .collect();
+
+    for (series_id, series_name) in series {
+        let series_information_path =
+            CACHER.get_cache_file_path(CacheFilePath::SeriesMainInformation(series_id));
+
+        if fs::try_exists(&series_information_path)
+            .await
+            .unwrap_or(false)
+        {
+            DB.clear_series_misses(series_id);
+            continue;
+        }
+
+        match series_information::get_series_main_info_with_id(series_id).await {
+            Ok(_) => DB.clear_series_misses(series_id),
+            Err(ApiError::NotFound(_)) => DB.record_series_miss(series_id, &series_name),
+            Err(err) => error!(
+                "failed to look up series \"{}\" with id {}: {}",
+                series_name, series_id, err
+            ),
+        }
+    }
+}
+
+/// Finds the shows the missing series may live on as, the best match first
+pub async fn find_replacements(
+    missing_series: &MissingSeries,
+) -> Result<Vec<SeriesMainInformation>, ApiError> {
+    let best_match =
+        series_searching::single_search_series(missing_series.series_name.clone()).await?;
+
+    let search_results = series_searching::search_series(missing_series.series_name.clone())
+        .await?
+        .into_iter()
+        .map(|search_result| search_result.show);
+
+    let mut seen_series_ids = HashSet::from([missing_series.series_id]);
+    Ok(best_match
+        .into_iter()
+        .chain(search_results)
+        .filter(|series_info| seen_series_ids.insert(series_info.id))
+        .collect())
+}
+
+/// Moves the watch history and the other records of the missing series to the replacement,
+/// then removes the missing series
+pub fn relink(missing_series: &MissingSeries, replacement: &SeriesMainInformation) -> MergeOutcome {
+    DB.clear_series_misses(missing_series.series_id);
+
+    let Some(series) = DB.get_series(missing_series.series_id) else {
+        return MergeOutcome::default();
+    };
+
+    let states = merge::local_episode_states(&series);
+    let outcome = merge::merge_into_db(
+        replacement.id,
+        &replacement.name,
+        series.is_tracked(),
+        &states,
+    );
+
+    // The missing series is written back when dropped, so it is removed only afterwards
+    drop(series);
+    DB.move_series_records(missing_series.series_id, replacement.id);
+    DB.remove_series(missing_series.series_id);
+
+    outcome
+}
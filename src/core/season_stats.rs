@@ -0,0 +1,82 @@
+//! # Season stats
+//!
+//! Numbers about a single season shown on the series page, worked out from the cached
+//! episode list and what the user has watched, skipped and rated in the database.
+
+use super::caching::episode_list::EpisodeList;
+use super::database::DB;
+
+#[derive(Debug, Clone, Default)]
+pub struct SeasonStats {
+    /// The TVmaze average of the rated episodes
+    pub average_episode_rating: Option<f32>,
+    /// The user's average of the rated episodes
+    pub average_user_rating: Option<f32>,
+    /// The runtime of all the episodes with a known runtime
+    pub total_runtime: u32,
+    /// The watched and skipped episodes
+    pub completed_episodes: usize,
+    pub watchable_episodes: usize,
+    /// The runtime of the aired episodes neither watched nor skipped
+    pub time_to_finish: u32,
+}
+
+impl SeasonStats {
+    pub fn new(series_id: u32, episode_list: &EpisodeList, season_number: u32) -> Self {
+        let episodes = episode_list.get_episodes(season_number);
+
+        let series = DB.get_series(series_id);
+        let skipped_episodes = DB.get_skipped_episodes(series_id);
+
+        let is_completed = |episode_number: u32| {
+            skipped_episodes.contains(&(season_number, episode_number))
+                || series
+                    .as_ref()
+                    .and_then(|series| series.get_season(season_number))
+                    .is_some_and(|season| season.is_episode_watched(episode_number))
+        };
+
+        let ratings: Vec<f32> = episodes
+            .iter()
+            .filter_map(|episode| episode.rating.average)
+            .collect();
+        let average_episode_rating =
+            (!ratings.is_empty()).then(|| ratings.iter().sum::<f32>() / ratings.len() as f32);
+
+        let total_runtime = episodes.iter().filter_map(|episode| episode.runtime).sum();
+
+        let aired_episodes: Vec<_> = episodes
+            .iter()
+            .filter(|episode| episode.is_future_release() == Ok(false))
+            .filter_map(|episode| episode.number.map(|number| (number, episode.runtime)))
+            .collect();
+
+        let completed_episodes = aired_episodes
+            .iter()
+            .filter(|(number, _)| is_completed(*number))
+            .count();
+
+        let time_to_finish = aired_episodes
+            .iter()
+            .filter(|(number, _)| !is_completed(*number))
+            .filter_map(|(_, runtime)| *runtime)
+            .sum();
+
+        Self {
+            average_episode_rating,
+            average_user_rating: DB.get_average_episode_rating(series_id, Some(season_number)),
+            total_runtime,
+            completed_episodes,
+            watchable_episodes: aired_episodes.len(),
+            time_to_finish,
+        }
+    }
+
+    /// The completed fraction of the watchable episodes
+    pub fn completion(&self) -> f32 {
+        if self.watchable_episodes == 0 {
+            return 0.0;
+        }
+        self.completed_episodes as f32 / self.watchable_episodes as f32
+    }
+}
@@ -0,0 +1,71 @@
+//! # Secrets
+//!
+//! Passwords and passphrases entered in the settings are kept in the keyring of the OS
+//! (Secret Service on Linux, Keychain on macOS and the Credential Manager on Windows)
+//! instead of the config file, which is plain text.
+//!
+//! When no keyring is available they are not remembered across launches and have to be
+//! entered again.
+
+use tracing::{error, warn};
+
+const SERVICE: &str = env!("CARGO_PKG_NAME");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    /// WebDAV password or S3 secret access key of the remote backup
+    RemoteBackupPassword,
+    /// Passphrase remote backups are encrypted with
+    RemoteBackupPassphrase,
+    /// Generated passphrase the database values are encrypted with
+    DatabasePassphrase,
+}
+
+impl Secret {
+    fn user(self) -> &'static str {
+        match self {
+            Secret::RemoteBackupPassword => "remote-backup-password",
+            Secret::RemoteBackupPassphrase => "remote-backup-passphrase",
+            Secret::DatabasePassphrase => "database-passphrase",
+        }
+    }
+
+    fn entry(self) -> Option<keyring::Entry> {
+        keyring::Entry::new(SERVICE, self.user())
+            .map_err(|err| error!("failed to open the keyring entry of {:?}: {}", self, err))
+            .ok()
+    }
+}
+
+/// Reads the secret from the keyring, `None` when it was never stored or the keyring is
+/// not available
+pub fn get(secret: Secret) -> Option<String> {
+    match secret.entry()?.get_password() {
+        Ok(value) => Some(value),
+        Err(keyring::Error::NoEntry) => None,
+        Err(err) => {
+            warn!("failed to read {:?} from the keyring: {}", secret, err);
+            None
+        }
+    }
+}
+
+/// Stores the secret in the keyring, removing it when empty
+pub fn set(secret: Secret, value: &str) {
+    let Some(entry) = secret.entry() else {
+        return;
+    };
+
+    let result = if value.is_empty() {
+        match entry.delete_password() {
+            Err(keyring::Error::NoEntry) => Ok(()),
+            result => result,
+        }
+    } else {
+        entry.set_password(value)
+    };
+
+    if let Err(err) = result {
+        error!("failed to store {:?} in the keyring: {}", secret, err);
+    }
+}
@@ -0,0 +1,85 @@
+//! # Series updates
+//!
+//! TVmaze only says when a series was last updated, not what changed. So when the cache of
+//! a series in the database is refreshed after a TVmaze update, the cached information is
+//! compared against the refreshed one and the differences are kept in the database to be
+//! shown along the updated series.
+
+use super::api::tv_maze::episodes_information::Episode;
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::caching::{read_cache, CacheFilePath, CACHER};
+use super::database::{SeriesChange, SeriesUpdate, DB};
+
+/// What is compared of the cached information of a series
+pub struct SeriesSnapshot {
+    status: String,
+    image_url: Option<String>,
+    total_episodes: usize,
+}
+
+impl SeriesSnapshot {
+    /// Takes the snapshot from the cache, `None` when the series is not fully cached
+    pub async fn from_cache(series_id: u32) -> Option<Self> {
+        let series_info =
+            read_cache(CACHER.get_cache_file_path(CacheFilePath::SeriesMainInformation(series_id)))
+                .await
+                .ok()?;
+        let series_info: SeriesMainInformation = serde_json::from_str(&series_info).ok()?;
+
+        let episode_list =
+            read_cache(CACHER.get_cache_file_path(CacheFilePath::SeriesEpisodeList(series_id)))
+                .await
+                .ok()?;
+        let episode_list: Vec<Episode> = serde_json::from_str(&episode_list).ok()?;
+
+        Some(Self {
+            status: series_info.status,
+            image_url: series_info.image.map(|image| image.original_image_url),
+            total_episodes: episode_list.len(),
+        })
+    }
+
+    /// What changed from this snapshot to the newer one
+    pub fn changes_to(&self, newer: &Self) -> Vec<SeriesChange> {
+        let mut changes = vec![];
+
+        if newer.total_episodes > self.total_episodes {
+            changes.push(SeriesChange::NewEpisodes(
+                newer.total_episodes - self.total_episodes,
+            ));
+        }
+        if newer.image_url != self.image_url {
+            changes.push(SeriesChange::ArtworkChanged);
+        }
+        if newer.status != self.status {
+            changes.push(SeriesChange::StatusChanged {
+                from: self.status.clone(),
+                to: newer.status.clone(),
+            });
+        }
+
+        changes
+    }
+}
+
+/// Keeps the changes of the series update in the database
+pub fn record_update(series_id: u32, updated_at: i64, changes: Vec<SeriesChange>) {
+    DB.set_series_update(&SeriesUpdate {
+        series_id,
+        updated_at,
+        changes,
+    });
+}
+
+impl std::fmt::Display for SeriesChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SeriesChange::NewEpisodes(1) => write!(f, "New episode added"),
+            SeriesChange::NewEpisodes(amount) => write!(f, "{} new episodes added", amount),
+            SeriesChange::ArtworkChanged => write!(f, "Artwork changed"),
+            SeriesChange::StatusChanged { from, to } => {
+                write!(f, "Status changed from {} to {}", from, to)
+            }
+        }
+    }
+}
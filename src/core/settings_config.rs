@@ -1,14 +1,17 @@
 use std::{
     io::ErrorKind,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
 };
 
+use anyhow::Context;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 
+use crate::core::data_location::DataLocation;
 use crate::core::paths;
+use crate::core::secrets::{self, Secret};
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Theme {
@@ -36,42 +39,526 @@ pub struct Config {
     pub locale: LocaleSettings,
     pub notifications: NotificationSettings,
     pub custom_paths: Option<CustomPaths>,
+    #[serde(default)]
+    pub media_server: Option<MediaServerSettings>,
+    #[serde(default)]
+    pub hooks: HookSettings,
+    #[serde(default)]
+    pub remote_backup: Option<RemoteBackupSettings>,
+    #[serde(default)]
+    pub lan_sync: Option<LanSyncSettings>,
+    #[serde(default)]
+    pub content_filter: ContentFilterSettings,
+    #[serde(default)]
+    pub discover_filter: DiscoverFilterSettings,
+    #[serde(default)]
+    pub window: WindowSettings,
+    #[serde(default)]
+    pub session: SessionSettings,
+    #[serde(default)]
+    pub app_lock: AppLockSettings,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub local_files: LocalFilesSettings,
+    #[serde(default)]
+    pub media_players: MediaPlayerSettings,
+    #[serde(default)]
+    pub watch_goal: WatchGoalSettings,
+    #[serde(default)]
+    pub database: DatabaseSettings,
 }
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct AppearanceSettings {
     pub theme: Theme,
+    #[serde(default)]
+    pub layout: Layout,
+}
+
+/// How series pages are shown next to the My Shows and Discover tabs
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum Layout {
+    /// Split pane when the window is wide enough, single page otherwise
+    #[default]
+    Auto,
+    /// Series pages replace the tab
+    SinglePage,
+    /// Series pages are docked on the right of the tab
+    SplitPane,
+}
+
+pub const ALL_LAYOUTS: [Layout; 3] = [Layout::Auto, Layout::SinglePage, Layout::SplitPane];
+
+impl std::fmt::Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            Layout::Auto => "Auto",
+            Layout::SinglePage => "Single page",
+            Layout::SplitPane => "Split pane",
+        };
+
+        write!(f, "{}", str)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct LocaleSettings {
     pub country_code: String,
+    #[serde(default)]
+    pub date_format: DateFormat,
+    #[serde(default)]
+    pub week_start: WeekStart,
+    #[serde(default)]
+    pub time_format: TimeFormat,
 }
 
 impl Default for LocaleSettings {
     fn default() -> Self {
         Self {
             country_code: "US".to_owned(),
+            date_format: DateFormat::default(),
+            week_start: WeekStart::default(),
+            time_format: TimeFormat::default(),
+        }
+    }
+}
+
+/// How dates are written throughout the app, see `date_formatting`
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum DateFormat {
+    /// Like `2024-03-31`
+    #[default]
+    Iso,
+    /// Like `03/31/2024`
+    Us,
+    /// Like `31/03/2024`
+    Eu,
+}
+
+pub const ALL_DATE_FORMATS: [DateFormat; 3] = [DateFormat::Iso, DateFormat::Us, DateFormat::Eu];
+
+impl std::fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            DateFormat::Iso => "ISO (2024-03-31)",
+            DateFormat::Us => "US (03/31/2024)",
+            DateFormat::Eu => "EU (31/03/2024)",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
+/// How times are written throughout the app, see `date_formatting`
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum TimeFormat {
+    /// 12-hour times with the US date format, 24-hour times otherwise
+    #[default]
+    Auto,
+    /// Like `8:30 PM`
+    TwelveHour,
+    /// Like `20:30`
+    TwentyFourHour,
+}
+
+pub const ALL_TIME_FORMATS: [TimeFormat; 3] = [
+    TimeFormat::Auto,
+    TimeFormat::TwelveHour,
+    TimeFormat::TwentyFourHour,
+];
+
+impl std::fmt::Display for TimeFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            TimeFormat::Auto => "Same as the date format",
+            TimeFormat::TwelveHour => "12-hour (8:30 PM)",
+            TimeFormat::TwentyFourHour => "24-hour (20:30)",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
+/// The first day of the week
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+    Saturday,
+}
+
+pub const ALL_WEEK_STARTS: [WeekStart; 3] =
+    [WeekStart::Monday, WeekStart::Sunday, WeekStart::Saturday];
+
+impl WeekStart {
+    pub fn weekday(&self) -> chrono::Weekday {
+        match self {
+            WeekStart::Monday => chrono::Weekday::Mon,
+            WeekStart::Sunday => chrono::Weekday::Sun,
+            WeekStart::Saturday => chrono::Weekday::Sat,
         }
     }
 }
 
+impl std::fmt::Display for WeekStart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            WeekStart::Monday => "Monday",
+            WeekStart::Sunday => "Sunday",
+            WeekStart::Saturday => "Saturday",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct NotificationSettings {
     // the time is in minutes
     pub time_to_notify: u32,
+    /// Whether a summary of the past and coming week is sent once a week
+    #[serde(default)]
+    pub weekly_digest: bool,
+    /// Whether new series and episodes featuring followed people are notified
+    #[serde(default)]
+    pub followed_people: bool,
+    /// How long in minutes a snoozed release notification waits before showing again
+    #[serde(default = "default_snooze_time")]
+    pub snooze_time: u32,
+}
+
+fn default_snooze_time() -> u32 {
+    60
 }
 
 impl Default for NotificationSettings {
     fn default() -> Self {
-        Self { time_to_notify: 60 }
+        Self {
+            time_to_notify: 60,
+            weekly_digest: false,
+            followed_people: false,
+            snooze_time: default_snooze_time(),
+        }
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct DatabaseSettings {
+    /// Whether the values of the database are encrypted, applied on the next start as the
+    /// whole database has to be rewritten
+    pub encryption: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
 pub struct CustomPaths {
     pub data_dir: Option<PathBuf>,
     pub cache_dir: Option<PathBuf>,
+    /// Where the data directory is moved to on the next start
+    #[serde(default)]
+    pub pending_data_dir: Option<PathBuf>,
+    /// Where the cache directory is moved to on the next start
+    #[serde(default)]
+    pub pending_cache_dir: Option<PathBuf>,
+}
+
+/// Folders whose video files are matched to the tracked series for marking them watched
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct LocalFilesSettings {
+    pub enabled: bool,
+    pub folders: Vec<PathBuf>,
+}
+
+/// Media players whose playback marks episodes watched, through MPRIS on Linux
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct MediaPlayerSettings {
+    pub enabled: bool,
+    /// The MPRIS names of the players listened to, like `vlc` or `mpv`
+    pub allowed_players: Vec<String>,
+}
+
+/// A budget of watch time for a week or a month
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct WatchGoalSettings {
+    /// The goal in minutes, no goal is set when zero
+    pub minutes: u32,
+    pub period: WatchGoalPeriod,
+    /// Whether a notification is sent once the watch time goes over the goal
+    pub notify_when_exceeded: bool,
+}
+
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum WatchGoalPeriod {
+    /// Starting on the first day of the week set in the locale settings
+    #[default]
+    Weekly,
+    Monthly,
+}
+
+pub const ALL_WATCH_GOAL_PERIODS: [WatchGoalPeriod; 2] =
+    [WatchGoalPeriod::Weekly, WatchGoalPeriod::Monthly];
+
+impl std::fmt::Display for WatchGoalPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            WatchGoalPeriod::Weekly => "Weekly",
+            WatchGoalPeriod::Monthly => "Monthly",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct HookSettings {
+    /// Url receiving watch events as json POST requests
+    pub webhook_url: Option<String>,
+    /// Command ran by the system shell on watch events
+    pub command: Option<String>,
+}
+
+impl HookSettings {
+    pub fn is_configured(&self) -> bool {
+        self.webhook_url.is_some() || self.command.is_some()
+    }
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum MediaServerKind {
+    #[default]
+    Jellyfin,
+    Plex,
+}
+
+impl std::fmt::Display for MediaServerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            MediaServerKind::Jellyfin => "Jellyfin",
+            MediaServerKind::Plex => "Plex",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MediaServerSettings {
+    pub kind: MediaServerKind,
+    /// The base address of the server e.g. `http://localhost:8096`
+    pub address: String,
+    /// Jellyfin api key or Plex token
+    pub token: String,
+    /// The Jellyfin user whose playback state is imported, not used by Plex
+    pub user_id: Option<String>,
+    // the interval is in hours
+    pub sync_interval: u32,
+}
+
+impl Default for MediaServerSettings {
+    fn default() -> Self {
+        Self {
+            kind: MediaServerKind::default(),
+            address: String::new(),
+            token: String::new(),
+            user_id: None,
+            sync_interval: 6,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum RemoteBackupKind {
+    #[default]
+    WebDav,
+    S3,
+}
+
+pub const ALL_REMOTE_BACKUP_KINDS: [RemoteBackupKind; 2] =
+    [RemoteBackupKind::WebDav, RemoteBackupKind::S3];
+
+impl std::fmt::Display for RemoteBackupKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            RemoteBackupKind::WebDav => "WebDAV",
+            RemoteBackupKind::S3 => "S3 Compatible",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct RemoteBackupSettings {
+    pub kind: RemoteBackupKind,
+    /// WebDAV folder url or the S3 endpoint e.g. `https://s3.eu-central-1.amazonaws.com`
+    pub endpoint: String,
+    /// The bucket holding the backup, not used by WebDAV
+    pub bucket: String,
+    /// The S3 region, not used by WebDAV
+    pub region: String,
+    /// WebDAV username or S3 access key id
+    pub username: String,
+    /// WebDAV password or S3 secret access key
+    ///
+    /// Kept in the OS keyring rather than the config file, only read from the config file
+    /// to move it to the keyring.
+    #[serde(default, skip_serializing)]
+    pub password: String,
+    /// Passphrase the backup is encrypted with before leaving the device, kept in the OS
+    /// keyring like the password
+    #[serde(default, skip_serializing)]
+    pub passphrase: String,
+    /// How often a backup is automatically made, `None` when backups are only made manually
+    // the interval is in hours
+    pub schedule_interval: Option<u32>,
+}
+
+impl Default for RemoteBackupSettings {
+    fn default() -> Self {
+        Self {
+            kind: RemoteBackupKind::default(),
+            endpoint: String::new(),
+            bucket: String::new(),
+            region: "us-east-1".to_owned(),
+            username: String::new(),
+            password: String::new(),
+            passphrase: String::new(),
+            schedule_interval: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LanSyncSettings {
+    /// The name other devices see this device as
+    pub device_name: String,
+    /// Code shared by the devices allowed to sync with each other
+    pub pairing_code: String,
+    /// The tcp port sync requests are accepted on
+    pub port: u16,
+}
+
+impl Default for LanSyncSettings {
+    fn default() -> Self {
+        let device_name = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .unwrap_or_else(|_| "Series Troxide".to_owned());
+
+        Self {
+            device_name,
+            pairing_code: String::new(),
+            port: 47474,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct ContentFilterSettings {
+    /// Hides series with the `Adult` genre
+    pub hide_adult: bool,
+    /// Other genres whose series are hidden
+    pub hidden_genres: Vec<String>,
+    /// Salted Argon2 hash of the PIN required to change the content filter, see `passwords`
+    pub pin_hash: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NetworkSettings {
+    /// Whether the images of the next episodes to watch are loaded ahead of time
+    #[serde(default = "default_prefetch_images")]
+    pub prefetch_images: bool,
+    /// Whether images are only loaded when asked for and the cache is updated less often
+    #[serde(default)]
+    pub bandwidth_saver: bool,
+    #[serde(default)]
+    pub image_quality: ImageQuality,
+}
+
+fn default_prefetch_images() -> bool {
+    true
+}
+
+/// The resolution the images are loaded in
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ImageQuality {
+    /// Original for the large images like the series page poster, medium for the rest
+    #[default]
+    Auto,
+    Medium,
+    Original,
+}
+
+pub const ALL_IMAGE_QUALITIES: [ImageQuality; 3] = [
+    ImageQuality::Auto,
+    ImageQuality::Medium,
+    ImageQuality::Original,
+];
+
+impl std::fmt::Display for ImageQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            ImageQuality::Auto => "Auto",
+            ImageQuality::Medium => "Medium",
+            ImageQuality::Original => "Original",
+        };
+
+        write!(f, "{}", str)
+    }
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            prefetch_images: default_prefetch_images(),
+            bandwidth_saver: false,
+            image_quality: ImageQuality::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct AppLockSettings {
+    /// Salted Argon2 hash of the PIN or password asked to unlock the app, no lock when unset
+    pub password_hash: Option<String>,
+    /// Minutes without any input before the app is locked again, only at startup when 0
+    pub idle_minutes: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Default)]
+pub struct DiscoverFilterSettings {
+    /// Show types whose series are hidden from Discover i.e. `Talk Show`
+    pub hidden_types: Vec<String>,
+    /// Languages the series in Discover are limited to, all languages when empty
+    pub languages: Vec<String>,
+}
+
+/// The geometry the window was last closed with, restored on launch
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct WindowSettings {
+    pub width: u32,
+    pub height: u32,
+    /// `None` lets the platform place the window
+    pub position: Option<(i32, i32)>,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 1024,
+            height: 768,
+            position: None,
+        }
+    }
+}
+
+/// Where the user was when the app was last closed, restored on launch
+#[derive(Clone, Default, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SessionSettings {
+    /// The index of the active tab
+    pub tab: usize,
+    /// The id of the series whose page was open
+    pub series_page_id: Option<u32>,
 }
 
 lazy_static! {
@@ -108,15 +595,42 @@ impl Settings {
     /// Loads the default settings
     ///
     /// # Note
-    /// Does not save the settings
+    /// Does not save the settings, keeps a PIN protected content filter, the window geometry
+    /// and the database encryption
     pub fn set_default_settings(&mut self) {
-        self.unsaved_config = Config::default();
+        let content_filter = self.unsaved_config.content_filter.clone();
+        let window = self.unsaved_config.window.clone();
+        let session = self.unsaved_config.session.clone();
+        let custom_paths = self.unsaved_config.custom_paths.clone();
+        let app_lock = self.unsaved_config.app_lock.clone();
+        let database = self.unsaved_config.database.clone();
+        self.unsaved_config = Config {
+            window,
+            session,
+            custom_paths,
+            database,
+            ..Config::default()
+        };
+
+        if content_filter.is_pin_protected() {
+            self.unsaved_config.content_filter = content_filter;
+        }
+        if app_lock.is_enabled() {
+            self.unsaved_config.app_lock = app_lock;
+        }
     }
 
     /// Checks if the unsaved settings curresponds to the
     /// default settings of the program
     pub fn has_default_settings(&self) -> bool {
-        self.unsaved_config == Config::default()
+        self.unsaved_config
+            == Config {
+                window: self.unsaved_config.window.clone(),
+                session: self.unsaved_config.session.clone(),
+                custom_paths: self.unsaved_config.custom_paths.clone(),
+                database: self.unsaved_config.database.clone(),
+                ..Config::default()
+            }
     }
 
     pub fn has_pending_save(&self) -> bool {
@@ -124,9 +638,118 @@ impl Settings {
     }
 
     pub fn save_settings(&mut self) {
+        if self.unsaved_config.remote_backup != self.current_config.remote_backup {
+            save_remote_backup_secrets(self.unsaved_config.remote_backup.as_ref());
+        }
         save_config(&self.unsaved_config);
         self.current_config = self.unsaved_config.clone();
     }
+
+    /// The saved settings that make sense on other machines
+    ///
+    /// Paths, hooks, credentials, devices and the window geometry are left out as they belong
+    /// to this machine, so are the PIN of the content filter and the app lock.
+    pub fn get_portable_settings(&self) -> Config {
+        Config {
+            appearance: self.current_config.appearance.clone(),
+            locale: self.current_config.locale.clone(),
+            notifications: self.current_config.notifications.clone(),
+            content_filter: ContentFilterSettings {
+                pin_hash: None,
+                ..self.current_config.content_filter.clone()
+            },
+            discover_filter: self.current_config.discover_filter.clone(),
+            network: self.current_config.network.clone(),
+            ..Config::default()
+        }
+    }
+
+    /// Loads the portable part of the imported settings, see `get_portable_settings`
+    ///
+    /// # Note
+    /// Does not save the settings, and keeps a PIN protected content filter
+    pub fn import_portable_settings(&mut self, imported_config: Config) {
+        let content_filter = if self.unsaved_config.content_filter.is_pin_protected() {
+            self.unsaved_config.content_filter.clone()
+        } else {
+            ContentFilterSettings {
+                pin_hash: self.unsaved_config.content_filter.pin_hash.clone(),
+                ..imported_config.content_filter
+            }
+        };
+
+        self.unsaved_config = Config {
+            appearance: imported_config.appearance,
+            locale: imported_config.locale,
+            notifications: imported_config.notifications,
+            content_filter,
+            discover_filter: imported_config.discover_filter,
+            network: imported_config.network,
+            ..self.unsaved_config.clone()
+        };
+    }
+
+    /// Saves the window geometry right away, leaving any other unsaved settings unsaved
+    pub fn save_window_settings(&mut self, window: WindowSettings) {
+        self.current_config.window = window.clone();
+        self.unsaved_config.window = window;
+        save_config(&self.current_config);
+    }
+
+    /// Saves the hash of the content filter PIN right away, leaving any other unsaved settings
+    /// unsaved
+    pub fn save_content_filter_pin_hash(&mut self, pin_hash: String) {
+        self.current_config.content_filter.pin_hash = Some(pin_hash.clone());
+        self.unsaved_config.content_filter.pin_hash = Some(pin_hash);
+        save_config(&self.current_config);
+    }
+
+    /// Saves the hash of the app lock password right away, leaving any other unsaved settings
+    /// unsaved
+    pub fn save_app_lock_password_hash(&mut self, password_hash: String) {
+        self.current_config.app_lock.password_hash = Some(password_hash.clone());
+        self.unsaved_config.app_lock.password_hash = Some(password_hash);
+        save_config(&self.current_config);
+    }
+
+    /// Saves where the user is in the app right away, leaving any other unsaved settings unsaved
+    pub fn save_session_settings(&mut self, session: SessionSettings) {
+        self.current_config.session = session.clone();
+        self.unsaved_config.session = session;
+        save_config(&self.current_config);
+    }
+
+    /// Saves the new directory of the data location right away, leaving any other unsaved
+    /// settings unsaved
+    pub fn save_custom_path(&mut self, location: DataLocation, dir: PathBuf) {
+        for config in [&mut self.current_config, &mut self.unsaved_config] {
+            let custom_paths = config.custom_paths.get_or_insert_with(CustomPaths::default);
+            match location {
+                DataLocation::Database => {
+                    custom_paths.data_dir = Some(dir.clone());
+                    custom_paths.pending_data_dir = None;
+                }
+                DataLocation::Cache => {
+                    custom_paths.cache_dir = Some(dir.clone());
+                    custom_paths.pending_cache_dir = None;
+                }
+            }
+        }
+        save_config(&self.current_config);
+    }
+
+    /// Saves the directory the data location moves to on the next start right away, leaving
+    /// any other unsaved settings unsaved
+    pub fn save_pending_path(&mut self, location: DataLocation, dir: Option<PathBuf>) {
+        for config in [&mut self.current_config, &mut self.unsaved_config] {
+            let custom_paths = config.custom_paths.get_or_insert_with(CustomPaths::default);
+            match location {
+                DataLocation::Database => custom_paths.pending_data_dir = dir.clone(),
+                DataLocation::Cache => custom_paths.pending_cache_dir = dir.clone(),
+            }
+        }
+        save_config(&self.current_config);
+    }
 }
 
 impl Default for Settings {
@@ -173,8 +796,11 @@ fn load_config() -> Config {
         }
     };
 
-    match toml::from_str(&file_contents) {
-        Ok(config) => config,
+    match toml::from_str::<Config>(&file_contents) {
+        Ok(mut config) => {
+            load_remote_backup_secrets(&mut config);
+            config
+        }
         Err(err) => {
             error!("could not parse the config file: {}", err);
             warn!("loading with default settings");
@@ -183,6 +809,40 @@ fn load_config() -> Config {
     }
 }
 
+/// Fills in the remote backup secrets from the keyring
+///
+/// Secrets still found in the config file, from before they were kept in the keyring, are
+/// moved to the keyring and removed from the config file.
+fn load_remote_backup_secrets(config: &mut Config) {
+    let Some(remote_backup) = config.remote_backup.as_mut() else {
+        return;
+    };
+
+    if !remote_backup.password.is_empty() || !remote_backup.passphrase.is_empty() {
+        info!("moving the remote backup secrets from the config file to the keyring");
+        save_remote_backup_secrets(Some(remote_backup));
+        save_config(config);
+        return;
+    }
+
+    remote_backup.password = secrets::get(Secret::RemoteBackupPassword).unwrap_or_default();
+    remote_backup.passphrase = secrets::get(Secret::RemoteBackupPassphrase).unwrap_or_default();
+}
+
+fn save_remote_backup_secrets(remote_backup: Option<&RemoteBackupSettings>) {
+    let (password, passphrase) = remote_backup
+        .map(|remote_backup| {
+            (
+                remote_backup.password.as_str(),
+                remote_backup.passphrase.as_str(),
+            )
+        })
+        .unwrap_or_default();
+
+    secrets::set(Secret::RemoteBackupPassword, password);
+    secrets::set(Secret::RemoteBackupPassphrase, passphrase);
+}
+
 fn save_config(settings_config: &Config) {
     let mut config_file = paths::PATHS
         .read()
@@ -204,6 +864,23 @@ fn save_config(settings_config: &Config) {
     }
 }
 
+/// Writes the portable settings to the toml file, see `Settings::get_portable_settings`
+pub async fn export_portable_settings(path: impl AsRef<Path>) -> anyhow::Result<()> {
+    let portable_settings = SETTINGS.read().unwrap().get_portable_settings();
+    let settings_toml = toml::to_string_pretty(&portable_settings)?;
+    tokio::fs::write(path, settings_toml)
+        .await
+        .context("failed to write the settings file")
+}
+
+/// Reads the settings exported by `export_portable_settings` from the toml file
+pub async fn read_portable_settings(path: impl AsRef<Path>) -> anyhow::Result<Config> {
+    let settings_toml = tokio::fs::read_to_string(path)
+        .await
+        .context("failed to read the settings file")?;
+    toml::from_str(&settings_toml).context("the file does not contain valid settings")
+}
+
 pub mod locale_settings {
     //! Deals with interaction of GUI locale settings with the actual settings from
     //! the config file
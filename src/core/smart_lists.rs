@@ -0,0 +1,55 @@
+//! # Smart lists
+//!
+//! Advanced searches saved under a name, like "short comedies I haven't finished", shown in
+//! the My Shows tab. A smart list is evaluated again every time it's opened so that it
+//! follows the series being watched and the new series airing.
+
+use super::advanced_search;
+use super::api::tv_maze::series_information::SeriesMainInformation;
+use super::caching::episode_list::EpisodeList;
+use super::caching::series_list::SeriesList;
+use super::database::{SmartList, SmartListSource, DB};
+
+/// Gets the series currently matching the smart list
+pub async fn evaluate(smart_list: SmartList) -> anyhow::Result<Vec<SeriesMainInformation>> {
+    if let SmartListSource::Discover = smart_list.source {
+        return advanced_search::search(smart_list.term, smart_list.filter).await;
+    }
+
+    let term = smart_list.term.trim().to_lowercase();
+
+    let series_infos = SeriesList::new()
+        .get_all_series_information()
+        .await?
+        .into_iter()
+        .filter(|series_info| term.is_empty() || series_info.name.to_lowercase().contains(&term))
+        .filter(|series_info| smart_list.filter.matches(series_info));
+
+    if let SmartListSource::MyShows = smart_list.source {
+        return Ok(series_infos.collect());
+    }
+
+    let mut unfinished_series_infos = vec![];
+    for series_info in series_infos {
+        if is_unfinished(series_info.id).await? {
+            unfinished_series_infos.push(series_info);
+        }
+    }
+
+    Ok(unfinished_series_infos)
+}
+
+/// Whether the series has aired episodes neither watched nor skipped
+async fn is_unfinished(series_id: u32) -> anyhow::Result<bool> {
+    let watchable_episodes = EpisodeList::new(series_id)
+        .await?
+        .get_total_watchable_episodes();
+
+    let seen_episodes = DB
+        .get_series(series_id)
+        .map(|series| series.get_total_episodes())
+        .unwrap_or_default()
+        + DB.get_total_skipped_episodes(series_id, None);
+
+    Ok(watchable_episodes > seen_episodes)
+}
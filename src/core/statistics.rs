@@ -0,0 +1,181 @@
+//! # Statistics
+//!
+//! The watch statistics shown in the Statistics tab and printed by the `stats` command,
+//! both computed here so that their numbers always match.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use serde::Serialize;
+
+use super::api::tv_maze::series_information::{Genre, SeriesMainInformation};
+use super::caching::refresh_queue::{self, RefreshPriority};
+use super::database::DB;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Statistics {
+    pub totals: Totals,
+    /// The watched series, the most watched first
+    pub series: Vec<SeriesWatchTime>,
+    /// How many of the watched series have each genre, the most common first
+    pub genres: Vec<(String, usize)>,
+    pub streaks: Streaks,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Totals {
+    pub series: usize,
+    pub seasons: usize,
+    pub episodes: usize,
+    pub skipped_episodes: usize,
+    /// Watched episodes marked automatically as they aired, part of `episodes`
+    pub auto_marked_episodes: usize,
+    /// Based on the average runtime of each series
+    pub watch_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesWatchTime {
+    pub id: u32,
+    pub name: String,
+    pub watch_minutes: Option<u32>,
+}
+
+/// Consecutive days with at least one episode watched
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Streaks {
+    /// The streak going on today or that ended yesterday
+    pub current_days: usize,
+    pub longest_days: usize,
+}
+
+impl Statistics {
+    pub async fn load() -> Self {
+        let series_infos_and_time = get_series_with_runtime().await;
+
+        let series_infos: Vec<&SeriesMainInformation> = series_infos_and_time
+            .iter()
+            .map(|(series_info, _)| series_info)
+            .collect();
+        let genres = genre_count(&series_infos)
+            .into_iter()
+            .map(|(genre, count)| (genre.to_string(), count))
+            .collect();
+
+        let mut series: Vec<SeriesWatchTime> = series_infos_and_time
+            .iter()
+            .map(|(series_info, watch_minutes)| SeriesWatchTime {
+                id: series_info.id,
+                name: series_info.name.clone(),
+                watch_minutes: *watch_minutes,
+            })
+            .collect();
+        series.sort_by_key(|series| std::cmp::Reverse(series.watch_minutes));
+
+        Self {
+            totals: Totals {
+                series: DB.get_total_series(),
+                seasons: DB.get_total_seasons(),
+                episodes: DB.get_total_episodes(),
+                skipped_episodes: DB.get_all_skipped_episodes_count(),
+                auto_marked_episodes: DB.get_all_auto_marked_episodes_count(),
+                watch_minutes: total_watch_minutes(&series_infos_and_time),
+            },
+            series,
+            genres,
+            streaks: get_streaks(),
+        }
+    }
+}
+
+/// Get the collection of all series with their associated total
+/// average runtime
+pub async fn get_series_with_runtime() -> Vec<(SeriesMainInformation, Option<u32>)> {
+    let series_ids_handles: Vec<_> = DB
+        .get_series_collection()
+        .into_iter()
+        .map(|series| {
+            refresh_queue::spawn(RefreshPriority::Visible, async move {
+                series.get_total_average_watchtime().await
+            })
+        })
+        .collect();
+
+    let mut infos_and_time = Vec::with_capacity(series_ids_handles.len());
+    for handle in series_ids_handles {
+        infos_and_time.push(
+            handle
+                .await
+                .expect("failed to await all series_infos and their average runtime"),
+        );
+    }
+    infos_and_time
+}
+
+pub fn total_watch_minutes(series_infos_and_time: &[(SeriesMainInformation, Option<u32>)]) -> u32 {
+    series_infos_and_time
+        .iter()
+        .map(|(_, average_runtime)| average_runtime.unwrap_or(0))
+        .sum()
+}
+
+/// How many of the given series have each genre, the most common first
+pub fn genre_count(series_infos: &[&SeriesMainInformation]) -> Vec<(Genre, usize)> {
+    let mut genre_count: HashMap<Genre, usize> = HashMap::new();
+
+    series_infos.iter().for_each(|series_info| {
+        series_info.get_genres().into_iter().for_each(|genre| {
+            genre_count
+                .entry(genre)
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+        })
+    });
+
+    let mut genre_count: Vec<(Genre, usize)> = genre_count.into_iter().collect();
+    genre_count.sort_unstable_by_key(|(_, count)| std::cmp::Reverse(*count));
+    genre_count
+}
+
+/// The watch streaks from the days episodes were last marked watched
+///
+/// Episodes marked before their changes were recorded have no watch day and are left out,
+/// as are the episodes marked automatically as they aired.
+pub fn get_streaks() -> Streaks {
+    let watch_days: BTreeSet<NaiveDate> = DB
+        .get_series_id_collection()
+        .iter()
+        .filter_map(|series_id| series_id.parse::<u32>().ok())
+        .flat_map(|series_id| {
+            let auto_marked_episodes = DB.get_auto_marked_episodes(series_id);
+            DB.get_episode_changes(series_id)
+                .into_iter()
+                .filter(move |(season, episode, _)| {
+                    !auto_marked_episodes.contains(&(*season, *episode))
+                })
+        })
+        .filter(|(_, _, change)| change.watched)
+        .filter_map(|(_, _, change)| Local.timestamp_millis_opt(change.changed_at).single())
+        .map(|watched_at| watched_at.date_naive())
+        .collect();
+
+    let mut streaks = Streaks::default();
+    let mut streak = 0;
+    let mut previous_day: Option<NaiveDate> = None;
+
+    for day in watch_days.iter() {
+        streak = match previous_day {
+            Some(previous_day) if *day - previous_day == Duration::days(1) => streak + 1,
+            _ => 1,
+        };
+        streaks.longest_days = streaks.longest_days.max(streak);
+        previous_day = Some(*day);
+    }
+
+    let today = Local::now().date_naive();
+    if previous_day.is_some_and(|last_day| today - last_day <= Duration::days(1)) {
+        streaks.current_days = streak;
+    }
+
+    streaks
+}
@@ -0,0 +1,7 @@
+//! Syncing of watch data with external services and other devices
+
+pub mod lan;
+pub mod local_files;
+pub mod media_server;
+#[cfg(all(unix, not(target_os = "macos")))]
+pub mod mpris;
@@ -0,0 +1,672 @@
+//! # Local network sync
+//!
+//! Lets two instances of the app on the same network merge their watch data without
+//! any cloud account. Instances find each other through mDNS and exchange their watch
+//! state over a simple line based json protocol on tcp:
+//!
+//! 1. The accepting device sends a `Challenge` with a random nonce.
+//! 2. The connecting device answers with `Hello` with a nonce of its own, proving it
+//!    knows the pairing code.
+//! 3. The accepting device answers with `Welcome`, proving it knows the pairing code
+//!    as well. Nothing is sent or merged before both devices proved it.
+//! 4. Both devices send their `Payload` encrypted and merge the other's into their database.
+//!
+//! The key both devices prove knowing is derived from the pairing code with Argon2id
+//! salted with the nonce of the accepting device, so guessing the pairing code of a
+//! recorded sync is slow and has to be done for every sync on its own. The payloads are
+//! encrypted with AES-256-GCM using a key derived from it and the nonce of the
+//! connecting device.
+//!
+//! Conflicts are resolved per episode with the last write winning, watched winning
+//! when both changes happened at the same time.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::Argon2;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use tracing::{error, info, warn};
+
+use crate::core::background::{self, TaskKind};
+use crate::core::database::merge::{self, EpisodeStates};
+use crate::core::database::{self, EpisodeChange, DB};
+use crate::core::settings_config::{LanSyncSettings, SETTINGS};
+
+pub use mdns::DiscoveredService;
+
+mod mdns;
+
+/// How long other devices are looked for on the network
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a sync with another device can take before giving up
+const SYNC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often the settings are checked for changes while the sync service runs
+const SETTINGS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Pairing codes shorter than this are too easy to guess from a recorded sync
+pub const MIN_PAIRING_CODE_LEN: usize = 8;
+
+/// How many syncs are accepted at the same time, the rest are turned away
+const MAX_CONNECTIONS: usize = 4;
+
+/// The longest message accepted before both devices proved knowing the pairing code
+const MAX_HANDSHAKE_MESSAGE_LEN: usize = 4 * 1024;
+
+/// The longest payload message accepted
+const MAX_PAYLOAD_MESSAGE_LEN: usize = 64 * 1024 * 1024;
+
+const NONCE_LEN: usize = 16;
+const PAYLOAD_NONCE_LEN: usize = 12;
+
+#[derive(Debug, Error)]
+pub enum LanSyncError {
+    #[error("network error: {0}")]
+    Io(std::io::Error),
+    #[error("invalid message from the other device: {0}")]
+    Protocol(serde_json::Error),
+    #[error("unexpected message from the other device")]
+    UnexpectedMessage,
+    #[error("the message of the other device is too long")]
+    MessageTooLong,
+    #[error("the other device rejected the pairing code")]
+    PairingRejected,
+    #[error("the other device does not know the pairing code")]
+    AuthenticationFailed,
+    #[error("the data of the other device could not be decrypted")]
+    Decryption,
+    #[error("deriving the pairing key failed: {0}")]
+    KeyDerivation(tokio::task::JoinError),
+    #[error("the other device took too long to respond")]
+    Timeout,
+    #[error("lan sync requires a pairing code")]
+    MissingPairingCode,
+    #[error("the pairing code must be at least {MIN_PAIRING_CODE_LEN} characters long")]
+    PairingCodeTooShort,
+}
+
+/// The outcome of syncing with another device
+#[derive(Debug, Clone)]
+pub struct SyncSummary {
+    pub device_name: String,
+    /// Episodes whose watched state changed locally because of the sync
+    pub changed_episodes: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum LanMessage {
+    Challenge {
+        nonce: String,
+    },
+    Hello {
+        device_name: String,
+        nonce: String,
+        proof: String,
+    },
+    Welcome {
+        proof: String,
+    },
+    /// A `SyncPayload` encrypted with the key of the sync
+    Payload {
+        nonce: String,
+        data: String,
+    },
+    Rejected {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncPayload {
+    series: Vec<SeriesState>,
+    #[serde(default)]
+    notes: Vec<database::EpisodeNote>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SeriesState {
+    id: u32,
+    name: String,
+    is_tracked: bool,
+    episodes: Vec<EpisodeState>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EpisodeState {
+    season: u32,
+    episode: database::Episode,
+    change: EpisodeChange,
+}
+
+/// Which end of the sync a message comes from, so that a message can not be sent back
+/// to the device it came from as if it was the other's
+#[derive(Debug, Clone, Copy)]
+enum Role {
+    Connecting,
+    Accepting,
+}
+
+impl Role {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Role::Connecting => b"connecting",
+            Role::Accepting => b"accepting",
+        }
+    }
+}
+
+/// Looks for other devices on the network that have lan sync enabled
+pub async fn discover(settings: &LanSyncSettings) -> Result<Vec<DiscoveredService>, LanSyncError> {
+    let services = mdns::browse(DISCOVERY_TIMEOUT)
+        .await
+        .map_err(LanSyncError::Io)?;
+
+    Ok(services
+        .into_iter()
+        .filter(|service| service.name != settings.device_name)
+        .collect())
+}
+
+fn check_pairing_code(pairing_code: &str) -> Result<(), LanSyncError> {
+    if pairing_code.is_empty() {
+        Err(LanSyncError::MissingPairingCode)
+    } else if pairing_code.chars().count() < MIN_PAIRING_CODE_LEN {
+        Err(LanSyncError::PairingCodeTooShort)
+    } else {
+        Ok(())
+    }
+}
+
+/// Syncs with the given discovered device
+pub async fn sync_with(
+    service: &DiscoveredService,
+    settings: &LanSyncSettings,
+) -> Result<SyncSummary, LanSyncError> {
+    check_pairing_code(&settings.pairing_code)?;
+
+    tokio::time::timeout(SYNC_TIMEOUT, async {
+        let stream = TcpStream::connect(service.address)
+            .await
+            .map_err(LanSyncError::Io)?;
+        let mut connection = Connection::new(stream);
+
+        let LanMessage::Challenge { nonce } = connection.receive(MAX_HANDSHAKE_MESSAGE_LEN).await?
+        else {
+            return Err(LanSyncError::UnexpectedMessage);
+        };
+        let server_nonce: [u8; NONCE_LEN] = from_hex(&nonce)
+            .and_then(|nonce| nonce.try_into().ok())
+            .ok_or(LanSyncError::UnexpectedMessage)?;
+
+        let client_nonce = random_bytes::<NONCE_LEN>();
+        let session = Session::new(&settings.pairing_code, server_nonce, client_nonce).await?;
+
+        connection
+            .send(&LanMessage::Hello {
+                device_name: settings.device_name.clone(),
+                nonce: to_hex(&client_nonce),
+                proof: session.prove(Role::Connecting),
+            })
+            .await?;
+
+        match connection.receive(MAX_HANDSHAKE_MESSAGE_LEN).await? {
+            LanMessage::Welcome { proof } if session.verify(Role::Accepting, &proof) => {}
+            LanMessage::Welcome { .. } => return Err(LanSyncError::AuthenticationFailed),
+            LanMessage::Rejected { .. } => return Err(LanSyncError::PairingRejected),
+            _ => return Err(LanSyncError::UnexpectedMessage),
+        }
+
+        let remote_payload = match connection.receive(MAX_PAYLOAD_MESSAGE_LEN).await? {
+            LanMessage::Payload { nonce, data } => session.open(Role::Accepting, &nonce, &data)?,
+            _ => return Err(LanSyncError::UnexpectedMessage),
+        };
+
+        connection
+            .send(&session.seal(Role::Connecting, &local_payload()))
+            .await?;
+
+        Ok(SyncSummary {
+            device_name: service.name.clone(),
+            changed_episodes: merge_payload(remote_payload),
+        })
+    })
+    .await
+    .map_err(|_| LanSyncError::Timeout)?
+}
+
+/// Runs the lan sync service forever, accepting syncs and answering discovery
+/// while lan sync is enabled in the settings
+pub async fn run_sync_service() {
+    let mut running_settings: Option<LanSyncSettings> = None;
+    let mut service_handles: Vec<tokio::task::JoinHandle<()>> = vec![];
+
+    loop {
+        let lan_sync_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .lan_sync
+            .clone()
+            .filter(|settings| check_pairing_code(&settings.pairing_code).is_ok());
+
+        if lan_sync_settings != running_settings {
+            service_handles.drain(..).for_each(|handle| handle.abort());
+
+            if let Some(settings) = lan_sync_settings.clone() {
+                info!("starting lan sync service on port {}", settings.port);
+                service_handles.push(tokio::spawn(accept_syncs(settings.clone())));
+                service_handles.push(tokio::spawn(async move {
+                    if let Err(err) = mdns::respond(settings.device_name, settings.port).await {
+                        error!("lan sync discovery stopped: {}", err);
+                    }
+                }));
+            }
+            running_settings = lan_sync_settings;
+        }
+
+        tokio::time::sleep(SETTINGS_CHECK_INTERVAL).await;
+    }
+}
+
+async fn accept_syncs(settings: LanSyncSettings) {
+    let listener = match TcpListener::bind(("0.0.0.0", settings.port)).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!("failed to listen for lan syncs: {}", err);
+            return;
+        }
+    };
+
+    let connection_slots = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                let Ok(connection_slot) = connection_slots.clone().try_acquire_owned() else {
+                    warn!("turning away lan sync from {}, too many syncs", address);
+                    continue;
+                };
+
+                let settings = settings.clone();
+                background::spawn(TaskKind::LanSync, async move {
+                    let result =
+                        tokio::time::timeout(SYNC_TIMEOUT, handle_sync(stream, &settings)).await;
+                    match result {
+                        Ok(Ok(summary)) => info!(
+                            "lan sync with '{}' complete: {} episodes changed",
+                            summary.device_name, summary.changed_episodes
+                        ),
+                        Ok(Err(err)) => warn!("lan sync with {} failed: {}", address, err),
+                        Err(_) => warn!("lan sync with {} timed out", address),
+                    }
+                    drop(connection_slot);
+                });
+            }
+            Err(err) => error!("failed to accept lan sync connection: {}", err),
+        }
+    }
+}
+
+async fn handle_sync(
+    stream: TcpStream,
+    settings: &LanSyncSettings,
+) -> Result<SyncSummary, LanSyncError> {
+    let mut connection = Connection::new(stream);
+
+    let server_nonce = random_bytes::<NONCE_LEN>();
+    connection
+        .send(&LanMessage::Challenge {
+            nonce: to_hex(&server_nonce),
+        })
+        .await?;
+
+    let LanMessage::Hello {
+        device_name,
+        nonce,
+        proof,
+    } = connection.receive(MAX_HANDSHAKE_MESSAGE_LEN).await?
+    else {
+        return Err(LanSyncError::UnexpectedMessage);
+    };
+    let client_nonce: [u8; NONCE_LEN] = from_hex(&nonce)
+        .and_then(|nonce| nonce.try_into().ok())
+        .ok_or(LanSyncError::UnexpectedMessage)?;
+
+    let session = Session::new(&settings.pairing_code, server_nonce, client_nonce).await?;
+
+    if !session.verify(Role::Connecting, &proof) {
+        connection
+            .send(&LanMessage::Rejected {
+                reason: "wrong pairing code".to_owned(),
+            })
+            .await?;
+        return Err(LanSyncError::PairingRejected);
+    }
+
+    connection
+        .send(&LanMessage::Welcome {
+            proof: session.prove(Role::Accepting),
+        })
+        .await?;
+
+    connection
+        .send(&session.seal(Role::Accepting, &local_payload()))
+        .await?;
+
+    let remote_payload = match connection.receive(MAX_PAYLOAD_MESSAGE_LEN).await? {
+        LanMessage::Payload { nonce, data } => session.open(Role::Connecting, &nonce, &data)?,
+        _ => return Err(LanSyncError::UnexpectedMessage),
+    };
+
+    Ok(SyncSummary {
+        device_name,
+        changed_episodes: merge_payload(remote_payload),
+    })
+}
+
+/// The keys of a single sync
+struct Session {
+    key: [u8; 32],
+    client_nonce: [u8; NONCE_LEN],
+}
+
+impl Session {
+    async fn new(
+        pairing_code: &str,
+        server_nonce: [u8; NONCE_LEN],
+        client_nonce: [u8; NONCE_LEN],
+    ) -> Result<Self, LanSyncError> {
+        let pairing_code = pairing_code.to_owned();
+
+        // The derivation is deliberately slow
+        let key = tokio::task::spawn_blocking(move || {
+            let mut key = [0; 32];
+            Argon2::default()
+                .hash_password_into(pairing_code.as_bytes(), &server_nonce, &mut key)
+                .expect("valid key derivation parameters");
+            key
+        })
+        .await
+        .map_err(LanSyncError::KeyDerivation)?;
+
+        Ok(Self { key, client_nonce })
+    }
+
+    fn mac(&self, purpose: &[u8], role: Role) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+            .expect("hmac accepts keys of any length");
+        mac.update(purpose);
+        mac.update(role.label());
+        mac.update(&self.client_nonce);
+        mac
+    }
+
+    /// Proof of knowing the pairing code sent by the given end of the sync
+    fn prove(&self, role: Role) -> String {
+        to_hex(&self.mac(b"proof", role).finalize().into_bytes())
+    }
+
+    /// Checks the proof in constant time
+    fn verify(&self, role: Role, proof: &str) -> bool {
+        from_hex(proof).is_some_and(|proof| self.mac(b"proof", role).verify_slice(&proof).is_ok())
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        let key = self
+            .mac(b"payload", Role::Connecting)
+            .finalize()
+            .into_bytes();
+        Aes256Gcm::new(&key)
+    }
+
+    fn seal(&self, role: Role, payload: &SyncPayload) -> LanMessage {
+        let nonce = random_bytes::<PAYLOAD_NONCE_LEN>();
+        let payload = serde_json::to_vec(payload).expect("lan payload serialization");
+
+        let data = self
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &payload,
+                    aad: role.label(),
+                },
+            )
+            .expect("lan payload encryption");
+
+        LanMessage::Payload {
+            nonce: to_hex(&nonce),
+            data: to_hex(&data),
+        }
+    }
+
+    fn open(&self, role: Role, nonce: &str, data: &str) -> Result<SyncPayload, LanSyncError> {
+        let nonce = from_hex(nonce)
+            .filter(|nonce| nonce.len() == PAYLOAD_NONCE_LEN)
+            .ok_or(LanSyncError::Decryption)?;
+        let data = from_hex(data).ok_or(LanSyncError::Decryption)?;
+
+        let payload = self
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &data,
+                    aad: role.label(),
+                },
+            )
+            .map_err(|_| LanSyncError::Decryption)?;
+
+        serde_json::from_slice(&payload).map_err(LanSyncError::Protocol)
+    }
+}
+
+struct Connection {
+    reader: BufReader<tokio::net::tcp::OwnedReadHalf>,
+    writer: tokio::net::tcp::OwnedWriteHalf,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Self {
+        let (reader, writer) = stream.into_split();
+        Self {
+            reader: BufReader::new(reader),
+            writer,
+        }
+    }
+
+    async fn send(&mut self, message: &LanMessage) -> Result<(), LanSyncError> {
+        let mut line = serde_json::to_string(message).expect("lan message serialization");
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .await
+            .map_err(LanSyncError::Io)
+    }
+
+    /// Receives the next message, giving up on messages longer than `max_len`
+    async fn receive(&mut self, max_len: usize) -> Result<LanMessage, LanSyncError> {
+        let mut line = String::new();
+        (&mut self.reader)
+            .take(max_len as u64 + 1)
+            .read_line(&mut line)
+            .await
+            .map_err(LanSyncError::Io)?;
+
+        if line.len() > max_len {
+            return Err(LanSyncError::MessageTooLong);
+        }
+        serde_json::from_str(&line).map_err(LanSyncError::Protocol)
+    }
+}
+
+fn random_bytes<const LEN: usize>() -> [u8; LEN] {
+    let mut bytes = [0; LEN];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+fn local_payload() -> SyncPayload {
+    let series = DB
+        .get_series_collection()
+        .iter()
+        .map(|series| SeriesState {
+            id: series.id(),
+            name: series.get_name().to_owned(),
+            is_tracked: series.is_tracked(),
+            episodes: merge::local_episode_states(series)
+                .into_iter()
+                .map(|((season, episode), change)| EpisodeState {
+                    season,
+                    episode,
+                    change,
+                })
+                .collect(),
+        })
+        .collect();
+
+    SyncPayload {
+        series,
+        notes: DB.get_all_episode_notes(),
+    }
+}
+
+/// Merges the watch state of the other device into the database
+///
+/// Returns the amount of episodes whose watched state changed.
+fn merge_payload(payload: SyncPayload) -> usize {
+    merge::merge_notes_into_db(payload.notes);
+
+    payload
+        .series
+        .into_iter()
+        .map(|remote_series| {
+            let remote_states: EpisodeStates = remote_series
+                .episodes
+                .into_iter()
+                .map(|state| ((state.season, state.episode), state.change))
+                .collect();
+
+            merge::merge_into_db(
+                remote_series.id,
+                &remote_series.name,
+                remote_series.is_tracked,
+                &remote_states,
+            )
+            .changed_episodes()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIRING_CODE: &str = "correct horse battery";
+
+    async fn new_session(pairing_code: &str) -> Session {
+        Session::new(pairing_code, [1; NONCE_LEN], [2; NONCE_LEN])
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn proofs_are_tied_to_the_pairing_code_and_role() {
+        let session = new_session(PAIRING_CODE).await;
+        let other_session = new_session("wrong pairing code").await;
+
+        let proof = session.prove(Role::Connecting);
+        assert!(session.verify(Role::Connecting, &proof));
+        assert!(!session.verify(Role::Accepting, &proof));
+        assert!(!other_session.verify(Role::Connecting, &proof));
+        assert!(!session.verify(Role::Connecting, "not hex"));
+    }
+
+    #[tokio::test]
+    async fn proofs_are_tied_to_the_nonces() {
+        let session = new_session(PAIRING_CODE).await;
+        let other_session = Session::new(PAIRING_CODE, [1; NONCE_LEN], [3; NONCE_LEN])
+            .await
+            .unwrap();
+
+        assert!(!other_session.verify(Role::Connecting, &session.prove(Role::Connecting)));
+    }
+
+    #[tokio::test]
+    async fn payloads_round_trip_and_are_encrypted() {
+        let session = new_session(PAIRING_CODE).await;
+        let payload = SyncPayload {
+            series: vec![SeriesState {
+                id: 1,
+                name: "Some Show".to_owned(),
+                is_tracked: true,
+                episodes: vec![],
+            }],
+            notes: vec![],
+        };
+
+        let LanMessage::Payload { nonce, data } = session.seal(Role::Accepting, &payload) else {
+            panic!("sealing gives a payload message");
+        };
+        assert!(!data.contains(&to_hex(b"Some Show")));
+
+        let opened = session.open(Role::Accepting, &nonce, &data).unwrap();
+        assert_eq!(opened.series[0].name, "Some Show");
+
+        assert!(matches!(
+            session.open(Role::Connecting, &nonce, &data),
+            Err(LanSyncError::Decryption)
+        ));
+        assert!(matches!(
+            new_session("wrong pairing code")
+                .await
+                .open(Role::Accepting, &nonce, &data),
+            Err(LanSyncError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn short_pairing_codes_are_refused() {
+        assert!(matches!(
+            check_pairing_code(""),
+            Err(LanSyncError::MissingPairingCode)
+        ));
+        assert!(matches!(
+            check_pairing_code("1234"),
+            Err(LanSyncError::PairingCodeTooShort)
+        ));
+        assert!(check_pairing_code(PAIRING_CODE).is_ok());
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        assert_eq!(
+            from_hex(&to_hex(&[0, 1, 254, 255])).unwrap(),
+            [0, 1, 254, 255]
+        );
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
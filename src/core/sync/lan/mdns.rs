@@ -0,0 +1,382 @@
+//! # Minimal mDNS service discovery
+//!
+//! Only what is needed for instances of the app to find each other is implemented:
+//! - Browsing sends a one shot PTR query for the service type and collects the
+//!   SRV records of the answering instances.
+//! - Responding answers PTR queries for the service type with a PTR and an SRV record.
+//!
+//! Queries are sent from an ephemeral port so responders answer them directly with
+//! unicast as described by the legacy unicast section of RFC 6762.
+
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::Duration;
+
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+use tracing::warn;
+
+/// The service type the instances of the app advertise themselves as
+const SERVICE_TYPE: &str = "_series-troxide._tcp.local";
+
+const MDNS_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+
+const TYPE_PTR: u16 = 12;
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+const RECORD_TTL: u32 = 120;
+
+/// An instance of the app found on the local network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredService {
+    pub name: String,
+    pub address: SocketAddr,
+}
+
+/// Queries the local network for other instances of the app
+pub async fn browse(timeout: Duration) -> std::io::Result<Vec<DiscoveredService>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket
+        .send_to(&build_query(), (MDNS_ADDR, MDNS_PORT))
+        .await?;
+
+    let mut services = vec![];
+    let mut buffer = [0; 9000];
+
+    let _ = tokio::time::timeout(timeout, async {
+        loop {
+            let Ok((len, source)) = socket.recv_from(&mut buffer).await else {
+                break;
+            };
+
+            for (name, port) in parse_srv_answers(&buffer[..len]) {
+                let service = DiscoveredService {
+                    name,
+                    address: SocketAddr::new(source.ip(), port),
+                };
+                if !services.contains(&service) {
+                    services.push(service);
+                }
+            }
+        }
+    })
+    .await;
+
+    Ok(services)
+}
+
+/// Answers queries for the service type forever, advertising the given instance name and port
+pub async fn respond(instance_name: String, port: u16) -> std::io::Result<()> {
+    let socket = bind_multicast_socket()?;
+    let mut buffer = [0; 9000];
+
+    loop {
+        let (len, source) = socket.recv_from(&mut buffer).await?;
+        let packet = &buffer[..len];
+
+        if let Some(query_id) = parse_service_query(packet) {
+            let response = build_response(query_id, &instance_name, port);
+
+            // Queries from the mdns port expect multicast answers, the rest are legacy unicast queries
+            let destination = if source.port() == MDNS_PORT {
+                SocketAddr::V4(SocketAddrV4::new(MDNS_ADDR, MDNS_PORT))
+            } else {
+                source
+            };
+
+            if let Err(err) = socket.send_to(&response, destination).await {
+                warn!("failed to answer mdns query: {}", err);
+            }
+        }
+    }
+}
+
+/// Binds the mdns port alongside any other mdns responder running on the system
+fn bind_multicast_socket() -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, MDNS_PORT)).into())?;
+    socket.join_multicast_v4(&MDNS_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_nonblocking(true)?;
+
+    UdpSocket::from_std(socket.into())
+}
+
+fn build_query() -> Vec<u8> {
+    let mut packet = header(0, 0, 1, 0);
+    push_question(&mut packet);
+    packet
+}
+
+fn build_response(query_id: u16, instance_name: &str, port: u16) -> Vec<u8> {
+    let instance = format!("{}.{}", instance_name.replace('.', " "), SERVICE_TYPE);
+    let target = format!("{}.local", sanitize_host_label(instance_name));
+
+    // Echoing the question as legacy unicast resolvers expect it
+    let mut packet = header(query_id, 0x8400, 1, 2);
+    push_question(&mut packet);
+
+    push_name(&mut packet, SERVICE_TYPE);
+    let mut ptr_data = vec![];
+    push_name(&mut ptr_data, &instance);
+    push_record(&mut packet, TYPE_PTR, &ptr_data);
+
+    push_name(&mut packet, &instance);
+    let mut srv_data = vec![0, 0, 0, 0];
+    srv_data.extend_from_slice(&port.to_be_bytes());
+    push_name(&mut srv_data, &target);
+    push_record(&mut packet, TYPE_SRV, &srv_data);
+
+    packet
+}
+
+fn header(id: u16, flags: u16, questions: u16, answers: u16) -> Vec<u8> {
+    [id, flags, questions, answers, 0, 0]
+        .iter()
+        .flat_map(|field| field.to_be_bytes())
+        .collect()
+}
+
+fn push_question(packet: &mut Vec<u8>) {
+    push_name(packet, SERVICE_TYPE);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+}
+
+fn push_record(packet: &mut Vec<u8>, record_type: u16, data: &[u8]) {
+    packet.extend_from_slice(&record_type.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    packet.extend_from_slice(&RECORD_TTL.to_be_bytes());
+    packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    packet.extend_from_slice(data);
+}
+
+fn push_name(packet: &mut Vec<u8>, name: &str) {
+    for label in name.split('.') {
+        let label = &label.as_bytes()[..label.len().min(63)];
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label);
+    }
+    packet.push(0);
+}
+
+fn sanitize_host_label(name: &str) -> String {
+    name.chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Returns the id of the query when the packet is a query for the service type
+fn parse_service_query(packet: &[u8]) -> Option<u16> {
+    let id = read_u16(packet, 0)?;
+    let flags = read_u16(packet, 2)?;
+    let questions = read_u16(packet, 4)?;
+
+    // Ignoring responses
+    if flags & 0x8000 != 0 {
+        return None;
+    }
+
+    let mut offset = 12;
+    for _ in 0..questions {
+        let (name, next_offset) = read_name(packet, offset)?;
+        let question_type = read_u16(packet, next_offset)?;
+        offset = next_offset + 4;
+
+        if question_type == TYPE_PTR && name.eq_ignore_ascii_case(SERVICE_TYPE) {
+            return Some(id);
+        }
+    }
+
+    None
+}
+
+/// Returns the instance names and ports of the SRV records of the service type in the packet
+fn parse_srv_answers(packet: &[u8]) -> Vec<(String, u16)> {
+    let mut services = vec![];
+
+    let parse = |services: &mut Vec<(String, u16)>| -> Option<()> {
+        let questions = read_u16(packet, 4)?;
+        let answers = [6, 8, 10]
+            .into_iter()
+            .map(|offset| read_u16(packet, offset).map(usize::from))
+            .sum::<Option<usize>>()?;
+
+        let mut offset = 12;
+        for _ in 0..questions {
+            offset = read_name(packet, offset)?.1 + 4;
+        }
+
+        for _ in 0..answers {
+            let (name, next_offset) = read_name(packet, offset)?;
+            let record_type = read_u16(packet, next_offset)?;
+            let data_len = read_u16(packet, next_offset + 8)? as usize;
+            let data_offset = next_offset + 10;
+            offset = data_offset + data_len;
+
+            if record_type != TYPE_SRV {
+                continue;
+            }
+
+            if let Some(instance_name) = strip_service_type(&name) {
+                let port = read_u16(packet, data_offset + 4)?;
+                services.push((instance_name.to_owned(), port));
+            }
+        }
+        Some(())
+    };
+
+    parse(&mut services);
+    services
+}
+
+/// The instance name of a service name of the service type, ignoring the case of the type
+fn strip_service_type(name: &str) -> Option<&str> {
+    let suffix = format!(".{}", SERVICE_TYPE);
+    let split = name.len().checked_sub(suffix.len())?;
+
+    // Names come from the network, the split may not fall on a char boundary
+    name.get(split..)
+        .filter(|type_name| type_name.eq_ignore_ascii_case(&suffix))
+        .and_then(|_| name.get(..split))
+}
+
+fn read_u16(packet: &[u8], offset: usize) -> Option<u16> {
+    let bytes = packet.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads a possibly compressed name returning it together with the offset right after it
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = vec![];
+    let mut end_offset = None;
+
+    // Bounding the amount of jumps to guard against compression loops
+    for _ in 0..128 {
+        let len = *packet.get(offset)? as usize;
+
+        if len == 0 {
+            return Some((labels.join("."), end_offset.unwrap_or(offset + 1)));
+        }
+
+        if len & 0xC0 == 0xC0 {
+            let pointer = (read_u16(packet, offset)? & 0x3FFF) as usize;
+            end_offset.get_or_insert(offset + 2);
+            offset = pointer;
+            continue;
+        }
+
+        let label = packet.get(offset + 1..offset + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        offset += 1 + len;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_is_parsed_back() {
+        let response = build_response(7, "Living Room", 47474);
+        assert_eq!(
+            parse_srv_answers(&response),
+            vec![("Living Room".to_owned(), 47474)]
+        );
+    }
+
+    #[test]
+    fn dots_in_instance_names_are_replaced() {
+        let response = build_response(7, "my.laptop", 47474);
+        assert_eq!(
+            parse_srv_answers(&response),
+            vec![("my laptop".to_owned(), 47474)]
+        );
+    }
+
+    #[test]
+    fn service_type_case_is_ignored() {
+        let mut packet = header(0, 0x8400, 0, 1);
+        push_name(&mut packet, "Kitchen._SERIES-TROXIDE._TCP.LOCAL");
+        let mut srv_data = vec![0, 0, 0, 0];
+        srv_data.extend_from_slice(&1234_u16.to_be_bytes());
+        push_name(&mut srv_data, "kitchen.local");
+        push_record(&mut packet, TYPE_SRV, &srv_data);
+
+        assert_eq!(
+            parse_srv_answers(&packet),
+            vec![("Kitchen".to_owned(), 1234)]
+        );
+    }
+
+    #[test]
+    fn other_services_are_ignored() {
+        let mut packet = header(0, 0x8400, 0, 1);
+        push_name(&mut packet, "Printer._ipp._tcp.local");
+        let mut srv_data = vec![0, 0, 0, 0];
+        srv_data.extend_from_slice(&631_u16.to_be_bytes());
+        push_name(&mut srv_data, "printer.local");
+        push_record(&mut packet, TYPE_SRV, &srv_data);
+
+        assert!(parse_srv_answers(&packet).is_empty());
+    }
+
+    #[test]
+    fn names_changing_length_when_lowercased_are_kept() {
+        // 'İ' lowercases to more bytes than it has
+        let response = build_response(7, "İstanbul", 47474);
+        assert_eq!(
+            parse_srv_answers(&response),
+            vec![("İstanbul".to_owned(), 47474)]
+        );
+    }
+
+    #[test]
+    fn names_shorter_than_the_service_type_are_ignored() {
+        assert_eq!(strip_service_type("İİ.local"), None);
+        assert_eq!(strip_service_type("ü_series-troxide._tcp.local"), None);
+        assert_eq!(
+            strip_service_type("a._series-troxide._tcp.local"),
+            Some("a")
+        );
+    }
+
+    #[test]
+    fn answer_counts_do_not_overflow() {
+        let mut packet = header(0, 0x8400, 0, 0);
+        packet[6..12].copy_from_slice(&[0xFF; 6]);
+        assert!(parse_srv_answers(&packet).is_empty());
+    }
+
+    #[test]
+    fn truncated_and_looping_packets_are_ignored() {
+        let response = build_response(7, "Living Room", 47474);
+        for len in 0..response.len() {
+            parse_srv_answers(&response[..len]);
+        }
+
+        // A name pointing at itself
+        let mut packet = header(0, 0x8400, 0, 1);
+        packet.extend_from_slice(&[0xC0, 12]);
+        assert!(parse_srv_answers(&packet).is_empty());
+    }
+
+    #[test]
+    fn service_queries_are_recognized() {
+        assert_eq!(parse_service_query(&build_query()), Some(0));
+        assert_eq!(
+            parse_service_query(&build_response(7, "Living Room", 47474)),
+            None
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! # Local files
+//!
+//! Watches the folders the user downloads episodes to and matches the video files appearing
+//! there to the tracked series by their names, like `Show.Name.S02E05.1080p.mkv`. Matched
+//! episodes are only suggested, the user reviews them before they are marked watched.
+//!
+//! Files being opened are also matched on the platforms reporting it, on the others only
+//! new files are noticed.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use notify::event::{AccessKind, ModifyKind};
+use notify::{EventKind, RecursiveMode, Watcher};
+use tracing::warn;
+
+use crate::core::episode_matching;
+
+/// The extensions of the files taken as episodes
+const VIDEO_EXTENSIONS: [&str; 9] = [
+    "mkv", "mp4", "avi", "m4v", "mov", "wmv", "webm", "ts", "mpg",
+];
+
+/// How often the watcher checks whether it should stop while no files change
+const STOP_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An unwatched episode of a tracked series matched from a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedEpisode {
+    pub series_id: u32,
+    pub series_name: String,
+    pub season: u32,
+    pub episode: u32,
+    pub file_name: String,
+}
+
+fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| VIDEO_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Matches the video file to an unwatched episode of a tracked series
+pub fn match_file(path: &Path) -> Option<DetectedEpisode> {
+    if !is_video_file(path) {
+        return None;
+    }
+
+    let file_name = path.file_name()?.to_str()?;
+    let matched_episode = episode_matching::match_unwatched_episode(path.file_stem()?.to_str()?)?;
+
+    Some(DetectedEpisode {
+        series_id: matched_episode.series_id,
+        series_name: matched_episode.series_name,
+        season: matched_episode.season,
+        episode: matched_episode.episode,
+        file_name: file_name.to_owned(),
+    })
+}
+
+/// Watches the folders and their subfolders, calling `on_detected` for the matched files
+///
+/// Blocks until `should_stop` returns true, so it's meant to be run on its own thread.
+pub fn watch_folders(
+    folders: &[PathBuf],
+    mut on_detected: impl FnMut(DetectedEpisode),
+    should_stop: impl Fn() -> bool,
+) -> notify::Result<()> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+
+    for folder in folders {
+        if let Err(err) = watcher.watch(folder, RecursiveMode::Recursive) {
+            warn!("failed to watch '{}': {}", folder.display(), err);
+        }
+    }
+
+    loop {
+        match receiver.recv_timeout(STOP_CHECK_INTERVAL) {
+            Ok(Ok(event)) => {
+                let is_file_appearing_or_opened = matches!(
+                    event.kind,
+                    EventKind::Create(_)
+                        | EventKind::Modify(ModifyKind::Name(_))
+                        | EventKind::Access(AccessKind::Open(_))
+                );
+                if is_file_appearing_or_opened {
+                    event
+                        .paths
+                        .iter()
+                        .filter_map(|path| match_file(path))
+                        .for_each(&mut on_detected);
+                }
+            }
+            Ok(Err(err)) => warn!("local files watcher error: {}", err),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+
+        if should_stop() {
+            return Ok(());
+        }
+    }
+}
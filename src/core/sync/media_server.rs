@@ -0,0 +1,446 @@
+//! # Media server syncing
+//!
+//! Imports episodes marked as played on a Jellyfin or Plex server into the
+//! local database. Shows are matched to their TVmaze counterparts using their
+//! IMDb id, falling back to their TVDB id and lastly their name.
+
+use std::collections::HashMap;
+use std::time;
+
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::{error, info, warn};
+
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::api::tv_maze::series_searching::search_series;
+use crate::core::api::tv_maze::show_lookup::{show_lookup, Id};
+use crate::core::api::tv_maze::ApiError as TvMazeApiError;
+use crate::core::background::{self, TaskKind};
+use crate::core::caching::series_information::cache_series_information;
+use crate::core::database::{Series, DB};
+use crate::core::settings_config::{MediaServerKind, MediaServerSettings, SETTINGS};
+
+#[derive(Debug, Error)]
+pub enum MediaServerError {
+    #[error("network error during request: {0}")]
+    Network(reqwest::Error),
+    #[error("invalid status code from media server: '{0}'")]
+    InvalidStatusCode(reqwest::StatusCode),
+    #[error("media server error when deserializing json: {0}")]
+    Deserialization(serde_json::Error),
+    #[error("invalid media server token")]
+    InvalidToken,
+    #[error("jellyfin sync requires a user id")]
+    MissingUserId,
+}
+
+/// An episode the media server reports as played
+#[derive(Debug, Clone)]
+pub struct PlayedEpisode {
+    pub season: u32,
+    pub episode: u32,
+}
+
+/// A show from the media server together with it's played episodes
+#[derive(Debug, Clone, Default)]
+pub struct PlayedShow {
+    pub name: String,
+    pub imdb_id: Option<String>,
+    pub tvdb_id: Option<u32>,
+    pub episodes: Vec<PlayedEpisode>,
+}
+
+/// The outcome of a media server sync
+#[derive(Debug, Clone, Default)]
+pub struct SyncSummary {
+    /// Episodes that were not in the database before the sync
+    pub imported_episodes: usize,
+    /// Names of the shows that could not be matched on TVmaze
+    pub unmatched_shows: Vec<String>,
+    /// Names of the shows whose TVmaze lookup failed, to be retried on the next sync
+    pub failed_shows: Vec<String>,
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    url: String,
+    headers: HeaderMap,
+) -> Result<T, MediaServerError> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(MediaServerError::Network)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(MediaServerError::InvalidStatusCode(status));
+    }
+
+    let text = response.text().await.map_err(MediaServerError::Network)?;
+    serde_json::from_str(&text).map_err(MediaServerError::Deserialization)
+}
+
+fn token_header(name: &'static str, token: &str) -> Result<HeaderMap, MediaServerError> {
+    let mut headers = HeaderMap::new();
+    headers.insert("Accept", HeaderValue::from_static("application/json"));
+    headers.insert(
+        name,
+        HeaderValue::from_str(token).map_err(|_| MediaServerError::InvalidToken)?,
+    );
+    Ok(headers)
+}
+
+mod jellyfin {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Items<T> {
+        items: Vec<T>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct JellyfinSeries {
+        id: String,
+        name: String,
+        #[serde(default)]
+        provider_ids: HashMap<String, String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct JellyfinEpisode {
+        series_id: Option<String>,
+        series_name: Option<String>,
+        parent_index_number: Option<u32>,
+        index_number: Option<u32>,
+    }
+
+    pub async fn get_played_shows(
+        settings: &MediaServerSettings,
+    ) -> Result<Vec<PlayedShow>, MediaServerError> {
+        let user_id = settings
+            .user_id
+            .as_ref()
+            .ok_or(MediaServerError::MissingUserId)?;
+        let address = settings.address.trim_end_matches('/');
+        let headers = token_header("X-Emby-Token", &settings.token)?;
+
+        let series_url = format!(
+            "{}/Users/{}/Items?IncludeItemTypes=Series&Recursive=true&Fields=ProviderIds",
+            address, user_id
+        );
+        let episodes_url = format!(
+            "{}/Users/{}/Items?IncludeItemTypes=Episode&Recursive=true&IsPlayed=true",
+            address, user_id
+        );
+
+        let series: Items<JellyfinSeries> = get_json(series_url, headers.clone()).await?;
+        let episodes: Items<JellyfinEpisode> = get_json(episodes_url, headers).await?;
+
+        let mut shows: HashMap<String, PlayedShow> = series
+            .items
+            .into_iter()
+            .map(|series| {
+                let show = PlayedShow {
+                    name: series.name,
+                    imdb_id: series.provider_ids.get("Imdb").cloned(),
+                    tvdb_id: series
+                        .provider_ids
+                        .get("Tvdb")
+                        .and_then(|id| id.parse().ok()),
+                    episodes: vec![],
+                };
+                (series.id, show)
+            })
+            .collect();
+
+        for episode in episodes.items {
+            let (Some(season), Some(episode_number)) =
+                (episode.parent_index_number, episode.index_number)
+            else {
+                continue;
+            };
+
+            let show = shows
+                .entry(episode.series_id.unwrap_or_default())
+                .or_insert_with(|| PlayedShow {
+                    name: episode.series_name.unwrap_or_default(),
+                    ..Default::default()
+                });
+
+            show.episodes.push(PlayedEpisode {
+                season,
+                episode: episode_number,
+            });
+        }
+
+        Ok(shows.into_values().collect())
+    }
+}
+
+mod plex {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Response<T> {
+        media_container: T,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Sections {
+        #[serde(default)]
+        directory: Vec<Section>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Section {
+        key: String,
+        #[serde(rename = "type")]
+        kind: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Metadata<T> {
+        #[serde(default = "Vec::new")]
+        metadata: Vec<T>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct PlexShow {
+        #[serde(rename = "ratingKey")]
+        rating_key: String,
+        title: String,
+        #[serde(default, rename = "Guid")]
+        guids: Vec<PlexGuid>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct PlexGuid {
+        id: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PlexEpisode {
+        grandparent_rating_key: Option<String>,
+        grandparent_title: Option<String>,
+        parent_index: Option<u32>,
+        index: Option<u32>,
+        #[serde(default)]
+        view_count: u32,
+    }
+
+    pub async fn get_played_shows(
+        settings: &MediaServerSettings,
+    ) -> Result<Vec<PlayedShow>, MediaServerError> {
+        let address = settings.address.trim_end_matches('/');
+        let headers = token_header("X-Plex-Token", &settings.token)?;
+
+        let sections: Response<Sections> =
+            get_json(format!("{}/library/sections", address), headers.clone()).await?;
+
+        let mut shows: HashMap<String, PlayedShow> = HashMap::new();
+
+        for section in sections
+            .media_container
+            .directory
+            .into_iter()
+            .filter(|section| section.kind == "show")
+        {
+            // type 2 are shows and type 4 are episodes
+            let shows_url = format!(
+                "{}/library/sections/{}/all?type=2&includeGuids=1",
+                address, section.key
+            );
+            let episodes_url = format!(
+                "{}/library/sections/{}/all?type=4&viewCount>=1",
+                address, section.key
+            );
+
+            let plex_shows: Response<Metadata<PlexShow>> =
+                get_json(shows_url, headers.clone()).await?;
+            let plex_episodes: Response<Metadata<PlexEpisode>> =
+                get_json(episodes_url, headers.clone()).await?;
+
+            for plex_show in plex_shows.media_container.metadata {
+                let guid = |prefix: &str| {
+                    plex_show
+                        .guids
+                        .iter()
+                        .find_map(|guid| guid.id.strip_prefix(prefix).map(str::to_owned))
+                };
+
+                let show = PlayedShow {
+                    name: plex_show.title.clone(),
+                    imdb_id: guid("imdb://"),
+                    tvdb_id: guid("tvdb://").and_then(|id| id.parse().ok()),
+                    episodes: vec![],
+                };
+                shows.insert(plex_show.rating_key, show);
+            }
+
+            // Older servers ignore the filter, so unplayed episodes are dropped here as well
+            for episode in plex_episodes
+                .media_container
+                .metadata
+                .into_iter()
+                .filter(|episode| episode.view_count > 0)
+            {
+                let (Some(season), Some(episode_number)) = (episode.parent_index, episode.index)
+                else {
+                    continue;
+                };
+
+                let show = shows
+                    .entry(episode.grandparent_rating_key.unwrap_or_default())
+                    .or_insert_with(|| PlayedShow {
+                        name: episode.grandparent_title.unwrap_or_default(),
+                        ..Default::default()
+                    });
+
+                show.episodes.push(PlayedEpisode {
+                    season,
+                    episode: episode_number,
+                });
+            }
+        }
+
+        Ok(shows.into_values().collect())
+    }
+}
+
+/// Fetches all the shows with played episodes from the configured media server
+pub async fn get_played_shows(
+    settings: &MediaServerSettings,
+) -> Result<Vec<PlayedShow>, MediaServerError> {
+    match settings.kind {
+        MediaServerKind::Jellyfin => jellyfin::get_played_shows(settings).await,
+        MediaServerKind::Plex => plex::get_played_shows(settings).await,
+    }
+}
+
+/// Finds the TVmaze show corresponding to the given media server show
+async fn match_show(
+    played_show: &PlayedShow,
+) -> Result<Option<SeriesMainInformation>, TvMazeApiError> {
+    if let Some(imdb_id) = &played_show.imdb_id {
+        if let Some(series_info) = show_lookup(Id::Imdb(imdb_id.clone())).await? {
+            return Ok(Some(series_info));
+        }
+    }
+
+    if let Some(tvdb_id) = played_show.tvdb_id {
+        if let Some(series_info) = show_lookup(Id::Tvdb(tvdb_id)).await? {
+            return Ok(Some(series_info));
+        }
+    }
+
+    // Falling back to the show name, only accepting exact matches to avoid
+    // importing episodes into the wrong show
+    Ok(search_series(played_show.name.clone())
+        .await?
+        .into_iter()
+        .map(|search_result| search_result.show)
+        .find(|series_info| series_info.name.eq_ignore_ascii_case(&played_show.name)))
+}
+
+/// Imports all played episodes from the media server into the database
+///
+/// Matched shows are marked as tracked, episodes already in the database are left untouched.
+pub async fn sync(settings: &MediaServerSettings) -> Result<SyncSummary, MediaServerError> {
+    let played_shows = get_played_shows(settings).await?;
+
+    let mut summary = SyncSummary::default();
+
+    for played_show in played_shows
+        .into_iter()
+        .filter(|show| !show.episodes.is_empty())
+    {
+        let series_info = match match_show(&played_show).await {
+            Ok(Some(series_info)) => series_info,
+            Ok(None) => {
+                warn!("could not match '{}' on tvmaze", played_show.name);
+                summary.unmatched_shows.push(played_show.name);
+                continue;
+            }
+            Err(err) => {
+                error!(
+                    "failed to look up '{}' on tvmaze: {}",
+                    played_show.name, err
+                );
+                summary.failed_shows.push(played_show.name);
+                continue;
+            }
+        };
+
+        cache_series_information(&series_info).await;
+
+        let mut series = DB
+            .get_series(series_info.id)
+            .unwrap_or_else(|| Series::new(series_info.name, series_info.id));
+        series.mark_tracked();
+
+        for played_episode in played_show.episodes {
+            let already_watched = series
+                .get_season(played_episode.season)
+                .map(|season| season.is_episode_watched(played_episode.episode))
+                .unwrap_or(false);
+
+            if !already_watched {
+                series.add_episode_unchecked(played_episode.season, played_episode.episode);
+                summary.imported_episodes += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Runs the media server sync forever at the interval set in the settings
+///
+/// Sync is skipped when no media server is configured.
+pub async fn run_scheduled_sync() {
+    loop {
+        let media_server_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .media_server
+            .clone();
+
+        // Rechecking the settings every hour when no media server is configured
+        let mut sync_interval = 1;
+
+        if let Some(settings) = media_server_settings {
+            sync_interval = settings.sync_interval.max(1);
+
+            info!("syncing watched episodes from {}", settings.kind);
+            let sync_result = background::run(TaskKind::Sync, {
+                let settings = settings.clone();
+                async move { sync(&settings).await }
+            })
+            .await;
+
+            match sync_result {
+                Ok(Ok(summary)) => info!(
+                    "{} sync complete: {} episodes imported, {} shows unmatched, {} shows failed",
+                    settings.kind,
+                    summary.imported_episodes,
+                    summary.unmatched_shows.len(),
+                    summary.failed_shows.len()
+                ),
+                Ok(Err(err)) => error!("failed to sync with {}: {}", settings.kind, err),
+                Err(err) => error!("failed to sync with {}: {}", settings.kind, err),
+            }
+        }
+
+        tokio::time::sleep(time::Duration::from_secs(sync_interval as u64 * 60 * 60)).await;
+    }
+}
@@ -0,0 +1,170 @@
+//! # MPRIS scrobbling
+//!
+//! Listens to what the allowed media players are playing through MPRIS, the D-Bus interface
+//! of media players on Linux. An episode of a tracked series is marked watched once 90% of it
+//! has been played, when the player's title reads like `Show – S01E02 – Episode Name`.
+//!
+//! The players don't announce their playback position, so they are polled instead.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use thiserror::Error;
+use tracing::{info, warn};
+use zbus::blocking::{fdo::DBusProxy, Connection, Proxy, ProxyBuilder};
+use zbus::zvariant::OwnedValue;
+use zbus::CacheProperties;
+
+use crate::core::database::DB;
+use crate::core::episode_matching;
+use crate::core::settings_config::{MediaPlayerSettings, SETTINGS};
+
+/// The prefix of the bus names of media players
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+/// How often the players are checked for their playback position
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How much of an episode has to be played for it to be marked watched
+const WATCHED_FRACTION: f64 = 0.9;
+
+#[derive(Debug, Error)]
+pub enum MprisError {
+    #[error("d-bus error: {0}")]
+    DBus(#[from] zbus::Error),
+    #[error("d-bus error: {0}")]
+    Fdo(#[from] zbus::fdo::Error),
+}
+
+/// Watches the allowed players for as long as the app runs, marking the played episodes
+pub fn run_scrobbler() {
+    let mut connection: Option<Connection> = None;
+    // The titles already handled by each player, so that they are matched only once
+    let mut handled_titles: HashSet<(String, String)> = HashSet::new();
+
+    loop {
+        let settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .media_players
+            .clone();
+
+        if settings.enabled && !settings.allowed_players.is_empty() {
+            if connection.is_none() {
+                connection = Connection::session()
+                    .map_err(|err| warn!("failed to connect to the session bus: {}", err))
+                    .ok();
+            }
+
+            if let Some(session) = &connection {
+                if let Err(err) = scrobble_players(session, &settings, &mut handled_titles) {
+                    warn!("failed to check the media players: {}", err);
+                    connection = None;
+                }
+            }
+        } else {
+            connection = None;
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Whether the player, named like `vlc` or `firefox.instance123`, is an allowed one
+fn is_allowed_player(player: &str, settings: &MediaPlayerSettings) -> bool {
+    settings.allowed_players.iter().any(|allowed_player| {
+        player == allowed_player
+            || player
+                .strip_prefix(allowed_player.as_str())
+                .is_some_and(|instance| instance.starts_with('.'))
+    })
+}
+
+fn scrobble_players(
+    connection: &Connection,
+    settings: &MediaPlayerSettings,
+    handled_titles: &mut HashSet<(String, String)>,
+) -> Result<(), MprisError> {
+    let bus_names = DBusProxy::new(connection)?.list_names()?;
+
+    for bus_name in bus_names {
+        let Some(player) = bus_name.strip_prefix(MPRIS_PREFIX) else {
+            continue;
+        };
+        if !is_allowed_player(player, settings) {
+            continue;
+        }
+
+        // A player closing while being checked is not an error with the session
+        let title = match played_title(connection, bus_name.as_str()) {
+            Ok(title) => title,
+            Err(err) => {
+                warn!("failed to read the playback of '{}': {}", player, err);
+                continue;
+            }
+        };
+
+        if let Some(title) = title {
+            if handled_titles.insert((player.to_owned(), title.clone())) {
+                mark_watched(player, &title);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The title the player is playing when it has played most of it
+fn played_title(connection: &Connection, bus_name: &str) -> Result<Option<String>, MprisError> {
+    // The position changes without the player signalling it, so nothing is cached
+    let proxy: Proxy<'_> = ProxyBuilder::new_bare(connection)
+        .destination(bus_name)?
+        .path(MPRIS_PATH)?
+        .interface(PLAYER_INTERFACE)?
+        .cache_properties(CacheProperties::No)
+        .build()?;
+
+    let metadata: HashMap<String, OwnedValue> = proxy.get_property("Metadata")?;
+    let Some(title) = metadata
+        .get("xesam:title")
+        .and_then(|title| <&str>::try_from(title).ok())
+        .map(str::to_owned)
+    else {
+        return Ok(None);
+    };
+
+    // Players disagree on whether the length is signed
+    let Some(length) = metadata.get("mpris:length").and_then(|length| {
+        i64::try_from(length)
+            .ok()
+            .or_else(|| u64::try_from(length).ok().map(|length| length as i64))
+    }) else {
+        return Ok(None);
+    };
+    let position: i64 = proxy.get_property("Position")?;
+
+    if length > 0 && position as f64 >= length as f64 * WATCHED_FRACTION {
+        Ok(Some(title))
+    } else {
+        Ok(None)
+    }
+}
+
+fn mark_watched(player: &str, title: &str) {
+    let Some(matched_episode) = episode_matching::match_unwatched_episode(title) else {
+        return;
+    };
+    let Some(mut series) = DB.get_series(matched_episode.series_id) else {
+        return;
+    };
+
+    series.add_episode_unchecked(matched_episode.season, matched_episode.episode);
+    info!(
+        "marked {} S{:02}E{:02} watched from '{}'",
+        matched_episode.series_name, matched_episode.season, matched_episode.episode, player
+    );
+}
@@ -0,0 +1,222 @@
+//! # Watch goal
+//!
+//! Compares the time spent watching in the current week or month against the goal set in
+//! the settings. The watch time is worked out from when the episodes were marked watched and
+//! their runtime, or the average runtime of their series when the episode has none.
+//!
+//! A gentle notification can be sent once per period when the goal is exceeded.
+
+use std::time;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone};
+use tracing::{error, info};
+
+use super::caching::episode_list::EpisodeList;
+use super::caching::series_information;
+use super::database::DB;
+use super::date_formatting;
+use super::paths;
+use super::settings_config::{WatchGoalPeriod, WatchGoalSettings, SETTINGS};
+
+/// File in the data directory remembering the start of the last period notified about
+const LAST_NOTIFIED_PERIOD_FILE_NAME: &str = "last-watch-goal-notification";
+
+/// The watch time of the current period next to the goal
+#[derive(Debug, Clone)]
+pub struct WatchGoalProgress {
+    pub period: WatchGoalPeriod,
+    pub watched_minutes: u32,
+    pub goal_minutes: u32,
+}
+
+impl WatchGoalProgress {
+    pub fn is_exceeded(&self) -> bool {
+        self.watched_minutes > self.goal_minutes
+    }
+
+    /// The watched fraction of the goal, going over 1.0 when exceeded
+    pub fn fraction(&self) -> f32 {
+        self.watched_minutes as f32 / self.goal_minutes as f32
+    }
+}
+
+/// When the current period started, weeks starting on the day set in the locale settings
+pub fn get_period_start(period: WatchGoalPeriod) -> DateTime<Local> {
+    let today = Local::now().date_naive();
+
+    let start_date = match period {
+        WatchGoalPeriod::Weekly => date_formatting::get_week_start(today),
+        WatchGoalPeriod::Monthly => {
+            NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap_or(today)
+        }
+    };
+
+    start_date
+        .and_hms_opt(0, 0, 0)
+        .and_then(|start| Local.from_local_datetime(&start).earliest())
+        .unwrap_or_else(Local::now)
+}
+
+/// Gets the progress toward the goal set in the settings, `None` when no goal is set
+pub async fn get_watch_goal_progress() -> anyhow::Result<Option<WatchGoalProgress>> {
+    let watch_goal = get_watch_goal_settings();
+
+    if watch_goal.minutes == 0 {
+        return Ok(None);
+    }
+
+    let period_start = get_period_start(watch_goal.period);
+    let watched_minutes = get_watched_minutes_since(period_start).await?;
+
+    Ok(Some(WatchGoalProgress {
+        period: watch_goal.period,
+        watched_minutes,
+        goal_minutes: watch_goal.minutes,
+    }))
+}
+
+/// Sums the runtime of the episodes marked watched since the given time
+async fn get_watched_minutes_since(since: DateTime<Local>) -> anyhow::Result<u32> {
+    let since_millis = since.timestamp_millis();
+
+    // Only the ids are kept as series write themselves back to the database when dropped
+    let series_ids: Vec<u32> = DB
+        .get_series_collection()
+        .iter()
+        .map(|series| series.id())
+        .collect();
+
+    let mut watched_minutes = 0;
+
+    for series_id in series_ids {
+        let recent_watches: Vec<_> = DB
+            .get_episode_changes(series_id)
+            .into_iter()
+            .filter(|(_, _, change)| change.watched && change.changed_at >= since_millis)
+            .collect();
+
+        if recent_watches.is_empty() {
+            continue;
+        }
+
+        let episode_list = EpisodeList::new(series_id).await?;
+        let average_runtime = series_information::get_series_main_info_with_id(series_id)
+            .await?
+            .average_runtime;
+
+        watched_minutes += recent_watches
+            .into_iter()
+            .filter_map(|(season, number, _)| {
+                episode_list
+                    .get_episode(season, number)
+                    .and_then(|episode| episode.runtime)
+                    .or(average_runtime)
+            })
+            .sum::<u32>();
+    }
+
+    Ok(watched_minutes)
+}
+
+fn get_watch_goal_settings() -> WatchGoalSettings {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .watch_goal
+        .clone()
+}
+
+/// Notifies once per period when the goal is exceeded, forever while enabled in the settings
+pub async fn run_watch_goal_notifications() {
+    loop {
+        let watch_goal = get_watch_goal_settings();
+
+        if watch_goal.notify_when_exceeded && watch_goal.minutes != 0 {
+            let period_start = get_period_start(watch_goal.period);
+            let already_notified = get_last_notified_period().await == Some(period_start);
+
+            if !already_notified {
+                match get_watch_goal_progress().await {
+                    Ok(Some(progress)) if progress.is_exceeded() => {
+                        info!("notifying the exceeded watch goal");
+                        notify_exceeded_goal(&progress);
+                        save_last_notified_period(period_start).await;
+                    }
+                    Ok(_) => {}
+                    Err(err) => error!("failed to get the watch goal progress: {}", err),
+                }
+            }
+        }
+
+        // Rechecking every hour so that settings changes and new watches are picked up
+        tokio::time::sleep(time::Duration::from_secs(60 * 60)).await;
+    }
+}
+
+fn notify_exceeded_goal(progress: &WatchGoalProgress) {
+    let period = match progress.period {
+        WatchGoalPeriod::Weekly => "week",
+        WatchGoalPeriod::Monthly => "month",
+    };
+
+    let notification_body = format!(
+        "You have watched {} this {}, over your goal of {}.",
+        minutes_str(progress.watched_minutes),
+        period,
+        minutes_str(progress.goal_minutes),
+    );
+
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("Series Troxide")
+        .summary("Watch goal exceeded")
+        .body(&notification_body)
+        .auto_icon()
+        .show()
+    {
+        error!("failed to show the watch goal notification: {}", err);
+    }
+}
+
+/// Formats minutes like `2 hours 30 minutes`
+pub fn minutes_str(minutes: u32) -> String {
+    let hours = minutes / 60;
+    let minutes = minutes % 60;
+
+    match (hours, minutes) {
+        (0, minutes) => format!("{} minutes", minutes),
+        (hours, 0) => format!("{} hours", hours),
+        (hours, minutes) => format!("{} hours {} minutes", hours, minutes),
+    }
+}
+
+async fn get_last_notified_period() -> Option<DateTime<Local>> {
+    let last_notified_period = tokio::fs::read_to_string(get_last_notified_period_file_path())
+        .await
+        .ok()?;
+
+    DateTime::parse_from_rfc3339(last_notified_period.trim())
+        .ok()
+        .map(|period_start| period_start.with_timezone(&Local))
+}
+
+async fn save_last_notified_period(period_start: DateTime<Local>) {
+    if let Err(err) = tokio::fs::write(
+        get_last_notified_period_file_path(),
+        period_start.to_rfc3339(),
+    )
+    .await
+    {
+        error!("failed to save the last watch goal notification: {}", err);
+    }
+}
+
+fn get_last_notified_period_file_path() -> std::path::PathBuf {
+    let mut last_notified_period_file = paths::PATHS
+        .read()
+        .expect("failed to read paths")
+        .get_data_dir_path()
+        .to_path_buf();
+    last_notified_period_file.push(LAST_NOTIFIED_PERIOD_FILE_NAME);
+    last_notified_period_file
+}
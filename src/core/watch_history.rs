@@ -0,0 +1,88 @@
+//! # Watch history
+//!
+//! The log of watched episodes built from the recorded episode changes, printed by the
+//! `history` command.
+//!
+//! Only the last change of every episode is recorded, so an episode watched again shows
+//! once at the time it was last marked. Episodes marked before changes were recorded are
+//! left out as they have no watch time.
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+
+use super::caching::episode_list::EpisodeList;
+use super::database::DB;
+
+#[derive(Debug, Clone)]
+pub struct WatchEntry {
+    pub watched_at: DateTime<Local>,
+    pub series_id: u32,
+    pub series_name: String,
+    pub season: u32,
+    pub number: u32,
+    /// `None` when the episode is not in the series' episode list
+    pub episode_name: Option<String>,
+}
+
+/// The watched episodes, the oldest first
+///
+/// Limited to the episodes watched on and after the `since` day and to the given series
+/// when set.
+pub async fn get_watch_history(
+    since: Option<NaiveDate>,
+    series_id: Option<u32>,
+) -> anyhow::Result<Vec<WatchEntry>> {
+    let since_millis = since
+        .and_then(|since| since.and_hms_opt(0, 0, 0))
+        .and_then(|since| Local.from_local_datetime(&since).earliest())
+        .map(|since| since.timestamp_millis())
+        .unwrap_or(i64::MIN);
+
+    // Only the basics are kept as series write themselves back to the database when dropped
+    let series_collection: Vec<(u32, String)> = DB
+        .get_series_collection()
+        .iter()
+        .filter(|series| series_id.is_none_or(|series_id| series.id() == series_id))
+        .map(|series| (series.id(), series.get_name().to_owned()))
+        .collect();
+
+    let mut history = vec![];
+
+    for (series_id, series_name) in series_collection {
+        let watches: Vec<_> = DB
+            .get_episode_changes(series_id)
+            .into_iter()
+            .filter(|(_, _, change)| change.watched && change.changed_at >= since_millis)
+            .collect();
+
+        if watches.is_empty() {
+            continue;
+        }
+
+        // The names are nice to have, the history is still useful without them
+        let episode_list = EpisodeList::new(series_id)
+            .await
+            .map_err(|err| tracing::warn!("no episode names for series {}: {}", series_id, err))
+            .ok();
+
+        for (season, number, change) in watches {
+            let Some(watched_at) = Local.timestamp_millis_opt(change.changed_at).single() else {
+                continue;
+            };
+
+            history.push(WatchEntry {
+                watched_at,
+                series_id,
+                series_name: series_name.clone(),
+                season,
+                number,
+                episode_name: episode_list
+                    .as_ref()
+                    .and_then(|episode_list| episode_list.get_episode(season, number))
+                    .map(|episode| episode.name.clone()),
+            });
+        }
+    }
+
+    history.sort_by_key(|entry| entry.watched_at);
+    Ok(history)
+}
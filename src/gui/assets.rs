@@ -8,6 +8,7 @@ pub mod icons {
 
     pub static CARET_LEFT_FILL: &[u8] = include_bytes!("../../assets/icons/caret-left-fill.svg");
     pub static CLOCK_FILL: &[u8] = include_bytes!("../../assets/icons/clock-fill.svg");
+    pub static CLIPBOARD: &[u8] = include_bytes!("../../assets/icons/clipboard.svg");
     pub static ARROW_REPEAT: &[u8] = include_bytes!("../../assets/icons/arrow-repeat.svg");
     pub static CHEVRON_UP: &[u8] = include_bytes!("../../assets/icons/chevron-up.svg");
     pub static CHEVRON_DOWN: &[u8] = include_bytes!("../../assets/icons/chevron-down.svg");
@@ -19,6 +20,12 @@ pub mod icons {
     pub static CUP_HOT_FILL: &[u8] = include_bytes!("../../assets/icons/cup-hot-fill.svg");
     pub static EYE_SLASH_FILL: &[u8] = include_bytes!("../../assets/icons/eye-slash-fill.svg");
     pub static EYE_FILL: &[u8] = include_bytes!("../../assets/icons/eye-fill.svg");
+    pub static X_LG: &[u8] = include_bytes!("../../assets/icons/x-lg.svg");
+    pub static SKIP_FORWARD_FILL: &[u8] =
+        include_bytes!("../../assets/icons/skip-forward-fill.svg");
+    pub static PENCIL_FILL: &[u8] = include_bytes!("../../assets/icons/pencil-fill.svg");
+    pub static CHECK2_ALL: &[u8] = include_bytes!("../../assets/icons/check2-all.svg");
+    pub static DASH_SQUARE_FILL: &[u8] = include_bytes!("../../assets/icons/dash-square-fill.svg");
     pub static GITHUB_ICON: &[u8] = include_bytes!("../../assets/icons/github.svg");
     pub static TRAKT_ICON_RED: &[u8] = include_bytes!("../../assets/logos/trakt-icon-red.svg");
     pub static SERIES_TROXIDE_ICON: &[u8] = include_bytes!("../../assets/logos/series-troxide.svg");
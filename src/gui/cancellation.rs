@@ -0,0 +1,84 @@
+//! # Cancelling commands
+//!
+//! Commands started by a page keep running after the page has been left, wasting
+//! network and CPU on results no one is going to see. A page owns a [`PageLifetime`]
+//! and wraps it's commands with [`cancellable`] so that whatever is still running
+//! stops as soon as the page is dropped.
+
+use iced::Command;
+use iced_runtime::command::Action;
+use tokio::sync::watch;
+
+/// Lives as long as the page owning it, dropping it cancels the page's commands
+pub struct PageLifetime {
+    sender: watch::Sender<bool>,
+}
+
+impl PageLifetime {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self { sender }
+    }
+
+    pub fn token(&self) -> CancellationToken {
+        CancellationToken {
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+impl Default for PageLifetime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for PageLifetime {
+    fn drop(&mut self) {
+        // Receivers are also notified when the sender is dropped, sending just makes it explicit
+        self.sender.send_replace(true);
+    }
+}
+
+#[derive(Clone)]
+pub struct CancellationToken {
+    receiver: watch::Receiver<bool>,
+}
+
+impl CancellationToken {
+    /// Completes once the page the token belongs to is dropped
+    pub async fn cancelled(&self) {
+        let mut receiver = self.receiver.clone();
+        // An error means the page lifetime is gone which is a cancellation all the same
+        let _ = receiver.wait_for(|is_cancelled| *is_cancelled).await;
+    }
+
+    /// Runs the future to completion unless cancelled first
+    pub async fn run<F: std::future::Future>(&self, future: F) -> Option<F::Output> {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => None,
+            output = future => Some(output),
+        }
+    }
+}
+
+/// Makes the futures of the command stop once the token is cancelled
+///
+/// A cancelled future produces `on_cancel` in place of it's message, the other
+/// actions of the command (scrolling, clipboard etc) are left untouched.
+pub fn cancellable<T>(command: Command<T>, token: &CancellationToken, on_cancel: T) -> Command<T>
+where
+    T: Clone + Send + 'static,
+{
+    Command::batch(command.actions().into_iter().map(|action| match action {
+        Action::Future(future) => {
+            let token = token.clone();
+            let on_cancel = on_cancel.clone();
+            Command::single(Action::Future(Box::pin(async move {
+                token.run(future).await.unwrap_or(on_cancel)
+            })))
+        }
+        action => Command::single(action),
+    }))
+}
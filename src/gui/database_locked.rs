@@ -0,0 +1,60 @@
+use iced::widget::{button, column, container, text};
+use iced::{window, Alignment, Application, Command, Element, Length, Renderer};
+
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    QuitPressed,
+}
+
+/// Shown in place of the app when the database could not be opened
+///
+/// Nothing can be shown without the database, so the app has to be started again once the
+/// cause, most likely a locked keyring, is dealt with.
+pub struct DatabaseLocked {
+    error: String,
+}
+
+impl Application for DatabaseLocked {
+    type Executor = iced::executor::Default;
+    type Message = Message;
+    type Theme = iced::Theme;
+    type Flags = String;
+
+    fn new(error: Self::Flags) -> (Self, Command<Message>) {
+        (Self { error }, Command::none())
+    }
+
+    fn title(&self) -> String {
+        "Series Troxide".to_string()
+    }
+
+    fn theme(&self) -> iced::Theme {
+        super::current_theme()
+    }
+
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::QuitPressed => window::close(),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message, Renderer> {
+        let content = column![
+            text("The database could not be opened").size(21),
+            text(&self.error).style(styles::text_styles::red_text_theme()),
+            text("Unlock the keyring of the system and start Series Troxide again").size(11),
+            button("Quit").on_press(Message::QuitPressed),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
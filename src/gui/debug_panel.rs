@@ -0,0 +1,147 @@
+//! # Debug panel
+//!
+//! A hidden panel toggled with Ctrl+Shift+D showing the api requests in flight, how often
+//! the cache is hit, how deep the loading queues are and the last errors, so that users
+//! can tell what the app is busy with when reporting it being slow.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use iced::widget::{column, container, horizontal_space, row, scrollable, text, Column};
+use iced::{Element, Length, Renderer};
+
+use super::styles;
+use crate::core::background::{self, TaskKind, TaskStats};
+use crate::core::caching::refresh_queue;
+use crate::core::date_formatting;
+use crate::core::metrics::{self, CacheKind, CacheStats};
+
+/// How often the numbers are refreshed while the panel is shown
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+const PANEL_HEIGHT: f32 = 260.0;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+}
+
+pub struct DebugPanel {
+    in_flight_requests: Vec<(String, Duration)>,
+    cache_stats: Vec<(CacheKind, CacheStats)>,
+    /// The running and waiting loads of the refresh queue
+    refresh_queue_depth: (usize, usize),
+    background_stats: Vec<(TaskKind, TaskStats)>,
+    recent_errors: Vec<(DateTime<Local>, String)>,
+}
+
+impl DebugPanel {
+    pub fn new() -> Self {
+        Self {
+            in_flight_requests: metrics::get_in_flight_requests(),
+            cache_stats: metrics::get_cache_stats(),
+            refresh_queue_depth: refresh_queue::get_queue_depth(),
+            background_stats: background::get_stats(),
+            recent_errors: metrics::get_recent_errors(),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Refresh => *self = Self::new(),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let in_flight_requests = section(
+            format!("Requests in flight ({})", self.in_flight_requests.len()),
+            self.in_flight_requests
+                .iter()
+                .map(|(url, elapsed)| format!("{:.1}s  {}", elapsed.as_secs_f32(), url))
+                .collect(),
+        );
+
+        let cache_hit_rates = section(
+            "Cache hit rates".to_owned(),
+            self.cache_stats
+                .iter()
+                .map(|(kind, stats)| match stats.hit_rate() {
+                    Some(hit_rate) => format!(
+                        "{}: {:.0}% of {}",
+                        kind,
+                        hit_rate * 100.0,
+                        stats.hits + stats.misses
+                    ),
+                    None => format!("{}: no lookups", kind),
+                })
+                .collect(),
+        );
+
+        let (running_loads, waiting_loads) = self.refresh_queue_depth;
+        let queue_depths = section(
+            "Queues".to_owned(),
+            std::iter::once(format!(
+                "Loading: {} running, {} waiting",
+                running_loads, waiting_loads
+            ))
+            .chain(self.background_stats.iter().map(|(kind, stats)| {
+                format!(
+                    "{}: {} running, {} waiting",
+                    kind, stats.running, stats.queued
+                )
+            }))
+            .collect(),
+        );
+
+        let recent_errors = section(
+            format!("Last errors ({})", self.recent_errors.len()),
+            self.recent_errors
+                .iter()
+                .map(|(time, error)| format!("{}  {}", date_formatting::time_str(time), error))
+                .collect(),
+        );
+
+        let content = column![
+            row![
+                text("Debug").size(15),
+                horizontal_space(Length::Fill),
+                text("Ctrl+Shift+D to hide").size(11),
+            ],
+            row![
+                in_flight_requests,
+                cache_hit_rates,
+                queue_depths,
+                recent_errors
+            ]
+            .spacing(10),
+        ]
+        .spacing(5)
+        .padding(5);
+
+        container(content)
+            .width(Length::Fill)
+            .height(PANEL_HEIGHT)
+            .style(styles::container_styles::second_class_container_square_theme())
+            .into()
+    }
+}
+
+fn section(title: String, lines: Vec<String>) -> Element<'static, Message, Renderer> {
+    let lines = Column::with_children(
+        lines
+            .into_iter()
+            .map(|line| text(line).size(11).into())
+            .collect(),
+    )
+    .spacing(2);
+
+    column![
+        text(title)
+            .size(13)
+            .style(styles::text_styles::accent_color_theme()),
+        scrollable(lines).direction(styles::scrollable_styles::vertical_direction()),
+    ]
+    .spacing(5)
+    .width(Length::FillPortion(1))
+    .into()
+}
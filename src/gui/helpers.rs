@@ -35,6 +35,119 @@ pub fn genres_with_pipes(genres: &[String]) -> String {
     genres_string
 }
 
+/// Formats the amount of bytes in the largest unit keeping it above one i.e. `1.5 MB`
+pub fn data_size_str(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1000.0;
+    let mut unit = UNITS[0];
+    for next_unit in UNITS.into_iter().skip(1) {
+        if size < 1000.0 {
+            break;
+        }
+        size /= 1000.0;
+        unit = next_unit;
+    }
+    format!("{:.1} {}", size, unit)
+}
+
+pub mod skeleton {
+    //! Placeholders sized like the content being loaded so that the layout does not
+    //! shift once it arrives
+
+    use crate::gui::styles;
+
+    use iced::widget::{column, container, row, Column, Space};
+    use iced::{Element, Length, Renderer};
+    use iced_aw::Wrap;
+
+    /// A plain block standing in for an image or a line of text
+    fn block<'a, Message: 'a>(
+        width: impl Into<Length>,
+        height: impl Into<Length>,
+    ) -> Element<'a, Message, Renderer> {
+        container(Space::new(width, height))
+            .style(styles::container_styles::skeleton_container_theme())
+            .into()
+    }
+
+    /// Stands in for a series poster
+    pub fn series_poster<'a, Message: 'a>() -> Element<'a, Message, Renderer> {
+        column![block(100, 140), block(80, 11)]
+            .padding(2)
+            .spacing(10)
+            .width(100)
+            .align_items(iced::Alignment::Center)
+            .into()
+    }
+
+    /// Stands in for a collection of series posters
+    pub fn series_posters<'a, Message: 'a>(amount: usize) -> Element<'a, Message, Renderer> {
+        Wrap::with_elements((0..amount).map(|_| series_poster()).collect())
+            .spacing(5.0)
+            .line_spacing(5.0)
+            .into()
+    }
+
+    /// Stands in for a titled section of series posters
+    pub fn series_posters_section<'a, Message: 'a>(
+        amount: usize,
+    ) -> Element<'a, Message, Renderer> {
+        column![block(250, 21), series_posters(amount)]
+            .spacing(5)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// Stands in for the cards of the cast
+    pub fn cast_cards<'a, Message: 'a>(amount: usize) -> Element<'a, Message, Renderer> {
+        Wrap::with_elements(
+            (0..amount)
+                .map(|_| {
+                    let info = column![block(120, 15), block(80, 11), block(100, 11)].spacing(5);
+                    container(row![block(100, 140), info].spacing(10).width(260))
+                        .style(styles::container_styles::first_class_container_square_theme())
+                        .padding(7)
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(5.0)
+        .line_spacing(5.0)
+        .into()
+    }
+
+    /// Stands in for rows such as seasons or episodes
+    pub fn rows<'a, Message: 'a>(
+        amount: usize,
+        width: impl Into<Length> + Copy,
+        height: impl Into<Length> + Copy,
+    ) -> Element<'a, Message, Renderer> {
+        Column::with_children((0..amount).map(|_| block(width, height)).collect())
+            .spacing(5)
+            .into()
+    }
+}
+
+pub mod episode_badge {
+    use crate::core::caching::episode_list::EpisodeBadge;
+    use crate::gui::styles;
+
+    use iced::widget::{container, text, Container};
+    use iced::Renderer;
+
+    /// A pill marking an episode as a premiere or finale
+    pub fn episode_badge<'a, Message: 'a>(badge: EpisodeBadge) -> Container<'a, Message, Renderer> {
+        container(text(badge.to_string()).size(11))
+            .padding([2, 8])
+            .style(styles::container_styles::release_time_container_theme())
+    }
+}
+
 pub mod time {
     //! Time related helpers
     use chrono::Duration;
@@ -0,0 +1,166 @@
+//! # Image viewer
+//!
+//! Shows a poster or an episode still over the whole window, zoomed in with the mouse
+//! wheel and panned by dragging, with a way to save the image to disk.
+//!
+//! Like toasts, images can be opened from anywhere in the gui. The application picks up the
+//! opened image after every update and shows the viewer in place of everything else.
+
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use iced::widget::{button, column, container, horizontal_space, image, row, text};
+use iced::{Alignment, Command, Element, Length, Renderer};
+use lazy_static::lazy_static;
+
+use super::{styles, toast};
+
+/// How far the image can be zoomed out and in
+const MIN_SCALE: f32 = 0.25;
+const MAX_SCALE: f32 = 10.0;
+
+lazy_static! {
+    static ref OPENED_IMAGE: Mutex<Option<ViewedImage>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SavePressed,
+    Saved(Result<bool, String>),
+    Closed,
+}
+
+struct ViewedImage {
+    /// What the image is of, used as the title and the default file name
+    name: String,
+    bytes: Bytes,
+    handle: image::Handle,
+}
+
+/// Opens the image in the viewer, `name` being what the image is of
+pub fn open(image_bytes: Bytes, name: impl Into<String>) {
+    *OPENED_IMAGE.lock().unwrap() = Some(ViewedImage {
+        name: name.into(),
+        handle: image::Handle::from_memory(image_bytes.clone()),
+        bytes: image_bytes,
+    });
+}
+
+#[derive(Default)]
+pub struct ImageViewer {
+    image: Option<ViewedImage>,
+}
+
+impl ImageViewer {
+    /// Shows the image opened since the last time, if any
+    pub fn pick_up_opened(&mut self) {
+        if let Some(image) = OPENED_IMAGE.lock().unwrap().take() {
+            self.image = Some(image);
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.image.is_some()
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::SavePressed => {
+                let Some(image) = &self.image else {
+                    return Command::none();
+                };
+                Command::perform(
+                    image_saving::save(image.bytes.clone(), image.name.clone()),
+                    |result| Message::Saved(result.map_err(|err| err.to_string())),
+                )
+            }
+            Message::Saved(result) => {
+                match result {
+                    Ok(true) => toast::push("Image saved"),
+                    Ok(false) => {}
+                    Err(err) => {
+                        tracing::error!("failed to save image: {}", err);
+                        toast::push("Could not save the image");
+                    }
+                }
+                Command::none()
+            }
+            Message::Closed => {
+                self.image = None;
+                Command::none()
+            }
+        }
+    }
+
+    /// The viewer covering the window, `None` when no image is opened
+    pub fn view(&self) -> Option<Element<'_, Message, Renderer>> {
+        let image = self.image.as_ref()?;
+
+        let controls = row![
+            text(&image.name).size(18),
+            horizontal_space(Length::Fill),
+            text("Scroll to zoom, drag to move around").size(11),
+            button(text("Save").size(13))
+                .on_press(Message::SavePressed)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+            button(text("Close").size(13)).on_press(Message::Closed),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let viewer = image::Viewer::new(image.handle.clone())
+            .min_scale(MIN_SCALE)
+            .max_scale(MAX_SCALE)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let content = column![controls, viewer].spacing(10).padding(10);
+
+        Some(
+            container(content)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(styles::container_styles::first_class_container_square_theme())
+                .into(),
+        )
+    }
+}
+
+mod image_saving {
+    use bytes::Bytes;
+    use directories::UserDirs;
+    use rfd::AsyncFileDialog;
+
+    /// Saves the image to a file chosen by the user
+    ///
+    /// Returns false when the user did not choose any file
+    pub async fn save(image_bytes: Bytes, name: String) -> anyhow::Result<bool> {
+        let extension = image::guess_format(&image_bytes)
+            .ok()
+            .and_then(|format| format.extensions_str().first().copied())
+            .unwrap_or("jpg");
+
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(get_pictures_directory()?)
+            .set_file_name(format!("{}.{}", name, extension))
+            .save_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            tokio::fs::write(chosen_path, image_bytes).await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// The pictures directory of the user, their home directory when there is none
+    fn get_pictures_directory() -> anyhow::Result<std::path::PathBuf> {
+        let user_dirs = UserDirs::new().ok_or(anyhow::anyhow!("could not get user directory"))?;
+        Ok(user_dirs
+            .picture_dir()
+            .unwrap_or(user_dirs.home_dir())
+            .to_path_buf())
+    }
+}
@@ -0,0 +1,66 @@
+use iced::widget::{button, column, container, text, text_input, Space};
+use iced::{Alignment, Element, Length, Renderer};
+
+use crate::core::app_lock;
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PasswordChanged(String),
+    UnlockPressed,
+}
+
+/// Covers the whole app until the app lock PIN or password is entered
+#[derive(Default)]
+pub struct LockScreen {
+    password_input: String,
+    wrong_password: bool,
+}
+
+impl LockScreen {
+    /// Returns true when the app has been unlocked
+    pub fn update(&mut self, message: Message) -> bool {
+        match message {
+            Message::PasswordChanged(password) => {
+                self.password_input = password;
+                false
+            }
+            Message::UnlockPressed => {
+                let unlocked = app_lock::unlock(&self.password_input);
+                self.wrong_password = !unlocked;
+                self.password_input.clear();
+                unlocked
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let wrong_password: Element<'_, Message, Renderer> = if self.wrong_password {
+            text("Wrong PIN or password")
+                .style(styles::text_styles::red_text_theme())
+                .into()
+        } else {
+            Space::new(0, 0).into()
+        };
+
+        let content = column![
+            text("Series Troxide is locked").size(21),
+            text_input("PIN or password", &self.password_input)
+                .on_input(Message::PasswordChanged)
+                .on_submit(Message::UnlockPressed)
+                .password()
+                .width(250),
+            button("Unlock").on_press(Message::UnlockPressed),
+            wrong_password,
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
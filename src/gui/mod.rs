@@ -1,18 +1,41 @@
-use crate::core::settings_config::{self, SETTINGS};
-use iced::widget::column;
-use iced::{Application, Command};
+use crate::core::api::tv_maze::data_usage;
+use crate::core::api::tv_maze::diagnostics::SchemaError;
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::app_lock::get_app_lock_settings;
+use crate::core::caching::series_information::get_series_main_info_with_id;
+use crate::core::database::DB;
+use crate::core::deep_link::DeepLink;
+use crate::core::settings_config::{self, Layout, SessionSettings, WindowSettings, SETTINGS};
+use iced::widget::{column, container, row, text, vertical_rule};
+use iced::{keyboard, mouse, window, Application, Command, Event, Length};
+use iced_aw::floating_element;
 use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use debug_panel::{DebugPanel, Message as DebugPanelMessage};
+use image_viewer::{ImageViewer, Message as ImageViewerMessage};
+use lock_screen::{LockScreen, Message as LockScreenMessage};
+use toast::ToastAction;
 
 use series_page::{Message as SeriesPageControllerMessage, SeriesPageController};
 use tabs::{Message as TabsControllerMessage, TabId, TabsController};
+use transition::Transition;
 use troxide_widget::title_bar::{Message as TitleBarMessage, TitleBar};
 
 pub mod assets;
+mod cancellation;
+pub mod database_locked;
+mod debug_panel;
 pub mod helpers;
+mod image_viewer;
+mod lock_screen;
 pub mod message;
+mod rich_text;
 pub mod series_page;
 mod styles;
 mod tabs;
+mod toast;
+mod transition;
 mod troxide_widget;
 
 #[derive(Debug, Clone)]
@@ -21,36 +44,136 @@ pub enum Message {
     SeriesPageController(SeriesPageControllerMessage),
     TabsController(TabsControllerMessage),
     FontLoaded(Result<(), iced::font::Error>),
+    ToastsTick,
+    TransitionTick,
+    WindowResized(u32, u32),
+    WindowMoved(i32, i32),
+    WindowCloseRequested,
+    LockScreen(LockScreenMessage),
+    UserActive,
+    IdleCheck,
+    SchemaError(SchemaError),
+    ToastActionPressed(ToastAction),
+    LastSeriesPageLoaded(Option<Box<SeriesMainInformation>>),
+    ConnectivityChanged(bool),
+    DeepLinkOpened(DeepLink),
+    DeepLinkSeriesLoaded(Result<Box<SeriesMainInformation>, String>),
+    ImageViewer(ImageViewerMessage),
+    DebugPanelToggled,
+    DebugPanel(DebugPanelMessage),
 }
 
+/// The theme picked in the appearance settings
+fn current_theme() -> iced::Theme {
+    let custom_theme = Box::new(
+        match SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .appearance
+            .theme
+        {
+            settings_config::Theme::Light => styles::theme::TroxideTheme::Light,
+            settings_config::Theme::Dark => styles::theme::TroxideTheme::Dark,
+        }
+        .get_custom_theme(),
+    );
+    iced::Theme::Custom(custom_theme)
+}
+
+/// The narrowest window showing series pages next to the tabs with the auto layout
+const SPLIT_PANE_MIN_WIDTH: u32 = 1400;
+
+/// How often the idle time is checked for locking the app
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
 pub struct TroxideGui<'a> {
     active_tab: TabId,
     title_bar: TitleBar,
     tabs_controller: TabsController<'a>,
     series_page_controller: SeriesPageController<'a>,
+    /// The animation of the series page being opened
+    transition: Transition,
+    /// The current window geometry, saved when the window is closed
+    window_settings: WindowSettings,
+    /// Whether the lock screen is shown instead of the app
+    locked: bool,
+    lock_screen: LockScreen,
+    last_activity: Instant,
+    /// Whether the TVmaze api could not be reached the last time it was checked
+    offline: bool,
+    /// Shows the images opened in full screen over everything else
+    image_viewer: ImageViewer,
+    /// The hidden diagnostics shown below everything when toggled
+    debug_panel: Option<DebugPanel>,
 }
 
 impl<'a> Application for TroxideGui<'a> {
     type Executor = iced::executor::Default;
     type Message = Message;
     type Theme = iced::Theme;
-    type Flags = ();
+    type Flags = Option<DeepLink>;
 
-    fn new(_flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+    fn new(deep_link: Self::Flags) -> (Self, iced::Command<Self::Message>) {
         let font_command = iced::font::load(assets::fonts::NOTOSANS_REGULAR_STATIC);
         let (sender, receiver) = mpsc::channel();
-        let (tabs_controller, tabs_controller_command) = TabsController::new(sender.clone());
+        let (mut tabs_controller, tabs_controller_command) = TabsController::new(sender.clone());
+
+        // Returning to where the user was when the app was last closed
+        let session = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .session
+            .clone();
+        let active_tab = if session.tab <= usize::from(TabId::Settings) {
+            TabId::from(session.tab)
+        } else {
+            TabId::Discover
+        };
+        let mut title_bar = TitleBar::new();
+        title_bar.update(TitleBarMessage::TabSelected(active_tab.into()));
+        let switch_tab_command = match active_tab {
+            // Already refreshed by the tabs controller
+            TabId::Discover => Command::none(),
+            tab_id => tabs_controller.switch_to_tab(tab_id),
+        };
+        let series_page_command = match (deep_link, session.series_page_id) {
+            // The link the app was opened with takes the place of the last series page
+            (Some(deep_link), _) => open_deep_link(deep_link),
+            (None, Some(series_id)) => {
+                Command::perform(get_series_main_info_with_id(series_id), |result| {
+                    Message::LastSeriesPageLoaded(result.ok().map(Box::new))
+                })
+            }
+            (None, None) => Command::none(),
+        };
 
         (
             Self {
-                active_tab: TabId::Discover,
-                title_bar: TitleBar::new(),
+                active_tab,
+                title_bar,
                 tabs_controller,
                 series_page_controller: SeriesPageController::new(sender, receiver),
+                transition: Transition::default(),
+                window_settings: SETTINGS
+                    .read()
+                    .unwrap()
+                    .get_current_settings()
+                    .window
+                    .clone(),
+                locked: get_app_lock_settings().is_enabled(),
+                lock_screen: LockScreen::default(),
+                last_activity: Instant::now(),
+                offline: false,
+                image_viewer: ImageViewer::default(),
+                debug_panel: None,
             },
             Command::batch([
                 font_command.map(Message::FontLoaded),
                 tabs_controller_command.map(Message::TabsController),
+                switch_tab_command.map(Message::TabsController),
+                series_page_command,
             ]),
         )
     }
@@ -60,30 +183,107 @@ impl<'a> Application for TroxideGui<'a> {
     }
 
     fn theme(&self) -> iced::Theme {
-        let custom_theme = Box::new(
-            match SETTINGS
-                .read()
-                .unwrap()
-                .get_current_settings()
-                .appearance
-                .theme
-            {
-                settings_config::Theme::Light => styles::theme::TroxideTheme::Light,
-                settings_config::Theme::Dark => styles::theme::TroxideTheme::Dark,
-            }
-            .get_custom_theme(),
-        );
-        iced::Theme::Custom(custom_theme)
+        current_theme()
     }
 
     fn subscription(&self) -> iced::Subscription<Message> {
-        self.tabs_controller
-            .subscription()
-            .map(Message::TabsController)
+        let toasts_subscription = if toast::has_toasts() {
+            iced::time::every(toast::TICK_INTERVAL).map(|_| Message::ToastsTick)
+        } else {
+            iced::Subscription::none()
+        };
+
+        // Keys pressed on the lock screen or the image viewer are not meant for the hidden
+        // series page
+        let series_page_subscription = if self.locked || self.image_viewer.is_open() {
+            iced::Subscription::none()
+        } else {
+            self.series_page_controller
+                .subscription()
+                .map(Message::SeriesPageController)
+        };
+
+        let transition_subscription = if self.transition.is_running() {
+            iced::time::every(transition::TICK_INTERVAL).map(|_| Message::TransitionTick)
+        } else {
+            iced::Subscription::none()
+        };
+
+        let image_viewer_subscription = if self.image_viewer.is_open() {
+            iced::subscription::events_with(|event, _| match event {
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Escape,
+                    ..
+                }) => Some(Message::ImageViewer(ImageViewerMessage::Closed)),
+                _ => None,
+            })
+        } else {
+            iced::Subscription::none()
+        };
+
+        let debug_panel_subscription = if self.debug_panel.is_some() {
+            iced::time::every(debug_panel::REFRESH_INTERVAL)
+                .map(|_| Message::DebugPanel(DebugPanelMessage::Refresh))
+        } else {
+            iced::Subscription::none()
+        };
+
+        let window_subscription = iced::subscription::events_with(|event, _| match event {
+            Event::Window(window::Event::Resized { width, height }) => {
+                Some(Message::WindowResized(width, height))
+            }
+            Event::Window(window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+            Event::Window(window::Event::CloseRequested) => Some(Message::WindowCloseRequested),
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: keyboard::KeyCode::D,
+                modifiers,
+            }) if modifiers.control() && modifiers.shift() => Some(Message::DebugPanelToggled),
+            _ => None,
+        });
+
+        let app_lock_settings = get_app_lock_settings();
+        let idle_subscription = if !self.locked
+            && app_lock_settings.is_enabled()
+            && app_lock_settings.idle_minutes > 0
+        {
+            iced::Subscription::batch([
+                iced::time::every(IDLE_CHECK_INTERVAL).map(|_| Message::IdleCheck),
+                iced::subscription::events_with(|event, _| match event {
+                    Event::Keyboard(keyboard::Event::KeyPressed { .. })
+                    | Event::Mouse(mouse::Event::ButtonPressed(_))
+                    | Event::Mouse(mouse::Event::WheelScrolled { .. }) => Some(Message::UserActive),
+                    _ => None,
+                }),
+            ])
+        } else {
+            iced::Subscription::none()
+        };
+
+        iced::Subscription::batch([
+            self.tabs_controller
+                .subscription()
+                .map(Message::TabsController),
+            series_page_subscription,
+            toasts_subscription,
+            transition_subscription,
+            image_viewer_subscription,
+            debug_panel_subscription,
+            window_subscription,
+            idle_subscription,
+            schema_errors::subscription(),
+            connectivity::subscription(),
+            deep_links::subscription(),
+        ])
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
-        match message {
+        let shown_page_id = self.series_page_controller.shown_page_id();
+        let going_back = matches!(
+            message,
+            Message::TitleBar(TitleBarMessage::BackButtonPressed)
+        );
+
+        let command = match message {
             Message::TabsController(message) => Command::batch([
                 self.tabs_controller
                     .update(message)
@@ -102,6 +302,140 @@ impl<'a> Application for TroxideGui<'a> {
                 }
                 Command::none()
             }
+            Message::ToastsTick => {
+                toast::remove_expired();
+                Command::none()
+            }
+            Message::TransitionTick => {
+                self.transition.tick();
+                Command::none()
+            }
+            Message::WindowResized(width, height) => {
+                // Minimized windows are resized to nothing
+                if width > 0 && height > 0 {
+                    self.window_settings.width = width;
+                    self.window_settings.height = height;
+                }
+                Command::none()
+            }
+            Message::WindowMoved(x, y) => {
+                self.window_settings.position = Some((x, y));
+                Command::none()
+            }
+            Message::WindowCloseRequested => {
+                let mut settings = SETTINGS.write().unwrap();
+                settings.save_window_settings(self.window_settings.clone());
+                settings.save_session_settings(SessionSettings {
+                    tab: self.active_tab.into(),
+                    series_page_id: self.series_page_controller.shown_page_id(),
+                });
+                data_usage::save();
+                window::close()
+            }
+            Message::LockScreen(message) => {
+                if self.lock_screen.update(message) {
+                    self.locked = false;
+                    self.last_activity = Instant::now();
+                }
+                Command::none()
+            }
+            Message::UserActive => {
+                self.last_activity = Instant::now();
+                Command::none()
+            }
+            Message::SchemaError(schema_error) => {
+                tracing::error!(
+                    "tvmaze response from {} could not be decoded: {}",
+                    schema_error.url.as_deref().unwrap_or("cache"),
+                    schema_error.reason
+                );
+                toast::push_with_link(
+                    format!(
+                        "TVmaze sent data that could not be understood ({})",
+                        schema_error.endpoint()
+                    ),
+                    "Report issue",
+                    schema_error.report_url(),
+                );
+                Command::none()
+            }
+            Message::DebugPanelToggled => {
+                self.debug_panel = match self.debug_panel {
+                    Some(_) => None,
+                    None => Some(DebugPanel::new()),
+                };
+                Command::none()
+            }
+            Message::DebugPanel(message) => {
+                if let Some(debug_panel) = self.debug_panel.as_mut() {
+                    debug_panel.update(message);
+                }
+                Command::none()
+            }
+            Message::ImageViewer(message) => {
+                self.image_viewer.update(message).map(Message::ImageViewer)
+            }
+            Message::ConnectivityChanged(is_online) => {
+                self.offline = !is_online;
+                Command::none()
+            }
+            Message::DeepLinkOpened(deep_link) => {
+                Command::batch([window::gain_focus(), open_deep_link(deep_link)])
+            }
+            Message::DeepLinkSeriesLoaded(result) => match result {
+                Ok(series_info) => self
+                    .series_page_controller
+                    .open_series_page(*series_info)
+                    .map(Message::SeriesPageController),
+                Err(err) => {
+                    tracing::error!("failed to open linked series: {}", err);
+                    toast::push("Could not open the linked show");
+                    Command::none()
+                }
+            },
+            Message::LastSeriesPageLoaded(series_info) => match series_info {
+                // Not opening the page if the user already went somewhere else
+                Some(series_info) if !self.series_page_controller.has_a_series_page() => self
+                    .series_page_controller
+                    .open_series_page(*series_info)
+                    .map(Message::SeriesPageController),
+                _ => Command::none(),
+            },
+            Message::ToastActionPressed(action) => {
+                toast::dismiss(&action);
+                match action {
+                    ToastAction::OpenLink(url) => webbrowser::open(&url)
+                        .unwrap_or_else(|err| tracing::error!("failed to open link: {}", err)),
+                    ToastAction::UnwatchEpisodes {
+                        series_id,
+                        season_number,
+                        episodes,
+                    } => {
+                        if let Some(mut series) = DB.get_series(series_id) {
+                            series.remove_episodes(season_number, &episodes);
+                        }
+                        toast::push(format!("{} episodes unwatched again", episodes.len()));
+                    }
+                    ToastAction::RestoreSeason(cleared_season) => {
+                        DB.restore_cleared_season(&cleared_season);
+                        toast::push(format!("Season {} restored", cleared_season.season_number));
+                    }
+                    ToastAction::UndoChecklistImport(applied_checklist) => {
+                        applied_checklist.undo();
+                        toast::push(format!(
+                            "{} episodes changed back",
+                            applied_checklist.get_total_changes()
+                        ));
+                    }
+                }
+                Command::none()
+            }
+            Message::IdleCheck => {
+                if get_app_lock_settings().is_idle_timeout(self.last_activity.elapsed()) {
+                    self.locked = true;
+                }
+                Command::none()
+            }
             Message::TitleBar(message) => {
                 self.title_bar.update(message.clone());
                 match message {
@@ -121,7 +455,7 @@ impl<'a> Application for TroxideGui<'a> {
                         let scrollers_offset_restore_command =
                             if !self.series_page_controller.has_a_series_page() {
                                 self.tabs_controller
-                                    .update_scrollables_offsets()
+                                    .restore_scrollable_offset()
                                     .map(Message::TabsController)
                             } else {
                                 Command::none()
@@ -131,25 +465,217 @@ impl<'a> Application for TroxideGui<'a> {
                     }
                 }
             }
+        };
+
+        // Images are opened from within the tabs and the series pages
+        self.image_viewer.pick_up_opened();
+
+        // Animating the series pages opened from a poster or another page, going back
+        // to a previous page is not animated
+        let opened_page_id = self.series_page_controller.shown_page_id();
+        if !going_back && opened_page_id.is_some() && opened_page_id != shown_page_id {
+            self.transition.start();
         }
+
+        command
     }
 
     fn view(&self) -> iced::Element<'_, Message, iced::Renderer<Self::Theme>> {
+        if self.locked {
+            return self.lock_screen.view().map(Message::LockScreen);
+        }
+
         let view = if let Some(series_page_view) = self.series_page_controller.view() {
-            series_page_view.map(Message::SeriesPageController)
+            let series_page_view = self
+                .transition
+                .view(series_page_view.map(Message::SeriesPageController));
+
+            if self.is_split_pane() {
+                row![
+                    container(self.tabs_controller.view().map(Message::TabsController))
+                        .width(Length::FillPortion(2)),
+                    vertical_rule(1),
+                    container(series_page_view).width(Length::FillPortion(3)),
+                ]
+                .into()
+            } else {
+                series_page_view
+            }
         } else {
             self.tabs_controller.view().map(Message::TabsController)
         };
 
-        column![
-            self.title_bar
-                .view(
-                    &self.tabs_controller.get_labels(),
-                    self.series_page_controller.has_a_series_page()
-                )
-                .map(Message::TitleBar),
-            view
-        ]
-        .into()
+        let mut content = column![self
+            .title_bar
+            .view(
+                &self.tabs_controller.get_labels(),
+                self.series_page_controller.has_a_series_page()
+            )
+            .map(Message::TitleBar)];
+
+        if self.offline {
+            content = content.push(
+                container(text("No connection — showing cached data").size(13))
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding(5)
+                    .style(styles::container_styles::failure_container_theme()),
+            );
+        }
+
+        let content = match self.image_viewer.view() {
+            Some(image_viewer) => column![image_viewer.map(Message::ImageViewer)],
+            None => content.push(view),
+        };
+
+        let content = match &self.debug_panel {
+            Some(debug_panel) => content.push(debug_panel.view().map(Message::DebugPanel)),
+            None => content,
+        };
+
+        floating_element::FloatingElement::new(content, toast::view(Message::ToastActionPressed))
+            .anchor(floating_element::Anchor::South)
+            .hide(!toast::has_toasts())
+            .into()
+    }
+}
+
+impl<'a> TroxideGui<'a> {
+    /// Whether series pages are docked next to the active tab
+    fn is_split_pane(&self) -> bool {
+        if !matches!(self.active_tab, TabId::MyShows | TabId::Discover) {
+            return false;
+        }
+
+        match SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .appearance
+            .layout
+        {
+            Layout::Auto => self.window_settings.width >= SPLIT_PANE_MIN_WIDTH,
+            Layout::SinglePage => false,
+            Layout::SplitPane => true,
+        }
+    }
+}
+
+mod schema_errors {
+    use iced::futures::sink::SinkExt;
+    use iced::subscription::{self, Subscription};
+    use tokio::sync::broadcast::error::RecvError;
+
+    use super::Message;
+    use crate::core::api::tv_maze::diagnostics;
+
+    pub fn subscription() -> Subscription<Message> {
+        subscription::channel("schema-errors", 10, |mut output| async move {
+            let mut receiver = diagnostics::subscribe_schema_errors();
+
+            loop {
+                match receiver.recv().await {
+                    Ok(schema_error) => output
+                        .send(Message::SchemaError(schema_error))
+                        .await
+                        .expect("failed to send schema error"),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("{} schema errors were not shown", skipped)
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+
+            std::future::pending().await
+        })
+    }
+}
+
+mod connectivity {
+    use std::time::Duration;
+
+    use iced::futures::sink::SinkExt;
+    use iced::subscription::{self, Subscription};
+
+    use super::Message;
+    use crate::core::api::tv_maze::connectivity;
+
+    /// How often the api is checked while it's reachable
+    const ONLINE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// How often the api is checked while it's not reachable, so that recovering shows soon
+    const OFFLINE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+    /// Reports whether the api is reachable each time that changes
+    pub fn subscription() -> Subscription<Message> {
+        subscription::channel("connectivity", 10, |mut output| async move {
+            let mut was_online = true;
+
+            loop {
+                let is_online = connectivity::is_api_reachable().await;
+                if is_online != was_online {
+                    output
+                        .send(Message::ConnectivityChanged(is_online))
+                        .await
+                        .expect("failed to send connectivity change");
+                    was_online = is_online;
+                }
+
+                tokio::time::sleep(if is_online {
+                    ONLINE_CHECK_INTERVAL
+                } else {
+                    OFFLINE_CHECK_INTERVAL
+                })
+                .await;
+            }
+        })
+    }
+}
+
+/// Loads whatever the link points to for opening it
+fn open_deep_link(deep_link: DeepLink) -> Command<Message> {
+    match deep_link {
+        DeepLink::Series(series_id) => {
+            Command::perform(get_series_main_info_with_id(series_id), |result| {
+                Message::DeepLinkSeriesLoaded(result.map(Box::new).map_err(|err| err.to_string()))
+            })
+        }
+    }
+}
+
+mod deep_links {
+    use iced::futures::channel::mpsc;
+    use iced::futures::sink::SinkExt;
+    use iced::futures::StreamExt;
+    use iced::subscription::{self, Subscription};
+
+    use super::Message;
+    use crate::core::deep_link;
+
+    /// Receives the links other instances of the app were opened with
+    pub fn subscription() -> Subscription<Message> {
+        subscription::channel("deep-links", 10, |mut output| async move {
+            let (sender, mut receiver) = mpsc::unbounded();
+
+            std::thread::spawn(move || {
+                let result = deep_link::receive_forwarded_links(|deep_link| {
+                    sender
+                        .unbounded_send(deep_link)
+                        .unwrap_or_else(|err| tracing::error!("failed to send link: {}", err))
+                });
+                if let Err(err) = result {
+                    tracing::warn!("links can't be forwarded to this instance: {}", err);
+                }
+            });
+
+            while let Some(deep_link) = receiver.next().await {
+                output
+                    .send(Message::DeepLinkOpened(deep_link))
+                    .await
+                    .expect("failed to send deep link");
+            }
+
+            iced::futures::future::pending().await
+        })
     }
 }
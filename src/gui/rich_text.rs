@@ -0,0 +1,163 @@
+//! # Rich text
+//!
+//! Shows the HTML summaries of TVmaze, made of `<p>`, `<b>`, `<i>` and the like, as styled
+//! text instead of the raw markup.
+//!
+//! The summary is parsed into paragraphs of words, each word made of pieces of text that
+//! are either plain, bold or italic. As iced has no italic fonts, italic text is shown in a
+//! light font weight.
+
+use html2text::render::text_renderer::RichAnnotation;
+use iced::font::Weight;
+use iced::widget::{container, text, Column, Row};
+use iced::{Element, Font, Length, Renderer};
+use iced_aw::Wrap;
+
+/// Width given to html2text so that it never wraps lines by itself
+const UNWRAPPED_WIDTH: usize = 100_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Style {
+    #[default]
+    Plain,
+    Bold,
+    Italic,
+}
+
+impl Style {
+    fn from_annotations(annotations: &[RichAnnotation]) -> Self {
+        if annotations
+            .iter()
+            .any(|annotation| matches!(annotation, RichAnnotation::Strong))
+        {
+            Style::Bold
+        } else if annotations
+            .iter()
+            .any(|annotation| matches!(annotation, RichAnnotation::Emphasis))
+        {
+            Style::Italic
+        } else {
+            Style::Plain
+        }
+    }
+
+    fn font(self) -> Font {
+        let weight = match self {
+            Style::Plain => Weight::Normal,
+            Style::Bold => Weight::Bold,
+            Style::Italic => Weight::Light,
+        };
+
+        Font {
+            weight,
+            ..Default::default()
+        }
+    }
+}
+
+/// Pieces of text written without spaces between them, like `word` in bold followed by `,`
+type Word = Vec<(String, Style)>;
+
+type Paragraph = Vec<Word>;
+
+/// Splits the HTML into paragraphs of styled words
+fn parse(html: &str) -> Vec<Paragraph> {
+    let lines = html2text::from_read_rich(html.as_bytes(), UNWRAPPED_WIDTH);
+
+    let mut paragraphs = vec![];
+    let mut paragraph: Paragraph = vec![];
+
+    for line in lines {
+        // A blank line ends the paragraph
+        let mut is_blank = true;
+        // The end of a line separates words like a space does
+        let mut is_word_started = false;
+
+        for tagged_string in line.tagged_strings() {
+            let style = Style::from_annotations(&tagged_string.tag);
+
+            let mut pieces = tagged_string.s.split(char::is_whitespace).peekable();
+            while let Some(piece) = pieces.next() {
+                if !piece.is_empty() {
+                    is_blank = false;
+                    match paragraph.last_mut() {
+                        Some(word) if is_word_started => word.push((piece.to_owned(), style)),
+                        _ => paragraph.push(vec![(piece.to_owned(), style)]),
+                    }
+                    is_word_started = true;
+                }
+
+                // Whitespace comes between the pieces split
+                if pieces.peek().is_some() {
+                    is_word_started = false;
+                }
+            }
+        }
+
+        if is_blank && !paragraph.is_empty() {
+            paragraphs.push(std::mem::take(&mut paragraph));
+        }
+    }
+
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+
+    paragraphs
+}
+
+/// Shows the HTML summary as styled paragraphs, wrapping within the given width
+pub fn summary<'a, Message: 'a>(
+    html: &str,
+    size: u16,
+    width: impl Into<Length>,
+) -> Element<'a, Message, Renderer> {
+    let paragraphs = parse(html).into_iter().map(|paragraph| {
+        let is_plain = paragraph
+            .iter()
+            .flatten()
+            .all(|(_, style)| *style == Style::Plain);
+
+        // A single text wraps better and is lighter when there is nothing to style
+        if is_plain {
+            return text(paragraph_text(paragraph)).size(size).into();
+        }
+
+        Wrap::with_elements(
+            paragraph
+                .into_iter()
+                .map(|word| {
+                    Row::with_children(
+                        word.into_iter()
+                            .map(|(piece, style)| text(piece).size(size).font(style.font()).into())
+                            .collect(),
+                    )
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(size as f32 / 3.0)
+        .into()
+    });
+
+    container(Column::with_children(paragraphs.collect()).spacing(size / 2))
+        .width(width)
+        .into()
+}
+
+/// The HTML summary as plain paragraphs, for copying it
+pub fn plain(html: &str) -> String {
+    parse(html)
+        .into_iter()
+        .map(paragraph_text)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn paragraph_text(paragraph: Paragraph) -> String {
+    paragraph
+        .into_iter()
+        .map(|word| word.into_iter().map(|(piece, _)| piece).collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
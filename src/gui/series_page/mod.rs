@@ -1,12 +1,14 @@
 use std::sync::mpsc;
 
-use iced::{Command, Element, Renderer};
+use iced::keyboard::{self, KeyCode};
+use iced::{event, Command, Element, Event, Renderer, Subscription};
 use indexmap::IndexMap;
 
 use series::{Message as SeriesMessage, Series};
 
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 
+use super::cancellation;
 use super::troxide_widget::series_poster::IndexedMessage;
 
 mod series;
@@ -15,6 +17,9 @@ mod series;
 pub enum Message {
     Series(IndexedMessage<u32, SeriesMessage>),
     SeriesCacheFileWritten,
+    /// A command of a series page that was left before it completed
+    LoadCancelled,
+    KeyPressed(KeyCode),
 }
 
 pub struct SeriesPageController<'a> {
@@ -45,25 +50,38 @@ impl<'a> SeriesPageController<'a> {
         !self.series_pages.is_empty()
     }
 
+    /// The id of the series whose page is shown, if any
+    pub fn shown_page_id(&self) -> Option<u32> {
+        self.series_pages.last().map(|(id, _)| *id)
+    }
+
     /// Goes to the previous opened series page discarding the current one
     pub fn go_previous(&mut self) -> Command<Message> {
         self.series_pages.pop();
         self.series_pages
             .last()
             .map(|(id, series_page)| {
-                let id = *id;
-                series_page
-                    .restore_scroller_relative_offset()
-                    .map(move |message| Message::Series(IndexedMessage::new(id, message)))
+                page_command(
+                    *id,
+                    series_page,
+                    series_page.restore_scroller_relative_offset(),
+                )
             })
             .unwrap_or(Command::none())
     }
 
+    /// Switches to the page of the given series
+    pub fn open_series_page(&mut self, series_info: SeriesMainInformation) -> Command<Message> {
+        self.series_page_sender
+            .send(series_info)
+            .expect("failed to send series page info");
+        self.try_series_page_switch()
+    }
+
     /// Tries to switch to series page if any has been received
     pub fn try_series_page_switch(&mut self) -> Command<Message> {
-        use crate::core::caching::{CacheFilePath, CACHER};
+        use crate::core::caching::{write_cache, CacheFilePath, CACHER};
         use tokio::fs;
-        use tracing::error;
 
         match self.series_page_receiver.try_recv() {
             Ok(series_info) => {
@@ -72,22 +90,24 @@ impl<'a> SeriesPageController<'a> {
                 let series_page_command = if let Some((series_page_id, series_page)) =
                     self.series_pages.shift_remove_entry(&series_page_id)
                 {
-                    let restore_scroller_command = series_page.set_relative_offset_to_start();
+                    let restore_scroller_command = page_command(
+                        series_page_id,
+                        &series_page,
+                        series_page.set_relative_offset_to_start(),
+                    );
 
                     // Shifting the series page to the front if it already exists in the map
                     self.series_pages.insert(series_page_id, series_page);
 
-                    restore_scroller_command.map(move |message| {
-                        Message::Series(IndexedMessage::new(series_page_id, message))
-                    })
+                    restore_scroller_command
                 } else {
                     let (series_page, series_page_command) =
                         Series::new(series_info.clone(), self.series_page_sender.clone());
+                    let series_page_command =
+                        page_command(series_page_id, &series_page, series_page_command);
                     self.series_pages.insert(series_page_id, series_page);
 
-                    series_page_command.map(move |message| {
-                        Message::Series(IndexedMessage::new(series_page_id, message))
-                    })
+                    series_page_command
                 };
 
                 // Caching SeriesMainInformation if it is not cached already
@@ -105,28 +125,12 @@ impl<'a> SeriesPageController<'a> {
                         .await
                         .unwrap_or(false)
                     {
-                        let mut folder_path = series_main_info_cache_path.to_owned();
-                        folder_path.pop();
-
-                        fs::create_dir_all(folder_path).await.unwrap_or_else(|err| {
-                            error!(
-                                "failed to create series cache folder for series id {}: {}",
-                                series_page_id, err
-                            )
-                        });
-
-                        fs::write(
-                            series_main_info_cache_path,
+                        write_cache(
                             serde_json::to_string_pretty(&series_info)
                                 .expect("fail to serialize series info to json"),
+                            &series_main_info_cache_path,
                         )
-                        .await
-                        .unwrap_or_else(|err| {
-                            error!(
-                                "failed to save series main information cache for series id {}: {}",
-                                series_page_id, err
-                            )
-                        });
+                        .await;
                     }
                 };
 
@@ -151,19 +155,44 @@ impl<'a> SeriesPageController<'a> {
 
                 let command = if let Some(series_page) = self.series_pages.get_mut(&series_page_id)
                 {
-                    series_page
-                        .update(identifiable_message.message())
-                        .map(move |message| {
-                            Message::Series(IndexedMessage::new(series_page_id, message))
-                        })
+                    let command = series_page.update(identifiable_message.message());
+                    page_command(series_page_id, series_page, command)
                 } else {
+                    // The page was left while the message was on it's way
                     Command::none()
                 };
 
                 Command::batch([command, self.try_series_page_switch()])
             }
-            Message::SeriesCacheFileWritten => Command::none(),
+            Message::KeyPressed(key_code) => self
+                .series_pages
+                .last_mut()
+                .map(|(id, series_page)| {
+                    let command = series_page.update(SeriesMessage::KeyPressed(key_code));
+                    page_command(*id, series_page, command)
+                })
+                .unwrap_or(Command::none()),
+            Message::SeriesCacheFileWritten | Message::LoadCancelled => Command::none(),
+        }
+    }
+
+    /// Listens to the keys moving through the episodes of the shown series page, leaving
+    /// the keys typed in text inputs alone
+    pub fn subscription(&self) -> Subscription<Message> {
+        if !self.has_a_series_page() {
+            return Subscription::none();
         }
+
+        iced::subscription::events_with(|event, status| match (event, status) {
+            (
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code,
+                    modifiers,
+                }),
+                event::Status::Ignored,
+            ) if modifiers.is_empty() => Some(Message::KeyPressed(key_code)),
+            _ => None,
+        })
     }
 
     pub fn view(&self) -> Option<Element<'_, Message, Renderer>> {
@@ -174,3 +203,16 @@ impl<'a> SeriesPageController<'a> {
         })
     }
 }
+
+/// Maps the command of the series page, it stops running once the page is dropped
+fn page_command(
+    series_page_id: u32,
+    series_page: &Series,
+    command: Command<SeriesMessage>,
+) -> Command<Message> {
+    cancellation::cancellable(
+        command.map(move |message| Message::Series(IndexedMessage::new(series_page_id, message))),
+        &series_page.cancellation_token(),
+        Message::LoadCancelled,
+    )
+}
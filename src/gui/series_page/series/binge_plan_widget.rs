@@ -0,0 +1,248 @@
+use chrono::{Duration, Local, NaiveDate};
+use iced::widget::{button, column, row, text, Column};
+use iced::{Alignment, Command, Element, Renderer};
+use iced_aw::date_picker::{self, DatePicker};
+
+use crate::core::binge_plan;
+use crate::core::database::{BingePlan, DB};
+use crate::core::date_formatting;
+use crate::gui::{styles, toast};
+
+/// The default length in days of a new binge plan
+const DEFAULT_PLAN_DAYS: i64 = 7;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PickFirstDayPressed,
+    PickLastDayPressed,
+    PickDayCanceled,
+    FirstDayPicked(date_picker::Date),
+    LastDayPicked(date_picker::Date),
+    GeneratePressed,
+    PlanGenerated(Result<BingePlan, String>),
+    RemovePressed,
+    ExportPressed,
+    ExportComplete(Result<bool, String>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PickedDay {
+    First,
+    Last,
+}
+
+/// Spreads the unwatched episodes of the series over days chosen by the user
+pub struct BingePlanWidget {
+    series_id: u32,
+    series_name: String,
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+    picking_day: Option<PickedDay>,
+    generating: bool,
+    binge_plan: Option<BingePlan>,
+}
+
+impl BingePlanWidget {
+    pub fn new(series_id: u32, series_name: String) -> Self {
+        let binge_plan = DB.get_binge_plan(series_id);
+
+        let today = Local::now().date_naive();
+        let (first_day, last_day) = binge_plan
+            .as_ref()
+            .and_then(|binge_plan| {
+                Some((binge_plan.days.first()?.date, binge_plan.days.last()?.date))
+            })
+            .unwrap_or((today, today + Duration::days(DEFAULT_PLAN_DAYS - 1)));
+
+        Self {
+            series_id,
+            series_name,
+            first_day,
+            last_day,
+            picking_day: None,
+            generating: false,
+            binge_plan,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::PickFirstDayPressed => self.picking_day = Some(PickedDay::First),
+            Message::PickLastDayPressed => self.picking_day = Some(PickedDay::Last),
+            Message::PickDayCanceled => self.picking_day = None,
+            Message::FirstDayPicked(date) => {
+                self.picking_day = None;
+                self.first_day = date.into();
+                self.last_day = self.last_day.max(self.first_day);
+            }
+            Message::LastDayPicked(date) => {
+                self.picking_day = None;
+                self.last_day = date.into();
+                self.first_day = self.first_day.min(self.last_day);
+            }
+            Message::GeneratePressed => {
+                self.generating = true;
+                return Command::perform(
+                    binge_plan::generate(
+                        self.series_id,
+                        self.series_name.clone(),
+                        self.first_day,
+                        self.last_day,
+                    ),
+                    |result| Message::PlanGenerated(result.map_err(|err| err.to_string())),
+                );
+            }
+            Message::PlanGenerated(result) => {
+                self.generating = false;
+                match result {
+                    Ok(binge_plan) => {
+                        DB.set_binge_plan(&binge_plan);
+                        self.binge_plan = Some(binge_plan);
+                        toast::push("Binge plan added to the schedule");
+                    }
+                    Err(err) => {
+                        tracing::error!("failed to generate binge plan: {}", err);
+                        toast::push(format!("Could not plan the episodes, {}", err));
+                    }
+                }
+            }
+            Message::RemovePressed => {
+                DB.remove_binge_plan(self.series_id);
+                self.binge_plan = None;
+            }
+            Message::ExportPressed => {
+                if let Some(binge_plan) = self.binge_plan.clone() {
+                    return Command::perform(ical_transfer::export(binge_plan), |result| {
+                        Message::ExportComplete(result.map_err(|err| err.to_string()))
+                    });
+                }
+            }
+            Message::ExportComplete(result) => match result {
+                Ok(true) => toast::push("Binge plan exported"),
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::error!("failed to export binge plan: {}", err);
+                    toast::push("Could not export the binge plan");
+                }
+            },
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let day_button = |date: NaiveDate, message: Message| {
+            button(text(date_formatting::long_date_str(date)).size(13))
+                .on_press(message)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+        };
+
+        let first_day_picker = DatePicker::new(
+            self.picking_day == Some(PickedDay::First),
+            self.first_day,
+            day_button(self.first_day, Message::PickFirstDayPressed),
+            Message::PickDayCanceled,
+            Message::FirstDayPicked,
+        );
+        let last_day_picker = DatePicker::new(
+            self.picking_day == Some(PickedDay::Last),
+            self.last_day,
+            day_button(self.last_day, Message::PickLastDayPressed),
+            Message::PickDayCanceled,
+            Message::LastDayPicked,
+        );
+
+        let generate_button = if self.generating {
+            button(text("Planning...").size(13))
+        } else {
+            button(text("Plan").size(13)).on_press(Message::GeneratePressed)
+        };
+
+        let mut content = column![
+            text("Binge Plan").size(21),
+            text("Spread the unwatched episodes over the chosen days, the viewing days are suggested in the schedule").size(11),
+            row![
+                text("From").size(13),
+                first_day_picker,
+                text("to").size(13),
+                last_day_picker,
+                generate_button,
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center),
+        ]
+        .spacing(5)
+        .padding(5);
+
+        if let Some(binge_plan) = &self.binge_plan {
+            content = content.push(plan_view(binge_plan));
+        }
+
+        content.into()
+    }
+}
+
+fn plan_view(binge_plan: &BingePlan) -> Element<'_, Message, Renderer> {
+    let days = Column::with_children(
+        binge_plan
+            .days
+            .iter()
+            .map(|day| {
+                row![
+                    text(date_formatting::short_date_str(day.date))
+                        .size(13)
+                        .width(120),
+                    text(day.episodes_range()).size(13),
+                ]
+                .into()
+            })
+            .collect(),
+    )
+    .spacing(2);
+
+    let action_button = |label: &'static str, message: Message| {
+        button(text(label).size(11))
+            .on_press(message)
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+    };
+
+    column![
+        days,
+        row![
+            action_button("Export iCal", Message::ExportPressed),
+            action_button("Remove plan", Message::RemovePressed),
+        ]
+        .spacing(5),
+    ]
+    .spacing(5)
+    .into()
+}
+
+mod ical_transfer {
+    use directories::UserDirs;
+    use rfd::AsyncFileDialog;
+
+    use crate::core::database::BingePlan;
+    use crate::core::export::ical;
+
+    /// Saves the binge plan to an iCalendar file chosen by the user
+    ///
+    /// Returns false when the user did not choose any file
+    pub async fn export(binge_plan: BingePlan) -> anyhow::Result<bool> {
+        let user_dirs = UserDirs::new().ok_or(anyhow::anyhow!("could not get user directory"))?;
+
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(user_dirs.home_dir())
+            .set_file_name(format!("{} binge plan.ics", binge_plan.series_name))
+            .add_filter("iCalendar", &["ics"])
+            .save_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            ical::write_binge_plan(&binge_plan, chosen_path).await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+}
@@ -1,20 +1,31 @@
 use cast_poster::{CastPoster, IndexedMessage, Message as CastMessage};
 use iced::widget::{button, column, container, horizontal_space, row, svg, text, Space};
 use iced::{Command, Element, Length, Renderer};
-use iced_aw::{Spinner, Wrap};
+use iced_aw::Wrap;
 
 use crate::core::{api::tv_maze::show_cast::Cast, caching};
 use crate::gui::assets::icons::{CHEVRON_DOWN, CHEVRON_UP};
-use crate::gui::styles;
+use crate::gui::{helpers, styles};
 
-const INITIAL_CAST_NUMBER: usize = 20;
+/// How many more cast members are shown each time more cast is requested
+const CAST_PAGE_SIZE: usize = 20;
+const SKELETON_CAST_AMOUNT: usize = 6;
 
 #[derive(Clone, Debug)]
 pub enum Message {
     CastReceived(Vec<Cast>),
     Cast(IndexedMessage<usize, CastMessage>),
-    Expand,
-    Shrink,
+    ShowMore,
+    ShowLess,
+    OrderToggled,
+}
+
+/// The order the cast members are shown in
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CastOrder {
+    /// The order TVmaze lists them in, main cast first
+    Billing,
+    Alphabetical,
 }
 
 enum LoadState {
@@ -24,8 +35,13 @@ enum LoadState {
 
 pub struct CastWidget {
     load_state: LoadState,
+    /// The cast posters in billing order
     casts: Vec<CastPoster>,
-    is_expanded: bool,
+    order: CastOrder,
+    /// Indices of the cast posters in the current order
+    ordered_casts: Vec<usize>,
+    /// How many cast members are shown, only their images are loaded
+    shown_cast: usize,
 }
 
 impl CastWidget {
@@ -33,7 +49,9 @@ impl CastWidget {
         let cast_widget = Self {
             load_state: LoadState::Loading,
             casts: vec![],
-            is_expanded: false,
+            order: CastOrder::Billing,
+            ordered_casts: vec![],
+            shown_cast: CAST_PAGE_SIZE,
         };
 
         let cast_command = Command::perform(caching::show_cast::get_show_cast(series_id), |cast| {
@@ -47,54 +65,85 @@ impl CastWidget {
         match message {
             Message::CastReceived(cast) => {
                 self.load_state = LoadState::Loaded;
-                let mut cast_posters = Vec::with_capacity(cast.len());
-                let mut posters_commands = Vec::with_capacity(cast.len());
-                for (index, person) in cast.into_iter().enumerate() {
-                    let (cast_poster, poster_command) = CastPoster::new(index, person);
-                    cast_posters.push(cast_poster);
-                    posters_commands.push(poster_command);
-                }
-                self.casts = cast_posters;
-                Command::batch(posters_commands).map(Message::Cast)
+                self.casts = cast
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, person)| CastPoster::new(index, person))
+                    .collect();
+                self.order_casts();
+                self.load_shown_images()
             }
             Message::Cast(message) => self.casts[message.index()]
                 .update(message)
                 .map(Message::Cast),
-            Message::Expand => {
-                self.is_expanded = true;
-                Command::none()
+            Message::ShowMore => {
+                self.shown_cast += CAST_PAGE_SIZE;
+                self.load_shown_images()
             }
-            Message::Shrink => {
-                self.is_expanded = false;
+            Message::ShowLess => {
+                self.shown_cast = CAST_PAGE_SIZE;
                 Command::none()
             }
+            Message::OrderToggled => {
+                self.order = match self.order {
+                    CastOrder::Billing => CastOrder::Alphabetical,
+                    CastOrder::Alphabetical => CastOrder::Billing,
+                };
+                self.order_casts();
+                self.load_shown_images()
+            }
         }
     }
 
+    fn order_casts(&mut self) {
+        self.ordered_casts = (0..self.casts.len()).collect();
+        if let CastOrder::Alphabetical = self.order {
+            self.ordered_casts
+                .sort_by_key(|index| self.casts[*index].get_person_name().to_lowercase());
+        }
+    }
+
+    fn shown_casts(&self) -> impl Iterator<Item = usize> + '_ {
+        self.ordered_casts.iter().copied().take(self.shown_cast)
+    }
+
+    /// Loads the images of the shown cast members that have not been loaded yet
+    fn load_shown_images(&mut self) -> Command<Message> {
+        let shown_casts: Vec<usize> = self.shown_casts().collect();
+        Command::batch(
+            shown_casts
+                .into_iter()
+                .map(|index| self.casts[index].load_person_image()),
+        )
+        .map(Message::Cast)
+    }
+
     pub fn view(&self) -> Element<'_, Message, Renderer> {
         match self.load_state {
-            LoadState::Loading => {
-                return container(Spinner::new())
-                    .center_x()
-                    .center_y()
-                    .height(100)
-                    .width(Length::Fill)
-                    .into()
-            }
+            LoadState::Loading => helpers::skeleton::cast_cards(SKELETON_CAST_AMOUNT),
             LoadState::Loaded => {
                 if self.casts.is_empty() {
                     Space::new(0, 0).into()
                 } else {
                     let cast_posters: Vec<_> = self
-                        .casts
-                        .iter()
-                        .enumerate()
-                        .take_while(|(index, _)| self.is_expanded || *index < INITIAL_CAST_NUMBER)
-                        .map(|(_, poster)| poster.view().map(Message::Cast))
+                        .shown_casts()
+                        .map(|index| self.casts[index].view().map(Message::Cast))
                         .collect();
 
+                    let order_button = button(
+                        text(match self.order {
+                            CastOrder::Billing => "Billing order",
+                            CastOrder::Alphabetical => "Alphabetical",
+                        })
+                        .size(11),
+                    )
+                    .on_press(Message::OrderToggled)
+                    .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+
                     column![
-                        text("Cast").size(21),
+                        row![text("Cast").size(21), order_button]
+                            .spacing(10)
+                            .align_items(iced::Alignment::Center),
                         Wrap::with_elements(cast_posters)
                             .padding(5.0)
                             .line_spacing(10.0)
@@ -109,48 +158,55 @@ impl CastWidget {
     }
 
     fn expansion_widget(&self) -> Element<'_, Message, Renderer> {
-        if self.casts.len() > INITIAL_CAST_NUMBER {
-            let (info, expansion_icon, message) = if self.is_expanded {
-                let svg_handle = svg::Handle::from_memory(CHEVRON_UP);
-                let up_icon = svg(svg_handle)
-                    .width(Length::Shrink)
-                    .style(styles::svg_styles::colored_svg_theme());
-                (text("show less"), up_icon, Message::Shrink)
-            } else {
-                let svg_handle = svg::Handle::from_memory(CHEVRON_DOWN);
-                let down_icon = svg(svg_handle)
-                    .width(Length::Shrink)
-                    .style(styles::svg_styles::colored_svg_theme());
-                (text("show more"), down_icon, Message::Expand)
-            };
+        let mut buttons = row![].spacing(10);
+
+        if self.shown_cast < self.casts.len() {
+            buttons = buttons.push(expansion_button(
+                "show more",
+                CHEVRON_DOWN,
+                Message::ShowMore,
+            ));
+        }
 
-            let content = row![
-                horizontal_space(5),
-                info,
-                expansion_icon,
-                horizontal_space(5),
-            ]
-            .spacing(10)
-            .align_items(iced::Alignment::Center);
-
-            let content = button(content)
-                .on_press(message)
-                .style(styles::button_styles::transparent_button_theme());
-
-            container(
-                container(content)
-                    .style(styles::container_styles::first_class_container_square_theme()),
-            )
+        if self.shown_cast > CAST_PAGE_SIZE {
+            buttons = buttons.push(expansion_button("show less", CHEVRON_UP, Message::ShowLess));
+        }
+
+        container(buttons)
             .center_x()
             .width(Length::Fill)
             .padding(20)
             .into()
-        } else {
-            Space::new(0, 0).into()
-        }
     }
 }
 
+fn expansion_button(
+    info: &str,
+    icon: &'static [u8],
+    message: Message,
+) -> Element<'static, Message, Renderer> {
+    let expansion_icon = svg(svg::Handle::from_memory(icon))
+        .width(Length::Shrink)
+        .style(styles::svg_styles::colored_svg_theme());
+
+    let content = row![
+        horizontal_space(5),
+        text(info),
+        expansion_icon,
+        horizontal_space(5),
+    ]
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+
+    let content = button(content)
+        .on_press(message)
+        .style(styles::button_styles::transparent_button_theme());
+
+    container(content)
+        .style(styles::container_styles::first_class_container_square_theme())
+        .into()
+}
+
 mod cast_poster {
     use bytes::Bytes;
     use iced::{
@@ -165,9 +221,11 @@ mod cast_poster {
     use crate::{
         core::{
             api::tv_maze::{show_cast::Cast, Image},
-            caching,
+            caching::{self, episode_guests::GuestAppearance},
+            database::DB,
+            date_formatting, followed_people,
         },
-        gui::{assets::icons::ARROW_REPEAT, helpers, styles},
+        gui::{assets::icons::ARROW_REPEAT, helpers, styles, toast},
     };
 
     #[derive(Debug, Clone)]
@@ -175,6 +233,18 @@ mod cast_poster {
         PersonImageLoaded(Option<Bytes>),
         CharacterImageLoaded(Option<Bytes>),
         SwitchDisplayImage,
+        GuestAppearancesPressed,
+        GuestAppearancesLoaded(Option<Vec<GuestAppearance>>),
+        FollowPressed,
+        /// Whether following the person succeeded
+        Followed(bool),
+    }
+
+    enum GuestAppearances {
+        Hidden,
+        Loading,
+        Loaded(Vec<GuestAppearance>),
+        Failed,
     }
 
     enum DisplayImage {
@@ -187,26 +257,47 @@ mod cast_poster {
         cast: Cast,
         person_image: Option<Bytes>,
         character_image: Option<Bytes>,
+        /// Whether loading the person image has been started
+        person_image_requested: bool,
         character_image_loading: bool,
         current_display_image: DisplayImage,
+        guest_appearances: GuestAppearances,
+        is_following: bool,
+        /// Whether the credits of the person are being fetched to follow them
+        follow_pending: bool,
     }
 
     impl CastPoster {
-        pub fn new(id: usize, cast: Cast) -> (Self, Command<IndexedMessage<usize, Message>>) {
-            let image = cast.person.image.clone();
-            let poster = Self {
+        /// Creates the poster without loading it's images, see `load_person_image`
+        pub fn new(id: usize, cast: Cast) -> Self {
+            Self {
                 index: id,
-                cast,
                 person_image: None,
+                person_image_requested: false,
                 character_image: None,
                 character_image_loading: false,
                 current_display_image: DisplayImage::Person,
-            };
-            let poster_command = Self::load_person_image(image);
-            (
-                poster,
-                poster_command.map(move |message| IndexedMessage::new(id, message)),
-            )
+                guest_appearances: GuestAppearances::Hidden,
+                is_following: DB.is_following_person(cast.person.id),
+                follow_pending: false,
+                cast,
+            }
+        }
+
+        pub fn get_person_name(&self) -> &str {
+            &self.cast.person.name
+        }
+
+        /// Loads the person image through the image cache, only the first call loads it
+        pub fn load_person_image(&mut self) -> Command<IndexedMessage<usize, Message>> {
+            if self.person_image_requested {
+                return Command::none();
+            }
+            self.person_image_requested = true;
+
+            let index = self.index;
+            Self::person_image_command(self.cast.person.image.clone())
+                .map(move |message| IndexedMessage::new(index, message))
         }
 
         pub fn update(
@@ -239,6 +330,78 @@ mod cast_poster {
                         Command::none()
                     }
                 },
+                Message::GuestAppearancesPressed => match self.guest_appearances {
+                    GuestAppearances::Hidden | GuestAppearances::Failed => {
+                        self.guest_appearances = GuestAppearances::Loading;
+                        Command::perform(
+                            caching::episode_guests::get_guest_appearances(self.cast.person.id),
+                            |guest_appearances| {
+                                Message::GuestAppearancesLoaded(
+                                    guest_appearances
+                                        .map_err(|err| {
+                                            tracing::error!(
+                                                "failed to load guest appearances: {}",
+                                                err
+                                            )
+                                        })
+                                        .ok(),
+                                )
+                            },
+                        )
+                    }
+                    GuestAppearances::Loading => Command::none(),
+                    GuestAppearances::Loaded(_) => {
+                        self.guest_appearances = GuestAppearances::Hidden;
+                        Command::none()
+                    }
+                },
+                Message::GuestAppearancesLoaded(guest_appearances) => {
+                    self.guest_appearances = match guest_appearances {
+                        Some(guest_appearances) => GuestAppearances::Loaded(guest_appearances),
+                        None => GuestAppearances::Failed,
+                    };
+                    Command::none()
+                }
+                Message::FollowPressed => {
+                    if self.is_following {
+                        DB.unfollow_person(self.cast.person.id);
+                        self.is_following = false;
+                        toast::push(format!("Unfollowed {}", self.cast.person.name));
+                        Command::none()
+                    } else if self.follow_pending {
+                        Command::none()
+                    } else {
+                        self.follow_pending = true;
+                        Command::perform(
+                            followed_people::follow(
+                                self.cast.person.id,
+                                self.cast.person.name.clone(),
+                            ),
+                            |result| {
+                                Message::Followed(
+                                    result
+                                        .map_err(|err| {
+                                            tracing::error!("failed to follow person: {}", err)
+                                        })
+                                        .is_ok(),
+                                )
+                            },
+                        )
+                    }
+                }
+                Message::Followed(followed) => {
+                    self.follow_pending = false;
+                    self.is_following = followed;
+                    if followed {
+                        toast::push(format!(
+                            "Following {}, their new shows will appear in Discover",
+                            self.cast.person.name
+                        ));
+                    } else {
+                        toast::push(format!("Failed to follow {}", self.cast.person.name));
+                    }
+                    Command::none()
+                }
             };
             let index = self.index;
             command.map(move |message| IndexedMessage::new(index, message))
@@ -278,7 +441,7 @@ mod cast_poster {
                 text(&self.cast.person.name)
                     .style(styles::text_styles::accent_color_theme())
                     .size(15),
-                text(format!("as {}", &self.cast.character.name)).size(11)
+                text(self.cast.role()).size(11)
             ]);
 
             // A little bit of space between cast name and other information
@@ -304,11 +467,17 @@ mod cast_poster {
             }
 
             if let Some(birthday) = self.cast.person.birthday.as_ref() {
-                cast_info = cast_info.push(cast_info_field("Birthday: ", birthday));
+                cast_info = cast_info.push(cast_info_field(
+                    "Birthday: ",
+                    date_formatting::api_date_str(birthday),
+                ));
             }
 
             if let Some(deathday) = self.cast.person.deathday.as_ref() {
-                cast_info = cast_info.push(cast_info_field("Deathday: ", deathday));
+                cast_info = cast_info.push(cast_info_field(
+                    "Deathday: ",
+                    date_formatting::api_date_str(deathday),
+                ));
             }
 
             if let Some(country) = self.cast.person.country.as_ref() {
@@ -317,7 +486,35 @@ mod cast_poster {
 
             cast_info = cast_info.push(self.image_switch_button());
 
-            let content = content.push(cast_info);
+            cast_info = cast_info.push(
+                button(
+                    text(match self.guest_appearances {
+                        GuestAppearances::Loaded(_) => "Hide guest appearances",
+                        _ => "Guest appearances",
+                    })
+                    .size(11),
+                )
+                .on_press(Message::GuestAppearancesPressed)
+                .style(styles::button_styles::transparent_button_theme()),
+            );
+
+            cast_info = cast_info.push(
+                button(
+                    text(if self.is_following {
+                        "Unfollow"
+                    } else if self.follow_pending {
+                        "Following..."
+                    } else {
+                        "Follow"
+                    })
+                    .size(11),
+                )
+                .on_press(Message::FollowPressed)
+                .style(styles::button_styles::transparent_button_theme()),
+            );
+
+            let content =
+                column![content.push(cast_info), self.guest_appearances_widget()].spacing(5);
 
             let element: Element<'_, Message, Renderer> = container(content)
                 .style(styles::container_styles::first_class_container_square_theme())
@@ -326,6 +523,50 @@ mod cast_poster {
             element.map(|message| IndexedMessage::new(self.index, message))
         }
 
+        fn guest_appearances_widget(&self) -> Element<'_, Message, Renderer> {
+            let guest_appearances = match &self.guest_appearances {
+                GuestAppearances::Hidden => return Space::new(0, 0).into(),
+                GuestAppearances::Loading => return text("Loading...").size(11).into(),
+                GuestAppearances::Failed => {
+                    return text("Failed to load guest appearances")
+                        .size(11)
+                        .style(styles::text_styles::red_text_theme())
+                        .into()
+                }
+                GuestAppearances::Loaded(guest_appearances) => guest_appearances,
+            };
+
+            if guest_appearances.is_empty() {
+                return text("No guest appearances").size(11).into();
+            }
+
+            Column::with_children(
+                guest_appearances
+                    .iter()
+                    .map(|appearance| {
+                        let episode = appearance
+                            .number
+                            .map(|number| {
+                                helpers::season_episode_str_gen(appearance.season, number)
+                            })
+                            .unwrap_or_else(|| "a special".to_owned());
+                        text(format!(
+                            "Appeared in {} \"{}\" of {} as {}",
+                            episode,
+                            appearance.episode_name,
+                            appearance.series_name,
+                            appearance.character_name
+                        ))
+                        .size(11)
+                        .width(260)
+                        .into()
+                    })
+                    .collect(),
+            )
+            .spacing(3)
+            .into()
+        }
+
         fn image_switch_button(&self) -> Element<'_, Message, Renderer> {
             if self.cast.character.image.is_some() {
                 let image_switch_button_handle = svg::Handle::from_memory(ARROW_REPEAT);
@@ -344,10 +585,12 @@ mod cast_poster {
             }
         }
 
-        fn load_person_image(image: Option<Image>) -> Command<Message> {
+        fn person_image_command(image: Option<Image>) -> Command<Message> {
             if let Some(image) = image {
+                let (image_url, image_resolution) =
+                    caching::image_source(image, caching::ImageKind::Poster, false);
                 Command::perform(
-                    caching::load_image(image.medium_image_url, caching::ImageResolution::Medium),
+                    caching::load_image(image_url, image_resolution),
                     Message::PersonImageLoaded,
                 )
             } else {
@@ -357,8 +600,10 @@ mod cast_poster {
 
         fn load_character_image(image: Option<Image>) -> Command<Message> {
             if let Some(image) = image {
+                let (image_url, image_resolution) =
+                    caching::image_source(image, caching::ImageKind::Poster, false);
                 Command::perform(
-                    caching::load_image(image.medium_image_url, caching::ImageResolution::Medium),
+                    caching::load_image(image_url, image_resolution),
                     Message::CharacterImageLoaded,
                 )
             } else {
@@ -0,0 +1,117 @@
+use iced::widget::{button, column, pick_list, row, text};
+use iced::{Alignment, Command, Element, Renderer};
+
+use crate::core::catch_up::{self, AlreadyWatched};
+use crate::gui::{styles, toast};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    CaughtUpPressed,
+    SeasonSelected(u32),
+    UpToSeasonPressed,
+    StartingFreshPressed,
+    Marked(Result<usize, String>),
+}
+
+/// Asks how much of a series that was just tracked has already been watched and marks it
+pub struct CatchUpWidget {
+    series_id: u32,
+    season_numbers: Vec<u32>,
+    selected_season: Option<u32>,
+    marking: bool,
+    /// Whether the user answered and the widget can be dismissed
+    done: bool,
+}
+
+impl CatchUpWidget {
+    pub fn new(series_id: u32, season_numbers: Vec<u32>) -> Self {
+        Self {
+            series_id,
+            selected_season: season_numbers.first().copied(),
+            season_numbers,
+            marking: false,
+            done: false,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::CaughtUpPressed => return self.mark(AlreadyWatched::CaughtUp),
+            Message::SeasonSelected(season_number) => self.selected_season = Some(season_number),
+            Message::UpToSeasonPressed => {
+                if let Some(season_number) = self.selected_season {
+                    return self.mark(AlreadyWatched::UpToSeason(season_number));
+                }
+            }
+            Message::StartingFreshPressed => self.done = true,
+            Message::Marked(result) => {
+                self.marking = false;
+                match result {
+                    Ok(marked_episodes) => {
+                        self.done = true;
+                        toast::push(format!("Marked {} episodes as watched", marked_episodes));
+                    }
+                    Err(err) => {
+                        tracing::error!("failed to mark already watched episodes: {}", err);
+                        toast::push("Could not mark the watched episodes");
+                    }
+                }
+            }
+        }
+        Command::none()
+    }
+
+    fn mark(&mut self, already_watched: AlreadyWatched) -> Command<Message> {
+        self.marking = true;
+        Command::perform(
+            catch_up::mark_already_watched(self.series_id, already_watched),
+            |result| Message::Marked(result.map_err(|err| err.to_string())),
+        )
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let answer_button = |label: &'static str, message: Message| {
+            let answer_button = button(text(label).size(13))
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+            if self.marking {
+                answer_button
+            } else {
+                answer_button.on_press(message)
+            }
+        };
+
+        let mut answers = row![answer_button("I'm caught up", Message::CaughtUpPressed)]
+            .spacing(5)
+            .align_items(Alignment::Center);
+
+        if !self.season_numbers.is_empty() {
+            answers = answers.push(answer_button("Up to season", Message::UpToSeasonPressed));
+            answers = answers.push(
+                pick_list(
+                    self.season_numbers.clone(),
+                    self.selected_season,
+                    Message::SeasonSelected,
+                )
+                .text_size(13),
+            );
+        }
+
+        answers = answers.push(answer_button(
+            "Starting fresh",
+            Message::StartingFreshPressed,
+        ));
+
+        column![
+            text("Have you already seen some of it?").size(21),
+            text("Only the episodes that already aired are marked as watched").size(11),
+            answers,
+        ]
+        .spacing(5)
+        .padding(5)
+        .into()
+    }
+}
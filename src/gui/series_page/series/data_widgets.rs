@@ -4,30 +4,40 @@ use super::Message;
 use crate::core::api::tv_maze::episodes_information::Episode;
 use crate::core::api::tv_maze::series_information::{SeriesMainInformation, ShowStatus};
 use crate::core::database;
+use crate::core::date_formatting;
+use crate::core::export::checklist::{ChecklistFormat, ChecklistImport, EpisodeStatus};
 use crate::gui::assets::icons::{
     CLOCK_FILL, PATCH_PLUS, PATCH_PLUS_FILL, STAR, STAR_FILL, STAR_HALF,
 };
 use crate::gui::helpers::{self, season_episode_str_gen};
-use crate::gui::styles;
+use crate::gui::troxide_widget::copyable_text::copyable;
+use crate::gui::{rich_text, styles};
 
 use iced::widget::{
-    button, column, container, horizontal_rule, horizontal_space, row, svg, text, vertical_space,
-    Button, Space,
+    button, checkbox, column, container, horizontal_rule, horizontal_space, pick_list, row, svg,
+    text, vertical_space, Button, Column, Space,
 };
 use iced::{Alignment, Element, Length, Renderer};
 use iced_aw::Grid;
 
+/// How many of the rows left out of an imported checklist are listed
+const INVALID_ROWS_SHOWN: usize = 5;
+
 /// Generates the Series Metadata
 pub fn series_metadata<'a>(
     series_information: &'a SeriesMainInformation,
     image_bytes: Option<Bytes>,
     next_episode_to_air: Option<&'a Episode>,
+    checklist_import: Option<&'a ChecklistImport>,
 ) -> Element<'a, Message, Renderer> {
     let mut main_info = row!().padding(5).spacing(10);
 
     if let Some(image_bytes) = image_bytes {
         let image_handle = iced::widget::image::Handle::from_memory(image_bytes);
-        let image = iced::widget::image(image_handle).width(180);
+        let image = button(iced::widget::image(image_handle).width(180))
+            .on_press(Message::PosterPressed)
+            .padding(0)
+            .style(styles::button_styles::transparent_button_theme());
 
         main_info = main_info.push(image);
     } else {
@@ -45,6 +55,7 @@ pub fn series_metadata<'a>(
     webchannel_widget(series_information, &mut series_data_grid);
     premiered_widget(series_information, &mut series_data_grid);
     ended_widget(series_information, &mut series_data_grid);
+    tags_widget(series_information, &mut series_data_grid);
 
     let rating_widget = rating_widget(series_information);
     let summary = summary_widget(series_information);
@@ -54,14 +65,24 @@ pub fn series_metadata<'a>(
         .style(styles::text_styles::accent_color_theme());
 
     let title_bar = row![
-        series_name.width(Length::FillPortion(10)),
+        copyable(
+            series_name,
+            Message::CopyPressed(series_information.name.clone())
+        )
+        .width(Length::FillPortion(10)),
+        status_picker(series_information.id),
+        stop_following_button(series_information.id),
         tracking_button(series_information.id)
-    ];
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
 
     let next_episode_widget = next_episode_to_air_widget(next_episode_to_air);
 
     let rating_and_release_widget = row![
         rating_widget,
+        horizontal_space(20),
+        user_rating_widget(series_information.id),
         horizontal_space(Length::Fill),
         next_episode_widget
     ]
@@ -72,6 +93,11 @@ pub fn series_metadata<'a>(
         rating_and_release_widget,
         horizontal_rule(1),
         series_data_grid,
+        watch_party_widget(),
+        checklist_widget(checklist_import),
+        binge_mode_checkbox(series_information.id),
+        auto_mark_aired_checkbox(series_information.id),
+        archive_checkbox(series_information.id),
         vertical_space(10),
     ]
     .width(700)
@@ -146,6 +172,184 @@ pub fn tracking_button(series_id: u32) -> Button<'static, Message, Renderer> {
     .style(styles::button_styles::transparent_button_theme())
 }
 
+/// Untracks the series while keeping it's watch history, only shown while tracked
+pub fn stop_following_button(series_id: u32) -> Element<'static, Message, Renderer> {
+    let is_tracked = database::DB
+        .get_series(series_id)
+        .is_some_and(|series| series.is_tracked());
+    if !is_tracked {
+        return Space::new(0, 0).into();
+    }
+
+    button(text("Stop following").size(11))
+        .on_press(Message::StopFollowingSeries)
+        .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+        .into()
+}
+
+pub fn status_picker(series_id: u32) -> Element<'static, Message, Renderer> {
+    pick_list(
+        &database::SeriesStatus::ALL[..],
+        Some(database::DB.get_series_status(series_id)),
+        Message::StatusSelected,
+    )
+    .text_size(13)
+    .into()
+}
+
+/// Toggles notifying about the series only once its season finale has aired
+pub fn binge_mode_checkbox(series_id: u32) -> Element<'static, Message, Renderer> {
+    checkbox(
+        "Notify only when the whole season is out",
+        database::DB.is_binge_mode(series_id),
+        Message::BingeModeToggled,
+    )
+    .text_size(13)
+    .into()
+}
+
+/// Toggles marking the episodes of the series watched as soon as they air
+pub fn auto_mark_aired_checkbox(series_id: u32) -> Element<'static, Message, Renderer> {
+    column![
+        checkbox(
+            "Mark episodes watched as they air",
+            database::DB.is_auto_mark_aired(series_id),
+            Message::AutoMarkAiredToggled,
+        )
+        .text_size(13),
+        text("For shows watched live, the episodes are labeled as automatically marked in the statistics")
+            .size(11),
+    ]
+    .spacing(2)
+    .into()
+}
+
+/// Toggles archiving the series, for the finished shows that no longer need refreshing
+pub fn archive_checkbox(series_id: u32) -> Element<'static, Message, Renderer> {
+    column![
+        checkbox(
+            "Archive",
+            database::DB.is_archived(series_id),
+            Message::ArchiveToggled,
+        )
+        .text_size(13),
+        text("Archived shows stay in the statistics but are no longer refreshed, notified about or listed in Up Next and My Shows")
+            .size(11),
+    ]
+    .spacing(2)
+    .into()
+}
+
+/// Buttons for sharing and importing the watch progress of the series before a watch party
+pub fn watch_party_widget() -> Element<'static, Message, Renderer> {
+    let action_button = |label: &'static str, message: Message| {
+        button(text(label).size(11))
+            .on_press(message)
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+    };
+
+    row![
+        text("Watch party").size(13),
+        horizontal_space(Length::Fill),
+        action_button("Copy progress", Message::CopySnapshotPressed),
+        action_button("Save progress", Message::SaveSnapshotPressed),
+        action_button(
+            "Import from clipboard",
+            Message::ImportSnapshotFromClipboardPressed
+        ),
+        action_button("Import file", Message::ImportSnapshotFromFilePressed),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+/// Buttons for exporting and importing the episodes of the series with their watched status
+///
+/// An imported checklist is previewed below them until it's applied or canceled.
+pub fn checklist_widget(
+    checklist_import: Option<&ChecklistImport>,
+) -> Element<'_, Message, Renderer> {
+    let action_button = |label: String, message: Message| {
+        button(text(label).size(11))
+            .on_press(message)
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+    };
+
+    let actions = row![
+        text("Episode checklist").size(13),
+        horizontal_space(Length::Fill),
+        action_button(
+            format!("Export {}", ChecklistFormat::Csv),
+            Message::ExportChecklistPressed(ChecklistFormat::Csv)
+        ),
+        action_button(
+            format!("Export {}", ChecklistFormat::Markdown),
+            Message::ExportChecklistPressed(ChecklistFormat::Markdown)
+        ),
+        action_button("Import CSV".to_owned(), Message::ImportChecklistPressed),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center);
+
+    let Some(checklist_import) = checklist_import else {
+        return actions.into();
+    };
+
+    let summary = text(format!(
+        "{} to mark as watched, {} to skip, {} to unwatch, {} already up to date",
+        checklist_import.get_total_changes(EpisodeStatus::Watched),
+        checklist_import.get_total_changes(EpisodeStatus::Skipped),
+        checklist_import.get_total_changes(EpisodeStatus::Unwatched),
+        checklist_import.get_total_unchanged(),
+    ))
+    .size(11);
+
+    let invalid_rows = checklist_import.get_invalid_rows();
+    let invalid_rows_widget = Column::with_children(
+        invalid_rows
+            .iter()
+            .take(INVALID_ROWS_SHOWN)
+            .map(|invalid_row| {
+                text(format!("Line {}: {}", invalid_row.line, invalid_row.reason))
+                    .size(11)
+                    .style(styles::text_styles::red_text_theme())
+                    .into()
+            })
+            .chain((invalid_rows.len() > INVALID_ROWS_SHOWN).then(|| {
+                text(format!(
+                    "and {} more rows left out",
+                    invalid_rows.len() - INVALID_ROWS_SHOWN
+                ))
+                .size(11)
+                .style(styles::text_styles::red_text_theme())
+                .into()
+            }))
+            .collect(),
+    );
+
+    let preview = column![
+        summary,
+        invalid_rows_widget,
+        row![
+            action_button("Apply".to_owned(), Message::ChecklistImportApplied),
+            action_button("Cancel".to_owned(), Message::ChecklistImportCanceled),
+        ]
+        .spacing(5),
+    ]
+    .spacing(5);
+
+    column![
+        actions,
+        container(preview)
+            .padding(5)
+            .width(Length::Fill)
+            .style(styles::container_styles::second_class_container_rounded_theme()),
+    ]
+    .spacing(5)
+    .into()
+}
+
 pub fn status_widget(
     series_info: &SeriesMainInformation,
     data_grid: &mut Grid<'_, Message, Renderer>,
@@ -226,7 +430,7 @@ pub fn premiered_widget(
 ) {
     let title_text = text("Premiered");
     let body_text = if let Some(premier) = &series_info.premiered {
-        text(premier)
+        text(date_formatting::api_date_str(premier))
     } else {
         text("unavailable")
     };
@@ -242,7 +446,7 @@ pub fn ended_widget(
     if let ShowStatus::Ended = series_info.get_status() {
         let title_text = text("Ended");
         let body_text = if let Some(ended) = &series_info.ended {
-            text(ended)
+            text(date_formatting::api_date_str(ended))
         } else {
             text("unavailable")
         };
@@ -252,10 +456,24 @@ pub fn ended_widget(
     }
 }
 
+pub fn tags_widget(
+    series_info: &SeriesMainInformation,
+    data_grid: &mut Grid<'_, Message, Renderer>,
+) {
+    let tags = database::DB.get_series_tags(series_info.id);
+    if !tags.is_empty() {
+        data_grid.insert(text("Tags"));
+        data_grid.insert(text(tags.join(", ")));
+    }
+}
+
 pub fn summary_widget(series_info: &SeriesMainInformation) -> iced::Element<'_, Message, Renderer> {
     if let Some(summary) = &series_info.summary {
-        let summary = html2text::from_read(summary.as_bytes(), 1000);
-        text(summary).size(11).width(880).into()
+        copyable(
+            rich_text::summary(summary, 11, 880),
+            Message::CopyPressed(rich_text::plain(summary)),
+        )
+        .into()
     } else {
         text("").into()
     }
@@ -312,6 +530,15 @@ pub fn rating_widget(series_info: &SeriesMainInformation) -> Element<'_, Message
     }
 }
 
+/// The user's average rating of the rated episodes of the series
+pub fn user_rating_widget(series_id: u32) -> Element<'static, Message, Renderer> {
+    if let Some(average_rating) = database::DB.get_average_episode_rating(series_id, None) {
+        text(format!("Your rating: {:.1} / 10", average_rating)).into()
+    } else {
+        Space::new(0, 0).into()
+    }
+}
+
 pub fn network_widget(
     series_info: &SeriesMainInformation,
     data_grid: &mut Grid<'_, Message, Renderer>,
@@ -5,19 +5,32 @@ use image;
 
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 use crate::core::api::tv_maze::Image;
+use crate::core::bulk_actions;
+use crate::core::export::checklist::{ChecklistFormat, ChecklistImport};
+use crate::core::export::snapshot::{self, Snapshot};
+use crate::core::hooks::{self, HookEvent};
 use crate::core::{caching, database};
+use crate::gui::cancellation::{CancellationToken, PageLifetime};
+use crate::gui::image_viewer;
 use crate::gui::styles;
+use crate::gui::toast::{self, ToastAction};
+use crate::gui::troxide_widget::copyable_text;
+use binge_plan_widget::{BingePlanWidget, Message as BingePlanMessage};
 use cast_widget::{CastWidget, Message as CastWidgetMessage};
+use catch_up_widget::{CatchUpWidget, Message as CatchUpMessage};
 use data_widgets::*;
 use season_widget::{Message as SeasonsMessage, Seasons};
 use series_suggestion_widget::{Message as SeriesSuggestionMessage, SeriesSuggestion};
 
+use iced::keyboard::KeyCode;
 use iced::widget::scrollable::{Id, RelativeOffset, Viewport};
 use iced::widget::vertical_space;
-use iced::widget::{column, scrollable};
-use iced::{Command, Element, Renderer};
+use iced::widget::{button, column, container, row, scrollable, text, Space};
+use iced::{Alignment, Command, Element, Renderer};
 
+mod binge_plan_widget;
 mod cast_widget;
+mod catch_up_widget;
 mod data_widgets;
 mod season_widget;
 mod series_suggestion_widget;
@@ -26,12 +39,36 @@ mod series_suggestion_widget;
 pub enum Message {
     SeriesImageLoaded(Option<Bytes>),
     SeriesBackgroundLoaded(Option<Bytes>),
+    LoadImagesPressed,
     Seasons(SeasonsMessage),
     CastWidgetAction(CastWidgetMessage),
+    BingePlan(BingePlanMessage),
+    CatchUp(CatchUpMessage),
     SeriesSuggestion(SeriesSuggestionMessage),
     PageScrolled(Viewport),
     TrackSeries,
     UntrackSeries,
+    StopFollowingSeries,
+    StatusSelected(database::SeriesStatus),
+    BingeModeToggled(bool),
+    AutoMarkAiredToggled(bool),
+    ArchiveToggled(bool),
+    CopySnapshotPressed,
+    SaveSnapshotPressed,
+    SnapshotSaved(Result<bool, String>),
+    ImportSnapshotFromClipboardPressed,
+    SnapshotClipboardRead(Option<String>),
+    ImportSnapshotFromFilePressed,
+    SnapshotLoaded(Result<Option<Snapshot>, String>),
+    KeyPressed(KeyCode),
+    CopyPressed(String),
+    PosterPressed,
+    ExportChecklistPressed(ChecklistFormat),
+    ChecklistExported(Result<bool, String>),
+    ImportChecklistPressed,
+    ChecklistRead(Result<Option<ChecklistImport>, String>),
+    ChecklistImportApplied,
+    ChecklistImportCanceled,
 }
 
 pub struct Series<'a> {
@@ -42,9 +79,16 @@ pub struct Series<'a> {
     series_background: Option<Bytes>,
     seasons: Seasons,
     casts_widget: CastWidget,
+    binge_plan_widget: BingePlanWidget,
+    /// Shown when the series is tracked for the first time
+    catch_up_widget: Option<CatchUpWidget>,
     series_suggestion_widget: SeriesSuggestion<'a>,
+    /// The imported checklist being previewed
+    checklist_import: Option<ChecklistImport>,
     scroll_offset: RelativeOffset,
     scroller_id: Id,
+    /// Cancels the commands still running once the page is dropped
+    lifetime: PageLifetime,
 }
 
 impl<'a> Series<'a> {
@@ -62,6 +106,7 @@ impl<'a> Series<'a> {
             series_information.get_genres(),
             series_page_sender,
         );
+        let binge_plan_widget = BingePlanWidget::new(series_id, series_information.name.clone());
         let scroller_id = Id::new(format!("series-page-scroller-{}", series_id));
 
         let series_image = series_information.image.clone();
@@ -73,15 +118,19 @@ impl<'a> Series<'a> {
             series_background: None,
             seasons,
             casts_widget,
+            binge_plan_widget,
+            catch_up_widget: None,
             series_suggestion_widget,
+            checklist_import: None,
             scroll_offset: RelativeOffset::default(),
             scroller_id: scroller_id.clone(),
+            lifetime: PageLifetime::new(),
         };
 
         let scroller_command = scrollable::snap_to(scroller_id, RelativeOffset::START);
 
         let commands = [
-            Command::batch(load_images(series_image, series_id)),
+            Command::batch(load_images(series_image, series_id, false)),
             seasons_command.map(Message::Seasons),
             casts_widget_command.map(Message::CastWidgetAction),
             series_suggestion_widget_command.map(Message::SeriesSuggestion),
@@ -91,6 +140,10 @@ impl<'a> Series<'a> {
         (series, Command::batch(commands))
     }
 
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.lifetime.token()
+    }
+
     /// Restores the last `RelativeOffset` of the series page scroller.
     pub fn restore_scroller_relative_offset(&self) -> Command<Message> {
         scrollable::snap_to(self.scroller_id.clone(), self.scroll_offset)
@@ -120,9 +173,29 @@ impl<'a> Series<'a> {
                 self.series_image = image;
             }
             Message::Seasons(message) => return self.seasons.update(message).map(Message::Seasons),
+            Message::KeyPressed(key_code) => {
+                return self
+                    .seasons
+                    .update(SeasonsMessage::KeyPressed(key_code))
+                    .map(Message::Seasons)
+            }
             Message::TrackSeries => {
                 let series_id = self.series_information.id;
 
+                // Offering to mark what was already seen unless episodes were already watched
+                let is_first_track = database::DB
+                    .get_series(series_id)
+                    .is_none_or(|series| series.get_total_episodes() == 0);
+                if is_first_track {
+                    self.catch_up_widget = Some(CatchUpWidget::new(
+                        series_id,
+                        self.seasons.get_season_numbers(),
+                    ));
+                }
+
+                // Following a series again after having stopped
+                database::DB.set_archived(series_id, false);
+
                 if let Some(mut series) = database::DB.get_series(series_id) {
                     series.mark_tracked();
                 } else {
@@ -133,13 +206,51 @@ impl<'a> Series<'a> {
                     series.mark_tracked();
                     database::DB.add_series(self.series_information.id, &series);
                 }
+
+                hooks::trigger(HookEvent::series_tracked(
+                    series_id,
+                    self.series_information.name.to_owned(),
+                ));
             }
             Message::UntrackSeries => {
+                self.catch_up_widget = None;
                 let series_id = self.series_information.id;
                 if let Some(mut series) = database::DB.get_series(series_id) {
                     series.mark_untracked();
                 }
             }
+            Message::StopFollowingSeries => {
+                self.catch_up_widget = None;
+                bulk_actions::stop_following_series(&[self.series_information.id]);
+                toast::push("Stopped following, the watch history is kept in the statistics");
+            }
+            Message::StatusSelected(status) => {
+                database::DB.set_series_status(self.series_information.id, status)
+            }
+            Message::BingeModeToggled(binge_mode) => {
+                database::DB.set_binge_mode(self.series_information.id, binge_mode)
+            }
+            Message::AutoMarkAiredToggled(auto_mark_aired) => {
+                database::DB.set_auto_mark_aired(self.series_information.id, auto_mark_aired)
+            }
+            Message::ArchiveToggled(archived) => {
+                database::DB.set_archived(self.series_information.id, archived)
+            }
+            Message::BingePlan(message) => {
+                return self
+                    .binge_plan_widget
+                    .update(message)
+                    .map(Message::BingePlan)
+            }
+            Message::CatchUp(message) => {
+                if let Some(catch_up_widget) = self.catch_up_widget.as_mut() {
+                    let command = catch_up_widget.update(message).map(Message::CatchUp);
+                    if catch_up_widget.is_done() {
+                        self.catch_up_widget = None;
+                    }
+                    return command;
+                }
+            }
             Message::CastWidgetAction(message) => {
                 return self
                     .casts_widget
@@ -147,6 +258,13 @@ impl<'a> Series<'a> {
                     .map(Message::CastWidgetAction)
             }
             Message::SeriesBackgroundLoaded(background) => self.series_background = background,
+            Message::LoadImagesPressed => {
+                return Command::batch(load_images(
+                    self.series_information.image.clone(),
+                    self.series_id,
+                    true,
+                ))
+            }
             Message::SeriesSuggestion(message) => {
                 return self
                     .series_suggestion_widget
@@ -156,11 +274,148 @@ impl<'a> Series<'a> {
             Message::PageScrolled(view_port) => {
                 self.scroll_offset = view_port.relative_offset();
             }
+            Message::CopySnapshotPressed => match Snapshot::from_db(self.series_id) {
+                Some(snapshot) => {
+                    toast::push("Progress copied to the clipboard");
+                    return iced::clipboard::write(snapshot.to_share_string());
+                }
+                None => toast::push("There is no progress to share yet"),
+            },
+            Message::SaveSnapshotPressed => match Snapshot::from_db(self.series_id) {
+                Some(snapshot) => {
+                    let file_name = format!(
+                        "{}.{}",
+                        self.series_information.name,
+                        snapshot::SNAPSHOT_FILE_EXTENSION
+                    );
+                    return Command::perform(
+                        snapshot_transfer::save(snapshot, file_name),
+                        |result| Message::SnapshotSaved(result.map_err(|err| err.to_string())),
+                    );
+                }
+                None => toast::push("There is no progress to share yet"),
+            },
+            Message::SnapshotSaved(result) => match result {
+                Ok(true) => toast::push("Progress saved"),
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::error!("failed to save snapshot: {}", err);
+                    toast::push("Could not save the progress");
+                }
+            },
+            Message::ExportChecklistPressed(format) => {
+                return Command::perform(
+                    checklist_transfer::export(
+                        self.series_id,
+                        self.series_information.name.clone(),
+                        format,
+                    ),
+                    |result| Message::ChecklistExported(result.map_err(|err| err.to_string())),
+                )
+            }
+            Message::ChecklistExported(result) => match result {
+                Ok(true) => toast::push("Checklist exported"),
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::error!("failed to export checklist: {}", err);
+                    toast::push("Could not export the checklist");
+                }
+            },
+            Message::ImportChecklistPressed => {
+                return Command::perform(
+                    checklist_transfer::import(
+                        self.series_id,
+                        self.series_information.name.clone(),
+                    ),
+                    |result| Message::ChecklistRead(result.map_err(|err| err.to_string())),
+                )
+            }
+            Message::ChecklistRead(result) => match result {
+                Ok(Some(checklist_import)) => self.checklist_import = Some(checklist_import),
+                Ok(None) => {}
+                Err(err) => toast::push(format!("Could not import the checklist: {}", err)),
+            },
+            Message::ChecklistImportApplied => {
+                if let Some(checklist_import) = self.checklist_import.take() {
+                    let applied_checklist = checklist_import.apply();
+                    toast::push_with_action(
+                        format!(
+                            "Changed {} episodes from the checklist",
+                            applied_checklist.get_total_changes()
+                        ),
+                        "Undo",
+                        ToastAction::UndoChecklistImport(applied_checklist),
+                    );
+                    return self.reload_seasons();
+                }
+            }
+            Message::ChecklistImportCanceled => self.checklist_import = None,
+            Message::CopyPressed(copied_text) => return copyable_text::copy(copied_text),
+            Message::PosterPressed => {
+                if let Some(image_bytes) = self.series_image.clone() {
+                    image_viewer::open(image_bytes, self.series_information.name.clone());
+                }
+            }
+            Message::ImportSnapshotFromClipboardPressed => {
+                return iced::clipboard::read(Message::SnapshotClipboardRead)
+            }
+            Message::SnapshotClipboardRead(clipboard_content) => {
+                let snapshot = clipboard_content
+                    .as_deref()
+                    .map(Snapshot::from_share_string)
+                    .transpose()
+                    .map_err(|err| err.to_string());
+                return self.update(Message::SnapshotLoaded(snapshot));
+            }
+            Message::ImportSnapshotFromFilePressed => {
+                return Command::perform(snapshot_transfer::load(), |result| {
+                    Message::SnapshotLoaded(result.map_err(|err| err.to_string()))
+                })
+            }
+            Message::SnapshotLoaded(result) => match result {
+                Ok(Some(snapshot)) => return self.apply_snapshot(snapshot),
+                Ok(None) => {}
+                Err(err) => toast::push(format!("Could not import progress: {}", err)),
+            },
         }
         Command::none()
     }
 
-    pub fn view(&self) -> Element<Message, Renderer> {
+    /// Marks the episodes of the snapshot as watched and reloads the seasons to show them
+    fn apply_snapshot(&mut self, snapshot: Snapshot) -> Command<Message> {
+        if snapshot.get_series_id() != self.series_id {
+            toast::push(format!(
+                "That progress is for {}, not {}",
+                snapshot.get_series_name(),
+                self.series_information.name
+            ));
+            return Command::none();
+        }
+
+        match snapshot.apply() {
+            Ok(marked_episodes) => {
+                toast::push(format!(
+                    "Marked {} episodes as watched from the shared progress",
+                    marked_episodes
+                ));
+                self.reload_seasons()
+            }
+            Err(err) => {
+                toast::push(format!("Could not import progress: {}", err));
+                Command::none()
+            }
+        }
+    }
+
+    /// Loads the seasons again to show the episodes changed outside of them
+    fn reload_seasons(&mut self) -> Command<Message> {
+        let (seasons, seasons_command) =
+            Seasons::new(self.series_id, self.series_information.name.clone());
+        self.seasons = seasons;
+        seasons_command.map(Message::Seasons)
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
         let background = background(
             self.series_background.clone(),
             self.series_image_blurred.clone(),
@@ -170,21 +425,52 @@ impl<'a> Series<'a> {
             &self.series_information,
             self.series_image.clone(),
             self.seasons.get_next_episode_to_air(),
+            self.checklist_import.as_ref(),
         );
 
         let seasons_widget = self.seasons.view().map(Message::Seasons);
 
         let casts_widget = self.casts_widget.view().map(Message::CastWidgetAction);
+        let binge_plan_widget = self.binge_plan_widget.view().map(Message::BingePlan);
         let series_suggestion_widget = self
             .series_suggestion_widget
             .view()
             .map(Message::SeriesSuggestion);
 
+        let load_images_notice: Element<'_, Message, Renderer> = if caching::is_saving_bandwidth()
+            && self.series_image.is_none()
+            && self.series_information.image.is_some()
+        {
+            row![
+                text("Images are not loaded to save bandwidth").size(11),
+                button(text("Load images").size(11))
+                    .on_press(Message::LoadImagesPressed)
+                    .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+            ]
+            .spacing(5)
+            .padding(5)
+            .align_items(Alignment::Center)
+            .into()
+        } else {
+            Space::new(0, 0).into()
+        };
+
+        let catch_up_widget: Element<'_, Message, Renderer> = match &self.catch_up_widget {
+            Some(catch_up_widget) => container(catch_up_widget.view().map(Message::CatchUp))
+                .padding(5)
+                .style(styles::container_styles::first_class_container_rounded_theme())
+                .into(),
+            None => Space::new(0, 0).into(),
+        };
+
         let content = column![
             background,
+            load_images_notice,
             series_metadata,
+            catch_up_widget,
             vertical_space(10),
             seasons_widget,
+            binge_plan_widget,
             casts_widget,
             series_suggestion_widget
         ];
@@ -197,22 +483,142 @@ impl<'a> Series<'a> {
     }
 }
 
-/// Returns two commands that requests series' image and seasons list
-fn load_images(series_info_image: Option<Image>, series_id: u32) -> [Command<Message>; 2] {
+mod snapshot_transfer {
+    use directories::UserDirs;
+    use rfd::AsyncFileDialog;
+
+    use crate::core::export::snapshot::{Snapshot, SNAPSHOT_FILE_EXTENSION};
+
+    /// Saves the snapshot to a file chosen by the user
+    ///
+    /// Returns false when the user did not choose any file
+    pub async fn save(snapshot: Snapshot, file_name: String) -> anyhow::Result<bool> {
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(get_home_directory()?)
+            .set_file_name(&file_name)
+            .add_filter("Series Troxide snapshot", &[SNAPSHOT_FILE_EXTENSION])
+            .save_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            snapshot.write_to_file(chosen_path).await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    pub async fn load() -> anyhow::Result<Option<Snapshot>> {
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(get_home_directory()?)
+            .add_filter("Series Troxide snapshot", &[SNAPSHOT_FILE_EXTENSION])
+            .pick_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            return Ok(Some(Snapshot::read_from_file(chosen_path).await?));
+        }
+
+        Ok(None)
+    }
+
+    fn get_home_directory() -> anyhow::Result<std::path::PathBuf> {
+        let user_dirs = UserDirs::new().ok_or(anyhow::anyhow!("could not get user directory"))?;
+        Ok(user_dirs.home_dir().to_path_buf())
+    }
+}
+
+mod checklist_transfer {
+    use directories::UserDirs;
+    use rfd::AsyncFileDialog;
+
+    use crate::core::export::checklist::{self, ChecklistFormat, ChecklistImport};
+
+    /// Saves the episode checklist of the series to a file chosen by the user
+    ///
+    /// Returns false when the user did not choose any file
+    pub async fn export(
+        series_id: u32,
+        series_name: String,
+        format: ChecklistFormat,
+    ) -> anyhow::Result<bool> {
+        let user_dirs = UserDirs::new().ok_or(anyhow::anyhow!("could not get user directory"))?;
+
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(user_dirs.home_dir())
+            .set_file_name(format!("{} checklist.{}", series_name, format.extension()))
+            .add_filter(format.to_string(), &[format.extension()])
+            .save_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            checklist::write_checklist(series_id, &series_name, format, chosen_path).await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Reads a CSV checklist chosen by the user for previewing it
+    ///
+    /// Returns `None` when the user did not choose any file
+    pub async fn import(
+        series_id: u32,
+        series_name: String,
+    ) -> anyhow::Result<Option<ChecklistImport>> {
+        let user_dirs = UserDirs::new().ok_or(anyhow::anyhow!("could not get user directory"))?;
+
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(user_dirs.home_dir())
+            .add_filter("CSV", &[ChecklistFormat::Csv.extension()])
+            .pick_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            let csv = tokio::fs::read_to_string(chosen_path).await?;
+            return Ok(Some(
+                ChecklistImport::new(series_id, series_name, &csv).await?,
+            ));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Returns two commands that requests series' image and background
+///
+/// The resolution follows the image quality setting, `on_demand` loads the images even
+/// when saving bandwidth.
+fn load_images(
+    series_info_image: Option<Image>,
+    series_id: u32,
+    on_demand: bool,
+) -> [Command<Message>; 2] {
     let image_command = if let Some(image_url) = series_info_image {
-        Command::perform(
-            caching::load_image(
-                image_url.original_image_url,
-                caching::ImageResolution::Original(caching::ImageKind::Poster),
-            ),
-            Message::SeriesImageLoaded,
-        )
+        let (image_url, image_resolution) =
+            caching::image_source(image_url, caching::ImageKind::Poster, true);
+
+        if on_demand {
+            Command::perform(
+                caching::load_image_on_demand(image_url, image_resolution),
+                Message::SeriesImageLoaded,
+            )
+        } else {
+            Command::perform(
+                caching::load_image(image_url, image_resolution),
+                Message::SeriesImageLoaded,
+            )
+        }
     } else {
         Command::none()
     };
 
     let background_command = Command::perform(
-        caching::show_images::get_recent_banner(series_id),
+        caching::show_images::get_recent_banner(series_id, on_demand),
         Message::SeriesBackgroundLoaded,
     );
 
@@ -1,19 +1,76 @@
+use std::collections::HashSet;
 use std::rc::Rc;
 
-use iced::widget::{column, container, text, Column};
+use bytes::Bytes;
+use iced::keyboard::KeyCode;
+use iced::widget::scrollable::{AbsoluteOffset, Id};
+use iced::widget::{
+    button, checkbox, column, container, image, pick_list, row, scrollable, text, text_input,
+    Column, Row,
+};
 use iced::{Alignment, Command, Element, Length};
-use iced_aw::Spinner;
 
+use crate::core::api::tv_maze::alternate_lists;
 use crate::core::api::tv_maze::episodes_information::Episode;
-use crate::core::caching::episode_list::EpisodeList;
+use crate::core::caching;
+use crate::core::caching::episode_list::{EpisodeList, EpisodeNumber};
+use crate::core::database::{self, SeasonOrdering};
+use crate::gui::helpers::{self, season_episode_str_gen};
 use crate::gui::message::IndexedMessage;
-use crate::gui::styles;
-use season::{Message as SeasonMessage, Season};
+use crate::gui::{image_viewer, styles, toast};
+use season::{Message as SeasonMessage, Season, SEASON_ROW_HEIGHT};
+
+/// The amount of seasons from which the seasons get their own scroller and a quick-jump list
+const SEASON_JUMP_THRESHOLD: usize = 10;
+
+const SEASONS_SCROLLER_HEIGHT: f32 = 600.0;
+const SEASONS_PADDING: f32 = 5.0;
+const SEASONS_SPACING: f32 = 5.0;
+
+/// The amount of the most recently aired episodes shown in the stills strip
+const STILLS_AMOUNT: usize = 15;
+
+const SKELETON_SEASONS_AMOUNT: usize = 5;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Season(IndexedMessage<usize, SeasonMessage>),
-    EpisodeListLoaded(EpisodeList),
+    EpisodeListLoaded(Result<EpisodeList, &'static str>),
+    JumpToSeason(SeasonChoice),
+    OrderingSelected(SeasonOrdering),
+    DvdSeasonOrderLoaded(Result<Option<Vec<u32>>, &'static str>),
+    AbsoluteNumberingToggled(bool),
+    EpisodeInputChanged(String),
+    EpisodeInputSubmitted,
+    StillLoaded(usize, Option<Bytes>),
+    /// Season and episode number of the pressed still
+    StillPressed(u32, u32),
+    /// The index of the still whose image is to be viewed in full screen
+    StillImagePressed(usize),
+    KeyPressed(KeyCode),
+}
+
+/// An image of a recently aired episode in the stills strip
+struct EpisodeStill {
+    season: u32,
+    number: u32,
+    name: String,
+    image: Option<Bytes>,
+}
+
+/// A season in the quick-jump list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeasonChoice {
+    index: usize,
+    /// Where the season is in the current ordering
+    position: usize,
+    label: String,
+}
+
+impl std::fmt::Display for SeasonChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
 }
 
 pub struct Seasons {
@@ -21,28 +78,66 @@ pub struct Seasons {
     series_id: u32,
     episode_list: Option<Rc<EpisodeList>>,
     seasons: Vec<Season>,
+    scroller_id: Id,
+    ordering: SeasonOrdering,
+    /// The season numbers in their DVD order, loaded the first time the DVD ordering is used
+    dvd_season_order: Option<Vec<u32>>,
+    absolute_numbering: bool,
+    /// The episode typed in the go to episode input
+    episode_input: String,
+    stills: Vec<EpisodeStill>,
+    /// The index of the season last expanded, whose episodes are traversed with the keyboard
+    focused_season: Option<usize>,
+    /// The numbers of the seasons collapsed only to jump to a later one, still saved as expanded
+    collapsed_by_jump: HashSet<u32>,
 }
 
 impl Seasons {
     pub fn new(series_id: u32, series_name: String) -> (Self, Command<Message>) {
+        let preferences = database::DB.get_season_preferences(series_id);
+        let dvd_season_order_command = if preferences.ordering == SeasonOrdering::Dvd {
+            load_dvd_season_order(series_id)
+        } else {
+            Command::none()
+        };
         (
             Self {
                 series_name,
                 series_id,
                 episode_list: None,
                 seasons: vec![],
+                scroller_id: Id::new(format!("seasons-scroller-{}", series_id)),
+                ordering: preferences.ordering,
+                dvd_season_order: None,
+                absolute_numbering: preferences.absolute_numbering,
+                episode_input: String::new(),
+                stills: vec![],
+                focused_season: None,
+                collapsed_by_jump: HashSet::new(),
             },
-            Command::perform(
-                async move {
-                    EpisodeList::new(series_id)
-                        .await
-                        .expect("failed to get episodes list")
-                },
-                Message::EpisodeListLoaded,
-            ),
+            Command::batch([
+                Command::perform(
+                    async move {
+                        EpisodeList::new(series_id).await.map_err(|err| {
+                            tracing::error!("failed to get the episode list: {}", err);
+                            err.user_message()
+                        })
+                    },
+                    Message::EpisodeListLoaded,
+                ),
+                dvd_season_order_command,
+            ]),
         )
     }
 
+    /// The numbers of the seasons, empty until the episode list is loaded
+    pub fn get_season_numbers(&self) -> Vec<u32> {
+        self.seasons
+            .iter()
+            .map(|season| season.get_season_number())
+            .collect()
+    }
+
     pub fn get_next_episode_to_air(&self) -> Option<&Episode> {
         self.episode_list
             .as_ref()
@@ -51,13 +146,153 @@ impl Seasons {
 
     pub fn update(&mut self, message: Message) -> Command<Message> {
         match message {
-            Message::Season(message) => self.seasons[message.index()]
-                .update(message)
-                .map(Message::Season),
-            Message::EpisodeListLoaded(episode_list) => {
+            Message::Season(message) => {
+                let is_expand_message = matches!(message.clone().message(), SeasonMessage::Expand);
+                let season_index = message.index();
+
+                let command = self.seasons[season_index]
+                    .update(message)
+                    .map(Message::Season);
+
+                if is_expand_message {
+                    self.collapsed_by_jump
+                        .remove(&self.seasons[season_index].get_season_number());
+                    self.save_expanded_seasons();
+                    if self.seasons[season_index].is_expanded() {
+                        self.focused_season = Some(season_index);
+                    }
+                }
+                command
+            }
+            Message::KeyPressed(key_code) => {
+                let Some(season) = self.keyboard_season() else {
+                    return Command::none();
+                };
+
+                match key_code {
+                    KeyCode::Down => season.move_focus(true),
+                    KeyCode::Up => season.move_focus(false),
+                    KeyCode::Space => return season.toggle_focused_episode().map(Message::Season),
+                    KeyCode::Escape => season.clear_focus(),
+                    _ => {}
+                }
+                Command::none()
+            }
+            Message::JumpToSeason(season_choice) => self.jump_to_season(season_choice.index),
+            Message::OrderingSelected(ordering) => {
+                self.ordering = ordering;
+
+                let mut preferences = database::DB.get_season_preferences(self.series_id);
+                preferences.ordering = ordering;
+                database::DB.set_season_preferences(self.series_id, &preferences);
+
+                if ordering == SeasonOrdering::Dvd && self.dvd_season_order.is_none() {
+                    load_dvd_season_order(self.series_id)
+                } else {
+                    Command::none()
+                }
+            }
+            Message::DvdSeasonOrderLoaded(result) => {
+                match result {
+                    Ok(Some(season_order)) => self.dvd_season_order = Some(season_order),
+                    Ok(None) => {
+                        toast::push("TVmaze knows of no DVD release, ordering by season number");
+                        self.dvd_season_order = Some(vec![]);
+                    }
+                    Err(user_message) => toast::push(user_message),
+                }
+                Command::none()
+            }
+            Message::AbsoluteNumberingToggled(absolute_numbering) => {
+                self.absolute_numbering = absolute_numbering;
+                self.seasons
+                    .iter_mut()
+                    .for_each(|season| season.set_absolute_numbering(absolute_numbering));
+
+                let mut preferences = database::DB.get_season_preferences(self.series_id);
+                preferences.absolute_numbering = absolute_numbering;
+                database::DB.set_season_preferences(self.series_id, &preferences);
+
+                Command::none()
+            }
+            Message::EpisodeInputChanged(episode_input) => {
+                self.episode_input = episode_input;
+                Command::none()
+            }
+            Message::EpisodeInputSubmitted => {
+                let episode = match self.episode_input.parse::<EpisodeNumber>() {
+                    Ok(episode_number) => self
+                        .episode_list
+                        .as_ref()
+                        .and_then(|episode_list| episode_list.find_episode(&episode_number)),
+                    Err(err) => {
+                        toast::push(err);
+                        return Command::none();
+                    }
+                };
+
+                let season_index = episode.and_then(|episode| {
+                    self.seasons
+                        .iter()
+                        .position(|season| season.get_season_number() == episode.season)
+                });
+
+                if let Some(season_index) = season_index {
+                    self.episode_input.clear();
+                    self.jump_to_season(season_index)
+                } else {
+                    toast::push(format!("Episode '{}' not found", self.episode_input.trim()));
+                    Command::none()
+                }
+            }
+            Message::StillLoaded(index, image) => {
+                self.stills[index].image = image;
+                Command::none()
+            }
+            Message::StillImagePressed(index) => {
+                if let Some(still) = self.stills.get(index) {
+                    if let Some(image_bytes) = still.image.clone() {
+                        image_viewer::open(
+                            image_bytes,
+                            format!(
+                                "{} {} {}",
+                                self.series_name,
+                                season_episode_str_gen(still.season, still.number),
+                                still.name
+                            ),
+                        );
+                    }
+                }
+                Command::none()
+            }
+            Message::StillPressed(season_number, episode_number) => {
+                let season_index = self
+                    .seasons
+                    .iter()
+                    .position(|season| season.get_season_number() == season_number);
+
+                if let Some(season_index) = season_index {
+                    self.jump_to_season(season_index)
+                } else {
+                    toast::push(format!(
+                        "Episode '{}' not found",
+                        season_episode_str_gen(season_number, episode_number)
+                    ));
+                    Command::none()
+                }
+            }
+            Message::EpisodeListLoaded(Err(user_message)) => {
+                toast::push(user_message);
+                Command::none()
+            }
+            Message::EpisodeListLoaded(Ok(episode_list)) => {
+                let stills_command = self.load_stills(&episode_list);
                 let season_numbers = episode_list.get_season_numbers();
+                let preferences = database::DB.get_season_preferences(self.series_id);
 
                 self.episode_list = Some(Rc::new(episode_list));
+                self.focused_season = None;
+                self.collapsed_by_jump.clear();
 
                 self.seasons = season_numbers
                     .into_iter()
@@ -71,13 +306,253 @@ impl Seasons {
                                 .unwrap_or_else(|| unreachable!("EpisodeList should be present")),
                             self.series_name.to_string(),
                             season,
+                            preferences.get_label(season).map(str::to_owned),
                         )
                     })
                     .collect();
 
-                Command::none()
+                let absolute_numbering = self.absolute_numbering;
+                self.seasons
+                    .iter_mut()
+                    .for_each(|season| season.set_absolute_numbering(absolute_numbering));
+
+                // Restoring the seasons that were expanded the last time the page was open
+                let expanded_seasons = database::DB.get_expanded_seasons(self.series_id);
+                let expand_commands: Vec<_> = self
+                    .seasons
+                    .iter_mut()
+                    .filter(|season| expanded_seasons.contains(&season.get_season_number()))
+                    .map(|season| season.set_expanded(true).map(Message::Season))
+                    .collect();
+
+                Command::batch([Command::batch(expand_commands), stills_command])
+            }
+        }
+    }
+
+    /// The season whose episodes the keyboard moves through, the last expanded one or the first
+    /// expanded one when it was collapsed since
+    fn keyboard_season(&mut self) -> Option<&mut Season> {
+        let season_index = self
+            .focused_season
+            .filter(|index| self.seasons[*index].is_expanded())
+            .or_else(|| self.seasons.iter().position(|season| season.is_expanded()))?;
+
+        self.seasons.get_mut(season_index)
+    }
+
+    /// Picks the most recently aired episodes having an image and loads their images
+    fn load_stills(&mut self, episode_list: &EpisodeList) -> Command<Message> {
+        let mut aired_episodes: Vec<_> = episode_list
+            .get_all_episodes()
+            .iter()
+            .filter(|episode| episode.image.is_some() && episode.number.is_some())
+            .filter(|episode| matches!(episode.is_future_release(), Ok(false)))
+            .collect();
+
+        aired_episodes.sort_by_key(|episode| episode.local_date_time().ok());
+
+        let mut stills = Vec::with_capacity(STILLS_AMOUNT);
+        let mut commands = Vec::with_capacity(STILLS_AMOUNT);
+
+        for (index, episode) in aired_episodes
+            .into_iter()
+            .rev()
+            .take(STILLS_AMOUNT)
+            .enumerate()
+        {
+            let (image_url, image_resolution) = episode
+                .image
+                .clone()
+                .map(|image| caching::image_source(image, caching::ImageKind::Background, false))
+                .unwrap_or_else(|| unreachable!("episodes without images are filtered out"));
+
+            stills.push(EpisodeStill {
+                season: episode.season,
+                number: episode.number.unwrap_or_default(),
+                name: episode.name.clone(),
+                image: None,
+            });
+            commands.push(Command::perform(
+                caching::load_image(image_url, image_resolution),
+                move |image| Message::StillLoaded(index, image),
+            ));
+        }
+
+        self.stills = stills;
+        Command::batch(commands)
+    }
+
+    fn stills_strip(&self) -> Element<'_, Message> {
+        let stills = Row::with_children(
+            self.stills
+                .iter()
+                .enumerate()
+                .map(|(index, still)| {
+                    let still_image: Element<'_, Message> = match still.image.clone() {
+                        Some(image_bytes) => {
+                            button(image(image::Handle::from_memory(image_bytes)).height(90))
+                                .on_press(Message::StillImagePressed(index))
+                                .padding(0)
+                                .style(styles::button_styles::transparent_button_theme())
+                                .into()
+                        }
+                        None => helpers::empty_image::empty_image()
+                            .width(160)
+                            .height(90)
+                            .into(),
+                    };
+
+                    // The image opens in the viewer while the caption jumps to the episode
+                    let caption = button(
+                        text(format!(
+                            "{} {}",
+                            season_episode_str_gen(still.season, still.number),
+                            still.name
+                        ))
+                        .size(11)
+                        .width(160),
+                    )
+                    .on_press(Message::StillPressed(still.season, still.number))
+                    .padding(0)
+                    .style(styles::button_styles::transparent_button_theme());
+
+                    column![still_image, caption].spacing(3).into()
+                })
+                .collect(),
+        )
+        .spacing(5)
+        .padding([0, 0, 10, 0]);
+
+        // As wide as the seasons themselves
+        scrollable(stills)
+            .width(700)
+            .direction(styles::scrollable_styles::horizontal_direction())
+            .into()
+    }
+
+    /// Expands the season at the given index and scrolls to it
+    fn jump_to_season(&mut self, index: usize) -> Command<Message> {
+        let display_order = self.display_order();
+        let position = display_order
+            .iter()
+            .position(|season_index| *season_index == index)
+            .unwrap_or_default();
+
+        // Collapsing the seasons before the chosen one makes it's position predictable, they
+        // are still remembered as expanded since the user did not collapse them
+        let mut commands: Vec<Command<Message>> = vec![];
+        for index in display_order[..position].iter() {
+            let season = &mut self.seasons[*index];
+            if season.is_expanded() {
+                self.collapsed_by_jump.insert(season.get_season_number());
+                commands.push(season.set_expanded(false).map(Message::Season));
+            }
+        }
+
+        self.collapsed_by_jump
+            .remove(&self.seasons[index].get_season_number());
+        commands.push(self.seasons[index].set_expanded(true).map(Message::Season));
+
+        let offset_y = SEASONS_PADDING + position as f32 * (SEASON_ROW_HEIGHT + SEASONS_SPACING);
+        commands.push(scrollable::scroll_to(
+            self.scroller_id.clone(),
+            AbsoluteOffset {
+                x: 0.0,
+                y: offset_y,
+            },
+        ));
+
+        self.save_expanded_seasons();
+        Command::batch(commands)
+    }
+
+    fn save_expanded_seasons(&self) {
+        let expanded_seasons = self
+            .seasons
+            .iter()
+            .filter(|season| season.is_expanded())
+            .map(|season| season.get_season_number())
+            .chain(self.collapsed_by_jump.iter().copied())
+            .collect();
+
+        database::DB.set_expanded_seasons(self.series_id, &expanded_seasons);
+    }
+
+    /// The indices of the seasons in the order they are displayed
+    fn display_order(&self) -> Vec<usize> {
+        let mut display_order: Vec<usize> = (0..self.seasons.len()).collect();
+
+        match self.ordering {
+            SeasonOrdering::SeasonNumber => {}
+            SeasonOrdering::Airdate => {
+                // Seasons without any airdate yet are put last
+                display_order.sort_by_key(|index| {
+                    let premiere_date = self.seasons[*index].get_premiere_date();
+                    (premiere_date.is_none(), premiere_date)
+                });
+            }
+            SeasonOrdering::Dvd => {
+                // Seasons not released on DVD are put last, keeping their season number order
+                let dvd_season_order = self.dvd_season_order.as_deref().unwrap_or_default();
+                display_order.sort_by_key(|index| {
+                    let season_number = self.seasons[*index].get_season_number();
+                    dvd_season_order
+                        .iter()
+                        .position(|dvd_season| *dvd_season == season_number)
+                        .unwrap_or(usize::MAX)
+                });
             }
         }
+
+        display_order
+    }
+
+    fn season_controls(&self) -> Element<'_, Message> {
+        let mut controls = row![
+            text("Order by"),
+            pick_list(
+                &SeasonOrdering::ALL[..],
+                Some(self.ordering),
+                Message::OrderingSelected
+            )
+            .text_size(13),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        controls = controls
+            .push(checkbox(
+                "Absolute numbering",
+                self.absolute_numbering,
+                Message::AbsoluteNumberingToggled,
+            ))
+            .push(
+                text_input("Go to episode (S07E05 or 137)", &self.episode_input)
+                    .on_input(Message::EpisodeInputChanged)
+                    .on_submit(Message::EpisodeInputSubmitted)
+                    .size(13)
+                    .width(200),
+            );
+
+        if self.seasons.len() >= SEASON_JUMP_THRESHOLD {
+            let season_choices: Vec<SeasonChoice> = self
+                .display_order()
+                .into_iter()
+                .enumerate()
+                .map(|(position, index)| SeasonChoice {
+                    index,
+                    position,
+                    label: self.seasons[index].get_label(),
+                })
+                .collect();
+
+            controls = controls
+                .push(text("Jump to"))
+                .push(pick_list(season_choices, None, Message::JumpToSeason).text_size(13));
+        }
+
+        controls.into()
     }
 
     pub fn view(&self) -> Element<'_, Message> {
@@ -86,27 +561,45 @@ impl Seasons {
             .spacing(10);
 
         let content = if self.episode_list.is_none() {
-            container(seasons_body.push(Spinner::new()))
-                .width(700)
-                .center_x()
+            container(seasons_body.push(helpers::skeleton::rows(
+                SKELETON_SEASONS_AMOUNT,
+                700,
+                SEASON_ROW_HEIGHT,
+            )))
+            .width(700)
+            .center_x()
         } else if self.seasons.is_empty() {
             container(seasons_body.push(text("No seasons found")))
                 .width(700)
                 .center_x()
         } else {
-            container(
-                seasons_body.push(
-                    Column::with_children(
-                        self.seasons
-                            .iter()
-                            .map(|season| season.view().map(Message::Season))
-                            .collect(),
-                    )
-                    .padding(5)
-                    .spacing(5)
-                    .align_items(Alignment::Center),
-                ),
+            let seasons_body = if self.stills.is_empty() {
+                seasons_body
+            } else {
+                seasons_body.push(self.stills_strip())
+            };
+
+            let seasons: Element<'_, Message> = Column::with_children(
+                self.display_order()
+                    .into_iter()
+                    .map(|index| self.seasons[index].view().map(Message::Season))
+                    .collect(),
             )
+            .padding(SEASONS_PADDING)
+            .spacing(SEASONS_SPACING)
+            .align_items(Alignment::Center)
+            .into();
+
+            if self.seasons.len() >= SEASON_JUMP_THRESHOLD {
+                let seasons = scrollable(seasons)
+                    .id(self.scroller_id.clone())
+                    .height(SEASONS_SCROLLER_HEIGHT)
+                    .direction(styles::scrollable_styles::vertical_direction());
+
+                container(seasons_body.push(self.season_controls()).push(seasons))
+            } else {
+                container(seasons_body.push(self.season_controls()).push(seasons))
+            }
         }
         .padding(10)
         .style(styles::container_styles::first_class_container_rounded_theme());
@@ -120,30 +613,76 @@ impl Seasons {
     }
 }
 
+fn load_dvd_season_order(series_id: u32) -> Command<Message> {
+    Command::perform(
+        async move {
+            alternate_lists::get_dvd_season_order(series_id)
+                .await
+                .map_err(|err| {
+                    tracing::error!("failed to get the dvd order: {}", err);
+                    err.user_message()
+                })
+        },
+        Message::DvdSeasonOrderLoaded,
+    )
+}
+
 mod season {
     use std::rc::Rc;
 
-    use iced::widget::{button, checkbox, column, container, progress_bar, row, svg, text, Column};
+    use iced::widget::{
+        button, checkbox, column, container, horizontal_space, progress_bar, row, svg, text,
+        text_input, Column,
+    };
     use iced::{Command, Element, Length, Renderer};
-    use iced_aw::Spinner;
 
     use crate::core::api::tv_maze::episodes_information::Episode as EpisodeInfo;
     use crate::core::caching::episode_list::{EpisodeList, TotalEpisodes};
     use crate::core::database;
-    use crate::core::database::AddResult;
-    use crate::gui::assets::icons::{CHEVRON_DOWN, CHEVRON_UP};
+    use crate::core::season_stats::SeasonStats;
+    use crate::core::watch_goal::minutes_str;
+    use crate::gui::assets::icons::{
+        CHEVRON_DOWN, CHEVRON_UP, DASH_SQUARE_FILL, GRAPH_UP_ARROW, PENCIL_FILL,
+    };
     use crate::gui::message::IndexedMessage;
-    use crate::gui::styles;
+    use crate::gui::toast::{self, ToastAction};
     use crate::gui::troxide_widget::episode_widget::{
         Episode, Message as EpisodeMessage, PosterType,
     };
+    use crate::gui::{helpers, styles};
+
+    /// The height of a collapsed season
+    pub const SEASON_ROW_HEIGHT: f32 = 30.0;
+
+    const SKELETON_EPISODES_AMOUNT: usize = 3;
+    /// The height of an episode in a season
+    const SKELETON_EPISODE_HEIGHT: f32 = 70.0;
 
     #[derive(Clone, Debug)]
     pub enum Message {
         CheckboxPressed,
-        TrackCommandComplete(AddResult),
+        CompleteSeasonPressed,
+        ClearSeasonPressed,
+        PartialChoiceDismissed,
+        SeasonCompleted,
         Expand,
         Episode(IndexedMessage<usize, EpisodeMessage>),
+        UnwatchedOnlyToggled,
+        AiredOnlyToggled,
+        ShowWatchedPressed,
+        EditLabelPressed,
+        LabelInputChanged(String),
+        LabelSubmitted,
+        StatsToggled,
+    }
+
+    /// How much of the season the checkbox shows as done
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum CheckState {
+        Unchecked,
+        /// Some of the watchable episodes are watched or skipped
+        Partial,
+        Full,
     }
 
     #[derive(Clone)]
@@ -156,6 +695,20 @@ mod season {
         total_episodes: TotalEpisodes,
         episodes: Vec<Episode>,
         is_expanded: bool,
+        unwatched_only: bool,
+        aired_only: bool,
+        /// Whether the watched episodes before the next episode to watch are shown
+        show_watched: bool,
+        /// The custom label of the season
+        label: Option<String>,
+        /// The label being typed when renaming the season
+        label_input: Option<String>,
+        absolute_numbering: bool,
+        /// The index of the episode selected with the keyboard
+        focused_episode: Option<usize>,
+        /// Whether completing or clearing the partially watched season is being asked
+        partial_choice_shown: bool,
+        stats_shown: bool,
     }
 
     impl Season {
@@ -165,6 +718,7 @@ mod season {
             episode_list: Rc<EpisodeList>,
             series_name: String,
             season_number: u32,
+            label: Option<String>,
         ) -> Self {
             let total_episodes = episode_list.get_season_total_episodes(season_number);
             Self {
@@ -176,75 +730,180 @@ mod season {
                 total_episodes,
                 episodes: vec![],
                 is_expanded: false,
+                unwatched_only: false,
+                aired_only: false,
+                show_watched: false,
+                label,
+                label_input: None,
+                absolute_numbering: false,
+                focused_episode: None,
+                partial_choice_shown: false,
+                stats_shown: false,
+            }
+        }
+
+        /// Switches the episodes between absolute and seasonal numbering
+        pub fn set_absolute_numbering(&mut self, absolute_numbering: bool) {
+            self.absolute_numbering = absolute_numbering;
+
+            for episode in self.episodes.iter_mut() {
+                let absolute_number = absolute_numbering
+                    .then(|| {
+                        let episode_info = episode.get_episode_information();
+                        episode_info.number.and_then(|episode_number| {
+                            self.episode_list
+                                .get_absolute_number(episode_info.season, episode_number)
+                        })
+                    })
+                    .flatten();
+
+                episode.set_absolute_number(absolute_number);
+            }
+        }
+        pub fn get_season_number(&self) -> u32 {
+            self.season_number
+        }
+
+        /// The custom label of the season or it's default one when not set
+        pub fn get_label(&self) -> String {
+            self.label
+                .clone()
+                .unwrap_or_else(|| format!("Season {}", self.season_number))
+        }
+
+        /// The airdate of the first episode of the season
+        pub fn get_premiere_date(&self) -> Option<chrono::NaiveDate> {
+            self.episode_list
+                .get_episodes(self.season_number)
+                .into_iter()
+                .filter_map(|episode| episode.date_naive().ok())
+                .min()
+        }
+
+        pub fn is_expanded(&self) -> bool {
+            self.is_expanded
+        }
+
+        /// Moves the keyboard selection to the next or previous shown episode, starting from
+        /// the first or last one
+        pub fn move_focus(&mut self, forward: bool) {
+            let shown_episodes = self.shown_episodes();
+            let Some(last_position) = shown_episodes.len().checked_sub(1) else {
+                return;
+            };
+
+            let focused_position = self.focused_episode.and_then(|focused_episode| {
+                shown_episodes
+                    .iter()
+                    .position(|index| *index == focused_episode)
+            });
+
+            let position = match (focused_position, forward) {
+                (Some(position), true) => (position + 1).min(last_position),
+                (Some(position), false) => position.saturating_sub(1),
+                (None, true) => 0,
+                (None, false) => last_position,
+            };
+
+            self.focused_episode = Some(shown_episodes[position]);
+        }
+
+        pub fn clear_focus(&mut self) {
+            self.focused_episode = None;
+        }
+
+        /// Marks the episode selected with the keyboard watched or unwatched like its checkbox
+        pub fn toggle_focused_episode(&mut self) -> Command<IndexedMessage<usize, Message>> {
+            let Some(episode_index) = self.focused_episode else {
+                return Command::none();
+            };
+
+            // Episodes without a number have no checkbox to toggle
+            if self.episodes[episode_index]
+                .get_episode_information()
+                .number
+                .is_none()
+            {
+                return Command::none();
+            }
+
+            self.update(IndexedMessage::new(
+                self.index,
+                Message::Episode(IndexedMessage::new(
+                    episode_index,
+                    EpisodeMessage::MarkedWatched(PosterType::Season),
+                )),
+            ))
+        }
+
+        /// Expands or collapses the season, loading it's episodes when expanded the first time
+        pub fn set_expanded(&mut self, expanded: bool) -> Command<IndexedMessage<usize, Message>> {
+            self.is_expanded = expanded;
+
+            // preventing reloading episodes when already loaded
+            // when expanding and shrinking the season widget multiple times
+            if !expanded || !self.episodes.is_empty() {
+                return Command::none();
+            }
+
+            let episode_infos: Vec<EpisodeInfo> = self
+                .episode_list
+                .get_episodes(self.season_number)
+                .into_iter()
+                .cloned()
+                .collect();
+
+            let epis: Vec<(Episode, Command<IndexedMessage<usize, EpisodeMessage>>)> =
+                episode_infos
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, info)| {
+                        Episode::new(index, self.series_id, self.series_name.clone(), info)
+                    })
+                    .collect();
+
+            let index = self.index;
+            let mut commands = Vec::with_capacity(epis.len());
+            let mut episodes = Vec::with_capacity(epis.len());
+            for (mut episode, command) in epis {
+                episode.set_badge(
+                    self.episode_list
+                        .get_episode_badge(episode.get_episode_information()),
+                );
+                episodes.push(episode);
+                commands.push(command);
             }
+
+            self.episodes = episodes;
+            self.set_absolute_numbering(self.absolute_numbering);
+
+            Command::batch(commands)
+                .map(Message::Episode)
+                .map(move |message| IndexedMessage::new(index, message))
         }
+
         pub fn update(
             &mut self,
             message: IndexedMessage<usize, Message>,
         ) -> Command<IndexedMessage<usize, Message>> {
             match message.message() {
-                Message::CheckboxPressed => {
-                    let series_id = self.series_id;
-                    let series_name = self.series_name.clone();
-                    let season_number = self.season_number;
-                    let total_episodes = self.total_episodes.get_all_episodes();
-                    let index = self.index;
-
-                    return Command::perform(
-                        async move {
-                            if let Some(mut series) = database::DB.get_series(series_id) {
-                                series
-                                    .add_episodes(season_number, 1..=total_episodes as u32)
-                                    .await
-                            } else {
-                                let mut series = database::Series::new(series_name, series_id);
-                                series
-                                    .add_episodes(season_number, 1..=total_episodes as u32)
-                                    .await
-                            }
-                        },
-                        Message::TrackCommandComplete,
-                    )
-                    .map(move |message| IndexedMessage::new(index, message));
+                Message::CheckboxPressed => match self.check_state() {
+                    CheckState::Unchecked => return self.complete_season(),
+                    CheckState::Partial => self.partial_choice_shown = true,
+                    CheckState::Full => self.clear_season(),
+                },
+                Message::CompleteSeasonPressed => {
+                    self.partial_choice_shown = false;
+                    return self.complete_season();
                 }
-                Message::Expand => {
-                    self.is_expanded = !self.is_expanded;
-
-                    // preventing reloading episodes when already loaded
-                    // when expanding and shrinking the season widget multiple times
-                    if !self.episodes.is_empty() {
-                        return Command::none();
-                    }
-
-                    let episode_infos: Vec<EpisodeInfo> = self
-                        .episode_list
-                        .get_episodes(self.season_number)
-                        .into_iter()
-                        .cloned()
-                        .collect();
-
-                    let epis: Vec<(Episode, Command<IndexedMessage<usize, EpisodeMessage>>)> =
-                        episode_infos
-                            .into_iter()
-                            .enumerate()
-                            .map(|(index, info)| {
-                                Episode::new(index, self.series_id, self.series_name.clone(), info)
-                            })
-                            .collect();
-
-                    let index = self.index;
-                    let mut commands = Vec::with_capacity(epis.len());
-                    let mut episodes = Vec::with_capacity(epis.len());
-                    for (episode, command) in epis {
-                        episodes.push(episode);
-                        commands.push(command);
-                    }
-
-                    self.episodes = episodes;
-                    return Command::batch(commands)
-                        .map(Message::Episode)
-                        .map(move |message| IndexedMessage::new(index, message));
+                Message::ClearSeasonPressed => {
+                    self.partial_choice_shown = false;
+                    self.clear_season();
                 }
+                Message::PartialChoiceDismissed => self.partial_choice_shown = false,
+                // The checkbox reads the database when viewed
+                Message::SeasonCompleted => {}
+                Message::Expand => return self.set_expanded(!self.is_expanded),
                 Message::Episode(message) => {
                     let season_index = self.index;
                     return self.episodes[message.index()]
@@ -252,18 +911,27 @@ mod season {
                         .map(Message::Episode)
                         .map(move |message| IndexedMessage::new(season_index, message));
                 }
-                Message::TrackCommandComplete(add_result) => {
-                    if let AddResult::None = add_result {
-                        if let Some(mut series) = database::DB.get_series(self.series_id) {
-                            series.remove_season(self.season_number);
-                        }
+                Message::UnwatchedOnlyToggled => self.unwatched_only = !self.unwatched_only,
+                Message::AiredOnlyToggled => self.aired_only = !self.aired_only,
+                Message::ShowWatchedPressed => self.show_watched = true,
+                Message::EditLabelPressed => self.label_input = Some(self.get_label()),
+                Message::LabelInputChanged(label) => self.label_input = Some(label),
+                Message::LabelSubmitted => {
+                    if let Some(label) = self.label_input.take() {
+                        let mut preferences = database::DB.get_season_preferences(self.series_id);
+                        preferences.set_label(self.season_number, label);
+                        database::DB.set_season_preferences(self.series_id, &preferences);
+
+                        self.label = preferences.get_label(self.season_number).map(str::to_owned);
                     }
                 }
+                Message::StatsToggled => self.stats_shown = !self.stats_shown,
             }
             Command::none()
         }
 
-        pub fn view(&self) -> Element<'_, IndexedMessage<usize, Message>, Renderer> {
+        /// The watched and skipped episodes of the season
+        fn completed_episodes(&self) -> usize {
             let tracked_episodes = database::DB
                 .get_series(self.series_id)
                 .map(|series| {
@@ -274,28 +942,144 @@ mod season {
                 })
                 .unwrap_or_default();
 
-            let track_checkbox = checkbox(
-                "",
-                (self.total_episodes.get_all_watchable_episodes() == tracked_episodes)
-                    && (tracked_episodes != 0),
-                |_| Message::CheckboxPressed,
+            // Skipped episodes count towards the progress of the season
+            tracked_episodes
+                + database::DB.get_total_skipped_episodes(self.series_id, Some(self.season_number))
+        }
+
+        fn check_state(&self) -> CheckState {
+            let completed_episodes = self.completed_episodes();
+            if completed_episodes == 0 {
+                CheckState::Unchecked
+            } else if self.total_episodes.get_all_watchable_episodes() <= completed_episodes {
+                CheckState::Full
+            } else {
+                CheckState::Partial
+            }
+        }
+
+        /// Marks all the watchable episodes of the season watched
+        fn complete_season(&self) -> Command<IndexedMessage<usize, Message>> {
+            let series_id = self.series_id;
+            let series_name = self.series_name.clone();
+            let season_number = self.season_number;
+            let total_episodes = self.total_episodes.get_all_episodes();
+            let index = self.index;
+
+            Command::perform(
+                async move {
+                    let mut series = database::DB
+                        .get_series(series_id)
+                        .unwrap_or_else(|| database::Series::new(series_name, series_id));
+
+                    series
+                        .add_episodes(season_number, 1..=total_episodes as u32)
+                        .await;
+                },
+                |_| Message::SeasonCompleted,
+            )
+            .map(move |message| IndexedMessage::new(index, message))
+        }
+
+        /// Unwatches and unskips all the episodes of the season, offering to undo it as a single
+        /// click can toss a lot of watch history
+        fn clear_season(&self) {
+            let Some(cleared_season) =
+                database::DB.clear_season(self.series_id, self.season_number)
+            else {
+                return;
+            };
+
+            toast::push_with_action(
+                format!("Cleared {}", self.get_label()),
+                "Undo",
+                ToastAction::RestoreSeason(cleared_season),
             );
-            let season_name = text(format!("Season {}", self.season_number)).width(80);
+        }
+
+        pub fn view(&self) -> Element<'_, IndexedMessage<usize, Message>, Renderer> {
+            let completed_episodes = self.completed_episodes();
+
+            let track_checkbox: Element<'_, Message, Renderer> = match self.check_state() {
+                CheckState::Partial => {
+                    let partial_icon = svg(svg::Handle::from_memory(DASH_SQUARE_FILL))
+                        .width(20)
+                        .height(20)
+                        .style(styles::svg_styles::colored_svg_theme());
+                    button(partial_icon)
+                        .padding(0)
+                        .on_press(Message::CheckboxPressed)
+                        .style(styles::button_styles::transparent_button_theme())
+                        .into()
+                }
+                check_state => checkbox("", check_state == CheckState::Full, |_| {
+                    Message::CheckboxPressed
+                })
+                .into(),
+            };
+            let season_name: Element<'_, Message, Renderer> =
+                if let Some(label_input) = &self.label_input {
+                    text_input(&format!("Season {}", self.season_number), label_input)
+                        .on_input(Message::LabelInputChanged)
+                        .on_submit(Message::LabelSubmitted)
+                        .size(13)
+                        .width(120)
+                        .into()
+                } else {
+                    let edit_icon = svg(svg::Handle::from_memory(PENCIL_FILL))
+                        .width(12)
+                        .height(12)
+                        .style(styles::svg_styles::colored_svg_theme());
+
+                    row![
+                        text(self.get_label()).width(Length::Fill),
+                        button(edit_icon)
+                            .padding(2)
+                            .on_press(Message::EditLabelPressed)
+                            .style(styles::button_styles::transparent_button_theme()),
+                    ]
+                    .align_items(iced::Alignment::Center)
+                    .width(120)
+                    .into()
+                };
 
             let season_progress = progress_bar(
                 0.0..=self.total_episodes.get_all_episodes() as f32,
-                tracked_episodes as f32,
+                completed_episodes as f32,
             )
             .height(10)
-            .width(500);
+            .width(380);
 
             let episodes_progress = text(format!(
                 "{}/{}",
-                tracked_episodes,
+                completed_episodes,
                 self.total_episodes.get_all_episodes()
             ))
             .width(50);
 
+            // The user's average of the rated episodes of the season
+            let average_rating = text(
+                database::DB
+                    .get_average_episode_rating(self.series_id, Some(self.season_number))
+                    .map(|average_rating| format!("{:.1}/10", average_rating))
+                    .unwrap_or_default(),
+            )
+            .size(11)
+            .width(45);
+
+            let stats_icon = svg(svg::Handle::from_memory(GRAPH_UP_ARROW))
+                .width(15)
+                .height(15)
+                .style(styles::svg_styles::colored_svg_theme());
+            let stats_button =
+                button(stats_icon)
+                    .on_press(Message::StatsToggled)
+                    .style(if self.stats_shown {
+                        styles::button_styles::transparent_button_with_rounded_border_theme()
+                    } else {
+                        styles::button_styles::transparent_button_theme()
+                    });
+
             let expand_button = if self.is_expanded {
                 let svg_handle = svg::Handle::from_memory(CHEVRON_UP);
                 let up_icon = svg(svg_handle)
@@ -319,31 +1103,230 @@ mod season {
                 season_name,
                 season_progress,
                 episodes_progress,
+                average_rating,
+                stats_button,
                 expand_button,
             ]
-            .spacing(5);
+            .spacing(5)
+            .height(SEASON_ROW_HEIGHT)
+            .align_items(iced::Alignment::Center);
 
             let mut content = column!(content);
+            if self.partial_choice_shown {
+                content = content.push(self.partial_choice());
+            }
+            if self.stats_shown {
+                content = content.push(self.stats_view());
+            }
             if self.is_expanded {
                 if self.episodes.is_empty() {
-                    content = content.push(container(Spinner::new()))
+                    content = content.push(helpers::skeleton::rows(
+                        SKELETON_EPISODES_AMOUNT,
+                        700,
+                        SKELETON_EPISODE_HEIGHT,
+                    ))
                 } else {
-                    content = content.push(
-                        Column::with_children(
-                            self.episodes
-                                .iter()
-                                .map(|episode| {
-                                    episode.view(PosterType::Season).map(Message::Episode)
-                                })
-                                .collect(),
-                        )
-                        .spacing(3),
-                    );
+                    content = content.push(self.episodes_view());
                 }
             }
 
             let element: Element<'_, Message, Renderer> = content.into();
             element.map(|message| IndexedMessage::new(self.index, message))
         }
+
+        /// Asks whether the partially watched season is completed or cleared
+        fn partial_choice(&self) -> Element<'_, Message, Renderer> {
+            let choice_button = |label: &'static str, message: Message| {
+                button(text(label).size(11))
+                    .on_press(message)
+                    .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+            };
+
+            row![
+                text("Some episodes of this season are watched").size(11),
+                choice_button("Complete season", Message::CompleteSeasonPressed),
+                choice_button("Clear season", Message::ClearSeasonPressed),
+                choice_button("Cancel", Message::PartialChoiceDismissed),
+            ]
+            .spacing(5)
+            .align_items(iced::Alignment::Center)
+            .into()
+        }
+
+        /// The ratings, runtime and progress of the season
+        fn stats_view(&self) -> Element<'_, Message, Renderer> {
+            let stats = SeasonStats::new(self.series_id, &self.episode_list, self.season_number);
+
+            let rating_str = |rating: Option<f32>| {
+                rating.map_or("-".to_owned(), |rating| format!("{:.1}", rating))
+            };
+
+            let stat = |title: &'static str, value: String| {
+                column![
+                    text(title)
+                        .size(11)
+                        .style(styles::text_styles::accent_color_theme()),
+                    text(value).size(11),
+                ]
+                .spacing(2)
+            };
+
+            let time_to_finish = if stats.time_to_finish == 0 {
+                "Nothing left".to_owned()
+            } else {
+                minutes_str(stats.time_to_finish)
+            };
+
+            let content = row![
+                stat(
+                    "Average rating",
+                    format!(
+                        "TVmaze {}, yours {}",
+                        rating_str(stats.average_episode_rating),
+                        rating_str(stats.average_user_rating)
+                    )
+                ),
+                stat("Total runtime", minutes_str(stats.total_runtime)),
+                stat(
+                    "Completion",
+                    format!(
+                        "{:.0}% ({}/{} aired)",
+                        stats.completion() * 100.0,
+                        stats.completed_episodes,
+                        stats.watchable_episodes
+                    )
+                ),
+                stat("Time to finish", time_to_finish),
+            ]
+            .spacing(20);
+
+            container(content)
+                .style(styles::container_styles::first_class_container_rounded_theme())
+                .padding(10)
+                .width(Length::Fill)
+                .into()
+        }
+
+        /// The indices of the episodes matching the filters and the amount of them collapsed
+        /// at the start
+        fn visible_episodes(&self) -> (Vec<usize>, usize) {
+            let series = database::DB.get_series(self.series_id);
+            let skipped_episodes = database::DB.get_skipped_episodes(self.series_id);
+
+            // Skipped episodes are treated as watched so that they are filtered and collapsed too
+            let is_watched = |episode: &Episode| {
+                let episode_info = episode.get_episode_information();
+                let Some(episode_number) = episode_info.number else {
+                    return false;
+                };
+
+                skipped_episodes.contains(&(episode_info.season, episode_number))
+                    || series
+                        .as_ref()
+                        .and_then(|series| series.get_season(episode_info.season))
+                        .map(|season| season.is_episode_watched(episode_number))
+                        .unwrap_or(false)
+            };
+
+            let visible_episodes: Vec<usize> = self
+                .episodes
+                .iter()
+                .enumerate()
+                .filter(|(_, episode)| !self.unwatched_only || !is_watched(episode))
+                .filter(|(_, episode)| {
+                    !self.aired_only
+                        || episode.get_episode_information().is_future_release() == Ok(false)
+                })
+                .map(|(index, _)| index)
+                .collect();
+
+            // Collapsing the watched episodes before the next episode to watch so that long
+            // seasons open right where the user left off
+            let collapsed_episodes = if self.show_watched {
+                0
+            } else {
+                visible_episodes
+                    .iter()
+                    .position(|index| !is_watched(&self.episodes[*index]))
+                    .unwrap_or_default()
+            };
+
+            (visible_episodes, collapsed_episodes)
+        }
+
+        /// The indices of the episodes on screen
+        fn shown_episodes(&self) -> Vec<usize> {
+            let (visible_episodes, collapsed_episodes) = self.visible_episodes();
+            visible_episodes
+                .into_iter()
+                .skip(collapsed_episodes)
+                .collect()
+        }
+
+        fn episodes_view(&self) -> Element<'_, Message, Renderer> {
+            let filters = row![
+                filter_chip(
+                    "Unwatched only",
+                    self.unwatched_only,
+                    Message::UnwatchedOnlyToggled
+                ),
+                filter_chip("Aired only", self.aired_only, Message::AiredOnlyToggled),
+                horizontal_space(Length::Fill),
+                text("Up and Down to select episodes, Space to mark them watched").size(11),
+            ]
+            .spacing(5)
+            .align_items(iced::Alignment::Center);
+
+            let (visible_episodes, collapsed_episodes) = self.visible_episodes();
+
+            let mut episodes = Column::new().spacing(3);
+
+            if visible_episodes.is_empty() {
+                episodes = episodes.push(text("No episodes match the filters").size(11));
+            }
+
+            if collapsed_episodes > 0 {
+                episodes = episodes.push(
+                    button(text(format!("Show {} watched episodes", collapsed_episodes)).size(11))
+                        .on_press(Message::ShowWatchedPressed)
+                        .style(
+                            styles::button_styles::transparent_button_with_rounded_border_theme(),
+                        ),
+                );
+            }
+
+            for index in visible_episodes.into_iter().skip(collapsed_episodes) {
+                let episode = self.episodes[index]
+                    .view(PosterType::Season)
+                    .map(Message::Episode);
+
+                episodes = if self.focused_episode == Some(index) {
+                    episodes.push(
+                        container(episode)
+                            .style(styles::container_styles::focused_container_theme()),
+                    )
+                } else {
+                    episodes.push(episode)
+                };
+            }
+
+            column![filters, episodes].spacing(5).into()
+        }
+    }
+
+    fn filter_chip(
+        label: &'static str,
+        is_active: bool,
+        message: Message,
+    ) -> Element<'static, Message, Renderer> {
+        let mut label = text(label).size(11);
+        if is_active {
+            label = label.style(styles::text_styles::accent_color_theme());
+        }
+
+        button(label)
+            .on_press(message)
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+            .into()
     }
 }
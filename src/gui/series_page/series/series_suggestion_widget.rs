@@ -2,13 +2,16 @@ use std::sync::mpsc;
 
 use crate::core::api::tv_maze::series_information::{Genre, SeriesMainInformation};
 use crate::core::caching::tv_schedule::full_schedule;
+use crate::gui::helpers;
 use crate::gui::troxide_widget::series_poster::{
     IndexedMessage, Message as SeriesPosterMessage, SeriesPoster,
 };
 
-use iced::widget::{column, container, text, Space};
-use iced::{Command, Element, Length, Renderer};
-use iced_aw::{Spinner, Wrap};
+use iced::widget::{column, text, Space};
+use iced::{Command, Element, Renderer};
+use iced_aw::Wrap;
+
+const SKELETON_POSTERS_AMOUNT: usize = 10;
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -89,10 +92,7 @@ impl<'a> SeriesSuggestion<'a> {
 
     pub fn view(&self) -> Element<'_, Message, Renderer> {
         match self.load_state {
-            LoadState::Loading => container(Spinner::new())
-                .width(Length::Fill)
-                .center_x()
-                .into(),
+            LoadState::Loading => helpers::skeleton::series_posters(SKELETON_POSTERS_AMOUNT),
             LoadState::Loaded => {
                 if self.suggested_series.is_empty() {
                     Space::new(0, 0).into()
@@ -55,6 +55,25 @@ pub fn loading_container_theme() -> Container {
     Container::Custom(Box::new(LoadingContainerTheme) as Box<dyn StyleSheet<Style = iced::Theme>>)
 }
 
+/// A custom theme for container respecting Light and Dark TroxideTheme
+/// designed for the skeleton placeholders shown while content loads
+pub fn skeleton_container_theme() -> Container {
+    Container::Custom(Box::new(SkeletonContainerTheme) as Box<dyn StyleSheet<Style = iced::Theme>>)
+}
+
+/// A custom theme for container covering content with the background color of the theme,
+/// `opacity` going from 0.0 for fully see-through to 1.0 for fully covered
+pub fn fade_container_theme(opacity: f32) -> Container {
+    Container::Custom(
+        Box::new(FadeContainerTheme { opacity }) as Box<dyn StyleSheet<Style = iced::Theme>>
+    )
+}
+
+/// A custom theme for container outlining the content selected with the keyboard
+pub fn focused_container_theme() -> Container {
+    Container::Custom(Box::new(FocusedContainerTheme) as Box<dyn StyleSheet<Style = iced::Theme>>)
+}
+
 pub struct FirstClassContainerRoundedTheme;
 
 impl StyleSheet for FirstClassContainerRoundedTheme {
@@ -279,3 +298,64 @@ impl StyleSheet for LoadingContainerTheme {
         }
     }
 }
+
+pub struct SkeletonContainerTheme;
+
+impl StyleSheet for SkeletonContainerTheme {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        let mut appearance = Appearance {
+            border_radius: BorderRadius::from(5.0),
+            ..Appearance::default()
+        };
+
+        match style {
+            iced::Theme::Custom(custom) => {
+                if **custom == TroxideTheme::get_custom_theme(&TroxideTheme::Light) {
+                    appearance.background = Some(Background::Color(color!(0xbbbbbb)));
+                    appearance
+                } else {
+                    appearance.background = Some(Background::Color(color!(0x333333)));
+                    appearance
+                }
+            }
+            _ => unreachable!("built-in iced themes are not in use"),
+        }
+    }
+}
+
+pub struct FadeContainerTheme {
+    opacity: f32,
+}
+
+impl StyleSheet for FadeContainerTheme {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        let background = Color {
+            a: self.opacity.clamp(0.0, 1.0),
+            ..style.palette().background
+        };
+
+        Appearance {
+            background: Some(Background::Color(background)),
+            ..Appearance::default()
+        }
+    }
+}
+
+pub struct FocusedContainerTheme;
+
+impl StyleSheet for FocusedContainerTheme {
+    type Style = iced::Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> Appearance {
+        Appearance {
+            border_width: 2.0,
+            border_radius: BorderRadius::from(5.0),
+            border_color: super::colors::accent_color(),
+            ..Appearance::default()
+        }
+    }
+}
@@ -3,3 +3,7 @@ use iced::widget::scrollable::{Direction, Properties};
 pub fn vertical_direction() -> Direction {
     Direction::Vertical(Properties::new().width(5).scroller_width(5))
 }
+
+pub fn horizontal_direction() -> Direction {
+    Direction::Horizontal(Properties::new().width(5).scroller_width(5))
+}
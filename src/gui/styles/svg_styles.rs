@@ -20,3 +20,20 @@ impl StyleSheet for ColoredSvgTheme {
         }
     }
 }
+
+/// A custom theme that greys out svg, for icons of toggles that are off
+pub fn greyed_svg_theme() -> Svg {
+    Svg::Custom(Box::new(GreyedSvgTheme) as Box<dyn StyleSheet<Style = iced::Theme>>)
+}
+
+pub struct GreyedSvgTheme;
+
+impl StyleSheet for GreyedSvgTheme {
+    type Style = iced::Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> Appearance {
+        Appearance {
+            color: Some(color!(0x808080)),
+        }
+    }
+}
@@ -0,0 +1,460 @@
+use std::borrow::Cow;
+use std::sync::mpsc;
+
+use chrono::{Duration, Local, NaiveDate};
+use iced::widget::scrollable::{Id, RelativeOffset, Viewport};
+use iced::widget::{button, column, container, row, scrollable, text, Column, Row, Space};
+use iced::{Alignment, Command, Element, Length, Renderer};
+use iced_aw::date_picker::{self, DatePicker};
+use iced_aw::Spinner;
+
+use super::full_schedule::series_posters_viewer;
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::binge_plan;
+use crate::core::caching::tv_schedule;
+use crate::core::caching::tv_schedule::prime_time_grid::{self, GridChannel, GridEntry};
+use crate::core::database;
+use crate::core::date_formatting;
+use crate::core::settings_config::locale_settings;
+use crate::gui::troxide_widget::series_poster::{
+    IndexedMessage, Message as SeriesPosterMessage, SeriesPoster,
+};
+use crate::gui::{helpers, styles};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PreviousDayPressed,
+    NextDayPressed,
+    TodayPressed,
+    PickDatePressed,
+    PickDateCanceled,
+    DatePicked(date_picker::Date),
+    /// The streaming and the local series airing on the date
+    SeriesLoaded(
+        NaiveDate,
+        Option<(Vec<SeriesMainInformation>, Vec<SeriesMainInformation>)>,
+    ),
+    GridLoaded(NaiveDate, Option<Vec<GridChannel>>),
+    /// Switches between the posters and the prime time grid
+    ViewToggled,
+    /// The channel index and the entry index of a pressed grid entry
+    GridEntryPressed(usize, usize),
+    GridScrolled(Viewport),
+    StreamingPosters(IndexedMessage<usize, SeriesPosterMessage>),
+    LocalPosters(IndexedMessage<usize, SeriesPosterMessage>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum View {
+    Posters,
+    PrimeTimeGrid,
+}
+
+enum LoadState {
+    /// Nothing is requested until a date is chosen
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed,
+}
+
+/// What aired or airs on any chosen date as posters or as a prime time grid, requested
+/// from TVmaze as the full schedule only knows about the future
+pub struct DateSchedule<'a> {
+    date: NaiveDate,
+    show_date_picker: bool,
+    view: View,
+    load_state: LoadState,
+    grid: Vec<GridChannel>,
+    /// Where the prime time grid was scrolled to, restored when coming back from a series page
+    grid_scrollable_offset: RelativeOffset,
+    streaming_posters: Vec<SeriesPoster<'a>>,
+    local_posters: Vec<SeriesPoster<'a>>,
+    series_page_sender: mpsc::Sender<SeriesMainInformation>,
+}
+
+impl<'a> DateSchedule<'a> {
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            date: Local::now().date_naive(),
+            show_date_picker: false,
+            view: View::Posters,
+            load_state: LoadState::NotLoaded,
+            grid: vec![],
+            grid_scrollable_offset: RelativeOffset::START,
+            streaming_posters: vec![],
+            local_posters: vec![],
+            series_page_sender,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::PreviousDayPressed => self.set_date(self.date - Duration::days(1)),
+            Message::NextDayPressed => self.set_date(self.date + Duration::days(1)),
+            Message::TodayPressed => self.set_date(Local::now().date_naive()),
+            Message::PickDatePressed => {
+                self.show_date_picker = true;
+                Command::none()
+            }
+            Message::PickDateCanceled => {
+                self.show_date_picker = false;
+                Command::none()
+            }
+            Message::DatePicked(date) => {
+                self.show_date_picker = false;
+                self.set_date(date.into())
+            }
+            Message::SeriesLoaded(date, series_infos) => {
+                // Ignoring the series of a date or a view that was navigated away from
+                if date != self.date || self.view != View::Posters {
+                    return Command::none();
+                }
+
+                let Some((streaming_series_infos, local_series_infos)) = series_infos else {
+                    self.load_state = LoadState::Failed;
+                    return Command::none();
+                };
+
+                self.load_state = LoadState::Loaded;
+
+                let (streaming_posters, streaming_posters_commands) =
+                    self.generate_posters(streaming_series_infos);
+                let (local_posters, local_posters_commands) =
+                    self.generate_posters(local_series_infos);
+
+                self.streaming_posters = streaming_posters;
+                self.local_posters = local_posters;
+
+                Command::batch([
+                    Command::batch(streaming_posters_commands).map(Message::StreamingPosters),
+                    Command::batch(local_posters_commands).map(Message::LocalPosters),
+                ])
+            }
+            Message::GridLoaded(date, grid) => {
+                if date != self.date || self.view != View::PrimeTimeGrid {
+                    return Command::none();
+                }
+
+                match grid {
+                    Some(grid) => {
+                        self.grid = grid;
+                        self.load_state = LoadState::Loaded;
+                    }
+                    None => self.load_state = LoadState::Failed,
+                }
+                Command::none()
+            }
+            Message::ViewToggled => {
+                self.view = match self.view {
+                    View::Posters => View::PrimeTimeGrid,
+                    View::PrimeTimeGrid => View::Posters,
+                };
+
+                // Loading what the other view shows for the same day
+                if let LoadState::NotLoaded = self.load_state {
+                    Command::none()
+                } else {
+                    self.set_date(self.date)
+                }
+            }
+            Message::GridEntryPressed(channel_index, entry_index) => {
+                let series_info = self.grid[channel_index].entries[entry_index]
+                    .series_info
+                    .clone();
+                self.series_page_sender
+                    .send(series_info)
+                    .expect("failed to send series page info");
+                Command::none()
+            }
+            Message::GridScrolled(view_port) => {
+                self.grid_scrollable_offset = view_port.relative_offset();
+                Command::none()
+            }
+            Message::StreamingPosters(message) => self.streaming_posters[message.index()]
+                .update(message)
+                .map(Message::StreamingPosters),
+            Message::LocalPosters(message) => self.local_posters[message.index()]
+                .update(message)
+                .map(Message::LocalPosters),
+        }
+    }
+
+    fn set_date(&mut self, date: NaiveDate) -> Command<Message> {
+        self.date = date;
+        self.load_state = LoadState::Loading;
+        self.streaming_posters.clear();
+        self.local_posters.clear();
+        self.grid.clear();
+        self.grid_scrollable_offset = RelativeOffset::START;
+
+        let country_code = locale_settings::get_country_code_from_settings();
+
+        match self.view {
+            View::Posters => {
+                Command::perform(load_series(date, country_code), move |series_infos| {
+                    Message::SeriesLoaded(date, series_infos)
+                })
+            }
+            View::PrimeTimeGrid => Command::perform(load_grid(date, country_code), move |grid| {
+                Message::GridLoaded(date, grid)
+            }),
+        }
+    }
+
+    fn generate_posters(
+        &self,
+        series_infos: Vec<SeriesMainInformation>,
+    ) -> (
+        Vec<SeriesPoster<'a>>,
+        Vec<Command<IndexedMessage<usize, SeriesPosterMessage>>>,
+    ) {
+        series_infos
+            .into_iter()
+            .enumerate()
+            .map(|(index, series_info)| {
+                SeriesPoster::new(
+                    index,
+                    Cow::Owned(series_info),
+                    self.series_page_sender.clone(),
+                )
+            })
+            .unzip()
+    }
+
+    /// Scrolls the prime time grid back to where it was left
+    pub fn restore_scrollable_offset(&self) -> Command<Message> {
+        scrollable::snap_to(grid_scrollable_id(), self.grid_scrollable_offset)
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let date_button = DatePicker::new(
+            self.show_date_picker,
+            self.date,
+            button(text(date_formatting::long_date_str(self.date)))
+                .on_press(Message::PickDatePressed)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+            Message::PickDateCanceled,
+            Message::DatePicked,
+        );
+
+        let navigation = row![
+            text("Schedule").size(21),
+            button(text("Previous day")).on_press(Message::PreviousDayPressed),
+            date_button,
+            button(text("Next day")).on_press(Message::NextDayPressed),
+            button(text("Today")).on_press(Message::TodayPressed),
+            button(text(match self.view {
+                View::Posters => "Prime time grid",
+                View::PrimeTimeGrid => "Posters",
+            }))
+            .on_press(Message::ViewToggled)
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let body: Element<'_, Message, Renderer> = match self.load_state {
+            LoadState::NotLoaded => text("Pick a day to see the series airing on it")
+                .size(11)
+                .into(),
+            LoadState::Loading => container(Spinner::new())
+                .width(Length::Fill)
+                .center_x()
+                .into(),
+            LoadState::Failed => text("Failed to load the schedule of the day")
+                .style(styles::text_styles::red_text_theme())
+                .into(),
+            LoadState::Loaded if self.view == View::PrimeTimeGrid => grid_view(&self.grid),
+            LoadState::Loaded => column![
+                series_posters_viewer("Streaming", &self.streaming_posters)
+                    .map(Message::StreamingPosters),
+                series_posters_viewer(
+                    &format!(
+                        "On TV in {}",
+                        locale_settings::get_country_name_from_settings()
+                    ),
+                    &self.local_posters
+                )
+                .map(Message::LocalPosters),
+            ]
+            .spacing(10)
+            .into(),
+        };
+
+        column![
+            navigation,
+            suggested_viewing(self.date),
+            body,
+            Space::new(0, 10)
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+/// The episodes suggested by the binge plans for the date
+fn suggested_viewing(date: NaiveDate) -> Element<'static, Message, Renderer> {
+    let plans_of_day = binge_plan::get_plans_of_day(date);
+    if plans_of_day.is_empty() {
+        return Space::new(0, 0).into();
+    }
+
+    let suggestions = Column::with_children(
+        plans_of_day
+            .into_iter()
+            .map(|(binge_plan, day)| {
+                text(format!(
+                    "{} {}",
+                    binge_plan.series_name,
+                    day.episodes_range()
+                ))
+                .size(13)
+                .into()
+            })
+            .collect(),
+    )
+    .spacing(2);
+
+    container(
+        column![
+            text("Suggested viewing").style(styles::text_styles::accent_color_theme()),
+            suggestions
+        ]
+        .spacing(5),
+    )
+    .padding(5)
+    .style(styles::container_styles::first_class_container_rounded_theme())
+    .into()
+}
+
+/// Width of a time slot column of the prime time grid
+const SLOT_WIDTH: f32 = 110.0;
+/// Width of the channel names column of the prime time grid
+const CHANNEL_WIDTH: f32 = 130.0;
+
+fn grid_view(grid: &[GridChannel]) -> Element<'_, Message, Renderer> {
+    if grid.is_empty() {
+        return text("Nothing airs in prime time on this day").into();
+    }
+
+    let time_slots = Row::with_children(
+        (0..prime_time_grid::SLOTS_AMOUNT)
+            .map(|slot| {
+                text(date_formatting::naive_time_str(
+                    prime_time_grid::slot_start_time(slot),
+                ))
+                .size(11)
+                .width(SLOT_WIDTH)
+                .into()
+            })
+            .collect(),
+    );
+    let header = row![Space::with_width(CHANNEL_WIDTH), time_slots];
+
+    let channels = grid
+        .iter()
+        .enumerate()
+        .map(|(channel_index, channel)| grid_channel_row(channel_index, channel));
+
+    let grid =
+        Column::with_children(std::iter::once(header.into()).chain(channels).collect()).spacing(3);
+
+    column![
+        scrollable(grid)
+            .direction(styles::scrollable_styles::horizontal_direction())
+            .id(grid_scrollable_id())
+            .on_scroll(Message::GridScrolled),
+        text(format!(
+            "Air times are in {}",
+            locale_settings::get_country_name_from_settings()
+        ))
+        .size(11),
+    ]
+    .spacing(5)
+    .into()
+}
+
+fn grid_scrollable_id() -> Id {
+    Id::new("prime-time-grid-scrollable")
+}
+
+fn grid_channel_row(channel_index: usize, channel: &GridChannel) -> Element<'_, Message, Renderer> {
+    let mut channel_row = row![text(&channel.name).size(13).width(CHANNEL_WIDTH)];
+
+    let mut next_slot = 0;
+    for (entry_index, entry) in channel.entries.iter().enumerate() {
+        if entry.start_slot > next_slot {
+            channel_row = channel_row.push(Space::with_width(
+                (entry.start_slot - next_slot) as f32 * SLOT_WIDTH,
+            ));
+        }
+        channel_row = channel_row.push(grid_entry(channel_index, entry_index, entry));
+        next_slot = entry.start_slot + entry.slots;
+    }
+
+    channel_row.align_items(Alignment::Center).into()
+}
+
+fn grid_entry(
+    channel_index: usize,
+    entry_index: usize,
+    entry: &GridEntry,
+) -> Element<'_, Message, Renderer> {
+    let is_tracked = database::DB
+        .get_series(entry.series_info.id)
+        .is_some_and(|series| series.is_tracked());
+
+    let episode = entry
+        .number
+        .map(|number| helpers::season_episode_str_gen(entry.season, number))
+        .unwrap_or_default();
+
+    let content = column![
+        text(&entry.series_info.name).size(12),
+        text(format!("{} {}", episode, entry.episode_name)).size(10),
+    ];
+
+    let content = button(content)
+        .on_press(Message::GridEntryPressed(channel_index, entry_index))
+        .style(styles::button_styles::transparent_button_theme())
+        .width(Length::Fill);
+
+    container(content)
+        .style(if is_tracked {
+            styles::container_styles::release_time_container_theme()
+        } else {
+            styles::container_styles::second_class_container_square_theme()
+        })
+        .width(entry.slots as f32 * SLOT_WIDTH)
+        .height(50)
+        .padding(2)
+        .into()
+}
+
+async fn load_grid(date: NaiveDate, country_code: String) -> Option<Vec<GridChannel>> {
+    // TVmaze dates are in the format 2020-05-29
+    let date = date.format("%Y-%m-%d").to_string();
+
+    prime_time_grid::get_prime_time_grid(&country_code, &date)
+        .await
+        .map_err(|err| tracing::error!("failed to load the prime time grid of {}: {}", date, err))
+        .ok()
+}
+
+async fn load_series(
+    date: NaiveDate,
+    country_code: String,
+) -> Option<(Vec<SeriesMainInformation>, Vec<SeriesMainInformation>)> {
+    // TVmaze dates are in the format 2020-05-29
+    let date = date.format("%Y-%m-%d").to_string();
+
+    let series_infos = tokio::try_join!(
+        tv_schedule::get_series_with_date(Some(&date)),
+        tv_schedule::get_series_with_country(&country_code, Some(&date)),
+    );
+
+    series_infos
+        .map_err(|err| tracing::error!("failed to load the schedule of {}: {}", date, err))
+        .ok()
+}
@@ -2,22 +2,34 @@ use std::collections::HashMap;
 use std::ops::RangeInclusive;
 use std::sync::mpsc;
 
-use iced::widget::{column, container, text, vertical_space, Column};
+use iced::widget::{
+    button, column, container, horizontal_space, row, svg, text, vertical_space, Column, Space,
+};
 use iced::{Command, Element, Length, Renderer};
-use iced_aw::{Spinner, Wrap};
+use iced_aw::Wrap;
 
 use crate::core::api::tv_maze::series_information::{
     Genre, SeriesMainInformation, ShowNetwork, ShowWebChannel,
 };
 use crate::core::caching;
+use crate::core::caching::popularity_trends::{self, Trend};
 use crate::core::caching::tv_schedule::full_schedule::FullSchedule;
+use crate::core::followed_people;
+use crate::core::recommendations::{self, UserProfile};
 use crate::core::settings_config::locale_settings;
+use crate::core::{content_filter, random_picker};
+use crate::gui::assets::icons::{CHEVRON_DOWN, CHEVRON_UP, X_LG};
 use crate::gui::troxide_widget::series_poster::{
     IndexedMessage, Message as SeriesPosterMessage, SeriesPoster,
 };
+use crate::gui::{helpers, styles, toast};
 
 const SECTIONS_POSTERS_AMOUNT: usize = 20;
 const DAILY_POSTERS_AMOUNT: usize = 80;
+const RECOMMENDED_POSTERS_AMOUNT: usize = 20;
+const BECAUSE_YOU_WATCHED_ROWS_AMOUNT: usize = 3;
+const SKELETON_SECTIONS_AMOUNT: usize = 3;
+const SKELETON_POSTERS_AMOUNT: usize = 10;
 
 const NETWORK_SECTIONS: [ShowNetwork; 7] = [
     ShowNetwork::TheCW,
@@ -45,17 +57,29 @@ const GENRE_SECTIONS: [Genre; 8] = [
 #[derive(Debug, Clone)]
 pub enum Message {
     FullScheduleLoaded(&'static caching::tv_schedule::full_schedule::FullSchedule),
+    UserProfileLoaded(Option<UserProfile>),
+    RecommendedPosters(IndexedMessage<usize, SeriesPosterMessage>),
+    AnchorSeriesLoaded(Vec<SeriesMainInformation>),
+    BecauseYouWatchedPosters(IndexedMessage<usize, IndexedMessage<usize, SeriesPosterMessage>>),
+    DismissBecauseYouWatched(u32),
+    BecauseYouWatchedDismissed(u32),
+    FollowedPeopleSeriesLoaded(Vec<SeriesMainInformation>),
+    FollowedPeoplePosters(IndexedMessage<usize, SeriesPosterMessage>),
     MonthlyNewPosters(IndexedMessage<usize, SeriesPosterMessage>),
     MonthlyReturningPosters(IndexedMessage<usize, SeriesPosterMessage>),
     GlobalSeries(IndexedMessage<usize, SeriesPosterMessage>),
     LocalSeries(IndexedMessage<usize, SeriesPosterMessage>),
     PopularPosters(IndexedMessage<usize, SeriesPosterMessage>),
+    PopularTrendsLoaded(HashMap<u32, Trend>),
     NetworkPosters(IndexedMessage<usize, SeriesPosterMessage>),
     WebChannelPosters(IndexedMessage<usize, SeriesPosterMessage>),
     GenrePosters(IndexedMessage<usize, SeriesPosterMessage>),
+    SurpriseMePressed,
 }
 
 enum LoadState {
+    /// The full schedule is only downloaded once discover is first shown
+    NotLoaded,
     Loading,
     Loaded,
 }
@@ -68,6 +92,12 @@ pub struct FullSchedulePosters<'a> {
     daily_global_series: Vec<SeriesPoster<'a>>,
     daily_local_series: Vec<SeriesPoster<'a>>,
     popular_posters: Vec<SeriesPoster<'a>>,
+    /// How the popular series moved since a week ago, keyed by series id
+    popular_trends: HashMap<u32, Trend>,
+    recommended_posters: Vec<SeriesPoster<'a>>,
+    because_you_watched_rows: Vec<BecauseYouWatchedRow<'a>>,
+    followed_people_posters: Vec<SeriesPoster<'a>>,
+    user_profile: Option<UserProfile>,
     network_posters: Posters<'a, ShowNetwork>,
     web_channel_posters: Posters<'a, ShowWebChannel>,
     genre_posters: Posters<'a, Genre>,
@@ -76,26 +106,37 @@ pub struct FullSchedulePosters<'a> {
 }
 
 impl<'a> FullSchedulePosters<'a> {
-    pub fn new(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-    ) -> (Self, Command<Message>) {
-        (
-            Self {
-                load_state: LoadState::Loading,
-                full_schedule: None,
-                monthly_new_poster: vec![],
-                monthly_returning_posters: vec![],
-                daily_global_series: vec![],
-                daily_local_series: vec![],
-                popular_posters: vec![],
-                network_posters: Posters::new(series_page_sender.clone()),
-                web_channel_posters: Posters::new(series_page_sender.clone()),
-                genre_posters: Posters::new(series_page_sender.clone()),
-                country_name: locale_settings::get_country_name_from_settings(),
-                series_page_sender,
-            },
-            Self::load_full_schedule(),
-        )
+    /// Creates the posters without loading them, see `load`
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            load_state: LoadState::NotLoaded,
+            full_schedule: None,
+            monthly_new_poster: vec![],
+            monthly_returning_posters: vec![],
+            daily_global_series: vec![],
+            daily_local_series: vec![],
+            popular_posters: vec![],
+            popular_trends: HashMap::new(),
+            recommended_posters: vec![],
+            because_you_watched_rows: vec![],
+            followed_people_posters: vec![],
+            user_profile: None,
+            network_posters: Posters::new(series_page_sender.clone()),
+            web_channel_posters: Posters::new(series_page_sender.clone()),
+            genre_posters: Posters::new(series_page_sender.clone()),
+            country_name: locale_settings::get_country_name_from_settings(),
+            series_page_sender,
+        }
+    }
+
+    /// Loads the full schedule the first time it is called
+    pub fn load(&mut self) -> Command<Message> {
+        if let LoadState::NotLoaded = self.load_state {
+            self.load_state = LoadState::Loading;
+            Self::load_full_schedule()
+        } else {
+            Command::none()
+        }
     }
 
     pub fn reload(&mut self) -> Command<Message> {
@@ -153,9 +194,16 @@ impl<'a> FullSchedulePosters<'a> {
                         self.series_page_sender.clone(),
                     );
 
+                let popular_series =
+                    full_schedule.get_popular_series(Some(SECTIONS_POSTERS_AMOUNT));
+                let popular_series_ratings: Vec<(u32, Option<f32>)> = popular_series
+                    .iter()
+                    .map(|series_info| (series_info.id, series_info.rating.average))
+                    .collect();
+
                 let (popular_posters, popular_posters_commands) =
                     Self::generate_posters_and_commands_from_series_infos(
-                        full_schedule.get_popular_series(Some(SECTIONS_POSTERS_AMOUNT)),
+                        popular_series,
                         self.series_page_sender.clone(),
                     );
 
@@ -223,10 +271,19 @@ impl<'a> FullSchedulePosters<'a> {
                     .collect();
 
                 Command::batch([
+                    Command::perform(load_user_profile(), Message::UserProfileLoaded),
+                    Command::perform(
+                        load_followed_people_series(),
+                        Message::FollowedPeopleSeriesLoaded,
+                    ),
                     Command::batch(genre_posters_commands),
                     Command::batch(webchannel_posters_commands),
                     Command::batch(network_posters_commands),
                     Command::batch(popular_posters_commands).map(Message::PopularPosters),
+                    Command::perform(
+                        popularity_trends::record_and_get_trends(popular_series_ratings),
+                        Message::PopularTrendsLoaded,
+                    ),
                     Command::batch(monthly_returning_posters_commands)
                         .map(Message::MonthlyReturningPosters),
                     Command::batch(monthly_new_posters_commands).map(Message::MonthlyNewPosters),
@@ -234,12 +291,129 @@ impl<'a> FullSchedulePosters<'a> {
                     Command::batch(daily_local_posters_commands).map(Message::LocalSeries),
                 ])
             }
+            Message::UserProfileLoaded(user_profile) => {
+                let (Some(full_schedule), Some(user_profile)) = (self.full_schedule, user_profile)
+                else {
+                    return Command::none();
+                };
+
+                let (recommended_posters, recommended_posters_commands) =
+                    Self::generate_posters_and_commands_from_series_infos(
+                        recommendations::get_recommended_series(
+                            full_schedule,
+                            &user_profile,
+                            RECOMMENDED_POSTERS_AMOUNT,
+                        ),
+                        self.series_page_sender.clone(),
+                    );
+
+                self.recommended_posters = recommended_posters;
+                self.user_profile = Some(user_profile);
+
+                Command::batch([
+                    Command::batch(recommended_posters_commands).map(Message::RecommendedPosters),
+                    Command::perform(load_anchor_series(), Message::AnchorSeriesLoaded),
+                ])
+            }
+            Message::AnchorSeriesLoaded(anchor_series_infos) => {
+                let (Some(full_schedule), Some(user_profile)) =
+                    (self.full_schedule, self.user_profile.as_ref())
+                else {
+                    return Command::none();
+                };
+
+                let mut rows = Vec::with_capacity(anchor_series_infos.len());
+                let mut rows_commands = Vec::with_capacity(anchor_series_infos.len());
+
+                for (row_index, anchor_series_info) in anchor_series_infos.into_iter().enumerate() {
+                    let (posters, posters_commands) =
+                        Self::generate_posters_and_commands_from_series_infos(
+                            recommendations::get_similar_series(
+                                full_schedule,
+                                &anchor_series_info,
+                                user_profile,
+                                SECTIONS_POSTERS_AMOUNT,
+                            ),
+                            self.series_page_sender.clone(),
+                        );
+
+                    rows.push(BecauseYouWatchedRow {
+                        anchor_series_info,
+                        posters,
+                    });
+                    rows_commands.push(
+                        Command::batch(posters_commands)
+                            .map(move |message| IndexedMessage::new(row_index, message)),
+                    );
+                }
+
+                self.because_you_watched_rows = rows;
+                Command::batch(rows_commands).map(Message::BecauseYouWatchedPosters)
+            }
+            Message::BecauseYouWatchedPosters(message) => {
+                let row_index = message.index();
+                let poster_message = message.message();
+                self.because_you_watched_rows[row_index].posters[poster_message.index()]
+                    .update(poster_message)
+                    .map(move |message| IndexedMessage::new(row_index, message))
+                    .map(Message::BecauseYouWatchedPosters)
+            }
+            Message::DismissBecauseYouWatched(series_id) => Command::perform(
+                async move {
+                    recommendations::dismiss_anchor(series_id)
+                        .await
+                        .unwrap_or_else(|err| {
+                            tracing::error!("failed to dismiss recommendation row: {}", err)
+                        })
+                },
+                move |_| Message::BecauseYouWatchedDismissed(series_id),
+            ),
+            Message::BecauseYouWatchedDismissed(series_id) => {
+                // Rows are rebuilt to keep the poster indices in sync with their rows
+                let anchor_series_infos = self
+                    .because_you_watched_rows
+                    .drain(..)
+                    .map(|row| row.anchor_series_info)
+                    .filter(|anchor_series_info| anchor_series_info.id != series_id)
+                    .collect();
+                self.update(Message::AnchorSeriesLoaded(anchor_series_infos))
+            }
+            Message::FollowedPeopleSeriesLoaded(series_infos) => {
+                let mut posters = Vec::with_capacity(series_infos.len());
+                let mut posters_commands = Vec::with_capacity(series_infos.len());
+                for (index, series_info) in
+                    content_filter::filter_series(series_infos, |series_info| series_info)
+                        .into_iter()
+                        .enumerate()
+                {
+                    let (poster, command) = SeriesPoster::new(
+                        index,
+                        std::borrow::Cow::Owned(series_info),
+                        self.series_page_sender.clone(),
+                    );
+                    posters.push(poster);
+                    posters_commands.push(command);
+                }
+                self.followed_people_posters = posters;
+                Command::batch(posters_commands).map(Message::FollowedPeoplePosters)
+            }
+            Message::FollowedPeoplePosters(message) => self.followed_people_posters
+                [message.index()]
+            .update(message)
+            .map(Message::FollowedPeoplePosters),
+            Message::RecommendedPosters(message) => self.recommended_posters[message.index()]
+                .update(message)
+                .map(Message::RecommendedPosters),
             Message::MonthlyNewPosters(message) => self.monthly_new_poster[message.index()]
                 .update(message)
                 .map(Message::MonthlyNewPosters),
             Message::PopularPosters(message) => self.popular_posters[message.index()]
                 .update(message)
                 .map(Message::PopularPosters),
+            Message::PopularTrendsLoaded(popular_trends) => {
+                self.popular_trends = popular_trends;
+                Command::none()
+            }
             Message::MonthlyReturningPosters(message) => self.monthly_returning_posters
                 [message.index()]
             .update(message)
@@ -265,17 +439,34 @@ impl<'a> FullSchedulePosters<'a> {
             Message::LocalSeries(message) => self.daily_local_series[message.index()]
                 .update(message)
                 .map(Message::LocalSeries),
+            Message::SurpriseMePressed => {
+                match self
+                    .full_schedule
+                    .and_then(random_picker::pick_random_popular_series)
+                {
+                    Some(series_info) => {
+                        toast::push(format!("Surprise! Check out {}", series_info.name));
+                        self.series_page_sender
+                            .send(series_info)
+                            .expect("failed to send series page info");
+                    }
+                    None => toast::push("No popular shows left to pick from"),
+                }
+                Command::none()
+            }
         }
     }
 
     pub fn view(&self) -> Element<'_, Message, Renderer> {
         match self.load_state {
-            LoadState::Loading => container(Spinner::new())
-                .width(Length::Fill)
-                .height(500)
-                .center_x()
-                .center_y()
-                .into(),
+            LoadState::NotLoaded | LoadState::Loading => Column::with_children(
+                (0..SKELETON_SECTIONS_AMOUNT)
+                    .map(|_| helpers::skeleton::series_posters_section(SKELETON_POSTERS_AMOUNT))
+                    .collect(),
+            )
+            .spacing(30)
+            .padding(10)
+            .into(),
             LoadState::Loaded => {
                 let network_sections = Column::with_children(
                     NETWORK_SECTIONS
@@ -310,7 +501,43 @@ impl<'a> FullSchedulePosters<'a> {
                 )
                 .spacing(30);
 
+                let recommended_section: Element<'_, Message, Renderer> =
+                    if self.recommended_posters.is_empty() {
+                        Space::new(0, 0).into()
+                    } else {
+                        series_posters_viewer("Recommended for You", &self.recommended_posters)
+                            .map(Message::RecommendedPosters)
+                    };
+
+                let followed_people_section: Element<'_, Message, Renderer> = if self
+                    .followed_people_posters
+                    .is_empty()
+                {
+                    Space::new(0, 0).into()
+                } else {
+                    series_posters_viewer("From People You Follow", &self.followed_people_posters)
+                        .map(Message::FollowedPeoplePosters)
+                };
+
+                let because_you_watched_sections = Column::with_children(
+                    self.because_you_watched_rows
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, row)| !row.posters.is_empty())
+                        .map(|(row_index, row)| row.view(row_index))
+                        .collect(),
+                )
+                .spacing(30);
+
+                let surprise_me_button = button(text("Surprise me"))
+                    .on_press(Message::SurpriseMePressed)
+                    .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+
                 column![
+                    surprise_me_button,
+                    recommended_section,
+                    because_you_watched_sections,
+                    followed_people_section,
                     series_posters_viewer("Shows Airing Today Globally", &self.daily_global_series)
                         .map(Message::GlobalSeries),
                     series_posters_viewer(
@@ -318,8 +545,7 @@ impl<'a> FullSchedulePosters<'a> {
                         &self.daily_local_series
                     )
                     .map(Message::LocalSeries),
-                    series_posters_viewer("Popular Shows", &self.popular_posters)
-                        .map(Message::PopularPosters),
+                    self.popular_posters_viewer(),
                     series_posters_viewer(
                         &format!("New Shows Airing in {}", get_current_month().name()),
                         &self.monthly_new_poster,
@@ -341,6 +567,41 @@ impl<'a> FullSchedulePosters<'a> {
         }
     }
 
+    /// The popular posters with how each series moved since a week ago below it
+    fn popular_posters_viewer(&self) -> Element<'_, Message, Renderer> {
+        if self.popular_trends.is_empty() {
+            return series_posters_viewer("Popular Shows", &self.popular_posters)
+                .map(Message::PopularPosters);
+        }
+
+        let wrapped_posters = Wrap::with_elements(
+            self.popular_posters
+                .iter()
+                .filter(|poster| !poster.is_hidden())
+                .map(|poster| {
+                    let trend = self.popular_trends.get(&poster.get_series_info().id);
+                    column![
+                        poster.view(true).map(Message::PopularPosters),
+                        trend_widget(trend)
+                    ]
+                    .align_items(iced::Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5.0)
+        .line_spacing(5.0);
+
+        column![
+            text("Popular Shows").size(21),
+            text("Compared with a week ago").size(11),
+            wrapped_posters
+        ]
+        .spacing(5)
+        .width(Length::Fill)
+        .into()
+    }
+
     fn load_full_schedule() -> Command<Message> {
         Command::perform(
             caching::tv_schedule::full_schedule::FullSchedule::new(),
@@ -370,6 +631,28 @@ impl<'a> FullSchedulePosters<'a> {
     }
 }
 
+async fn load_user_profile() -> Option<UserProfile> {
+    UserProfile::new()
+        .await
+        .map_err(|err| tracing::error!("failed to load user profile for recommendations: {}", err))
+        .ok()
+}
+
+/// Checks the followed people for new series before loading them
+async fn load_followed_people_series() -> Vec<SeriesMainInformation> {
+    followed_people::refresh_followed_people().await;
+    followed_people::get_followed_people_series(SECTIONS_POSTERS_AMOUNT).await
+}
+
+async fn load_anchor_series() -> Vec<SeriesMainInformation> {
+    recommendations::get_anchor_series(BECAUSE_YOU_WATCHED_ROWS_AMOUNT)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::error!("failed to load 'because you watched' series: {}", err);
+            vec![]
+        })
+}
+
 fn get_current_month() -> chrono::Month {
     use chrono::{Datelike, Local, Month};
     use num_traits::FromPrimitive;
@@ -378,6 +661,61 @@ fn get_current_month() -> chrono::Month {
     Month::from_u32(current_month).expect("current month should be valid!")
 }
 
+/// Shows whether the series climbed or fell in rank and how it's rating changed
+fn trend_widget(trend: Option<&Trend>) -> Element<'static, Message, Renderer> {
+    let Some(trend) = trend else {
+        return Space::new(0, 0).into();
+    };
+
+    let (rank_change, rating_change) = match *trend {
+        Trend::New => {
+            return text("New")
+                .size(11)
+                .style(styles::text_styles::accent_color_theme())
+                .into()
+        }
+        Trend::Moved {
+            rank_change,
+            rating_change,
+        } => (rank_change, rating_change),
+    };
+
+    let mut content = row![].spacing(3).align_items(iced::Alignment::Center);
+
+    if rank_change != 0 {
+        let (icon, text_style) = if rank_change > 0 {
+            (CHEVRON_UP, styles::text_styles::green_text_theme())
+        } else {
+            (CHEVRON_DOWN, styles::text_styles::red_text_theme())
+        };
+
+        content = content
+            .push(
+                svg(svg::Handle::from_memory(icon))
+                    .width(10)
+                    .height(10)
+                    .style(styles::svg_styles::colored_svg_theme()),
+            )
+            .push(text(rank_change.abs()).size(11).style(text_style));
+    }
+
+    // Changes that would show as 0.0 are left out
+    if let Some(rating_change) = rating_change.filter(|change| change.abs() >= 0.05) {
+        let text_style = if rating_change > 0.0 {
+            styles::text_styles::green_text_theme()
+        } else {
+            styles::text_styles::red_text_theme()
+        };
+        content = content.push(
+            text(format!("{:+.1} rating", rating_change))
+                .size(11)
+                .style(text_style),
+        );
+    }
+
+    content.into()
+}
+
 /// Show `No Series Found` information in a discover section
 fn no_series_found() -> Element<'static, Message, Renderer> {
     container(text("No Series Found"))
@@ -388,7 +726,7 @@ fn no_series_found() -> Element<'static, Message, Renderer> {
         .into()
 }
 
-fn series_posters_viewer<'a>(
+pub fn series_posters_viewer<'a>(
     title: &str,
     posters: &'a [SeriesPoster],
 ) -> Element<'a, IndexedMessage<usize, SeriesPosterMessage>, Renderer> {
@@ -422,6 +760,57 @@ fn series_posters_viewer<'a>(
     }
 }
 
+struct BecauseYouWatchedRow<'a> {
+    anchor_series_info: SeriesMainInformation,
+    posters: Vec<SeriesPoster<'a>>,
+}
+
+impl<'a> BecauseYouWatchedRow<'a> {
+    fn view(&self, row_index: usize) -> Element<'_, Message, Renderer> {
+        let dismiss_icon = svg(svg::Handle::from_memory(X_LG))
+            .width(15)
+            .height(15)
+            .style(styles::svg_styles::colored_svg_theme());
+
+        let dismiss_button = button(dismiss_icon)
+            .style(styles::button_styles::transparent_button_theme())
+            .on_press(Message::DismissBecauseYouWatched(
+                self.anchor_series_info.id,
+            ));
+
+        let title = row![
+            text(format!(
+                "Because you watched {}",
+                self.anchor_series_info.name
+            ))
+            .size(21),
+            horizontal_space(Length::Fill),
+            dismiss_button
+        ]
+        .align_items(iced::Alignment::Center);
+
+        let posters: Element<'_, Message, Renderer> = Wrap::with_elements(
+            self.posters
+                .iter()
+                .filter(|poster| !poster.is_hidden())
+                .map(|poster| {
+                    poster.view(true).map(move |message| {
+                        Message::BecauseYouWatchedPosters(IndexedMessage::new(row_index, message))
+                    })
+                })
+                .collect(),
+        )
+        .spacing(5.0)
+        .line_spacing(5.0)
+        .into();
+
+        column![title, posters]
+            .spacing(5)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
 struct Posters<'a, T> {
     index: HashMap<T, RangeInclusive<usize>>,
     posters: Vec<SeriesPoster<'a>>,
@@ -483,7 +872,7 @@ where
         let mut posters = Vec::with_capacity(series_infos.len());
         let mut posters_commands = Vec::with_capacity(series_infos.len());
 
-        for (index, series_info) in range.clone().zip(series_infos.into_iter()) {
+        for (index, series_info) in range.clone().zip(series_infos) {
             let (poster, command) = SeriesPoster::new(
                 index,
                 std::borrow::Cow::Borrowed(series_info),
@@ -495,7 +884,7 @@ where
         (posters, posters_commands)
     }
 
-    fn get_section(&self, section_id: &T) -> &[SeriesPoster] {
+    fn get_section(&self, section_id: &T) -> &[SeriesPoster<'_>] {
         let range = self
             .index
             .get(section_id)
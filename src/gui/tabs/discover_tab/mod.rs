@@ -3,17 +3,19 @@ use std::sync::mpsc;
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 use crate::gui::assets::icons::BINOCULARS_FILL;
 use crate::gui::styles;
+use date_schedule::{DateSchedule, Message as DateScheduleMessage};
 use full_schedule::{FullSchedulePosters, Message as FullSchedulePostersMessage};
 use searching::Message as SearchMessage;
 
 use iced::widget::scrollable::{RelativeOffset, Viewport};
-use iced::widget::{column, scrollable, Space};
+use iced::widget::{column, container, scrollable, Space};
 use iced::{Command, Element, Length, Renderer};
 
 use iced_aw::floating_element;
 
 use super::Tab;
 
+mod date_schedule;
 mod full_schedule;
 mod searching;
 
@@ -21,6 +23,7 @@ mod searching;
 pub enum Message {
     Reload,
     FullSchedulePosters(FullSchedulePostersMessage),
+    DateSchedule(DateScheduleMessage),
     Search(SearchMessage),
     PageScrolled(Viewport),
 }
@@ -28,30 +31,43 @@ pub enum Message {
 pub struct DiscoverTab<'a> {
     search: searching::Search,
     full_schedule_series: FullSchedulePosters<'a>,
+    date_schedule: DateSchedule<'a>,
     scrollable_offset: RelativeOffset,
 }
 
 impl<'a> DiscoverTab<'a> {
-    pub fn new(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-    ) -> (Self, Command<Message>) {
-        let (full_schedule_series, full_schedule_command) =
-            FullSchedulePosters::new(series_page_sender.clone());
-
-        (
-            Self {
-                search: searching::Search::new(series_page_sender),
-                full_schedule_series,
-                scrollable_offset: RelativeOffset::START,
-            },
-            full_schedule_command.map(Message::FullSchedulePosters),
-        )
+    /// Creates the tab without loading anything, the schedule loads once the tab is shown
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            search: searching::Search::new(series_page_sender.clone()),
+            full_schedule_series: FullSchedulePosters::new(series_page_sender.clone()),
+            date_schedule: DateSchedule::new(series_page_sender),
+            scrollable_offset: RelativeOffset::START,
+        }
     }
 
+    /// Loads the full schedule on the first call and refreshes the local series after that
     pub fn refresh(&mut self) -> Command<Message> {
-        self.full_schedule_series
-            .refresh_daily_local_series()
-            .map(Message::FullSchedulePosters)
+        Command::batch([
+            self.full_schedule_series.load(),
+            self.full_schedule_series.refresh_daily_local_series(),
+        ])
+        .map(Message::FullSchedulePosters)
+    }
+
+    /// Updates what the tab shows from the database, like the progress in the search results
+    pub fn database_changed(&self) -> Command<Message> {
+        self.search.reload_progress().map(Message::Search)
+    }
+
+    /// Scrolls the tab and it's prime time grid back to where they were left
+    pub fn restore_scrollable_offsets(&self) -> Command<Message> {
+        Command::batch([
+            Self::set_scrollable_offset(self.scrollable_offset),
+            self.date_schedule
+                .restore_scrollable_offset()
+                .map(Message::DateSchedule),
+        ])
     }
 
     pub fn subscription(&self) -> iced::Subscription<Message> {
@@ -83,6 +99,10 @@ impl<'a> DiscoverTab<'a> {
                 .full_schedule_series
                 .update(message)
                 .map(Message::FullSchedulePosters),
+            Message::DateSchedule(message) => self
+                .date_schedule
+                .update(message)
+                .map(Message::DateSchedule),
             Message::PageScrolled(view_port) => {
                 self.scrollable_offset = view_port.relative_offset();
                 Command::none()
@@ -91,11 +111,12 @@ impl<'a> DiscoverTab<'a> {
     }
 
     pub fn view(&self) -> Element<'_, Message, Renderer> {
-        let underlay: Element<'_, Message, Renderer> = scrollable(
+        let underlay: Element<'_, Message, Renderer> = scrollable(column![
+            container(self.date_schedule.view().map(Message::DateSchedule)).padding(10),
             self.full_schedule_series
                 .view()
                 .map(Message::FullSchedulePosters),
-        )
+        ])
         .direction(styles::scrollable_styles::vertical_direction())
         .id(Self::scrollable_id())
         .on_scroll(Message::PageScrolled)
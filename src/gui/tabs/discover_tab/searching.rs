@@ -1,18 +1,35 @@
 use std::sync::mpsc;
 
-use iced::widget::{column, container, scrollable, text, text_input, vertical_space, Column};
+use iced::widget::{
+    button, column, container, pick_list, row, scrollable, text, text_input, vertical_space, Column,
+};
 use iced::{Command, Element, Length, Renderer};
 use iced_aw::Spinner;
 use search_result::{IndexedMessage, Message as SearchResultMessage, SearchResult};
 
+use crate::core::advanced_search::{self, SearchFilter};
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
-use crate::core::api::tv_maze::series_searching;
-use crate::gui::styles;
+use crate::core::api::tv_maze::{series_searching, ApiError};
+use crate::core::caching::episode_list::{EpisodeList, EpisodeNumber};
+use crate::core::caching::series_information::get_series_main_info_with_id;
+use crate::core::caching::tv_schedule::full_schedule::FullSchedule;
+use crate::core::content_filter;
+use crate::core::database::{EpisodeNote, SmartList, SmartListSource, DB};
+use crate::gui::helpers::season_episode_str_gen;
+use crate::gui::{styles, toast};
+
+/// How many characters the term needs before the full schedule is searched while typing
+const MIN_SCHEDULE_SEARCH_LENGTH: usize = 2;
+
+/// How many series of the full schedule are shown while typing
+const SCHEDULE_RESULTS_AMOUNT: usize = 10;
 
 #[derive(Default)]
 pub enum LoadState {
     Loaded,
     Loading,
+    /// The search failed with the given user facing reason
+    Failed(&'static str),
     #[default]
     NotLoaded,
 }
@@ -22,16 +39,54 @@ pub enum Message {
     TermChanged(String),
     TermSearched,
     SearchSuccess(Vec<series_searching::SeriesSearchResult>),
-    SearchFail,
+    /// The term with the series of the full schedule matching it
+    ScheduleSearchSuccess(String, Vec<SeriesMainInformation>),
+    /// The term with the TVmaze search results, merged into the ones of the full schedule
+    RemoteSearchSuccess(String, Vec<series_searching::SeriesSearchResult>),
+    SearchFail(&'static str),
     SearchResult(IndexedMessage<usize, SearchResultMessage>),
     EscapeKeyPressed,
+    /// The term with the episode its episode number refers to
+    EpisodeSearchSuccess(String, Option<EpisodeResult>),
+    NoteResultPressed(u32),
+    EpisodeResultPressed(u32),
+    NoteSeriesLoaded(Option<Box<SeriesMainInformation>>),
+    AdvancedToggled,
+    MinYearChanged(String),
+    MaxYearChanged(String),
+    GenreSelected(&'static str),
+    StatusSelected(&'static str),
+    MinRatingSelected(u8),
+    MaxRuntimeSelected(u32),
+    FiltersCleared,
+    SmartListNameChanged(String),
+    SmartListSourceSelected(SmartListSource),
+    SmartListSaved,
 }
 
 pub struct Search {
     search_term: String,
     search_results: Vec<SearchResult>,
+    /// The term the search results are of
+    results_term: Option<String>,
+    /// Whether the TVmaze search results are merged in, the full schedule results coming
+    /// late being of no use then
+    is_remote_merged: bool,
+    /// Episode notes matching the search term together with the names of their series
+    note_results: Vec<(EpisodeNote, String)>,
+    /// The episode a term like `Show 145` or `Show S07E05` refers to
+    episode_result: Option<EpisodeResult>,
     series_page_sender: mpsc::Sender<SeriesMainInformation>,
     pub load_state: LoadState,
+    /// Whether the advanced search filters are shown and applied
+    advanced_shown: bool,
+    filter: SearchFilter,
+    /// The years as typed, only applied to the filter when they are numbers
+    min_year_input: String,
+    max_year_input: String,
+    /// The name the current search is saved under as a smart list
+    smart_list_name: String,
+    smart_list_source: SmartListSource,
 }
 
 impl Search {
@@ -39,8 +94,18 @@ impl Search {
         Self {
             search_term: String::new(),
             search_results: vec![],
+            results_term: None,
+            is_remote_merged: false,
+            note_results: vec![],
+            episode_result: None,
             load_state: LoadState::NotLoaded,
             series_page_sender,
+            advanced_shown: false,
+            filter: SearchFilter::default(),
+            min_year_input: String::new(),
+            max_year_input: String::new(),
+            smart_list_name: String::new(),
+            smart_list_source: SmartListSource::default(),
         }
     }
 
@@ -63,69 +128,291 @@ impl Search {
         match message {
             Message::TermChanged(term) => {
                 self.search_term = term;
-                self.load_state = LoadState::NotLoaded;
+                self.is_remote_merged = false;
+                self.episode_result = None;
+
+                // The full schedule is searched as the term is typed, keeping the results
+                // of the previous term shown meanwhile
+                if self.advanced_shown
+                    || self.search_term.trim().chars().count() < MIN_SCHEDULE_SEARCH_LENGTH
+                {
+                    self.load_state = LoadState::NotLoaded;
+                    return Command::none();
+                }
+
+                let term = self.search_term.clone();
+                return Command::perform(search_full_schedule(term.clone()), move |series_infos| {
+                    Message::ScheduleSearchSuccess(term, series_infos)
+                });
+            }
+            Message::ScheduleSearchSuccess(term, series_infos) => {
+                if term != self.search_term || self.is_remote_merged || self.advanced_shown {
+                    return Command::none();
+                }
+
+                self.note_results = search_notes(&term);
+                self.search_results.clear();
+                self.results_term = Some(term);
+
+                if series_infos.is_empty() && self.note_results.is_empty() {
+                    self.load_state = LoadState::NotLoaded;
+                    return Command::none();
+                }
+
+                self.load_state = LoadState::Loaded;
+                return self.push_search_results(
+                    series_infos
+                        .into_iter()
+                        .map(|show| series_searching::SeriesSearchResult { show })
+                        .collect(),
+                );
             }
             Message::TermSearched => {
-                self.load_state = LoadState::Loading;
+                self.note_results = search_notes(&self.search_term);
+                self.episode_result = None;
+
+                if self.advanced_shown && !self.filter.is_empty() {
+                    self.load_state = LoadState::Loading;
+                    self.search_results.clear();
+                    self.results_term = None;
+                    return Command::perform(
+                        advanced_search::search(self.search_term.clone(), self.filter.clone()),
+                        |res| match res {
+                            Ok(series_infos) => Message::SearchSuccess(
+                                series_infos
+                                    .into_iter()
+                                    .map(|show| series_searching::SeriesSearchResult { show })
+                                    .collect(),
+                            ),
+                            Err(err) => {
+                                tracing::error!("failed to search series: {}", err);
+                                Message::SearchFail(
+                                    err.downcast_ref::<ApiError>()
+                                        .map(ApiError::user_message)
+                                        .unwrap_or("Failed to search the series schedule"),
+                                )
+                            }
+                        },
+                    );
+                }
 
-                let series_result = series_searching::search_series(self.search_term.clone());
+                // The results of the full schedule stay shown until the TVmaze ones arrive
+                if self.results_term.as_ref() != Some(&self.search_term) {
+                    self.search_results.clear();
+                    self.results_term = None;
+                }
+                if self.search_results.is_empty() {
+                    self.load_state = LoadState::Loading;
+                }
 
-                return Command::perform(series_result, |res| match res {
-                    Ok(res) => Message::SearchSuccess(res),
-                    Err(_) => Message::SearchFail,
+                let term = self.search_term.clone();
+                let episode_search_command =
+                    Command::perform(search_episode(term.clone()), move |episode_result| {
+                        Message::EpisodeSearchSuccess(term, episode_result)
+                    });
+
+                let term = self.search_term.clone();
+                let series_result = series_searching::search_series(term.clone());
+
+                let series_search_command = Command::perform(series_result, move |res| match res {
+                    Ok(res) => Message::RemoteSearchSuccess(
+                        term,
+                        content_filter::filter_series(res, |result| &result.show),
+                    ),
+                    Err(err) => {
+                        tracing::error!("failed to search series: {}", err);
+                        Message::SearchFail(err.user_message())
+                    }
                 });
+                return Command::batch([series_search_command, episode_search_command]);
+            }
+            Message::EpisodeSearchSuccess(term, episode_result) => {
+                if term == self.search_term {
+                    self.episode_result = episode_result;
+                    if self.episode_result.is_some() {
+                        self.load_state = LoadState::Loaded;
+                    }
+                }
             }
             Message::SearchSuccess(results) => {
                 self.load_state = LoadState::Loaded;
+                self.search_results.clear();
+                return self.push_search_results(results);
+            }
+            Message::RemoteSearchSuccess(term, results) => {
+                if term != self.search_term {
+                    return Command::none();
+                }
 
-                let mut search_results = Vec::with_capacity(results.len());
-                let mut search_results_commands = Vec::with_capacity(results.len());
-                results.into_iter().enumerate().for_each(|(index, result)| {
-                    let (search_result, search_result_command) =
-                        SearchResult::new(index, result, self.series_page_sender.clone());
-                    search_results.push(search_result);
-                    search_results_commands.push(search_result_command.map(Message::SearchResult));
-                });
-
-                self.search_results = search_results;
-
-                return Command::batch(search_results_commands);
+                self.load_state = LoadState::Loaded;
+                self.is_remote_merged = true;
+                self.results_term = Some(term);
+
+                let new_results = results
+                    .into_iter()
+                    .filter(|result| {
+                        !self
+                            .search_results
+                            .iter()
+                            .any(|search_result| search_result.series_id() == result.show.id)
+                    })
+                    .collect();
+                return self.push_search_results(new_results);
+            }
+            Message::SearchFail(reason) => {
+                // The results of the full schedule are still of use
+                if self.search_results.is_empty() {
+                    self.load_state = LoadState::Failed(reason);
+                } else {
+                    toast::push(reason);
+                }
             }
-            Message::SearchFail => panic!("Series Search Failed"),
             Message::SearchResult(message) => {
                 if let SearchResultMessage::SeriesResultPressed = message.clone().message() {
                     self.load_state = LoadState::NotLoaded;
+                    self.advanced_shown = false;
+                }
+                // Results replaced since may still be sending messages
+                return self
+                    .search_results
+                    .get_mut(message.index())
+                    .map(|search_result| search_result.update(message).map(Message::SearchResult))
+                    .unwrap_or_else(Command::none);
+            }
+            Message::EscapeKeyPressed => {
+                self.load_state = LoadState::NotLoaded;
+                self.advanced_shown = false;
+            }
+            Message::NoteResultPressed(series_id) | Message::EpisodeResultPressed(series_id) => {
+                self.load_state = LoadState::NotLoaded;
+                return Command::perform(get_series_main_info_with_id(series_id), |series_info| {
+                    Message::NoteSeriesLoaded(
+                        series_info
+                            .map(Box::new)
+                            .map_err(|err| tracing::error!("failed to open series: {}", err))
+                            .ok(),
+                    )
+                });
+            }
+            Message::NoteSeriesLoaded(series_info) => {
+                if let Some(series_info) = series_info {
+                    self.series_page_sender
+                        .send(*series_info)
+                        .expect("failed to send series page info");
+                }
+            }
+            Message::AdvancedToggled => self.advanced_shown = !self.advanced_shown,
+            Message::MinYearChanged(min_year) => {
+                self.filter.min_year = min_year.trim().parse().ok();
+                self.min_year_input = min_year;
+            }
+            Message::MaxYearChanged(max_year) => {
+                self.filter.max_year = max_year.trim().parse().ok();
+                self.max_year_input = max_year;
+            }
+            Message::GenreSelected(genre) => self.filter.genre = Some(genre.to_owned()),
+            Message::StatusSelected(status) => self.filter.status = Some(status.to_owned()),
+            Message::MinRatingSelected(min_rating) => self.filter.min_rating = Some(min_rating),
+            Message::MaxRuntimeSelected(max_runtime) => self.filter.max_runtime = Some(max_runtime),
+            Message::FiltersCleared => {
+                self.filter = SearchFilter::default();
+                self.min_year_input.clear();
+                self.max_year_input.clear();
+            }
+            Message::SmartListNameChanged(name) => self.smart_list_name = name,
+            Message::SmartListSourceSelected(source) => self.smart_list_source = source,
+            Message::SmartListSaved => {
+                let name = self.smart_list_name.trim().to_owned();
+                if name.is_empty() {
+                    return Command::none();
                 }
-                self.search_results[message.index()].update(message)
+
+                DB.set_smart_list(&SmartList {
+                    name: name.clone(),
+                    term: self.search_term.trim().to_owned(),
+                    filter: self.filter.clone(),
+                    source: self.smart_list_source,
+                });
+                self.smart_list_name.clear();
+                toast::push(format!("Saved '{}' to the smart lists in My Shows", name));
             }
-            Message::EscapeKeyPressed => self.load_state = LoadState::NotLoaded,
         }
         Command::none()
     }
 
+    /// Appends the results after the ones already shown
+    fn push_search_results(
+        &mut self,
+        results: Vec<series_searching::SeriesSearchResult>,
+    ) -> Command<Message> {
+        let mut search_results_commands = Vec::with_capacity(results.len());
+        for result in results {
+            let (search_result, search_result_command) = SearchResult::new(
+                self.search_results.len(),
+                result,
+                self.series_page_sender.clone(),
+            );
+            self.search_results.push(search_result);
+            search_results_commands.push(search_result_command.map(Message::SearchResult));
+        }
+
+        Command::batch(search_results_commands)
+    }
+
+    /// Loads the progress of the results that got tracked, for when the database changes
+    pub fn reload_progress(&self) -> Command<Message> {
+        Command::batch(
+            self.search_results
+                .iter()
+                .map(|search_result| search_result.load_progress().map(Message::SearchResult)),
+        )
+    }
+
     pub fn view(
         &self,
     ) -> (
         Element<'_, Message, Renderer>,
         Option<Element<'_, Message, Renderer>>,
     ) {
+        let advanced_button = button(text("Advanced").size(11))
+            .on_press(Message::AdvancedToggled)
+            .style(if self.advanced_shown {
+                styles::button_styles::transparent_button_with_rounded_border_theme()
+            } else {
+                styles::button_styles::transparent_button_theme()
+            });
+
         let search_bar = column!(
             vertical_space(10),
-            text_input("Search", &self.search_term)
-                .width(300)
-                .on_input(Message::TermChanged)
-                .on_submit(Message::TermSearched)
+            row![
+                text_input("Search", &self.search_term)
+                    .width(300)
+                    .on_input(Message::TermChanged)
+                    .on_submit(Message::TermSearched),
+                advanced_button
+            ]
+            .spacing(5)
+            .align_items(iced::Alignment::Center)
         )
         .width(Length::Fill)
         .align_items(iced::Alignment::Center);
 
         let search_results: Option<Element<'_, Message, Renderer>> = match self.load_state {
             LoadState::Loaded => {
-                let result_items: Vec<_> = self
-                    .search_results
-                    .iter()
-                    .map(|result| result.view().map(Message::SearchResult))
-                    .collect();
+                let mut result_items: Vec<_> =
+                    self.episode_result.iter().map(episode_result).collect();
+
+                result_items.extend(
+                    self.note_results
+                        .iter()
+                        .map(|(episode_note, series_name)| note_result(episode_note, series_name)),
+                );
+
+                result_items.extend(
+                    self.search_results
+                        .iter()
+                        .map(|result| result.view().map(Message::SearchResult)),
+                );
 
                 Some(if result_items.is_empty() {
                     container(text("No results"))
@@ -146,50 +433,316 @@ impl Search {
                     .center_x()
                     .into(),
             ),
+            LoadState::Failed(reason) => Some(
+                container(text(reason).style(styles::text_styles::red_text_theme()))
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding(10)
+                    .into(),
+            ),
             LoadState::NotLoaded => None,
         };
 
-        let search_results = search_results.map(|search_results| {
-            container(
+        if !self.advanced_shown && search_results.is_none() {
+            return (search_bar.into(), None);
+        }
+
+        let mut overlay = column![].spacing(5);
+
+        if self.advanced_shown {
+            overlay = overlay.push(self.filter_panel());
+        }
+
+        if let Some(search_results) = search_results {
+            overlay = overlay.push(
                 scrollable(search_results)
                     .width(Length::Fill)
                     .direction(styles::scrollable_styles::vertical_direction()),
-            )
+            );
+        }
+
+        let overlay = container(overlay)
             .padding(5)
             .width(500)
             .style(styles::container_styles::first_class_container_rounded_theme())
-            .into()
-        });
+            .into();
 
-        (search_bar.into(), search_results)
+        (search_bar.into(), Some(overlay))
     }
+
+    fn filter_panel(&self) -> Element<'_, Message, Renderer> {
+        let years = row![
+            text("Premiered").size(11).width(70),
+            text_input("From year", &self.min_year_input)
+                .on_input(Message::MinYearChanged)
+                .on_submit(Message::TermSearched)
+                .size(11)
+                .width(80),
+            text_input("To year", &self.max_year_input)
+                .on_input(Message::MaxYearChanged)
+                .on_submit(Message::TermSearched)
+                .size(11)
+                .width(80),
+        ]
+        .spacing(5)
+        .align_items(iced::Alignment::Center);
+
+        let genre = advanced_search::GENRES
+            .into_iter()
+            .find(|genre| self.filter.genre.as_deref() == Some(*genre));
+        let status = advanced_search::STATUSES
+            .into_iter()
+            .find(|status| self.filter.status.as_deref() == Some(*status));
+
+        let genre_and_status = row![
+            pick_list(&advanced_search::GENRES[..], genre, Message::GenreSelected)
+                .placeholder("Genre")
+                .text_size(11),
+            pick_list(
+                &advanced_search::STATUSES[..],
+                status,
+                Message::StatusSelected
+            )
+            .placeholder("Status")
+            .text_size(11),
+        ]
+        .spacing(5);
+
+        let rating_and_runtime = row![
+            text("Rated at least").size(11),
+            pick_list(
+                &advanced_search::MIN_RATINGS[..],
+                self.filter.min_rating,
+                Message::MinRatingSelected
+            )
+            .placeholder("Any")
+            .text_size(11),
+            text("Episodes of at most").size(11),
+            pick_list(
+                &advanced_search::MAX_RUNTIMES[..],
+                self.filter.max_runtime,
+                Message::MaxRuntimeSelected
+            )
+            .placeholder("Any")
+            .text_size(11),
+            text("minutes").size(11),
+        ]
+        .spacing(5)
+        .align_items(iced::Alignment::Center);
+
+        let controls = row![
+            button(text("Search").size(11))
+                .on_press(Message::TermSearched)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+            button(text("Clear filters").size(11))
+                .on_press(Message::FiltersCleared)
+                .style(styles::button_styles::transparent_button_theme()),
+        ]
+        .spacing(5);
+
+        let mut save_button = button(text("Save as smart list").size(11))
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+        if !self.smart_list_name.trim().is_empty() {
+            save_button = save_button.on_press(Message::SmartListSaved);
+        }
+
+        let smart_list = row![
+            text_input("Smart list name", &self.smart_list_name)
+                .on_input(Message::SmartListNameChanged)
+                .on_submit(Message::SmartListSaved)
+                .size(11)
+                .width(150),
+            pick_list(
+                &SmartListSource::ALL[..],
+                Some(self.smart_list_source),
+                Message::SmartListSourceSelected
+            )
+            .text_size(11),
+            save_button,
+        ]
+        .spacing(5)
+        .align_items(iced::Alignment::Center);
+
+        column![
+            years,
+            genre_and_status,
+            rating_and_runtime,
+            text("Searches the results of the term together with the series currently airing, only the airing series without a term")
+                .size(11),
+            controls,
+            smart_list,
+        ]
+        .spacing(5)
+        .padding(5)
+        .into()
+    }
+}
+
+/// Finds the series of the full schedule matching the term, without any request when the
+/// full schedule is already loaded
+async fn search_full_schedule(term: String) -> Vec<SeriesMainInformation> {
+    let full_schedule: &'static FullSchedule = match FullSchedule::new().await {
+        Ok(full_schedule) => full_schedule,
+        Err(err) => {
+            tracing::error!("failed to load the full schedule for searching: {}", err);
+            return vec![];
+        }
+    };
+
+    // Searching reads the hidden series with a blocking lock
+    tokio::task::spawn_blocking(move || {
+        full_schedule
+            .search(&term, SCHEDULE_RESULTS_AMOUNT)
+            .into_iter()
+            .cloned()
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Finds the episode notes containing the term among the series in the database
+fn search_notes(term: &str) -> Vec<(EpisodeNote, String)> {
+    if term.trim().is_empty() {
+        return vec![];
+    }
+
+    DB.search_episode_notes(term.trim())
+        .into_iter()
+        .filter_map(|episode_note| {
+            let series_name = DB.get_series(episode_note.series_id)?.get_name().to_owned();
+            Some((episode_note, series_name))
+        })
+        .collect()
+}
+
+/// An episode found from the episode number the search term ends with
+#[derive(Clone, Debug)]
+pub struct EpisodeResult {
+    series_id: u32,
+    series_name: String,
+    season: u32,
+    number: Option<u32>,
+    name: String,
+}
+
+/// Finds the episode a term like `Show 145` or `Show S07E05` refers to
+///
+/// The episode number is parsed like everywhere else episodes are typed, absolute numbers
+/// included. The series is looked up among the tracked ones first, TVmaze's best match
+/// otherwise.
+async fn search_episode(term: String) -> Option<EpisodeResult> {
+    let (series_name, episode_number) = EpisodeNumber::split_search_term(&term)?;
+
+    let tracked_series = DB
+        .get_series_collection()
+        .iter()
+        .filter(|series| series.is_tracked())
+        .find(|series| series.get_name().eq_ignore_ascii_case(series_name))
+        .map(|series| (series.id(), series.get_name().to_owned()));
+
+    let (series_id, series_name) = match tracked_series {
+        Some(tracked_series) => tracked_series,
+        None => {
+            let series_info = series_searching::single_search_series(series_name.to_owned())
+                .await
+                .map_err(|err| tracing::error!("failed to search series: {}", err))
+                .ok()??;
+            (series_info.id, series_info.name)
+        }
+    };
+
+    let episode_list = EpisodeList::new(series_id)
+        .await
+        .map_err(|err| tracing::error!("failed to get the episode list: {}", err))
+        .ok()?;
+    let episode = episode_list.find_episode(&episode_number)?;
+
+    Some(EpisodeResult {
+        series_id,
+        series_name,
+        season: episode.season,
+        number: episode.number,
+        name: episode.name.clone(),
+    })
+}
+
+fn episode_result(episode_result: &EpisodeResult) -> Element<'_, Message, Renderer> {
+    let episode = match episode_result.number {
+        Some(number) => season_episode_str_gen(episode_result.season, number),
+        None => format!("Season {} special", episode_result.season),
+    };
+
+    let content = column![
+        text(format!("{} {}", episode_result.series_name, episode))
+            .style(styles::text_styles::accent_color_theme()),
+        text(&episode_result.name).size(11),
+    ]
+    .spacing(3);
+
+    button(content)
+        .width(Length::Fill)
+        .on_press(Message::EpisodeResultPressed(episode_result.series_id))
+        .style(styles::button_styles::transparent_button_theme())
+        .into()
+}
+
+fn note_result<'a>(
+    episode_note: &'a EpisodeNote,
+    series_name: &'a str,
+) -> Element<'a, Message, Renderer> {
+    let content = column![
+        text(format!(
+            "{} {}",
+            series_name,
+            season_episode_str_gen(episode_note.season_number, episode_note.episode_number)
+        ))
+        .style(styles::text_styles::accent_color_theme()),
+        text(format!("Note: {}", episode_note.note)).size(11),
+    ]
+    .spacing(3);
+
+    button(content)
+        .width(Length::Fill)
+        .on_press(Message::NoteResultPressed(episode_note.series_id))
+        .style(styles::button_styles::transparent_button_theme())
+        .into()
 }
 
 mod search_result {
     use std::sync::mpsc;
 
     use bytes::Bytes;
-    use iced::widget::{column, image, mouse_area, row, svg, text, Space};
-    use iced::{Command, Element, Renderer};
+    use iced::widget::{
+        button, column, container, horizontal_space, image, mouse_area, row, svg, text, Space,
+    };
+    use iced::{Command, Element, Length, Renderer};
 
     use crate::core::api::tv_maze::series_information::SeriesMainInformation;
     use crate::core::api::tv_maze::Rating;
+    use crate::core::caching::episode_list::EpisodeList;
+    use crate::core::database::{self, SeriesStatus};
+    use crate::core::date_formatting;
     use crate::core::{api::tv_maze::series_searching, caching};
     use crate::gui::assets::icons::STAR_FILL;
     use crate::gui::helpers::empty_image;
     pub use crate::gui::message::IndexedMessage;
-    use crate::gui::{helpers, styles};
+    use crate::gui::{helpers, styles, toast};
 
     #[derive(Debug, Clone)]
     pub enum Message {
         ImageLoaded(Option<Bytes>),
+        AiredEpisodesLoaded(Option<usize>),
         SeriesResultPressed,
+        PlanToWatchPressed,
     }
 
     pub struct SearchResult {
         index: usize,
         search_result: series_searching::SeriesSearchResult,
         image: Option<Bytes>,
+        /// The amount of aired episodes, only loaded for the tracked series to show their progress
+        aired_episodes: Option<usize>,
         series_page_sender: mpsc::Sender<SeriesMainInformation>,
     }
 
@@ -200,37 +753,91 @@ mod search_result {
             series_page_sender: mpsc::Sender<SeriesMainInformation>,
         ) -> (Self, Command<IndexedMessage<usize, Message>>) {
             let image_url = search_result.show.image.clone();
+            let search_result = Self {
+                index,
+                search_result,
+                image: None,
+                aired_episodes: None,
+                series_page_sender,
+            };
+
+            let image_command = image_url
+                .map(|url| {
+                    let (image_url, image_resolution) =
+                        caching::image_source(url, caching::ImageKind::Poster, false);
+                    Command::perform(
+                        caching::load_image(image_url, image_resolution),
+                        Message::ImageLoaded,
+                    )
+                    .map(move |message| IndexedMessage::new(index, message))
+                })
+                .unwrap_or(Command::none());
+            let progress_command = search_result.load_progress();
+
             (
-                Self {
-                    index,
-                    search_result,
-                    image: None,
-                    series_page_sender,
+                search_result,
+                Command::batch([image_command, progress_command]),
+            )
+        }
+
+        pub fn series_id(&self) -> u32 {
+            self.search_result.show.id
+        }
+
+        /// Loads the amount of aired episodes when the series is tracked and they are not
+        /// loaded yet
+        pub fn load_progress(&self) -> Command<IndexedMessage<usize, Message>> {
+            let series_id = self.search_result.show.id;
+            let is_tracked = database::DB
+                .get_series(series_id)
+                .is_some_and(|series| series.is_tracked());
+
+            if !is_tracked || self.aired_episodes.is_some() {
+                return Command::none();
+            }
+
+            let index = self.index;
+            Command::perform(
+                async move {
+                    EpisodeList::new(series_id)
+                        .await
+                        .map(|episode_list| episode_list.get_total_watchable_episodes())
+                        .map_err(|err| {
+                            tracing::error!("failed to load search result progress: {}", err)
+                        })
+                        .ok()
                 },
-                image_url
-                    .map(|url| {
-                        Command::perform(
-                            caching::load_image(
-                                url.medium_image_url,
-                                caching::ImageResolution::Medium,
-                            ),
-                            Message::ImageLoaded,
-                        )
-                        .map(move |message| IndexedMessage::new(index, message))
-                    })
-                    .unwrap_or(Command::none()),
+                Message::AiredEpisodesLoaded,
             )
+            .map(move |message| IndexedMessage::new(index, message))
         }
 
-        pub fn update(&mut self, message: IndexedMessage<usize, Message>) {
+        pub fn update(
+            &mut self,
+            message: IndexedMessage<usize, Message>,
+        ) -> Command<IndexedMessage<usize, Message>> {
             match message.message() {
                 Message::ImageLoaded(image) => self.image = image,
+                Message::AiredEpisodesLoaded(aired_episodes) => {
+                    self.aired_episodes = aired_episodes
+                }
                 Message::SeriesResultPressed => {
                     self.series_page_sender
                         .send(self.search_result.show.clone())
                         .expect("failed to send series page info");
                 }
+                Message::PlanToWatchPressed => {
+                    let series_info = &self.search_result.show;
+                    if database::DB.get_series_status(series_info.id) == SeriesStatus::PlanToWatch {
+                        database::DB.set_series_status(series_info.id, SeriesStatus::Watching);
+                        toast::push(format!("Removed {} from plan to watch", series_info.name));
+                    } else {
+                        database::DB.set_series_status(series_info.id, SeriesStatus::PlanToWatch);
+                        toast::push(format!("Planning to watch {}", series_info.name));
+                    }
+                }
             }
+            Command::none()
         }
 
         pub fn view(&self) -> Element<'_, IndexedMessage<usize, Message>, Renderer> {
@@ -254,24 +861,78 @@ mod search_result {
                 };
 
             let mut column = column![
-                text(&self.search_result.show.name)
-                    .size(16)
-                    .style(styles::text_styles::accent_color_theme()),
+                row![
+                    text(&self.search_result.show.name)
+                        .size(16)
+                        .style(styles::text_styles::accent_color_theme()),
+                    self.tracked_badge(),
+                ]
+                .spacing(5)
+                .align_items(iced::Alignment::Center),
                 genres
             ];
 
             if let Some(premier) = &self.search_result.show.premiered {
-                column = column.push(text(format!("Premiered: {}", premier)).size(9));
+                column = column.push(
+                    text(format!(
+                        "Premiered: {}",
+                        date_formatting::api_date_str(premier)
+                    ))
+                    .size(9),
+                );
             }
 
             column = column.push(Self::rating_widget(&self.search_result.show.rating));
 
-            let element: Element<'_, Message, Renderer> = mouse_area(row.push(column))
+            let is_planned = database::DB.get_series_status(self.search_result.show.id)
+                == SeriesStatus::PlanToWatch;
+            let plan_to_watch_button = button(
+                text(if is_planned {
+                    "Planned"
+                } else {
+                    "Plan to watch"
+                })
+                .size(11),
+            )
+            .on_press(Message::PlanToWatchPressed)
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+
+            let row = row
+                .push(column)
+                .push(horizontal_space(Length::Fill))
+                .push(plan_to_watch_button)
+                .align_items(iced::Alignment::Center);
+
+            let element: Element<'_, Message, Renderer> = mouse_area(row)
                 .on_press(Message::SeriesResultPressed)
                 .into();
             element.map(|message| IndexedMessage::new(self.index, message))
         }
 
+        /// Shows whether the series is tracked together with how far along it is
+        fn tracked_badge(&self) -> Element<'_, Message, Renderer> {
+            let Some(series) = database::DB
+                .get_series(self.search_result.show.id)
+                .filter(|series| series.is_tracked())
+            else {
+                return Space::new(0, 0).into();
+            };
+
+            let label = match self.aired_episodes {
+                Some(aired_episodes) => format!(
+                    "Tracked, {}/{} watched",
+                    series.get_total_episodes(),
+                    aired_episodes
+                ),
+                None => "Tracked".to_owned(),
+            };
+
+            container(text(label).size(11))
+                .padding([2, 5])
+                .style(styles::container_styles::second_class_container_rounded_theme())
+                .into()
+        }
+
         fn rating_widget(rating: &Rating) -> Element<'_, Message, Renderer> {
             if let Some(average_rating) = rating.average {
                 let star_handle = svg::Handle::from_memory(STAR_FILL);
@@ -1,4 +1,7 @@
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::background::{self, TaskKind};
+use crate::core::caching::image_prefetch;
+use crate::core::quick_stats::QuickStats;
 use discover_tab::{DiscoverTab, Message as DiscoverMessage};
 use my_shows_tab::{Message as MyShowsMessage, MyShowsTab};
 use settings_tab::{Message as SettingsMessage, SettingsTab};
@@ -77,11 +80,21 @@ impl From<TabId> for usize {
 pub struct TabLabel {
     pub text: &'static str,
     pub icon: &'static [u8],
+    pub tooltip: Option<String>,
 }
 
 impl TabLabel {
     pub fn new(text: &'static str, icon: &'static [u8]) -> Self {
-        Self { text, icon }
+        Self {
+            text,
+            icon,
+            tooltip: None,
+        }
+    }
+
+    pub fn with_tooltip(mut self, tooltip: String) -> Self {
+        self.tooltip = Some(tooltip);
+        self
     }
 }
 
@@ -92,150 +105,91 @@ pub enum Message {
     MyShows(MyShowsMessage),
     Statistics(StatisticsMessage),
     Settings(SettingsMessage),
+    QuickStatsLoaded(Option<QuickStats>),
+    DatabaseChanged,
+    ImagesPrefetched(usize),
 }
 
-enum ReloadableTab<'a> {
-    Watchlist(WatchlistTab<'a>),
-    MyShows(MyShowsTab<'a>),
-    Statistics(StatisticsTab<'a>),
-}
-
+/// Holds every tab for the whole session so that their widget state (scroll position,
+/// selections, filters etc) survives switching between them
+///
+/// Tabs are refreshed in place through their `refresh` methods whenever they are switched
+/// to, keeping what they show until the refreshed information arrives.
 pub struct TabsController<'a> {
     current_tab: TabId,
     discover_tab: DiscoverTab<'a>,
+    watchlist_tab: WatchlistTab<'a>,
+    my_shows_tab: Box<MyShowsTab<'a>>,
+    statistics_tab: StatisticsTab<'a>,
     settings_tab: SettingsTab,
-    reloadable_tab: Option<ReloadableTab<'a>>,
-    tabs_scrollable_offsets: [RelativeOffset; 5],
-    series_page_sender: mpsc::Sender<SeriesMainInformation>,
+    /// Shown in the tooltip of the My Shows tab, `None` while loading
+    quick_stats: Option<QuickStats>,
 }
 
 impl<'a> TabsController<'a> {
     pub fn new(
         series_page_sender: mpsc::Sender<SeriesMainInformation>,
     ) -> (Self, Command<Message>) {
-        let (discover_tab, discover_command) = DiscoverTab::new(series_page_sender.clone());
+        let mut discover_tab = DiscoverTab::new(series_page_sender.clone());
+        // Discover is the first tab shown
+        let discover_command = discover_tab.refresh();
         let (settings_tab, settings_command) = SettingsTab::new();
 
         (
             Self {
                 current_tab: TabId::Discover,
                 discover_tab,
-                reloadable_tab: None,
+                watchlist_tab: WatchlistTab::new(series_page_sender.clone()),
+                my_shows_tab: Box::new(MyShowsTab::new(series_page_sender.clone())),
+                statistics_tab: StatisticsTab::new(series_page_sender),
                 settings_tab,
-                tabs_scrollable_offsets: [RelativeOffset::START; 5],
-                series_page_sender,
+                quick_stats: None,
             },
             Command::batch([
                 discover_command.map(Message::Discover),
                 settings_command.map(Message::Settings),
+                load_quick_stats(),
+                prefetch_images(),
             ]),
         )
     }
 
-    fn record_scrollable_offset(&mut self, index: usize, scrollable_offset: RelativeOffset) {
-        self.tabs_scrollable_offsets[index] = scrollable_offset;
-    }
-
-    fn record_tabs_scrollable_offsets(&mut self) {
-        let index: usize = self.current_tab.into();
-
+    /// Scrolls the current tab back to where it was left
+    ///
+    /// Needed whenever the tab is shown again as it's scrollable is rebuilt.
+    pub fn restore_scrollable_offset(&self) -> Command<Message> {
         match self.current_tab {
-            TabId::Discover => {
-                self.record_scrollable_offset(index, self.discover_tab.get_scrollable_offset())
-            }
-            TabId::Settings => {
-                self.record_scrollable_offset(index, self.settings_tab.get_scrollable_offset())
+            TabId::Discover => self
+                .discover_tab
+                .restore_scrollable_offsets()
+                .map(Message::Discover),
+            TabId::Watchlist => {
+                WatchlistTab::set_scrollable_offset(self.watchlist_tab.get_scrollable_offset())
+                    .map(Message::Watchlist)
             }
-            _ => {
-                if let Some(reloadable_tab) = &self.reloadable_tab {
-                    match reloadable_tab {
-                        ReloadableTab::Watchlist(watchlist_tab) => self
-                            .record_scrollable_offset(index, watchlist_tab.get_scrollable_offset()),
-                        ReloadableTab::MyShows(my_shows_tab) => self
-                            .record_scrollable_offset(index, my_shows_tab.get_scrollable_offset()),
-                        ReloadableTab::Statistics(statistics_tab) => self.record_scrollable_offset(
-                            index,
-                            statistics_tab.get_scrollable_offset(),
-                        ),
-                    }
-                }
+            TabId::MyShows => {
+                MyShowsTab::set_scrollable_offset(self.my_shows_tab.get_scrollable_offset())
+                    .map(Message::MyShows)
             }
-        }
-    }
-
-    pub fn update_scrollables_offsets(&mut self) -> Command<Message> {
-        self.record_tabs_scrollable_offsets();
-        self.restore_scrollable_offset()
-    }
-
-    fn restore_scrollable_offset(&mut self) -> Command<Message> {
-        let index: usize = self.current_tab.into();
-
-        match self.current_tab {
-            TabId::Discover => {
-                DiscoverTab::set_scrollable_offset(self.tabs_scrollable_offsets[index])
-                    .map(Message::Discover)
+            TabId::Statistics => {
+                StatisticsTab::set_scrollable_offset(self.statistics_tab.get_scrollable_offset())
+                    .map(Message::Statistics)
             }
             TabId::Settings => {
-                SettingsTab::set_scrollable_offset(self.tabs_scrollable_offsets[index])
+                SettingsTab::set_scrollable_offset(self.settings_tab.get_scrollable_offset())
                     .map(Message::Settings)
             }
-            _ => {
-                let reloadable_tab = self
-                    .reloadable_tab
-                    .as_ref()
-                    .expect("there should be reloadable tab at this point");
-
-                match reloadable_tab {
-                    ReloadableTab::Watchlist(_) => {
-                        WatchlistTab::set_scrollable_offset(self.tabs_scrollable_offsets[index])
-                            .map(Message::Watchlist)
-                    }
-                    ReloadableTab::MyShows(_) => {
-                        MyShowsTab::set_scrollable_offset(self.tabs_scrollable_offsets[index])
-                            .map(Message::MyShows)
-                    }
-                    ReloadableTab::Statistics(_) => {
-                        StatisticsTab::set_scrollable_offset(self.tabs_scrollable_offsets[index])
-                            .map(Message::Statistics)
-                    }
-                }
-            }
         }
     }
 
     pub fn switch_to_tab(&mut self, tab: TabId) -> Command<Message> {
-        self.record_tabs_scrollable_offsets();
-
-        let index: usize = tab.into();
         self.current_tab = tab;
 
         let tab_command = match tab {
             TabId::Discover => self.discover_tab.refresh().map(Message::Discover),
-            TabId::Watchlist => {
-                let (watchlist_tab, watchlist_command) = WatchlistTab::new(
-                    self.series_page_sender.clone(),
-                    Some(self.tabs_scrollable_offsets[index]),
-                );
-                self.reloadable_tab = Some(ReloadableTab::Watchlist(watchlist_tab));
-                watchlist_command.map(Message::Watchlist)
-            }
-            TabId::MyShows => {
-                let (my_shows_tab, my_shows_command) = MyShowsTab::new(
-                    self.series_page_sender.clone(),
-                    Some(self.tabs_scrollable_offsets[index]),
-                );
-                self.reloadable_tab = Some(ReloadableTab::MyShows(my_shows_tab));
-                my_shows_command.map(Message::MyShows)
-            }
-            TabId::Statistics => {
-                let (statistics_tab, statistics_command) = StatisticsTab::new(
-                    self.series_page_sender.clone(),
-                    Some(self.tabs_scrollable_offsets[index]),
-                );
-                self.reloadable_tab = Some(ReloadableTab::Statistics(statistics_tab));
-                statistics_command.map(Message::Statistics)
-            }
+            TabId::Watchlist => self.watchlist_tab.refresh().map(Message::Watchlist),
+            TabId::MyShows => self.my_shows_tab.refresh().map(Message::MyShows),
+            TabId::Statistics => self.statistics_tab.refresh().map(Message::Statistics),
             TabId::Settings => Command::none(),
         };
 
@@ -245,22 +199,19 @@ impl<'a> TabsController<'a> {
     pub fn subscription(&self) -> iced::Subscription<Message> {
         let tab_subscription = match self.current_tab {
             TabId::Discover => self.discover_tab.subscription().map(Message::Discover),
-            _ => {
-                if let Some(reloadable_tab) = &self.reloadable_tab {
-                    match reloadable_tab {
-                        ReloadableTab::MyShows(my_shows) => {
-                            my_shows.subscription().map(Message::MyShows)
-                        }
-                        _ => iced::subscription::Subscription::none(),
-                    }
-                } else {
-                    iced::subscription::Subscription::none()
-                }
-            }
+            TabId::MyShows => self.my_shows_tab.subscription().map(Message::MyShows),
+            TabId::Settings => self.settings_tab.subscription().map(Message::Settings),
+            _ => iced::Subscription::none(),
         };
         iced::Subscription::batch([
             tab_subscription,
-            self.settings_tab.subscription().map(Message::Settings),
+            self.settings_tab
+                .background_subscription()
+                .map(Message::Settings),
+            self.my_shows_tab
+                .background_subscription()
+                .map(Message::MyShows),
+            database_changes::subscription(),
         ])
     }
 
@@ -268,27 +219,30 @@ impl<'a> TabsController<'a> {
         match message {
             Message::Discover(message) => self.discover_tab.update(message).map(Message::Discover),
             Message::Watchlist(message) => {
-                if let Some(ReloadableTab::Watchlist(ref mut watchlist)) = self.reloadable_tab {
-                    watchlist.update(message).map(Message::Watchlist)
-                } else {
-                    Command::none()
-                }
-            }
-            Message::MyShows(message) => {
-                if let Some(ReloadableTab::MyShows(ref mut my_shows)) = self.reloadable_tab {
-                    my_shows.update(message).map(Message::MyShows)
-                } else {
-                    Command::none()
-                }
+                self.watchlist_tab.update(message).map(Message::Watchlist)
             }
+            Message::MyShows(message) => self.my_shows_tab.update(message).map(Message::MyShows),
             Message::Statistics(message) => {
-                if let Some(ReloadableTab::Statistics(ref mut statistics)) = self.reloadable_tab {
-                    statistics.update(message).map(Message::Statistics)
-                } else {
-                    Command::none()
-                }
+                self.statistics_tab.update(message).map(Message::Statistics)
             }
             Message::Settings(message) => self.settings_tab.update(message).map(Message::Settings),
+            Message::QuickStatsLoaded(quick_stats) => {
+                self.quick_stats = quick_stats;
+                Command::none()
+            }
+            Message::DatabaseChanged => {
+                self.quick_stats = None;
+                // Watching moves the next episodes along
+                Command::batch([
+                    load_quick_stats(),
+                    prefetch_images(),
+                    self.discover_tab.database_changed().map(Message::Discover),
+                ])
+            }
+            Message::ImagesPrefetched(prefetched) => {
+                tracing::info!("prefetched {} next episode images", prefetched);
+                Command::none()
+            }
         }
     }
 
@@ -296,7 +250,12 @@ impl<'a> TabsController<'a> {
         [
             DiscoverTab::tab_label(),
             WatchlistTab::tab_label(),
-            MyShowsTab::tab_label(),
+            MyShowsTab::tab_label().with_tooltip(
+                self.quick_stats
+                    .as_ref()
+                    .map(|quick_stats| quick_stats.to_string())
+                    .unwrap_or_else(|| "Counting your shows...".to_owned()),
+            ),
             StatisticsTab::tab_label(),
             SettingsTab::tab_label(),
         ]
@@ -305,17 +264,72 @@ impl<'a> TabsController<'a> {
     pub fn view(&self) -> Element<'_, Message, Renderer> {
         match self.current_tab {
             TabId::Discover => self.discover_tab.view().map(Message::Discover),
+            TabId::Watchlist => self.watchlist_tab.view().map(Message::Watchlist),
+            TabId::MyShows => self.my_shows_tab.view().map(Message::MyShows),
+            TabId::Statistics => self.statistics_tab.view().map(Message::Statistics),
             TabId::Settings => self.settings_tab.view().map(Message::Settings),
-            _ => {
-                let reloadable_tab = self.reloadable_tab.as_ref().expect("there must be a tab");
-                match reloadable_tab {
-                    ReloadableTab::Watchlist(watchlist) => watchlist.view().map(Message::Watchlist),
-                    ReloadableTab::MyShows(my_shows) => my_shows.view().map(Message::MyShows),
-                    ReloadableTab::Statistics(statistics) => {
-                        statistics.view().map(Message::Statistics)
-                    }
-                }
-            }
         }
     }
 }
+
+fn load_quick_stats() -> Command<Message> {
+    Command::perform(QuickStats::load(), |quick_stats| {
+        Message::QuickStatsLoaded(
+            quick_stats
+                .map_err(|err| tracing::error!("failed to load quick stats: {}", err))
+                .ok(),
+        )
+    })
+}
+
+/// Prefetches on the background runtime as nobody is waiting on the images yet
+fn prefetch_images() -> Command<Message> {
+    Command::perform(
+        background::run(
+            TaskKind::Prefetch,
+            image_prefetch::prefetch_next_episode_images(),
+        ),
+        |prefetched| {
+            Message::ImagesPrefetched(
+                prefetched
+                    .map_err(|err| tracing::warn!("images were not prefetched: {}", err))
+                    .unwrap_or_default(),
+            )
+        },
+    )
+}
+
+mod database_changes {
+    use std::time::Duration;
+
+    use iced::futures::sink::SinkExt;
+    use iced::subscription::{self, Subscription};
+
+    use super::Message;
+    use crate::core::database::DB;
+
+    /// How long the database has to stay unchanged before the changes are reported, so
+    /// that bulk changes are only reported once
+    const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+    pub fn subscription() -> Subscription<Message> {
+        subscription::channel("database-changes", 10, |mut output| async move {
+            let mut subscriber = DB.watch_series_changes();
+
+            while (&mut subscriber).await.is_some() {
+                while tokio::time::timeout(SETTLE_TIME, &mut subscriber)
+                    .await
+                    .is_ok_and(|event| event.is_some())
+                {}
+
+                output
+                    .send(Message::DatabaseChanged)
+                    .await
+                    .expect("failed to send database change");
+            }
+
+            tracing::error!("database change subscriber closed");
+            std::future::pending().await
+        })
+    }
+}
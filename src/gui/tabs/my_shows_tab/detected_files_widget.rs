@@ -0,0 +1,175 @@
+use iced::widget::{button, column, horizontal_space, row, text, Column};
+use iced::{Alignment, Element, Length, Renderer};
+
+use crate::core::database::DB;
+use crate::core::sync::local_files::DetectedEpisode;
+use crate::gui::helpers::season_episode_str_gen;
+use crate::gui::{styles, toast};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EpisodeDetected(DetectedEpisode),
+    MarkWatchedPressed(usize),
+    DismissPressed(usize),
+    MarkAllWatchedPressed,
+    DismissAllPressed,
+}
+
+/// The episodes matched from local files, waiting for the user to mark them watched
+#[derive(Default)]
+pub struct DetectedFiles {
+    review_queue: Vec<DetectedEpisode>,
+}
+
+impl DetectedFiles {
+    pub fn is_empty(&self) -> bool {
+        self.review_queue.is_empty()
+    }
+
+    pub fn subscription(&self) -> iced::Subscription<Message> {
+        local_files_watcher::subscription().map(Message::EpisodeDetected)
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::EpisodeDetected(detected_episode) => {
+                // A single file can be reported many times, like when it's written and renamed
+                let is_queued = self.review_queue.iter().any(|queued| {
+                    queued.series_id == detected_episode.series_id
+                        && queued.season == detected_episode.season
+                        && queued.episode == detected_episode.episode
+                });
+                if !is_queued {
+                    self.review_queue.push(detected_episode);
+                }
+            }
+            Message::MarkWatchedPressed(index) => {
+                if index < self.review_queue.len() {
+                    mark_watched(self.review_queue.remove(index));
+                }
+            }
+            Message::DismissPressed(index) => {
+                if index < self.review_queue.len() {
+                    self.review_queue.remove(index);
+                }
+            }
+            Message::MarkAllWatchedPressed => {
+                let marked_episodes = self.review_queue.len();
+                self.review_queue.drain(..).for_each(mark_watched);
+                toast::push(format!("Marked {} episodes as watched", marked_episodes));
+            }
+            Message::DismissAllPressed => self.review_queue.clear(),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let action_button = |label: &'static str, message: Message| {
+            button(text(label).size(11))
+                .on_press(message)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+        };
+
+        let episodes = Column::with_children(
+            self.review_queue
+                .iter()
+                .enumerate()
+                .map(|(index, detected_episode)| {
+                    row![
+                        column![
+                            text(format!(
+                                "{} {}",
+                                detected_episode.series_name,
+                                season_episode_str_gen(
+                                    detected_episode.season,
+                                    detected_episode.episode
+                                )
+                            )),
+                            text(&detected_episode.file_name).size(11),
+                        ],
+                        horizontal_space(Length::Fill),
+                        action_button("Mark watched", Message::MarkWatchedPressed(index)),
+                        action_button("Dismiss", Message::DismissPressed(index)),
+                    ]
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        column![
+            text("Found in local files, review before marking them watched").size(11),
+            episodes,
+            row![
+                action_button("Mark all watched", Message::MarkAllWatchedPressed),
+                action_button("Dismiss all", Message::DismissAllPressed),
+            ]
+            .spacing(5),
+        ]
+        .spacing(5)
+        .into()
+    }
+}
+
+fn mark_watched(detected_episode: DetectedEpisode) {
+    let Some(mut series) = DB.get_series(detected_episode.series_id) else {
+        return;
+    };
+    series.add_episode_unchecked(detected_episode.season, detected_episode.episode);
+}
+
+mod local_files_watcher {
+    use iced::futures::channel::mpsc;
+    use iced::futures::sink::SinkExt;
+    use iced::futures::StreamExt;
+    use iced::subscription::{self, Subscription};
+
+    use crate::core::settings_config::SETTINGS;
+    use crate::core::sync::local_files::{self, DetectedEpisode};
+
+    /// Watches the folders set in the settings, restarting whenever they change
+    pub fn subscription() -> Subscription<DetectedEpisode> {
+        let local_files_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .local_files
+            .clone();
+
+        if !local_files_settings.enabled || local_files_settings.folders.is_empty() {
+            return Subscription::none();
+        }
+
+        let folders = local_files_settings.folders;
+        subscription::channel(
+            ("local-files-watcher", folders.clone()),
+            10,
+            |mut output| async move {
+                let (sender, mut receiver) = mpsc::unbounded();
+
+                std::thread::spawn(move || {
+                    let result = local_files::watch_folders(
+                        &folders,
+                        |detected_episode| {
+                            sender.unbounded_send(detected_episode).ok();
+                        },
+                        || sender.is_closed(),
+                    );
+                    if let Err(err) = result {
+                        tracing::error!("failed to watch local files: {}", err);
+                    }
+                });
+
+                while let Some(detected_episode) = receiver.next().await {
+                    output
+                        .send(detected_episode)
+                        .await
+                        .expect("failed to send detected episode");
+                }
+
+                iced::futures::future::pending().await
+            },
+        )
+    }
+}
@@ -1,28 +1,65 @@
+use std::collections::HashSet;
 use std::sync::mpsc;
 
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::bulk_actions;
+use crate::core::database::SeriesStatus;
 use crate::gui::assets::icons::FILM;
-use crate::gui::styles;
+use crate::gui::{styles, toast};
 
 use iced::widget::scrollable::{RelativeOffset, Viewport};
-use iced::widget::{column, scrollable, text};
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, row, scrollable, text, text_input,
+};
 use iced::{Command, Element, Length, Renderer};
 
-use my_shows_widget::{Message as MyShowsMessage, MyShows};
+use detected_files_widget::{DetectedFiles, Message as DetectedFilesMessage};
+use my_shows_widget::{Message as MyShowsMessage, MyShows, MyShowsKind};
+use plan_to_watch_widget::{Message as PlanToWatchMessage, PlanToWatch};
+use series_updates_widget::{Message as SeriesUpdatesMessage, SeriesUpdates};
+use smart_lists_widget::{Message as SmartListsMessage, SmartLists};
 use upcoming_releases_widget::{Message as UpcomingReleasesMessage, UpcomingReleases};
 
 use super::Tab;
 
+mod detected_files_widget;
 mod my_shows_widget;
+mod plan_to_watch_widget;
+mod series_updates_widget;
+mod smart_lists_widget;
 mod upcoming_releases_widget;
 
+/// The statuses whose series are grouped apart from the ones being watched, in display order
+///
+/// Series planned to be watched have their own widget as they are ordered by priority.
+const GROUPED_STATUSES: [SeriesStatus; 3] = [
+    SeriesStatus::OnHold,
+    SeriesStatus::Completed,
+    SeriesStatus::Dropped,
+];
+
 #[derive(Debug, Clone)]
 pub enum Message {
     Ended(MyShowsMessage),
     Waiting(MyShowsMessage),
     Upcoming(UpcomingReleasesMessage),
     Untracked(MyShowsMessage),
+    Archived(MyShowsMessage),
+    Status(SeriesStatus, MyShowsMessage),
+    PlanToWatch(PlanToWatchMessage),
+    SeriesUpdates(SeriesUpdatesMessage),
+    SmartLists(SmartListsMessage),
+    DetectedFiles(DetectedFilesMessage),
     PageScrolled(Viewport),
+    ShowArchivedToggled(bool),
+    SelectionModeToggled,
+    SelectAll,
+    TagInputChanged(String),
+    BulkUntrack,
+    BulkStopFollowing,
+    BulkTag,
+    BulkMarkAiredWatched,
+    BulkActionCompleted(Result<String, String>),
 }
 
 pub struct MyShowsTab<'a> {
@@ -30,46 +67,111 @@ pub struct MyShowsTab<'a> {
     upcoming_releases: UpcomingReleases<'a>,
     ended_releases: MyShows<'a>,
     untracked_releases: MyShows<'a>,
+    archived_series: MyShows<'a>,
+    /// Whether the archived series are shown, they are hidden by default
+    show_archived: bool,
+    status_groups: Vec<(SeriesStatus, MyShows<'a>)>,
+    plan_to_watch: PlanToWatch<'a>,
+    series_updates: SeriesUpdates<'a>,
+    smart_lists: SmartLists<'a>,
+    detected_files: DetectedFiles,
     scrollable_offset: RelativeOffset,
+    /// The selected series ids, `None` when not in selection mode
+    selection: Option<HashSet<u32>>,
+    tag_input: String,
+    bulk_action_running: bool,
 }
 
 impl<'a> MyShowsTab<'a> {
-    pub fn new(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-        scrollable_offset: Option<RelativeOffset>,
-    ) -> (Self, Command<Message>) {
-        let (untracked_releases, untracked_releases_commands) =
-            MyShows::new_as_untracked_series(series_page_sender.clone());
-        let (ended_releases, ended_releases_commands) =
-            MyShows::new_as_ended_tracked_series(series_page_sender.clone());
-        let (upcoming_releases, upcoming_releases_commands) =
-            UpcomingReleases::new(series_page_sender.clone());
-        let (waiting_releases, waiting_releases_commands) =
-            MyShows::new_as_waiting_release_series(series_page_sender);
-
-        (
-            Self {
-                ended_releases,
-                untracked_releases,
-                waiting_releases,
-                upcoming_releases,
-                scrollable_offset: scrollable_offset.unwrap_or(RelativeOffset::START),
-            },
-            Command::batch([
-                untracked_releases_commands.map(Message::Untracked),
-                ended_releases_commands.map(Message::Ended),
-                waiting_releases_commands.map(Message::Waiting),
-                upcoming_releases_commands.map(Message::Upcoming),
-            ]),
-        )
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        let status_groups = GROUPED_STATUSES
+            .into_iter()
+            .map(|status| {
+                (
+                    status,
+                    MyShows::new(
+                        MyShowsKind::SeriesWithStatus(status),
+                        series_page_sender.clone(),
+                    ),
+                )
+            })
+            .collect();
+
+        Self {
+            ended_releases: MyShows::new(
+                MyShowsKind::EndedTrackedSeries,
+                series_page_sender.clone(),
+            ),
+            untracked_releases: MyShows::new(
+                MyShowsKind::UntrackedSeries,
+                series_page_sender.clone(),
+            ),
+            waiting_releases: MyShows::new(
+                MyShowsKind::WaitingReleaseSeries,
+                series_page_sender.clone(),
+            ),
+            archived_series: MyShows::new(MyShowsKind::ArchivedSeries, series_page_sender.clone()),
+            show_archived: false,
+            upcoming_releases: UpcomingReleases::new(series_page_sender.clone()),
+            status_groups,
+            plan_to_watch: PlanToWatch::new(series_page_sender.clone()),
+            series_updates: SeriesUpdates::new(series_page_sender.clone()),
+            smart_lists: SmartLists::new(series_page_sender),
+            detected_files: DetectedFiles::default(),
+            scrollable_offset: RelativeOffset::START,
+            selection: None,
+            tag_input: String::new(),
+            bulk_action_running: false,
+        }
+    }
+
+    /// Reloads all the series of the tab, keeping the selection and scroll position
+    pub fn refresh(&self) -> Command<Message> {
+        Command::batch([
+            self.untracked_releases.refresh().map(Message::Untracked),
+            self.ended_releases.refresh().map(Message::Ended),
+            self.waiting_releases.refresh().map(Message::Waiting),
+            self.archived_series.refresh().map(Message::Archived),
+            self.upcoming_releases.refresh().map(Message::Upcoming),
+            Command::batch(self.status_groups.iter().map(|(status, status_group)| {
+                let status = *status;
+                status_group
+                    .refresh()
+                    .map(move |message| Message::Status(status, message))
+            })),
+            self.plan_to_watch.refresh().map(Message::PlanToWatch),
+            self.series_updates.refresh().map(Message::SeriesUpdates),
+            self.smart_lists.refresh().map(Message::SmartLists),
+        ])
     }
 
     pub fn subscription(&self) -> iced::Subscription<Message> {
         self.upcoming_releases.subscription().map(Message::Upcoming)
     }
 
+    /// The subscription kept running while other tabs are shown
+    pub fn background_subscription(&self) -> iced::Subscription<Message> {
+        self.detected_files
+            .subscription()
+            .map(Message::DetectedFiles)
+    }
+
     pub fn update(&mut self, message: Message) -> Command<Message> {
         match message {
+            Message::Ended(MyShowsMessage::SelectionToggled(series_id))
+            | Message::Waiting(MyShowsMessage::SelectionToggled(series_id))
+            | Message::Untracked(MyShowsMessage::SelectionToggled(series_id))
+            | Message::Archived(MyShowsMessage::SelectionToggled(series_id))
+            | Message::Status(_, MyShowsMessage::SelectionToggled(series_id))
+            | Message::PlanToWatch(PlanToWatchMessage::SelectionToggled(series_id))
+            | Message::Upcoming(UpcomingReleasesMessage::SelectionToggled(series_id)) => {
+                if let Some(selection) = self.selection.as_mut() {
+                    if !selection.remove(&series_id) {
+                        selection.insert(series_id);
+                    }
+                }
+                Command::none()
+            }
             Message::Ended(message) => self.ended_releases.update(message).map(Message::Ended),
             Message::Waiting(message) => {
                 self.waiting_releases.update(message).map(Message::Waiting)
@@ -82,21 +184,226 @@ impl<'a> MyShowsTab<'a> {
                 .untracked_releases
                 .update(message)
                 .map(Message::Untracked),
+            Message::Archived(message) => {
+                self.archived_series.update(message).map(Message::Archived)
+            }
+            Message::Status(status, message) => self
+                .status_groups
+                .iter_mut()
+                .find(|(group_status, _)| *group_status == status)
+                .map(|(_, status_group)| {
+                    status_group
+                        .update(message)
+                        .map(move |message| Message::Status(status, message))
+                })
+                .unwrap_or_else(Command::none),
+            Message::PlanToWatch(message) => {
+                self.plan_to_watch.update(message).map(Message::PlanToWatch)
+            }
+            Message::SeriesUpdates(message) => self
+                .series_updates
+                .update(message)
+                .map(Message::SeriesUpdates),
+            Message::SmartLists(message) => {
+                self.smart_lists.update(message).map(Message::SmartLists)
+            }
+            Message::DetectedFiles(message) => {
+                self.detected_files.update(message);
+                Command::none()
+            }
             Message::PageScrolled(view_port) => {
                 self.scrollable_offset = view_port.relative_offset();
                 Command::none()
             }
+            Message::ShowArchivedToggled(show_archived) => {
+                self.show_archived = show_archived;
+                Command::none()
+            }
+            Message::SelectionModeToggled => {
+                self.selection = match self.selection {
+                    Some(_) => None,
+                    None => Some(HashSet::new()),
+                };
+                Command::none()
+            }
+            Message::SelectAll => {
+                let series_ids = self
+                    .upcoming_releases
+                    .get_series_ids()
+                    .into_iter()
+                    .chain(self.waiting_releases.get_series_ids())
+                    .chain(self.ended_releases.get_series_ids())
+                    .chain(self.untracked_releases.get_series_ids())
+                    .chain(self.plan_to_watch.get_series_ids())
+                    .chain(if self.show_archived {
+                        self.archived_series.get_series_ids()
+                    } else {
+                        vec![]
+                    })
+                    .chain(
+                        self.status_groups
+                            .iter()
+                            .flat_map(|(_, status_group)| status_group.get_series_ids()),
+                    );
+
+                if let Some(selection) = self.selection.as_mut() {
+                    selection.extend(series_ids);
+                }
+                Command::none()
+            }
+            Message::TagInputChanged(tag) => {
+                self.tag_input = tag;
+                Command::none()
+            }
+            Message::BulkUntrack => {
+                let series_ids = self.selected_series_ids();
+                bulk_actions::untrack_series(&series_ids);
+                self.complete_bulk_action(Ok(format!("Untracked {} series", series_ids.len())))
+            }
+            Message::BulkStopFollowing => {
+                let series_ids = self.selected_series_ids();
+                bulk_actions::stop_following_series(&series_ids);
+                self.complete_bulk_action(Ok(format!(
+                    "Stopped following {} series",
+                    series_ids.len()
+                )))
+            }
+            Message::BulkTag => {
+                let tag = self.tag_input.trim().to_owned();
+                if tag.is_empty() {
+                    return Command::none();
+                }
+
+                let series_ids = self.selected_series_ids();
+                bulk_actions::tag_series(&series_ids, &tag);
+                self.tag_input.clear();
+                self.complete_bulk_action(Ok(format!(
+                    "Tagged {} series as '{}'",
+                    series_ids.len(),
+                    tag
+                )))
+            }
+            Message::BulkMarkAiredWatched => {
+                self.bulk_action_running = true;
+                let series_ids = self.selected_series_ids();
+
+                Command::perform(
+                    bulk_actions::mark_aired_episodes_watched(series_ids),
+                    |outcome| {
+                        let mut summary = format!(
+                            "Marked {} aired episodes as watched in {} series",
+                            outcome.marked_episodes, outcome.marked_series
+                        );
+                        if outcome.failed_series > 0 {
+                            summary.push_str(&format!(
+                                ", {} series failed to load",
+                                outcome.failed_series
+                            ));
+                        }
+                        Message::BulkActionCompleted(Ok(summary))
+                    },
+                )
+            }
+            Message::BulkActionCompleted(result) => self.complete_bulk_action(result),
         }
     }
 
-    pub fn view(&self) -> Element<Message, Renderer> {
-        let upcoming_releases = self.upcoming_releases.view().map(Message::Upcoming);
+    fn selected_series_ids(&self) -> Vec<u32> {
+        self.selection
+            .as_ref()
+            .map(|selection| selection.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Reports the outcome of a bulk action and reloads the tab to reflect it's changes
+    fn complete_bulk_action(&mut self, result: Result<String, String>) -> Command<Message> {
+        match result {
+            Ok(outcome) => toast::push(outcome),
+            Err(err) => toast::push(format!("Bulk action failed: {}", err)),
+        }
+
+        self.selection = None;
+        self.bulk_action_running = false;
+        self.refresh()
+    }
+
+    fn selection_bar(&self) -> Element<'_, Message, Renderer> {
+        let Some(selection) = &self.selection else {
+            return row![
+                horizontal_space(Length::Fill),
+                checkbox(
+                    "Show archived",
+                    self.show_archived,
+                    Message::ShowArchivedToggled
+                )
+                .text_size(13),
+                button(text("Select").size(13))
+                    .on_press(Message::SelectionModeToggled)
+                    .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .into();
+        };
+
+        let has_selection = !selection.is_empty() && !self.bulk_action_running;
+        let action_button = |label: &'static str, message: Message| {
+            let mut action_button = button(text(label).size(13))
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+            if has_selection {
+                action_button = action_button.on_press(message);
+            }
+            action_button
+        };
+
+        let status = if self.bulk_action_running {
+            text("Working...").size(13)
+        } else {
+            text(format!("{} selected", selection.len())).size(13)
+        };
+
+        let content = row![
+            status,
+            button(text("Select all").size(13))
+                .on_press(Message::SelectAll)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+            action_button("Untrack", Message::BulkUntrack),
+            action_button("Stop following", Message::BulkStopFollowing),
+            action_button("Mark aired watched", Message::BulkMarkAiredWatched),
+            text_input("tag", &self.tag_input)
+                .on_input(Message::TagInputChanged)
+                .on_submit(Message::BulkTag)
+                .size(13)
+                .width(150),
+            action_button("Tag", Message::BulkTag),
+            horizontal_space(Length::Fill),
+            button(text("Done").size(13))
+                .on_press(Message::SelectionModeToggled)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+        ]
+        .spacing(10)
+        .align_items(iced::Alignment::Center);
+
+        container(content)
+            .padding(5)
+            .width(Length::Fill)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .into()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let selection = self.selection.as_ref();
+
+        let upcoming_releases = self
+            .upcoming_releases
+            .view(selection)
+            .map(Message::Upcoming);
 
         let waiting_releases: Element<'_, Message, Renderer> = column![
             text("Waiting for release date")
                 .size(21)
                 .style(styles::text_styles::green_text_theme()),
-            self.waiting_releases.view().map(Message::Waiting)
+            self.waiting_releases.view(selection).map(Message::Waiting)
         ]
         .spacing(5)
         .into();
@@ -105,37 +412,128 @@ impl<'a> MyShowsTab<'a> {
             text("Ended")
                 .size(21)
                 .style(styles::text_styles::red_text_theme()),
-            self.ended_releases.view().map(Message::Ended)
+            self.ended_releases.view(selection).map(Message::Ended)
         ]
         .spacing(5)
         .into();
 
         let untracked_releases: Element<'_, Message, Renderer> = column![
             text("Untracked").size(21),
-            self.untracked_releases.view().map(Message::Untracked)
+            self.untracked_releases
+                .view(selection)
+                .map(Message::Untracked)
         ]
         .spacing(5)
         .into();
 
-        scrollable(
-            column![
-                upcoming_releases,
-                waiting_releases,
-                ended_releases,
-                untracked_releases,
-            ]
-            .padding(10)
-            .spacing(50)
-            .width(Length::Fill)
-            .align_items(iced::Alignment::Start),
+        let mut sections = column![];
+
+        if !self.detected_files.is_empty() {
+            sections = sections.push(
+                column![
+                    text("Detected Episodes").size(21),
+                    self.detected_files.view().map(Message::DetectedFiles)
+                ]
+                .spacing(5),
+            );
+        }
+
+        let mut sections = sections
+            .push(upcoming_releases)
+            .push(waiting_releases)
+            .push(ended_releases);
+
+        if !self.plan_to_watch.is_empty() {
+            sections = sections.push(
+                column![
+                    text("Plan to Watch").size(21),
+                    self.plan_to_watch.view(selection).map(Message::PlanToWatch)
+                ]
+                .spacing(5),
+            );
+        }
+
+        if !self.smart_lists.is_empty() {
+            sections = sections.push(
+                column![
+                    text("Smart Lists").size(21),
+                    self.smart_lists.view().map(Message::SmartLists)
+                ]
+                .spacing(5),
+            );
+        }
+
+        if !self.series_updates.is_empty() {
+            sections = sections.push(
+                column![
+                    text("Shows Updates").size(21),
+                    self.series_updates.view().map(Message::SeriesUpdates)
+                ]
+                .spacing(5),
+            );
+        }
+
+        // Only showing the statuses the user made use of
+        for (status, status_group) in self
+            .status_groups
+            .iter()
+            .filter(|(_, status_group)| !status_group.is_empty())
+        {
+            let status = *status;
+            sections = sections.push(
+                column![
+                    text(status.to_string()).size(21),
+                    status_group
+                        .view(selection)
+                        .map(move |message| Message::Status(status, message))
+                ]
+                .spacing(5),
+            );
+        }
+
+        if self.show_archived {
+            sections = sections.push(
+                column![
+                    text("Archived").size(21),
+                    self.archived_series.view(selection).map(Message::Archived)
+                ]
+                .spacing(5),
+            );
+        }
+
+        let content = scrollable(
+            sections
+                .push(untracked_releases)
+                .padding(10)
+                .spacing(50)
+                .width(Length::Fill)
+                .align_items(iced::Alignment::Start),
         )
         .direction(styles::scrollable_styles::vertical_direction())
         .id(Self::scrollable_id())
-        .on_scroll(Message::PageScrolled)
-        .into()
+        .on_scroll(Message::PageScrolled);
+
+        column![self.selection_bar(), content]
+            .padding(5)
+            .spacing(5)
+            .into()
     }
 }
 
+/// Shows a checkbox next to the given poster for selecting it's series
+fn selectable<'a, M: Clone + 'a>(
+    poster: Element<'a, M, Renderer>,
+    is_selected: bool,
+    on_toggle: M,
+) -> Element<'a, M, Renderer> {
+    row![
+        checkbox("", is_selected, move |_| on_toggle.clone()),
+        poster
+    ]
+    .spacing(2)
+    .into()
+}
+
 impl<'a> Tab for MyShowsTab<'a> {
     type Message = Message;
 
@@ -1,20 +1,26 @@
+use std::collections::HashSet;
 use std::sync::mpsc;
 
 use iced::widget::{container, text};
 use iced::{Command, Element, Length, Renderer};
-use iced_aw::{Spinner, Wrap};
+use iced_aw::Wrap;
 
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 use crate::core::caching;
-use crate::gui::styles;
+use crate::core::database::SeriesStatus;
 use crate::gui::troxide_widget::series_poster::{
     IndexedMessage, Message as SeriesPosterMessage, SeriesPoster,
 };
+use crate::gui::{helpers, styles};
+
+const SKELETON_POSTERS_AMOUNT: usize = 10;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     SeriesPosters(IndexedMessage<usize, SeriesPosterMessage>),
     SeriesInformationReceived(Option<Vec<SeriesMainInformation>>),
+    /// Handled by the tab as the selection spans all of it's widgets
+    SelectionToggled(u32),
 }
 
 #[derive(Default)]
@@ -24,70 +30,62 @@ enum LoadState {
     Loaded,
 }
 
+/// Which of the series in the database are shown
+#[derive(Clone, Copy)]
+pub enum MyShowsKind {
+    EndedTrackedSeries,
+    WaitingReleaseSeries,
+    UntrackedSeries,
+    SeriesWithStatus(SeriesStatus),
+    ArchivedSeries,
+}
+
 pub struct MyShows<'a> {
+    kind: MyShowsKind,
     load_state: LoadState,
     series_posters: Vec<SeriesPoster<'a>>,
     series_page_sender: mpsc::Sender<SeriesMainInformation>,
 }
 
 impl<'a> MyShows<'a> {
-    pub fn new_as_ended_tracked_series(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-    ) -> (Self, Command<Message>) {
-        (
-            Self {
-                load_state: LoadState::default(),
-                series_posters: vec![],
-                series_page_sender,
-            },
-            Command::perform(
-                async {
-                    caching::series_list::SeriesList::new()
-                        .get_ended_tracked_series_information()
-                        .await
-                },
-                move |res| Message::SeriesInformationReceived(res.ok()),
-            ),
-        )
-    }
-
-    pub fn new_as_waiting_release_series(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-    ) -> (Self, Command<Message>) {
-        (
-            Self {
-                load_state: LoadState::default(),
-                series_posters: vec![],
-                series_page_sender,
-            },
-            Command::perform(
-                async {
-                    caching::series_list::SeriesList::new()
-                        .get_waiting_release_series_information()
-                        .await
-                },
-                |res| Message::SeriesInformationReceived(res.ok()),
-            ),
-        )
+    pub fn new(kind: MyShowsKind, series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            kind,
+            load_state: LoadState::default(),
+            series_posters: vec![],
+            series_page_sender,
+        }
     }
 
-    pub fn new_as_untracked_series(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-    ) -> (Self, Command<Message>) {
-        (
-            Self {
-                load_state: LoadState::default(),
-                series_posters: vec![],
-                series_page_sender,
+    /// Reloads the series, the current ones stay shown until they are reloaded
+    pub fn refresh(&self) -> Command<Message> {
+        let kind = self.kind;
+        Command::perform(
+            async move {
+                let series_list =
+                    caching::series_list::SeriesList::with_status(SeriesStatus::Watching);
+                match kind {
+                    MyShowsKind::EndedTrackedSeries => {
+                        series_list.get_ended_tracked_series_information().await
+                    }
+                    MyShowsKind::WaitingReleaseSeries => {
+                        series_list.get_waiting_release_series_information().await
+                    }
+                    MyShowsKind::UntrackedSeries => {
+                        series_list.get_untracked_series_information().await
+                    }
+                    MyShowsKind::SeriesWithStatus(status) => {
+                        caching::series_list::SeriesList::get_series_information_with_status(status)
+                            .await
+                    }
+                    MyShowsKind::ArchivedSeries => {
+                        caching::series_list::SeriesList::archived()
+                            .get_all_series_information()
+                            .await
+                    }
+                }
             },
-            Command::perform(
-                async {
-                    caching::series_list::SeriesList::new()
-                        .get_untracked_series_information()
-                        .await
-                },
-                |res| Message::SeriesInformationReceived(res.ok()),
-            ),
+            |res| Message::SeriesInformationReceived(res.ok()),
         )
     }
 
@@ -115,20 +113,33 @@ impl<'a> MyShows<'a> {
                 self.series_posters = series_posters;
                 Command::batch(series_posters_commands).map(Message::SeriesPosters)
             }
-            Message::SeriesPosters(message) => self.series_posters[message.index()]
-                .update(message)
-                .map(Message::SeriesPosters),
+            // Posters of series since reloaded may still be sending messages
+            Message::SeriesPosters(message) => self
+                .series_posters
+                .get_mut(message.index())
+                .map(|poster| poster.update(message).map(Message::SeriesPosters))
+                .unwrap_or_else(Command::none),
+            Message::SelectionToggled(_) => Command::none(),
         }
     }
 
-    pub fn view(&self) -> Element<'_, Message, Renderer> {
+    /// Whether the widget is done loading and has no series to show
+    pub fn is_empty(&self) -> bool {
+        matches!(self.load_state, LoadState::Loaded) && self.series_posters.is_empty()
+    }
+
+    /// The ids of all the series shown by the widget
+    pub fn get_series_ids(&self) -> Vec<u32> {
+        self.series_posters
+            .iter()
+            .map(|poster| poster.get_series_info().id)
+            .collect()
+    }
+
+    /// Shows the posters with checkboxes when given the currently selected series
+    pub fn view(&self, selection: Option<&HashSet<u32>>) -> Element<'_, Message, Renderer> {
         if let LoadState::Loading = self.load_state {
-            return container(Spinner::new())
-                .center_x()
-                .center_y()
-                .height(100)
-                .width(Length::Fill)
-                .into();
+            return helpers::skeleton::series_posters(SKELETON_POSTERS_AMOUNT);
         }
         if self.series_posters.is_empty() {
             container(text("Nothing to show"))
@@ -142,7 +153,19 @@ impl<'a> MyShows<'a> {
             Wrap::with_elements(
                 self.series_posters
                     .iter()
-                    .map(|poster| poster.view(false).map(Message::SeriesPosters))
+                    .map(|poster| {
+                        let poster_view = poster.view(true).map(Message::SeriesPosters);
+                        let series_id = poster.get_series_info().id;
+
+                        match selection {
+                            Some(selection) => super::selectable(
+                                poster_view,
+                                selection.contains(&series_id),
+                                Message::SelectionToggled(series_id),
+                            ),
+                            None => poster_view,
+                        }
+                    })
                     .collect(),
             )
             .line_spacing(5.0)
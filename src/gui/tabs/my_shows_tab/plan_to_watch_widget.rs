@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::sync::mpsc;
+
+use iced::widget::{button, column, row, text, Column};
+use iced::{Alignment, Command, Element, Renderer};
+
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::caching;
+use crate::core::database::{self, SeriesStatus};
+use crate::core::hooks::{self, HookEvent};
+use crate::gui::troxide_widget::series_poster::{
+    IndexedMessage, Message as SeriesPosterMessage, SeriesPoster,
+};
+use crate::gui::{helpers, styles, toast};
+
+const SKELETON_POSTERS_AMOUNT: usize = 10;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SeriesPosters(IndexedMessage<usize, SeriesPosterMessage>),
+    SeriesInformationReceived(Option<Vec<SeriesMainInformation>>),
+    MoveUpPressed(u32),
+    MoveDownPressed(u32),
+    TrackPressed(u32),
+    RemovePressed(u32),
+    /// Handled by the tab as the selection spans all of it's widgets
+    SelectionToggled(u32),
+}
+
+#[derive(Default)]
+enum LoadState {
+    #[default]
+    Loading,
+    Loaded,
+}
+
+/// The series planned to be watched, ordered by priority
+pub struct PlanToWatch<'a> {
+    load_state: LoadState,
+    series_posters: Vec<SeriesPoster<'a>>,
+    series_page_sender: mpsc::Sender<SeriesMainInformation>,
+}
+
+impl<'a> PlanToWatch<'a> {
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            load_state: LoadState::default(),
+            series_posters: vec![],
+            series_page_sender,
+        }
+    }
+
+    pub fn refresh(&self) -> Command<Message> {
+        Command::perform(
+            caching::series_list::SeriesList::get_series_information_with_status(
+                SeriesStatus::PlanToWatch,
+            ),
+            |res| Message::SeriesInformationReceived(res.ok()),
+        )
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::SeriesInformationReceived(series_infos) => {
+                self.load_state = LoadState::Loaded;
+
+                let mut series_posters_commands = vec![];
+                let mut series_posters = vec![];
+
+                for (index, series_info) in series_infos.unwrap().into_iter().enumerate() {
+                    let (poster, command) = SeriesPoster::new(
+                        index,
+                        std::borrow::Cow::Owned(series_info),
+                        self.series_page_sender.clone(),
+                    );
+                    series_posters.push(poster);
+                    series_posters_commands.push(command);
+                }
+                self.series_posters = series_posters;
+                return Command::batch(series_posters_commands).map(Message::SeriesPosters);
+            }
+            Message::SeriesPosters(message) => {
+                // Posters of series since reloaded may still be sending messages
+                if let Some(poster) = self.series_posters.get_mut(message.index()) {
+                    return poster.update(message).map(Message::SeriesPosters);
+                }
+            }
+            Message::MoveUpPressed(series_id) => {
+                database::DB.move_in_plan_to_watch_queue(series_id, true)
+            }
+            Message::MoveDownPressed(series_id) => {
+                database::DB.move_in_plan_to_watch_queue(series_id, false)
+            }
+            Message::TrackPressed(series_id) => {
+                if let Some(series_info) = self.get_series_info(series_id) {
+                    track_series(series_info);
+                }
+            }
+            Message::RemovePressed(series_id) => {
+                database::DB.set_series_status(series_id, SeriesStatus::Watching)
+            }
+            Message::SelectionToggled(_) => {}
+        }
+        Command::none()
+    }
+
+    fn get_series_info(&self, series_id: u32) -> Option<&SeriesMainInformation> {
+        self.series_posters
+            .iter()
+            .map(|poster| poster.get_series_info())
+            .find(|series_info| series_info.id == series_id)
+    }
+
+    /// The posters still in the queue, from the highest priority
+    fn queued_posters(&self) -> Vec<&SeriesPoster<'a>> {
+        database::DB
+            .get_plan_to_watch_queue()
+            .into_iter()
+            .filter_map(|series_id| {
+                self.series_posters
+                    .iter()
+                    .find(|poster| poster.get_series_info().id == series_id)
+            })
+            .collect()
+    }
+
+    /// Whether the widget is done loading and has no series to show
+    pub fn is_empty(&self) -> bool {
+        matches!(self.load_state, LoadState::Loaded) && self.queued_posters().is_empty()
+    }
+
+    /// The ids of all the series shown by the widget
+    pub fn get_series_ids(&self) -> Vec<u32> {
+        self.queued_posters()
+            .into_iter()
+            .map(|poster| poster.get_series_info().id)
+            .collect()
+    }
+
+    /// Shows the posters with checkboxes when given the currently selected series
+    pub fn view(&self, selection: Option<&HashSet<u32>>) -> Element<'_, Message, Renderer> {
+        if let LoadState::Loading = self.load_state {
+            return helpers::skeleton::series_posters(SKELETON_POSTERS_AMOUNT);
+        }
+
+        let queued_posters = self.queued_posters();
+        let last_position = queued_posters.len().saturating_sub(1);
+
+        Column::with_children(
+            queued_posters
+                .into_iter()
+                .enumerate()
+                .map(|(position, poster)| {
+                    let series_id = poster.get_series_info().id;
+                    let poster_view = poster.view(true).map(Message::SeriesPosters);
+
+                    let poster_view = match selection {
+                        Some(selection) => super::selectable(
+                            poster_view,
+                            selection.contains(&series_id),
+                            Message::SelectionToggled(series_id),
+                        ),
+                        None => poster_view,
+                    };
+
+                    let action_button = |label: &'static str, message: Option<Message>| {
+                        let action_button = button(text(label).size(11)).style(
+                            styles::button_styles::transparent_button_with_rounded_border_theme(),
+                        );
+                        match message {
+                            Some(message) => action_button.on_press(message),
+                            None => action_button,
+                        }
+                    };
+
+                    let actions = column![
+                        action_button(
+                            "Move up",
+                            (position != 0).then_some(Message::MoveUpPressed(series_id))
+                        ),
+                        action_button(
+                            "Move down",
+                            (position != last_position)
+                                .then_some(Message::MoveDownPressed(series_id))
+                        ),
+                        action_button("Start tracking", Some(Message::TrackPressed(series_id))),
+                        action_button("Remove", Some(Message::RemovePressed(series_id))),
+                    ]
+                    .spacing(5);
+
+                    row![
+                        text(format!("{}.", position + 1))
+                            .size(21)
+                            .style(styles::text_styles::accent_color_theme()),
+                        poster_view,
+                        actions
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5)
+        .into()
+    }
+}
+
+/// Tracks the series taking it off the plan to watch queue
+fn track_series(series_info: &SeriesMainInformation) {
+    if let Some(mut series) = database::DB.get_series(series_info.id) {
+        series.mark_tracked();
+    } else {
+        let mut series = database::Series::new(series_info.name.to_owned(), series_info.id);
+        series.mark_tracked();
+        database::DB.add_series(series_info.id, &series);
+    }
+    database::DB.set_series_status(series_info.id, SeriesStatus::Watching);
+
+    toast::push(format!("Tracking {}", series_info.name));
+    hooks::trigger(HookEvent::series_tracked(
+        series_info.id,
+        series_info.name.to_owned(),
+    ));
+}
@@ -0,0 +1,171 @@
+use std::sync::mpsc;
+
+use chrono::Utc;
+use iced::widget::{column, row, text, Column};
+use iced::{Alignment, Command, Element, Renderer};
+
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::caching::refresh_queue::{self, RefreshPriority};
+use crate::core::caching::series_information;
+use crate::core::database::{SeriesUpdate, DB};
+use crate::gui::troxide_widget::series_poster::{
+    IndexedMessage, Message as SeriesPosterMessage, SeriesPoster,
+};
+use crate::gui::{helpers, styles};
+
+/// How many days back the updates of the series are shown
+const UPDATES_DAYS: i64 = 14;
+
+const SKELETON_POSTERS_AMOUNT: usize = 3;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SeriesPosters(IndexedMessage<usize, SeriesPosterMessage>),
+    UpdatesLoaded(Option<Vec<(SeriesMainInformation, SeriesUpdate)>>),
+}
+
+#[derive(Default)]
+enum LoadState {
+    #[default]
+    Loading,
+    Loaded,
+}
+
+/// The series in the database recently updated on TVmaze along with what changed
+pub struct SeriesUpdates<'a> {
+    load_state: LoadState,
+    series_posters: Vec<(SeriesPoster<'a>, SeriesUpdate)>,
+    series_page_sender: mpsc::Sender<SeriesMainInformation>,
+}
+
+impl<'a> SeriesUpdates<'a> {
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            load_state: LoadState::default(),
+            series_posters: vec![],
+            series_page_sender,
+        }
+    }
+
+    pub fn refresh(&self) -> Command<Message> {
+        Command::perform(load_series_updates(), |series_updates| {
+            Message::UpdatesLoaded(
+                series_updates
+                    .map_err(|err| tracing::error!("failed to load series updates: {}", err))
+                    .ok(),
+            )
+        })
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::UpdatesLoaded(series_updates) => {
+                self.load_state = LoadState::Loaded;
+
+                let mut series_posters_commands = vec![];
+                let mut series_posters = vec![];
+
+                for (index, (series_info, series_update)) in
+                    series_updates.unwrap_or_default().into_iter().enumerate()
+                {
+                    let (poster, command) = SeriesPoster::new(
+                        index,
+                        std::borrow::Cow::Owned(series_info),
+                        self.series_page_sender.clone(),
+                    );
+                    series_posters.push((poster, series_update));
+                    series_posters_commands.push(command);
+                }
+                self.series_posters = series_posters;
+                Command::batch(series_posters_commands).map(Message::SeriesPosters)
+            }
+            // Posters of updates since reloaded may still be sending messages
+            Message::SeriesPosters(message) => self
+                .series_posters
+                .get_mut(message.index())
+                .map(|(poster, _)| poster.update(message).map(Message::SeriesPosters))
+                .unwrap_or_else(Command::none),
+        }
+    }
+
+    /// Whether the widget is done loading and has no updates to show
+    pub fn is_empty(&self) -> bool {
+        matches!(self.load_state, LoadState::Loaded) && self.series_posters.is_empty()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        if let LoadState::Loading = self.load_state {
+            return helpers::skeleton::series_posters(SKELETON_POSTERS_AMOUNT);
+        }
+
+        Column::with_children(
+            self.series_posters
+                .iter()
+                .map(|(poster, series_update)| {
+                    let changes: Element<'_, Message, Renderer> =
+                        if series_update.changes.is_empty() {
+                            text("Minor details updated").size(11).into()
+                        } else {
+                            Column::with_children(
+                                series_update
+                                    .changes
+                                    .iter()
+                                    .map(|change| {
+                                        text(change.to_string())
+                                            .size(11)
+                                            .style(styles::text_styles::accent_color_theme())
+                                            .into()
+                                    })
+                                    .collect(),
+                            )
+                            .spacing(2)
+                            .into()
+                        };
+
+                    row![
+                        poster.view(true).map(Message::SeriesPosters),
+                        column![text(updated_ago(series_update)).size(11), changes].spacing(5)
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5)
+        .into()
+    }
+}
+
+fn updated_ago(series_update: &SeriesUpdate) -> String {
+    match (Utc::now().timestamp() - series_update.updated_at) / (60 * 60 * 24) {
+        0 => "Updated today".to_owned(),
+        1 => "Updated yesterday".to_owned(),
+        days => format!("Updated {} days ago", days),
+    }
+}
+
+/// The recent updates of the series in the database along with their series information
+async fn load_series_updates() -> anyhow::Result<Vec<(SeriesMainInformation, SeriesUpdate)>> {
+    let oldest_update = Utc::now().timestamp() - UPDATES_DAYS * 60 * 60 * 24;
+
+    let handles: Vec<_> = DB
+        .get_series_updates()
+        .into_iter()
+        .filter(|series_update| series_update.updated_at >= oldest_update)
+        .filter(|series_update| DB.get_series(series_update.series_id).is_some())
+        .map(|series_update| {
+            refresh_queue::spawn(RefreshPriority::Visible, async move {
+                series_information::get_series_main_info_with_id(series_update.series_id)
+                    .await
+                    .map(|series_info| (series_info, series_update))
+            })
+        })
+        .collect();
+
+    let mut series_updates = Vec::with_capacity(handles.len());
+    for handle in handles {
+        series_updates.push(handle.await??);
+    }
+    Ok(series_updates)
+}
@@ -0,0 +1,204 @@
+use std::sync::mpsc;
+
+use iced::widget::{button, column, container, text, Row};
+use iced::{Command, Element, Length, Renderer};
+use iced_aw::Wrap;
+
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::database::{SmartList, DB};
+use crate::core::smart_lists;
+use crate::gui::troxide_widget::series_poster::{
+    IndexedMessage, Message as SeriesPosterMessage, SeriesPoster,
+};
+use crate::gui::{helpers, styles, toast};
+
+const SKELETON_POSTERS_AMOUNT: usize = 5;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SmartListsLoaded(Vec<SmartList>),
+    SmartListPressed(String),
+    /// The name of the evaluated smart list with it's series
+    SmartListEvaluated(String, Option<Vec<SeriesMainInformation>>),
+    DeletePressed,
+    SeriesPosters(IndexedMessage<usize, SeriesPosterMessage>),
+}
+
+enum LoadState {
+    Loading,
+    Loaded,
+    Failed,
+}
+
+/// The saved advanced searches, the opened one evaluated again every time it's opened
+pub struct SmartLists<'a> {
+    smart_lists: Vec<SmartList>,
+    /// The name of the opened smart list
+    opened: Option<String>,
+    load_state: LoadState,
+    series_posters: Vec<SeriesPoster<'a>>,
+    series_page_sender: mpsc::Sender<SeriesMainInformation>,
+}
+
+impl<'a> SmartLists<'a> {
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            smart_lists: vec![],
+            opened: None,
+            load_state: LoadState::Loading,
+            series_posters: vec![],
+            series_page_sender,
+        }
+    }
+
+    /// Reloads the saved smart lists, the opened one is only evaluated again when pressed
+    pub fn refresh(&self) -> Command<Message> {
+        Command::perform(async { DB.get_smart_lists() }, Message::SmartListsLoaded)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.smart_lists.is_empty()
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::SmartListsLoaded(smart_lists) => {
+                self.smart_lists = smart_lists;
+
+                let is_opened_removed = self.opened.as_ref().is_some_and(|opened| {
+                    !self
+                        .smart_lists
+                        .iter()
+                        .any(|smart_list| smart_list.name == *opened)
+                });
+                if is_opened_removed {
+                    self.opened = None;
+                    self.series_posters.clear();
+                }
+                Command::none()
+            }
+            Message::SmartListPressed(name) => {
+                let Some(smart_list) = self
+                    .smart_lists
+                    .iter()
+                    .find(|smart_list| smart_list.name == name)
+                    .cloned()
+                else {
+                    return Command::none();
+                };
+
+                self.opened = Some(name.clone());
+                self.load_state = LoadState::Loading;
+
+                Command::perform(smart_lists::evaluate(smart_list), move |series_infos| {
+                    Message::SmartListEvaluated(
+                        name,
+                        series_infos
+                            .map_err(|err| {
+                                tracing::error!("failed to evaluate smart list: {}", err)
+                            })
+                            .ok(),
+                    )
+                })
+            }
+            Message::SmartListEvaluated(name, series_infos) => {
+                // A smart list opened since is the one to show
+                if self.opened.as_ref() != Some(&name) {
+                    return Command::none();
+                }
+
+                let Some(series_infos) = series_infos else {
+                    self.load_state = LoadState::Failed;
+                    return Command::none();
+                };
+                self.load_state = LoadState::Loaded;
+
+                let mut series_posters_commands = Vec::with_capacity(series_infos.len());
+                let mut series_posters = Vec::with_capacity(series_infos.len());
+
+                for (index, series_info) in series_infos.into_iter().enumerate() {
+                    let (poster, command) = SeriesPoster::new(
+                        index,
+                        std::borrow::Cow::Owned(series_info),
+                        self.series_page_sender.clone(),
+                    );
+                    series_posters.push(poster);
+                    series_posters_commands.push(command);
+                }
+                self.series_posters = series_posters;
+                Command::batch(series_posters_commands).map(Message::SeriesPosters)
+            }
+            Message::DeletePressed => {
+                if let Some(name) = self.opened.take() {
+                    DB.remove_smart_list(&name);
+                    self.series_posters.clear();
+                    toast::push(format!("Deleted the smart list '{}'", name));
+                }
+                self.refresh()
+            }
+            // Posters of smart lists since closed may still be sending messages
+            Message::SeriesPosters(message) => self
+                .series_posters
+                .get_mut(message.index())
+                .map(|poster| poster.update(message).map(Message::SeriesPosters))
+                .unwrap_or_else(Command::none),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let smart_list_buttons = Row::with_children(
+            self.smart_lists
+                .iter()
+                .map(|smart_list| {
+                    let is_opened = self.opened.as_ref() == Some(&smart_list.name);
+                    button(text(&smart_list.name).size(13))
+                        .on_press(Message::SmartListPressed(smart_list.name.clone()))
+                        .style(if is_opened {
+                            styles::button_styles::transparent_button_with_rounded_border_theme()
+                        } else {
+                            styles::button_styles::transparent_button_theme()
+                        })
+                        .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let mut content = column![smart_list_buttons].spacing(10);
+
+        if self.opened.is_some() {
+            let series: Element<'_, Message, Renderer> = match self.load_state {
+                LoadState::Loading => helpers::skeleton::series_posters(SKELETON_POSTERS_AMOUNT),
+                LoadState::Failed => text("Failed to load the smart list")
+                    .style(styles::text_styles::red_text_theme())
+                    .into(),
+                LoadState::Loaded if self.series_posters.is_empty() => {
+                    container(text("No series match this smart list"))
+                        .style(styles::container_styles::first_class_container_square_theme())
+                        .center_x()
+                        .center_y()
+                        .height(200)
+                        .width(Length::Fill)
+                        .into()
+                }
+                LoadState::Loaded => Wrap::with_elements(
+                    self.series_posters
+                        .iter()
+                        .map(|poster| poster.view(true).map(Message::SeriesPosters))
+                        .collect(),
+                )
+                .line_spacing(5.0)
+                .spacing(5.0)
+                .into(),
+            };
+
+            content = content.push(series).push(
+                button(text("Delete smart list").size(11))
+                    .on_press(Message::DeletePressed)
+                    .style(styles::button_styles::transparent_button_theme()),
+            );
+        }
+
+        content.into()
+    }
+}
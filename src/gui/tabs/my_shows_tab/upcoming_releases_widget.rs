@@ -1,13 +1,14 @@
+use std::collections::HashSet;
 use std::sync::mpsc;
 
 use iced::widget::{container, text, Column};
 use iced::{Command, Element, Length, Renderer};
 use iced_aw::Spinner;
 
-use crate::core::api::tv_maze::episodes_information::Episode;
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 use crate::core::caching;
-use crate::core::caching::episode_list::EpisodeReleaseTime;
+use crate::core::caching::series_list::UpcomingRelease;
+use crate::core::database::SeriesStatus;
 use crate::gui::message::IndexedMessage;
 use crate::gui::{helpers, styles};
 use upcoming_poster::{Message as UpcomingPosterMessage, UpcomingPoster};
@@ -15,8 +16,10 @@ use upcoming_poster::{Message as UpcomingPosterMessage, UpcomingPoster};
 #[derive(Debug, Clone)]
 pub enum Message {
     UpcomingPoster(IndexedMessage<usize, UpcomingPosterMessage>),
-    SeriesInformationReceived(Option<Vec<(SeriesMainInformation, Episode, EpisodeReleaseTime)>>),
+    SeriesInformationReceived(Option<Vec<UpcomingRelease>>),
     Refresh,
+    /// Handled by the tab as the selection spans all of it's widgets
+    SelectionToggled(u32),
 }
 
 #[derive(Default)]
@@ -33,16 +36,22 @@ pub struct UpcomingReleases<'a> {
 }
 
 impl<'a> UpcomingReleases<'a> {
-    pub fn new(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-    ) -> (Self, Command<Message>) {
-        (
-            Self {
-                load_state: LoadState::default(),
-                upcoming_posters: vec![],
-                series_page_sender,
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            load_state: LoadState::default(),
+            upcoming_posters: vec![],
+            series_page_sender,
+        }
+    }
+
+    pub fn refresh(&self) -> Command<Message> {
+        Command::perform(
+            async {
+                caching::series_list::SeriesList::with_status(SeriesStatus::Watching)
+                    .get_upcoming_release_series_information_and_episodes()
+                    .await
             },
-            load_upcoming_releases(),
+            |res| Message::SeriesInformationReceived(res.ok()),
         )
     }
 
@@ -76,12 +85,12 @@ impl<'a> UpcomingReleases<'a> {
                 let mut series_infos = series_infos.unwrap();
 
                 // sorting the list according to release time
-                series_infos.sort_by_key(|(_, _, release_time)| release_time.clone());
+                series_infos.sort_by_key(|(_, _, release_time, _)| release_time.clone());
 
                 let mut series_posters_commands = Vec::with_capacity(series_infos.len());
                 let mut series_posters = Vec::with_capacity(series_infos.len());
 
-                for (index, (series_info, episode, release_time)) in
+                for (index, (series_info, episode, release_time, badge)) in
                     series_infos.into_iter().enumerate()
                 {
                     let (poster, command) = UpcomingPoster::new(
@@ -90,6 +99,7 @@ impl<'a> UpcomingReleases<'a> {
                         self.series_page_sender.clone(),
                         episode,
                         release_time,
+                        badge,
                     );
                     series_posters.push(poster);
                     series_posters_commands.push(command);
@@ -97,14 +107,27 @@ impl<'a> UpcomingReleases<'a> {
                 self.upcoming_posters = series_posters;
                 Command::batch(series_posters_commands).map(Message::UpcomingPoster)
             }
-            Message::UpcomingPoster(message) => self.upcoming_posters[message.index()]
-                .update(message)
-                .map(Message::UpcomingPoster),
-            Message::Refresh => load_upcoming_releases(),
+            // Posters of releases since reloaded may still be sending messages
+            Message::UpcomingPoster(message) => self
+                .upcoming_posters
+                .get_mut(message.index())
+                .map(|poster| poster.update(message).map(Message::UpcomingPoster))
+                .unwrap_or_else(Command::none),
+            Message::Refresh => self.refresh(),
+            Message::SelectionToggled(_) => Command::none(),
         }
     }
 
-    pub fn view(&self) -> Element<'_, Message, Renderer> {
+    /// The ids of all the series shown by the widget
+    pub fn get_series_ids(&self) -> Vec<u32> {
+        self.upcoming_posters
+            .iter()
+            .map(|poster| poster.get_series_id())
+            .collect()
+    }
+
+    /// Shows the posters with checkboxes when given the currently selected series
+    pub fn view(&self, selection: Option<&HashSet<u32>>) -> Element<'_, Message, Renderer> {
         if let LoadState::Loading = self.load_state {
             return container(Spinner::new())
                 .center_x()
@@ -125,7 +148,19 @@ impl<'a> UpcomingReleases<'a> {
             Column::with_children(
                 self.upcoming_posters
                     .iter()
-                    .map(|poster| poster.view().map(Message::UpcomingPoster))
+                    .map(|poster| {
+                        let poster_view = poster.view().map(Message::UpcomingPoster);
+                        let series_id = poster.get_series_id();
+
+                        match selection {
+                            Some(selection) => super::selectable(
+                                poster_view,
+                                selection.contains(&series_id),
+                                Message::SelectionToggled(series_id),
+                            ),
+                            None => poster_view,
+                        }
+                    })
                     .collect(),
             )
             .spacing(5)
@@ -136,24 +171,13 @@ impl<'a> UpcomingReleases<'a> {
     }
 }
 
-fn load_upcoming_releases() -> Command<Message> {
-    Command::perform(
-        async {
-            caching::series_list::SeriesList::new()
-                .get_upcoming_release_series_information_and_episodes()
-                .await
-        },
-        |res| Message::SeriesInformationReceived(res.ok()),
-    )
-}
-
 mod upcoming_poster {
     use std::sync::mpsc;
 
     use crate::core::api::tv_maze::episodes_information::Episode;
     use crate::core::{
         api::tv_maze::series_information::SeriesMainInformation,
-        caching::episode_list::EpisodeReleaseTime,
+        caching::episode_list::{EpisodeBadge, EpisodeReleaseTime},
     };
     use crate::gui::helpers::{self, season_episode_str_gen};
     use crate::gui::message::IndexedMessage;
@@ -176,6 +200,7 @@ mod upcoming_poster {
         poster: GenericPoster<'a>,
         upcoming_episode: Episode,
         episode_release_time: EpisodeReleaseTime,
+        badge: Option<EpisodeBadge>,
     }
 
     impl<'a> UpcomingPoster<'a> {
@@ -185,6 +210,7 @@ mod upcoming_poster {
             series_page_sender: mpsc::Sender<SeriesMainInformation>,
             upcoming_episode: Episode,
             episode_release_time: EpisodeReleaseTime,
+            badge: Option<EpisodeBadge>,
         ) -> (Self, Command<IndexedMessage<usize, Message>>) {
             let (poster, poster_command) = GenericPoster::new(series_info, series_page_sender);
             (
@@ -193,6 +219,7 @@ mod upcoming_poster {
                     poster,
                     upcoming_episode,
                     episode_release_time,
+                    badge,
                 },
                 poster_command
                     .map(Message::Poster)
@@ -200,6 +227,10 @@ mod upcoming_poster {
             )
         }
 
+        pub fn get_series_id(&self) -> u32 {
+            self.poster.get_series_info().id
+        }
+
         pub fn get_episode_release_time(&self) -> &EpisodeReleaseTime {
             &self.episode_release_time
         }
@@ -247,11 +278,20 @@ mod upcoming_poster {
 
             let episode_name = &self.upcoming_episode.name;
 
-            metadata = metadata.push(text(format!(
+            let mut episode_heading = row![text(format!(
                 "{}: {}",
                 season_episode_str_gen(season_number, episode_number),
                 episode_name,
-            )));
+            ))]
+            .spacing(5)
+            .align_items(iced::Alignment::Center);
+
+            if let Some(badge) = self.badge {
+                episode_heading =
+                    episode_heading.push(helpers::episode_badge::episode_badge(badge));
+            }
+
+            metadata = metadata.push(episode_heading);
 
             metadata = metadata.push(text(&self.episode_release_time));
 
@@ -0,0 +1,125 @@
+use iced::widget::{button, column, container, row, text, text_input};
+use iced::{Alignment, Element, Length, Renderer};
+use iced_aw::NumberInput;
+
+use crate::core::app_lock::get_app_lock_settings;
+use crate::core::settings_config::SETTINGS;
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PasswordInputChanged(String),
+    SetPasswordPressed,
+    RemoveLockPressed,
+    IdleMinutesChanged(u32),
+}
+
+#[derive(Default)]
+pub struct AppLock {
+    password_input: String,
+}
+
+impl AppLock {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::PasswordInputChanged(password) => self.password_input = password,
+            Message::SetPasswordPressed => {
+                if !self.password_input.is_empty() {
+                    SETTINGS
+                        .write()
+                        .unwrap()
+                        .change_settings()
+                        .app_lock
+                        .set_password(&self.password_input);
+                    self.password_input.clear();
+                }
+            }
+            Message::RemoveLockPressed => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .app_lock
+                    .password_hash = None;
+            }
+            Message::IdleMinutesChanged(idle_minutes) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .app_lock
+                    .idle_minutes = idle_minutes;
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let app_lock_settings = get_app_lock_settings();
+
+        let lock_text = if app_lock_settings.is_enabled() {
+            "Series Troxide asks for the PIN or password when it starts"
+        } else {
+            "Set a PIN or password asked when Series Troxide starts, to keep your notes and history private on shared computers"
+        };
+
+        let mut password_controls = row![text_input("new PIN or password", &self.password_input)
+            .on_input(Message::PasswordInputChanged)
+            .password()
+            .width(250)]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        password_controls = password_controls.push(if self.password_input.is_empty() {
+            button("Set")
+        } else {
+            button("Set").on_press(Message::SetPasswordPressed)
+        });
+
+        if app_lock_settings.is_enabled() {
+            password_controls =
+                password_controls.push(button("Remove Lock").on_press(Message::RemoveLockPressed));
+        }
+
+        let mut content = column![
+            text("App Lock")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            text(lock_text).size(11),
+            text("Only this window is locked, the history command line and exported notes can still be read by anyone using this computer").size(11),
+            password_controls,
+        ]
+        .spacing(5);
+
+        if app_lock_settings.is_enabled() {
+            let idle_text = if app_lock_settings.idle_minutes == 0 {
+                "Never locked again while running, set the minutes without any input to lock after"
+                    .to_owned()
+            } else {
+                format!(
+                    "Locked again after {} minutes without any input",
+                    app_lock_settings.idle_minutes
+                )
+            };
+
+            content = content.push(
+                column![
+                    text("Idle time"),
+                    text(idle_text).size(11),
+                    NumberInput::new(
+                        app_lock_settings.idle_minutes,
+                        u32::MAX,
+                        Message::IdleMinutesChanged
+                    )
+                    .width(Length::Fixed(200.0)),
+                ]
+                .spacing(5),
+            );
+        }
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
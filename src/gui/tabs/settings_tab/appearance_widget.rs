@@ -1,12 +1,19 @@
 use iced::widget::{column, container, horizontal_space, radio, text, Column};
 use iced::{Element, Renderer};
 
-use crate::core::settings_config::{Theme, ALL_THEMES, SETTINGS};
+use crate::core::settings_config::{
+    DateFormat, Layout, Theme, TimeFormat, WeekStart, ALL_DATE_FORMATS, ALL_LAYOUTS, ALL_THEMES,
+    ALL_TIME_FORMATS, ALL_WEEK_STARTS, SETTINGS,
+};
 use crate::gui::styles;
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    ThemeSelected(Theme),
+    Theme(Theme),
+    Layout(Layout),
+    DateFormat(DateFormat),
+    TimeFormat(TimeFormat),
+    WeekStart(WeekStart),
 }
 
 #[derive(Default)]
@@ -15,13 +22,45 @@ pub struct Appearance;
 impl Appearance {
     pub fn update(&mut self, message: Message) {
         match message {
-            Message::ThemeSelected(theme) => {
+            Message::Theme(theme) => {
                 SETTINGS.write().unwrap().change_settings().appearance.theme = theme;
             }
+            Message::Layout(layout) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .appearance
+                    .layout = layout;
+            }
+            Message::DateFormat(date_format) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .locale
+                    .date_format = date_format;
+            }
+            Message::TimeFormat(time_format) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .locale
+                    .time_format = time_format;
+            }
+            Message::WeekStart(week_start) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .locale
+                    .week_start = week_start;
+            }
         }
     }
 
-    pub fn view(&self) -> Element<Message, Renderer> {
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
         let content = column![text("Appearance")
             .size(21)
             .style(styles::text_styles::accent_color_theme())]
@@ -46,7 +85,7 @@ impl Appearance {
                 .map(|theme| {
                     let elem: Element<'_, Message, Renderer> =
                         radio(theme.to_string(), theme, current_theme.as_ref(), |theme| {
-                            Message::ThemeSelected(theme.clone())
+                            Message::Theme(theme.clone())
                         })
                         .into();
                     elem
@@ -61,6 +100,128 @@ impl Appearance {
                 .spacing(5),
         );
 
+        let current_layout = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .appearance
+            .layout;
+
+        let layout_list = Column::with_children(
+            ALL_LAYOUTS
+                .into_iter()
+                .map(|layout| {
+                    radio(
+                        layout.to_string(),
+                        layout,
+                        Some(current_layout),
+                        Message::Layout,
+                    )
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let content = content.push(
+            column!(
+                text("Layout").size(18),
+                text("Show series pages next to My Shows and Discover instead of in place of them, Auto does so on wide windows").size(11),
+                layout_list
+            )
+            .padding(5)
+            .spacing(5),
+        );
+
+        let locale = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .locale
+            .clone();
+
+        let date_format_list = Column::with_children(
+            ALL_DATE_FORMATS
+                .into_iter()
+                .map(|date_format| {
+                    radio(
+                        date_format.to_string(),
+                        date_format,
+                        Some(locale.date_format),
+                        Message::DateFormat,
+                    )
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let time_format_list = Column::with_children(
+            ALL_TIME_FORMATS
+                .into_iter()
+                .map(|time_format| {
+                    radio(
+                        time_format.to_string(),
+                        time_format,
+                        Some(locale.time_format),
+                        Message::TimeFormat,
+                    )
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let week_start_list = Column::with_children(
+            ALL_WEEK_STARTS
+                .into_iter()
+                .map(|week_start| {
+                    radio(
+                        week_start.to_string(),
+                        week_start,
+                        Some(locale.week_start),
+                        Message::WeekStart,
+                    )
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let content = content.push(
+            column!(
+                text("Date Format").size(18),
+                text(
+                    "How dates are written in the schedule, episodes, statistics and notifications"
+                )
+                .size(11),
+                date_format_list
+            )
+            .padding(5)
+            .spacing(5),
+        );
+
+        let content = content.push(
+            column!(
+                text("Time Format").size(18),
+                text("How air times are written in the episodes, the schedule and notifications")
+                    .size(11),
+                time_format_list
+            )
+            .padding(5)
+            .spacing(5),
+        );
+
+        let content = content.push(
+            column!(
+                text("First Day of the Week").size(18),
+                text("Where weeks start, like for a weekly watch goal").size(11),
+                week_start_list
+            )
+            .padding(5)
+            .spacing(5),
+        );
+
         container(content)
             .style(styles::container_styles::first_class_container_rounded_theme())
             .width(1000)
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use iced::widget::{column, container, text};
+use iced::{Element, Renderer};
+use iced_aw::Grid;
+
+use crate::core::background::{self, TaskKind, TaskStats};
+use crate::gui::styles;
+
+/// How often the numbers are refreshed while the settings are open
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+const CELL_WIDTH: f32 = 130.0;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+}
+
+pub struct BackgroundTasks {
+    stats: Vec<(TaskKind, TaskStats)>,
+}
+
+impl BackgroundTasks {
+    pub fn new() -> Self {
+        Self {
+            stats: background::get_stats(),
+        }
+    }
+
+    pub fn subscription(&self) -> iced::Subscription<Message> {
+        iced::time::every(REFRESH_INTERVAL).map(|_| Message::Refresh)
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Refresh => self.stats = background::get_stats(),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let mut grid = Grid::with_columns(7);
+        let cell = |content: String| container(text(content).size(13)).width(CELL_WIDTH);
+
+        for header in [
+            "Task",
+            "Running",
+            "Waiting",
+            "Done",
+            "Failed",
+            "Turned away",
+            "Average time",
+        ] {
+            grid.insert(cell(header.to_owned()));
+        }
+
+        for (kind, stats) in self.stats.iter() {
+            grid.insert(cell(kind.to_string()));
+            grid.insert(cell(stats.running.to_string()));
+            grid.insert(cell(stats.queued.to_string()));
+            grid.insert(cell(stats.completed.to_string()));
+            grid.insert(cell(stats.failed.to_string()));
+            grid.insert(cell(stats.rejected.to_string()));
+            grid.insert(cell(
+                stats
+                    .average_run_time()
+                    .map(|run_time| format!("{:.1}s", run_time.as_secs_f32()))
+                    .unwrap_or_else(|| "-".to_owned()),
+            ));
+        }
+
+        let content = column![
+            text("Background Tasks")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            text("Refreshing, prefetching, syncing and backing up run apart from what you open so that they never slow it down")
+                .size(11),
+            grid,
+        ]
+        .spacing(10);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
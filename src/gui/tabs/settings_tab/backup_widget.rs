@@ -0,0 +1,332 @@
+use chrono::{DateTime, Local, Utc};
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, pick_list, row, text, text_input,
+    Column, Space,
+};
+use iced::{Alignment, Command, Element, Length, Renderer};
+use iced_aw::NumberInput;
+
+use crate::core::backup::remote;
+use crate::core::date_formatting;
+use crate::core::settings_config::{
+    RemoteBackupKind, RemoteBackupSettings, ALL_REMOTE_BACKUP_KINDS, SETTINGS,
+};
+use crate::gui::styles;
+
+/// The default interval in hours of scheduled backups
+const DEFAULT_SCHEDULE_INTERVAL: u32 = 24;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EnabledToggled(bool),
+    KindSelected(RemoteBackupKind),
+    EndpointChanged(String),
+    BucketChanged(String),
+    RegionChanged(String),
+    UsernameChanged(String),
+    PasswordChanged(String),
+    PassphraseChanged(String),
+    ScheduledToggled(bool),
+    ScheduleIntervalChanged(u32),
+    BackupNowPressed,
+    RestorePressed,
+    BackupComplete(Result<(), String>),
+    RestoreComplete(Result<usize, String>),
+    LastBackupTimeLoaded(Option<DateTime<Utc>>),
+}
+
+pub struct Backup {
+    status: Option<Result<String, String>>,
+    running: bool,
+    last_backup_time: Option<DateTime<Utc>>,
+}
+
+impl Backup {
+    pub fn new() -> (Self, Command<Message>) {
+        (
+            Self {
+                status: None,
+                running: false,
+                last_backup_time: None,
+            },
+            Command::perform(
+                remote::get_last_backup_time(),
+                Message::LastBackupTimeLoaded,
+            ),
+        )
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::EnabledToggled(enabled) => {
+                SETTINGS.write().unwrap().change_settings().remote_backup =
+                    enabled.then(RemoteBackupSettings::default);
+            }
+            Message::BackupNowPressed => {
+                if let Some(settings) = get_remote_backup_settings() {
+                    self.running = true;
+                    self.status = None;
+                    return Command::perform(
+                        async move {
+                            remote::backup(&settings)
+                                .await
+                                .map_err(|err| err.to_string())
+                        },
+                        Message::BackupComplete,
+                    );
+                }
+            }
+            Message::RestorePressed => {
+                if let Some(settings) = get_remote_backup_settings() {
+                    self.running = true;
+                    self.status = None;
+                    return Command::perform(
+                        async move {
+                            remote::restore(&settings)
+                                .await
+                                .map_err(|err| err.to_string())
+                        },
+                        Message::RestoreComplete,
+                    );
+                }
+            }
+            Message::BackupComplete(result) => {
+                self.running = false;
+                self.status = Some(result.map(|_| "Backed up!".to_owned()));
+                return Command::perform(
+                    remote::get_last_backup_time(),
+                    Message::LastBackupTimeLoaded,
+                );
+            }
+            Message::RestoreComplete(result) => {
+                self.running = false;
+                self.status =
+                    Some(result.map(|total_series| format!("Restored {} series!", total_series)));
+            }
+            Message::LastBackupTimeLoaded(last_backup_time) => {
+                self.last_backup_time = last_backup_time
+            }
+            message => {
+                let mut settings = SETTINGS.write().unwrap();
+                let Some(remote_backup) = settings.change_settings().remote_backup.as_mut() else {
+                    return Command::none();
+                };
+
+                match message {
+                    Message::KindSelected(kind) => remote_backup.kind = kind,
+                    Message::EndpointChanged(endpoint) => remote_backup.endpoint = endpoint,
+                    Message::BucketChanged(bucket) => remote_backup.bucket = bucket,
+                    Message::RegionChanged(region) => remote_backup.region = region,
+                    Message::UsernameChanged(username) => remote_backup.username = username,
+                    Message::PasswordChanged(password) => remote_backup.password = password,
+                    Message::PassphraseChanged(passphrase) => remote_backup.passphrase = passphrase,
+                    Message::ScheduledToggled(scheduled) => {
+                        remote_backup.schedule_interval =
+                            scheduled.then_some(DEFAULT_SCHEDULE_INTERVAL)
+                    }
+                    Message::ScheduleIntervalChanged(interval) => {
+                        remote_backup.schedule_interval = Some(interval)
+                    }
+                    _ => unreachable!("non settings messages are handled above"),
+                }
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let remote_backup = get_remote_backup_settings();
+
+        let enabled_checkbox = checkbox(
+            "Back up to a WebDAV or S3 compatible storage",
+            remote_backup.is_some(),
+            Message::EnabledToggled,
+        );
+
+        let mut content = column![
+            text("Cloud Backup")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            text("Backups are encrypted with your passphrase before leaving this device, it can not be recovered if lost. The password and the passphrase are kept in the keyring of your system").size(11),
+            enabled_checkbox,
+        ]
+        .spacing(10);
+
+        if let Some(remote_backup) = remote_backup {
+            content = content.push(self.backup_settings_view(remote_backup));
+        }
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+
+    fn backup_settings_view(
+        &self,
+        remote_backup: RemoteBackupSettings,
+    ) -> Element<'_, Message, Renderer> {
+        let is_s3 = remote_backup.kind == RemoteBackupKind::S3;
+
+        let kind = row![
+            text("Storage"),
+            pick_list(
+                &ALL_REMOTE_BACKUP_KINDS[..],
+                Some(remote_backup.kind.clone()),
+                Message::KindSelected
+            ),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        let (endpoint_placeholder, username_label, password_label) = if is_s3 {
+            (
+                "https://s3.eu-central-1.amazonaws.com",
+                "Access Key ID",
+                "Secret Access Key",
+            )
+        } else {
+            (
+                "https://example.com/remote.php/dav/files/me",
+                "Username",
+                "Password",
+            )
+        };
+
+        let mut fields = Column::new().spacing(10);
+        fields = fields.push(
+            column![
+                text("Endpoint"),
+                text_input(endpoint_placeholder, &remote_backup.endpoint)
+                    .on_input(Message::EndpointChanged)
+                    .width(500)
+            ]
+            .spacing(5),
+        );
+
+        if is_s3 {
+            fields = fields.push(row![
+                column![
+                    text("Bucket"),
+                    text_input("bucket", &remote_backup.bucket)
+                        .on_input(Message::BucketChanged)
+                        .width(240)
+                ]
+                .spacing(5),
+                horizontal_space(20),
+                column![
+                    text("Region"),
+                    text_input("us-east-1", &remote_backup.region)
+                        .on_input(Message::RegionChanged)
+                        .width(240)
+                ]
+                .spacing(5),
+            ]);
+        }
+
+        fields = fields
+            .push(
+                column![
+                    text(username_label),
+                    text_input("", &remote_backup.username)
+                        .on_input(Message::UsernameChanged)
+                        .width(500)
+                ]
+                .spacing(5),
+            )
+            .push(
+                column![
+                    text(password_label),
+                    text_input("", &remote_backup.password)
+                        .on_input(Message::PasswordChanged)
+                        .password()
+                        .width(500)
+                ]
+                .spacing(5),
+            )
+            .push(
+                column![
+                    text("Passphrase"),
+                    text_input("encryption passphrase", &remote_backup.passphrase)
+                        .on_input(Message::PassphraseChanged)
+                        .password()
+                        .width(500)
+                ]
+                .spacing(5),
+            );
+
+        let schedule = {
+            let mut schedule = row![checkbox(
+                "Back up automatically every",
+                remote_backup.schedule_interval.is_some(),
+                Message::ScheduledToggled
+            )]
+            .spacing(5)
+            .align_items(Alignment::Center);
+
+            if let Some(schedule_interval) = remote_backup.schedule_interval {
+                schedule = schedule.push(
+                    NumberInput::new(schedule_interval, 24 * 30, Message::ScheduleIntervalChanged)
+                        .min(1)
+                        .width(Length::Fixed(100.0)),
+                );
+                schedule = schedule.push(text("hours"));
+            }
+            schedule
+        };
+
+        let last_backup = text(match self.last_backup_time {
+            Some(last_backup_time) => {
+                let last_backup_time = last_backup_time.with_timezone(&Local);
+                format!(
+                    "Last backup: {} at {}",
+                    date_formatting::long_date_str(last_backup_time.date_naive()),
+                    date_formatting::time_str(&last_backup_time)
+                )
+            }
+            None => "No backup has been made from this device".to_owned(),
+        })
+        .size(11);
+
+        let status: Element<'_, Message, Renderer> = match &self.status {
+            Some(Ok(status)) => text(status)
+                .style(styles::text_styles::green_text_theme())
+                .into(),
+            Some(Err(err)) => text(err)
+                .style(styles::text_styles::red_text_theme())
+                .into(),
+            None => Space::new(0, 0).into(),
+        };
+
+        let (backup_button, restore_button) = if self.running {
+            (button("Back up now"), button("Restore"))
+        } else {
+            (
+                button("Back up now").on_press(Message::BackupNowPressed),
+                button("Restore").on_press(Message::RestorePressed),
+            )
+        };
+
+        let actions = row![
+            last_backup,
+            horizontal_space(Length::Fill),
+            status,
+            backup_button,
+            restore_button
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        column![kind, fields, schedule, actions].spacing(10).into()
+    }
+}
+
+fn get_remote_backup_settings() -> Option<RemoteBackupSettings> {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .remote_backup
+        .clone()
+}
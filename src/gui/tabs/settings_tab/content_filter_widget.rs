@@ -0,0 +1,192 @@
+use iced::widget::{button, checkbox, column, container, row, text, text_input, Space};
+use iced::{Alignment, Element, Renderer};
+use iced_aw::Wrap;
+
+use crate::core::content_filter::{self, FILTERABLE_GENRES};
+use crate::core::settings_config::{ContentFilterSettings, SETTINGS};
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    HideAdultToggled(bool),
+    GenreToggled(&'static str, bool),
+    PinInputChanged(String),
+    UnlockPressed,
+    SetPinPressed,
+    RemovePinPressed,
+}
+
+#[derive(Default)]
+pub struct ContentFilter {
+    pin_input: String,
+    /// Whether the PIN has been entered since the settings were opened
+    unlocked: bool,
+    wrong_pin: bool,
+}
+
+impl ContentFilter {
+    pub fn update(&mut self, message: Message) {
+        if !self.is_unlocked() {
+            match message {
+                Message::PinInputChanged(pin) => self.pin_input = pin,
+                Message::UnlockPressed => {
+                    self.unlocked = content_filter::unlock(&self.pin_input);
+                    self.wrong_pin = !self.unlocked;
+                    self.pin_input.clear();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let mut settings = SETTINGS.write().unwrap();
+        let content_filter = &mut settings.change_settings().content_filter;
+
+        match message {
+            Message::HideAdultToggled(hide_adult) => content_filter.hide_adult = hide_adult,
+            Message::GenreToggled(genre, hidden) => {
+                content_filter
+                    .hidden_genres
+                    .retain(|hidden_genre| hidden_genre != genre);
+                if hidden {
+                    content_filter.hidden_genres.push(genre.to_owned());
+                }
+            }
+            Message::PinInputChanged(pin) => self.pin_input = pin,
+            Message::SetPinPressed => {
+                if !self.pin_input.is_empty() {
+                    content_filter.set_pin(&self.pin_input);
+                    self.pin_input.clear();
+                    // Staying unlocked for whoever just set the PIN
+                    self.unlocked = true;
+                }
+            }
+            Message::RemovePinPressed => content_filter.pin_hash = None,
+            Message::UnlockPressed => {}
+        }
+    }
+
+    fn is_unlocked(&self) -> bool {
+        self.unlocked || !get_content_filter_settings().is_pin_protected()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let content_filter = get_content_filter_settings();
+
+        let body = if self.is_unlocked() {
+            self.filter_view(content_filter)
+        } else {
+            self.locked_view()
+        };
+
+        let content = column![
+            text("Content Filter")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            text("Hide series from Discover, search and recommendations by their genres, TVmaze does not provide age ratings").size(11),
+            body,
+        ]
+        .spacing(10);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+
+    fn locked_view(&self) -> Element<'_, Message, Renderer> {
+        let wrong_pin: Element<'_, Message, Renderer> = if self.wrong_pin {
+            text("Wrong PIN")
+                .style(styles::text_styles::red_text_theme())
+                .into()
+        } else {
+            Space::new(0, 0).into()
+        };
+
+        column![
+            text("The content filter is protected by a PIN"),
+            row![
+                text_input("PIN", &self.pin_input)
+                    .on_input(Message::PinInputChanged)
+                    .on_submit(Message::UnlockPressed)
+                    .password()
+                    .width(200),
+                button("Unlock").on_press(Message::UnlockPressed),
+                wrong_pin,
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center)
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    fn filter_view(&self, content_filter: ContentFilterSettings) -> Element<'_, Message, Renderer> {
+        let hide_adult = checkbox(
+            "Hide series with adult content",
+            content_filter.hide_adult,
+            Message::HideAdultToggled,
+        );
+
+        let genres = Wrap::with_elements(
+            FILTERABLE_GENRES
+                .into_iter()
+                .map(|genre| {
+                    let is_hidden = content_filter
+                        .hidden_genres
+                        .iter()
+                        .any(|hidden_genre| hidden_genre == genre);
+                    container(checkbox(genre, is_hidden, move |hidden| {
+                        Message::GenreToggled(genre, hidden)
+                    }))
+                    .padding(5)
+                    .into()
+                })
+                .collect(),
+        );
+
+        let pin_text = if content_filter.is_pin_protected() {
+            "Changing the content filter requires the PIN"
+        } else {
+            "Set a PIN to prevent others from changing the content filter"
+        };
+
+        let mut pin_controls = row![text_input("new PIN", &self.pin_input)
+            .on_input(Message::PinInputChanged)
+            .password()
+            .width(200)]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        pin_controls = pin_controls.push(if self.pin_input.is_empty() {
+            button("Set PIN")
+        } else {
+            button("Set PIN").on_press(Message::SetPinPressed)
+        });
+
+        if content_filter.is_pin_protected() {
+            pin_controls =
+                pin_controls.push(button("Remove PIN").on_press(Message::RemovePinPressed));
+        }
+
+        column![
+            hide_adult,
+            text("Hidden genres"),
+            genres,
+            text(pin_text).size(11),
+            pin_controls,
+        ]
+        .spacing(5)
+        .into()
+    }
+}
+
+fn get_content_filter_settings() -> ContentFilterSettings {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .content_filter
+        .clone()
+}
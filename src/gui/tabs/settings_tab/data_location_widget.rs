@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use iced::widget::{button, column, container, horizontal_space, row, text, Space};
+use iced::{Alignment, Command, Element, Length, Renderer};
+
+use crate::core::data_location::{self, DataLocation};
+use crate::gui::styles;
+
+const DATA_LOCATIONS: [DataLocation; 2] = [DataLocation::Database, DataLocation::Cache];
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ChangePressed(DataLocation),
+    DestinationPicked(DataLocation, Option<PathBuf>),
+    MoveScheduled(Result<(), String>),
+    CancelPressed(DataLocation),
+    RestartPressed,
+}
+
+#[derive(Default)]
+pub struct DataLocations {
+    checking_destination: bool,
+    status: Option<Result<(), String>>,
+}
+
+impl DataLocations {
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::ChangePressed(location) => {
+                return Command::perform(pick_destination(), move |destination| {
+                    Message::DestinationPicked(location, destination)
+                })
+            }
+            Message::DestinationPicked(location, destination) => {
+                if let Some(destination) = destination {
+                    self.status = None;
+                    self.checking_destination = true;
+                    return Command::perform(
+                        async move {
+                            data_location::schedule_move(location, destination)
+                                .await
+                                .map_err(|err| err.to_string())
+                        },
+                        Message::MoveScheduled,
+                    );
+                }
+            }
+            Message::MoveScheduled(result) => {
+                self.checking_destination = false;
+                self.status = Some(result);
+            }
+            Message::CancelPressed(location) => {
+                data_location::cancel_move(location);
+                self.status = None;
+            }
+            Message::RestartPressed => {
+                let restart = std::env::current_exe().and_then(|program| {
+                    std::process::Command::new(program)
+                        .args(std::env::args_os().skip(1))
+                        .spawn()
+                });
+
+                match restart {
+                    Ok(_) => return iced::window::close(),
+                    Err(err) => {
+                        tracing::error!("failed to restart: {}", err);
+                        self.status =
+                            Some(Err("failed to restart, please restart manually".to_owned()));
+                    }
+                }
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let mut content = column![
+            text("Data Location")
+                .size(21)
+                .style(styles::text_styles::accent_color_theme()),
+            text("Move the database and the cache to another folder, like a bigger drive or a network storage").size(11),
+        ]
+        .spacing(5);
+
+        let mut restart_required = false;
+
+        for location in DATA_LOCATIONS {
+            let pending_dir = location.pending_dir();
+            restart_required |= pending_dir.is_some();
+
+            let mut dirs = column![
+                text(location),
+                text(location.current_dir().display()).size(11)
+            ];
+            if let Some(pending_dir) = &pending_dir {
+                dirs = dirs.push(text(format!("moving to {}", pending_dir.display())).size(11));
+            }
+
+            let action = if pending_dir.is_some() {
+                button("Cancel").on_press(Message::CancelPressed(location))
+            } else if self.checking_destination {
+                button("Change")
+            } else {
+                button("Change").on_press(Message::ChangePressed(location))
+            };
+
+            content = content.push(
+                row![dirs, horizontal_space(Length::Fill), action]
+                    .align_items(Alignment::Center)
+                    .spacing(5),
+            );
+        }
+
+        let status: Element<'_, Message, Renderer> = match &self.status {
+            Some(Err(err)) => text(err)
+                .style(styles::text_styles::red_text_theme())
+                .into(),
+            _ if restart_required => row![
+                text("Restart Series Troxide to move the data, it is copied on start before being opened").size(11),
+                horizontal_space(Length::Fill),
+                button("Restart").on_press(Message::RestartPressed),
+            ]
+            .align_items(Alignment::Center)
+            .spacing(5)
+            .into(),
+            _ => Space::new(0, 0).into(),
+        };
+        content = content.push(status);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
+
+async fn pick_destination() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .pick_folder()
+        .await
+        .map(|folder_handle| folder_handle.path().to_owned())
+}
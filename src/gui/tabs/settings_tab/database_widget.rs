@@ -1,13 +1,19 @@
 use iced::widget::{
-    button, column, container, horizontal_space, progress_bar, row, text, vertical_space, Space,
+    button, checkbox, column, container, horizontal_space, progress_bar, row, text, vertical_space,
+    Space,
 };
 use iced::{Command, Element, Length, Renderer};
 
 use crate::core::database::database_transfer::TransferData;
 use crate::core::database::DB;
+use crate::core::settings_config::SETTINGS;
 
 use crate::gui::styles;
 
+mod duplicates;
+mod recently_removed;
+mod reconciliation;
+mod relinking;
 mod trakt_integration;
 
 #[derive(Debug, Clone)]
@@ -20,6 +26,11 @@ pub enum Message {
     ExportTimeoutComplete,
     ImportCachingEvent(full_caching::Event),
     TraktIntegration(trakt_integration::Message),
+    Duplicates(duplicates::Message),
+    RecentlyRemoved(recently_removed::Message),
+    Relinking(relinking::Message),
+    Reconciliation(reconciliation::Message),
+    EncryptionToggled(bool),
 }
 
 pub struct Database {
@@ -30,6 +41,10 @@ pub struct Database {
     transfer_data: Option<TransferData>,
     sender: Option<iced::futures::channel::mpsc::Sender<full_caching::Input>>,
     trakt_widget: trakt_integration::TraktIntegration,
+    duplicates_widget: duplicates::Duplicates,
+    recently_removed_widget: recently_removed::RecentlyRemoved,
+    relinking_widget: relinking::Relinking,
+    reconciliation_widget: reconciliation::Reconciliation,
 }
 
 impl Database {
@@ -42,6 +57,10 @@ impl Database {
             transfer_data: None,
             sender: None,
             trakt_widget: trakt_integration::TraktIntegration::new(),
+            duplicates_widget: duplicates::Duplicates::new(),
+            recently_removed_widget: recently_removed::RecentlyRemoved::new(),
+            relinking_widget: relinking::Relinking::new(),
+            reconciliation_widget: reconciliation::Reconciliation::new(),
         }
     }
     pub fn subscription(&self) -> iced::Subscription<Message> {
@@ -115,7 +134,7 @@ impl Database {
 
                         let data = self
                             .transfer_data
-                            .as_ref()
+                            .take()
                             .expect("there should be transfer data at this point");
 
                         DB.import(data);
@@ -135,6 +154,41 @@ impl Database {
                 .trakt_widget
                 .update(message)
                 .map(Message::TraktIntegration),
+            Message::Duplicates(message) => {
+                let command = self
+                    .duplicates_widget
+                    .update(message)
+                    .map(Message::Duplicates);
+                // Merging removes the duplicate series
+                self.recently_removed_widget.reload();
+                command
+            }
+            Message::RecentlyRemoved(message) => {
+                self.recently_removed_widget.update(message);
+                Command::none()
+            }
+            Message::Relinking(message) => {
+                let command = self
+                    .relinking_widget
+                    .update(message)
+                    .map(Message::Relinking);
+                // Relinking removes the missing series
+                self.recently_removed_widget.reload();
+                command
+            }
+            Message::Reconciliation(message) => self
+                .reconciliation_widget
+                .update(message)
+                .map(Message::Reconciliation),
+            Message::EncryptionToggled(encryption) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .database
+                    .encryption = encryption;
+                Command::none()
+            }
         }
     }
 
@@ -189,10 +243,44 @@ impl Database {
             .spacing(5)
         ];
 
+        let encryption = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .database
+            .encryption;
+
+        let encryption_status = if encryption != DB.is_encrypted() {
+            "Restart Series Troxide to apply, the whole database is rewritten on start"
+        } else if encryption {
+            "The key is kept in the keyring of your system, the database can not be opened without it"
+        } else {
+            "Encrypt the database on this device, with a key kept in the keyring of your system"
+        };
+
+        let encryption_widget = column![
+            text("Encryption"),
+            row![
+                text(encryption_status).size(11),
+                horizontal_space(Length::Fill),
+                checkbox("Encrypt", encryption, Message::EncryptionToggled),
+            ]
+            .spacing(5)
+        ];
+
         let series_troxide_data = column![
             text("Series Troxide Data").size(18),
             import_widget,
             export_widget,
+            encryption_widget,
+            self.duplicates_widget.view().map(Message::Duplicates),
+            self.relinking_widget.view().map(Message::Relinking),
+            self.reconciliation_widget
+                .view()
+                .map(Message::Reconciliation),
+            self.recently_removed_widget
+                .view()
+                .map(Message::RecentlyRemoved),
         ]
         .spacing(5);
 
@@ -0,0 +1,185 @@
+use iced::widget::{button, column, horizontal_space, row, text, Column};
+use iced::{Alignment, Command, Element, Length, Renderer};
+
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::database::DB;
+use crate::core::duplicates::{self, Duplicate};
+use crate::gui::{styles, toast};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    FindPressed,
+    DuplicatesFound(Result<Vec<Duplicate>, String>),
+    /// The index of the duplicate and whether the first series is the one kept
+    KeepPressed(usize, bool),
+    MergeConfirmed,
+    MergeCanceled,
+}
+
+enum State {
+    Idle,
+    Searching,
+    Found(Vec<Duplicate>),
+}
+
+/// Finds the series tracked twice and guides merging them into one
+pub struct Duplicates {
+    state: State,
+    /// The duplicate about to be merged and whether the first series is the one kept
+    confirming: Option<(usize, bool)>,
+}
+
+impl Duplicates {
+    pub fn new() -> Self {
+        Self {
+            state: State::Idle,
+            confirming: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::FindPressed => {
+                self.state = State::Searching;
+                self.confirming = None;
+                return Command::perform(duplicates::find_duplicates(), |result| {
+                    Message::DuplicatesFound(result.map_err(|err| err.to_string()))
+                });
+            }
+            Message::DuplicatesFound(result) => match result {
+                Ok(found_duplicates) => self.state = State::Found(found_duplicates),
+                Err(err) => {
+                    tracing::error!("failed to find duplicates: {}", err);
+                    toast::push("Could not look for duplicates");
+                    self.state = State::Idle;
+                }
+            },
+            Message::KeepPressed(index, keep_first) => self.confirming = Some((index, keep_first)),
+            Message::MergeCanceled => self.confirming = None,
+            Message::MergeConfirmed => {
+                let (State::Found(found_duplicates), Some((index, keep_first))) =
+                    (&mut self.state, self.confirming.take())
+                else {
+                    return Command::none();
+                };
+
+                let duplicate = found_duplicates.remove(index);
+                let (kept, merged) = if keep_first {
+                    (&duplicate.first, &duplicate.second)
+                } else {
+                    (&duplicate.second, &duplicate.first)
+                };
+
+                let outcome = duplicates::merge(kept, merged.id);
+                toast::push(format!(
+                    "Merged into \"{}\", {} more episodes watched",
+                    kept.name, outcome.newly_watched
+                ));
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let find_button = match self.state {
+            State::Searching => button("Looking..."),
+            _ => button("Find Duplicates").on_press(Message::FindPressed),
+        };
+
+        let header = row![
+            text("Find shows tracked twice, like after a rebrand, and merge their watch records")
+                .size(11),
+            horizontal_space(Length::Fill),
+            find_button,
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        let body: Element<'_, Message, Renderer> = match &self.state {
+            State::Found(found_duplicates) if found_duplicates.is_empty() => {
+                text("No duplicates found").size(11).into()
+            }
+            State::Found(found_duplicates) => Column::with_children(
+                found_duplicates
+                    .iter()
+                    .enumerate()
+                    .map(|(index, duplicate)| self.duplicate_view(index, duplicate))
+                    .collect(),
+            )
+            .spacing(10)
+            .into(),
+            _ => column![].into(),
+        };
+
+        column![text("Duplicates"), header, body].spacing(5).into()
+    }
+
+    fn duplicate_view<'b>(
+        &self,
+        index: usize,
+        duplicate: &'b Duplicate,
+    ) -> Element<'b, Message, Renderer> {
+        if let Some((confirming_index, keep_first)) = self.confirming {
+            if confirming_index == index {
+                let (kept, merged) = if keep_first {
+                    (&duplicate.first, &duplicate.second)
+                } else {
+                    (&duplicate.second, &duplicate.first)
+                };
+
+                return row![
+                    text(format!(
+                        "Merge the watch records of \"{}\" (#{}) into \"{}\" (#{}) and remove it?",
+                        merged.name, merged.id, kept.name, kept.id
+                    ))
+                    .size(11),
+                    horizontal_space(Length::Fill),
+                    button("Merge").on_press(Message::MergeConfirmed),
+                    button("Cancel").on_press(Message::MergeCanceled),
+                ]
+                .spacing(5)
+                .align_items(Alignment::Center)
+                .into();
+            }
+        }
+
+        let series_view = |series_info: &SeriesMainInformation, keep_first: bool| {
+            let watched_episodes = DB
+                .get_series(series_info.id)
+                .map(|series| series.get_total_episodes())
+                .unwrap_or_default();
+
+            row![
+                column![
+                    text(&series_info.name),
+                    text(format!(
+                        "TVmaze #{}, {}, {} episodes watched",
+                        series_info.id,
+                        series_info
+                            .premiered
+                            .as_deref()
+                            .unwrap_or("unknown premiere"),
+                        watched_episodes
+                    ))
+                    .size(11)
+                ],
+                horizontal_space(Length::Fill),
+                button(text("Keep this").size(11))
+                    .on_press(Message::KeepPressed(index, keep_first))
+                    .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center)
+        };
+
+        column![
+            text(format!("Tracked twice ({})", duplicate.reason))
+                .size(11)
+                .style(styles::text_styles::accent_color_theme()),
+            series_view(&duplicate.first, true),
+            series_view(&duplicate.second, false),
+        ]
+        .spacing(5)
+        .into()
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{Local, TimeZone};
+use iced::widget::{button, column, horizontal_space, row, text, Column};
+use iced::{Alignment, Element, Length, Renderer};
+
+use crate::core::database::{RemovedSeries, DB, REMOVED_SERIES_RETENTION_DAYS};
+use crate::core::date_formatting;
+use crate::gui::{styles, toast};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    RestorePressed(u32),
+}
+
+/// Lists the removed series that still have their watch records and restores them
+pub struct RecentlyRemoved {
+    removed_series: Vec<RemovedSeries>,
+}
+
+impl RecentlyRemoved {
+    pub fn new() -> Self {
+        Self {
+            removed_series: DB.get_removed_series(),
+        }
+    }
+
+    /// Loads the removed series again, for when series were removed elsewhere
+    pub fn reload(&mut self) {
+        self.removed_series = DB.get_removed_series();
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::RestorePressed(series_id) => {
+                let series_name = self
+                    .removed_series
+                    .iter()
+                    .find(|removed_series| removed_series.series_id == series_id)
+                    .map(|removed_series| removed_series.series_name.clone())
+                    .unwrap_or_default();
+
+                match DB.restore_removed_series(series_id) {
+                    Some(_) => toast::push(format!("Restored \"{}\"", series_name)),
+                    None => {
+                        tracing::error!("failed to restore removed series of id {}", series_id);
+                        toast::push(format!("Could not restore \"{}\"", series_name));
+                    }
+                }
+                self.reload();
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let description = text(format!(
+            "Removed shows with watched episodes can be restored for {} days",
+            REMOVED_SERIES_RETENTION_DAYS
+        ))
+        .size(11);
+
+        let body: Element<'_, Message, Renderer> = if self.removed_series.is_empty() {
+            text("No recently removed shows").size(11).into()
+        } else {
+            Column::with_children(
+                self.removed_series
+                    .iter()
+                    .map(removed_series_view)
+                    .collect(),
+            )
+            .spacing(5)
+            .into()
+        };
+
+        column![text("Recently Removed"), description, body]
+            .spacing(5)
+            .into()
+    }
+}
+
+fn removed_series_view(removed_series: &RemovedSeries) -> Element<'_, Message, Renderer> {
+    let removed_at = Local
+        .timestamp_opt(removed_series.removed_at, 0)
+        .single()
+        .map(|removed_at| date_formatting::date_str(removed_at.date_naive()))
+        .unwrap_or_default();
+
+    row![
+        column![
+            text(&removed_series.series_name),
+            text(format!(
+                "Removed {}, {} episodes watched",
+                removed_at, removed_series.watched_episodes
+            ))
+            .size(11)
+        ],
+        horizontal_space(Length::Fill),
+        button(text("Restore").size(11))
+            .on_press(Message::RestorePressed(removed_series.series_id))
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center)
+    .into()
+}
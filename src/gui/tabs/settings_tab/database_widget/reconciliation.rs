@@ -0,0 +1,143 @@
+use iced::widget::{button, column, horizontal_space, row, text, Column};
+use iced::{Alignment, Command, Element, Length, Renderer};
+
+use crate::core::reconciliation::{self, Mismatch};
+use crate::gui::{styles, toast};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    CheckPressed,
+    MismatchesFound(Vec<Mismatch>),
+    /// The index of the mismatch
+    FixPressed(usize),
+    FixAllPressed,
+}
+
+enum State {
+    Idle,
+    Checking,
+    Checked(Vec<Mismatch>),
+}
+
+/// Reports the watched and skipped episodes no longer in the episode list of their series
+/// and clears them
+pub struct Reconciliation {
+    state: State,
+}
+
+impl Reconciliation {
+    pub fn new() -> Self {
+        Self { state: State::Idle }
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::CheckPressed => {
+                self.state = State::Checking;
+                return Command::perform(
+                    reconciliation::find_mismatches(),
+                    Message::MismatchesFound,
+                );
+            }
+            Message::MismatchesFound(mismatches) => self.state = State::Checked(mismatches),
+            Message::FixPressed(index) => {
+                let State::Checked(mismatches) = &mut self.state else {
+                    return Command::none();
+                };
+
+                if index < mismatches.len() {
+                    let mismatch = mismatches.remove(index);
+                    let cleared_episodes = reconciliation::fix(&mismatch);
+                    toast::push(format!(
+                        "Cleared {} orphaned episodes of \"{}\"",
+                        cleared_episodes, mismatch.series_name
+                    ));
+                }
+            }
+            Message::FixAllPressed => {
+                let State::Checked(mismatches) = &mut self.state else {
+                    return Command::none();
+                };
+
+                let cleared_episodes: usize = mismatches
+                    .drain(..)
+                    .map(|mismatch| reconciliation::fix(&mismatch))
+                    .sum();
+                toast::push(format!("Cleared {} orphaned episodes", cleared_episodes));
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let check_button = match self.state {
+            State::Checking => button("Checking..."),
+            _ => button("Check Episodes").on_press(Message::CheckPressed),
+        };
+
+        let header = row![
+            text("Find watched and skipped episodes removed or renumbered on TVmaze").size(11),
+            horizontal_space(Length::Fill),
+            check_button,
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        let body: Element<'_, Message, Renderer> = match &self.state {
+            State::Checked(mismatches) if mismatches.is_empty() => {
+                text("All recorded episodes match their episode lists")
+                    .size(11)
+                    .into()
+            }
+            State::Checked(mismatches) => {
+                let mismatches_list = Column::with_children(
+                    mismatches
+                        .iter()
+                        .enumerate()
+                        .map(|(index, mismatch)| mismatch_view(index, mismatch))
+                        .collect(),
+                )
+                .spacing(5);
+
+                column![
+                    mismatches_list,
+                    button(text("Fix all").size(11))
+                        .on_press(Message::FixAllPressed)
+                        .style(
+                            styles::button_styles::transparent_button_with_rounded_border_theme()
+                        ),
+                ]
+                .spacing(5)
+                .into()
+            }
+            _ => column![].into(),
+        };
+
+        column![text("Episode Reconciliation"), header, body]
+            .spacing(5)
+            .into()
+    }
+}
+
+fn mismatch_view(index: usize, mismatch: &Mismatch) -> Element<'_, Message, Renderer> {
+    let orphaned_episodes = mismatch
+        .orphaned_episodes
+        .iter()
+        .map(|orphaned_episode| orphaned_episode.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    row![
+        column![
+            text(&mismatch.series_name),
+            text(format!("Not on TVmaze: {}", orphaned_episodes)).size(11)
+        ]
+        .width(Length::Fill),
+        button(text("Fix").size(11))
+            .on_press(Message::FixPressed(index))
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme()),
+    ]
+    .spacing(5)
+    .align_items(Alignment::Center)
+    .into()
+}
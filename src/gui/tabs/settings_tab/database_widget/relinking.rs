@@ -0,0 +1,239 @@
+use chrono::{Local, TimeZone};
+use iced::widget::{button, column, horizontal_space, row, text, Column};
+use iced::{Alignment, Command, Element, Length, Renderer};
+
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::database::{MissingSeries, DB};
+use crate::core::date_formatting;
+use crate::core::relinking;
+use crate::gui::{styles, toast};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    FindReplacementsPressed(u32),
+    /// The id of the missing series with it's replacements
+    ReplacementsFound(u32, Result<Vec<SeriesMainInformation>, String>),
+    /// The index of the replacement
+    RelinkPressed(usize),
+    RelinkConfirmed,
+    RelinkCanceled,
+}
+
+/// Lists the series TVmaze no longer knows and relinks them to the show they live on as
+pub struct Relinking {
+    missing_series: Vec<MissingSeries>,
+    /// The id of the missing series replacements are looked for, with it's replacements
+    /// once found
+    replacements: Option<(u32, Option<Vec<SeriesMainInformation>>)>,
+    /// The index of the replacement about to be relinked to
+    confirming: Option<usize>,
+}
+
+impl Relinking {
+    pub fn new() -> Self {
+        Self {
+            missing_series: DB.get_missing_series(),
+            replacements: None,
+            confirming: None,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::FindReplacementsPressed(series_id) => {
+                let Some(missing_series) = self.get_missing_series(series_id).cloned() else {
+                    return Command::none();
+                };
+
+                self.replacements = Some((series_id, None));
+                self.confirming = None;
+
+                return Command::perform(
+                    async move { relinking::find_replacements(&missing_series).await },
+                    move |result| {
+                        Message::ReplacementsFound(series_id, result.map_err(|err| err.to_string()))
+                    },
+                );
+            }
+            Message::ReplacementsFound(series_id, result) => {
+                // Replacements of another series are being looked for since
+                if self.replacements.as_ref().map(|(id, _)| *id) != Some(series_id) {
+                    return Command::none();
+                }
+
+                match result {
+                    Ok(replacements) => self.replacements = Some((series_id, Some(replacements))),
+                    Err(err) => {
+                        tracing::error!("failed to find replacements: {}", err);
+                        toast::push("Could not look for replacements");
+                        self.replacements = None;
+                    }
+                }
+            }
+            Message::RelinkPressed(index) => self.confirming = Some(index),
+            Message::RelinkCanceled => self.confirming = None,
+            Message::RelinkConfirmed => {
+                let (Some((series_id, Some(replacements))), Some(index)) =
+                    (self.replacements.take(), self.confirming.take())
+                else {
+                    return Command::none();
+                };
+
+                let (Some(missing_series), Some(replacement)) =
+                    (self.get_missing_series(series_id), replacements.get(index))
+                else {
+                    return Command::none();
+                };
+
+                let outcome = relinking::relink(missing_series, replacement);
+                toast::push(format!(
+                    "Relinked \"{}\" to \"{}\", {} more episodes watched",
+                    missing_series.series_name, replacement.name, outcome.newly_watched
+                ));
+
+                self.missing_series = DB.get_missing_series();
+            }
+        }
+        Command::none()
+    }
+
+    fn get_missing_series(&self, series_id: u32) -> Option<&MissingSeries> {
+        self.missing_series
+            .iter()
+            .find(|missing_series| missing_series.series_id == series_id)
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let description =
+            text("Shows no longer found on TVmaze, relink them to keep their watch history")
+                .size(11);
+
+        let body: Element<'_, Message, Renderer> = if self.missing_series.is_empty() {
+            text("No missing shows").size(11).into()
+        } else {
+            Column::with_children(
+                self.missing_series
+                    .iter()
+                    .map(|missing_series| self.missing_series_view(missing_series))
+                    .collect(),
+            )
+            .spacing(10)
+            .into()
+        };
+
+        column![text("Missing Shows"), description, body]
+            .spacing(5)
+            .into()
+    }
+
+    fn missing_series_view<'b>(
+        &'b self,
+        missing_series: &'b MissingSeries,
+    ) -> Element<'b, Message, Renderer> {
+        let first_missed_at = Local
+            .timestamp_opt(missing_series.first_missed_at, 0)
+            .single()
+            .map(|first_missed_at| date_formatting::date_str(first_missed_at.date_naive()))
+            .unwrap_or_default();
+
+        let replacements = self
+            .replacements
+            .as_ref()
+            .filter(|(series_id, _)| *series_id == missing_series.series_id)
+            .map(|(_, replacements)| replacements);
+
+        let find_button = match replacements {
+            Some(None) => button(text("Looking...").size(11)),
+            _ => button(text("Find replacement").size(11))
+                .on_press(Message::FindReplacementsPressed(missing_series.series_id)),
+        }
+        .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+
+        let header = row![
+            column![
+                text(&missing_series.series_name),
+                text(format!(
+                    "TVmaze #{}, missing since {}",
+                    missing_series.series_id, first_missed_at
+                ))
+                .size(11)
+            ],
+            horizontal_space(Length::Fill),
+            find_button,
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        let Some(Some(replacements)) = replacements else {
+            return header.into();
+        };
+
+        let replacements: Element<'_, Message, Renderer> = if replacements.is_empty() {
+            text("No replacement found").size(11).into()
+        } else {
+            Column::with_children(
+                replacements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, replacement)| {
+                        self.replacement_view(missing_series, index, replacement)
+                    })
+                    .collect(),
+            )
+            .spacing(5)
+            .into()
+        };
+
+        column![header, replacements].spacing(5).into()
+    }
+
+    fn replacement_view<'b>(
+        &self,
+        missing_series: &'b MissingSeries,
+        index: usize,
+        replacement: &'b SeriesMainInformation,
+    ) -> Element<'b, Message, Renderer> {
+        if self.confirming == Some(index) {
+            return row![
+                text(format!(
+                    "Move the watch history of \"{}\" (#{}) to \"{}\" (#{})?",
+                    missing_series.series_name,
+                    missing_series.series_id,
+                    replacement.name,
+                    replacement.id
+                ))
+                .size(11),
+                horizontal_space(Length::Fill),
+                button("Relink").on_press(Message::RelinkConfirmed),
+                button("Cancel").on_press(Message::RelinkCanceled),
+            ]
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into();
+        }
+
+        row![
+            column![
+                text(&replacement.name).size(13),
+                text(format!(
+                    "TVmaze #{}, {}, {}",
+                    replacement.id,
+                    replacement
+                        .premiered
+                        .as_deref()
+                        .unwrap_or("unknown premiere"),
+                    replacement.status
+                ))
+                .size(11)
+            ],
+            horizontal_space(Length::Fill),
+            button(text("Relink to this").size(11))
+                .on_press(Message::RelinkPressed(index))
+                .style(styles::button_styles::transparent_button_theme()),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center)
+        .padding([0, 0, 0, 10])
+        .into()
+    }
+}
@@ -823,13 +823,14 @@ impl ImportPage {
                     }
                 }
                 trakt_data_import::Event::WorkFinished(imports) => {
-                    use crate::core::database::DB;
+                    use crate::core::database::merge;
+                    use std::mem::ManuallyDrop;
 
                     match imports {
                         Ok(imports) => {
-                            imports.0.into_iter().for_each(|(series_id, mut series)| {
+                            imports.0.into_iter().for_each(|(_, mut series)| {
                                 series.mark_tracked();
-                                DB.add_series(series_id, &series)
+                                merge::merge_series_into_db(ManuallyDrop::into_inner(series));
                             });
                             self.failed_imports = imports.1;
                         }
@@ -0,0 +1,117 @@
+use iced::widget::{button, column, container, horizontal_space, row, text, Space};
+use iced::{Alignment, Command, Element, Length, Renderer};
+
+use crate::core::deep_link;
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    RegistrationChecked(bool),
+    RegisterPressed,
+    Registered(Result<(), String>),
+}
+
+pub struct DeepLinks {
+    is_registered: Option<bool>,
+    registering: bool,
+    error: Option<String>,
+}
+
+impl DeepLinks {
+    pub fn new() -> (Self, Command<Message>) {
+        (
+            Self {
+                is_registered: None,
+                registering: false,
+                error: None,
+            },
+            Command::perform(
+                async {
+                    tokio::task::spawn_blocking(deep_link::is_uri_scheme_registered)
+                        .await
+                        .unwrap_or(false)
+                },
+                Message::RegistrationChecked,
+            ),
+        )
+    }
+
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::RegistrationChecked(is_registered) => self.is_registered = Some(is_registered),
+            Message::RegisterPressed => {
+                self.registering = true;
+                self.error = None;
+                return Command::perform(
+                    async {
+                        tokio::task::spawn_blocking(deep_link::register_uri_scheme)
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|result| result)
+                            .map_err(|err| err.to_string())
+                    },
+                    Message::Registered,
+                );
+            }
+            Message::Registered(result) => {
+                self.registering = false;
+                match result {
+                    Ok(()) => self.is_registered = Some(true),
+                    Err(err) => self.error = Some(err),
+                }
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let content = column![
+            text("Links")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            text(format!(
+                "Open {}:// links from exported feeds and calendars with this copy of the app",
+                deep_link::SCHEME
+            ))
+            .size(11),
+            self.registration_view(),
+        ]
+        .spacing(10);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+
+    fn registration_view(&self) -> Element<'_, Message, Renderer> {
+        if !deep_link::is_uri_scheme_supported() {
+            return text("Links can not be registered on this operating system")
+                .size(11)
+                .into();
+        }
+
+        let status: Element<'_, Message, Renderer> = match (&self.error, self.is_registered) {
+            (Some(err), _) => text(err)
+                .style(styles::text_styles::red_text_theme())
+                .into(),
+            (None, Some(true)) => text("Links open with this copy of the app")
+                .style(styles::text_styles::green_text_theme())
+                .into(),
+            (None, Some(false)) => text("Links do not open with this copy of the app").into(),
+            (None, None) => Space::new(0, 0).into(),
+        };
+
+        let register_button = if self.registering || self.is_registered != Some(false) {
+            button("Register")
+        } else {
+            button("Register").on_press(Message::RegisterPressed)
+        };
+
+        row![status, horizontal_space(Length::Fill), register_button]
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into()
+    }
+}
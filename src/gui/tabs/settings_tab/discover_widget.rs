@@ -1,8 +1,10 @@
-use iced::widget::{column, combo_box, container, text};
+use iced::widget::{checkbox, column, combo_box, container, text};
 use iced::{Command, Element, Renderer};
+use iced_aw::Wrap;
 use locale_settings::{get_country_code_from_settings, get_country_name_from_country_code};
 use rust_iso3166::ALL;
 
+use crate::core::discover_filter::{LANGUAGES, SHOW_TYPES};
 use crate::core::settings_config::{locale_settings, SETTINGS};
 use crate::gui::styles;
 use hidden_series::{HiddenSeries, Message as HiddenSeriesMessage};
@@ -10,6 +12,8 @@ use hidden_series::{HiddenSeries, Message as HiddenSeriesMessage};
 #[derive(Clone, Debug)]
 pub enum Message {
     CountrySelected(String),
+    ShowTypeToggled(&'static str, bool),
+    LanguageToggled(&'static str, bool),
     HiddenSeries(HiddenSeriesMessage),
 }
 
@@ -44,6 +48,24 @@ impl Discover {
                     .country_code = country_code.to_owned();
                 Command::none()
             }
+            Message::ShowTypeToggled(show_type, hidden) => {
+                let mut settings = SETTINGS.write().unwrap();
+                toggle(
+                    &mut settings.change_settings().discover_filter.hidden_types,
+                    show_type,
+                    hidden,
+                );
+                Command::none()
+            }
+            Message::LanguageToggled(language, shown) => {
+                let mut settings = SETTINGS.write().unwrap();
+                toggle(
+                    &mut settings.change_settings().discover_filter.languages,
+                    language,
+                    shown,
+                );
+                Command::none()
+            }
             Message::HiddenSeries(message) => self
                 .hidden_series
                 .update(message)
@@ -57,6 +79,7 @@ impl Discover {
                 .size(21)
                 .style(styles::text_styles::accent_color_theme()),
             self.country_widget(),
+            self.filter_widget(),
             self.hidden_series.view().map(Message::HiddenSeries),
         ]
         .padding(5)
@@ -90,6 +113,72 @@ impl Discover {
             .spacing(5)
             .into()
     }
+
+    fn filter_widget(&self) -> Element<'_, Message, Renderer> {
+        let discover_filter = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .discover_filter
+            .clone();
+
+        let show_types = Wrap::with_elements(
+            SHOW_TYPES
+                .into_iter()
+                .map(|show_type| {
+                    let is_hidden = discover_filter
+                        .hidden_types
+                        .iter()
+                        .any(|hidden_type| hidden_type == show_type);
+                    container(checkbox(show_type, is_hidden, move |hidden| {
+                        Message::ShowTypeToggled(show_type, hidden)
+                    }))
+                    .padding(5)
+                    .into()
+                })
+                .collect(),
+        );
+
+        let languages = Wrap::with_elements(
+            LANGUAGES
+                .into_iter()
+                .map(|language| {
+                    let is_shown = discover_filter
+                        .languages
+                        .iter()
+                        .any(|shown_language| shown_language == language);
+                    container(checkbox(language, is_shown, move |shown| {
+                        Message::LanguageToggled(language, shown)
+                    }))
+                    .padding(5)
+                    .into()
+                })
+                .collect(),
+        );
+
+        column![
+            column![
+                text("Hidden Show Types").size(18),
+                text("Series of the checked types are left out of the discover page, reload the discover page to apply.").size(11),
+            ],
+            show_types,
+            column![
+                text("Languages").size(18),
+                text("Only series in the checked languages are shown in the discover page, all languages are shown when none is checked.").size(11),
+            ],
+            languages,
+        ]
+        .spacing(5)
+        .into()
+    }
+}
+
+/// Adds or removes the value from the list of values
+fn toggle(values: &mut Vec<String>, value: &str, present: bool) {
+    values.retain(|existing_value| existing_value != value);
+    if present {
+        values.push(value.to_owned());
+    }
 }
 
 impl Default for Discover {
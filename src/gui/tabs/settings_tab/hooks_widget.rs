@@ -0,0 +1,76 @@
+use iced::widget::{column, container, text, text_input};
+use iced::{Element, Length, Renderer};
+
+use crate::core::settings_config::SETTINGS;
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    WebhookUrlChanged(String),
+    CommandChanged(String),
+}
+
+#[derive(Default)]
+pub struct Hooks;
+
+impl Hooks {
+    pub fn update(&mut self, message: Message) {
+        let mut settings = SETTINGS.write().unwrap();
+        let hooks = &mut settings.change_settings().hooks;
+
+        match message {
+            Message::WebhookUrlChanged(webhook_url) => {
+                hooks.webhook_url = Some(webhook_url).filter(|url| !url.is_empty());
+            }
+            Message::CommandChanged(command) => {
+                hooks.command = Some(command).filter(|command| !command.is_empty());
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let hooks = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .hooks
+            .clone();
+
+        let webhook = column![
+            text("Webhook"),
+            text("Watch events will be sent as json POST requests to this url").size(11),
+            text_input(
+                "https://example.com/troxide",
+                hooks.webhook_url.as_deref().unwrap_or_default()
+            )
+            .on_input(Message::WebhookUrlChanged)
+            .width(Length::Fixed(500.0)),
+        ]
+        .spacing(5);
+
+        let command = column![
+            text("Command"),
+            text("Ran on watch events with the 'TROXIDE_EVENT' and 'TROXIDE_PAYLOAD' environment variables set")
+                .size(11),
+            text_input("command", hooks.command.as_deref().unwrap_or_default())
+                .on_input(Message::CommandChanged)
+                .width(Length::Fixed(500.0)),
+        ]
+        .spacing(5);
+
+        let content = column![
+            text("Hooks")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            webhook,
+            command,
+        ]
+        .spacing(10);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
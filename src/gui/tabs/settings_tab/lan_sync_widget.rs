@@ -0,0 +1,222 @@
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, row, text, text_input, Column, Space,
+};
+use iced::{Alignment, Command, Element, Length, Renderer};
+
+use crate::core::settings_config::{LanSyncSettings, SETTINGS};
+use crate::core::sync::lan::{self, DiscoveredService};
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EnabledToggled(bool),
+    DeviceNameChanged(String),
+    PairingCodeChanged(String),
+    FindDevicesPressed,
+    DevicesFound(Result<Vec<DiscoveredService>, String>),
+    SyncPressed(usize),
+    SyncComplete(Result<String, String>),
+}
+
+#[derive(Default)]
+pub struct LanSync {
+    devices: Vec<DiscoveredService>,
+    searching: bool,
+    syncing: bool,
+    status: Option<Result<String, String>>,
+}
+
+impl LanSync {
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::EnabledToggled(enabled) => {
+                SETTINGS.write().unwrap().change_settings().lan_sync =
+                    enabled.then(LanSyncSettings::default);
+            }
+            Message::DeviceNameChanged(device_name) => {
+                if let Some(lan_sync) = SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .lan_sync
+                    .as_mut()
+                {
+                    lan_sync.device_name = device_name;
+                }
+            }
+            Message::PairingCodeChanged(pairing_code) => {
+                if let Some(lan_sync) = SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .lan_sync
+                    .as_mut()
+                {
+                    lan_sync.pairing_code = pairing_code;
+                }
+            }
+            Message::FindDevicesPressed => {
+                if let Some(settings) = get_lan_sync_settings() {
+                    self.searching = true;
+                    self.status = None;
+                    return Command::perform(
+                        async move {
+                            lan::discover(&settings)
+                                .await
+                                .map_err(|err| err.to_string())
+                        },
+                        Message::DevicesFound,
+                    );
+                }
+            }
+            Message::DevicesFound(result) => {
+                self.searching = false;
+                match result {
+                    Ok(devices) => self.devices = devices,
+                    Err(err) => self.status = Some(Err(err)),
+                }
+            }
+            Message::SyncPressed(index) => {
+                if let (Some(settings), Some(device)) =
+                    (get_lan_sync_settings(), self.devices.get(index).cloned())
+                {
+                    self.syncing = true;
+                    self.status = None;
+                    return Command::perform(
+                        async move {
+                            lan::sync_with(&device, &settings)
+                                .await
+                                .map(|summary| {
+                                    format!(
+                                        "Synced with {}, {} episodes updated",
+                                        summary.device_name, summary.changed_episodes
+                                    )
+                                })
+                                .map_err(|err| err.to_string())
+                        },
+                        Message::SyncComplete,
+                    );
+                }
+            }
+            Message::SyncComplete(result) => {
+                self.syncing = false;
+                self.status = Some(result);
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let lan_sync = get_lan_sync_settings();
+
+        let mut content = column![
+            text("Local Network Sync")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            text("Merge watch data with your other devices on the same network, the devices need the same pairing code").size(11),
+            checkbox(
+                "Allow syncing with devices on this network",
+                lan_sync.is_some(),
+                Message::EnabledToggled
+            ),
+        ]
+        .spacing(10);
+
+        if let Some(lan_sync) = lan_sync {
+            content = content.push(self.sync_view(lan_sync));
+        }
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+
+    fn sync_view(&self, lan_sync: LanSyncSettings) -> Element<'_, Message, Renderer> {
+        let device_name = column![
+            text("Device Name"),
+            text_input("device name", &lan_sync.device_name)
+                .on_input(Message::DeviceNameChanged)
+                .width(500)
+        ]
+        .spacing(5);
+
+        let pairing_code = column![
+            text("Pairing Code"),
+            text_input("pairing code", &lan_sync.pairing_code)
+                .on_input(Message::PairingCodeChanged)
+                .password()
+                .width(500),
+            text(format!(
+                "At least {} characters, the longer the harder to guess for anyone on the network",
+                lan::MIN_PAIRING_CODE_LEN
+            ))
+            .size(11),
+        ]
+        .spacing(5);
+
+        let status: Element<'_, Message, Renderer> = match &self.status {
+            Some(Ok(status)) => text(status)
+                .style(styles::text_styles::green_text_theme())
+                .into(),
+            Some(Err(err)) => text(err)
+                .style(styles::text_styles::red_text_theme())
+                .into(),
+            None => Space::new(0, 0).into(),
+        };
+
+        let find_devices_button = if self.searching {
+            button("Searching...")
+        } else {
+            button("Find Devices").on_press(Message::FindDevicesPressed)
+        };
+
+        let actions = row![
+            text(format!("{} devices found", self.devices.len())).size(11),
+            horizontal_space(Length::Fill),
+            status,
+            find_devices_button
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        let devices = Column::with_children(
+            self.devices
+                .iter()
+                .enumerate()
+                .map(|(index, device)| {
+                    let sync_button = if self.syncing {
+                        button("Sync")
+                    } else {
+                        button("Sync").on_press(Message::SyncPressed(index))
+                    };
+
+                    row![
+                        text(&device.name),
+                        text(device.address.to_string()).size(11),
+                        horizontal_space(Length::Fill),
+                        sync_button
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        column![device_name, pairing_code, actions, devices]
+            .spacing(10)
+            .into()
+    }
+}
+
+fn get_lan_sync_settings() -> Option<LanSyncSettings> {
+    SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .lan_sync
+        .clone()
+}
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use iced::widget::{button, checkbox, column, container, horizontal_space, row, text, Column};
+use iced::{Alignment, Command, Element, Length, Renderer};
+
+use crate::core::settings_config::SETTINGS;
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EnabledToggled(bool),
+    AddFolderPressed,
+    FolderPicked(Option<PathBuf>),
+    RemoveFolderPressed(usize),
+}
+
+#[derive(Default)]
+pub struct LocalFiles;
+
+impl LocalFiles {
+    pub fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::EnabledToggled(enabled) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .local_files
+                    .enabled = enabled;
+            }
+            Message::AddFolderPressed => {
+                return Command::perform(pick_folder(), Message::FolderPicked);
+            }
+            Message::FolderPicked(folder) => {
+                if let Some(folder) = folder {
+                    let mut settings = SETTINGS.write().unwrap();
+                    let folders = &mut settings.change_settings().local_files.folders;
+                    if !folders.contains(&folder) {
+                        folders.push(folder);
+                    }
+                }
+            }
+            Message::RemoveFolderPressed(index) => {
+                let mut settings = SETTINGS.write().unwrap();
+                let folders = &mut settings.change_settings().local_files.folders;
+                if index < folders.len() {
+                    folders.remove(index);
+                }
+            }
+        }
+        Command::none()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let local_files_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .local_files
+            .clone();
+
+        let enabled = column![
+            checkbox(
+                "Detect watched episodes from local files",
+                local_files_settings.enabled,
+                Message::EnabledToggled
+            ),
+            text("Video files of tracked shows appearing or being opened in the folders below, named like Show.Name.S02E05.mkv, are suggested for marking watched in My Shows")
+                .size(11)
+        ]
+        .spacing(5);
+
+        let folders: Element<'_, Message, Renderer> = if local_files_settings.folders.is_empty() {
+            text("No folders added").size(11).into()
+        } else {
+            Column::with_children(
+                local_files_settings
+                    .folders
+                    .iter()
+                    .enumerate()
+                    .map(|(index, folder)| {
+                        row![
+                            text(folder.display()).size(13),
+                            horizontal_space(Length::Fill),
+                            button(text("Remove").size(11))
+                                .on_press(Message::RemoveFolderPressed(index))
+                                .style(
+                                    styles::button_styles::transparent_button_with_rounded_border_theme()
+                                ),
+                        ]
+                        .spacing(5)
+                        .align_items(Alignment::Center)
+                        .into()
+                    })
+                    .collect(),
+            )
+            .spacing(5)
+            .into()
+        };
+
+        let content = column![
+            text("Local Files")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            enabled,
+            folders,
+            button("Add Folder").on_press(Message::AddFolderPressed),
+        ]
+        .spacing(5);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
+
+async fn pick_folder() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .pick_folder()
+        .await
+        .map(|folder_handle| folder_handle.path().to_owned())
+}
@@ -0,0 +1,139 @@
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, row, text, text_input, Column,
+};
+use iced::{Alignment, Element, Length, Renderer};
+
+use crate::core::settings_config::SETTINGS;
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EnabledToggled(bool),
+    PlayerInputChanged(String),
+    AddPlayerPressed,
+    RemovePlayerPressed(usize),
+}
+
+#[derive(Default)]
+pub struct MediaPlayers {
+    player_input: String,
+}
+
+impl MediaPlayers {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::EnabledToggled(enabled) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .media_players
+                    .enabled = enabled;
+            }
+            Message::PlayerInputChanged(player) => self.player_input = player,
+            Message::AddPlayerPressed => {
+                let player = self.player_input.trim().to_lowercase();
+                if player.is_empty() {
+                    return;
+                }
+
+                let mut settings = SETTINGS.write().unwrap();
+                let allowed_players = &mut settings.change_settings().media_players.allowed_players;
+                if !allowed_players.contains(&player) {
+                    allowed_players.push(player);
+                }
+                self.player_input.clear();
+            }
+            Message::RemovePlayerPressed(index) => {
+                let mut settings = SETTINGS.write().unwrap();
+                let allowed_players = &mut settings.change_settings().media_players.allowed_players;
+                if index < allowed_players.len() {
+                    allowed_players.remove(index);
+                }
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let media_player_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .media_players
+            .clone();
+
+        let mut content = column![text("Media Players")
+            .style(styles::text_styles::accent_color_theme())
+            .size(21)]
+        .spacing(5);
+
+        if !cfg!(all(unix, not(target_os = "macos"))) {
+            content = content.push(text("Only available on Linux").size(11));
+            return container(content)
+                .style(styles::container_styles::first_class_container_rounded_theme())
+                .padding(5)
+                .width(1000)
+                .into();
+        }
+
+        let enabled = column![
+            checkbox(
+                "Mark episodes watched from media players",
+                media_player_settings.enabled,
+                Message::EnabledToggled
+            ),
+            text("Episodes of tracked shows are marked watched once 90% is played in the players below, when they show titles like \"Show - S01E02 - Episode Name\"")
+                .size(11)
+        ]
+        .spacing(5);
+
+        let allowed_players: Element<'_, Message, Renderer> = if media_player_settings
+            .allowed_players
+            .is_empty()
+        {
+            text("No players allowed").size(11).into()
+        } else {
+            Column::with_children(
+                    media_player_settings
+                        .allowed_players
+                        .iter()
+                        .enumerate()
+                        .map(|(index, player)| {
+                            row![
+                                text(player).size(13),
+                                horizontal_space(Length::Fill),
+                                button(text("Remove").size(11))
+                                    .on_press(Message::RemovePlayerPressed(index))
+                                    .style(
+                                        styles::button_styles::transparent_button_with_rounded_border_theme()
+                                    ),
+                            ]
+                            .spacing(5)
+                            .align_items(Alignment::Center)
+                            .into()
+                        })
+                        .collect(),
+                )
+                .spacing(5)
+                .into()
+        };
+
+        let add_player = row![
+            text_input("player, like vlc or mpv", &self.player_input)
+                .on_input(Message::PlayerInputChanged)
+                .on_submit(Message::AddPlayerPressed)
+                .width(250),
+            button("Allow").on_press(Message::AddPlayerPressed),
+        ]
+        .spacing(5)
+        .align_items(Alignment::Center);
+
+        content = content.push(enabled).push(allowed_players).push(add_player);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
@@ -5,27 +5,63 @@ use iced::{Alignment, Command, Element, Length, Renderer};
 use crate::gui::assets::icons::GEAR_WIDE_CONNECTED;
 use crate::gui::styles;
 use about_widget::{About, Message as AboutMessage};
+use app_lock_widget::{AppLock, Message as AppLockMessage};
 use appearance_widget::{Appearance, Message as AppearanceMessage};
+use background_tasks_widget::{BackgroundTasks, Message as BackgroundTasksMessage};
+use backup_widget::{Backup, Message as BackupMessage};
+use content_filter_widget::{ContentFilter, Message as ContentFilterMessage};
+use data_location_widget::{DataLocations, Message as DataLocationsMessage};
 use database_widget::{Database, Message as DatabaseMessage};
+use deep_links_widget::{DeepLinks, Message as DeepLinksMessage};
 use discover_widget::{Discover, Message as DiscoverMessage};
+use hooks_widget::{Hooks, Message as HooksMessage};
+use lan_sync_widget::{LanSync, Message as LanSyncMessage};
+use local_files_widget::{LocalFiles, Message as LocalFilesMessage};
+use media_players_widget::{MediaPlayers, Message as MediaPlayersMessage};
+use network_widget::{Message as NetworkMessage, Network};
 use notifications_widget::{Message as NotificationsMessage, Notifications};
 use settings_controls_widget::{Message as SettingsControlsMessage, SettingsControls};
+use watch_goal_widget::{Message as WatchGoalMessage, WatchGoal};
 
 use super::Tab;
 
 mod about_widget;
+mod app_lock_widget;
 mod appearance_widget;
+mod background_tasks_widget;
+mod backup_widget;
+mod content_filter_widget;
+mod data_location_widget;
 mod database_widget;
+mod deep_links_widget;
 mod discover_widget;
+mod hooks_widget;
+mod lan_sync_widget;
+mod local_files_widget;
+mod media_players_widget;
+mod network_widget;
 mod notifications_widget;
 mod settings_controls_widget;
+mod watch_goal_widget;
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Appearance(AppearanceMessage),
     Database(DatabaseMessage),
+    DataLocations(DataLocationsMessage),
+    Backup(BackupMessage),
+    LanSync(LanSyncMessage),
     Notifications(NotificationsMessage),
+    WatchGoal(WatchGoalMessage),
+    Network(NetworkMessage),
+    LocalFiles(LocalFilesMessage),
+    MediaPlayers(MediaPlayersMessage),
+    Hooks(HooksMessage),
     Discover(DiscoverMessage),
+    DeepLinks(DeepLinksMessage),
+    ContentFilter(ContentFilterMessage),
+    AppLock(AppLockMessage),
+    BackgroundTasks(BackgroundTasksMessage),
     About(AboutMessage),
     Controls(SettingsControlsMessage),
     PageScrolled(Viewport),
@@ -34,8 +70,20 @@ pub enum Message {
 pub struct SettingsTab {
     appearance_settings: Appearance,
     database_settings: Database,
+    data_location_settings: DataLocations,
+    backup_settings: Backup,
+    lan_sync_settings: LanSync,
     notifications_settings: Notifications,
+    watch_goal_settings: WatchGoal,
+    network_settings: Network,
+    local_files_settings: LocalFiles,
+    media_players_settings: MediaPlayers,
+    hooks_settings: Hooks,
     discover_settings: Discover,
+    deep_links_settings: DeepLinks,
+    content_filter_settings: ContentFilter,
+    app_lock_settings: AppLock,
+    background_tasks: BackgroundTasks,
     about: About,
     controls_settings: SettingsControls,
     scrollable_offset: RelativeOffset,
@@ -44,21 +92,46 @@ pub struct SettingsTab {
 impl SettingsTab {
     pub fn new() -> (Self, Command<Message>) {
         let (about_widget, about_command) = About::new();
+        let (backup_widget, backup_command) = Backup::new();
+        let (deep_links_widget, deep_links_command) = DeepLinks::new();
         (
             Self {
                 appearance_settings: Appearance,
                 database_settings: Database::new(),
+                data_location_settings: DataLocations::default(),
+                backup_settings: backup_widget,
+                lan_sync_settings: LanSync::default(),
                 notifications_settings: Notifications,
+                watch_goal_settings: WatchGoal,
+                network_settings: Network,
+                local_files_settings: LocalFiles,
+                media_players_settings: MediaPlayers::default(),
+                hooks_settings: Hooks,
                 discover_settings: Discover::default(),
+                deep_links_settings: deep_links_widget,
+                content_filter_settings: ContentFilter::default(),
+                app_lock_settings: AppLock::default(),
+                background_tasks: BackgroundTasks::new(),
                 about: about_widget,
                 scrollable_offset: RelativeOffset::START,
                 controls_settings: SettingsControls,
             },
-            about_command.map(Message::About),
+            Command::batch([
+                about_command.map(Message::About),
+                backup_command.map(Message::Backup),
+                deep_links_command.map(Message::DeepLinks),
+            ]),
         )
     }
 
+    /// Subscriptions only needed while the settings are shown
     pub fn subscription(&self) -> iced::Subscription<Message> {
+        self.background_tasks
+            .subscription()
+            .map(Message::BackgroundTasks)
+    }
+
+    pub fn background_subscription(&self) -> iced::Subscription<Message> {
         self.database_settings.subscription().map(Message::Database)
     }
 
@@ -70,31 +143,85 @@ impl SettingsTab {
                     .update(message)
                     .map(Message::Database)
             }
+            Message::DataLocations(message) => {
+                return self
+                    .data_location_settings
+                    .update(message)
+                    .map(Message::DataLocations)
+            }
+            Message::Backup(message) => {
+                return self.backup_settings.update(message).map(Message::Backup)
+            }
+            Message::LanSync(message) => {
+                return self.lan_sync_settings.update(message).map(Message::LanSync)
+            }
             Message::Discover(message) => {
                 return self
                     .discover_settings
                     .update(message)
                     .map(Message::Discover)
             }
+            Message::DeepLinks(message) => {
+                return self
+                    .deep_links_settings
+                    .update(message)
+                    .map(Message::DeepLinks)
+            }
+            Message::LocalFiles(message) => {
+                return self
+                    .local_files_settings
+                    .update(message)
+                    .map(Message::LocalFiles)
+            }
             Message::About(message) => return self.about.update(message).map(Message::About),
             Message::Notifications(message) => self.notifications_settings.update(message),
+            Message::WatchGoal(message) => self.watch_goal_settings.update(message),
+            Message::Network(message) => self.network_settings.update(message),
+            Message::MediaPlayers(message) => self.media_players_settings.update(message),
+            Message::Hooks(message) => self.hooks_settings.update(message),
+            Message::ContentFilter(message) => self.content_filter_settings.update(message),
+            Message::AppLock(message) => self.app_lock_settings.update(message),
+            Message::BackgroundTasks(message) => self.background_tasks.update(message),
             Message::Appearance(message) => self.appearance_settings.update(message),
-            Message::Controls(message) => self.controls_settings.update(message),
+            Message::Controls(message) => {
+                return self
+                    .controls_settings
+                    .update(message)
+                    .map(Message::Controls)
+            }
             Message::PageScrolled(view_port) => {
                 self.scrollable_offset = view_port.relative_offset()
             }
         }
         Command::none()
     }
-    pub fn view(&self) -> Element<Message, Renderer> {
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
         let settings_body = scrollable(
             column![
                 self.appearance_settings.view().map(Message::Appearance),
                 self.database_settings.view().map(Message::Database),
+                self.data_location_settings
+                    .view()
+                    .map(Message::DataLocations),
+                self.backup_settings.view().map(Message::Backup),
+                self.lan_sync_settings.view().map(Message::LanSync),
                 self.notifications_settings
                     .view()
                     .map(Message::Notifications),
+                self.watch_goal_settings.view().map(Message::WatchGoal),
+                self.network_settings.view().map(Message::Network),
+                self.local_files_settings.view().map(Message::LocalFiles),
+                self.media_players_settings
+                    .view()
+                    .map(Message::MediaPlayers),
+                self.hooks_settings.view().map(Message::Hooks),
                 self.discover_settings.view().map(Message::Discover),
+                self.deep_links_settings.view().map(Message::DeepLinks),
+                self.content_filter_settings
+                    .view()
+                    .map(Message::ContentFilter),
+                self.app_lock_settings.view().map(Message::AppLock),
+                self.background_tasks.view().map(Message::BackgroundTasks),
                 self.about.view().map(Message::About),
             ]
             .spacing(10)
@@ -0,0 +1,130 @@
+use iced::widget::{checkbox, column, container, radio, text, Column};
+use iced::{Element, Renderer};
+
+use crate::core::api::tv_maze::data_usage;
+use crate::core::settings_config::{ImageQuality, ALL_IMAGE_QUALITIES, SETTINGS};
+use crate::gui::{helpers, styles};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PrefetchImagesToggled(bool),
+    BandwidthSaverToggled(bool),
+    ImageQualitySelected(ImageQuality),
+}
+
+#[derive(Default)]
+pub struct Network;
+
+impl Network {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::PrefetchImagesToggled(prefetch_images) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .network
+                    .prefetch_images = prefetch_images;
+            }
+            Message::BandwidthSaverToggled(bandwidth_saver) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .network
+                    .bandwidth_saver = bandwidth_saver;
+            }
+            Message::ImageQualitySelected(image_quality) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .network
+                    .image_quality = image_quality;
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let network_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .network
+            .clone();
+
+        let prefetch_images = column![
+            checkbox(
+                "Prefetch images of the next episodes",
+                network_settings.prefetch_images,
+                Message::PrefetchImagesToggled
+            ),
+            text("Load the images of the next episode of every tracked show when idle so that the watchlist shows them right away, turn off on metered connections")
+                .size(11)
+        ]
+        .spacing(5);
+
+        let bandwidth_saver = column![
+            checkbox(
+                "Bandwidth saver",
+                network_settings.bandwidth_saver,
+                Message::BandwidthSaverToggled
+            ),
+            text("Only show images already cached unless asked to load them, use smaller images and check for show updates weekly instead of daily")
+                .size(11)
+        ]
+        .spacing(5);
+
+        let image_qualities = Column::with_children(
+            ALL_IMAGE_QUALITIES
+                .into_iter()
+                .map(|image_quality| {
+                    radio(
+                        image_quality.to_string(),
+                        image_quality,
+                        Some(network_settings.image_quality),
+                        Message::ImageQualitySelected,
+                    )
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(5);
+
+        let image_quality = column![
+            text("Image quality"),
+            text("Auto loads the series page images in their original resolution and the rest in medium, original images are larger downloads")
+                .size(11),
+            image_qualities,
+        ]
+        .spacing(5);
+
+        let data_usage = column![
+            text("Data usage"),
+            text(format!(
+                "{} this session, {} this month",
+                helpers::data_size_str(data_usage::get_session_bytes()),
+                helpers::data_size_str(data_usage::get_month_bytes()),
+            ))
+            .size(11),
+        ]
+        .spacing(5);
+
+        let content = column![
+            text("Network")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            data_usage,
+            prefetch_images,
+            bandwidth_saver,
+            image_quality,
+        ]
+        .spacing(5);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
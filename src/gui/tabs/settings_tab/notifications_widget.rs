@@ -1,4 +1,4 @@
-use iced::widget::{column, container, text};
+use iced::widget::{checkbox, column, container, text};
 use iced::{Element, Length, Renderer};
 use iced_aw::NumberInput;
 
@@ -8,6 +8,9 @@ use crate::gui::styles;
 #[derive(Debug, Clone)]
 pub enum Message {
     TimeChanged(u32),
+    WeeklyDigestToggled(bool),
+    FollowedPeopleToggled(bool),
+    SnoozeTimeChanged(u32),
 }
 
 #[derive(Default)]
@@ -24,15 +27,40 @@ impl Notifications {
                     .notifications
                     .time_to_notify = new_time;
             }
+            Message::WeeklyDigestToggled(weekly_digest) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .notifications
+                    .weekly_digest = weekly_digest;
+            }
+            Message::FollowedPeopleToggled(followed_people) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .notifications
+                    .followed_people = followed_people;
+            }
+            Message::SnoozeTimeChanged(snooze_time) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .notifications
+                    .snooze_time = snooze_time;
+            }
         }
     }
     pub fn view(&self) -> Element<'_, Message, Renderer> {
-        let current_time_to_notify = SETTINGS
+        let notification_settings = SETTINGS
             .read()
             .unwrap()
             .get_current_settings()
             .notifications
-            .time_to_notify;
+            .clone();
+        let current_time_to_notify = notification_settings.time_to_notify;
 
         let notifications_info = column![
             text("When to notify"),
@@ -47,7 +75,59 @@ impl Notifications {
             NumberInput::new(current_time_to_notify, u32::MAX, Message::TimeChanged)
                 .width(Length::Fixed(200.0));
 
-        let content = column![notifications_info, time_to_notify,].spacing(5);
+        let snooze_time = column![
+            text("Snooze time"),
+            text(format!(
+                "Snoozed release notifications show again after {} minutes, or the next day with \"Remind me tomorrow\"",
+                notification_settings.snooze_time
+            ))
+            .size(11),
+            text(
+                "The Mark watched, Snooze, Remind me tomorrow and Open buttons of release \
+                notifications are only shown on Linux and BSD desktops following the \
+                freedesktop notification spec, elsewhere the notifications can only be closed",
+            )
+            .size(11),
+            NumberInput::new(
+                notification_settings.snooze_time,
+                u32::MAX,
+                Message::SnoozeTimeChanged
+            )
+            .min(1)
+            .width(Length::Fixed(200.0)),
+        ]
+        .spacing(5);
+
+        let weekly_digest = column![
+            checkbox(
+                "Send a weekly digest",
+                notification_settings.weekly_digest,
+                Message::WeeklyDigestToggled
+            ),
+            text("A weekly summary of what aired, what you watched and what is coming next week")
+                .size(11)
+        ]
+        .spacing(5);
+
+        let followed_people = column![
+            checkbox(
+                "Notify about people you follow",
+                notification_settings.followed_people,
+                Message::FollowedPeopleToggled
+            ),
+            text("New series and guest appearances of the people you follow in the cast of series")
+                .size(11)
+        ]
+        .spacing(5);
+
+        let content = column![
+            notifications_info,
+            time_to_notify,
+            snooze_time,
+            weekly_digest,
+            followed_people
+        ]
+        .spacing(5);
 
         let content = column![
             text("Notifications")
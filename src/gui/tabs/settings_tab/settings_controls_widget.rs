@@ -1,26 +1,62 @@
 use iced::widget::{button, horizontal_space, row};
-use iced::{Element, Length, Renderer};
+use iced::{Command, Element, Length, Renderer};
 
-use crate::core::settings_config::SETTINGS;
+use crate::core::settings_config::{Config, SETTINGS};
+use crate::gui::toast;
 
 #[derive(Clone, Debug)]
 pub enum Message {
     Save,
     Reset,
     RestoreDefaults,
+    Export,
+    ExportComplete(Result<bool, String>),
+    Import,
+    ImportComplete(Result<Option<Box<Config>>, String>),
 }
 
 #[derive(Default)]
 pub struct SettingsControls;
 
 impl SettingsControls {
-    pub fn update(&mut self, message: Message) {
+    pub fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Save => SETTINGS.write().unwrap().save_settings(),
             Message::Reset => SETTINGS.write().unwrap().reset_settings(),
             Message::RestoreDefaults => SETTINGS.write().unwrap().set_default_settings(),
+            Message::Export => {
+                return Command::perform(settings_transfer::export(), |res| {
+                    Message::ExportComplete(res.map_err(|err| err.to_string()))
+                })
+            }
+            Message::ExportComplete(res) => match res {
+                Ok(true) => toast::push("Settings exported"),
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::error!("failed to export settings: {}", err);
+                    toast::push("Could not export the settings");
+                }
+            },
+            Message::Import => {
+                return Command::perform(settings_transfer::import(), |res| {
+                    Message::ImportComplete(res.map_err(|err| err.to_string()))
+                })
+            }
+            Message::ImportComplete(res) => match res {
+                Ok(Some(config)) => {
+                    SETTINGS.write().unwrap().import_portable_settings(*config);
+                    toast::push("Settings imported, save them to keep them");
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::error!("failed to import settings: {}", err);
+                    toast::push("Could not import the settings");
+                }
+            },
         }
+        Command::none()
     }
+
     pub fn view(&self) -> Element<'_, Message, Renderer> {
         let mut save_settings_button = button("Save");
         let mut reset_settings_button = button("Reset");
@@ -37,6 +73,8 @@ impl SettingsControls {
         }
 
         row![
+            button("Export Settings").on_press(Message::Export),
+            button("Import Settings").on_press(Message::Import),
             horizontal_space(Length::Fill),
             restore_defaults_settings_button,
             reset_settings_button,
@@ -47,3 +85,49 @@ impl SettingsControls {
         .into()
     }
 }
+
+mod settings_transfer {
+    use directories::UserDirs;
+    use rfd::AsyncFileDialog;
+
+    use crate::core::settings_config::{self, Config};
+
+    /// Exports the settings to the chosen file, `false` when no file was chosen
+    pub async fn export() -> anyhow::Result<bool> {
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(get_home_directory()?)
+            .set_file_name("series-troxide-settings.toml")
+            .add_filter("toml", &["toml"])
+            .save_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            settings_config::export_portable_settings(chosen_path).await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    pub async fn import() -> anyhow::Result<Option<Box<Config>>> {
+        let chosen_path = AsyncFileDialog::new()
+            .set_directory(get_home_directory()?)
+            .add_filter("toml", &["toml"])
+            .pick_file()
+            .await
+            .map(|file_handle| file_handle.path().to_owned());
+
+        if let Some(chosen_path) = chosen_path {
+            let config = settings_config::read_portable_settings(chosen_path).await?;
+            return Ok(Some(Box::new(config)));
+        }
+
+        Ok(None)
+    }
+
+    fn get_home_directory() -> anyhow::Result<std::path::PathBuf> {
+        let user_dirs = UserDirs::new().ok_or(anyhow::anyhow!("could not get user directory"))?;
+        Ok(user_dirs.home_dir().to_path_buf())
+    }
+}
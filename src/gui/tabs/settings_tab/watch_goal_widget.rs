@@ -0,0 +1,120 @@
+use iced::widget::{checkbox, column, container, radio, text, Row};
+use iced::{Element, Length, Renderer};
+use iced_aw::NumberInput;
+
+use crate::core::settings_config::{WatchGoalPeriod, ALL_WATCH_GOAL_PERIODS, SETTINGS};
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    GoalMinutesChanged(u32),
+    PeriodSelected(WatchGoalPeriod),
+    NotifyWhenExceededToggled(bool),
+}
+
+#[derive(Default)]
+pub struct WatchGoal;
+
+impl WatchGoal {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::GoalMinutesChanged(minutes) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .watch_goal
+                    .minutes = minutes;
+            }
+            Message::PeriodSelected(period) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .watch_goal
+                    .period = period;
+            }
+            Message::NotifyWhenExceededToggled(notify_when_exceeded) => {
+                SETTINGS
+                    .write()
+                    .unwrap()
+                    .change_settings()
+                    .watch_goal
+                    .notify_when_exceeded = notify_when_exceeded;
+            }
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let watch_goal_settings = SETTINGS
+            .read()
+            .unwrap()
+            .get_current_settings()
+            .watch_goal
+            .clone();
+
+        let goal = column![
+            text("Watch time goal"),
+            text("Minutes of watching you are aiming for or don't want to go over, 0 for no goal. Progress is shown in the statistics tab")
+                .size(11),
+            NumberInput::new(
+                watch_goal_settings.minutes,
+                u32::MAX,
+                Message::GoalMinutesChanged
+            )
+            .step(30)
+            .width(Length::Fixed(200.0)),
+        ]
+        .spacing(5);
+
+        let periods = Row::with_children(
+            ALL_WATCH_GOAL_PERIODS
+                .into_iter()
+                .map(|period| {
+                    radio(
+                        period.to_string(),
+                        period,
+                        Some(watch_goal_settings.period),
+                        Message::PeriodSelected,
+                    )
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(10);
+
+        let period = column![
+            text("Period"),
+            text(
+                "Weeks start on the first day of the week set in Appearance and months on the 1st"
+            )
+            .size(11),
+            periods,
+        ]
+        .spacing(5);
+
+        let notify_when_exceeded = column![
+            checkbox(
+                "Notify when the goal is exceeded",
+                watch_goal_settings.notify_when_exceeded,
+                Message::NotifyWhenExceededToggled
+            ),
+            text("A single notification for each week or month you go over the goal").size(11)
+        ]
+        .spacing(5);
+
+        let content = column![
+            text("Watch Goal")
+                .style(styles::text_styles::accent_color_theme())
+                .size(21),
+            column![goal, period, notify_when_exceeded].spacing(10),
+        ]
+        .spacing(5);
+
+        container(content)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .padding(5)
+            .width(1000)
+            .into()
+    }
+}
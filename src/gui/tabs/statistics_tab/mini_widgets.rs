@@ -3,7 +3,7 @@ use iced::{Alignment, Element, Length, Renderer};
 use iced_aw::Grid;
 
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
-use crate::core::database;
+use crate::core::{database, statistics};
 use crate::gui::{helpers, styles};
 
 use super::Message;
@@ -40,7 +40,7 @@ pub fn watch_count() -> Element<'static, Message, Renderer> {
     ]
     .align_items(Alignment::Center);
 
-    let content = column![
+    let mut content = column![
         text("You've seen a total of"),
         episodes_count,
         text("In exactly"),
@@ -49,6 +49,40 @@ pub fn watch_count() -> Element<'static, Message, Renderer> {
     .align_items(Alignment::Center)
     .spacing(5);
 
+    // Skipped episodes are not part of the watch statistics, only mentioned
+    let skipped_total_number = database::DB.get_all_skipped_episodes_count();
+    if skipped_total_number != 0 {
+        content = content.push(
+            text(format!(
+                "Not counting {} skipped episodes",
+                skipped_total_number
+            ))
+            .size(11),
+        );
+    }
+
+    let auto_marked_total_number = database::DB.get_all_auto_marked_episodes_count();
+    if auto_marked_total_number != 0 {
+        content = content.push(
+            text(format!(
+                "Including {} episodes marked automatically as they aired",
+                auto_marked_total_number
+            ))
+            .size(11),
+        );
+    }
+
+    let streaks = statistics::get_streaks();
+    if streaks.longest_days != 0 {
+        content = content.push(
+            text(format!(
+                "{} day streak, the longest being {} days",
+                streaks.current_days, streaks.longest_days
+            ))
+            .size(11),
+        );
+    }
+
     container(content)
         .width(Length::Fill)
         .height(Length::Fill)
@@ -62,10 +96,7 @@ pub fn watch_count() -> Element<'static, Message, Renderer> {
 pub fn time_count(
     series_infos_and_time: &[(SeriesMainInformation, Option<u32>)],
 ) -> Element<'_, Message, Renderer> {
-    let total_average_minutes: u32 = series_infos_and_time
-        .iter()
-        .map(|(_, average_runtime)| average_runtime.unwrap_or(0))
-        .sum();
+    let total_average_minutes = statistics::total_watch_minutes(series_infos_and_time);
 
     let total_minutes_count = column![
         text(total_average_minutes)
@@ -124,30 +155,11 @@ pub fn time_count(
 }
 
 pub fn genre_stats(series_infos: Vec<&SeriesMainInformation>) -> Element<'_, Message, Renderer> {
-    use crate::core::api::tv_maze::series_information::Genre;
-    use std::collections::HashMap;
-
     if series_infos.is_empty() {
         return Space::new(0, 0).into();
     }
 
-    let mut genre_count: HashMap<Genre, usize> = HashMap::new();
-
-    series_infos.iter().for_each(|series_info| {
-        series_info.get_genres().into_iter().for_each(|genre| {
-            genre_count
-                .entry(genre)
-                .and_modify(|count| *count += 1)
-                .or_insert(1);
-        })
-    });
-
-    let mut genre_count: Vec<(Genre, usize)> = genre_count
-        .into_iter()
-        .map(|(genre, count)| (genre, count))
-        .collect();
-
-    genre_count.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+    let genre_count = statistics::genre_count(&series_infos);
 
     let mut content = Grid::with_columns(2);
 
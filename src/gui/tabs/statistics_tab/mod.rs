@@ -5,48 +5,62 @@ use iced::widget::{column, container, row, scrollable, text};
 use iced::{Command, Element, Length, Renderer};
 use iced_aw::Wrap;
 
-use crate::core::{api::tv_maze::series_information::SeriesMainInformation, database};
+use crate::core::api::tv_maze::series_information::SeriesMainInformation;
+use crate::core::statistics::get_series_with_runtime;
 use crate::gui::assets::icons::GRAPH_UP_ARROW;
 use crate::gui::styles;
 use series_banner::{IndexedMessage, Message as SeriesBannerMessage, SeriesBanner};
+use watch_goal_widget::{Message as WatchGoalMessage, WatchGoal};
+use weekly_digest_widget::{Message as WeeklyDigestMessage, WeeklyDigest};
 
 use mini_widgets::*;
 
 use super::Tab;
 
 mod mini_widgets;
+mod watch_goal_widget;
+mod weekly_digest_widget;
 
 #[derive(Clone, Debug)]
 pub enum Message {
     SeriesInfosAndTimeReceived(Vec<(SeriesMainInformation, Option<u32>)>),
     SeriesBanner(IndexedMessage<usize, SeriesBannerMessage>),
     PageScrolled(Viewport),
+    WeeklyDigest(WeeklyDigestMessage),
+    WatchGoal(WatchGoalMessage),
 }
 
 pub struct StatisticsTab<'a> {
     series_infos_and_time: Vec<(SeriesMainInformation, Option<u32>)>,
     series_banners: Vec<SeriesBanner<'a>>,
+    weekly_digest: WeeklyDigest,
+    watch_goal: WatchGoal,
     series_page_sender: mpsc::Sender<SeriesMainInformation>,
     scrollable_offset: RelativeOffset,
 }
 
 impl<'a> StatisticsTab<'a> {
-    pub fn new(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-        scrollable_offset: Option<RelativeOffset>,
-    ) -> (Self, Command<Message>) {
-        (
-            Self {
-                series_infos_and_time: vec![],
-                series_banners: vec![],
-                series_page_sender,
-                scrollable_offset: scrollable_offset.unwrap_or(RelativeOffset::START),
-            },
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            series_infos_and_time: vec![],
+            series_banners: vec![],
+            weekly_digest: WeeklyDigest::default(),
+            watch_goal: WatchGoal::default(),
+            series_page_sender,
+            scrollable_offset: RelativeOffset::START,
+        }
+    }
+
+    /// Recomputes the statistics, the current ones stay shown until they are recomputed
+    pub fn refresh(&self) -> Command<Message> {
+        Command::batch([
             Command::perform(
                 get_series_with_runtime(),
                 Message::SeriesInfosAndTimeReceived,
             ),
-        )
+            self.weekly_digest.refresh().map(Message::WeeklyDigest),
+            self.watch_goal.refresh().map(Message::WatchGoal),
+        ])
     }
 
     pub fn update(&mut self, message: Message) -> Command<Message> {
@@ -74,16 +88,26 @@ impl<'a> StatisticsTab<'a> {
                 Command::batch(banners_commands).map(Message::SeriesBanner)
             }
             Message::SeriesBanner(message) => {
-                self.series_banners[message.index()].update(message);
+                if let Some(banner) = self.series_banners.get_mut(message.index()) {
+                    banner.update(message);
+                }
                 Command::none()
             }
             Message::PageScrolled(view_port) => {
                 self.scrollable_offset = view_port.relative_offset();
                 Command::none()
             }
+            Message::WeeklyDigest(message) => {
+                self.weekly_digest.update(message);
+                Command::none()
+            }
+            Message::WatchGoal(message) => {
+                self.watch_goal.update(message);
+                Command::none()
+            }
         }
     }
-    pub fn view(&self) -> Element<Message, Renderer> {
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
         let series_list: Element<'_, Message, Renderer> = if self.series_banners.is_empty() {
             text("Your watched series will appear here").into()
         } else {
@@ -106,19 +130,24 @@ impl<'a> StatisticsTab<'a> {
             .map(|(series_info, _)| series_info)
             .collect();
 
-        let content = column![
-            row![
-                watch_count(),
-                genre_stats(series_infos),
-                time_count(&self.series_infos_and_time)
-            ]
-            .height(200)
-            .spacing(10),
-            series_list
+        let mut content = column![row![
+            watch_count(),
+            genre_stats(series_infos),
+            time_count(&self.series_infos_and_time)
         ]
+        .height(200)
+        .spacing(10)]
         .spacing(10)
         .padding(10);
 
+        if let Some(watch_goal) = self.watch_goal.view() {
+            content = content.push(watch_goal.map(Message::WatchGoal));
+        }
+
+        let content = content
+            .push(self.weekly_digest.view().map(Message::WeeklyDigest))
+            .push(series_list);
+
         container(
             scrollable(content)
                 .id(Self::scrollable_id())
@@ -131,26 +160,6 @@ impl<'a> StatisticsTab<'a> {
     }
 }
 
-/// Get the collection of all series with their associated total
-/// average runtime
-async fn get_series_with_runtime() -> Vec<(SeriesMainInformation, Option<u32>)> {
-    let series_ids_handles: Vec<_> = database::DB
-        .get_series_collection()
-        .into_iter()
-        .map(|series| tokio::spawn(async move { series.get_total_average_watchtime().await }))
-        .collect();
-
-    let mut infos_and_time = Vec::with_capacity(series_ids_handles.len());
-    for handle in series_ids_handles {
-        infos_and_time.push(
-            handle
-                .await
-                .expect("failed to await all series_infos and their average runtime"),
-        );
-    }
-    infos_and_time
-}
-
 impl<'a> Tab for StatisticsTab<'a> {
     type Message = Message;
 
@@ -0,0 +1,82 @@
+use iced::widget::{column, container, progress_bar, text};
+use iced::{Command, Element, Length, Renderer};
+
+use crate::core::settings_config::WatchGoalPeriod;
+use crate::core::watch_goal::{self, WatchGoalProgress};
+use crate::gui::styles;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ProgressLoaded(Option<WatchGoalProgress>),
+}
+
+/// The watch time of the current week or month next to the goal set in the settings
+#[derive(Default)]
+pub struct WatchGoal {
+    progress: Option<WatchGoalProgress>,
+}
+
+impl WatchGoal {
+    pub fn refresh(&self) -> Command<Message> {
+        Command::perform(watch_goal::get_watch_goal_progress(), |progress| {
+            Message::ProgressLoaded(
+                progress
+                    .map_err(|err| {
+                        tracing::error!("failed to load the watch goal progress: {}", err)
+                    })
+                    .ok()
+                    .flatten(),
+            )
+        })
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::ProgressLoaded(progress) => self.progress = progress,
+        }
+    }
+
+    /// Nothing is shown when no goal is set
+    pub fn view(&self) -> Option<Element<'_, Message, Renderer>> {
+        let progress = self.progress.as_ref()?;
+
+        let period = match progress.period {
+            WatchGoalPeriod::Weekly => "this week",
+            WatchGoalPeriod::Monthly => "this month",
+        };
+
+        let summary = if progress.is_exceeded() {
+            format!(
+                "{} watched {}, {} over your goal of {}",
+                watch_goal::minutes_str(progress.watched_minutes),
+                period,
+                watch_goal::minutes_str(progress.watched_minutes - progress.goal_minutes),
+                watch_goal::minutes_str(progress.goal_minutes),
+            )
+        } else {
+            format!(
+                "{} watched {} out of your goal of {}",
+                watch_goal::minutes_str(progress.watched_minutes),
+                period,
+                watch_goal::minutes_str(progress.goal_minutes),
+            )
+        };
+
+        let content = column![
+            text("Watch Goal")
+                .size(21)
+                .style(styles::text_styles::accent_color_theme()),
+            progress_bar(0.0..=1.0, progress.fraction().min(1.0)).height(10),
+            text(summary).size(11),
+        ]
+        .spacing(10);
+
+        Some(
+            container(content)
+                .style(styles::container_styles::first_class_container_rounded_theme())
+                .padding(10)
+                .width(Length::Fill)
+                .into(),
+        )
+    }
+}
@@ -0,0 +1,131 @@
+use iced::widget::{column, container, row, text, Column};
+use iced::{Command, Element, Length, Renderer};
+use iced_aw::Spinner;
+
+use crate::core::date_formatting;
+use crate::core::digest::{self, DigestEpisode, WeeklyDigest as WeeklyDigestData};
+use crate::gui::{helpers, styles};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    DigestLoaded(Option<WeeklyDigestData>),
+}
+
+#[derive(Default)]
+enum LoadState {
+    #[default]
+    Loading,
+    Loaded(Option<WeeklyDigestData>),
+}
+
+/// What aired, what was watched and what is airing around this week
+#[derive(Default)]
+pub struct WeeklyDigest {
+    load_state: LoadState,
+}
+
+impl WeeklyDigest {
+    pub fn refresh(&self) -> Command<Message> {
+        Command::perform(digest::get_weekly_digest(), |digest| {
+            Message::DigestLoaded(
+                digest
+                    .map_err(|err| tracing::error!("failed to load the weekly digest: {}", err))
+                    .ok(),
+            )
+        })
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::DigestLoaded(digest) => self.load_state = LoadState::Loaded(digest),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
+        let body: Element<'_, Message, Renderer> = match &self.load_state {
+            LoadState::Loading => container(Spinner::new())
+                .center_x()
+                .width(Length::Fill)
+                .into(),
+            LoadState::Loaded(None) => text("Failed to load the weekly digest").into(),
+            LoadState::Loaded(Some(digest)) => row![
+                digest_section(
+                    "Aired last week",
+                    &digest.aired_last_week,
+                    "Nothing aired last week"
+                ),
+                digest_section(
+                    "Watched last week",
+                    &digest.watched_last_week,
+                    "Nothing watched last week"
+                ),
+                digest_section(
+                    "Airing next week",
+                    &digest.airing_next_week,
+                    "Nothing airing next week"
+                ),
+            ]
+            .spacing(10)
+            .into(),
+        };
+
+        container(
+            column![
+                text("Weekly Digest")
+                    .size(21)
+                    .style(styles::text_styles::accent_color_theme()),
+                body
+            ]
+            .spacing(10),
+        )
+        .style(styles::container_styles::first_class_container_rounded_theme())
+        .padding(10)
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+fn digest_section<'a>(
+    title: &'a str,
+    episodes: &'a [DigestEpisode],
+    empty_text: &'a str,
+) -> Element<'a, Message, Renderer> {
+    let episodes: Element<'_, Message, Renderer> = if episodes.is_empty() {
+        text(empty_text).size(11).into()
+    } else {
+        Column::with_children(
+            episodes
+                .iter()
+                .map(|episode| {
+                    column![
+                        text(&episode.series_name).style(styles::text_styles::accent_color_theme()),
+                        text(format!(
+                            "{}{}",
+                            helpers::season_episode_str_gen(episode.season, episode.number),
+                            episode
+                                .name
+                                .as_ref()
+                                .map(|name| format!(": {}", name))
+                                .unwrap_or_default()
+                        ))
+                        .size(11),
+                        text(format!(
+                            "{} {}",
+                            date_formatting::long_date_str(episode.date.date_naive()),
+                            date_formatting::time_str(&episode.date)
+                        ))
+                        .size(11),
+                    ]
+                    .into()
+                })
+                .collect(),
+        )
+        .spacing(10)
+        .into()
+    };
+
+    column![text(title), episodes]
+        .spacing(10)
+        .width(Length::FillPortion(1))
+        .into()
+}
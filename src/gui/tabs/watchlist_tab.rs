@@ -1,18 +1,21 @@
 use std::sync::mpsc;
 
 use iced::widget::scrollable::{RelativeOffset, Viewport};
-use iced::widget::{column, container, scrollable, text, Column, Space};
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, Column, Space};
 use iced::{Command, Element, Length, Renderer};
-use iced_aw::Spinner;
+use iced_aw::{NumberInput, Spinner};
 
 use super::Tab;
+use crate::core::api::tv_maze::episodes_information::Episode;
 use crate::core::api::tv_maze::series_information::SeriesMainInformation;
 use crate::core::caching::episode_list::EpisodeList;
+use crate::core::caching::refresh_queue::{self, RefreshPriority};
 use crate::core::caching::series_list;
-use crate::core::{caching, database};
+use crate::core::{caching, database, random_picker};
 use crate::gui::assets::icons::CARD_CHECKLIST;
+use crate::gui::helpers::season_episode_str_gen;
 use crate::gui::message::IndexedMessage;
-use crate::gui::styles;
+use crate::gui::{styles, toast};
 use watchlist_poster::{Message as WatchlistPosterMessage, WatchlistPoster};
 use watchlist_summary::WatchlistSummary;
 
@@ -21,8 +24,15 @@ pub enum Message {
     SeriesInformationLoaded(Vec<(SeriesMainInformation, EpisodeList, usize)>),
     WatchlistPoster(IndexedMessage<usize, WatchlistPosterMessage>),
     PageScrolled(Viewport),
+    ShortEpisodesOnlyToggled(bool),
+    AvailableMinutesChanged(u32),
+    SurpriseMePressed,
+    RandomEpisodePicked(Result<Option<Box<(SeriesMainInformation, Episode)>>, String>),
 }
 
+/// The default amount of time available for watching when filtering short episodes
+const DEFAULT_AVAILABLE_MINUTES: u32 = 30;
+
 #[derive(Default)]
 enum LoadState {
     #[default]
@@ -36,25 +46,31 @@ pub struct WatchlistTab<'a> {
     watchlist_summary: Option<WatchlistSummary>,
     series_page_sender: mpsc::Sender<SeriesMainInformation>,
     scrollable_offset: RelativeOffset,
+    /// Whether only the series whose next episode fits in the available minutes are shown
+    short_episodes_only: bool,
+    available_minutes: u32,
+    picking_random_episode: bool,
 }
 
 impl<'a> WatchlistTab<'a> {
-    pub fn new(
-        series_page_sender: mpsc::Sender<SeriesMainInformation>,
-        scrollable_offset: Option<RelativeOffset>,
-    ) -> (Self, Command<Message>) {
-        (
-            Self {
-                watchlist_posters: vec![],
-                watchlist_summary: None,
-                load_state: LoadState::Loading,
-                series_page_sender,
-                scrollable_offset: scrollable_offset.unwrap_or(RelativeOffset::START),
-            },
-            Command::perform(
-                get_series_information_and_watched_episodes(),
-                Message::SeriesInformationLoaded,
-            ),
+    pub fn new(series_page_sender: mpsc::Sender<SeriesMainInformation>) -> Self {
+        Self {
+            watchlist_posters: vec![],
+            watchlist_summary: None,
+            load_state: LoadState::Loading,
+            series_page_sender,
+            scrollable_offset: RelativeOffset::START,
+            short_episodes_only: false,
+            available_minutes: DEFAULT_AVAILABLE_MINUTES,
+            picking_random_episode: false,
+        }
+    }
+
+    /// Reloads the watchlist, the current one stays shown until it is reloaded
+    pub fn refresh(&self) -> Command<Message> {
+        Command::perform(
+            get_series_information_and_watched_episodes(),
+            Message::SeriesInformationLoaded,
         )
     }
 
@@ -102,16 +118,115 @@ impl<'a> WatchlistTab<'a> {
 
                 Command::batch(commands).map(Message::WatchlistPoster)
             }
-            Message::WatchlistPoster(message) => self.watchlist_posters[message.index()]
-                .update(message)
-                .map(Message::WatchlistPoster),
+            // Posters of a watchlist since reloaded may still be sending messages
+            Message::WatchlistPoster(message) => self
+                .watchlist_posters
+                .get_mut(message.index())
+                .map(|poster| poster.update(message).map(Message::WatchlistPoster))
+                .unwrap_or_else(Command::none),
             Message::PageScrolled(view_port) => {
                 self.scrollable_offset = view_port.relative_offset();
                 Command::none()
             }
+            Message::ShortEpisodesOnlyToggled(short_episodes_only) => {
+                self.short_episodes_only = short_episodes_only;
+                Command::none()
+            }
+            Message::AvailableMinutesChanged(available_minutes) => {
+                self.available_minutes = available_minutes;
+                Command::none()
+            }
+            Message::SurpriseMePressed => {
+                self.picking_random_episode = true;
+                Command::perform(
+                    async {
+                        random_picker::pick_random_unwatched_episode()
+                            .await
+                            .map(|picked| picked.map(Box::new))
+                            .map_err(|err| err.to_string())
+                    },
+                    Message::RandomEpisodePicked,
+                )
+            }
+            Message::RandomEpisodePicked(result) => {
+                self.picking_random_episode = false;
+                match result {
+                    Ok(Some(picked)) => {
+                        let (series_info, episode) = *picked;
+                        self.open_random_episode(series_info, episode)
+                    }
+                    Ok(None) => toast::push("No unwatched episodes to pick from"),
+                    Err(err) => {
+                        tracing::error!("failed to pick a random episode: {}", err);
+                        toast::push("Could not pick a random episode");
+                    }
+                }
+                Command::none()
+            }
+        }
+    }
+
+    /// Opens the series page of the picked episode with its season already expanded
+    fn open_random_episode(&self, series_info: SeriesMainInformation, episode: Episode) {
+        let mut expanded_seasons = database::DB.get_expanded_seasons(series_info.id);
+        expanded_seasons.insert(episode.season);
+        database::DB.set_expanded_seasons(series_info.id, &expanded_seasons);
+
+        toast::push(format!(
+            "Surprise! Watch {} \"{}\" of {}",
+            season_episode_str_gen(episode.season, episode.number.unwrap_or_default()),
+            episode.name,
+            series_info.name
+        ));
+
+        self.series_page_sender
+            .send(series_info)
+            .expect("failed to send series page info");
+    }
+
+    fn surprise_me_button(&self) -> Element<'_, Message, Renderer> {
+        let label = if self.picking_random_episode {
+            "Picking..."
+        } else {
+            "Surprise me"
+        };
+
+        let button = button(text(label))
+            .style(styles::button_styles::transparent_button_with_rounded_border_theme());
+
+        if self.picking_random_episode {
+            button.into()
+        } else {
+            button.on_press(Message::SurpriseMePressed).into()
         }
     }
-    pub fn view(&self) -> Element<Message, Renderer> {
+
+    fn short_episodes_filter(&self) -> Element<'_, Message, Renderer> {
+        let content = row![
+            checkbox(
+                "Only what I can watch in under",
+                self.short_episodes_only,
+                Message::ShortEpisodesOnlyToggled
+            ),
+            NumberInput::new(
+                self.available_minutes,
+                24 * 60,
+                Message::AvailableMinutesChanged
+            )
+            .min(1)
+            .width(Length::Fixed(100.0)),
+            text("minutes"),
+        ]
+        .spacing(5)
+        .align_items(iced::Alignment::Center);
+
+        container(content)
+            .padding(5)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .into()
+    }
+
+    pub fn view(&self) -> Element<'_, Message, Renderer> {
         match self.load_state {
             LoadState::Loading => container(Spinner::new())
                 .width(Length::Fill)
@@ -131,12 +246,23 @@ impl<'a> WatchlistTab<'a> {
                     .width(Length::Fill)
                     .into()
                 } else {
-                    let watchlist_items: Vec<Element<'_, Message, Renderer>> = self
+                    let mut watchlist_items: Vec<Element<'_, Message, Renderer>> = self
                         .watchlist_posters
                         .iter()
+                        .filter(|poster| {
+                            !self.short_episodes_only
+                                || poster
+                                    .get_next_episode_runtime()
+                                    .map(|runtime| runtime <= self.available_minutes)
+                                    .unwrap_or(false)
+                        })
                         .map(|poster| poster.view().map(Message::WatchlistPoster))
                         .collect();
 
+                    if watchlist_items.is_empty() {
+                        watchlist_items.push(text("Nothing fits in the available time").into());
+                    }
+
                     let watchlist_summary = self
                         .watchlist_summary
                         .as_ref()
@@ -148,10 +274,16 @@ impl<'a> WatchlistTab<'a> {
                         .align_items(iced::Alignment::Center)
                         .width(Length::Fill);
 
-                    let content = column![watchlist_summary, watchlist_items]
-                        .padding(5)
-                        .spacing(10)
-                        .align_items(iced::Alignment::Center);
+                    let content = column![
+                        watchlist_summary,
+                        row![self.short_episodes_filter(), self.surprise_me_button()]
+                            .spacing(10)
+                            .align_items(iced::Alignment::Center),
+                        watchlist_items
+                    ]
+                    .padding(5)
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center);
 
                     scrollable(content)
                         .direction(styles::scrollable_styles::vertical_direction())
@@ -167,7 +299,9 @@ impl<'a> WatchlistTab<'a> {
 /// checks of the given series has pending episodes to be watched in the database. That given series
 /// is provided through it's EpisodeList Structure.
 fn has_pending_episodes(database_series: &database::Series, episodes_list: &EpisodeList) -> bool {
-    episodes_list.get_total_watchable_episodes() != database_series.get_total_episodes()
+    let skipped_episodes = database::DB.get_total_skipped_episodes(database_series.id(), None);
+    episodes_list.get_total_watchable_episodes()
+        > database_series.get_total_episodes() + skipped_episodes
 }
 
 async fn get_series_information_and_watched_episodes(
@@ -179,7 +313,12 @@ async fn get_series_information_and_watched_episodes(
 
     let episode_lists_handles: Vec<_> = tracked_series_information
         .iter()
-        .map(|series_info| tokio::spawn(caching::episode_list::EpisodeList::new(series_info.id)))
+        .map(|series_info| {
+            refresh_queue::spawn(
+                RefreshPriority::Visible,
+                caching::episode_list::EpisodeList::new(series_info.id),
+            )
+        })
         .collect();
 
     let mut episodes_lists = Vec::with_capacity(episode_lists_handles.len());
@@ -194,7 +333,7 @@ async fn get_series_information_and_watched_episodes(
 
     tracked_series_information
         .into_iter()
-        .zip(episodes_lists.into_iter())
+        .zip(episodes_lists)
         .filter(|(series_info, episode_list)| {
             let series = database::DB.get_series(series_info.id).unwrap();
             has_pending_episodes(&series, episode_list)
@@ -262,6 +401,15 @@ mod watchlist_poster {
     }
 
     impl<'a> WatchlistPoster<'a> {
+        /// The runtime in minutes of the next episode to watch, falling back to the series'
+        /// average runtime when the episode has none
+        pub fn get_next_episode_runtime(&self) -> Option<u32> {
+            self.episode_list
+                .get_next_episode_to_watch()
+                .and_then(|episode| episode.runtime)
+                .or(self.poster.get_series_info().average_runtime)
+        }
+
         pub fn new(
             index: usize,
             series_info: std::borrow::Cow<'a, SeriesMainInformation>,
@@ -343,12 +491,13 @@ mod watchlist_poster {
             self.current_poster_id += 1;
 
             if let Some(episode) = self.episode_list.get_next_episode_to_watch() {
-                let (episode_poster, episode_poster_command) = EpisodePoster::new(
+                let (mut episode_poster, episode_poster_command) = EpisodePoster::new(
                     self.current_poster_id,
                     self.poster.get_series_info().id,
                     self.poster.get_series_info().name.clone(),
                     episode.clone(),
                 );
+                episode_poster.set_badge(self.episode_list.get_episode_badge(episode));
                 let index = self.index;
                 self.episode_poster = Some(episode_poster);
                 episode_poster_command
@@ -379,7 +528,8 @@ mod watchlist_poster {
             let watched_episodes = database::DB
                 .get_series(self.poster.get_series_info().id)
                 .map(|series| series.get_total_episodes())
-                .unwrap_or(0);
+                .unwrap_or(0)
+                + database::DB.get_total_skipped_episodes(self.poster.get_series_info().id, None);
 
             let progress_bar = row![
                 progress_bar(
@@ -478,6 +628,15 @@ mod watchlist_summary {
         total_episodes: usize,
     }
 
+    /// The watched and skipped episodes of the series, skipped ones needing no time to watch
+    fn completed_episodes(series_id: u32) -> usize {
+        database::DB
+            .get_series(series_id)
+            .map(|series| series.get_total_episodes())
+            .unwrap_or(0)
+            + database::DB.get_total_skipped_episodes(series_id, None)
+    }
+
     impl WatchlistSummary {
         pub fn new(total_episodes: usize, series_ids: Vec<(u32, u32, u32)>) -> Self {
             Self {
@@ -496,12 +655,7 @@ mod watchlist_summary {
                     self.series_ids
                         .iter()
                         .map(|(id, total_episodes, time)| {
-                            let watched_episodes = database::DB
-                                .get_series(*id)
-                                .map(|series| series.get_total_episodes())
-                                .unwrap_or(0);
-
-                            (total_episodes - watched_episodes as u32) * time
+                            total_episodes.saturating_sub(completed_episodes(*id) as u32) * time
                         })
                         .sum(),
                 )
@@ -511,15 +665,10 @@ mod watchlist_summary {
             let total_episodes_watched: usize = self
                 .series_ids
                 .iter()
-                .map(|tup| {
-                    database::DB
-                        .get_series(tup.0)
-                        .map(|series| series.get_total_episodes())
-                        .unwrap_or(0)
-                })
+                .map(|tup| completed_episodes(tup.0))
                 .sum();
 
-            let episodes_left_to_watch = self.total_episodes - total_episodes_watched;
+            let episodes_left_to_watch = self.total_episodes.saturating_sub(total_episodes_watched);
 
             let total_episodes = Self::summary_item(
                 "Total Episodes to Watch",
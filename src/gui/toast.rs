@@ -0,0 +1,166 @@
+//! # Toast notifications
+//!
+//! Short lived messages shown at the bottom of the window, used to confirm actions
+//! that happen in place without any other visual feedback.
+//!
+//! Toasts can be pushed from anywhere in the gui, they are expired by the application's
+//! subscription which only runs when there are toasts being shown. Toasts with an action, like
+//! opening a link or undoing what was just done, stay longer so that there is time to press it.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Element, Renderer};
+use lazy_static::lazy_static;
+
+use super::styles;
+use crate::core::database::ClearedSeason;
+use crate::core::export::checklist::AppliedChecklist;
+
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// How long a toast with an action stays on screen
+const ACTION_TOAST_DURATION: Duration = Duration::from_secs(10);
+
+/// The maximum amount of toasts shown at once, older ones are dropped first
+const MAX_TOASTS: usize = 3;
+
+/// How often toasts are checked for expiry
+pub const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+lazy_static! {
+    static ref TOASTS: Mutex<Vec<Toast>> = Mutex::new(Vec::new());
+}
+
+struct Toast {
+    message: String,
+    /// The label and the action of the button shown next to the message
+    action: Option<(String, ToastAction)>,
+    created_at: Instant,
+}
+
+/// What pressing the button of a toast does, carried out by the application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToastAction {
+    OpenLink(String),
+    /// Unwatches the episodes of the season that were just watched
+    UnwatchEpisodes {
+        series_id: u32,
+        season_number: u32,
+        episodes: Vec<u32>,
+    },
+    /// Puts back the watched and skipped episodes of a season that was just cleared
+    RestoreSeason(ClearedSeason),
+    /// Puts back the episodes changed by a checklist that was just imported
+    UndoChecklistImport(AppliedChecklist),
+}
+
+impl Toast {
+    fn has_expired(&self) -> bool {
+        let duration = if self.action.is_some() {
+            ACTION_TOAST_DURATION
+        } else {
+            TOAST_DURATION
+        };
+        self.created_at.elapsed() >= duration
+    }
+}
+
+/// Shows the given message as a toast
+pub fn push(message: impl Into<String>) {
+    push_toast(Toast {
+        message: message.into(),
+        action: None,
+        created_at: Instant::now(),
+    });
+}
+
+/// Shows the given message as a toast with a link opening the url
+pub fn push_with_link(
+    message: impl Into<String>,
+    link_label: impl Into<String>,
+    url: impl Into<String>,
+) {
+    push_with_action(message, link_label, ToastAction::OpenLink(url.into()));
+}
+
+/// Shows the given message as a toast with a button carrying out the action
+pub fn push_with_action(
+    message: impl Into<String>,
+    action_label: impl Into<String>,
+    action: ToastAction,
+) {
+    push_toast(Toast {
+        message: message.into(),
+        action: Some((action_label.into(), action)),
+        created_at: Instant::now(),
+    });
+}
+
+fn push_toast(toast: Toast) {
+    let mut toasts = TOASTS.lock().unwrap();
+
+    toasts.push(toast);
+
+    if toasts.len() > MAX_TOASTS {
+        let excess = toasts.len() - MAX_TOASTS;
+        toasts.drain(..excess);
+    }
+}
+
+/// Whether there are toasts currently being shown
+pub fn has_toasts() -> bool {
+    !TOASTS.lock().unwrap().is_empty()
+}
+
+/// Removes the toasts with the given action once it has been carried out
+pub fn dismiss(action: &ToastAction) {
+    TOASTS.lock().unwrap().retain(|toast| {
+        toast.action.as_ref().map(|(_, toast_action)| toast_action) != Some(action)
+    });
+}
+
+/// Removes the toasts that have been shown long enough
+pub fn remove_expired() {
+    TOASTS.lock().unwrap().retain(|toast| !toast.has_expired());
+}
+
+/// The toasts being shown, `on_action` builds the message of a pressed action button
+pub fn view<'a, Message: Clone + 'a>(
+    on_action: impl Fn(ToastAction) -> Message,
+) -> Element<'a, Message, Renderer> {
+    let toasts = TOASTS.lock().unwrap();
+
+    let content = column(
+        toasts
+            .iter()
+            .map(|toast| {
+                let mut content = row![text(toast.message.clone()).size(13)]
+                    .spacing(10)
+                    .align_items(Alignment::Center);
+
+                if let Some((label, action)) = &toast.action {
+                    content = content.push(
+                        button(text(label).size(13))
+                            .on_press(on_action(action.clone()))
+                            .style(
+                                styles::button_styles::transparent_button_with_rounded_border_theme(
+                                ),
+                            ),
+                    );
+                }
+
+                container(content)
+                    .padding(10)
+                    .style(styles::container_styles::first_class_container_rounded_theme())
+                    .into()
+            })
+            .collect(),
+    )
+    .spacing(5)
+    .padding(10);
+
+    content.into()
+}
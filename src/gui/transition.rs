@@ -0,0 +1,95 @@
+//! # Series page transition
+//!
+//! The animation played when a series page is opened. The page zooms in from the middle of
+//! the window while fading in from the background, keeping a sense of where it came from.
+//!
+//! The animation is driven by the application's subscription which only ticks while the
+//! transition is running.
+
+use std::time::{Duration, Instant};
+
+use iced::widget::{column, container, row, Space};
+use iced::{Element, Length, Renderer};
+use iced_aw::floating_element;
+
+use super::styles;
+
+/// How often the animation is redrawn
+pub const TICK_INTERVAL: Duration = Duration::from_millis(16);
+
+/// How long the whole animation lasts
+const DURATION: Duration = Duration::from_millis(250);
+
+/// The margin around the page when the animation starts, in thousandths of the window
+const START_MARGIN: f32 = 150.0;
+
+#[derive(Default)]
+pub struct Transition {
+    started_at: Option<Instant>,
+}
+
+impl Transition {
+    pub fn start(&mut self) {
+        self.started_at = Some(Instant::now());
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    /// Advances the animation, stopping it once it's complete
+    pub fn tick(&mut self) {
+        if self
+            .started_at
+            .is_some_and(|started_at| started_at.elapsed() >= DURATION)
+        {
+            self.started_at = None;
+        }
+    }
+
+    /// How far the animation is from 0.0 to 1.0, easing out so that it slows down at the end
+    fn progress(&self) -> f32 {
+        let Some(started_at) = self.started_at else {
+            return 1.0;
+        };
+        let linear = (started_at.elapsed().as_secs_f32() / DURATION.as_secs_f32()).min(1.0);
+        1.0 - (1.0 - linear).powi(3)
+    }
+
+    pub fn view<'a, Message: 'a>(
+        &self,
+        content: Element<'a, Message, Renderer>,
+    ) -> Element<'a, Message, Renderer> {
+        if !self.is_running() {
+            return content;
+        }
+
+        let progress = self.progress();
+
+        // Fill portions can only be whole numbers, so the margins and the page share a thousand
+        // portions of the window. Empty portions are not filled at all, hence the smallest margin.
+        let margin = ((START_MARGIN * (1.0 - progress)).round() as u16).max(1);
+        let page_portion = 1000 - margin * 2;
+
+        let zoomed = column![
+            Space::with_height(Length::FillPortion(margin)),
+            row![
+                Space::with_width(Length::FillPortion(margin)),
+                container(content)
+                    .width(Length::FillPortion(page_portion))
+                    .height(Length::Fill),
+                Space::with_width(Length::FillPortion(margin)),
+            ]
+            .height(Length::FillPortion(page_portion)),
+            Space::with_height(Length::FillPortion(margin)),
+        ];
+
+        let fade = container(Space::new(Length::Fill, Length::Fill)).style(
+            styles::container_styles::fade_container_theme(1.0 - progress),
+        );
+
+        floating_element::FloatingElement::new(zoomed, fade)
+            .anchor(floating_element::Anchor::NorthWest)
+            .into()
+    }
+}
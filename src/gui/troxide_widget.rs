@@ -1,24 +1,51 @@
 pub mod episode_widget {
+    use super::copyable_text::{self, copyable};
     use crate::core::{
-        api::tv_maze::episodes_information::Episode as EpisodeInfo, caching, database,
+        api::tv_maze::episode_guests::{self, GuestCast, GuestCrew},
+        api::tv_maze::episodes_information::Episode as EpisodeInfo,
+        caching,
+        caching::episode_list::EpisodeBadge,
+        database,
     };
-    use crate::gui::assets::icons::EYE_FILL;
+    use crate::gui::assets::icons::{CHECK2_ALL, EYE_FILL, PENCIL_FILL, SKIP_FORWARD_FILL};
     use crate::gui::helpers::{self, season_episode_str_gen};
     pub use crate::gui::message::IndexedMessage;
-    use crate::gui::styles;
+    use crate::gui::toast::{self, ToastAction};
+    use crate::gui::{rich_text, styles};
     use bytes::Bytes;
     use iced::font::Weight;
     use iced::widget::{
-        button, checkbox, column, container, image, row, svg, text, vertical_space, Row, Space,
-        Text,
+        button, checkbox, column, container, image, pick_list, row, svg, text, text_input, tooltip,
+        vertical_space, Row, Space,
     };
     use iced::{Command, Element, Font, Length, Renderer};
 
     #[derive(Clone, Debug)]
     pub enum Message {
         ImageLoaded(Option<Bytes>),
+        LoadImagePressed,
         MarkedWatched(PosterType),
+        MarkedWatchedUpToHere,
+        /// The episodes of the season that were newly watched
+        MarkedUpToHereComplete(Vec<u32>),
+        SkipToggled(PosterType),
         TrackCommandComplete(bool),
+        GuestsToggled,
+        GuestsLoaded(Option<(Vec<GuestCast>, Vec<GuestCrew>)>),
+        NoteEditToggled,
+        NoteChanged(String),
+        RatingSelected(u8),
+        RatingCleared,
+        CopyPressed(String),
+    }
+
+    #[derive(Clone, Default)]
+    enum Guests {
+        #[default]
+        NotLoaded,
+        Loading,
+        Loaded(Vec<GuestCast>, Vec<GuestCrew>),
+        Failed,
     }
 
     #[derive(Clone, Copy, Debug)]
@@ -34,7 +61,20 @@ pub mod episode_widget {
         episode_information: EpisodeInfo,
         series_id: u32,
         episode_image: Option<Bytes>,
+        /// Whether the episode has been watched or skipped from this widget
         set_watched: bool,
+        /// Shown in place of the seasonal number when present
+        absolute_number: Option<u32>,
+        /// Marks the episode as a premiere or finale
+        badge: Option<EpisodeBadge>,
+        /// Guest cast and crew, only loaded when first shown
+        guests: Guests,
+        show_guests: bool,
+        /// The user's note on the episode, empty when there is none
+        note: String,
+        editing_note: bool,
+        /// The user's rating of the episode from 1 to 10
+        rating: Option<u8>,
     }
 
     impl Episode {
@@ -45,6 +85,15 @@ pub mod episode_widget {
             episode_information: EpisodeInfo,
         ) -> (Self, Command<IndexedMessage<usize, Message>>) {
             let episode_image = episode_information.image.clone();
+            let note = episode_information
+                .number
+                .and_then(|number| {
+                    database::DB.get_episode_note(series_id, episode_information.season, number)
+                })
+                .unwrap_or_default();
+            let rating = episode_information.number.and_then(|number| {
+                database::DB.get_episode_rating(series_id, episode_information.season, number)
+            });
             let episode = Self {
                 index,
                 series_name,
@@ -52,11 +101,20 @@ pub mod episode_widget {
                 series_id,
                 episode_image: None,
                 set_watched: false,
+                absolute_number: None,
+                badge: None,
+                guests: Guests::default(),
+                show_guests: false,
+                note,
+                editing_note: false,
+                rating,
             };
 
             let command = if let Some(image) = episode_image {
+                let (image_url, image_resolution) =
+                    caching::image_source(image, caching::ImageKind::Background, false);
                 Command::perform(
-                    caching::load_image(image.medium_image_url, caching::ImageResolution::Medium),
+                    caching::load_image(image_url, image_resolution),
                     Message::ImageLoaded,
                 )
                 .map(move |message| IndexedMessage::new(index, message))
@@ -71,6 +129,18 @@ pub mod episode_widget {
             self.set_watched
         }
 
+        pub fn get_episode_information(&self) -> &EpisodeInfo {
+            &self.episode_information
+        }
+
+        pub fn set_absolute_number(&mut self, absolute_number: Option<u32>) {
+            self.absolute_number = absolute_number;
+        }
+
+        pub fn set_badge(&mut self, badge: Option<EpisodeBadge>) {
+            self.badge = badge;
+        }
+
         pub fn update(
             &mut self,
             message: IndexedMessage<usize, Message>,
@@ -80,6 +150,19 @@ pub mod episode_widget {
                     self.episode_image = image;
                     Command::none()
                 }
+                Message::LoadImagePressed => {
+                    let Some(image) = self.episode_information.image.clone() else {
+                        return Command::none();
+                    };
+                    let index = self.index;
+                    let (image_url, image_resolution) =
+                        caching::image_source(image, caching::ImageKind::Background, false);
+                    Command::perform(
+                        caching::load_image_on_demand(image_url, image_resolution),
+                        Message::ImageLoaded,
+                    )
+                    .map(move |message| IndexedMessage::new(index, message))
+                }
                 Message::MarkedWatched(poster_type) => {
                     let season_number = self.episode_information.season;
                     let episode_number = self.episode_information.number.unwrap();
@@ -93,7 +176,8 @@ pub mod episode_widget {
                             if let Some(mut series) = database::DB.get_series(series_id) {
                                 series.add_episode_unchecked(season_number, episode_number);
                             } else {
-                                let mut series = database::Series::new(series_name, series_id);
+                                let mut series =
+                                    database::Series::new(series_name.clone(), series_id);
                                 series.add_episode_unchecked(season_number, episode_number)
                             }
 
@@ -113,7 +197,80 @@ pub mod episode_widget {
                         .map(move |message| IndexedMessage::new(episode_index, message)),
                     }
                 }
+                Message::MarkedWatchedUpToHere => {
+                    let Some(episode_number) = self.episode_information.number else {
+                        return Command::none();
+                    };
+                    let season_number = self.episode_information.season;
+                    let series_id = self.series_id;
+                    let series_name = self.series_name.clone();
+                    let episode_index = self.index;
+
+                    Command::perform(
+                        async move {
+                            let mut series = database::DB
+                                .get_series(series_id)
+                                .unwrap_or_else(|| database::Series::new(series_name, series_id));
+
+                            series
+                                .add_episodes_up_to(season_number, episode_number)
+                                .await
+                        },
+                        Message::MarkedUpToHereComplete,
+                    )
+                    .map(move |message| IndexedMessage::new(episode_index, message))
+                }
+                Message::MarkedUpToHereComplete(newly_watched) => {
+                    if newly_watched.is_empty() {
+                        toast::push("No more episodes to mark watched up to here");
+                    } else {
+                        toast::push_with_action(
+                            format!(
+                                "Marked {} episodes of season {} watched",
+                                newly_watched.len(),
+                                self.episode_information.season
+                            ),
+                            "Undo",
+                            ToastAction::UnwatchEpisodes {
+                                series_id: self.series_id,
+                                season_number: self.episode_information.season,
+                                episodes: newly_watched,
+                            },
+                        );
+                    }
+                    Command::none()
+                }
+                Message::SkipToggled(poster_type) => {
+                    let season_number = self.episode_information.season;
+                    let episode_number = self.episode_information.number.unwrap();
+
+                    let skipped = !database::DB.is_episode_skipped(
+                        self.series_id,
+                        season_number,
+                        episode_number,
+                    );
+
+                    // An episode is either watched or skipped, never both
+                    if skipped {
+                        if let Some(mut series) = database::DB.get_series(self.series_id) {
+                            series.remove_episode(season_number, episode_number);
+                        }
+                    }
+                    database::DB.set_episode_skipped(
+                        self.series_id,
+                        season_number,
+                        episode_number,
+                        skipped,
+                    );
+
+                    // Moving on to the next episode in the watchlist just like when watched
+                    if let PosterType::Watchlist = poster_type {
+                        self.set_watched = skipped;
+                    }
+                    Command::none()
+                }
                 Message::TrackCommandComplete(is_newly_added) => {
+                    // Tracking an episode that is already watched unwatches it
                     if !is_newly_added {
                         if let Some(mut series) = database::DB.get_series(self.series_id) {
                             series.remove_episode(
@@ -124,6 +281,75 @@ pub mod episode_widget {
                     }
                     Command::none()
                 }
+                Message::GuestsToggled => {
+                    self.show_guests = !self.show_guests;
+
+                    if !self.show_guests
+                        || !matches!(self.guests, Guests::NotLoaded | Guests::Failed)
+                    {
+                        return Command::none();
+                    }
+
+                    self.guests = Guests::Loading;
+                    let index = self.index;
+                    Command::perform(
+                        episode_guests::get_episode_guests(self.episode_information.id),
+                        |guests| {
+                            Message::GuestsLoaded(
+                                guests
+                                    .map_err(|err| {
+                                        tracing::error!("failed to load episode guests: {}", err)
+                                    })
+                                    .ok(),
+                            )
+                        },
+                    )
+                    .map(move |message| IndexedMessage::new(index, message))
+                }
+                Message::GuestsLoaded(guests) => {
+                    self.guests = match guests {
+                        Some((guest_cast, guest_crew)) => Guests::Loaded(guest_cast, guest_crew),
+                        None => Guests::Failed,
+                    };
+                    Command::none()
+                }
+                Message::NoteEditToggled => {
+                    self.editing_note = !self.editing_note;
+                    Command::none()
+                }
+                Message::NoteChanged(note) => {
+                    if let Some(episode_number) = self.episode_information.number {
+                        database::DB.set_episode_note(
+                            self.series_id,
+                            self.episode_information.season,
+                            episode_number,
+                            &note,
+                        );
+                    }
+                    self.note = note;
+                    Command::none()
+                }
+                Message::RatingSelected(rating) => {
+                    self.set_rating(Some(rating));
+                    Command::none()
+                }
+                Message::RatingCleared => {
+                    self.set_rating(None);
+                    Command::none()
+                }
+                Message::CopyPressed(copied_text) => copyable_text::copy(copied_text),
+            }
+        }
+
+        fn set_rating(&mut self, rating: Option<u8>) {
+            if let Some(episode_number) = self.episode_information.number {
+                database::DB.set_episode_rating(
+                    self.series_id,
+                    self.episode_information.season,
+                    episode_number,
+                    rating,
+                );
+                self.rating = rating;
             }
         }
 
@@ -142,6 +368,20 @@ pub mod episode_widget {
                 let image_handle = image::Handle::from_memory(image_bytes);
                 let image = image(image_handle).height(image_height);
                 content = content.push(image);
+            } else if caching::is_saving_bandwidth() && self.episode_information.image.is_some() {
+                // Tap to load as images are not downloaded automatically when saving bandwidth
+                content = content.push(tooltip(
+                    button(
+                        helpers::empty_image::empty_image()
+                            .width(image_width)
+                            .height(image_height),
+                    )
+                    .padding(0)
+                    .on_press(Message::LoadImagePressed)
+                    .style(styles::button_styles::transparent_button_theme()),
+                    "Load image",
+                    tooltip::Position::Bottom,
+                ));
             } else {
                 content = content.push(
                     helpers::empty_image::empty_image()
@@ -150,13 +390,64 @@ pub mod episode_widget {
                 );
             };
 
-            let episode_details = column!(
-                heading_widget(self.series_id, &self.episode_information, poster_type),
+            let mut episode_details = column!(
+                heading_widget(
+                    self.series_id,
+                    &self.episode_information,
+                    self.absolute_number,
+                    self.badge,
+                    !self.note.trim().is_empty(),
+                    poster_type
+                ),
                 date_time_widget(&self.episode_information),
                 vertical_space(5),
                 summary_widget(&self.episode_information)
             );
 
+            if self.editing_note {
+                episode_details = episode_details.push(
+                    text_input(
+                        "Thoughts, where you stopped, who you watched with...",
+                        &self.note,
+                    )
+                    .on_input(Message::NoteChanged)
+                    .on_submit(Message::NoteEditToggled)
+                    .size(11),
+                );
+            } else if !self.note.trim().is_empty() {
+                episode_details = episode_details.push(
+                    text(format!("Note: {}", self.note))
+                        .size(11)
+                        .style(styles::text_styles::accent_color_theme()),
+                );
+            }
+
+            if let PosterType::Season = poster_type {
+                let guests_button = button(
+                    text(if self.show_guests {
+                        "Hide guests"
+                    } else {
+                        "Guests"
+                    })
+                    .size(11),
+                )
+                .on_press(Message::GuestsToggled)
+                .style(styles::button_styles::transparent_button_theme());
+
+                episode_details = episode_details.push(
+                    row![
+                        rating_widget(&self.episode_information, self.rating),
+                        guests_button
+                    ]
+                    .spacing(5)
+                    .align_items(iced::Alignment::Center),
+                );
+
+                if self.show_guests {
+                    episode_details = episode_details.push(guests_widget(&self.guests));
+                }
+            }
+
             let content = content.push(episode_details);
 
             let mut content = container(content);
@@ -172,12 +463,84 @@ pub mod episode_widget {
         }
     }
 
-    fn summary_widget(episode_information: &EpisodeInfo) -> Text<'static, Renderer> {
+    fn rating_widget(
+        episode_information: &EpisodeInfo,
+        rating: Option<u8>,
+    ) -> Element<'static, Message, Renderer> {
+        if episode_information.number.is_none() {
+            return Space::new(0, 0).into();
+        }
+
+        let rating_picker = pick_list(
+            &database::EPISODE_RATINGS[..],
+            rating,
+            Message::RatingSelected,
+        )
+        .placeholder("Rate")
+        .text_size(11)
+        .padding(2);
+
+        let mut content = row![rating_picker].spacing(5);
+
+        if rating.is_some() {
+            content = content.push(
+                button(text("Clear rating").size(11))
+                    .on_press(Message::RatingCleared)
+                    .style(styles::button_styles::transparent_button_theme()),
+            );
+        }
+
+        content.align_items(iced::Alignment::Center).into()
+    }
+
+    fn guests_widget(guests: &Guests) -> Element<'_, Message, Renderer> {
+        let (guest_cast, guest_crew) = match guests {
+            Guests::NotLoaded | Guests::Loading => {
+                return text("Loading guests...").size(11).into()
+            }
+            Guests::Failed => {
+                return text("Failed to load guests")
+                    .size(11)
+                    .style(styles::text_styles::red_text_theme())
+                    .into()
+            }
+            Guests::Loaded(guest_cast, guest_crew) => (guest_cast, guest_crew),
+        };
+
+        if guest_cast.is_empty() && guest_crew.is_empty() {
+            return text("No guests").size(11).into();
+        }
+
+        let mut content = column!().spacing(3);
+
+        if !guest_cast.is_empty() {
+            content = content.push(text("Guest cast").size(12));
+            for cast in guest_cast {
+                content =
+                    content.push(text(format!("{} {}", cast.person.name, cast.role())).size(11));
+            }
+        }
+
+        if !guest_crew.is_empty() {
+            content = content.push(text("Guest crew").size(12));
+            for crew in guest_crew {
+                content =
+                    content.push(text(format!("{} ({})", crew.person.name, crew.kind)).size(11));
+            }
+        }
+
+        content.into()
+    }
+
+    fn summary_widget(episode_information: &EpisodeInfo) -> Element<'static, Message, Renderer> {
         if let Some(summary) = &episode_information.summary {
-            let summary = html2text::from_read(summary.as_bytes(), 1000);
-            text(summary).size(11)
+            copyable(
+                rich_text::summary(summary, 11, Length::Fill),
+                Message::CopyPressed(rich_text::plain(summary)),
+            )
+            .into()
         } else {
-            text("")
+            text("").into()
         }
     }
 
@@ -196,6 +559,9 @@ pub mod episode_widget {
     fn heading_widget(
         series_id: u32,
         episode_information: &EpisodeInfo,
+        absolute_number: Option<u32>,
+        badge: Option<EpisodeBadge>,
+        has_note: bool,
         poster_type: PosterType,
     ) -> Row<'static, Message, Renderer> {
         let mark_watched_widget: Element<'_, Message, Renderer> = match poster_type {
@@ -228,21 +594,109 @@ pub mod episode_widget {
             }
         };
 
-        row![
-            text(format!(
-                "{} {}",
-                episode_information
-                    .number
-                    .map(|number| season_episode_str_gen(episode_information.season, number))
-                    .unwrap_or_default(),
-                episode_information.name
-            ))
-            .font(Font {
-                weight: Weight::Bold,
-                ..Default::default()
+        let is_skipped = episode_information
+            .number
+            .map(|episode_number| {
+                database::DB.is_episode_skipped(
+                    series_id,
+                    episode_information.season,
+                    episode_number,
+                )
             })
-            .style(styles::text_styles::accent_color_theme())
+            .unwrap_or(false);
+
+        let skip_icon = svg(svg::Handle::from_memory(SKIP_FORWARD_FILL))
+            .width(17)
+            .height(17)
+            .style(if is_skipped {
+                styles::svg_styles::colored_svg_theme()
+            } else {
+                styles::svg_styles::greyed_svg_theme()
+            });
+
+        let skip_button = tooltip(
+            button(skip_icon)
+                .style(styles::button_styles::transparent_button_theme())
+                .on_press(Message::SkipToggled(poster_type)),
+            if is_skipped { "Unskip" } else { "Skip" },
+            tooltip::Position::Left,
+        )
+        .size(11)
+        .padding(5)
+        .style(styles::container_styles::first_class_container_rounded_theme());
+
+        let note_icon = svg(svg::Handle::from_memory(PENCIL_FILL))
+            .width(15)
+            .height(15)
+            .style(if has_note {
+                styles::svg_styles::colored_svg_theme()
+            } else {
+                styles::svg_styles::greyed_svg_theme()
+            });
+
+        let note_button = tooltip(
+            button(note_icon)
+                .style(styles::button_styles::transparent_button_theme())
+                .on_press(Message::NoteEditToggled),
+            if has_note { "Edit note" } else { "Add note" },
+            tooltip::Position::Left,
+        )
+        .size(11)
+        .padding(5)
+        .style(styles::container_styles::first_class_container_rounded_theme());
+
+        let badge: Element<'_, Message, Renderer> = match badge {
+            Some(badge) => helpers::episode_badge::episode_badge(badge).into(),
+            None => Space::new(0, 0).into(),
+        };
+
+        // Catching up on a season without ticking every episode before this one
+        let mark_up_to_here_widget: Element<'_, Message, Renderer> = match poster_type {
+            PosterType::Season if episode_information.number.is_some() => tooltip(
+                button(
+                    svg(svg::Handle::from_memory(CHECK2_ALL))
+                        .width(17)
+                        .height(17)
+                        .style(styles::svg_styles::colored_svg_theme()),
+                )
+                .style(styles::button_styles::transparent_button_theme())
+                .on_press(Message::MarkedWatchedUpToHere),
+                "Mark watched up to here",
+                tooltip::Position::Left,
+            )
+            .size(11)
+            .padding(5)
+            .style(styles::container_styles::first_class_container_rounded_theme())
+            .into(),
+            _ => Space::new(0, 0).into(),
+        };
+
+        let title = format!(
+            "{} {}",
+            absolute_number
+                .map(|absolute_number| format!("E{}", absolute_number))
+                .or_else(|| episode_information
+                    .number
+                    .map(|number| season_episode_str_gen(episode_information.season, number)))
+                .unwrap_or_default(),
+            episode_information.name
+        );
+
+        row![
+            copyable(
+                text(&title)
+                    .font(Font {
+                        weight: Weight::Bold,
+                        ..Default::default()
+                    })
+                    .style(styles::text_styles::accent_color_theme()),
+                Message::CopyPressed(title),
+            )
             .width(Length::FillPortion(10)),
+            badge,
+            note_button,
+            skip_button,
+            mark_up_to_here_widget,
             mark_watched_widget
         ]
         .spacing(5)
@@ -252,23 +706,36 @@ pub mod episode_widget {
 pub mod series_poster {
     use std::borrow::Cow;
     use std::sync::mpsc;
+    use std::time::Duration;
 
+    use super::hover_area::HoverArea;
     use crate::core::api::tv_maze::series_information::{Rating, SeriesMainInformation};
     use crate::core::api::tv_maze::Image;
     use crate::core::caching;
+    use crate::core::database;
+    use crate::core::date_formatting;
+    use crate::core::hooks::{self, HookEvent};
     use crate::core::posters_hiding::HIDDEN_SERIES;
-    use crate::gui::assets::icons::{EYE_SLASH_FILL, STAR_FILL};
+    use crate::gui::assets::icons::{EYE_SLASH_FILL, PATCH_PLUS, PATCH_PLUS_FILL, STAR_FILL};
     use crate::gui::helpers;
     pub use crate::gui::message::IndexedMessage;
     use crate::gui::styles;
+    use crate::gui::toast;
 
     use bytes::Bytes;
     use iced::font::Weight;
     use iced::widget::{
-        button, column, container, image, mouse_area, row, svg, text, vertical_space, Space,
+        button, column, container, image, mouse_area, pick_list, row, svg, text, tooltip,
+        vertical_space, Space,
     };
     use iced::{Command, Element, Font, Renderer};
 
+    /// How long a poster has to be hovered before it's quick info card is shown
+    const QUICK_INFO_DELAY: Duration = Duration::from_millis(600);
+
+    /// The maximum amount of characters of the summary shown in the quick info card
+    const QUICK_INFO_SUMMARY_LENGTH: usize = 90;
+
     #[derive(Debug, Clone)]
     pub enum GenericPosterMessage {
         ImageLoaded(Option<Bytes>),
@@ -319,14 +786,10 @@ pub mod series_poster {
 
         fn load_image(image: Option<Image>) -> Command<GenericPosterMessage> {
             if let Some(image) = image {
+                let (image_url, image_resolution) =
+                    caching::image_source(image, caching::ImageKind::Poster, false);
                 Command::perform(
-                    async move {
-                        caching::load_image(
-                            image.medium_image_url,
-                            caching::ImageResolution::Medium,
-                        )
-                        .await
-                    },
+                    async move { caching::load_image(image_url, image_resolution).await },
                     GenericPosterMessage::ImageLoaded,
                 )
             } else {
@@ -342,6 +805,10 @@ pub mod series_poster {
         Expand,
         Hide,
         SeriesHidden,
+        Hovered(bool),
+        ShowQuickInfo(usize),
+        ToggleTracking,
+        StatusSelected(database::SeriesStatus),
     }
 
     pub struct SeriesPoster<'a> {
@@ -349,6 +816,9 @@ pub mod series_poster {
         poster: GenericPoster<'a>,
         expanded: bool,
         hidden: bool,
+        /// Incremented on every hover so that delays of previous hovers can be ignored
+        hover_generation: usize,
+        show_quick_info: bool,
     }
 
     impl<'a> SeriesPoster<'a> {
@@ -364,6 +834,8 @@ pub mod series_poster {
                 poster,
                 expanded: false,
                 hidden: false,
+                hover_generation: 0,
+                show_quick_info: false,
             };
 
             (
@@ -404,11 +876,65 @@ pub mod series_poster {
                 Message::SeriesHidden => {
                     self.hidden = true;
                 }
+                Message::Hovered(is_hovered) => {
+                    self.hover_generation = self.hover_generation.wrapping_add(1);
+                    self.show_quick_info = false;
+
+                    if is_hovered {
+                        let hover_generation = self.hover_generation;
+                        let index = self.index;
+
+                        return Command::perform(tokio::time::sleep(QUICK_INFO_DELAY), move |_| {
+                            Message::ShowQuickInfo(hover_generation)
+                        })
+                        .map(move |message| IndexedMessage::new(index, message));
+                    }
+                }
+                Message::ShowQuickInfo(hover_generation) => {
+                    // The cursor may have left the poster before the delay finished
+                    if hover_generation == self.hover_generation {
+                        self.show_quick_info = true;
+                    }
+                }
+                Message::ToggleTracking => self.toggle_tracking(),
+                Message::StatusSelected(status) => {
+                    let series_info = self.poster.get_series_info();
+                    database::DB.set_series_status(series_info.id, status);
+                    toast::push(format!("Marked {} as {}", series_info.name, status));
+                }
                 Message::Poster(message) => self.poster.update(message),
             }
             Command::none()
         }
 
+        /// Tracks the series when it's not tracked and untracks it otherwise
+        fn toggle_tracking(&self) {
+            let series_info = self.poster.get_series_info();
+
+            if let Some(mut series) = database::DB.get_series(series_info.id) {
+                if series.is_tracked() {
+                    series.mark_untracked();
+                    toast::push(format!("Stopped tracking {}", series_info.name));
+                    return;
+                }
+                series.mark_tracked();
+            } else {
+                let mut series = database::Series::new(series_info.name.to_owned(), series_info.id);
+                series.mark_tracked();
+                database::DB.add_series(series_info.id, &series);
+            }
+
+            toast::push(format!("Tracking {}", series_info.name));
+            hooks::trigger(HookEvent::series_tracked(
+                series_info.id,
+                series_info.name.to_owned(),
+            ));
+        }
+
+        pub fn get_series_info(&self) -> &SeriesMainInformation {
+            self.poster.get_series_info()
+        }
+
         pub fn is_hidden(&self) -> bool {
             self.hidden
         }
@@ -444,6 +970,8 @@ pub mod series_poster {
                     Self::rating_widget(&self.poster.get_series_info().rating),
                     vertical_space(5),
                     Self::hiding_button(),
+                    self.tracking_button(true),
+                    self.status_picker(),
                 ]
                 .spacing(2);
 
@@ -456,12 +984,17 @@ pub mod series_poster {
                 let mut content = column![].padding(2).spacing(1);
                 content = content.push(poster_image);
                 content = content.push(
-                    text(&self.poster.get_series_info().name)
-                        .size(11)
-                        .width(100)
-                        .height(30)
-                        .vertical_alignment(iced::alignment::Vertical::Center)
-                        .horizontal_alignment(iced::alignment::Horizontal::Center),
+                    row![
+                        text(&self.poster.get_series_info().name)
+                            .size(11)
+                            .width(80)
+                            .height(30)
+                            .vertical_alignment(iced::alignment::Vertical::Center)
+                            .horizontal_alignment(iced::alignment::Horizontal::Center),
+                        self.tracking_button(false),
+                    ]
+                    .align_items(iced::Alignment::Center)
+                    .width(100),
                 );
                 content.into()
             };
@@ -476,10 +1009,63 @@ pub mod series_poster {
                 mouse_area = mouse_area.on_right_press(Message::Expand);
             }
 
-            let element: Element<'_, Message, Renderer> = mouse_area.into();
+            // The expanded poster already shows most of the information
+            let content: Element<'_, Message, Renderer> = if self.show_quick_info && !self.expanded
+            {
+                tooltip(
+                    mouse_area,
+                    self.quick_info(),
+                    tooltip::Position::FollowCursor,
+                )
+                .size(11)
+                .padding(5)
+                .style(styles::container_styles::first_class_container_rounded_theme())
+                .into()
+            } else {
+                mouse_area.into()
+            };
+
+            let element: Element<'_, Message, Renderer> =
+                HoverArea::new(content, Message::Hovered).into();
             element.map(|message| IndexedMessage::new(self.index, message))
         }
 
+        /// The text of the card shown when the poster is hovered for a while
+        fn quick_info(&self) -> String {
+            let series_info = self.poster.get_series_info();
+
+            let mut lines = vec![series_info.name.clone()];
+
+            let mut details = vec![];
+            if let Some(average_rating) = series_info.rating.average {
+                details.push(format!("★ {}", average_rating));
+            }
+            details.push(series_info.get_status().to_string());
+            if let Some(premiere_year) = series_info
+                .premiered
+                .as_deref()
+                .and_then(|premiered| premiered.split('-').next())
+            {
+                details.push(premiere_year.to_owned());
+            }
+            lines.push(details.join(" • "));
+
+            if !series_info.genres.is_empty() {
+                lines.push(helpers::genres_with_pipes(&series_info.genres));
+            }
+
+            if let Some(summary) = &series_info.summary {
+                let summary = html2text::from_read(summary.as_bytes(), 1000);
+                let summary = summary.lines().next().unwrap_or_default().trim();
+
+                if !summary.is_empty() {
+                    lines.push(one_line_summary(summary));
+                }
+            }
+
+            lines.join("\n")
+        }
+
         fn rating_widget(rating: &Rating) -> Element<'_, Message, Renderer> {
             if let Some(average_rating) = rating.average {
                 let star_handle = svg::Handle::from_memory(STAR_FILL);
@@ -498,7 +1084,12 @@ pub mod series_poster {
 
         fn premier_widget(premier_date: Option<&str>) -> Element<'_, Message, Renderer> {
             if let Some(premier_date) = premier_date {
-                text(format!("Premiered: {}", premier_date)).size(11).into()
+                text(format!(
+                    "Premiered: {}",
+                    date_formatting::api_date_str(premier_date)
+                ))
+                .size(11)
+                .into()
             } else {
                 Space::new(0, 0).into()
             }
@@ -512,6 +1103,51 @@ pub mod series_poster {
             }
         }
 
+        /// A button tracking or untracking the series in place, only showing the icon when
+        /// not labeled
+        fn tracking_button(&self, labeled: bool) -> Element<'static, Message, Renderer> {
+            let is_tracked = database::DB
+                .get_series(self.poster.get_series_info().id)
+                .map(|series| series.is_tracked())
+                .unwrap_or(false);
+
+            let (icon, label) = if is_tracked {
+                (PATCH_PLUS_FILL, "Untrack")
+            } else {
+                (PATCH_PLUS, "Track")
+            };
+
+            let icon = svg(svg::Handle::from_memory(icon))
+                .width(15)
+                .height(15)
+                .style(styles::svg_styles::colored_svg_theme());
+
+            let content: Element<'_, Message, Renderer> = if labeled {
+                row![icon, text(label).size(11)].spacing(5).into()
+            } else {
+                icon.into()
+            };
+
+            button(content)
+                .padding(2)
+                .on_press(Message::ToggleTracking)
+                .style(styles::button_styles::transparent_button_with_rounded_border_theme())
+                .into()
+        }
+
+        fn status_picker(&self) -> Element<'static, Message, Renderer> {
+            let status = database::DB.get_series_status(self.poster.get_series_info().id);
+
+            pick_list(
+                &database::SeriesStatus::ALL[..],
+                Some(status),
+                Message::StatusSelected,
+            )
+            .text_size(11)
+            .padding(2)
+            .into()
+        }
+
         fn hiding_button() -> Element<'static, Message, Renderer> {
             let tracked_icon_handle = svg::Handle::from_memory(EYE_SLASH_FILL);
             let icon = svg(tracked_icon_handle)
@@ -527,11 +1163,207 @@ pub mod series_poster {
                 .into()
         }
     }
+
+    /// Shortens the summary to it's first sentence, cutting it off when still too long
+    fn one_line_summary(summary: &str) -> String {
+        let first_sentence = summary
+            .find(". ")
+            .map(|end| &summary[..=end])
+            .unwrap_or(summary);
+
+        if first_sentence.chars().count() > QUICK_INFO_SUMMARY_LENGTH {
+            let shortened: String = first_sentence
+                .chars()
+                .take(QUICK_INFO_SUMMARY_LENGTH)
+                .collect();
+            format!("{}…", shortened.trim_end())
+        } else {
+            first_sentence.to_owned()
+        }
+    }
+}
+
+pub mod hover_area {
+    //! A container that reports when the cursor enters or leaves it's content
+    //!
+    //! `mouse_area` from iced does not support hover events yet, so this fills that gap.
+
+    use iced::advanced::layout;
+    use iced::advanced::overlay;
+    use iced::advanced::renderer;
+    use iced::advanced::widget::{tree, Operation, Tree};
+    use iced::advanced::{Clipboard, Layout, Shell, Widget};
+    use iced::event::{self, Event};
+    use iced::mouse;
+    use iced::{Element, Length, Rectangle};
+
+    pub struct HoverArea<'a, Message, Renderer> {
+        content: Element<'a, Message, Renderer>,
+        on_hover: Box<dyn Fn(bool) -> Message + 'a>,
+    }
+
+    impl<'a, Message, Renderer> HoverArea<'a, Message, Renderer> {
+        /// Creates a `HoverArea` calling `on_hover` with whether the content is hovered
+        /// everytime the cursor enters or leaves the content
+        pub fn new(
+            content: impl Into<Element<'a, Message, Renderer>>,
+            on_hover: impl Fn(bool) -> Message + 'a,
+        ) -> Self {
+            Self {
+                content: content.into(),
+                on_hover: Box::new(on_hover),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct State {
+        is_hovered: bool,
+    }
+
+    impl<'a, Message, Renderer> Widget<Message, Renderer> for HoverArea<'a, Message, Renderer>
+    where
+        Renderer: renderer::Renderer,
+    {
+        fn tag(&self) -> tree::Tag {
+            tree::Tag::of::<State>()
+        }
+
+        fn state(&self) -> tree::State {
+            tree::State::new(State::default())
+        }
+
+        fn children(&self) -> Vec<Tree> {
+            vec![Tree::new(&self.content)]
+        }
+
+        fn diff(&self, tree: &mut Tree) {
+            tree.diff_children(std::slice::from_ref(&self.content));
+        }
+
+        fn width(&self) -> Length {
+            self.content.as_widget().width()
+        }
+
+        fn height(&self) -> Length {
+            self.content.as_widget().height()
+        }
+
+        fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+            self.content.as_widget().layout(renderer, limits)
+        }
+
+        fn operate(
+            &self,
+            tree: &mut Tree,
+            layout: Layout<'_>,
+            renderer: &Renderer,
+            operation: &mut dyn Operation<Message>,
+        ) {
+            self.content
+                .as_widget()
+                .operate(&mut tree.children[0], layout, renderer, operation);
+        }
+
+        fn on_event(
+            &mut self,
+            tree: &mut Tree,
+            event: Event,
+            layout: Layout<'_>,
+            cursor: mouse::Cursor,
+            renderer: &Renderer,
+            clipboard: &mut dyn Clipboard,
+            shell: &mut Shell<'_, Message>,
+            viewport: &Rectangle,
+        ) -> event::Status {
+            if let Event::Mouse(mouse_event) = &event {
+                let is_hovered = !matches!(mouse_event, mouse::Event::CursorLeft)
+                    && cursor.is_over(layout.bounds());
+
+                let state = tree.state.downcast_mut::<State>();
+                if state.is_hovered != is_hovered {
+                    state.is_hovered = is_hovered;
+                    shell.publish((self.on_hover)(is_hovered));
+                }
+            }
+
+            self.content.as_widget_mut().on_event(
+                &mut tree.children[0],
+                event,
+                layout,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            )
+        }
+
+        fn mouse_interaction(
+            &self,
+            tree: &Tree,
+            layout: Layout<'_>,
+            cursor: mouse::Cursor,
+            viewport: &Rectangle,
+            renderer: &Renderer,
+        ) -> mouse::Interaction {
+            self.content.as_widget().mouse_interaction(
+                &tree.children[0],
+                layout,
+                cursor,
+                viewport,
+                renderer,
+            )
+        }
+
+        fn draw(
+            &self,
+            tree: &Tree,
+            renderer: &mut Renderer,
+            theme: &Renderer::Theme,
+            style: &renderer::Style,
+            layout: Layout<'_>,
+            cursor: mouse::Cursor,
+            viewport: &Rectangle,
+        ) {
+            self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                style,
+                layout,
+                cursor,
+                viewport,
+            );
+        }
+
+        fn overlay<'b>(
+            &'b mut self,
+            tree: &'b mut Tree,
+            layout: Layout<'_>,
+            renderer: &Renderer,
+        ) -> Option<overlay::Element<'b, Message, Renderer>> {
+            self.content
+                .as_widget_mut()
+                .overlay(&mut tree.children[0], layout, renderer)
+        }
+    }
+
+    impl<'a, Message, Renderer> From<HoverArea<'a, Message, Renderer>>
+        for Element<'a, Message, Renderer>
+    where
+        Message: 'a,
+        Renderer: 'a + renderer::Renderer,
+    {
+        fn from(hover_area: HoverArea<'a, Message, Renderer>) -> Self {
+            Element::new(hover_area)
+        }
+    }
 }
 
 pub mod title_bar {
     use iced::widget::{
-        button, container, horizontal_space, mouse_area, row, svg, text, Row, Space,
+        button, container, horizontal_space, mouse_area, row, svg, text, tooltip, Row, Space,
     };
     use iced::{Element, Length, Renderer};
 
@@ -576,11 +1408,21 @@ pub mod title_bar {
                         .width(Length::Shrink)
                         .style(styles::svg_styles::colored_svg_theme());
                     let text_label = text(tab_label.text);
-                    let mut tab = container(
-                        mouse_area(row![icon, text_label].spacing(5))
-                            .on_press(Message::TabSelected(index)),
-                    )
-                    .padding(5);
+                    let label: Element<'_, Message, Renderer> = match &tab_label.tooltip {
+                        Some(tooltip_text) => tooltip(
+                            row![icon, text_label].spacing(5),
+                            tooltip_text,
+                            tooltip::Position::Bottom,
+                        )
+                        .size(11)
+                        .padding(5)
+                        .style(styles::container_styles::first_class_container_rounded_theme())
+                        .into(),
+                        None => row![icon, text_label].spacing(5).into(),
+                    };
+                    let mut tab =
+                        container(mouse_area(label).on_press(Message::TabSelected(index)))
+                            .padding(5);
 
                     // Highlighting the tab if is active
                     if index == self.active_tab {
@@ -617,3 +1459,52 @@ pub mod title_bar {
         }
     }
 }
+
+pub mod copyable_text {
+    //! Texts can't be selected in iced, so titles, summaries and episode codes get a button
+    //! next to them copying them to the clipboard, ready to paste in chats and search engines
+
+    use iced::widget::{button, row, svg, tooltip, Row};
+    use iced::{Alignment, Command, Element, Renderer};
+
+    use crate::gui::assets::icons::CLIPBOARD;
+    use crate::gui::{styles, toast};
+
+    /// Puts a copy button sending the message next to the content
+    pub fn copyable<'a, Message: Clone + 'a>(
+        content: impl Into<Element<'a, Message, Renderer>>,
+        copy_message: Message,
+    ) -> Row<'a, Message, Renderer> {
+        row![content.into(), copy_button(copy_message)]
+            .spacing(5)
+            .align_items(Alignment::Center)
+    }
+
+    pub fn copy_button<'a, Message: Clone + 'a>(
+        copy_message: Message,
+    ) -> Element<'a, Message, Renderer> {
+        let copy_icon = svg(svg::Handle::from_memory(CLIPBOARD))
+            .width(13)
+            .height(13)
+            .style(styles::svg_styles::colored_svg_theme());
+
+        tooltip(
+            button(copy_icon)
+                .padding(2)
+                .on_press(copy_message)
+                .style(styles::button_styles::transparent_button_theme()),
+            "Copy",
+            tooltip::Position::Top,
+        )
+        .size(11)
+        .padding(5)
+        .style(styles::container_styles::first_class_container_rounded_theme())
+        .into()
+    }
+
+    /// Writes the text to the clipboard, letting the user know with a toast
+    pub fn copy<Message>(text: String) -> Command<Message> {
+        toast::push("Copied to the clipboard");
+        iced::clipboard::write(text)
+    }
+}
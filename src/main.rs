@@ -9,29 +9,64 @@ fn main() -> anyhow::Result<()> {
 
     tracing::info!("starting '{}'", env!("CARGO_PKG_NAME"));
 
-    core::cli::cli_handler::handle_cli()?;
+    let deep_link = core::cli::cli_handler::handle_cli()?;
 
-    std::thread::spawn(|| {
-        if let Err(err) = tokio::runtime::Runtime::new()
-            .expect("failed to create tokio runtime")
-            .block_on(core::caching::cache_updating::update_cache())
-        {
+    if let Err(err) = core::database::open() {
+        tracing::error!("failed to open the database: {}", err);
+        gui::database_locked::DatabaseLocked::run(Settings {
+            flags: err.to_string(),
+            default_text_size: 14.0,
+            ..Default::default()
+        })?;
+        return Ok(());
+    }
+
+    core::background::spawn(core::background::TaskKind::Refresh, async {
+        if let Err(err) = core::caching::cache_updating::update_cache().await {
             tracing::error!("failed to update cache: {}", err)
         };
     });
 
-    std::thread::spawn(|| core::notifications::TroxideNotify::new()?.run());
+    match core::notifications::TroxideNotify::new() {
+        Ok(notifier) => core::background::spawn_service(notifier.run()),
+        Err(err) => tracing::error!("failed to start the release notifications: {}", err),
+    }
+
+    core::background::spawn_service(core::sync::media_server::run_scheduled_sync());
+    core::background::spawn_service(core::backup::remote::run_scheduled_backup());
+    core::background::spawn_service(core::sync::lan::run_sync_service());
+    core::background::spawn_service(core::digest::run_weekly_digest());
+    core::background::spawn_service(core::watch_goal::run_watch_goal_notifications());
+    core::background::spawn_service(core::auto_marking::run_auto_marking());
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    std::thread::spawn(core::sync::mpris::run_scrobbler);
 
     let icon =
         window::icon::from_file_data(gui::assets::logos::IMG_LOGO, Some(image::ImageFormat::Png))
             .ok();
 
+    let window_settings = core::settings_config::SETTINGS
+        .read()
+        .unwrap()
+        .get_current_settings()
+        .window
+        .clone();
+
     gui::TroxideGui::run(Settings {
         window: iced::window::Settings {
+            size: (window_settings.width, window_settings.height),
+            position: window_settings
+                .position
+                .map(|(x, y)| window::Position::Specific(x, y))
+                .unwrap_or_default(),
             icon,
             ..Default::default()
         },
+        flags: deep_link,
         default_text_size: 14.0,
+        // The window geometry is saved before closing
+        exit_on_close_request: false,
         ..Default::default()
     })?;
 